@@ -50,6 +50,12 @@ impl ZBuf {
         self.slices.as_mut().iter_mut()
     }
 
+    /// Returns the bytes of each slice of this buffer for modification. The slices are copied
+    /// lazily, as they are iterated, only if they are shared with other buffers (copy-on-write).
+    pub fn to_mut(&mut self) -> impl Iterator<Item = &mut [u8]> + '_ {
+        self.zslices_mut().map(ZSlice::to_mut)
+    }
+
     pub fn push_zslice(&mut self, zslice: ZSlice) {
         if !zslice.is_empty() {
             self.slices.push(zslice);
@@ -156,6 +162,28 @@ impl SplitBuffer for ZBuf {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for ZBuf {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_bytes(&self.contiguous())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ZBuf {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer
+            .deserialize_byte_buf(crate::zslice::BytesVisitor)
+            .map(Into::into)
+    }
+}
+
 impl PartialEq for ZBuf {
     fn eq(&self, other: &Self) -> bool {
         let mut self_slices = self.slices();
@@ -387,11 +415,46 @@ impl<'a> std::io::Read for ZBufReader<'a> {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         match <Self as Reader>::read(self, buf) {
             Ok(n) => Ok(n.get()),
-            Err(_) => Err(std::io::Error::new(
-                std::io::ErrorKind::UnexpectedEof,
-                "UnexpectedEof",
-            )),
+            // Nothing left to read
+            Err(_) => Ok(0),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> std::io::Seek for ZBufReader<'a> {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        let slices = self.inner.slices.as_ref();
+        let current = slices[..self.cursor.slice]
+            .iter()
+            .map(ZSlice::len)
+            .sum::<usize>()
+            + self.cursor.byte;
+        let target = match pos {
+            std::io::SeekFrom::Start(pos) => Some(pos),
+            std::io::SeekFrom::End(offset) => (self.inner.len() as u64).checked_add_signed(offset),
+            std::io::SeekFrom::Current(offset) => (current as u64).checked_add_signed(offset),
+        };
+        let target = match target {
+            Some(target) if target <= self.inner.len() as u64 => target as usize,
+            _ => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "Seek out of the ZBuf bounds",
+                ))
+            }
+        };
+        let mut byte = target;
+        let mut slice = 0;
+        for s in slices {
+            if byte < s.len() {
+                break;
+            }
+            byte -= s.len();
+            slice += 1;
         }
+        self.cursor = ZBufPos { slice, byte };
+        Ok(target as u64)
     }
 }
 
@@ -663,4 +726,35 @@ mod tests {
 
         assert_eq!(zbuf1, zbuf2);
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn zbuf_read_seek() {
+        use super::{ZBuf, ZSlice};
+        use crate::reader::HasReader;
+        use std::io::{Read, Seek, SeekFrom};
+
+        let slice: ZSlice = [0u8, 1, 2, 3, 4, 5, 6, 7].to_vec().into();
+        let mut zbuf = ZBuf::empty();
+        zbuf.push_zslice(slice.subslice(0, 3).unwrap());
+        zbuf.push_zslice(slice.subslice(3, 8).unwrap());
+
+        let mut reader = zbuf.reader();
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes).unwrap();
+        assert_eq!(bytes, [0, 1, 2, 3, 4, 5, 6, 7]);
+
+        let mut byte = [0u8];
+        assert_eq!(reader.seek(SeekFrom::Start(3)).unwrap(), 3);
+        reader.read_exact(&mut byte).unwrap();
+        assert_eq!(byte, [3]);
+        assert_eq!(reader.seek(SeekFrom::Current(-3)).unwrap(), 1);
+        reader.read_exact(&mut byte).unwrap();
+        assert_eq!(byte, [1]);
+        assert_eq!(reader.seek(SeekFrom::End(-1)).unwrap(), 7);
+        reader.read_exact(&mut byte).unwrap();
+        assert_eq!(byte, [7]);
+        assert!(reader.seek(SeekFrom::End(1)).is_err());
+        assert!(reader.seek(SeekFrom::Current(-9)).is_err());
+    }
 }
@@ -144,6 +144,29 @@ impl ZSlice {
         crate::unsafe_slice!(self.buf.as_slice(), self.range())
     }
 
+    /// Returns the bytes of this slice for modification, copying them in a new buffer
+    /// only if the underlying buffer is shared with other slices (copy-on-write).
+    pub fn to_mut(&mut self) -> &mut [u8] {
+        #[cfg(feature = "shared-memory")]
+        let shared = self.kind != ZSliceKind::Raw;
+        #[cfg(not(feature = "shared-memory"))]
+        let shared = false;
+        if shared || Arc::get_mut(&mut self.buf).is_none() {
+            let buf: Vec<u8> = self.as_slice().to_vec();
+            self.start = 0;
+            self.end = buf.len();
+            self.buf = Arc::new(buf);
+            #[cfg(feature = "shared-memory")]
+            {
+                self.kind = ZSliceKind::Raw;
+            }
+        }
+        let range = self.range();
+        // the buffer is unique: either it already was or it has just been copied
+        let buf = Arc::get_mut(&mut self.buf).unwrap();
+        &mut buf.as_mut_slice()[range]
+    }
+
     #[must_use]
     pub fn subslice(&self, start: usize, end: usize) -> Option<ZSlice> {
         if start <= end && end <= self.len() {
@@ -238,6 +261,66 @@ impl PartialEq for ZSlice {
 
 impl Eq for ZSlice {}
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for ZSlice {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_bytes(self.as_slice())
+    }
+}
+
+// The bytes are accepted either as a byte string or as a sequence of bytes, e.g. in JSON
+#[cfg(feature = "serde")]
+pub(crate) struct BytesVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of bytes")
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ZSlice {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer
+            .deserialize_byte_buf(BytesVisitor)
+            .map(Into::into)
+    }
+}
+
 impl fmt::Display for ZSlice {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:02x?}", self.as_slice())
@@ -408,4 +491,20 @@ mod tests {
 
         assert_eq!(buf.as_slice(), zslice.as_slice());
     }
+
+    #[test]
+    fn zslice_to_mut() {
+        let mut zslice: ZSlice = vec![0u8, 1, 2, 3].into();
+        let ptr = zslice.as_slice().as_ptr();
+        // unique buffer: modified in place
+        zslice.to_mut()[0] = 10;
+        assert_eq!(zslice.as_slice(), &[10, 1, 2, 3]);
+        assert_eq!(zslice.as_slice().as_ptr(), ptr);
+
+        // shared buffer: copied, the other slices are left untouched
+        let mut sub = zslice.subslice(1, 3).unwrap();
+        sub.to_mut()[0] = 11;
+        assert_eq!(sub.as_slice(), &[11, 2]);
+        assert_eq!(zslice.as_slice(), &[10, 1, 2, 3]);
+    }
 }
@@ -85,6 +85,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         ext_qos: ext::QoSType::default(),
         ext_tstamp: None,
         ext_nodeid: ext::NodeIdType::default(),
+        ext_hoplimit: None,
         payload: PushBody::Put(Put {
             timestamp: None,
             encoding: Encoding::default(),
@@ -92,6 +93,7 @@ fn criterion_benchmark(c: &mut Criterion) {
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
             ext_attachment: None,
+            ext_tbound: None,
             ext_unknown: vec![],
             payload: ZBuf::from(vec![0u8; 8]),
         }),
@@ -131,6 +133,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         ext_qos: ext::QoSType::default(),
         ext_tstamp: None,
         ext_nodeid: ext::NodeIdType::default(),
+        ext_hoplimit: None,
         payload: PushBody::Put(Put {
             timestamp: None,
             encoding: Encoding::default(),
@@ -138,6 +141,7 @@ fn criterion_benchmark(c: &mut Criterion) {
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
             ext_attachment: None,
+            ext_tbound: None,
             ext_unknown: vec![],
             payload: ZBuf::from(vec![0u8; 8]),
         }),
@@ -172,6 +176,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         ext_qos: ext::QoSType::default(),
         ext_tstamp: None,
         ext_nodeid: ext::NodeIdType::default(),
+        ext_hoplimit: None,
         payload: PushBody::Put(Put {
             timestamp: None,
             encoding: Encoding::default(),
@@ -179,6 +184,7 @@ fn criterion_benchmark(c: &mut Criterion) {
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
             ext_attachment: None,
+            ext_tbound: None,
             ext_unknown: vec![],
             payload: ZBuf::from(vec![0u8; 8]),
         }),
@@ -213,6 +219,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         ext_qos: ext::QoSType::default(),
         ext_tstamp: None,
         ext_nodeid: ext::NodeIdType::default(),
+        ext_hoplimit: None,
         payload: PushBody::Put(Put {
             timestamp: None,
             encoding: Encoding::default(),
@@ -220,6 +227,7 @@ fn criterion_benchmark(c: &mut Criterion) {
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
             ext_attachment: None,
+            ext_tbound: None,
             ext_unknown: vec![],
             payload: ZBuf::from(vec![0u8; 1_000_000]),
         }),
@@ -241,6 +249,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         ext_qos: ext::QoSType::default(),
         ext_tstamp: None,
         ext_nodeid: ext::NodeIdType::default(),
+        ext_hoplimit: None,
         payload: PushBody::Put(Put {
             timestamp: None,
             encoding: Encoding::default(),
@@ -248,6 +257,7 @@ fn criterion_benchmark(c: &mut Criterion) {
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
             ext_attachment: None,
+            ext_tbound: None,
             ext_unknown: vec![],
             payload: ZBuf::from(vec![0u8; 1_000_000]),
         }),
@@ -280,6 +290,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         ext_qos: ext::QoSType::default(),
         ext_tstamp: None,
         ext_nodeid: ext::NodeIdType::default(),
+        ext_hoplimit: None,
         payload: PushBody::Put(Put {
             timestamp: None,
             encoding: Encoding::default(),
@@ -287,6 +298,7 @@ fn criterion_benchmark(c: &mut Criterion) {
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
             ext_attachment: None,
+            ext_tbound: None,
             ext_unknown: vec![],
             payload: ZBuf::from(vec![0u8; 1_000_000]),
         }),
@@ -0,0 +1,73 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::io::{self, BufRead, Read};
+use zenoh_codec::dissect::{Dissect, DissectError};
+use zenoh_protocol::{
+    network::NetworkMessage, scouting::ScoutingMessage, transport::TransportMessage,
+};
+
+const USAGE: &str = "\
+Usage: z_dissect <scouting|transport|network> [HEX]...
+       z_dissect <scouting|transport|network> --encode
+
+Renders the hex-encoded messages given as arguments, or one per line on stdin, as JSON documents.
+With --encode, parses a JSON document on stdin and prints the hex encoding of its message.";
+
+fn dissect<M: Dissect>(wire: &str) -> Result<String, DissectError> {
+    let wire = hex::decode(wire.trim()).map_err(|e| DissectError::Document(e.to_string()))?;
+    M::decode(&wire)?.to_json()
+}
+
+fn encode<M: Dissect>(json: &str) -> Result<String, DissectError> {
+    Ok(hex::encode(M::from_json(json)?.encode()?))
+}
+
+fn run<M: Dissect>(args: &[String]) -> Result<(), DissectError> {
+    if args.first().map(String::as_str) == Some("--encode") {
+        let mut json = String::new();
+        io::stdin()
+            .read_to_string(&mut json)
+            .map_err(|e| DissectError::Document(e.to_string()))?;
+        println!("{}", encode::<M>(&json)?);
+    } else if args.is_empty() {
+        for line in io::stdin().lock().lines() {
+            let line = line.map_err(|e| DissectError::Document(e.to_string()))?;
+            if !line.trim().is_empty() {
+                println!("{}", dissect::<M>(&line)?);
+            }
+        }
+    } else {
+        for wire in args {
+            println!("{}", dissect::<M>(wire)?);
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("scouting") => run::<ScoutingMessage>(&args[1..]),
+        Some("transport") => run::<TransportMessage>(&args[1..]),
+        Some("network") => run::<NetworkMessage>(&args[1..]),
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+}
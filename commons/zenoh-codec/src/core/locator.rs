@@ -67,7 +67,8 @@ where
     type Error = DidntRead;
 
     fn read(self, reader: &mut R) -> Result<Vec<Locator>, Self::Error> {
-        let len = self.read(&mut *reader)?;
+        let len: usize = self.read(&mut *reader)?;
+        let len = crate::limits::locators(reader, len)?;
         let mut vec: Vec<Locator> = Vec::with_capacity(len);
         for _ in 0..len {
             vec.push(self.read(&mut *reader)?);
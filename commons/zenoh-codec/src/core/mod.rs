@@ -121,6 +121,7 @@ macro_rules! vec_impl {
             #[allow(clippy::uninit_vec)]
             fn read(self, reader: &mut R) -> Result<Vec<u8>, Self::Error> {
                 let len: usize = self.read(&mut *reader)?;
+                let len = crate::limits::payload(reader, len)?;
                 let mut buff = zenoh_buffers::vec::uninit(len);
                 if len != 0 {
                     reader.read_exact(&mut buff[..])?;
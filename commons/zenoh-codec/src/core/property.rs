@@ -72,6 +72,7 @@ where
 
     fn read(self, reader: &mut R) -> Result<Vec<Property>, Self::Error> {
         let num: usize = self.read(&mut *reader)?;
+        let num = crate::limits::properties(reader, num)?;
 
         let mut ps = Vec::with_capacity(num);
         for _ in 0..num {
@@ -51,6 +51,7 @@ macro_rules! zbuf_impl {
 
             fn read(self, reader: &mut R) -> Result<ZBuf, Self::Error> {
                 let len: usize = self.read(&mut *reader)?;
+                let len = crate::limits::payload(reader, len)?;
                 let mut zbuf = ZBuf::empty();
                 reader.read_zslices(len, |s| zbuf.push_zslice(s))?;
                 Ok(zbuf)
@@ -43,6 +43,7 @@ macro_rules! zslice_impl {
             #[allow(clippy::uninit_vec)]
             fn read(self, reader: &mut R) -> Result<ZSlice, Self::Error> {
                 let len: usize = self.read(&mut *reader)?;
+                let len = crate::limits::payload(reader, len)?;
                 let zslice = reader.read_zslice(len)?;
                 Ok(zslice)
             }
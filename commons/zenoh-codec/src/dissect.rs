@@ -0,0 +1,177 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! A JSON or CBOR rendering of the protocol messages, e.g. to dissect the captured messages
+//! or to write golden-file tests.
+//!
+//! A message is rendered as a document holding both its fields and its wire encoding in hexadecimal:
+//! ```text
+//! { "message": { "version": 8, "whatami": "router", ... }, "wire": "0408..." }
+//! ```
+//! It is parsed back from its fields, which are encoded again. The wire encoding of the document
+//! is optional: if present, it must match the encoding of the fields, so an edited document must
+//! either update or drop it.
+use crate::{RCodec, WCodec, Zenoh080};
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use zenoh_buffers::{
+    reader::{DidntRead, HasReader, Reader},
+    writer::{DidntWrite, HasWriter},
+};
+use zenoh_protocol::{
+    network::{Declare, NetworkMessage, Push, Request, Response},
+    scouting::{Hello, Scout, ScoutingMessage},
+    transport::{Frame, TransportMessage},
+};
+
+/// The error of rendering or parsing a document.
+#[derive(Debug)]
+pub enum DissectError {
+    /// The message could not be encoded or decoded.
+    Wire,
+    /// The document could not be serialized or deserialized.
+    Document(String),
+    /// The fields of the document do not match its wire encoding, or do not survive their encoding.
+    Mismatch,
+}
+
+impl fmt::Display for DissectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DissectError::Wire => write!(f, "invalid wire encoding"),
+            DissectError::Document(e) => write!(f, "invalid document: {e}"),
+            DissectError::Mismatch => write!(f, "the message does not match its wire encoding"),
+        }
+    }
+}
+
+impl std::error::Error for DissectError {}
+
+impl From<DidntRead> for DissectError {
+    fn from(_: DidntRead) -> Self {
+        DissectError::Wire
+    }
+}
+
+impl From<DidntWrite> for DissectError {
+    fn from(_: DidntWrite) -> Self {
+        DissectError::Wire
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Document<M> {
+    message: M,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wire: Option<String>,
+}
+
+/// A protocol message that can be rendered to and parsed from a document.
+pub trait Dissect: Serialize + DeserializeOwned + PartialEq + Sized {
+    /// The wire encoding of the message.
+    fn encode(&self) -> Result<Vec<u8>, DissectError>;
+
+    /// Decodes a message from its whole wire encoding.
+    fn decode(wire: &[u8]) -> Result<Self, DissectError>;
+
+    /// Renders the message as a JSON document.
+    fn to_json(&self) -> Result<String, DissectError> {
+        let document = Document {
+            message: self,
+            wire: Some(hex::encode(self.encode()?)),
+        };
+        serde_json::to_string_pretty(&document).map_err(|e| DissectError::Document(e.to_string()))
+    }
+
+    /// Parses a message from a JSON document.
+    fn from_json(json: &str) -> Result<Self, DissectError> {
+        let document: Document<Self> =
+            serde_json::from_str(json).map_err(|e| DissectError::Document(e.to_string()))?;
+        from_document(document)
+    }
+
+    /// Renders the message as a CBOR document.
+    fn to_cbor(&self) -> Result<Vec<u8>, DissectError> {
+        let document = Document {
+            message: self,
+            wire: Some(hex::encode(self.encode()?)),
+        };
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&document, &mut cbor)
+            .map_err(|e| DissectError::Document(e.to_string()))?;
+        Ok(cbor)
+    }
+
+    /// Parses a message from a CBOR document.
+    fn from_cbor(cbor: &[u8]) -> Result<Self, DissectError> {
+        let document: Document<Self> =
+            ciborium::from_reader(cbor).map_err(|e| DissectError::Document(e.to_string()))?;
+        from_document(document)
+    }
+}
+
+fn from_document<M: Dissect>(document: Document<M>) -> Result<M, DissectError> {
+    // The message is encoded again from its fields, which must survive the encoding
+    let wire = document.message.encode()?;
+    let message = M::decode(&wire)?;
+    if message != document.message {
+        return Err(DissectError::Mismatch);
+    }
+    if let Some(expected) = document.wire {
+        let expected = hex::decode(expected).map_err(|e| DissectError::Document(e.to_string()))?;
+        if expected != wire {
+            return Err(DissectError::Mismatch);
+        }
+    }
+    Ok(message)
+}
+
+macro_rules! impl_dissect {
+    ($($type:ty),*) => {
+        $(
+            impl Dissect for $type {
+                fn encode(&self) -> Result<Vec<u8>, DissectError> {
+                    let mut wire = Vec::new();
+                    let mut writer = wire.writer();
+                    Zenoh080::new().write(&mut writer, self)?;
+                    Ok(wire)
+                }
+
+                fn decode(wire: &[u8]) -> Result<Self, DissectError> {
+                    let mut reader = wire.reader();
+                    let message: $type = Zenoh080::new().read(&mut reader)?;
+                    // The whole encoding must be consumed
+                    if reader.can_read() {
+                        return Err(DissectError::Wire);
+                    }
+                    Ok(message)
+                }
+            }
+        )*
+    };
+}
+
+impl_dissect!(
+    ScoutingMessage,
+    Scout,
+    Hello,
+    TransportMessage,
+    Frame,
+    NetworkMessage,
+    Declare,
+    Push,
+    Request,
+    Response
+);
@@ -22,12 +22,16 @@ extern crate alloc;
 
 pub mod common;
 pub mod core;
+#[cfg(feature = "dissect")]
+pub mod dissect;
+mod limits;
 pub mod network;
 pub mod scouting;
 pub mod transport;
 pub mod zenoh;
 
 use ::core::marker::PhantomData;
+pub use limits::CodecLimits;
 use zenoh_protocol::core::Reliability;
 
 pub trait WCodec<Message, Buffer> {
@@ -0,0 +1,118 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use zenoh_buffers::reader::{DidntRead, Reader};
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static CURRENT: core::cell::Cell<CodecLimits> = core::cell::Cell::new(CodecLimits::DEFAULT);
+}
+
+/// The bounds enforced when decoding the lengths and counts read from the wire, so that
+/// a malformed message can not make the decoder allocate beyond them.
+///
+/// The lengths and counts are also always bounded by the number of bytes left to read,
+/// as each byte or element takes at least one byte on the wire.
+///
+/// The limits are not global: they are enforced by the decoding done within
+/// [`CodecLimits::scope`], e.g. by a transport with the limits of its configuration,
+/// and the defaults are enforced elsewhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodecLimits {
+    /// The maximum number of locators of a list, e.g. in a Hello or a Join.
+    pub max_locators: usize,
+    /// The maximum number of properties of a list.
+    pub max_properties: usize,
+    /// The maximum length in bytes of a payload, a string or a byte array.
+    pub max_payload: usize,
+    /// The maximum number of links of a link state, and of link states of a list.
+    pub max_links: usize,
+}
+
+impl CodecLimits {
+    pub const DEFAULT_MAX_LOCATORS: usize = 256;
+    pub const DEFAULT_MAX_PROPERTIES: usize = 1_024;
+    // The default maximum size of a fragmented message
+    pub const DEFAULT_MAX_PAYLOAD: usize = 1 << 30;
+    pub const DEFAULT_MAX_LINKS: usize = 4_096;
+
+    const DEFAULT: Self = Self {
+        max_locators: Self::DEFAULT_MAX_LOCATORS,
+        max_properties: Self::DEFAULT_MAX_PROPERTIES,
+        max_payload: Self::DEFAULT_MAX_PAYLOAD,
+        max_links: Self::DEFAULT_MAX_LINKS,
+    };
+
+    /// The limits enforced by the codec on the current thread.
+    pub fn get() -> Self {
+        #[cfg(feature = "std")]
+        {
+            CURRENT.with(|c| c.get())
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            Self::DEFAULT
+        }
+    }
+
+    /// Runs `f` enforcing these limits in the codec, for the messages decoded by `f` on the
+    /// current thread. The previous limits are restored when `f` returns.
+    #[cfg(feature = "std")]
+    pub fn scope<T>(self, f: impl FnOnce() -> T) -> T {
+        struct Restore(CodecLimits);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT.with(|c| c.set(self.0));
+            }
+        }
+
+        let _restore = Restore(CURRENT.with(|c| c.replace(self)));
+        f()
+    }
+
+    /// Checks the number of links of a link state, or of link states of a list, read from
+    /// `reader`.
+    pub fn check_links<R: Reader>(reader: &R, len: usize) -> Result<usize, DidntRead> {
+        check(reader, len, Self::get().max_links)
+    }
+}
+
+impl Default for CodecLimits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[inline(always)]
+fn check<R: Reader>(reader: &R, len: usize, max: usize) -> Result<usize, DidntRead> {
+    if len > max || len > reader.remaining() {
+        return Err(DidntRead);
+    }
+    Ok(len)
+}
+
+/// Checks the number of locators of a list read from `reader`.
+pub(crate) fn locators<R: Reader>(reader: &R, len: usize) -> Result<usize, DidntRead> {
+    check(reader, len, CodecLimits::get().max_locators)
+}
+
+/// Checks the number of properties of a list read from `reader`.
+pub(crate) fn properties<R: Reader>(reader: &R, len: usize) -> Result<usize, DidntRead> {
+    check(reader, len, CodecLimits::get().max_properties)
+}
+
+/// Checks the length in bytes of a payload read from `reader`.
+pub(crate) fn payload<R: Reader>(reader: &R, len: usize) -> Result<usize, DidntRead> {
+    check(reader, len, CodecLimits::get().max_payload)
+}
@@ -39,6 +39,7 @@ where
             ext_qos,
             ext_tstamp,
             ext_nodeid,
+            ext_hoplimit,
             payload,
         } = x;
 
@@ -46,7 +47,8 @@ where
         let mut header = id::PUSH;
         let mut n_exts = ((ext_qos != &ext::QoSType::default()) as u8)
             + (ext_tstamp.is_some() as u8)
-            + ((ext_nodeid != &ext::NodeIdType::default()) as u8);
+            + ((ext_nodeid != &ext::NodeIdType::default()) as u8)
+            + (ext_hoplimit.is_some() as u8);
         if n_exts != 0 {
             header |= flag::Z;
         }
@@ -74,6 +76,10 @@ where
             n_exts -= 1;
             self.write(&mut *writer, (*ext_nodeid, n_exts != 0))?;
         }
+        if let Some(hl) = ext_hoplimit.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (*hl, n_exts != 0))?;
+        }
 
         // Payload
         self.write(&mut *writer, payload)?;
@@ -119,6 +125,7 @@ where
         let mut ext_qos = ext::QoSType::default();
         let mut ext_tstamp = None;
         let mut ext_nodeid = ext::NodeIdType::default();
+        let mut ext_hoplimit = None;
 
         let mut has_ext = imsg::has_flag(self.header, flag::Z);
         while has_ext {
@@ -140,6 +147,11 @@ where
                     ext_nodeid = nid;
                     has_ext = ext;
                 }
+                ext::HopLimit::ID => {
+                    let (hl, ext): (ext::HopLimitType, bool) = eodec.read(&mut *reader)?;
+                    ext_hoplimit = Some(hl);
+                    has_ext = ext;
+                }
                 _ => {
                     has_ext = extension::skip(reader, "Push", ext)?;
                 }
@@ -155,6 +167,7 @@ where
             ext_qos,
             ext_tstamp,
             ext_nodeid,
+            ext_hoplimit,
         })
     }
 }
@@ -11,66 +11,187 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use crate::{RCodec, WCodec, Zenoh080, Zenoh080Header, Zenoh080Length};
+use crate::{
+    common::extension, LCodec, RCodec, WCodec, Zenoh080, Zenoh080Bounded, Zenoh080Header,
+    Zenoh080Length,
+};
 use alloc::{vec, vec::Vec};
+use core::fmt;
 use zenoh_buffers::{
-    reader::{DidntRead, Reader},
-    writer::{DidntWrite, Writer},
+    reader::{DidntRead, HasReader, Reader},
+    writer::{DidntWrite, HasWriter, Writer},
 };
 use zenoh_protocol::{
-    common::{imsg, ZExtUnknown},
+    common::{iext, imsg, ZExtZBufHeader},
     core::{Locator, WhatAmI, ZenohId},
     scouting::{
-        hello::{flag, Hello},
+        hello::{ext, flag, Hello},
         id,
     },
 };
 
-impl<W> WCodec<&Hello, &mut W> for Zenoh080
+// Extension: Capabilities
+impl<W> WCodec<(&ext::Capabilities, bool), &mut W> for Zenoh080
 where
     W: Writer,
 {
     type Output = Result<(), DidntWrite>;
 
-    fn write(self, writer: &mut W, x: &Hello) -> Self::Output {
-        let Hello {
-            version,
-            whatami,
-            zid,
-            locators,
-        } = x;
+    fn write(self, writer: &mut W, x: (&ext::Capabilities, bool)) -> Self::Output {
+        let (capabilities, more) = x;
+        let ext::Capabilities {
+            shm,
+            compression,
+            versions,
+        } = capabilities;
 
-        // Header
-        let mut header = id::HELLO;
-        if !locators.is_empty() {
-            header |= flag::L;
+        let mut properties: Vec<(u64, Vec<u8>)> = vec![];
+        if *shm {
+            properties.push((ext::Capabilities::SHM, vec![]));
+        }
+        if *compression != 0 {
+            let mut value = vec![];
+            self.write(&mut value.writer(), *compression)?;
+            properties.push((ext::Capabilities::COMPRESSION, value));
+        }
+        if !versions.is_empty() {
+            properties.push((ext::Capabilities::VERSIONS, versions.clone()));
         }
-        self.write(&mut *writer, header)?;
 
-        // Body
-        self.write(&mut *writer, version)?;
+        let len = self.w_len(properties.len() as u64)
+            + properties
+                .iter()
+                .map(|(id, value)| self.w_len(*id) + self.w_len(value.as_slice()))
+                .sum::<usize>();
+        let header: ZExtZBufHeader<{ ext::CAPABILITIES }> = ZExtZBufHeader::new(len);
+        self.write(&mut *writer, (&header, more))?;
+        self.write(&mut *writer, properties.len() as u64)?;
+        for (id, value) in properties.iter() {
+            self.write(&mut *writer, *id)?;
+            self.write(&mut *writer, value.as_slice())?;
+        }
+        Ok(())
+    }
+}
 
-        let mut flags: u8 = 0;
-        let whatami: u8 = match whatami {
-            WhatAmI::Router => 0b00,
-            WhatAmI::Peer => 0b01,
-            WhatAmI::Client => 0b10,
-        };
-        flags |= whatami & 0b11;
-        flags |= ((zid.size() - 1) as u8) << 4;
-        self.write(&mut *writer, flags)?;
+impl<R> RCodec<(ext::Capabilities, bool), &mut R> for Zenoh080Header
+where
+    R: Reader,
+{
+    type Error = DidntRead;
 
-        let lodec = Zenoh080Length::new(zid.size());
-        lodec.write(&mut *writer, zid)?;
+    fn read(self, reader: &mut R) -> Result<(ext::Capabilities, bool), Self::Error> {
+        let (header, more): (ZExtZBufHeader<{ ext::CAPABILITIES }>, bool) =
+            self.read(&mut *reader)?;
+        if header.len > reader.remaining() {
+            return Err(DidntRead);
+        }
+        let mut body = vec![0u8; header.len];
+        reader.read_exact(&mut body)?;
+        let capabilities = read_capabilities(self.codec, &body)?;
+        Ok((capabilities, more))
+    }
+}
 
-        if !locators.is_empty() {
-            self.write(&mut *writer, locators.as_slice())?;
+// The body of the Capabilities extension
+fn read_capabilities(codec: Zenoh080, body: &[u8]) -> Result<ext::Capabilities, DidntRead> {
+    let mut reader = body.reader();
+    let mut capabilities = ext::Capabilities::default();
+    let num: u64 = codec.read(&mut reader)?;
+    for _ in 0..num {
+        let id: u64 = codec.read(&mut reader)?;
+        let value: Vec<u8> = codec.read(&mut reader)?;
+        match id {
+            ext::Capabilities::SHM => capabilities.shm = true,
+            ext::Capabilities::COMPRESSION => {
+                capabilities.compression = codec.read(&mut value.as_slice().reader())?
+            }
+            ext::Capabilities::VERSIONS => capabilities.versions = value,
+            // Skip the properties advertised by newer nodes
+            _ => {}
         }
+    }
+    Ok(capabilities)
+}
 
-        Ok(())
+impl<W> WCodec<&Hello, &mut W> for Zenoh080
+where
+    W: Writer,
+{
+    type Output = Result<(), DidntWrite>;
+
+    fn write(self, writer: &mut W, x: &Hello) -> Self::Output {
+        write_hello(self, writer, x, None)
     }
 }
 
+// The capabilities are advertised alongside the Hello, which is part of the public API
+impl<W> WCodec<(&Hello, &ext::Capabilities), &mut W> for Zenoh080
+where
+    W: Writer,
+{
+    type Output = Result<(), DidntWrite>;
+
+    fn write(self, writer: &mut W, x: (&Hello, &ext::Capabilities)) -> Self::Output {
+        let (hello, capabilities) = x;
+        write_hello(self, writer, hello, Some(capabilities))
+    }
+}
+
+fn write_hello<W>(
+    codec: Zenoh080,
+    writer: &mut W,
+    hello: &Hello,
+    ext_capabilities: Option<&ext::Capabilities>,
+) -> Result<(), DidntWrite>
+where
+    W: Writer,
+{
+    let Hello {
+        version,
+        whatami,
+        zid,
+        locators,
+    } = hello;
+
+    // Header
+    let mut header = id::HELLO;
+    if !locators.is_empty() {
+        header |= flag::L;
+    }
+    if ext_capabilities.is_some() {
+        header |= flag::Z;
+    }
+    codec.write(&mut *writer, header)?;
+
+    // Body
+    codec.write(&mut *writer, version)?;
+
+    let mut flags: u8 = 0;
+    let whatami: u8 = match whatami {
+        WhatAmI::Router => 0b00,
+        WhatAmI::Peer => 0b01,
+        WhatAmI::Client => 0b10,
+    };
+    flags |= whatami & 0b11;
+    flags |= ((zid.size() - 1) as u8) << 4;
+    codec.write(&mut *writer, flags)?;
+
+    let lodec = Zenoh080Length::new(zid.size());
+    lodec.write(&mut *writer, zid)?;
+
+    if !locators.is_empty() {
+        codec.write(&mut *writer, locators.as_slice())?;
+    }
+
+    // Extensions
+    if let Some(capabilities) = ext_capabilities {
+        codec.write(&mut *writer, (capabilities, false))?;
+    }
+
+    Ok(())
+}
+
 impl<R> RCodec<Hello, &mut R> for Zenoh080
 where
     R: Reader,
@@ -91,6 +212,31 @@ where
     type Error = DidntRead;
 
     fn read(self, reader: &mut R) -> Result<Hello, Self::Error> {
+        let (hello, _): (Hello, Option<ext::Capabilities>) = self.read(reader)?;
+        Ok(hello)
+    }
+}
+
+impl<R> RCodec<(Hello, Option<ext::Capabilities>), &mut R> for Zenoh080
+where
+    R: Reader,
+{
+    type Error = DidntRead;
+
+    fn read(self, reader: &mut R) -> Result<(Hello, Option<ext::Capabilities>), Self::Error> {
+        let header: u8 = self.read(&mut *reader)?;
+        let codec = Zenoh080Header::new(header);
+        codec.read(reader)
+    }
+}
+
+impl<R> RCodec<(Hello, Option<ext::Capabilities>), &mut R> for Zenoh080Header
+where
+    R: Reader,
+{
+    type Error = DidntRead;
+
+    fn read(self, reader: &mut R) -> Result<(Hello, Option<ext::Capabilities>), Self::Error> {
         if imsg::mid(self.header) != id::HELLO {
             return Err(DidntRead);
         }
@@ -116,17 +262,200 @@ where
         };
 
         // Extensions
-        let mut has_extensions = imsg::has_flag(self.header, flag::Z);
-        while has_extensions {
-            let (_, more): (ZExtUnknown, bool) = self.codec.read(&mut *reader)?;
-            has_extensions = more;
+        let mut ext_capabilities = None;
+
+        let mut has_ext = imsg::has_flag(self.header, flag::Z);
+        while has_ext {
+            let ext: u8 = self.codec.read(&mut *reader)?;
+            let eodec = Zenoh080Header::new(ext);
+            match iext::eid(ext) {
+                ext::CAPABILITIES => {
+                    let (c, ext): (ext::Capabilities, bool) = eodec.read(&mut *reader)?;
+                    ext_capabilities = Some(c);
+                    has_ext = ext;
+                }
+                _ => {
+                    has_ext = extension::skip(reader, "Hello", ext)?;
+                }
+            }
         }
 
-        Ok(Hello {
+        let hello = Hello {
             version,
             zid,
             whatami,
             locators,
+        };
+        Ok((hello, ext_capabilities))
+    }
+}
+
+/// A [`Hello`] decoded without allocating, borrowing its locators from the decoded buffer.
+///
+/// It allows to filter the Hellos on their [`WhatAmI`] or [`ZenohId`] before allocating
+/// their locators with [`to_hello`](HelloRef::to_hello).
+#[derive(Clone, Copy)]
+pub struct HelloRef<'a> {
+    pub version: u8,
+    pub whatami: WhatAmI,
+    pub zid: ZenohId,
+    locators: &'a [u8],
+    num_locators: usize,
+    ext_capabilities: Option<&'a [u8]>,
+}
+
+impl<'a> HelloRef<'a> {
+    /// The locators of the Hello, as string slices of the decoded buffer.
+    pub fn locators(&self) -> LocatorsRef<'a> {
+        LocatorsRef {
+            reader: self.locators,
+            remaining: self.num_locators,
+        }
+    }
+
+    /// The capabilities advertised in the Hello, if any.
+    pub fn capabilities(&self) -> Option<ext::Capabilities> {
+        self.ext_capabilities
+            .and_then(|body| read_capabilities(Zenoh080::new(), body).ok())
+    }
+
+    /// Builds the owned [`Hello`], allocating its locators.
+    pub fn to_hello(&self) -> Result<Hello, DidntRead> {
+        let locators = self
+            .locators()
+            .map(|l| Locator::try_from(l).map_err(|_| DidntRead))
+            .collect::<Result<Vec<Locator>, DidntRead>>()?;
+        Ok(Hello {
+            version: self.version,
+            whatami: self.whatami,
+            zid: self.zid,
+            locators,
+        })
+    }
+}
+
+impl fmt::Debug for HelloRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HelloRef")
+            .field("version", &self.version)
+            .field("whatami", &self.whatami)
+            .field("zid", &self.zid)
+            .field("locators", &self.locators())
+            .field("ext_capabilities", &self.capabilities())
+            .finish()
+    }
+}
+
+/// The locators of a [`HelloRef`].
+#[derive(Clone)]
+pub struct LocatorsRef<'a> {
+    reader: &'a [u8],
+    remaining: usize,
+}
+
+impl<'a> Iterator for LocatorsRef<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        // The locators have been checked when decoding the Hello
+        read_str(&mut self.reader).ok()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for LocatorsRef<'_> {}
+
+impl fmt::Debug for LocatorsRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+// A locator borrowed from the reader
+fn read_str<'a>(reader: &mut &'a [u8]) -> Result<&'a str, DidntRead> {
+    let len: usize = Zenoh080Bounded::<u8>::new().read(&mut *reader)?;
+    if len > reader.len() {
+        return Err(DidntRead);
+    }
+    let (s, rest) = reader.split_at(len);
+    *reader = rest;
+    core::str::from_utf8(s).map_err(|_| DidntRead)
+}
+
+impl<'a> RCodec<HelloRef<'a>, &mut &'a [u8]> for Zenoh080 {
+    type Error = DidntRead;
+
+    fn read(self, reader: &mut &'a [u8]) -> Result<HelloRef<'a>, Self::Error> {
+        let header: u8 = self.read(&mut *reader)?;
+        if imsg::mid(header) != id::HELLO {
+            return Err(DidntRead);
+        }
+
+        // Body
+        let version: u8 = self.read(&mut *reader)?;
+        let flags: u8 = self.read(&mut *reader)?;
+        let whatami = match flags & 0b11 {
+            0b00 => WhatAmI::Router,
+            0b01 => WhatAmI::Peer,
+            0b10 => WhatAmI::Client,
+            _ => return Err(DidntRead),
+        };
+        let length = 1 + ((flags >> 4) as usize);
+        let lodec = Zenoh080Length::new(length);
+        let zid: ZenohId = lodec.read(&mut *reader)?;
+
+        let mut locators: &[u8] = &[];
+        let mut num_locators = 0;
+        if imsg::has_flag(header, flag::L) {
+            let num: usize = self.read(&mut *reader)?;
+            num_locators = crate::limits::locators(&*reader, num)?;
+            let start = *reader;
+            for _ in 0..num_locators {
+                read_str(reader)?;
+            }
+            locators = &start[..start.len() - reader.len()];
+        }
+
+        // Extensions
+        let mut ext_capabilities = None;
+
+        let mut has_ext = imsg::has_flag(header, flag::Z);
+        while has_ext {
+            let ext: u8 = self.read(&mut *reader)?;
+            let eodec = Zenoh080Header::new(ext);
+            match iext::eid(ext) {
+                ext::CAPABILITIES => {
+                    let (h, more): (ZExtZBufHeader<{ ext::CAPABILITIES }>, bool) =
+                        eodec.read(&mut *reader)?;
+                    if h.len > reader.len() {
+                        return Err(DidntRead);
+                    }
+                    let (body, rest) = reader.split_at(h.len);
+                    *reader = rest;
+                    read_capabilities(self, body)?;
+                    ext_capabilities = Some(body);
+                    has_ext = more;
+                }
+                _ => {
+                    has_ext = extension::skip(reader, "Hello", ext)?;
+                }
+            }
+        }
+
+        Ok(HelloRef {
+            version,
+            whatami,
+            zid,
+            locators,
+            num_locators,
+            ext_capabilities,
         })
     }
 }
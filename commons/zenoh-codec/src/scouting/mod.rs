@@ -14,6 +14,8 @@
 mod hello;
 mod scout;
 
+pub use hello::{HelloRef, LocatorsRef};
+
 use crate::{RCodec, WCodec, Zenoh080, Zenoh080Header};
 use zenoh_buffers::{
     reader::{DidntRead, Reader},
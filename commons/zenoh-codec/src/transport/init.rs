@@ -49,6 +49,7 @@ where
             ext_mlink,
             ext_lowlatency,
             ext_compression,
+            ext_compression_algorithms,
         } = x;
 
         // Header
@@ -61,7 +62,8 @@ where
             + (ext_auth.is_some() as u8)
             + (ext_mlink.is_some() as u8)
             + (ext_lowlatency.is_some() as u8)
-            + (ext_compression.is_some() as u8);
+            + (ext_compression.is_some() as u8)
+            + (ext_compression_algorithms.is_some() as u8);
         if n_exts != 0 {
             header |= flag::Z;
         }
@@ -111,6 +113,10 @@ where
             n_exts -= 1;
             self.write(&mut *writer, (compression, n_exts != 0))?;
         }
+        if let Some(algorithms) = ext_compression_algorithms.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (algorithms, n_exts != 0))?;
+        }
 
         Ok(())
     }
@@ -170,6 +176,7 @@ where
         let mut ext_mlink = None;
         let mut ext_lowlatency = None;
         let mut ext_compression = None;
+        let mut ext_compression_algorithms = None;
 
         let mut has_ext = imsg::has_flag(self.header, flag::Z);
         while has_ext {
@@ -206,6 +213,11 @@ where
                     ext_compression = Some(q);
                     has_ext = ext;
                 }
+                ext::CompressionAlgorithms::ID => {
+                    let (a, ext): (ext::CompressionAlgorithms, bool) = eodec.read(&mut *reader)?;
+                    ext_compression_algorithms = Some(a);
+                    has_ext = ext;
+                }
                 _ => {
                     has_ext = extension::skip(reader, "InitSyn", ext)?;
                 }
@@ -224,6 +236,7 @@ where
             ext_mlink,
             ext_lowlatency,
             ext_compression,
+            ext_compression_algorithms,
         })
     }
 }
@@ -249,6 +262,7 @@ where
             ext_mlink,
             ext_lowlatency,
             ext_compression,
+            ext_compression_algorithms,
         } = x;
 
         // Header
@@ -261,7 +275,8 @@ where
             + (ext_auth.is_some() as u8)
             + (ext_mlink.is_some() as u8)
             + (ext_lowlatency.is_some() as u8)
-            + (ext_compression.is_some() as u8);
+            + (ext_compression.is_some() as u8)
+            + (ext_compression_algorithms.is_some() as u8);
         if n_exts != 0 {
             header |= flag::Z;
         }
@@ -314,6 +329,10 @@ where
             n_exts -= 1;
             self.write(&mut *writer, (compression, n_exts != 0))?;
         }
+        if let Some(algorithms) = ext_compression_algorithms.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (algorithms, n_exts != 0))?;
+        }
 
         Ok(())
     }
@@ -376,6 +395,7 @@ where
         let mut ext_mlink = None;
         let mut ext_lowlatency = None;
         let mut ext_compression = None;
+        let mut ext_compression_algorithms = None;
 
         let mut has_ext = imsg::has_flag(self.header, flag::Z);
         while has_ext {
@@ -412,6 +432,11 @@ where
                     ext_compression = Some(q);
                     has_ext = ext;
                 }
+                ext::CompressionAlgorithms::ID => {
+                    let (a, ext): (ext::CompressionAlgorithms, bool) = eodec.read(&mut *reader)?;
+                    ext_compression_algorithms = Some(a);
+                    has_ext = ext;
+                }
                 _ => {
                     has_ext = extension::skip(reader, "InitAck", ext)?;
                 }
@@ -431,6 +456,7 @@ where
             ext_mlink,
             ext_lowlatency,
             ext_compression,
+            ext_compression_algorithms,
         })
     }
 }
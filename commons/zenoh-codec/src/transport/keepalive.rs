@@ -17,10 +17,10 @@ use zenoh_buffers::{
     writer::{DidntWrite, Writer},
 };
 use zenoh_protocol::{
-    common::imsg,
+    common::{iext, imsg},
     transport::{
         id,
-        keepalive::{flag, KeepAlive},
+        keepalive::{ext, flag, KeepAlive},
     },
 };
 
@@ -31,11 +31,28 @@ where
     type Output = Result<(), DidntWrite>;
 
     fn write(self, writer: &mut W, x: &KeepAlive) -> Self::Output {
-        let KeepAlive = x;
+        let KeepAlive {
+            ext_echo_request,
+            ext_echo_reply,
+        } = x;
 
         // Header
-        let header = id::KEEP_ALIVE;
+        let mut header = id::KEEP_ALIVE;
+        let mut n_exts = (ext_echo_request.is_some() as u8) + (ext_echo_reply.is_some() as u8);
+        if n_exts != 0 {
+            header |= flag::Z;
+        }
         self.write(&mut *writer, header)?;
+
+        // Extensions
+        if let Some(request) = ext_echo_request.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (request, n_exts != 0))?;
+        }
+        if let Some(reply) = ext_echo_reply.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (reply, n_exts != 0))?;
+        }
         Ok(())
     }
 }
@@ -65,11 +82,33 @@ where
         }
 
         // Extensions
-        let has_ext = imsg::has_flag(self.header, flag::Z);
-        if has_ext {
-            extension::skip_all(reader, "Unknown KeepAlive ext")?;
+        let mut ext_echo_request = None;
+        let mut ext_echo_reply = None;
+
+        let mut has_ext = imsg::has_flag(self.header, flag::Z);
+        while has_ext {
+            let ext: u8 = self.codec.read(&mut *reader)?;
+            let eodec = Zenoh080Header::new(ext);
+            match iext::eid(ext) {
+                ext::EchoRequest::ID => {
+                    let (r, ext): (ext::EchoRequest, bool) = eodec.read(&mut *reader)?;
+                    ext_echo_request = Some(r);
+                    has_ext = ext;
+                }
+                ext::EchoReply::ID => {
+                    let (r, ext): (ext::EchoReply, bool) = eodec.read(&mut *reader)?;
+                    ext_echo_reply = Some(r);
+                    has_ext = ext;
+                }
+                _ => {
+                    has_ext = extension::skip(reader, "KeepAlive", ext)?;
+                }
+            }
         }
 
-        Ok(KeepAlive)
+        Ok(KeepAlive {
+            ext_echo_request,
+            ext_echo_reply,
+        })
     }
 }
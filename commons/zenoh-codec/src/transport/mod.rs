@@ -20,6 +20,7 @@ mod join;
 mod keepalive;
 mod oam;
 mod open;
+pub mod stream;
 
 use crate::{RCodec, WCodec, Zenoh080, Zenoh080Header};
 use zenoh_buffers::{
@@ -0,0 +1,182 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use crate::{RCodec, Zenoh080, Zenoh080Reliability};
+use alloc::vec::Vec;
+use core::mem::size_of;
+use zenoh_buffers::reader::{DidntRead, Reader};
+use zenoh_protocol::{
+    common::imsg,
+    network::{self, NetworkMessage},
+    transport::{id, BatchSize, FrameHeader, TransportMessage},
+};
+
+/// A message yielded by a [`Zenoh080StreamDecoder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamMessage {
+    /// A transport message other than a frame (e.g. KeepAlive, Close, Fragment).
+    Transport(TransportMessage),
+    /// A network message carried by the frame with the given header.
+    Network(FrameHeader, NetworkMessage),
+}
+
+/// An incremental decoder of the length-prefixed batches received on a streamed link.
+///
+/// Bytes are pushed as they arrive and messages are yielded as soon as they are complete,
+/// without waiting for the whole batch to be received: the network messages of a frame are
+/// yielded one by one. Only uncompressed batches are supported.
+///
+/// A message that is not complete yet is decoded again from its start once the bytes received
+/// for it have doubled, or once its batch is complete, so that a large message received in many
+/// small chunks is decoded in a linear rather than quadratic time.
+#[derive(Debug, Default)]
+pub struct Zenoh080StreamDecoder {
+    // The received bytes, the ones before `pos` being already decoded
+    buffer: Vec<u8>,
+    pos: usize,
+    // The number of bytes left in the current batch, None when waiting for a batch length
+    batch: Option<usize>,
+    // The header of the frame being decoded, if any
+    frame: Option<FrameHeader>,
+    // The number of bytes to receive before decoding again the current incomplete message
+    retry_at: usize,
+}
+
+impl Zenoh080StreamDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Push the bytes received on the link.
+    pub fn push(&mut self, bytes: &[u8]) {
+        if self.pos > 0 {
+            self.buffer.drain(..self.pos);
+            self.pos = 0;
+        }
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// The number of received bytes that have not been decoded yet.
+    pub fn pending(&self) -> usize {
+        self.buffer.len() - self.pos
+    }
+
+    /// Reset the decoder, e.g. after a decoding error.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.pos = 0;
+        self.batch = None;
+        self.frame = None;
+        self.retry_at = 0;
+    }
+
+    /// Decode the next complete message, if any.
+    ///
+    /// Returns `Ok(None)` when more bytes are needed, and an error when the current batch
+    /// is fully received but malformed.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Result<Option<StreamMessage>, DidntRead> {
+        loop {
+            let left = match self.batch {
+                Some(0) => {
+                    // End of batch
+                    self.batch = None;
+                    self.frame = None;
+                    continue;
+                }
+                Some(left) => left,
+                None => {
+                    const LEN: usize = size_of::<BatchSize>();
+                    if self.pending() < LEN {
+                        return Ok(None);
+                    }
+                    let mut len = [0u8; LEN];
+                    len.copy_from_slice(&self.buffer[self.pos..self.pos + LEN]);
+                    self.pos += LEN;
+                    self.batch = Some(BatchSize::from_le_bytes(len) as usize);
+                    continue;
+                }
+            };
+
+            let available = left.min(self.pending());
+            if available == 0 {
+                return Ok(None);
+            }
+            let is_complete = available == left;
+            if !is_complete && available < self.retry_at {
+                return Ok(None);
+            }
+            let mut reader = &self.buffer[self.pos..self.pos + available];
+            let mut next_frame = None;
+
+            let header = self.buffer[self.pos];
+            let codec = Zenoh080::new();
+            let res: Result<Option<StreamMessage>, DidntRead> =
+                match (self.frame, imsg::mid(header)) {
+                    // A network message of the current frame
+                    (
+                        Some(frame),
+                        network::id::OAM
+                        | network::id::DECLARE
+                        | network::id::PUSH
+                        | network::id::REQUEST
+                        | network::id::RESPONSE
+                        | network::id::RESPONSE_FINAL,
+                    ) => {
+                        let rcodec = Zenoh080Reliability::new(frame.reliability);
+                        let res: Result<NetworkMessage, DidntRead> = rcodec.read(&mut reader);
+                        res.map(|m| Some(StreamMessage::Network(frame, m)))
+                    }
+                    // A new frame: only its header is decoded, its messages are yielded one by one
+                    (_, id::FRAME) => {
+                        let res: Result<FrameHeader, DidntRead> = codec.read(&mut reader);
+                        match res {
+                            Ok(frame) => {
+                                next_frame = Some(frame);
+                                Ok(None)
+                            }
+                            Err(e) => Err(e),
+                        }
+                    }
+                    // A fragment spans until the end of the batch
+                    (_, id::FRAGMENT) if !is_complete => return Ok(None),
+                    _ => {
+                        let res: Result<TransportMessage, DidntRead> = codec.read(&mut reader);
+                        res.map(|m| Some(StreamMessage::Transport(m)))
+                    }
+                };
+            let read = available - reader.remaining();
+
+            match res {
+                Ok(msg) => {
+                    // Network messages belong to the current frame, any other message ends it
+                    if !matches!(msg, Some(StreamMessage::Network(..))) {
+                        self.frame = next_frame;
+                    }
+                    self.pos += read;
+                    self.batch = Some(left - read);
+                    self.retry_at = 0;
+                    if msg.is_some() {
+                        return Ok(msg);
+                    }
+                }
+                // Wait for the rest of the message
+                Err(_) if !is_complete => {
+                    self.retry_at = available.saturating_mul(2);
+                    return Ok(None);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
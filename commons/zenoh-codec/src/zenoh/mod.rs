@@ -32,7 +32,7 @@ use zenoh_buffers::{
 #[cfg(feature = "shared-memory")]
 use zenoh_protocol::common::{iext, ZExtUnit};
 use zenoh_protocol::{
-    common::{imsg, ZExtZBufHeader},
+    common::{imsg, ZExtZ64, ZExtZBufHeader},
     core::{Encoding, ZenohId},
     zenoh::{ext, id, PushBody, RequestBody, ResponseBody},
 };
@@ -415,3 +415,29 @@ where
         Ok((ext::AttachmentType { buffer }, more))
     }
 }
+
+// Extension: TimestampBound
+impl<W, const ID: u8> WCodec<(&ext::TimestampBoundType<{ ID }>, bool), &mut W> for Zenoh080
+where
+    W: Writer,
+{
+    type Output = Result<(), DidntWrite>;
+
+    fn write(self, writer: &mut W, x: (&ext::TimestampBoundType<{ ID }>, bool)) -> Self::Output {
+        let (x, more) = x;
+        let ext: ZExtZ64<{ ID }> = (*x).into();
+        self.write(&mut *writer, (&ext, more))
+    }
+}
+
+impl<R, const ID: u8> RCodec<(ext::TimestampBoundType<{ ID }>, bool), &mut R> for Zenoh080Header
+where
+    R: Reader,
+{
+    type Error = DidntRead;
+
+    fn read(self, reader: &mut R) -> Result<(ext::TimestampBoundType<{ ID }>, bool), Self::Error> {
+        let (ext, more): (ZExtZ64<{ ID }>, bool) = self.read(&mut *reader)?;
+        Ok((ext.into(), more))
+    }
+}
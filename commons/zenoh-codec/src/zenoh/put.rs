@@ -43,6 +43,7 @@ where
             encoding,
             ext_sinfo,
             ext_attachment,
+            ext_tbound,
             #[cfg(feature = "shared-memory")]
             ext_shm,
             ext_unknown,
@@ -59,6 +60,7 @@ where
         }
         let mut n_exts = (ext_sinfo.is_some()) as u8
             + (ext_attachment.is_some()) as u8
+            + (ext_tbound.is_some()) as u8
             + (ext_unknown.len() as u8);
         #[cfg(feature = "shared-memory")]
         {
@@ -91,6 +93,10 @@ where
             n_exts -= 1;
             self.write(&mut *writer, (att, n_exts != 0))?;
         }
+        if let Some(tbound) = ext_tbound.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (tbound, n_exts != 0))?;
+        }
         for u in ext_unknown.iter() {
             n_exts -= 1;
             self.write(&mut *writer, (u, n_exts != 0))?;
@@ -153,6 +159,7 @@ where
         #[cfg(feature = "shared-memory")]
         let mut ext_shm: Option<ext::ShmType> = None;
         let mut ext_attachment: Option<ext::AttachmentType> = None;
+        let mut ext_tbound: Option<ext::TimestampBoundType> = None;
         let mut ext_unknown = Vec::new();
 
         let mut has_ext = imsg::has_flag(self.header, flag::Z);
@@ -176,6 +183,11 @@ where
                     ext_attachment = Some(a);
                     has_ext = ext;
                 }
+                ext::TimestampBound::ID => {
+                    let (b, ext): (ext::TimestampBoundType, bool) = eodec.read(&mut *reader)?;
+                    ext_tbound = Some(b);
+                    has_ext = ext;
+                }
                 _ => {
                     let (u, ext) = extension::read(reader, "Put", ext)?;
                     ext_unknown.push(u);
@@ -206,6 +218,7 @@ where
             #[cfg(feature = "shared-memory")]
             ext_shm,
             ext_attachment,
+            ext_tbound,
             ext_unknown,
             payload,
         })
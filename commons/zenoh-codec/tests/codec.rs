@@ -279,6 +279,30 @@ fn codec_zbuf() {
     );
 }
 
+#[test]
+fn codec_zbuf_limits() {
+    let zbuf = ZBuf::rand(64);
+    let codec = Zenoh080::new();
+
+    let mut buff = vec![];
+    let mut writer = buff.writer();
+    codec.write(&mut writer, &zbuf).unwrap();
+
+    let limits = CodecLimits {
+        max_payload: 32,
+        ..Default::default()
+    };
+    let mut reader = buff.reader();
+    let r_res: Result<ZBuf, _> = limits.scope(|| codec.read(&mut reader));
+    assert!(r_res.is_err());
+    assert_eq!(CodecLimits::get(), CodecLimits::default());
+
+    // The limits only apply within the scope
+    let mut reader = buff.reader();
+    let r_res: Result<ZBuf, _> = codec.read(&mut reader);
+    assert_eq!(zbuf, r_res.unwrap());
+}
+
 #[test]
 fn codec_zbuf_bounded() {
     use crate::Zenoh080Bounded;
@@ -404,6 +428,84 @@ fn codec_hello() {
     run!(Hello, Hello::rand());
 }
 
+#[test]
+fn codec_hello_ref() {
+    use zenoh_codec::scouting::HelloRef;
+
+    let codec = Zenoh080::new();
+    for _ in 0..NUM_ITER {
+        let hello = Hello::rand();
+        let mut buff = vec![];
+        codec.write(&mut buff.writer(), &hello).unwrap();
+        let mut reader = buff.as_slice();
+        let hello_ref: HelloRef = codec.read(&mut reader).unwrap();
+        assert!(reader.is_empty());
+        assert_eq!(hello_ref.zid, hello.zid);
+        assert_eq!(hello_ref.locators().len(), hello.locators.len());
+        assert_eq!(hello_ref.to_hello().unwrap(), hello);
+    }
+}
+
+#[test]
+fn codec_hello_capabilities() {
+    use zenoh_codec::scouting::HelloRef;
+    use zenoh_protocol::scouting::hello::ext::Capabilities;
+
+    let codec = Zenoh080::new();
+    for _ in 0..NUM_ITER {
+        let hello = Hello::rand();
+        let capabilities = Capabilities::rand();
+        let mut buff = vec![];
+        codec
+            .write(&mut buff.writer(), (&hello, &capabilities))
+            .unwrap();
+
+        let mut reader = buff.reader();
+        let read: (Hello, Option<Capabilities>) = codec.read(&mut reader).unwrap();
+        assert_eq!(read, (hello.clone(), Some(capabilities.clone())));
+
+        // The nodes not interested in the capabilities skip them
+        let mut reader = buff.reader();
+        let read: Hello = codec.read(&mut reader).unwrap();
+        assert_eq!(read, hello);
+
+        let mut reader = buff.as_slice();
+        let hello_ref: HelloRef = codec.read(&mut reader).unwrap();
+        assert!(reader.is_empty());
+        assert_eq!(hello_ref.capabilities(), Some(capabilities));
+        assert_eq!(hello_ref.to_hello().unwrap(), hello);
+    }
+
+    // No capabilities are advertised by the older nodes
+    let hello = Hello::rand();
+    let mut buff = vec![];
+    codec.write(&mut buff.writer(), &hello).unwrap();
+    let read: (Hello, Option<Capabilities>) = codec.read(&mut buff.reader()).unwrap();
+    assert_eq!(read, (hello, None));
+}
+
+#[test]
+fn codec_hello_bounded() {
+    let codec = Zenoh080::new();
+    let hello = Hello::rand();
+    let mut buff = vec![];
+    codec
+        .write(
+            &mut buff.writer(),
+            &Hello {
+                locators: vec![],
+                ..hello
+            },
+        )
+        .unwrap();
+    // A Hello announcing a huge number of locators not present in the message
+    buff[0] |= zenoh_protocol::scouting::hello::flag::L;
+    codec.write(&mut buff.writer(), u64::MAX >> 1).unwrap();
+    let mut reader = buff.reader();
+    let res: Result<Hello, _> = codec.read(&mut reader);
+    assert!(res.is_err());
+}
+
 #[test]
 fn codec_scouting() {
     run!(ScoutingMessage, ScoutingMessage::rand());
@@ -475,6 +577,103 @@ fn codec_transport() {
     run!(TransportMessage, TransportMessage::rand());
 }
 
+#[test]
+fn codec_stream_decoder() {
+    use zenoh_codec::transport::stream::{StreamMessage, Zenoh080StreamDecoder};
+
+    let mut rng = rand::thread_rng();
+    let codec = Zenoh080::new();
+    let mut decoder = Zenoh080StreamDecoder::new();
+    for _ in 0..NUM_ITER {
+        let frame = Frame::rand();
+        let keep_alive: TransportMessage = TransportBody::KeepAlive(KeepAlive::rand()).into();
+
+        let mut batch = vec![];
+        let mut writer = batch.writer();
+        let msg: TransportMessage = TransportBody::Frame(frame.clone()).into();
+        codec.write(&mut writer, &msg).unwrap();
+        codec.write(&mut writer, &keep_alive).unwrap();
+
+        let mut stream = (batch.len() as BatchSize).to_le_bytes().to_vec();
+        stream.extend_from_slice(&batch);
+
+        // Push the bytes in random chunks, as received on a streamed link
+        let mut received = vec![];
+        let mut bytes = stream.as_slice();
+        while !bytes.is_empty() {
+            let len = rng.gen_range(1..=bytes.len().min(8));
+            decoder.push(&bytes[..len]);
+            bytes = &bytes[len..];
+            while let Some(m) = decoder.next().unwrap() {
+                received.push(m);
+            }
+        }
+
+        let header = FrameHeader {
+            reliability: frame.reliability,
+            sn: frame.sn,
+            ext_qos: frame.ext_qos,
+        };
+        let mut expected: Vec<StreamMessage> = frame
+            .payload
+            .into_iter()
+            .map(|m| StreamMessage::Network(header, m))
+            .collect();
+        expected.push(StreamMessage::Transport(keep_alive));
+        assert_eq!(received, expected);
+        assert_eq!(decoder.pending(), 0);
+    }
+}
+
+#[test]
+fn codec_stream_decoder_fragment() {
+    use zenoh_codec::transport::stream::{StreamMessage, Zenoh080StreamDecoder};
+
+    let mut rng = rand::thread_rng();
+    let codec = Zenoh080::new();
+    let mut decoder = Zenoh080StreamDecoder::new();
+    for _ in 0..NUM_ITER {
+        // A fragment spans until the end of its batch, followed by a batch with a keep alive
+        let fragment: TransportMessage = TransportBody::Fragment(Fragment::rand()).into();
+        let keep_alive: TransportMessage = TransportBody::KeepAlive(KeepAlive::rand()).into();
+
+        let mut stream = vec![];
+        let mut batch_ends = vec![];
+        for msg in [&fragment, &keep_alive] {
+            let mut batch = vec![];
+            let mut writer = batch.writer();
+            codec.write(&mut writer, msg).unwrap();
+            stream.extend_from_slice(&(batch.len() as BatchSize).to_le_bytes());
+            stream.extend_from_slice(&batch);
+            batch_ends.push(stream.len());
+        }
+
+        // The fragment is only yielded once its whole batch is received
+        let mut received = vec![];
+        let mut pushed = 0;
+        while pushed < stream.len() {
+            let len = rng.gen_range(1..=(stream.len() - pushed).min(8));
+            decoder.push(&stream[pushed..pushed + len]);
+            pushed += len;
+            while let Some(m) = decoder.next().unwrap() {
+                if received.is_empty() {
+                    assert!(pushed >= batch_ends[0]);
+                }
+                received.push(m);
+            }
+        }
+
+        assert_eq!(
+            received,
+            vec![
+                StreamMessage::Transport(fragment),
+                StreamMessage::Transport(keep_alive)
+            ]
+        );
+        assert_eq!(decoder.pending(), 0);
+    }
+}
+
 // Network
 #[test]
 fn codec_declare() {
@@ -591,3 +790,89 @@ fn codec_ack() {
 fn codec_pull() {
     run!(zenoh::Pull, zenoh::Pull::rand());
 }
+
+// JSON representation
+#[test]
+fn json_messages() {
+    for _ in 0..NUM_ITER {
+        serde_json::to_value(ScoutingMessage::rand()).unwrap();
+        serde_json::to_value(TransportMessage::rand()).unwrap();
+        serde_json::to_value(NetworkMessage::rand()).unwrap();
+    }
+}
+
+#[test]
+fn json_put() {
+    let put = zenoh::Put {
+        timestamp: None,
+        encoding: KnownEncoding::TextPlain.into(),
+        ext_sinfo: None,
+        ext_attachment: None,
+        ext_tbound: None,
+        #[cfg(feature = "shared-memory")]
+        ext_shm: None,
+        ext_unknown: vec![],
+        payload: vec![1u8, 2, 3].into(),
+    };
+    let json = serde_json::to_value(&put).unwrap();
+    assert_eq!(json["encoding"], "text/plain");
+    assert_eq!(json["payload"], serde_json::json!([1, 2, 3]));
+    assert!(json["timestamp"].is_null());
+}
+
+#[test]
+#[cfg(feature = "dissect")]
+fn dissect_messages() {
+    use zenoh_codec::dissect::{Dissect, DissectError};
+
+    for _ in 0..NUM_ITER {
+        let hello = Hello::rand();
+        assert_eq!(Hello::from_json(&hello.to_json().unwrap()).unwrap(), hello);
+        assert_eq!(Hello::from_cbor(&hello.to_cbor().unwrap()).unwrap(), hello);
+
+        let msg = NetworkMessage::rand();
+        assert_eq!(
+            NetworkMessage::from_json(&msg.to_json().unwrap()).unwrap(),
+            msg
+        );
+        assert_eq!(
+            NetworkMessage::from_cbor(&msg.to_cbor().unwrap()).unwrap(),
+            msg
+        );
+    }
+
+    // The fields of a document must match its wire encoding
+    let hello = Hello::rand();
+    let mut json: serde_json::Value = serde_json::from_str(&hello.to_json().unwrap()).unwrap();
+    json["message"]["version"] = serde_json::json!(hello.version.wrapping_add(1));
+    assert!(matches!(
+        Hello::from_json(&json.to_string()),
+        Err(DissectError::Mismatch)
+    ));
+
+    // A document without wire encoding is encoded from its fields
+    json.as_object_mut().unwrap().remove("wire");
+    let edited = Hello::from_json(&json.to_string()).unwrap();
+    assert_eq!(edited.version, hello.version.wrapping_add(1));
+    assert_eq!(edited.zid, hello.zid);
+    assert_eq!(Hello::decode(&edited.encode().unwrap()).unwrap(), edited);
+}
+
+#[test]
+fn json_qos() {
+    let qos = network::push::ext::QoSType::new(Priority::DataHigh, CongestionControl::Block, true);
+    assert_eq!(
+        serde_json::to_value(qos).unwrap(),
+        serde_json::json!({
+            "priority": "DataHigh",
+            "congestion_control": "Block",
+            "express": true,
+        })
+    );
+
+    let qos = transport::frame::ext::QoSType::new(Priority::Background);
+    assert_eq!(
+        serde_json::to_value(qos).unwrap(),
+        serde_json::json!({ "priority": "Background" })
+    );
+}
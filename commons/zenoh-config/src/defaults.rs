@@ -41,6 +41,25 @@ pub mod scouting {
         pub const enabled: bool = true;
         pub const address: ([u8; 4], u16) = ([224, 0, 0, 224], 7446);
         pub const interface: &str = "auto";
+        pub const ttl: u32 = 1;
+        pub mod autoconnect {
+            pub const router: &crate::WhatAmIMatcher = // ""
+                &crate::WhatAmIMatcher::empty();
+            pub const peer: &crate::WhatAmIMatcher = // "router|peer"
+                &crate::WhatAmIMatcher::empty().router().peer();
+            pub const client: &crate::WhatAmIMatcher = // "router|peer"
+                &crate::WhatAmIMatcher::empty().router().peer();
+            mode_accessor!(crate::WhatAmIMatcher);
+        }
+        pub mod listen {
+            pub const router: &bool = &true;
+            pub const peer: &bool = &true;
+            pub const client: &bool = &false;
+            mode_accessor!(bool);
+        }
+    }
+    pub mod mdns {
+        pub const enabled: bool = false;
         pub mod autoconnect {
             pub const router: &crate::WhatAmIMatcher = // ""
                 &crate::WhatAmIMatcher::empty();
@@ -88,15 +107,37 @@ pub mod timestamping {
 #[allow(dead_code)]
 pub const queries_default_timeout: u64 = 10000;
 
+#[allow(non_upper_case_globals)]
+#[allow(dead_code)]
+pub const default_locality: LocalityConf = LocalityConf::Any;
+
+#[allow(non_upper_case_globals)]
+#[allow(dead_code)]
+pub mod qos {
+    use super::*;
+
+    pub const default_priority: PriorityConf = PriorityConf::Data;
+    pub const default_congestion_control: CongestionControlModeConf =
+        CongestionControlModeConf::Drop;
+}
+
 #[allow(non_upper_case_globals)]
 #[allow(dead_code)]
 pub mod routing {
     pub mod router {
         pub const peers_failover_brokering: bool = true;
+        pub const retained_max_keys: usize = 10_000;
     }
     pub mod peer {
         pub const mode: &str = "peer_to_peer";
     }
+    pub mod sweep {
+        pub const interval: u64 = 0;
+        pub const tombstone_ttl: u64 = 60000;
+    }
+    pub mod queries {
+        pub const complete_short_circuit: bool = true;
+    }
 }
 
 impl Default for TransportUnicastConf {
@@ -106,9 +147,71 @@ impl Default for TransportUnicastConf {
             accept_pending: 100,
             max_sessions: 1_000,
             max_links: 1,
+            max_links_per_session: 1,
             lowlatency: false,
             qos: QoSUnicastConf::default(),
             compression: CompressionUnicastConf::default(),
+            hibernation: HibernationUnicastConf::default(),
+            tx: TxUnicastConf::default(),
+        }
+    }
+}
+
+impl Default for TxUnicastConf {
+    fn default() -> Self {
+        Self {
+            latency_target_us: None,
+            queue: TxQueueUnicastConf::default(),
+        }
+    }
+}
+
+impl Default for TxQueueUnicastConf {
+    fn default() -> Self {
+        Self {
+            scheduling: "strict".to_string(),
+            weights: QueueWeightsConf::default(),
+        }
+    }
+}
+
+impl QueueWeightsConf {
+    /// The weights indexed by priority, from the control priority to the background one.
+    pub fn as_array(&self) -> [usize; 8] {
+        [
+            self.control,
+            self.real_time,
+            self.interactive_high,
+            self.interactive_low,
+            self.data_high,
+            self.data,
+            self.data_low,
+            self.background,
+        ]
+    }
+}
+
+impl Default for QueueWeightsConf {
+    fn default() -> Self {
+        Self {
+            control: 64,
+            real_time: 32,
+            interactive_high: 16,
+            interactive_low: 8,
+            data_high: 4,
+            data: 2,
+            data_low: 1,
+            background: 1,
+        }
+    }
+}
+
+impl Default for HibernationUnicastConf {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_timeout: 60_000,
+            retention: 600_000,
         }
     }
 }
@@ -120,6 +223,7 @@ impl Default for TransportMulticastConf {
             max_sessions: Some(1000),
             qos: QoSMulticastConf::default(),
             compression: CompressionMulticastConf::default(),
+            fec: FecMulticastConf::default(),
         }
     }
 }
@@ -140,7 +244,10 @@ impl Default for QoSMulticastConf {
 #[allow(clippy::derivable_impls)]
 impl Default for CompressionUnicastConf {
     fn default() -> Self {
-        Self { enabled: false }
+        Self {
+            enabled: false,
+            algorithm: "lz4".to_string(),
+        }
     }
 }
 
@@ -151,6 +258,15 @@ impl Default for CompressionMulticastConf {
     }
 }
 
+impl Default for FecMulticastConf {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            group_size: 8,
+        }
+    }
+}
+
 impl Default for LinkTxConf {
     #[allow(clippy::unnecessary_cast)]
     fn default() -> Self {
@@ -159,9 +275,12 @@ impl Default for LinkTxConf {
             sequence_number_resolution: Bits::from(TransportSn::MAX),
             lease: 10_000,
             keep_alive: 4,
+            echo_interval: None,
+            rate_limit: None,
             batch_size: BatchSize::MAX,
             queue: QueueConf::default(),
             threads: num,
+            scheduling: TxSchedulingConf::default(),
         }
     }
 }
@@ -209,6 +328,9 @@ impl Default for LinkRxConf {
         Self {
             buffer_size: BatchSize::MAX as usize,
             max_message_size: 2_usize.pow(30),
+            max_locators: 256,
+            max_properties: 1_024,
+            max_links: 4_096,
         }
     }
 }
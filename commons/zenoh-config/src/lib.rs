@@ -38,7 +38,7 @@ pub use zenoh_protocol::core::{
     whatami, EndPoint, Locator, Priority, WhatAmI, WhatAmIMatcher, WhatAmIMatcherVisitor, ZenohId,
 };
 use zenoh_protocol::{
-    core::{key_expr::OwnedKeyExpr, Bits},
+    core::{key_expr::OwnedKeyExpr, Bits, CongestionControl},
     transport::{BatchSize, TransportSn},
 };
 use zenoh_result::{bail, zerror, ZResult};
@@ -80,6 +80,62 @@ pub enum InterceptorFlow {
     Ingress,
 }
 
+/// The locality of the entities an entity exchanges data with.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LocalityConf {
+    /// Only the entities declared on the same session.
+    SessionLocal,
+    /// Only the entities of other sessions.
+    Remote,
+    /// Both local and remote entities.
+    #[default]
+    Any,
+}
+
+/// The priority of the publications.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PriorityConf {
+    RealTime,
+    InteractiveHigh,
+    InteractiveLow,
+    DataHigh,
+    #[default]
+    Data,
+    DataLow,
+    Background,
+}
+
+/// The congestion control strategy of the publications.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CongestionControlModeConf {
+    /// Drop the messages when the transmission queues are full.
+    #[default]
+    Drop,
+    /// Block the publication until the transmission queues have room for the messages.
+    Block,
+}
+
+impl From<CongestionControlModeConf> for CongestionControl {
+    fn from(conf: CongestionControlModeConf) -> Self {
+        match conf {
+            CongestionControlModeConf::Drop => CongestionControl::Drop,
+            CongestionControlModeConf::Block => CongestionControl::Block,
+        }
+    }
+}
+
+/// The scheduling weight of the transports with some remote zenoh instances.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TransportWeightConf {
+    /// The zenoh ids of the remote zenoh instances.
+    pub zids: Vec<ZenohId>,
+    /// The share of the TX threads of the transports, relative to the weights of the other transports.
+    pub weight: usize,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct DownsamplingRuleConf {
     /// A list of key-expressions to which the downsampling will be applied.
@@ -100,6 +156,35 @@ pub struct DownsamplingItemConf {
     pub flow: InterceptorFlow,
 }
 
+/// The access to a portion of the admin space granted to some remote zenoh instances,
+/// see `adminspace.permissions.rules`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AdminSpaceRuleConf {
+    /// The zenoh ids of the remote zenoh instances, all if None.
+    pub zids: Option<Vec<ZenohId>>,
+    /// The network interfaces the remote zenoh instances are connected through, all if None.
+    pub interfaces: Option<Vec<String>>,
+    /// The protocols of the links the remote zenoh instances are connected through (e.g. `unixpipe`), all if None.
+    pub protocols: Option<Vec<String>>,
+    /// The key expressions of the admin space the rule applies to, e.g. `@/*/router/config/**`.
+    pub key_exprs: Vec<OwnedKeyExpr>,
+    /// Whether the queries on these key expressions are allowed.
+    #[serde(default)]
+    pub read: bool,
+    /// Whether the publications on these key expressions (i.e. the config changes) are allowed.
+    #[serde(default)]
+    pub write: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct StaticRouteConf {
+    /// The key expression whose included publications are routed statically.
+    pub key: OwnedKeyExpr,
+    /// The locator of the next hop the publications are forwarded to (e.g. `tcp/10.0.0.5:7447`).
+    /// It is matched against the destination locators of the links established by the router.
+    pub face: Locator,
+}
+
 #[derive(Serialize, Debug, Deserialize, Clone)]
 pub struct AclConfigRules {
     pub interfaces: Option<Vec<String>>,
@@ -215,6 +300,8 @@ validated_struct::validator! {
     Config {
         /// The Zenoh ID of the instance. This ID MUST be unique throughout your Zenoh infrastructure and cannot exceed 16 bytes of length. If left unset, a random u128 will be generated.
         id: ZenohId,
+        /// The directory where `zenohd` persists its runtime state (e.g. its ZenohId) to restore it on restart.
+        state_dir: Option<String>,
         /// The metadata of the instance. Arbitrary json data available from the admin space
         metadata: Value,
         /// The node's mode ("router" (default value in `zenohd`), "peer" or "client").
@@ -235,6 +322,9 @@ validated_struct::validator! {
             /// global timeout for full listen cycle
             pub timeout_ms: Option<ModeDependentValue<i64>>,
             pub endpoints: Vec<EndPoint>,
+            /// The network interfaces to listen on, by name or pattern (e.g. `eth*`, or `!docker*` to exclude them).
+            /// The endpoints on an unspecified address listen on the addresses of these interfaces instead.
+            pub interfaces: Option<Vec<String>>,
             /// if connection timeout exceed, exit from application
             pub exit_on_failure: Option<ModeDependentValue<bool>>,
             pub retry: Option<connection_retry::ConnectionRetryModeDependentConf>,
@@ -253,13 +343,28 @@ validated_struct::validator! {
                 /// The socket which should be used for multicast scouting. `zenohd` will use `224.0.0.224:7446` by default if none is provided.
                 address: Option<SocketAddr>,
                 /// The network interface which should be used for multicast scouting. `zenohd` will automatically select an interface if none is provided.
+                /// Accepts a comma separated list of interface names, addresses or patterns (e.g. `eth*,!eth2`).
                 interface: Option<String>,
+                /// The time-to-live of the scout messages sent on UDP multicast (default: 1, keeping them on the local network).
+                ttl: Option<u32>,
                 /// Which type of Zenoh instances to automatically establish sessions with upon discovery through UDP multicast.
                 #[serde(deserialize_with = "treat_error_as_none")]
                 autoconnect: Option<ModeDependentValue<WhatAmIMatcher>>,
                 /// Whether or not to listen for scout messages on UDP multicast and reply to them.
                 listen: Option<ModeDependentValue<bool>>,
             },
+            /// The mDNS/DNS-SD scouting configuration.
+            pub mdns: #[derive(Default)]
+            ScoutingMdnsConf {
+                /// Whether the `_zenoh._udp` DNS-SD services are registered and browsed over mDNS.
+                /// It requires zenoh to be built with the `scouting_mdns` feature.
+                enabled: Option<bool>,
+                /// Which type of Zenoh instances to automatically establish sessions with upon discovery through mDNS.
+                #[serde(deserialize_with = "treat_error_as_none")]
+                autoconnect: Option<ModeDependentValue<WhatAmIMatcher>>,
+                /// Whether or not to register a `_zenoh._udp` service advertising the locators of this instance.
+                listen: Option<ModeDependentValue<bool>>,
+            },
             /// The gossip scouting configuration.
             pub gossip: #[derive(Default)]
             GossipConf {
@@ -271,6 +376,14 @@ validated_struct::validator! {
                 /// It mostly makes sense when using "linkstate" routing mode where all nodes in the subsystem don't have
                 /// direct connectivity with each other.
                 multihop: Option<bool>,
+                /// The number of times the locators of this instance are relayed by the nodes receiving them,
+                /// e.g. to let the peers of disjoint multicast domains bridged by a router discover each other.
+                /// When set, it overrides `multihop` for the locators of this instance on the nodes supporting it.
+                max_hops: Option<u8>,
+                /// The time in milliseconds during which the locators of this instance are relayed by the nodes
+                /// receiving them. When set, it overrides `multihop` for the locators of this instance on the
+                /// nodes supporting it, and combines with `max_hops` if both are set.
+                ttl: Option<u64>,
                 /// Which type of Zenoh instances to automatically establish sessions with upon discovery through gossip.
                 #[serde(deserialize_with = "treat_error_as_none")]
                 autoconnect: Option<ModeDependentValue<WhatAmIMatcher>>,
@@ -286,11 +399,43 @@ validated_struct::validator! {
             /// If set to false (default), messages with timestamps in the future are retimestamped.
             /// Timestamps are ignored if timestamping is disabled.
             drop_future_timestamp: Option<bool>,
+            /// The bound in milliseconds of the uncertainty of the clock of this instance,
+            /// advertised with the timestamps it generates (default: none advertised).
+            uncertainty: Option<u64>,
+            /// The maximum drift in milliseconds tolerated between the timestamp of a received sample
+            /// and the clock of this instance, beyond the uncertainty bound advertised with the timestamp.
+            /// The samples exceeding it are flagged, but still delivered (default: not checked).
+            max_drift: Option<u64>,
         },
 
         /// The default timeout to apply to queries in milliseconds.
         queries_default_timeout: Option<u64>,
 
+        /// The default locality of the publishers, subscribers, queryables and queries declared by the session,
+        /// unless overridden with `allowed_origin()`/`allowed_destination()` (default: "any").
+        /// Set it to "remote" to suppress the delivery of the samples published by a session to its own subscribers.
+        /// It is read when the session is opened.
+        default_locality: Option<LocalityConf>,
+
+        /// The default quality of service of the publications of the session,
+        /// unless overridden with `priority()`/`congestion_control()` on each of them.
+        /// It is read when the session is opened.
+        pub qos: #[derive(Default)]
+        QoSConf {
+            /// The default priority of the publications (default: "data").
+            default_priority: Option<PriorityConf>,
+            /// The default congestion control of the publications (default: "drop").
+            default_congestion_control: Option<CongestionControlModeConf>,
+        },
+
+        /// The default settings of the publications of the session, read when it is opened.
+        pub publishing: #[derive(Default)]
+        PublishingConf {
+            /// The encoding of the published raw payloads (i.e. whose encoding would otherwise be
+            /// "application/octet-stream"), unless overridden with `encoding()`.
+            default_encoding: Option<String>,
+        },
+
         /// The routing strategy to use and it's configuration.
         pub routing: #[derive(Default)]
         RoutingConf {
@@ -302,6 +447,17 @@ validated_struct::validator! {
                 /// connected to each other.
                 /// The failover brokering only works if gossip discovery is enabled.
                 peers_failover_brokering: Option<bool>,
+                /// A list of static routes taking precedence over the routes learned through
+                /// declarations: publications included in a static route's `key` are only
+                /// forwarded to the face connected to the static route's `face` locator.
+                static_routes: Vec<StaticRouteConf>,
+                /// A list of key expressions whose last publication is retained by the router,
+                /// like the MQTT retained messages: it is sent to the subscribers declared afterwards
+                /// on an intersecting key expression. A deletion removes the retained publication.
+                retained: Vec<OwnedKeyExpr>,
+                /// The maximum number of key expressions whose last publication is retained (default: 10000).
+                /// The publications on new key expressions are not retained once it is reached.
+                retained_max_keys: Option<usize>,
             },
             /// The routing strategy to use in peers and it's configuration.
             pub peer: #[derive(Default)]
@@ -309,6 +465,38 @@ validated_struct::validator! {
                 /// The routing strategy to use in peers. ("peer_to_peer" or "linkstate").
                 mode: Option<String>,
             },
+            /// The limits enforced on the declarations received from the remote clients, the routers and
+            /// peers relaying the declarations of whole networks. Declarations exceeding a limit are
+            /// rejected and the declaring session is notified.
+            pub limits: #[derive(Default)]
+            RoutingLimitsConf {
+                /// The maximum length in bytes of a declared key expression.
+                max_key_expr_length: Option<usize>,
+                /// The maximum number of chunks of a declared key expression.
+                max_key_expr_depth: Option<usize>,
+                /// The maximum number of subscribers and queryables declared by a session.
+                max_declarations: Option<usize>,
+                /// The maximum number of key expressions (resources) declared by a session.
+                max_resources: Option<usize>,
+            },
+            /// The periodic consistency sweep of the routing tables, purging the declarations
+            /// left by the sessions closed abruptly.
+            pub sweep: #[derive(Default)]
+            RoutingSweepConf {
+                /// Interval in milliseconds between two sweeps, 0 to disable them (default: 0).
+                interval: Option<u64>,
+                /// Duration in milliseconds during which the declarations received from a closed
+                /// session are ignored (default: 60000).
+                tombstone_ttl: Option<u64>,
+            },
+            /// The routing of the queries.
+            pub queries: #[derive(Default)]
+            RoutingQueriesConf {
+                /// When set to true, a query targeting the best matching queryables is only routed to
+                /// a complete queryable including the queried key expression if there is one, instead
+                /// of all the matching queryables (default: true).
+                complete_short_circuit: Option<bool>,
+            },
         },
 
         /// The declarations aggregation strategy.
@@ -330,6 +518,9 @@ validated_struct::validator! {
                 max_sessions: usize,
                 /// Maximum number of unicast incoming links per transport session (default: 1)
                 max_links: usize,
+                /// Number of parallel links opened towards the same peer on a connected endpoint (default: 1).
+                /// The messages are striped across them round-robin. It can not exceed `max_links`.
+                max_links_per_session: usize,
                 /// Enables the LowLatency transport (default `false`).
                 /// This option does not make LowLatency transport mandatory, the actual implementation of transport
                 /// used will depend on Establish procedure and other party's settings
@@ -343,6 +534,41 @@ validated_struct::validator! {
                     /// You must compile zenoh with "transport_compression" feature to be able to enable compression.
                     /// When enabled is true, batches will be sent compressed. (default `false`).
                     enabled: bool,
+                    /// The algorithm used to compress the batches: "lz4" or "zstd" (default `"lz4"`).
+                    /// Falls back to "lz4" when the other side doesn't support it.
+                    algorithm: String,
+                },
+                pub hibernation: HibernationUnicastConf {
+                    /// Whether idle transports are hibernated or not (default `false`).
+                    /// A hibernated transport has its links closed but keeps its session and
+                    /// declaration state, and transparently resumes when the peer reconnects.
+                    enabled: bool,
+                    /// Duration in milliseconds without any data exchanged after which a transport is hibernated (default: 60000).
+                    idle_timeout: u64,
+                    /// Duration in milliseconds a hibernated transport is kept before being closed (default: 600000).
+                    retention: u64,
+                },
+                pub tx: TxUnicastConf {
+                    /// The maximum time in microseconds a message waits for its batch to be filled (default: disabled).
+                    /// The batches are flushed as soon as the next message is not expected within this target,
+                    /// adapting the batching to the arrival rate of the messages.
+                    latency_target_us: Option<u64>,
+                    pub queue: TxQueueUnicastConf {
+                        /// How the priority queues are drained: "strict" always sends the batches of the highest
+                        /// priority first, "wrr" sends up to `weights` batches of each priority in turn (default: "strict").
+                        scheduling: String where (queue_scheduling_validator),
+                        /// The number of batches of each priority sent in turn with the "wrr" scheduling.
+                        pub weights: QueueWeightsConf {
+                            control: usize,
+                            real_time: usize,
+                            interactive_high: usize,
+                            interactive_low: usize,
+                            data_high: usize,
+                            data: usize,
+                            data_low: usize,
+                            background: usize,
+                        } where (queue_weights_validator),
+                    },
                 },
             },
             pub multicast: TransportMulticastConf {
@@ -360,12 +586,24 @@ validated_struct::validator! {
                     /// When enabled is true, batches will be sent compressed. (default `false`).
                     enabled: bool,
                 },
+                pub fec: FecMulticastConf {
+                    /// Whether forward error correction is enabled or not on multicast links. (default `false`).
+                    /// All the nodes sharing a multicast group must have the same setting.
+                    enabled: bool,
+                    /// The number of batches per FEC group (default: 8). A parity batch is sent after each
+                    /// group, allowing the receivers to recover one lost batch per group.
+                    group_size: u8 where (fec_group_size_validator),
+                },
             },
             pub link: #[derive(Default)]
             TransportLinkConf {
                 // An optional whitelist of protocols to be used for accepting and opening sessions.
                 // If not configured, all the supported protocols are automatically whitelisted.
                 pub protocols: Option<Vec<String>>,
+                /// The maximum number of bytes buffered in the transmission queues of all the links.
+                /// When exceeded, the best-effort messages are dropped until the queues are drained
+                /// below it. If not configured, the memory usage is only bound by the queue sizes.
+                memory_budget: Option<usize>,
                 pub tx: LinkTxConf {
                     /// The resolution in bits to be used for the message sequence numbers.
                     /// When establishing a session with another Zenoh instance, the lowest value of the two instances will be used.
@@ -375,6 +613,10 @@ validated_struct::validator! {
                     lease: u64,
                     /// Number fo keep-alive messages in a link lease duration (default: 4)
                     keep_alive: usize,
+                    /// Interval in milliseconds between the echo requests measuring the round-trip time of the links (default: disabled)
+                    echo_interval: Option<u64>,
+                    /// Maximum bandwidth of each link in bits per second, e.g. "10mbps" (default: unlimited)
+                    rate_limit: Option<String>,
                     /// Zenoh's MTU equivalent (default: 2^16-1)
                     batch_size: BatchSize,
                     pub queue: QueueConf {
@@ -406,6 +648,17 @@ validated_struct::validator! {
                     },
                     // Number of threads used for TX
                     threads: usize,
+                    /// Weighted scheduling of the transports sharing the TX threads: the unicast and multicast
+                    /// transports with batches of non-control priorities to send share the TX in proportion to
+                    /// their weights, so that a bulk transport can't starve the others.
+                    pub scheduling: #[derive(Default)]
+                    TxSchedulingConf {
+                        /// The weight of the transports not listed in `weights`, including the multicast ones
+                        /// (default: not scheduled).
+                        default_weight: Option<usize>,
+                        /// The weights of the transports with the given remote zenoh instances.
+                        weights: Vec<TransportWeightConf>,
+                    },
                 },
                 pub rx: LinkRxConf {
                     /// Receiving buffer size in bytes for each link
@@ -416,7 +669,38 @@ validated_struct::validator! {
                     buffer_size: usize,
                     /// Maximum size of the defragmentation buffer at receiver end (default: 1GiB).
                     /// Fragmented messages that are larger than the configured size will be dropped.
+                    /// It also bounds the length of the payloads, strings and byte arrays decoded.
                     max_message_size: usize,
+                    /// Maximum number of locators of a list decoded, e.g. in a Hello or a Join.
+                    max_locators: usize,
+                    /// Maximum number of properties of a list decoded.
+                    max_properties: usize,
+                    /// Maximum number of links of a link state, and of link states of a list, decoded.
+                    max_links: usize,
+                },
+                /// Options of the sockets of the TCP links. They can also be set on each endpoint,
+                /// e.g. `tcp/10.0.0.1:7447#nodelay=false;mss=1200`.
+                pub tcp: #[derive(Default)]
+                TcpConf {
+                    /// Whether to disable Nagle's algorithm (default: true).
+                    nodelay: Option<bool>,
+                    /// The time in milliseconds the transmitted data may remain unacknowledged before
+                    /// the connection is closed (Linux only, default: system default).
+                    user_timeout_ms: Option<u64>,
+                    /// The maximum segment size in bytes (Unix only, default: system default).
+                    mss: Option<u32>,
+                },
+                /// The SOCKS5 or HTTP CONNECT proxy the outgoing TCP, TLS and custom links are established
+                /// through. It can also be set on each endpoint, e.g. `tcp/10.0.0.1:7447#proxy=socks5://10.0.0.2:1080`.
+                pub proxy: #[derive(Default)]
+                ProxyConf {
+                    /// The proxy URL, `socks5://<host>:<port>` or `http://<host>:<port>`.
+                    url: Option<String>,
+                    /// The user name to authenticate with the proxy.
+                    username: Option<String>,
+                    // Skip serializing field because it contains secrets
+                    #[serde(skip_serializing)]
+                    password: Option<SecretValue>,
                 },
                 pub tls: #[derive(Default)]
                 TLSConf {
@@ -443,6 +727,18 @@ validated_struct::validator! {
                 UnixPipeConf {
                     file_access_mask: Option<u32>
                 },
+                /// Tuning of all the custom links. It can also be set on each endpoint, e.g. `custom/modem#so_rcvbuf=65536`.
+                pub custom: #[derive(Default)]
+                CustomConf {
+                    /// The size in bytes of the buffer the reads on a custom link go through.
+                    so_rcvbuf: Option<usize>,
+                    /// The size in bytes of the buffer the writes on a custom link go through.
+                    so_sndbuf: Option<usize>,
+                    /// The time in milliseconds to wait for a stream to be registered when opening a custom link.
+                    connect_timeout: Option<u64>,
+                    /// The time in milliseconds after which a custom link not receiving any data is closed.
+                    keepalive: Option<u64>,
+                },
             },
             pub shared_memory:
             SharedMemoryConf {
@@ -494,8 +790,16 @@ validated_struct::validator! {
                 /// Whether the admin space accepts config changes at runtime (false by default).
                 #[serde(default = "set_false")]
                 pub write: bool,
+                /// The portions of the admin space the remote zenoh instances may read or write.
+                /// If set, the queries and publications on the admin space received from a remote
+                /// zenoh instance are dropped unless a rule matching this instance allows them.
+                /// The local sessions are only subject to `read` and `write`.
+                pub rules: Option<Vec<AdminSpaceRuleConf>>,
             },
-
+            /// Whether the publishers declared by the sessions are discoverable by the remote zenoh
+            /// instances (false by default: the sessions only list their own publishers).
+            #[serde(default = "set_false")]
+            pub publications: bool,
         },
 
         /// Configuration of the downsampling.
@@ -528,6 +832,7 @@ impl Default for PermissionsConf {
         PermissionsConf {
             read: true,
             write: false,
+            rules: None,
         }
     }
 }
@@ -969,6 +1274,18 @@ fn queue_size_validator(q: &QueueSizeConf) -> bool {
         && check(background)
 }
 
+fn queue_scheduling_validator(s: &str) -> bool {
+    matches!(s, "strict" | "wrr")
+}
+
+fn queue_weights_validator(w: &QueueWeightsConf) -> bool {
+    w.as_array().iter().all(|w| *w > 0)
+}
+
+fn fec_group_size_validator(k: &u8) -> bool {
+    *k > 0
+}
+
 fn user_conf_validator(u: &UsrPwdConf) -> bool {
     (u.password().is_none() && u.user().is_none()) || (u.password().is_some() && u.user().is_some())
 }
@@ -990,6 +1307,12 @@ fn user_conf_validator(u: &UsrPwdConf) -> bool {
 ///         // If any path is specified, file-search will be disabled, and the first path leading to
 ///         // an existing file will be used
 ///         __path__: string | [string],
+///         // The names of the plugins that must be started, and ready, before this one is started.
+///         // A plugin is ready once started if its status reports no error.
+///         __depends__: [string],
+///         // Defaults to `0`. The number of times the start of this plugin is retried should it fail
+///         // or should its dependencies not be ready yet.
+///         __retries__: number,
 ///         // [plugin_name] may require additional configuration
 ///         ...
 ///     }
@@ -1023,6 +1346,8 @@ pub struct PluginLoad {
     pub name: String,
     pub paths: Option<Vec<String>>,
     pub required: bool,
+    pub depends: Vec<String>,
+    pub retries: u32,
 }
 impl PluginsConfig {
     pub fn sift_privates(&mut self) {
@@ -1045,15 +1370,25 @@ impl PluginsConfig {
                 Some(Value::Bool(b)) => *b,
                 _ => panic!("Plugin '{}' has an invalid '__required__' configuration property (must be a boolean)", name)
             };
+            let depends = match value.get("__depends__") {
+                None => vec![],
+                Some(Value::Array(a)) => a.iter().map(|s| if let Value::String(s) = s {s.clone()} else {panic!("Plugin '{}' has an invalid '__depends__' configuration property (must be an array of strings)", name)}).collect(),
+                _ => panic!("Plugin '{}' has an invalid '__depends__' configuration property (must be an array of strings)", name)
+            };
+            let retries = match value.get("__retries__") {
+                None => 0,
+                Some(Value::Number(n)) if n.as_u64().is_some_and(|n| n <= u32::MAX as u64) => n.as_u64().unwrap() as u32,
+                _ => panic!("Plugin '{}' has an invalid '__retries__' configuration property (must be a positive integer)", name)
+            };
             if let Some(paths) = value.get("__path__"){
                 let paths = match paths {
                     Value::String(s) => vec![s.clone()],
                     Value::Array(a) => a.iter().map(|s| if let Value::String(s) = s {s.clone()} else {panic!("Plugin '{}' has an invalid '__path__' configuration property (must be either string or array of strings)", name)}).collect(),
                     _ => panic!("Plugin '{}' has an invalid '__path__' configuration property (must be either string or array of strings)", name)
                 };
-                PluginLoad {name: name.clone(), paths: Some(paths), required}
+                PluginLoad {name: name.clone(), paths: Some(paths), required, depends, retries}
             } else {
-                PluginLoad {name: name.clone(), paths: None, required}
+                PluginLoad {name: name.clone(), paths: None, required, depends, retries}
             }
         })
     }
@@ -0,0 +1,204 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The values declared `#[mutable]` with [`zconfigurable!`](crate::zconfigurable), that can be
+//! listed and modified at runtime once [`register`]ed.
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{OnceLock, RwLock};
+use zenoh_result::{zerror, ZResult};
+
+/// A value that can be stored in a [`ZConfigurable`].
+pub trait ConfigurableValue: Copy + Display + FromStr + Send + Sync + 'static {
+    fn to_bits(self) -> u64;
+    fn from_bits(bits: u64) -> Self;
+}
+
+macro_rules! impl_configurable_value {
+    ($($t:ty),*) => {
+        $(
+            #[allow(clippy::unnecessary_cast)]
+            impl ConfigurableValue for $t {
+                fn to_bits(self) -> u64 {
+                    self as u64
+                }
+
+                fn from_bits(bits: u64) -> Self {
+                    bits as $t
+                }
+            }
+        )*
+    };
+}
+impl_configurable_value!(u8, u16, u32, u64, usize);
+
+/// A value declared `#[mutable]` with [`zconfigurable!`](crate::zconfigurable).
+///
+/// Its default value can be overridden at compile time by an environment variable of the same
+/// name, and at runtime with [`set`] once registered with [`register`].
+pub struct ZConfigurable<T> {
+    name: &'static str,
+    init: fn() -> T,
+    value: OnceLock<AtomicU64>,
+}
+
+impl<T: ConfigurableValue> ZConfigurable<T> {
+    #[doc(hidden)]
+    pub const fn new(name: &'static str, init: fn() -> T) -> Self {
+        ZConfigurable {
+            name,
+            init,
+            value: OnceLock::new(),
+        }
+    }
+
+    /// The name of the value, i.e. the name of the static it is declared as.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The current value.
+    pub fn get(&self) -> T {
+        T::from_bits(self.value().load(Ordering::Relaxed))
+    }
+
+    /// Modifies the value.
+    pub fn set(&self, value: T) {
+        self.value().store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    fn value(&self) -> &AtomicU64 {
+        self.value
+            .get_or_init(|| AtomicU64::new((self.init)().to_bits()))
+    }
+}
+
+/// A [`ZConfigurable`] whatever the type of its value.
+pub trait Configurable: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn value_string(&self) -> String;
+    fn set_str(&self, value: &str) -> ZResult<()>;
+}
+
+impl<T> Configurable for ZConfigurable<T>
+where
+    T: ConfigurableValue,
+    T::Err: Display,
+{
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn value_string(&self) -> String {
+        self.get().to_string()
+    }
+
+    fn set_str(&self, value: &str) -> ZResult<()> {
+        let value = value
+            .parse()
+            .map_err(|e| zerror!("Invalid value {} for {}: {}", value, self.name, e))?;
+        self.set(value);
+        Ok(())
+    }
+}
+
+// Only filled by the registered statics, thus bounded by their number
+fn registry() -> &'static RwLock<BTreeMap<&'static str, &'static dyn Configurable>> {
+    static REGISTRY: OnceLock<RwLock<BTreeMap<&'static str, &'static dyn Configurable>>> =
+        OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(BTreeMap::new()))
+}
+
+/// Makes a value known to [`list`] and [`set`].
+///
+/// The crates declaring `#[mutable]` values register them all on the creation of the runtime,
+/// so that they can be set before their first use.
+pub fn register(configurable: &'static dyn Configurable) {
+    registry()
+        .write()
+        .unwrap()
+        .insert(configurable.name(), configurable);
+}
+
+/// A value listed by [`list`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigurableInfo {
+    pub name: &'static str,
+    pub value: String,
+}
+
+/// The registered values.
+pub fn list() -> Vec<ConfigurableInfo> {
+    registry()
+        .read()
+        .unwrap()
+        .values()
+        .map(|c| ConfigurableInfo {
+            name: c.name(),
+            value: c.value_string(),
+        })
+        .collect()
+}
+
+/// Sets the registered value named `name` from its string representation.
+pub fn set(name: &str, value: &str) -> ZResult<()> {
+    let configurable = registry()
+        .read()
+        .unwrap()
+        .get(name)
+        .copied()
+        .ok_or_else(|| zerror!("Unknown configurable value {}", name))?;
+    configurable.set_str(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::zconfigurable! {
+        #[mutable]
+        static ref TEST_CONFIGURABLE_MUTABLE: u32 = 42;
+        static ref TEST_CONFIGURABLE_CONSTANT: String = "constant".to_string();
+    }
+
+    #[test]
+    fn configurable() {
+        // The values that can not be modified are plain statics
+        assert_eq!(*TEST_CONFIGURABLE_CONSTANT, "constant");
+        assert!(set("TEST_CONFIGURABLE_CONSTANT", "modified").is_err());
+
+        // The mutable values are unknown until registered
+        assert_eq!(TEST_CONFIGURABLE_MUTABLE.get(), 42);
+        assert!(set("TEST_CONFIGURABLE_MUTABLE", "43").is_err());
+        assert!(!list().iter().any(|c| c.name == "TEST_CONFIGURABLE_MUTABLE"));
+
+        register(&TEST_CONFIGURABLE_MUTABLE);
+        set("TEST_CONFIGURABLE_MUTABLE", "43").unwrap();
+        assert_eq!(TEST_CONFIGURABLE_MUTABLE.get(), 43);
+        assert!(list().contains(&ConfigurableInfo {
+            name: "TEST_CONFIGURABLE_MUTABLE",
+            value: "43".to_string(),
+        }));
+
+        // An invalid value is rejected and leaves the value unchanged
+        assert!(set("TEST_CONFIGURABLE_MUTABLE", "-1").is_err());
+        assert!(set("TEST_CONFIGURABLE_MUTABLE", "forty-two").is_err());
+        assert_eq!(TEST_CONFIGURABLE_MUTABLE.get(), 43);
+
+        TEST_CONFIGURABLE_MUTABLE.set(u32::MAX);
+        assert_eq!(TEST_CONFIGURABLE_MUTABLE.get(), u32::MAX);
+    }
+}
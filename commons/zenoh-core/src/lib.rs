@@ -18,6 +18,7 @@
 //!
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
 pub use lazy_static::lazy_static;
+pub mod configurable;
 pub mod macros;
 
 use std::future::{Future, Ready};
@@ -113,9 +113,21 @@ macro_rules! zasyncrecv {
     };
 }
 
-// This macro allows to define some compile time configurable static constants
+// This macro allows to define some compile time configurable static constants.
+// The values prefixed by #[mutable] can also be modified at runtime, see the configurable module.
 #[macro_export]
 macro_rules! zconfigurable {
+    (#[mutable] $(#[$attr:meta])* $vis:vis static ref $N:ident : $T:ty = $e:expr; $($t:tt)*) => {
+        $(#[$attr])*
+        $vis static $N: $crate::configurable::ZConfigurable<$T> =
+            $crate::configurable::ZConfigurable::new(stringify!($N), || {
+                match option_env!(stringify!($N)) {
+                    Some(value) => value.parse().unwrap(),
+                    None => $e,
+                }
+            });
+        $crate::zconfigurable!($($t)*);
+    };
     ($(#[$attr:meta])* static ref $N:ident : $T:ty = $e:expr; $($t:tt)*) => {
         $crate::lazy_static!($(#[$attr])* static ref $N : $T = match option_env!(stringify!($N)) {
             Some(value) => {value.parse().unwrap()}
@@ -123,6 +123,58 @@ impl keyexpr {
         OwnedKeyExpr::autocanonize(format!("{}/{}", self, other.as_ref()))
     }
 
+    /// Joins both sides like [`keyexpr::join`], but refuses to move a key in or out of the admin space.
+    ///
+    /// Since `other` is appended after `self`, an admin key expression used as `other` would silently
+    /// lose its admin nature (e.g. `cache` joined with `@/session/**`). Such joins are rejected:
+    /// ```rust
+    /// # use zenoh_keyexpr::keyexpr;
+    /// let prefix = keyexpr::new("cache").unwrap();
+    /// assert_eq!(prefix.checked_join("demo/**").unwrap().as_str(), "cache/demo/**");
+    /// assert!(prefix.checked_join("@/session/**").is_err());
+    /// ```
+    pub fn checked_join<S: AsRef<str> + ?Sized>(&self, other: &S) -> ZResult<OwnedKeyExpr> {
+        let other = other.as_ref();
+        if is_admin_str(other) {
+            bail!(
+                "Joining `{}` with admin key expression `{}` would move it out of the admin space",
+                self,
+                other
+            )
+        }
+        let joined = self.join(other)?;
+        if joined.is_admin() != self.is_admin() {
+            bail!(
+                "Joining `{}` with `{}` would move it in or out of the admin space",
+                self,
+                other
+            )
+        }
+        Ok(joined)
+    }
+
+    /// Returns `true` if `self` belongs to the admin space, i.e. if its first chunk is the `@` verbatim chunk.
+    ///
+    /// As wildcards never match verbatim chunks, a key expression that isn't admin (such as `**`)
+    /// never intersects with the admin space.
+    /// ```rust
+    /// # use zenoh_keyexpr::keyexpr;
+    /// assert!(keyexpr::new("@/session/**").unwrap().is_admin());
+    /// assert!(!keyexpr::new("@session/**").unwrap().is_admin());
+    /// assert!(!keyexpr::new("**").unwrap().is_admin());
+    /// ```
+    pub fn is_admin(&self) -> bool {
+        is_admin_str(&self.0)
+    }
+
+    /// Returns `true` if `self` contains any verbatim chunk, i.e. a chunk starting with `@`.
+    ///
+    /// Verbatim chunks may only be matched by an identical chunk: wildcards (`*` and `**`) never match them.
+    pub fn has_verbatim(&self) -> bool {
+        use super::intersect::MayHaveVerbatim;
+        self.as_bytes().has_verbatim()
+    }
+
     /// Returns `true` if `self` contains any wildcard character (`**` or `$*`).
     pub fn is_wild(&self) -> bool {
         self.0.contains(super::SINGLE_WILD as char)
@@ -536,6 +588,10 @@ impl<'a> DoubleEndedIterator for Chunks<'a> {
     }
 }
 
+fn is_admin_str(s: &str) -> bool {
+    s == "@" || s.starts_with("@/")
+}
+
 impl Div for &keyexpr {
     type Output = OwnedKeyExpr;
     fn div(self, rhs: Self) -> Self::Output {
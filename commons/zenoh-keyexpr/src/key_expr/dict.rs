@@ -0,0 +1,198 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use super::{keyexpr, OwnedKeyExpr, DELIMITER};
+use alloc::{string::String, sync::Arc, vec::Vec};
+use std::collections::HashMap;
+
+/// The longest prefix shared by all the `keys` made of chunks without wildcards,
+/// or `None` if there is no key or they share no such prefix.
+///
+/// # Examples
+/// ```
+/// # use zenoh_keyexpr::{keyexpr, longest_common_prefix};
+/// let keys = ["demo/example/a/b", "demo/example/a/c", "demo/example/b"].map(|k| keyexpr::new(k).unwrap());
+/// assert_eq!(longest_common_prefix(keys), Some(keyexpr::new("demo/example").unwrap()));
+///
+/// let keys = ["demo/*/a", "demo/*/a"].map(|k| keyexpr::new(k).unwrap());
+/// assert_eq!(longest_common_prefix(keys), Some(keyexpr::new("demo").unwrap()));
+///
+/// let keys = ["demo/a", "test/a"].map(|k| keyexpr::new(k).unwrap());
+/// assert_eq!(longest_common_prefix(keys), None);
+/// ```
+pub fn longest_common_prefix<'a, I>(keys: I) -> Option<&'a keyexpr>
+where
+    I: IntoIterator<Item = &'a keyexpr>,
+{
+    let mut keys = keys.into_iter();
+    let mut prefix = keys.next()?.get_nonwild_prefix()?.as_str();
+    for key in keys {
+        let mut len = 0;
+        for (l, r) in prefix.split('/').zip(key.as_str().split('/')) {
+            if l != r {
+                break;
+            }
+            len += l.len() + 1;
+        }
+        if len == 0 {
+            return None;
+        }
+        // Remove the trailing delimiter
+        prefix = &prefix[..len - 1];
+    }
+    Some(unsafe { keyexpr::from_str_unchecked(prefix) })
+}
+
+/// The identifier of a key in a [`KeyDict`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KeyId(u32);
+
+impl KeyId {
+    /// The identifier as an integer, e.g. to index a table.
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
+}
+
+const ROOT: u32 = u32::MAX;
+
+// A key, as its last chunk appended to its parent key
+#[derive(Clone, Copy)]
+struct Node {
+    parent: u32,
+    chunk: u32,
+}
+
+/// A compact dictionary mapping keys to integer identifiers, and back.
+///
+/// The keys are stored as trees of chunks, each distinct chunk being stored once: the keys sharing
+/// deep prefixes, e.g. the millions of keys of a storage, cost a few integers each instead of their
+/// whole string. The prefixes of a key get their own identifier when the key is inserted.
+///
+/// The identifiers are never reclaimed, so that they remain valid as long as the dictionary: the
+/// keys no longer in use are dropped by building a new dictionary from the remaining ones.
+///
+/// # Examples
+/// ```
+/// # use zenoh_keyexpr::{keyexpr, KeyDict};
+/// let mut dict = KeyDict::new();
+/// let id = dict.insert(keyexpr::new("demo/example/a").unwrap());
+/// assert_eq!(dict.get_id(keyexpr::new("demo/example/a").unwrap()), Some(id));
+/// assert_eq!(dict.get(id).unwrap().as_str(), "demo/example/a");
+/// // "demo", "demo/example" and "demo/example/a"
+/// assert_eq!(dict.len(), 3);
+/// ```
+#[derive(Clone, Default)]
+pub struct KeyDict {
+    chunks: Vec<Arc<str>>,
+    chunk_ids: HashMap<Arc<str>, u32>,
+    nodes: Vec<Node>,
+    children: HashMap<(u32, u32), u32>,
+}
+
+impl KeyDict {
+    /// Create an empty dictionary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a key, returning its identifier: the same one if the key is already known.
+    ///
+    /// # Panics
+    /// If the dictionary already holds `u32::MAX` keys or chunks.
+    pub fn insert(&mut self, key: &keyexpr) -> KeyId {
+        let mut parent = ROOT;
+        for chunk in key.as_str().split('/') {
+            let chunk = match self.chunk_ids.get(chunk) {
+                Some(id) => *id,
+                None => {
+                    let id = Self::next_id(self.chunks.len());
+                    let chunk: Arc<str> = chunk.into();
+                    self.chunks.push(chunk.clone());
+                    self.chunk_ids.insert(chunk, id);
+                    id
+                }
+            };
+            parent = match self.children.get(&(parent, chunk)) {
+                Some(id) => *id,
+                None => {
+                    let id = Self::next_id(self.nodes.len());
+                    self.nodes.push(Node { parent, chunk });
+                    self.children.insert((parent, chunk), id);
+                    id
+                }
+            };
+        }
+        KeyId(parent)
+    }
+
+    fn next_id(len: usize) -> u32 {
+        match u32::try_from(len) {
+            Ok(id) if id != ROOT => id,
+            _ => panic!("KeyDict overflow: more than {} entries", ROOT),
+        }
+    }
+
+    /// The identifier of a key, if it has been inserted.
+    pub fn get_id(&self, key: &keyexpr) -> Option<KeyId> {
+        let mut parent = ROOT;
+        for chunk in key.as_str().split('/') {
+            let chunk = *self.chunk_ids.get(chunk)?;
+            parent = *self.children.get(&(parent, chunk))?;
+        }
+        Some(KeyId(parent))
+    }
+
+    /// The key of an identifier, if it belongs to this dictionary.
+    pub fn get(&self, id: KeyId) -> Option<OwnedKeyExpr> {
+        let mut node = *self.nodes.get(id.0 as usize)?;
+        let mut chunks = vec![&self.chunks[node.chunk as usize]];
+        while node.parent != ROOT {
+            node = self.nodes[node.parent as usize];
+            chunks.push(&self.chunks[node.chunk as usize]);
+        }
+        let mut key = String::with_capacity(chunks.iter().map(|c| c.len() + 1).sum());
+        for (i, chunk) in chunks.iter().rev().enumerate() {
+            if i != 0 {
+                key.push(DELIMITER as char);
+            }
+            key.push_str(chunk);
+        }
+        // The chunks come from canonized key expressions
+        Some(unsafe { OwnedKeyExpr::from_string_unchecked(key) })
+    }
+
+    /// The number of keys of the dictionary, including the prefixes of the inserted keys.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether no key has been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The number of distinct chunks of the keys of the dictionary.
+    pub fn chunks(&self) -> usize {
+        self.chunks.len()
+    }
+}
+
+impl core::fmt::Debug for KeyDict {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("KeyDict")
+            .field("keys", &self.len())
+            .field("chunks", &self.chunks())
+            .finish()
+    }
+}
@@ -43,5 +43,11 @@ pub mod fuzzer;
 
 pub mod format;
 
+/// Compact dictionaries of keys and prefix utilities, e.g. for storages holding many keys.
+#[cfg(feature = "std")]
+pub(crate) mod dict;
+#[cfg(feature = "std")]
+pub use dict::{longest_common_prefix, KeyDict, KeyId};
+
 #[cfg(test)]
 mod tests;
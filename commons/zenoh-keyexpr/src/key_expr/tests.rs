@@ -200,3 +200,71 @@ fn fuzz() {
         ke1 = ke2;
     }
 }
+
+#[test]
+fn verbatim_joins() {
+    let ke = |s: &'static str| keyexpr::new(s).unwrap();
+    assert!(ke("@").is_admin());
+    assert!(ke("@/router/**").is_admin());
+    assert!(!ke("@router/**").is_admin());
+    assert!(!ke("**").is_admin());
+    assert!(!ke("a/@/b").is_admin());
+
+    assert!(ke("a/@b/c").has_verbatim());
+    assert!(!ke("a/b@/c").has_verbatim());
+
+    assert_eq!(
+        ke("a/**").checked_join("@b/c").unwrap().as_str(),
+        "a/**/@b/c"
+    );
+    assert_eq!(
+        ke("@/session").checked_join("**").unwrap().as_str(),
+        "@/session/**"
+    );
+    assert!(ke("a").checked_join("@/session/**").is_err());
+    assert!(ke("a").checked_join("@").is_err());
+}
+
+#[test]
+fn key_dict() {
+    use crate::key_expr::{longest_common_prefix, KeyDict};
+    let ke = |s: &'static str| keyexpr::new(s).unwrap();
+
+    assert_eq!(longest_common_prefix(Vec::<&keyexpr>::new()), None);
+    assert_eq!(longest_common_prefix([ke("a/b/c")]), Some(ke("a/b/c")));
+    assert_eq!(
+        longest_common_prefix([ke("a/b/c"), ke("a/b/d"), ke("a/b")]),
+        Some(ke("a/b"))
+    );
+    assert_eq!(
+        longest_common_prefix([ke("a/bc/d"), ke("a/b/d")]),
+        Some(ke("a"))
+    );
+    assert_eq!(
+        longest_common_prefix([ke("a/**"), ke("a/b")]),
+        Some(ke("a"))
+    );
+    assert_eq!(longest_common_prefix([ke("a/b"), ke("a/*")]), Some(ke("a")));
+    assert_eq!(longest_common_prefix([ke("*/b"), ke("*/b")]), None);
+    assert_eq!(longest_common_prefix([ke("a/b"), ke("b/b")]), None);
+
+    let mut dict = KeyDict::new();
+    assert!(dict.is_empty());
+    let keys = ["a/b/c", "a/b/d", "a/e/c", "f", "a/b"];
+    let ids: Vec<_> = keys.iter().map(|k| dict.insert(ke(*k))).collect();
+    for (key, id) in keys.iter().zip(&ids) {
+        assert_eq!(dict.get_id(ke(*key)), Some(*id));
+        assert_eq!(dict.get(*id).unwrap().as_str(), *key);
+        assert_eq!(dict.insert(ke(*key)), *id);
+    }
+    // a, a/b, a/b/c, a/b/d, a/e, a/e/c, f
+    assert_eq!(dict.len(), 7);
+    // a, b, c, d, e, f
+    assert_eq!(dict.chunks(), 6);
+    assert_eq!(
+        dict.get_id(ke("a/e")).map(|id| dict.get(id).unwrap()),
+        Some(ke("a/e").into())
+    );
+    assert_eq!(dict.get_id(ke("a/c")), None);
+    assert_eq!(dict.get_id(ke("g")), None);
+}
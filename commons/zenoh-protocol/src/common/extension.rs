@@ -108,6 +108,7 @@ pub struct DidntConvert;
 
 #[repr(transparent)]
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ZExtUnit<const ID: u8>;
 
 impl<const ID: u8> Default for ZExtUnit<{ ID }> {
@@ -165,6 +166,7 @@ impl<const ID: u8> TryFrom<ZExtUnknown> for ZExtUnit<{ ID }> {
 
 #[repr(transparent)]
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ZExtZ64<const ID: u8> {
     pub value: u64,
 }
@@ -222,6 +224,7 @@ impl<const ID: u8> TryFrom<ZExtUnknown> for ZExtZ64<{ ID }> {
 
 #[repr(transparent)]
 #[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ZExtZBuf<const ID: u8> {
     pub value: ZBuf,
 }
@@ -278,6 +281,7 @@ impl<const ID: u8> TryFrom<ZExtUnknown> for ZExtZBuf<{ ID }> {
 }
 
 #[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ZExtZBufHeader<const ID: u8> {
     pub len: usize,
 }
@@ -307,6 +311,7 @@ impl<const ID: u8> Debug for ZExtZBufHeader<{ ID }> {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ZExtBody {
     Unit,
     Z64(u64),
@@ -330,6 +335,7 @@ impl ZExtBody {
 }
 
 #[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ZExtUnknown {
     pub id: u8,
     pub body: ZExtBody,
@@ -49,6 +49,7 @@ mod consts {
 
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum KnownEncoding {
     Empty = 0,
     AppOctetStream = 1,
@@ -196,6 +197,27 @@ impl Encoding {
     pub const IMAGE_GIF: Encoding = Encoding::Exact(KnownEncoding::ImageGif);
 }
 
+// Encodings are serialized in their string form, e.g. "text/plain;charset=utf-8"
+#[cfg(feature = "serialize")]
+impl serde::Serialize for Encoding {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<'de> serde::Deserialize<'de> for Encoding {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        <String as serde::Deserialize>::deserialize(deserializer).map(Encoding::from)
+    }
+}
+
 impl fmt::Display for Encoding {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -55,6 +55,7 @@ pub mod resolution;
 pub use resolution::*;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Property {
     pub key: u64,
     pub value: Vec<u8>,
@@ -63,6 +64,7 @@ pub struct Property {
 /// The kind of a `Sample`.
 #[repr(u8)]
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum SampleKind {
     /// if the `Sample` was issued by a `put` operation.
     #[default]
@@ -301,6 +303,7 @@ impl<'de> serde::Deserialize<'de> for ZenohId {
 
 #[repr(u8)]
 #[derive(Debug, Default, Copy, Clone, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Priority {
     Control = 0,
     RealTime = 1,
@@ -346,6 +349,7 @@ impl TryFrom<u8> for Priority {
 }
 
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Reliability {
     #[default]
@@ -369,6 +373,7 @@ impl Reliability {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Channel {
     pub priority: Priority,
     pub reliability: Reliability,
@@ -376,6 +381,7 @@ pub struct Channel {
 
 /// The kind of congestion control.
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum CongestionControl {
     #[default]
@@ -385,6 +391,7 @@ pub enum CongestionControl {
 
 /// The subscription mode.
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum SubMode {
     #[default]
@@ -393,12 +400,14 @@ pub enum SubMode {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct SubInfo {
     pub reliability: Reliability,
     pub mode: SubMode,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct QueryableInfo {
     pub complete: u64, // Default 0: incomplete
     pub distance: u64, // Default 0: no distance
@@ -406,6 +415,7 @@ pub struct QueryableInfo {
 
 /// The kind of consolidation.
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ConsolidationMode {
     /// No consolidation applied: multiple samples may be received for the same key-timestamp.
     None,
@@ -423,6 +433,7 @@ pub enum ConsolidationMode {
 
 /// The `zenoh::queryable::Queryable`s that should be target of a `zenoh::Session::get()`.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum QueryTarget {
     #[default]
     BestMatching,
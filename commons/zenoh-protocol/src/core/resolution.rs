@@ -113,6 +113,7 @@ impl fmt::Display for Bits {
 #[repr(u8)]
 // The value indicates the bit offest
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Field {
     FrameSN = 0,
     RequestID = 2,
@@ -120,6 +121,7 @@ pub enum Field {
 
 #[repr(transparent)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Resolution(u8);
 
 impl Resolution {
@@ -56,6 +56,7 @@ pub const EMPTY_EXPR_ID: ExprId = 0;
 // +---------------+
 //
 #[derive(PartialEq, Eq, Hash, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct WireExpr<'a> {
     pub scope: ExprId, // 0 marks global scope
     pub suffix: Cow<'a, str>,
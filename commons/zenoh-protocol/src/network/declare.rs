@@ -46,6 +46,7 @@ pub mod flag {
 /// +---------------+
 ///
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Declare {
     pub ext_qos: ext::QoSType,
     pub ext_tstamp: Option<ext::TimestampType>,
@@ -88,6 +89,7 @@ pub mod id {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum DeclareBody {
     DeclareKeyExpr(DeclareKeyExpr),
     UndeclareKeyExpr(UndeclareKeyExpr),
@@ -148,6 +150,7 @@ impl Declare {
 }
 
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Mode {
     #[default]
@@ -179,6 +182,7 @@ pub mod common {
         // WARNING: this is a temporary and mandatory extension used for undeclarations
         pub type WireExprExt = zextzbuf!(0x0f, true);
         #[derive(Debug, Clone, PartialEq, Eq)]
+        #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
         pub struct WireExprType {
             pub wire_expr: WireExpr<'static>,
         }
@@ -233,6 +237,7 @@ pub mod keyexpr {
     /// +---------------+
     /// ```
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct DeclareKeyExpr {
         pub id: ExprId,
         pub wire_expr: WireExpr<'static>,
@@ -267,6 +272,7 @@ pub mod keyexpr {
     /// +---------------+
     /// ```
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct UndeclareKeyExpr {
         pub id: ExprId,
     }
@@ -319,6 +325,7 @@ pub mod subscriber {
     ///
     /// ```
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct DeclareSubscriber {
         pub id: SubscriberId,
         pub wire_expr: WireExpr<'static>,
@@ -345,6 +352,7 @@ pub mod subscriber {
         /// - rsv:  Reserved
         /// ```        
         #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+        #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
         pub struct SubscriberInfo {
             pub reliability: Reliability,
             pub mode: Mode,
@@ -427,6 +435,7 @@ pub mod subscriber {
     /// +---------------+
     /// ```
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct UndeclareSubscriber {
         pub id: SubscriberId,
         // WARNING: this is a temporary and mandatory extension used for undeclarations
@@ -483,6 +492,7 @@ pub mod queryable {
     /// - if D==1 then the queryable distance is present
     /// ```
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct DeclareQueryable {
         pub id: QueryableId,
         pub wire_expr: WireExpr<'static>,
@@ -503,6 +513,7 @@ pub mod queryable {
         /// ~   distance    ~
         /// +---------------+
         #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+        #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
         pub struct QueryableInfo {
             pub complete: u8,  // Default 0: incomplete // @TODO: maybe a bitflag
             pub distance: u32, // Default 0: no distance
@@ -572,6 +583,7 @@ pub mod queryable {
     /// +---------------+
     /// ```
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct UndeclareQueryable {
         pub id: QueryableId,
         // WARNING: this is a temporary and mandatory extension used for undeclarations
@@ -624,6 +636,7 @@ pub mod token {
     ///
     /// ```
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct DeclareToken {
         pub id: TokenId,
         pub wire_expr: WireExpr<'static>,
@@ -658,6 +671,7 @@ pub mod token {
     /// +---------------+
     /// ```
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct UndeclareToken {
         pub id: TokenId,
         // WARNING: this is a temporary and mandatory extension used for undeclarations
@@ -759,6 +773,7 @@ pub mod interest {
     ///     - if A==1 then the replies SHOULD be aggregated
     /// ```
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct DeclareInterest {
         pub id: InterestId,
         pub wire_expr: WireExpr<'static>,
@@ -767,6 +782,7 @@ pub mod interest {
 
     #[repr(transparent)]
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct Interest(u8);
 
     impl Interest {
@@ -870,6 +886,7 @@ pub mod interest {
     /// +---------------+
     /// ```
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct FinalInterest {
         pub id: InterestId,
     }
@@ -902,6 +919,7 @@ pub mod interest {
     /// +---------------+
     /// ```
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct UndeclareInterest {
         pub id: InterestId,
         // WARNING: this is a temporary and mandatory extension used for undeclarations
@@ -44,6 +44,7 @@ pub mod id {
 
 #[repr(u8)]
 #[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Mapping {
     #[default]
     Receiver = 0,
@@ -66,6 +67,7 @@ impl Mapping {
 
 // Zenoh messages at zenoh-network level
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum NetworkBody {
     Push(Push),
     Request(Request),
@@ -76,6 +78,7 @@ pub enum NetworkBody {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct NetworkMessage {
     pub body: NetworkBody,
     #[cfg(feature = "stats")]
@@ -221,6 +224,41 @@ pub mod ext {
         inner: u8,
     }
 
+    // The QoS is serialized with its fields rather than its wire representation
+    #[cfg(feature = "serialize")]
+    impl<const ID: u8> serde::Serialize for QoSType<{ ID }> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            use serde::ser::SerializeStruct;
+
+            let mut s = serializer.serialize_struct("QoSType", 3)?;
+            s.serialize_field("priority", &self.get_priority())?;
+            s.serialize_field("congestion_control", &self.get_congestion_control())?;
+            s.serialize_field("express", &self.is_express())?;
+            s.end()
+        }
+    }
+
+    #[cfg(feature = "serialize")]
+    impl<'de, const ID: u8> serde::Deserialize<'de> for QoSType<{ ID }> {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct Fields {
+                priority: Priority,
+                congestion_control: CongestionControl,
+                express: bool,
+            }
+
+            let qos = <Fields as serde::Deserialize>::deserialize(deserializer)?;
+            Ok(Self::new(qos.priority, qos.congestion_control, qos.express))
+        }
+    }
+
     impl<const ID: u8> QoSType<{ ID }> {
         const P_MASK: u8 = 0b00000111;
         const D_FLAG: u8 = 0b00001000;
@@ -347,6 +385,7 @@ pub mod ext {
     /// +---------------+
     /// ```
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct TimestampType<const ID: u8> {
         pub timestamp: uhlc::Timestamp,
     }
@@ -373,6 +412,7 @@ pub mod ext {
     /// +---------------+
     /// ```
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct NodeIdType<const ID: u8> {
         pub node_id: u16,
     }
@@ -408,6 +448,49 @@ pub mod ext {
         }
     }
 
+    /// ```text
+    ///  7 6 5 4 3 2 1 0
+    /// +-+-+-+-+-+-+-+-+
+    /// %     hops      %
+    /// +---------------+
+    /// ```
+    ///
+    /// The number of links a message may still be forwarded on. A node doesn't forward a message
+    /// whose hop limit is exhausted to the other nodes, and decrements it otherwise.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+    pub struct HopLimitType<const ID: u8> {
+        pub hops: u8,
+    }
+
+    impl<const ID: u8> HopLimitType<{ ID }> {
+        pub const fn new(hops: u8) -> Self {
+            Self { hops }
+        }
+
+        #[cfg(feature = "test")]
+        pub fn rand() -> Self {
+            use rand::Rng;
+            let mut rng = rand::thread_rng();
+            let hops = rng.gen();
+            Self { hops }
+        }
+    }
+
+    impl<const ID: u8> From<ZExtZ64<{ ID }>> for HopLimitType<{ ID }> {
+        fn from(ext: ZExtZ64<{ ID }>) -> Self {
+            Self {
+                hops: u8::try_from(ext.value).unwrap_or(u8::MAX),
+            }
+        }
+    }
+
+    impl<const ID: u8> From<HopLimitType<{ ID }>> for ZExtZ64<{ ID }> {
+        fn from(ext: HopLimitType<{ ID }>) -> Self {
+            ZExtZ64::new(ext.hops as u64)
+        }
+    }
+
     ///  7 6 5 4 3 2 1 0
     /// +-+-+-+-+-+-+-+-+
     /// |zid_len|X|X|X|X|
@@ -417,6 +500,7 @@ pub mod ext {
     /// %      eid      %
     /// +---------------+
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct EntityIdType<const ID: u8> {
         pub zid: ZenohId,
         pub eid: u32,
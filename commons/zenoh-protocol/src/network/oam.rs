@@ -25,6 +25,15 @@ pub mod id {
     use super::OamId;
 
     pub const OAM_LINKSTATE: OamId = 0x0001;
+    /// Sent back to a session whose declaration was rejected, the body carries the reason.
+    pub const OAM_DECLARE_REJECTED: OamId = 0x0002;
+    /// Sent to the sessions whose complete queryables overlap, the body carries the key expression
+    /// of the queryable of the receiver, the one of the overlapping queryable and the id of its node.
+    pub const OAM_QUERYABLE_CONFLICT: OamId = 0x0003;
+    /// Sent to the neighbours when the transmission queues of a node exceed their memory budget
+    /// or fall back below it, the body carries the id of the node, then 1 if it is congested
+    /// and 0 if it recovered.
+    pub const OAM_CONGESTION: OamId = 0x0004;
 }
 
 /// ```text
@@ -51,6 +60,7 @@ pub mod id {
 /// - 0b11: Reserved
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Oam {
     pub id: OamId,
     pub body: ZExtBody,
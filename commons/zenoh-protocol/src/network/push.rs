@@ -39,11 +39,13 @@ pub mod flag {
 /// +---------------+
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Push {
     pub wire_expr: WireExpr<'static>,
     pub ext_qos: ext::QoSType,
     pub ext_tstamp: Option<ext::TimestampType>,
     pub ext_nodeid: ext::NodeIdType,
+    pub ext_hoplimit: Option<ext::HopLimitType>,
     pub payload: PushBody,
 }
 
@@ -61,6 +63,9 @@ pub mod ext {
 
     pub type NodeId = zextz64!(0x3, true);
     pub type NodeIdType = crate::network::ext::NodeIdType<{ NodeId::ID }>;
+
+    pub type HopLimit = zextz64!(0x4, false);
+    pub type HopLimitType = crate::network::ext::HopLimitType<{ HopLimit::ID }>;
 }
 
 impl Push {
@@ -74,6 +79,7 @@ impl Push {
         let ext_qos = ext::QoSType::rand();
         let ext_tstamp = rng.gen_bool(0.5).then(ext::TimestampType::rand);
         let ext_nodeid = ext::NodeIdType::rand();
+        let ext_hoplimit = rng.gen_bool(0.5).then(ext::HopLimitType::rand);
 
         Self {
             wire_expr,
@@ -81,6 +87,7 @@ impl Push {
             ext_tstamp,
             ext_qos,
             ext_nodeid,
+            ext_hoplimit,
         }
     }
 }
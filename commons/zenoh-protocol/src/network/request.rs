@@ -51,6 +51,7 @@ pub mod flag {
 ///     This implementation limits the resolution to 32bit.
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Request {
     pub id: RequestId,
     pub wire_expr: WireExpr<'static>,
@@ -46,6 +46,7 @@ pub mod flag {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Response {
     pub rid: RequestId,
     pub wire_expr: WireExpr<'static>,
@@ -114,6 +115,7 @@ impl Response {
 /// (*) The resolution of the request id is negotiated during the session establishment.
 ///     This implementation limits the resolution to 32bit.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ResponseFinal {
     pub rid: RequestId,
     pub ext_qos: ext::QoSType,
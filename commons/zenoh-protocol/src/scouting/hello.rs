@@ -83,6 +83,8 @@ use core::fmt;
 /// +---------------+
 /// ~   <utf8;z8>   ~ if Flag(L)==1 -- List of locators
 /// +---------------+
+/// ~   [HelloExts] ~ if Flag(Z)==1
+/// +---------------+
 ///
 /// (*) WhatAmI. It indicates the role of the zenoh node sending the HELLO message.
 ///    The valid WhatAmI values are:
@@ -99,6 +101,7 @@ pub mod flag {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Hello {
     pub version: u8,
     pub whatami: WhatAmI,
@@ -106,6 +109,68 @@ pub struct Hello {
     pub locators: Vec<Locator>,
 }
 
+pub mod ext {
+    use crate::common::ZExtZBufHeader;
+    use alloc::vec::Vec;
+
+    pub const CAPABILITIES: u8 = ZExtZBufHeader::<0x1>::id(false);
+
+    /// # Capabilities extension
+    ///
+    /// Advertises the capabilities of the node sending the [`super::Hello`], so that the scouting
+    /// node can pick the best node to connect to before opening a session. It is encoded and
+    /// decoded alongside the [`super::Hello`], which does not carry it.
+    ///
+    /// The extension body is a list of properties, each one made of a z64 identifier followed by
+    /// a length-prefixed value. The properties with an unknown identifier are skipped, so that
+    /// new capabilities can be advertised without breaking the older nodes.
+    ///
+    /// ```text
+    ///  7 6 5 4 3 2 1 0
+    /// +-+-+-+-+-+-+-+-+
+    /// %   num props   %
+    /// +---------------+
+    /// ~   <prop_id>   ~ -- z64
+    /// +---------------+
+    /// ~  <u8;z32>     ~ -- Property value
+    /// +---------------+
+    /// ~      ...      ~
+    /// +---------------+
+    /// ```
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Capabilities {
+        /// Whether the node is able to exchange messages over shared memory.
+        pub shm: bool,
+        /// The mask of the compression algorithms the node is able to decompress.
+        pub compression: u64,
+        /// The protocol versions the node is able to speak.
+        pub versions: Vec<u8>,
+    }
+
+    impl Capabilities {
+        pub const SHM: u64 = 0x1;
+        pub const COMPRESSION: u64 = 0x2;
+        pub const VERSIONS: u64 = 0x3;
+
+        #[cfg(feature = "test")]
+        pub fn rand() -> Self {
+            use rand::Rng;
+
+            let mut rng = rand::thread_rng();
+
+            let shm = rng.gen_bool(0.5);
+            let compression = if rng.gen_bool(0.5) { rng.gen() } else { 0 };
+            let versions = (0..rng.gen_range(0..4)).map(|_| rng.gen()).collect();
+            Self {
+                shm,
+                compression,
+                versions,
+            }
+        }
+    }
+}
+
 impl fmt::Display for Hello {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Hello")
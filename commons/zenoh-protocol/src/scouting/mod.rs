@@ -25,12 +25,14 @@ pub mod id {
 
 // Zenoh messages at scouting level
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ScoutingBody {
     Scout(Scout),
     Hello(Hello),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ScoutingMessage {
     pub body: ScoutingBody,
     #[cfg(feature = "stats")]
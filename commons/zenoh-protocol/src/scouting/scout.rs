@@ -72,6 +72,7 @@ pub mod flag {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Scout {
     pub version: u8,
     pub what: WhatAmIMatcher,
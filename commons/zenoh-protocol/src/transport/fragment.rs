@@ -68,6 +68,7 @@ pub mod flag {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Fragment {
     pub reliability: Reliability,
     pub more: bool,
@@ -109,6 +110,7 @@ impl Fragment {
 
 // FragmentHeader
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct FragmentHeader {
     pub reliability: Reliability,
     pub more: bool,
@@ -67,6 +67,7 @@ pub mod flag {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Frame {
     pub reliability: Reliability,
     pub sn: TransportSn,
@@ -109,6 +110,7 @@ impl Frame {
 
 // FrameHeader
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct FrameHeader {
     pub reliability: Reliability,
     pub sn: TransportSn,
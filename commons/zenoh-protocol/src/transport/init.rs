@@ -107,6 +107,7 @@ pub mod flag {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct InitSyn {
     pub version: u8,
     pub whatami: WhatAmI,
@@ -119,13 +120,14 @@ pub struct InitSyn {
     pub ext_mlink: Option<ext::MultiLink>,
     pub ext_lowlatency: Option<ext::LowLatency>,
     pub ext_compression: Option<ext::Compression>,
+    pub ext_compression_algorithms: Option<ext::CompressionAlgorithms>,
 }
 
 // Extensions
 pub mod ext {
     use crate::{
-        common::{ZExtUnit, ZExtZBuf},
-        zextunit, zextzbuf,
+        common::{ZExtUnit, ZExtZ64, ZExtZBuf},
+        zextunit, zextz64, zextzbuf,
     };
 
     /// # QoS extension
@@ -151,12 +153,17 @@ pub mod ext {
     /// # Compression extension
     /// Used to negotiate the use of compression on the link
     pub type Compression = zextunit!(0x6, false);
+
+    /// # Compression algorithms extension
+    /// Sent along with the Compression extension, it carries the mask of the compression
+    /// algorithms the sender is able to decompress. Without it, only lz4 is supported.
+    pub type CompressionAlgorithms = zextz64!(0x7, false);
 }
 
 impl InitSyn {
     #[cfg(feature = "test")]
     pub fn rand() -> Self {
-        use crate::common::{ZExtUnit, ZExtZBuf};
+        use crate::common::{ZExtUnit, ZExtZ64, ZExtZBuf};
         use rand::Rng;
 
         let mut rng = rand::thread_rng();
@@ -172,6 +179,7 @@ impl InitSyn {
         let ext_mlink = rng.gen_bool(0.5).then_some(ZExtZBuf::rand());
         let ext_lowlatency = rng.gen_bool(0.5).then_some(ZExtUnit::rand());
         let ext_compression = rng.gen_bool(0.5).then_some(ZExtUnit::rand());
+        let ext_compression_algorithms = rng.gen_bool(0.5).then_some(ZExtZ64::rand());
 
         Self {
             version,
@@ -185,11 +193,13 @@ impl InitSyn {
             ext_mlink,
             ext_lowlatency,
             ext_compression,
+            ext_compression_algorithms,
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct InitAck {
     pub version: u8,
     pub whatami: WhatAmI,
@@ -203,12 +213,13 @@ pub struct InitAck {
     pub ext_mlink: Option<ext::MultiLink>,
     pub ext_lowlatency: Option<ext::LowLatency>,
     pub ext_compression: Option<ext::Compression>,
+    pub ext_compression_algorithms: Option<ext::CompressionAlgorithms>,
 }
 
 impl InitAck {
     #[cfg(feature = "test")]
     pub fn rand() -> Self {
-        use crate::common::{ZExtUnit, ZExtZBuf};
+        use crate::common::{ZExtUnit, ZExtZ64, ZExtZBuf};
         use rand::Rng;
 
         let mut rng = rand::thread_rng();
@@ -229,6 +240,7 @@ impl InitAck {
         let ext_mlink = rng.gen_bool(0.5).then_some(ZExtZBuf::rand());
         let ext_lowlatency = rng.gen_bool(0.5).then_some(ZExtUnit::rand());
         let ext_compression = rng.gen_bool(0.5).then_some(ZExtUnit::rand());
+        let ext_compression_algorithms = rng.gen_bool(0.5).then_some(ZExtZ64::rand());
 
         Self {
             version,
@@ -243,6 +255,7 @@ impl InitAck {
             ext_mlink,
             ext_lowlatency,
             ext_compression,
+            ext_compression_algorithms,
         }
     }
 }
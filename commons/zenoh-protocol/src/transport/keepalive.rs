@@ -53,6 +53,11 @@
 ///       connectivity check which considers a link as failed when no messages are received in
 ///       3.5 times the target keep alive interval.
 ///
+/// A [`KeepAlive`] message MAY carry an EchoRequest extension with a timestamp of its sender,
+/// in which case the receiver SHOULD promptly send back a [`KeepAlive`] message carrying an
+/// EchoReply extension with the same timestamp, allowing the sender to measure the round-trip
+/// time of the link. The timestamp is only meaningful to its sender.
+///
 /// The [`KeepAlive`] message structure is defined as follows:
 ///
 /// ```text
@@ -81,12 +86,41 @@ pub mod flag {
     pub const Z: u8 = 1 << 7; // 0x80 Extensions    if Z==1 then an extension will follow
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct KeepAlive;
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeepAlive {
+    pub ext_echo_request: Option<ext::EchoRequest>,
+    pub ext_echo_reply: Option<ext::EchoReply>,
+}
+
+// Extensions
+pub mod ext {
+    use crate::{common::ZExtZ64, zextz64};
+
+    /// # EchoRequest extension
+    /// Used to measure the round-trip time of the link: it carries a timestamp of the sender
+    /// to be sent back in an EchoReply extension.
+    pub type EchoRequest = zextz64!(0x1, false);
+
+    /// # EchoReply extension
+    /// The timestamp of a received EchoRequest extension, sent back unchanged.
+    pub type EchoReply = zextz64!(0x2, false);
+}
 
 impl KeepAlive {
     #[cfg(feature = "test")]
     pub fn rand() -> Self {
-        Self
+        use crate::common::ZExtZ64;
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+
+        let ext_echo_request = rng.gen_bool(0.5).then_some(ZExtZ64::rand());
+        let ext_echo_reply = rng.gen_bool(0.5).then_some(ZExtZ64::rand());
+
+        Self {
+            ext_echo_request,
+            ext_echo_reply,
+        }
     }
 }
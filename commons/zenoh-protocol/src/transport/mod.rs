@@ -61,6 +61,7 @@ pub mod id {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransportMessageLowLatency {
     pub body: TransportBodyLowLatency,
 }
@@ -76,6 +77,7 @@ impl TryFrom<NetworkMessage> for TransportMessageLowLatency {
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum TransportBodyLowLatency {
     Close(Close),
     KeepAlive(KeepAlive),
@@ -85,6 +87,7 @@ pub enum TransportBodyLowLatency {
 pub type TransportSn = u32;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct PrioritySn {
     pub reliable: TransportSn,
     pub best_effort: TransportSn,
@@ -105,6 +108,7 @@ impl PrioritySn {
 
 // Zenoh messages at zenoh-transport level
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum TransportBody {
     InitSyn(InitSyn),
     InitAck(InitAck),
@@ -119,6 +123,7 @@ pub enum TransportBody {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransportMessage {
     pub body: TransportBody,
     #[cfg(feature = "stats")]
@@ -260,6 +265,37 @@ pub mod ext {
         inner: u8,
     }
 
+    // The QoS is serialized with its fields rather than its wire representation
+    #[cfg(feature = "serialize")]
+    impl<const ID: u8> serde::Serialize for QoSType<{ ID }> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            use serde::ser::SerializeStruct;
+
+            let mut s = serializer.serialize_struct("QoSType", 1)?;
+            s.serialize_field("priority", &self.priority())?;
+            s.end()
+        }
+    }
+
+    #[cfg(feature = "serialize")]
+    impl<'de, const ID: u8> serde::Deserialize<'de> for QoSType<{ ID }> {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct Fields {
+                priority: Priority,
+            }
+
+            let qos = <Fields as serde::Deserialize>::deserialize(deserializer)?;
+            Ok(Self::new(qos.priority))
+        }
+    }
+
     impl<const ID: u8> QoSType<{ ID }> {
         pub const P_MASK: u8 = 0b00000111;
 
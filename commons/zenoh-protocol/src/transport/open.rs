@@ -73,6 +73,7 @@ pub mod flag {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpenSyn {
     pub lease: Duration,
     pub initial_sn: TransportSn,
@@ -159,6 +160,7 @@ impl OpenSyn {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpenAck {
     pub lease: Duration,
     pub initial_sn: TransportSn,
@@ -39,14 +39,17 @@ pub mod flag {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Del {
     pub timestamp: Option<Timestamp>,
     pub ext_sinfo: Option<ext::SourceInfoType>,
     pub ext_attachment: Option<ext::AttachmentType>,
+    pub ext_tbound: Option<ext::TimestampBoundType>,
     pub ext_unknown: Vec<ZExtUnknown>,
 }
 
 pub mod ext {
+    use crate::{common::ZExtZ64, zextz64};
     use crate::{common::ZExtZBuf, zextzbuf};
 
     /// # SourceInfo extension
@@ -57,6 +60,11 @@ pub mod ext {
     /// # User attachment
     pub type Attachment = zextzbuf!(0x2, false);
     pub type AttachmentType = crate::zenoh::ext::AttachmentType<{ Attachment::ID }>;
+
+    /// # TimestampBound extension
+    /// Used to carry the uncertainty bound of the clock that generated the timestamp
+    pub type TimestampBound = zextz64!(0x3, false);
+    pub type TimestampBoundType = crate::zenoh::ext::TimestampBoundType<{ TimestampBound::ID }>;
 }
 
 impl Del {
@@ -73,10 +81,11 @@ impl Del {
         });
         let ext_sinfo = rng.gen_bool(0.5).then_some(ext::SourceInfoType::rand());
         let ext_attachment = rng.gen_bool(0.5).then_some(ext::AttachmentType::rand());
+        let ext_tbound = rng.gen_bool(0.5).then_some(ext::TimestampBoundType::rand());
         let mut ext_unknown = Vec::new();
         for _ in 0..rng.gen_range(0..4) {
             ext_unknown.push(ZExtUnknown::rand2(
-                iext::mid(ext::Attachment::ID) + 1,
+                iext::mid(ext::TimestampBound::ID) + 1,
                 false,
             ));
         }
@@ -85,6 +94,7 @@ impl Del {
             timestamp,
             ext_sinfo,
             ext_attachment,
+            ext_tbound,
             ext_unknown,
         }
     }
@@ -41,12 +41,14 @@ pub mod id {
 
 // DataInfo
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct DataInfo {
     pub encoding: Encoding,
 }
 
 // Push
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum PushBody {
     Put(Put),
     Del(Del),
@@ -81,6 +83,7 @@ impl From<Del> for PushBody {
 
 // Request
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum RequestBody {
     Query(Query),
     Put(Put),
@@ -124,6 +127,7 @@ impl From<Del> for RequestBody {
 
 // Response
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ResponseBody {
     Reply(Reply),
     Err(Err),
@@ -167,9 +171,13 @@ impl From<Ack> for ResponseBody {
 }
 
 pub mod ext {
+    use uhlc::NTP64;
     use zenoh_buffers::ZBuf;
 
-    use crate::core::{Encoding, ZenohId};
+    use crate::{
+        common::ZExtZ64,
+        core::{Encoding, ZenohId},
+    };
 
     ///  7 6 5 4 3 2 1 0
     /// +-+-+-+-+-+-+-+-+
@@ -182,6 +190,7 @@ pub mod ext {
     /// %      sn       %
     /// +---------------+
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct SourceInfoType<const ID: u8> {
         pub zid: ZenohId,
         pub eid: u32,
@@ -206,6 +215,7 @@ pub mod ext {
     /// +-+-+-+-+-+-+-+-+
     #[cfg(feature = "shared-memory")]
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct ShmType<const ID: u8>;
 
     #[cfg(feature = "shared-memory")]
@@ -233,6 +243,7 @@ pub mod ext {
     ///  ~ pl: [u8;z32]  ~  -- Payload
     ///  +---------------+
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct ValueType<const VID: u8, const SID: u8> {
         #[cfg(feature = "shared-memory")]
         pub ext_shm: Option<ShmType<{ SID }>>,
@@ -263,6 +274,47 @@ pub mod ext {
         }
     }
 
+    /// ```text
+    ///  7 6 5 4 3 2 1 0
+    /// +-+-+-+-+-+-+-+-+
+    /// %     bound     %  -- NTP64 duration
+    /// +---------------+
+    /// ```
+    ///
+    /// The bound of the uncertainty of the clock that generated the timestamp of the message:
+    /// its actual time is expected within `[timestamp - bound, timestamp + bound]`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+    pub struct TimestampBoundType<const ID: u8> {
+        pub bound: NTP64,
+    }
+
+    impl<const ID: u8> TimestampBoundType<{ ID }> {
+        pub const fn new(bound: NTP64) -> Self {
+            Self { bound }
+        }
+
+        #[cfg(feature = "test")]
+        pub fn rand() -> Self {
+            use rand::Rng;
+            let mut rng = rand::thread_rng();
+
+            Self::new(NTP64(rng.gen()))
+        }
+    }
+
+    impl<const ID: u8> From<ZExtZ64<{ ID }>> for TimestampBoundType<{ ID }> {
+        fn from(ext: ZExtZ64<{ ID }>) -> Self {
+            Self::new(NTP64(ext.value))
+        }
+    }
+
+    impl<const ID: u8> From<TimestampBoundType<{ ID }>> for ZExtZ64<{ ID }> {
+        fn from(ext: TimestampBoundType<{ ID }>) -> Self {
+            ZExtZ64::new(ext.bound.0)
+        }
+    }
+
     /// ```text
     /// 7 6 5 4 3 2 1 0
     /// +-+-+-+-+-+-+-+-+
@@ -275,6 +327,7 @@ pub mod ext {
     ///       ...         -- N times (key, value) tuples
     /// ```
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     pub struct AttachmentType<const ID: u8> {
         pub buffer: ZBuf,
     }
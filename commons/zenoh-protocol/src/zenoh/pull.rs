@@ -36,6 +36,7 @@ pub mod flag {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pull {
     pub ext_unknown: Vec<ZExtUnknown>,
 }
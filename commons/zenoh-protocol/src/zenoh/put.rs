@@ -44,11 +44,13 @@ pub mod flag {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Put {
     pub timestamp: Option<Timestamp>,
     pub encoding: Encoding,
     pub ext_sinfo: Option<ext::SourceInfoType>,
     pub ext_attachment: Option<ext::AttachmentType>,
+    pub ext_tbound: Option<ext::TimestampBoundType>,
     #[cfg(feature = "shared-memory")]
     pub ext_shm: Option<ext::ShmType>,
     pub ext_unknown: Vec<ZExtUnknown>,
@@ -58,6 +60,7 @@ pub struct Put {
 pub mod ext {
     #[cfg(feature = "shared-memory")]
     use crate::{common::ZExtUnit, zextunit};
+    use crate::{common::ZExtZ64, zextz64};
     use crate::{common::ZExtZBuf, zextzbuf};
 
     /// # SourceInfo extension
@@ -75,6 +78,11 @@ pub mod ext {
     /// # User attachment
     pub type Attachment = zextzbuf!(0x3, false);
     pub type AttachmentType = crate::zenoh::ext::AttachmentType<{ Attachment::ID }>;
+
+    /// # TimestampBound extension
+    /// Used to carry the uncertainty bound of the clock that generated the timestamp
+    pub type TimestampBound = zextz64!(0x4, false);
+    pub type TimestampBoundType = crate::zenoh::ext::TimestampBoundType<{ TimestampBound::ID }>;
 }
 
 impl Put {
@@ -94,10 +102,11 @@ impl Put {
         #[cfg(feature = "shared-memory")]
         let ext_shm = rng.gen_bool(0.5).then_some(ext::ShmType::rand());
         let ext_attachment = rng.gen_bool(0.5).then_some(ext::AttachmentType::rand());
+        let ext_tbound = rng.gen_bool(0.5).then_some(ext::TimestampBoundType::rand());
         let mut ext_unknown = Vec::new();
         for _ in 0..rng.gen_range(0..4) {
             ext_unknown.push(ZExtUnknown::rand2(
-                iext::mid(ext::Attachment::ID) + 1,
+                iext::mid(ext::TimestampBound::ID) + 1,
                 false,
             ));
         }
@@ -110,6 +119,7 @@ impl Put {
             #[cfg(feature = "shared-memory")]
             ext_shm,
             ext_attachment,
+            ext_tbound,
             ext_unknown,
             payload,
         }
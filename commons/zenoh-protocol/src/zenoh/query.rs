@@ -17,6 +17,7 @@ use alloc::{string::String, vec::Vec};
 /// The kind of consolidation.
 #[repr(u8)]
 #[derive(Debug, Default, Clone, PartialEq, Eq, Copy)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Consolidation {
     /// Apply automatic consolidation based on queryable's preferences
     #[default]
@@ -89,6 +90,7 @@ pub mod flag {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Query {
     pub parameters: String,
     pub ext_sinfo: Option<ext::SourceInfoType>,
@@ -11,7 +11,11 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+#[cfg(feature = "log-file")]
+use std::path::PathBuf;
+use tracing::Level;
 use tracing_subscriber::EnvFilter;
+use zenoh_result::{zerror, ZResult};
 
 /// This is an utility function to enable the tracing formatting subscriber from
 /// the `RUST_LOG` environment variable. If `RUST_LOG` is not set, then logging is not enabled.
@@ -55,6 +59,173 @@ fn init_env_filter(env_filter: EnvFilter) {
     let _ = tracing::subscriber::set_global_default(subscriber);
 }
 
+/// The rotation of a log file, i.e. how often a new file is created.
+#[cfg(feature = "log-file")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogRotation {
+    Minutely,
+    Hourly,
+    Daily,
+    Never,
+}
+
+#[cfg(feature = "log-file")]
+#[derive(Debug, Clone)]
+struct LogFile {
+    directory: PathBuf,
+    prefix: String,
+    rotation: LogRotation,
+    max_files: Option<usize>,
+}
+
+/// A programmatic setup of the tracing formatting subscriber, with per-module levels
+/// and, with the `log-file` feature, a rotating log file instead of the standard output.
+///
+/// ```no_run
+/// use zenoh_util::LogConfig;
+///
+/// LogConfig::new("info")
+///     .level("zenoh_transport", tracing::Level::DEBUG)
+///     .from_env(true)
+///     .try_init()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    // The default directives, e.g. `info` or `zenoh=info,zenoh_link=warn`
+    default: String,
+    levels: Vec<(String, Level)>,
+    // Whether the `RUST_LOG` directives override the configured ones
+    from_env: bool,
+    #[cfg(feature = "log-file")]
+    file: Option<LogFile>,
+}
+
+impl LogConfig {
+    /// A configuration logging with the given default directives, e.g. `info`.
+    pub fn new<S>(default: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            default: default.into(),
+            levels: vec![],
+            from_env: false,
+            #[cfg(feature = "log-file")]
+            file: None,
+        }
+    }
+
+    /// Log the events of `module` (e.g. `zenoh_transport`) up to `level`.
+    pub fn level<S>(mut self, module: S, level: Level) -> Self
+    where
+        S: Into<String>,
+    {
+        self.levels.push((module.into(), level));
+        self
+    }
+
+    /// Let the directives of the `RUST_LOG` environment variable, if set, override
+    /// the configured ones.
+    pub fn from_env(mut self, from_env: bool) -> Self {
+        self.from_env = from_env;
+        self
+    }
+
+    /// Log in the files of `directory` named after `prefix` and rotated as `rotation`.
+    #[cfg(feature = "log-file")]
+    pub fn file<P, S>(mut self, directory: P, prefix: S, rotation: LogRotation) -> Self
+    where
+        P: Into<PathBuf>,
+        S: Into<String>,
+    {
+        self.file = Some(LogFile {
+            directory: directory.into(),
+            prefix: prefix.into(),
+            rotation,
+            max_files: None,
+        });
+        self
+    }
+
+    /// Keep at most `max_files` rotated log files, deleting the oldest ones.
+    #[cfg(feature = "log-file")]
+    pub fn max_files(mut self, max_files: usize) -> Self {
+        if let Some(file) = self.file.as_mut() {
+            file.max_files = Some(max_files);
+        }
+        self
+    }
+
+    fn env_filter(&self) -> ZResult<EnvFilter> {
+        let mut env_filter = EnvFilter::try_new(&self.default)
+            .map_err(|e| zerror!("Invalid log directives {}: {}", self.default, e))?;
+        for (module, level) in self.levels.iter() {
+            let directive = format!("{module}={level}");
+            env_filter = env_filter.add_directive(
+                directive
+                    .parse()
+                    .map_err(|e| zerror!("Invalid log directive {}: {}", directive, e))?,
+            );
+        }
+        if self.from_env {
+            if let Ok(env) = std::env::var(EnvFilter::DEFAULT_ENV) {
+                for directive in env.split(',').filter(|d| !d.is_empty()) {
+                    env_filter = env_filter.add_directive(
+                        directive
+                            .parse()
+                            .map_err(|e| zerror!("Invalid log directive {}: {}", directive, e))?,
+                    );
+                }
+            }
+        }
+        Ok(env_filter)
+    }
+
+    /// Initializes the tracing formatting subscriber, failing if the configuration is
+    /// invalid or if a global subscriber is already set.
+    pub fn try_init(self) -> ZResult<()> {
+        let subscriber = tracing_subscriber::fmt()
+            .with_env_filter(self.env_filter()?)
+            .with_thread_ids(true)
+            .with_thread_names(true)
+            .with_level(true)
+            .with_target(true);
+
+        #[cfg(feature = "log-file")]
+        if let Some(file) = self.file {
+            use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+            let rotation = match file.rotation {
+                LogRotation::Minutely => Rotation::MINUTELY,
+                LogRotation::Hourly => Rotation::HOURLY,
+                LogRotation::Daily => Rotation::DAILY,
+                LogRotation::Never => Rotation::NEVER,
+            };
+            let mut appender = RollingFileAppender::builder()
+                .rotation(rotation)
+                .filename_prefix(&file.prefix);
+            if let Some(max_files) = file.max_files {
+                appender = appender.max_log_files(max_files);
+            }
+            let appender = appender.build(&file.directory).map_err(|e| {
+                zerror!(
+                    "Couldn't open log file {} in {}: {}",
+                    file.prefix,
+                    file.directory.display(),
+                    e
+                )
+            })?;
+            let subscriber = subscriber.with_ansi(false).with_writer(appender).finish();
+            return tracing::subscriber::set_global_default(subscriber)
+                .map_err(|e| zerror!("Couldn't initialize logging: {}", e).into());
+        }
+
+        tracing::subscriber::set_global_default(subscriber.finish())
+            .map_err(|e| zerror!("Couldn't initialize logging: {}", e).into())
+    }
+}
+
 #[cfg(feature = "test")]
 // Used to verify memory leaks for valgrind CI.
 // `EnvFilter` internally uses a static reference that is not cleaned up yielding to false positive in valgrind.
@@ -70,3 +241,20 @@ pub fn init_log_test() {
     let subscriber = subscriber.finish();
     let _ = tracing::subscriber::set_global_default(subscriber);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_config() {
+        let config = LogConfig::new("info")
+            .level("zenoh_transport", Level::DEBUG)
+            .level("zenoh_link::tcp", Level::TRACE);
+        let env_filter = config.env_filter().unwrap().to_string().to_lowercase();
+        assert!(env_filter.contains("zenoh_transport=debug"));
+        assert!(env_filter.contains("zenoh_link::tcp=trace"));
+
+        assert!(LogConfig::new("zenoh=loud").env_filter().is_err());
+    }
+}
@@ -8,6 +8,11 @@ pub use timer::*;
 pub mod log;
 pub use log::*;
 
+/// Registers the values of this crate that can be modified at runtime.
+pub fn register_configurables() {
+    timer::register_configurables();
+}
+
 /// The "ZENOH_HOME" environement variable name
 pub const ZENOH_HOME_ENV_VAR: &str = "ZENOH_HOME";
 
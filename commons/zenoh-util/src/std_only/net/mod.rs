@@ -269,6 +269,93 @@ pub fn get_unicast_addresses_of_interface(name: &str) -> ZResult<Vec<IpAddr>> {
     }
 }
 
+// Whether `name` matches `pattern`, where `*` matches any sequence of characters
+fn matches_interface_pattern(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => name.strip_prefix(prefix).is_some_and(|name| {
+            (0..=name.len())
+                .filter(|i| name.is_char_boundary(*i))
+                .any(|i| matches_interface_pattern(rest, &name[i..]))
+        }),
+    }
+}
+
+/// Whether the interface `name` is selected by the given interface names or patterns.
+///
+/// The patterns may contain `*` wildcards (e.g. `eth*`), and the patterns prefixed with `!` exclude
+/// the matching interfaces (e.g. `!docker*`): an interface is selected if it matches no excluding pattern,
+/// and at least one including pattern if there is any.
+pub fn is_interface_selected(name: &str, patterns: &[String]) -> bool {
+    let mut included = None;
+    for pattern in patterns {
+        let pattern = pattern.trim();
+        match pattern.strip_prefix('!') {
+            Some(pattern) => {
+                if matches_interface_pattern(pattern, name) {
+                    return false;
+                }
+            }
+            None => {
+                included =
+                    Some(included.unwrap_or(false) || matches_interface_pattern(pattern, name))
+            }
+        }
+    }
+    included.unwrap_or(true)
+}
+
+/// Get the unicast addresses of the active interfaces selected by the given interface names or patterns
+/// (see [`is_interface_selected`]).
+pub fn get_unicast_addresses_of_selected_interfaces(patterns: &[String]) -> Vec<IpAddr> {
+    #[cfg(unix)]
+    {
+        pnet_datalink::interfaces()
+            .iter()
+            .filter(|iface| {
+                iface.is_up() && iface.is_running() && is_interface_selected(&iface.name, patterns)
+            })
+            .flat_map(|iface| {
+                iface
+                    .ips
+                    .iter()
+                    .filter(|ip| !ip.ip().is_multicast())
+                    .map(|x| x.ip())
+                    .collect::<Vec<IpAddr>>()
+            })
+            .collect()
+    }
+
+    #[cfg(windows)]
+    {
+        unsafe {
+            use crate::ffi;
+            use winapi::um::iptypes::IP_ADAPTER_ADDRESSES_LH;
+
+            let Ok(buffer) = get_adapters_adresses(winapi::shared::ws2def::AF_UNSPEC) else {
+                return vec![];
+            };
+
+            let mut addrs = vec![];
+            let mut next_iface = (buffer.as_ptr() as *mut IP_ADAPTER_ADDRESSES_LH).as_ref();
+            while let Some(iface) = next_iface {
+                // The adapter names are GUIDs: select the interfaces by their friendly name
+                if is_interface_selected(&ffi::pwstr_to_string(iface.FriendlyName), patterns) {
+                    let mut next_ucast_addr = iface.FirstUnicastAddress.as_ref();
+                    while let Some(ucast_addr) = next_ucast_addr {
+                        if let Ok(ifaddr) = ffi::win::sockaddr_to_addr(ucast_addr.Address) {
+                            addrs.push(ifaddr.ip());
+                        }
+                        next_ucast_addr = ucast_addr.Next.as_ref();
+                    }
+                }
+                next_iface = iface.Next.as_ref();
+            }
+            addrs
+        }
+    }
+}
+
 pub fn get_index_of_interface(addr: IpAddr) -> ZResult<u32> {
     #[cfg(unix)]
     {
@@ -446,3 +533,50 @@ pub fn set_bind_to_device_udp_socket(socket: &UdpSocket, iface: &str) -> ZResult
     tracing::warn!("Binding the socket {socket:?} to the interface {iface} is not supported on macOS and Windows");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(patterns: &[&str]) -> Vec<String> {
+        patterns.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn interface_pattern() {
+        assert!(matches_interface_pattern("eth0", "eth0"));
+        assert!(!matches_interface_pattern("eth0", "eth01"));
+        assert!(matches_interface_pattern("eth*", "eth0"));
+        assert!(matches_interface_pattern("eth*", "eth"));
+        assert!(!matches_interface_pattern("eth*", "veth0"));
+        assert!(matches_interface_pattern("*eth*", "veth0"));
+        assert!(matches_interface_pattern("en*s0", "enp3s0"));
+        assert!(!matches_interface_pattern("en*s0", "enp3s01"));
+        assert!(matches_interface_pattern("*", "lo"));
+    }
+
+    #[test]
+    fn interface_selected() {
+        // No pattern selects all the interfaces
+        assert!(is_interface_selected("eth0", &[]));
+
+        assert!(is_interface_selected("eth0", &patterns(&["eth0", "wlan0"])));
+        assert!(!is_interface_selected("lo", &patterns(&["eth0", "wlan0"])));
+        assert!(is_interface_selected("eth1", &patterns(&[" eth* "])));
+
+        // Only excluding patterns select all the other interfaces
+        assert!(!is_interface_selected("docker0", &patterns(&["!docker*"])));
+        assert!(is_interface_selected("eth0", &patterns(&["!docker*"])));
+
+        // The excluding patterns take precedence over the including ones
+        assert!(!is_interface_selected(
+            "eth1",
+            &patterns(&["eth*", "!eth1"])
+        ));
+        assert!(is_interface_selected("eth0", &patterns(&["eth*", "!eth1"])));
+        assert!(!is_interface_selected(
+            "wlan0",
+            &patterns(&["eth*", "!eth1"])
+        ));
+    }
+}
@@ -14,6 +14,7 @@
 
 use humantime::{format_rfc3339, parse_rfc3339_weak};
 use std::{
+    cmp::Ordering,
     convert::{TryFrom, TryInto},
     fmt::Display,
     ops::Add,
@@ -40,6 +41,7 @@ const W_TO_SECS: f64 = D_TO_SECS * 7.0;
 /// - the "instant" syntax, which must be a UTC [RFC3339](https://datatracker.ietf.org/doc/html/rfc3339) formatted timestamp.
 /// - the "offset" syntax, which is written `now(<sign: '-'?><offset: Duration?>)`, and allows to specify a target instant as
 ///   an offset applied to an instant of evaluation. These offset are resolved at the evaluation site.
+///   The offset may also be written after the parentheses: `now()-5m` is equivalent to `now(-5m)`.
 ///
 /// In range syntax, omiting `<start>` and/or `<end>` implies that the range is unbounded in that direction.
 ///
@@ -80,6 +82,29 @@ impl TimeRange<TimeExpr> {
             _ => true,
         }
     }
+
+    /// Returns `true` if `self` and `other` have at least one instant in common.
+    ///
+    /// This method performs resolution with [`SystemTime::now`] if the bounds contain an "offset" time expression.
+    pub fn intersects(&self, other: &Self) -> bool {
+        let now = SystemTime::now();
+        self.resolve_at(now).intersects(&other.resolve_at(now))
+    }
+
+    /// Shifts both bounds of the range by `duration` seconds, returning `None` if a bound would be
+    /// outside the bounds of the underlying data structure (see [`TimeExpr::checked_add`]).
+    pub fn checked_add(&self, duration: f64) -> Option<Self> {
+        Some(TimeRange(
+            self.0.checked_map(|t| t.checked_add(duration))?,
+            self.1.checked_map(|t| t.checked_add(duration))?,
+        ))
+    }
+
+    /// Shifts both bounds of the range by `-duration` seconds, returning `None` if a bound would be
+    /// outside the bounds of the underlying data structure (see [`TimeExpr::checked_sub`]).
+    pub fn checked_sub(&self, duration: f64) -> Option<Self> {
+        self.checked_add(-duration)
+    }
 }
 impl TimeRange<SystemTime> {
     /// Returns `true` if the provided `instant` belongs to `self`.
@@ -95,6 +120,29 @@ impl TimeRange<SystemTime> {
             _ => true,
         }
     }
+
+    /// Returns `true` if no instant belongs to `self`.
+    pub fn is_empty(&self) -> bool {
+        match (&self.0, &self.1) {
+            (TimeBound::Inclusive(start), TimeBound::Inclusive(end)) => start > end,
+            (
+                TimeBound::Inclusive(start) | TimeBound::Exclusive(start),
+                TimeBound::Inclusive(end) | TimeBound::Exclusive(end),
+            ) => start >= end,
+            _ => false,
+        }
+    }
+
+    /// Returns the range of the instants belonging to both `self` and `other`, if any.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let range = TimeRange(self.0.max_start(other.0), self.1.min_end(other.1));
+        (!range.is_empty()).then_some(range)
+    }
+
+    /// Returns `true` if `self` and `other` have at least one instant in common.
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
 }
 impl From<TimeRange<SystemTime>> for TimeRange<TimeExpr> {
     fn from(value: TimeRange<SystemTime>) -> Self {
@@ -214,6 +262,48 @@ impl TryFrom<TimeBound<TimeExpr>> for TimeBound<SystemTime> {
         })
     }
 }
+impl<T: Copy> TimeBound<T> {
+    fn checked_map<U, F: FnOnce(T) -> Option<U>>(self, f: F) -> Option<TimeBound<U>> {
+        Some(match self {
+            TimeBound::Inclusive(t) => TimeBound::Inclusive(f(t)?),
+            TimeBound::Exclusive(t) => TimeBound::Exclusive(f(t)?),
+            TimeBound::Unbounded => TimeBound::Unbounded,
+        })
+    }
+}
+impl TimeBound<SystemTime> {
+    // The most restrictive of two start bounds
+    fn max_start(self, other: Self) -> Self {
+        match (self, other) {
+            (TimeBound::Unbounded, b) | (b, TimeBound::Unbounded) => b,
+            (
+                TimeBound::Inclusive(a) | TimeBound::Exclusive(a),
+                TimeBound::Inclusive(b) | TimeBound::Exclusive(b),
+            ) => match a.cmp(&b) {
+                Ordering::Greater => self,
+                Ordering::Less => other,
+                Ordering::Equal if matches!(self, TimeBound::Exclusive(_)) => self,
+                Ordering::Equal => other,
+            },
+        }
+    }
+
+    // The most restrictive of two end bounds
+    fn min_end(self, other: Self) -> Self {
+        match (self, other) {
+            (TimeBound::Unbounded, b) | (b, TimeBound::Unbounded) => b,
+            (
+                TimeBound::Inclusive(a) | TimeBound::Exclusive(a),
+                TimeBound::Inclusive(b) | TimeBound::Exclusive(b),
+            ) => match a.cmp(&b) {
+                Ordering::Less => self,
+                Ordering::Greater => other,
+                Ordering::Equal if matches!(self, TimeBound::Exclusive(_)) => self,
+                Ordering::Equal => other,
+            },
+        }
+    }
+}
 impl TimeBound<TimeExpr> {
     /// Resolves `self` into a [`TimeBound<SystemTime>`], using `now` as a reference for offset expressions.
     /// If `self` is time boundary that cannot be represented as `SystemTime` (which means it’s not inside
@@ -315,16 +405,24 @@ impl Display for TimeExpr {
 impl FromStr for TimeExpr {
     type Err = ZError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.starts_with("now(") && s.ends_with(')') {
-            let s = &s[4..s.len() - 1];
-            if s.is_empty() {
-                Ok(TimeExpr::Now { offset_secs: 0.0 })
-            } else {
-                match s.chars().next().unwrap() {
-                    '-' => parse_duration(&s[1..]).map(|f| TimeExpr::Now { offset_secs: -f }),
-                    _ => parse_duration(s).map(|f| TimeExpr::Now { offset_secs: f }),
-                }
-            }
+        if let Some((offset, shift)) = s.strip_prefix("now(").and_then(|s| s.split_once(')')) {
+            let offset = match offset.strip_prefix('-') {
+                _ if offset.is_empty() => Ok(0.0),
+                Some(offset) => parse_duration(offset).map(|f| -f),
+                None => parse_duration(offset),
+            };
+            // Support the `now()-5m` and `now()+5m` syntaxes as well
+            let shift = match (shift.strip_prefix('-'), shift.strip_prefix('+')) {
+                _ if shift.is_empty() => Ok(0.0),
+                (Some(shift), _) => parse_duration(shift).map(|f| -f),
+                (_, Some(shift)) => parse_duration(shift),
+                _ => Err(zerror!(r#"unexpected "{}" after "now()""#, shift)),
+            };
+            offset.and_then(|offset| {
+                shift.map(|shift| TimeExpr::Now {
+                    offset_secs: offset + shift,
+                })
+            })
         } else {
             parse_rfc3339_weak(s)
                 .map_err(|e| zerror!(e))
@@ -631,6 +729,60 @@ mod tests {
         assert_eq!(t.checked_resolve_at(now), Some(SystemTime::UNIX_EPOCH));
     }
 
+    #[test]
+    fn test_time_range_now_shift() {
+        assert_eq!(
+            "[now()-5m..now()]".parse::<TimeRange>().unwrap(),
+            "[now(-5m)..now()]".parse::<TimeRange>().unwrap()
+        );
+        assert_eq!(
+            "now(-1m)+30s".parse::<TimeExpr>().unwrap(),
+            TimeExpr::Now { offset_secs: -30.0 }
+        );
+        assert!("now()*5m".parse::<TimeExpr>().is_err());
+        assert!("now()-".parse::<TimeExpr>().is_err());
+
+        let range: TimeRange = "[now(-5m)..now()[".parse().unwrap();
+        assert_eq!(
+            range.checked_sub(60.0).unwrap(),
+            "[now(-6m)..now(-1m)[".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_time_range_intersection() {
+        let t = |secs: u64| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        let range = |s: TimeBound<SystemTime>, e: TimeBound<SystemTime>| TimeRange(s, e);
+
+        let a = range(TimeBound::Inclusive(t(10)), TimeBound::Inclusive(t(20)));
+        let b = range(TimeBound::Exclusive(t(15)), TimeBound::Unbounded);
+        assert_eq!(
+            a.intersection(&b),
+            Some(range(
+                TimeBound::Exclusive(t(15)),
+                TimeBound::Inclusive(t(20))
+            ))
+        );
+
+        let c = range(TimeBound::Exclusive(t(20)), TimeBound::Inclusive(t(30)));
+        assert!(!a.intersects(&c));
+        let d = range(TimeBound::Inclusive(t(20)), TimeBound::Inclusive(t(30)));
+        assert_eq!(
+            a.intersection(&d),
+            Some(range(
+                TimeBound::Inclusive(t(20)),
+                TimeBound::Inclusive(t(20))
+            ))
+        );
+
+        let unbounded = range(TimeBound::Unbounded, TimeBound::Unbounded);
+        assert_eq!(a.intersection(&unbounded), Some(a));
+        assert!("[now(-1m)..now()]"
+            .parse::<TimeRange>()
+            .unwrap()
+            .intersects(&"[now(-2m)..now(-30s)]".parse().unwrap()));
+    }
+
     #[test]
     fn test_parse_duration() {
         assert_eq!(parse_duration("0").unwrap(), 0.0);
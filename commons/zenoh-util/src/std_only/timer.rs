@@ -24,9 +24,14 @@ use std::time::{Duration, Instant};
 use zenoh_core::zconfigurable;
 
 zconfigurable! {
+    #[mutable]
     static ref TIMER_EVENTS_CHANNEL_SIZE: usize = 1;
 }
 
+pub(crate) fn register_configurables() {
+    zenoh_core::configurable::register(&TIMER_EVENTS_CHANNEL_SIZE);
+}
+
 #[async_trait]
 pub trait Timed {
     async fn run(&mut self);
@@ -188,7 +193,8 @@ pub struct Timer {
 impl Timer {
     pub fn new(spawn_blocking: bool) -> Timer {
         // Create the channels
-        let (ev_sender, ev_receiver) = bounded::<(bool, TimedEvent)>(*TIMER_EVENTS_CHANNEL_SIZE);
+        let (ev_sender, ev_receiver) =
+            bounded::<(bool, TimedEvent)>(TIMER_EVENTS_CHANNEL_SIZE.get());
         let (sl_sender, sl_receiver) = bounded::<()>(1);
 
         // Create the timer object
@@ -221,7 +227,7 @@ impl Timer {
         if self.sl_sender.is_none() {
             // Create the channels
             let (ev_sender, ev_receiver) =
-                bounded::<(bool, TimedEvent)>(*TIMER_EVENTS_CHANNEL_SIZE);
+                bounded::<(bool, TimedEvent)>(TIMER_EVENTS_CHANNEL_SIZE.get());
             let (sl_sender, sl_receiver) = bounded::<()>(1);
 
             // Store the channels handlers
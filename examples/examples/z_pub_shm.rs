@@ -24,7 +24,7 @@ const K: u32 = 3;
 #[tokio::main]
 async fn main() -> Result<(), zenoh::Error> {
     // Initiate logging
-    zenoh_util::try_init_log_from_env();
+    zenoh::try_init_log_from_env();
 
     let (mut config, path, value) = parse_args();
 
@@ -21,12 +21,13 @@ extern crate alloc;
 
 mod listener;
 mod multicast;
+pub mod proxy;
 pub mod tls;
 mod unicast;
 
 use alloc::{borrow::ToOwned, boxed::Box, string::String, vec, vec::Vec};
 use async_trait::async_trait;
-use core::{cmp::PartialEq, fmt, hash::Hash};
+use core::{cmp::PartialEq, fmt, hash::Hash, time::Duration};
 pub use listener::*;
 pub use multicast::*;
 use serde::Serialize;
@@ -39,6 +40,10 @@ use zenoh_result::ZResult;
 /*************************************/
 
 pub const BIND_INTERFACE: &str = "iface";
+/// The local `<ip>:<port>` an outgoing link is bound to, e.g. `tcp/192.168.1.1:7447#bind=10.0.0.1:0`.
+pub const BIND_SOCKET: &str = "bind";
+/// The maximum bandwidth of the links established with an endpoint, e.g. `tcp/192.168.1.1:7447#rate_limit=10mbps`.
+pub const RATE_LIMIT: &str = "rate_limit";
 
 #[derive(Clone, Debug, Serialize, Hash, PartialEq, Eq)]
 pub struct Link {
@@ -49,6 +54,29 @@ pub struct Link {
     pub is_reliable: bool,
     pub is_streamed: bool,
     pub interfaces: Vec<String>,
+    metrics: Option<LinkMetrics>,
+}
+
+impl Link {
+    /// The metrics of the link, when measured by its transport.
+    pub fn metrics(&self) -> Option<&LinkMetrics> {
+        self.metrics.as_ref()
+    }
+
+    pub fn set_metrics(&mut self, metrics: Option<LinkMetrics>) {
+        self.metrics = metrics;
+    }
+}
+
+/// The metrics of a link, estimated from the echo of the probes sent by its transport.
+#[derive(Clone, Copy, Debug, Serialize, Hash, PartialEq, Eq)]
+pub struct LinkMetrics {
+    /// The smoothed round-trip time.
+    pub srtt: Duration,
+    /// The mean deviation of the round-trip time.
+    pub jitter: Duration,
+    /// The smoothed ratio of probes not echoed, in per mille.
+    pub loss: u16,
 }
 
 #[async_trait]
@@ -77,6 +105,7 @@ impl From<&LinkUnicast> for Link {
             is_reliable: link.is_reliable(),
             is_streamed: link.is_streamed(),
             interfaces: link.get_interface_names(),
+            metrics: None,
         }
     }
 }
@@ -97,6 +126,7 @@ impl From<&LinkMulticast> for Link {
             is_reliable: link.is_reliable(),
             is_streamed: false,
             interfaces: vec![],
+            metrics: None,
         }
     }
 }
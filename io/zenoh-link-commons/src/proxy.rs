@@ -0,0 +1,499 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use alloc::{format, string::String, string::ToString, vec, vec::Vec};
+use base64::{engine::general_purpose, Engine};
+use core::fmt;
+use core::str::FromStr;
+use secrecy::ExposeSecret;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Mutex, OnceLock};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpSocket, TcpStream};
+use zenoh_config::SecretValue;
+use zenoh_core::zlock;
+use zenoh_protocol::core::endpoint::Config;
+use zenoh_result::{bail, zerror, ZResult};
+
+/// The proxy the outgoing links of an endpoint are established through, e.g.
+/// `tcp/10.0.0.1:7447#proxy=socks5://10.0.0.2:1080` or `tls/server:7447#proxy=http://proxy.corp:3128`.
+pub const PROXY: &str = "proxy";
+/// The user name to authenticate with the proxy.
+pub const PROXY_USER: &str = "proxy_user";
+/// The password to authenticate with the proxy. The password of `transport.link.proxy` is
+/// not set on the endpoints, whose configuration is displayed and logged.
+pub const PROXY_PASSWORD: &str = "proxy_password";
+
+// The passwords of `transport.link.proxy`, by proxy URL and user name, kept out of the endpoints.
+fn proxy_passwords() -> &'static Mutex<HashMap<(String, String), SecretValue>> {
+    static PROXY_PASSWORDS: OnceLock<Mutex<HashMap<(String, String), SecretValue>>> =
+        OnceLock::new();
+    PROXY_PASSWORDS.get_or_init(Default::default)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProxyProtocol {
+    /// SOCKS5 (RFC 1928), with an optional user name and password (RFC 1929).
+    Socks5,
+    /// HTTP CONNECT tunnel, with an optional basic authentication.
+    Http,
+}
+
+/// A SOCKS5 or HTTP CONNECT proxy, see [`PROXY`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct ProxyConfig {
+    pub protocol: ProxyProtocol,
+    /// The `<host>:<port>` of the proxy.
+    pub address: String,
+    pub credentials: Option<(String, String)>,
+}
+
+impl FromStr for ProxyConfig {
+    type Err = zenoh_result::Error;
+
+    /// Parses `<protocol>://[<user>:<password>@]<host>:<port>`, with `socks5` or `http` protocols.
+    fn from_str(s: &str) -> ZResult<Self> {
+        let (protocol, rest) = s
+            .split_once("://")
+            .ok_or_else(|| zerror!("Invalid proxy {}: missing protocol", s))?;
+        let protocol = match protocol {
+            "socks5" | "socks5h" => ProxyProtocol::Socks5,
+            "http" => ProxyProtocol::Http,
+            p => bail!("Invalid proxy {}: unsupported protocol {}", s, p),
+        };
+        let (credentials, address) = match rest.rsplit_once('@') {
+            Some((credentials, address)) => {
+                let (user, password) = credentials
+                    .split_once(':')
+                    .ok_or_else(|| zerror!("Invalid proxy credentials: missing password"))?;
+                (Some((user.to_string(), password.to_string())), address)
+            }
+            None => (None, rest),
+        };
+        let address = address.trim_end_matches('/');
+        if address.rsplit_once(':').is_none() {
+            bail!("Invalid proxy {}: missing port", address);
+        }
+        Ok(ProxyConfig {
+            protocol,
+            address: address.to_string(),
+            credentials,
+        })
+    }
+}
+
+impl fmt::Display for ProxyConfig {
+    // The credentials are not displayed
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.protocol {
+            ProxyProtocol::Socks5 => write!(f, "socks5://{}", self.address),
+            ProxyProtocol::Http => write!(f, "http://{}", self.address),
+        }
+    }
+}
+
+impl fmt::Debug for ProxyConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl ProxyConfig {
+    /// The proxy configured on an endpoint, if any.
+    pub fn new(config: &Config<'_>) -> ZResult<Option<Self>> {
+        let Some(proxy) = config.get(PROXY) else {
+            return Ok(None);
+        };
+        let mut proxy_config = ProxyConfig::from_str(proxy)?;
+        let user = config.get(PROXY_USER);
+        let password = match config.get(PROXY_PASSWORD) {
+            Some(_) if user.is_none() => {
+                bail!("Invalid proxy: {} without {}", PROXY_PASSWORD, PROXY_USER)
+            }
+            Some(password) => Some(password.to_string()),
+            None => zlock!(proxy_passwords())
+                .get(&(proxy.to_string(), user.unwrap_or_default().to_string()))
+                .map(|p| p.expose_secret().to_string()),
+        };
+        match (user, password) {
+            (Some(user), password) => {
+                proxy_config.credentials = Some((user.to_string(), password.unwrap_or_default()))
+            }
+            (None, Some(password)) => proxy_config.credentials = Some((String::new(), password)),
+            (None, None) => {}
+        }
+        Ok(Some(proxy_config))
+    }
+
+    /// The [`PROXY`] options of the endpoints, from the `transport.link.proxy` configuration.
+    /// Its password is not part of them: it is passed to [`ProxyConfig::new`] out of band.
+    pub fn properties(config: &zenoh_config::Config) -> Vec<(&'static str, String)> {
+        let c = config.transport().link().proxy();
+        let Some(url) = c.url() else {
+            return vec![];
+        };
+        let mut properties = vec![(PROXY, url.clone())];
+        if let Some(username) = c.username() {
+            properties.push((PROXY_USER, username.clone()));
+        }
+        if let Some(password) = c.password() {
+            let key = (url.clone(), c.username().clone().unwrap_or_default());
+            zlock!(proxy_passwords()).insert(key, password.clone());
+        }
+        properties
+    }
+
+    /// Connects to `<host>:<port>` through the proxy, which resolves the host name.
+    pub async fn connect(&self, destination: &str) -> ZResult<TcpStream> {
+        self.connect_from(destination, None).await
+    }
+
+    /// Connects to `<host>:<port>` through the proxy, binding the connection to the proxy to
+    /// `src_addr` if any.
+    pub async fn connect_from(
+        &self,
+        destination: &str,
+        src_addr: Option<&SocketAddr>,
+    ) -> ZResult<TcpStream> {
+        let (host, port) = destination
+            .rsplit_once(':')
+            .ok_or_else(|| zerror!("Invalid destination {}: missing port", destination))?;
+        let port = u16::from_str(port)
+            .map_err(|e| zerror!("Invalid destination {}: {}", destination, e))?;
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+
+        let mut stream = match src_addr {
+            Some(src_addr) => self.connect_bound(src_addr).await,
+            None => TcpStream::connect(self.address.as_str())
+                .await
+                .map_err(|e| zerror!("Can not connect to proxy {}: {}", self, e).into()),
+        }?;
+        match self.protocol {
+            ProxyProtocol::Socks5 => self.socks5_connect(&mut stream, host, port).await,
+            ProxyProtocol::Http => self.http_connect(&mut stream, host, port).await,
+        }
+        .map_err(|e| zerror!("Proxy {} can not connect to {}: {}", self, destination, e))?;
+        Ok(stream)
+    }
+
+    async fn connect_bound(&self, src_addr: &SocketAddr) -> ZResult<TcpStream> {
+        let dst_addr = tokio::net::lookup_host(self.address.as_str())
+            .await
+            .map_err(|e| zerror!("Can not resolve proxy {}: {}", self, e))?
+            .find(|a| a.is_ipv4() == src_addr.is_ipv4())
+            .ok_or_else(|| {
+                zerror!(
+                    "Can not connect to proxy {}: no address of the family of {}",
+                    self,
+                    src_addr
+                )
+            })?;
+        let socket = match dst_addr {
+            SocketAddr::V4(_) => TcpSocket::new_v4(),
+            SocketAddr::V6(_) => TcpSocket::new_v6(),
+        }?;
+        // Allow to reconnect from the same port while the previous connection is in TIME_WAIT
+        socket.set_reuseaddr(true)?;
+        socket
+            .bind(*src_addr)
+            .map_err(|e| zerror!("Can not bind to {}: {}", src_addr, e))?;
+        let stream = socket
+            .connect(dst_addr)
+            .await
+            .map_err(|e| zerror!("Can not connect to proxy {}: {}", self, e))?;
+        Ok(stream)
+    }
+
+    async fn socks5_connect(&self, stream: &mut TcpStream, host: &str, port: u16) -> ZResult<()> {
+        const VERSION: u8 = 0x05;
+        const NO_AUTH: u8 = 0x00;
+        const USER_PASSWORD: u8 = 0x02;
+        const CONNECT: u8 = 0x01;
+
+        // Method selection
+        let method = match self.credentials {
+            Some(_) => USER_PASSWORD,
+            None => NO_AUTH,
+        };
+        stream.write_all(&[VERSION, 1, method]).await?;
+        let mut reply = [0u8; 2];
+        stream.read_exact(&mut reply).await?;
+        if reply[0] != VERSION || reply[1] != method {
+            bail!("SOCKS5 authentication method refused");
+        }
+
+        // User name and password authentication (RFC 1929)
+        if let Some((user, password)) = &self.credentials {
+            if user.len() > u8::MAX as usize || password.len() > u8::MAX as usize {
+                bail!("SOCKS5 credentials too long");
+            }
+            let mut auth = Vec::with_capacity(3 + user.len() + password.len());
+            auth.push(0x01);
+            auth.push(user.len() as u8);
+            auth.extend_from_slice(user.as_bytes());
+            auth.push(password.len() as u8);
+            auth.extend_from_slice(password.as_bytes());
+            stream.write_all(&auth).await?;
+            stream.read_exact(&mut reply).await?;
+            if reply[1] != 0x00 {
+                bail!("SOCKS5 authentication failed");
+            }
+        }
+
+        // Connect request
+        let mut request = vec![VERSION, CONNECT, 0x00];
+        match IpAddr::from_str(host) {
+            Ok(IpAddr::V4(ip)) => {
+                request.push(0x01);
+                request.extend_from_slice(&ip.octets());
+            }
+            Ok(IpAddr::V6(ip)) => {
+                request.push(0x04);
+                request.extend_from_slice(&ip.octets());
+            }
+            Err(_) => {
+                if host.len() > u8::MAX as usize {
+                    bail!("SOCKS5 host name too long: {}", host);
+                }
+                request.push(0x03);
+                request.push(host.len() as u8);
+                request.extend_from_slice(host.as_bytes());
+            }
+        }
+        request.extend_from_slice(&port.to_be_bytes());
+        stream.write_all(&request).await?;
+
+        let mut reply = [0u8; 4];
+        stream.read_exact(&mut reply).await?;
+        if reply[1] != 0x00 {
+            bail!("SOCKS5 connect request failed with code {}", reply[1]);
+        }
+        // Skip the bound address and port
+        let len = match reply[3] {
+            0x01 => 4,
+            0x04 => 16,
+            0x03 => stream.read_u8().await? as usize,
+            t => bail!("SOCKS5 invalid address type {}", t),
+        };
+        let mut bound = vec![0u8; len + 2];
+        stream.read_exact(&mut bound).await?;
+        Ok(())
+    }
+
+    async fn http_connect(&self, stream: &mut TcpStream, host: &str, port: u16) -> ZResult<()> {
+        let authority = match IpAddr::from_str(host) {
+            Ok(ip) => SocketAddr::new(ip, port).to_string(),
+            Err(_) => format!("{host}:{port}"),
+        };
+        let mut request = format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n");
+        if let Some((user, password)) = &self.credentials {
+            let token = general_purpose::STANDARD.encode(format!("{user}:{password}"));
+            request.push_str(&format!("Proxy-Authorization: Basic {token}\r\n"));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).await?;
+
+        // Read the response header without consuming the tunneled bytes
+        let mut reader = BufReader::with_capacity(1, stream);
+        let mut status = String::new();
+        reader.read_line(&mut status).await?;
+        let code = status.split_whitespace().nth(1).unwrap_or_default();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await? == 0 || line == "\r\n" || line == "\n" {
+                break;
+            }
+        }
+        if code != "200" {
+            bail!("HTTP CONNECT failed: {}", status.trim_end());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proxy_config() {
+        let proxy = ProxyConfig::from_str("socks5://user:pa:ss@10.0.0.1:1080").unwrap();
+        assert_eq!(proxy.protocol, ProxyProtocol::Socks5);
+        assert_eq!(proxy.address, "10.0.0.1:1080");
+        assert_eq!(
+            proxy.credentials,
+            Some(("user".to_string(), "pa:ss".to_string()))
+        );
+        assert_eq!(proxy.to_string(), "socks5://10.0.0.1:1080");
+
+        let proxy = ProxyConfig::from_str("http://proxy.corp:3128/").unwrap();
+        assert_eq!(proxy.protocol, ProxyProtocol::Http);
+        assert_eq!(proxy.address, "proxy.corp:3128");
+        assert_eq!(proxy.credentials, None);
+
+        assert!(ProxyConfig::from_str("proxy.corp:3128").is_err());
+        assert!(ProxyConfig::from_str("ftp://proxy.corp:3128").is_err());
+        assert!(ProxyConfig::from_str("http://proxy.corp").is_err());
+    }
+
+    fn endpoint_config(endpoint: &str) -> ZResult<Option<ProxyConfig>> {
+        let endpoint = zenoh_protocol::core::EndPoint::from_str(endpoint).unwrap();
+        ProxyConfig::new(&endpoint.config())
+    }
+
+    #[test]
+    fn proxy_password() {
+        use zenoh_config::ValidatedMap;
+
+        let mut config = zenoh_config::Config::default();
+        config
+            .insert_json5(
+                "transport/link/proxy",
+                r#"{ url: "socks5://10.0.0.2:1081", username: "user", password: "secret" }"#,
+            )
+            .unwrap();
+
+        // The password is not set on the endpoints but passed out of band
+        let properties = ProxyConfig::properties(&config);
+        assert!(properties
+            .iter()
+            .all(|(k, v)| *k != PROXY_PASSWORD && v != "secret"));
+        let mut endpoint = "tcp/10.0.0.1:7447#".to_string();
+        zenoh_protocol::core::Parameters::extend(
+            properties.iter().map(|(k, v)| (*k, v.as_str())),
+            &mut endpoint,
+        );
+        assert!(!endpoint.contains("secret"));
+        let proxy = endpoint_config(&endpoint).unwrap().unwrap();
+        assert_eq!(
+            proxy.credentials,
+            Some(("user".to_string(), "secret".to_string()))
+        );
+
+        // The password of an endpoint takes precedence
+        let proxy = endpoint_config(
+            "tcp/10.0.0.1:7447#proxy=socks5://10.0.0.2:1081;proxy_user=user;proxy_password=other",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            proxy.credentials,
+            Some(("user".to_string(), "other".to_string()))
+        );
+        assert!(
+            endpoint_config("tcp/10.0.0.1:7447#proxy=socks5://10.0.0.2:1082;proxy_password=p")
+                .is_err()
+        );
+        assert!(endpoint_config("tcp/10.0.0.1:7447").unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn proxy_socks5_handshake() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut methods = [0u8; 3];
+            stream.read_exact(&mut methods).await.unwrap();
+            assert_eq!(methods, [0x05, 1, 0x02]);
+            stream.write_all(&[0x05, 0x02]).await.unwrap();
+
+            let mut auth = vec![0u8; 2];
+            stream.read_exact(&mut auth).await.unwrap();
+            let mut user = vec![0u8; auth[1] as usize];
+            stream.read_exact(&mut user).await.unwrap();
+            let mut password = vec![0u8; stream.read_u8().await.unwrap() as usize];
+            stream.read_exact(&mut password).await.unwrap();
+            stream.write_all(&[0x01, 0x00]).await.unwrap();
+
+            let mut request = [0u8; 5];
+            stream.read_exact(&mut request).await.unwrap();
+            assert_eq!(request[..4], [0x05, 0x01, 0x00, 0x03]);
+            let mut host = vec![0u8; request[4] as usize];
+            stream.read_exact(&mut host).await.unwrap();
+            let port = stream.read_u16().await.unwrap();
+            stream
+                .write_all(&[0x05, 0x00, 0x00, 0x01, 127, 0, 0, 1, 0x1d, 0x17])
+                .await
+                .unwrap();
+            stream.write_all(b"zenoh").await.unwrap();
+            (
+                String::from_utf8(user).unwrap(),
+                String::from_utf8(password).unwrap(),
+                String::from_utf8(host).unwrap(),
+                port,
+            )
+        });
+
+        let proxy = ProxyConfig::from_str(&format!("socks5://user:secret@{address}")).unwrap();
+        let mut stream = proxy.connect("router.corp:7447").await.unwrap();
+        // The bytes tunneled after the handshake are left to the link
+        let mut data = [0u8; 5];
+        stream.read_exact(&mut data).await.unwrap();
+        assert_eq!(&data, b"zenoh");
+        assert_eq!(
+            server.await.unwrap(),
+            (
+                "user".to_string(),
+                "secret".to_string(),
+                "router.corp".to_string(),
+                7447
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn proxy_http_handshake() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            while !request.ends_with("\r\n\r\n") {
+                reader.read_line(&mut request).await.unwrap();
+            }
+            let mut stream = reader.into_inner();
+            stream
+                .write_all(b"HTTP/1.1 200 Connection established\r\n\r\nzenoh")
+                .await
+                .unwrap();
+            request
+        });
+
+        let proxy = ProxyConfig::from_str(&format!("http://user:secret@{address}")).unwrap();
+        let mut stream = proxy.connect("10.0.0.1:7447").await.unwrap();
+        let mut data = [0u8; 5];
+        stream.read_exact(&mut data).await.unwrap();
+        assert_eq!(&data, b"zenoh");
+        let request = server.await.unwrap();
+        assert!(request.starts_with("CONNECT 10.0.0.1:7447 HTTP/1.1\r\n"));
+        let token = general_purpose::STANDARD.encode("user:secret");
+        assert!(request.contains(&format!("Proxy-Authorization: Basic {token}\r\n")));
+
+        // A refused tunnel fails the connection
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).await;
+            let _ = stream
+                .write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n")
+                .await;
+        });
+        let proxy = ProxyConfig::from_str(&format!("http://{address}")).unwrap();
+        assert!(proxy.connect("10.0.0.1:7447").await.is_err());
+    }
+}
@@ -19,8 +19,11 @@ use core::{
     ops::Deref,
 };
 use std::net::SocketAddr;
-use zenoh_protocol::core::{EndPoint, Locator};
-use zenoh_result::ZResult;
+use zenoh_buffers::ZSlice;
+use zenoh_protocol::core::{endpoint::Config, EndPoint, Locator, Priority};
+use zenoh_result::{bail, zerror, ZResult};
+
+use crate::{BIND_INTERFACE, BIND_SOCKET};
 
 pub type LinkManagerUnicast = Arc<dyn LinkManagerUnicastTrait>;
 #[async_trait]
@@ -47,8 +50,29 @@ pub trait LinkUnicastTrait: Send + Sync {
     fn is_reliable(&self) -> bool;
     fn is_streamed(&self) -> bool;
     fn get_interface_names(&self) -> Vec<String>;
+    /// Whether the messages of the given priority may be sent on the link (default: all of them).
+    fn supports_priority(&self, _priority: Priority) -> bool {
+        true
+    }
     async fn write(&self, buffer: &[u8]) -> ZResult<usize>;
     async fn write_all(&self, buffer: &[u8]) -> ZResult<()>;
+    /// Write the given slices as a single buffer, e.g. a batch made of several [`ZSlice`]s.
+    ///
+    /// The default implementation copies the slices in a contiguous buffer before writing it
+    /// with [`write_all`](LinkUnicastTrait::write_all). The links able to write several buffers
+    /// at once (e.g. with vectored I/O) should override it to save that copy.
+    async fn write_zslices(&self, slices: &[ZSlice]) -> ZResult<()> {
+        match slices {
+            [slice] => self.write_all(slice).await,
+            _ => {
+                let mut buffer = Vec::with_capacity(slices.iter().map(|s| s.len()).sum());
+                for slice in slices {
+                    buffer.extend_from_slice(slice);
+                }
+                self.write_all(&buffer).await
+            }
+        }
+    }
     async fn read(&self, buffer: &mut [u8]) -> ZResult<usize>;
     async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()>;
     async fn close(&self) -> ZResult<()>;
@@ -102,6 +126,38 @@ impl From<Arc<dyn LinkUnicastTrait>> for LinkUnicast {
     }
 }
 
+/// The interfaces selected on an endpoint with [`BIND_INTERFACE`], e.g. `tcp/0.0.0.0:7447#iface=eth0|eth1`
+/// for a listener on several interfaces of a multihomed host.
+pub fn get_bind_interfaces<'a>(config: &'a Config<'a>) -> Vec<&'a str> {
+    config
+        .values(BIND_INTERFACE)
+        .filter(|iface| !iface.is_empty())
+        .collect()
+}
+
+/// The interface a socket is bound to, if any: only one interface can be selected.
+pub fn get_bind_interface<'a>(config: &'a Config<'a>) -> ZResult<Option<&'a str>> {
+    match get_bind_interfaces(config).as_slice() {
+        [] => Ok(None),
+        [iface] => Ok(Some(*iface)),
+        ifaces => bail!(
+            "Can not bind a socket to several interfaces: {}",
+            ifaces.join("|")
+        ),
+    }
+}
+
+/// The local address an outgoing socket is bound to, if any.
+pub fn get_bind_socket(config: &Config<'_>) -> ZResult<Option<SocketAddr>> {
+    config
+        .get(BIND_SOCKET)
+        .map(|s| {
+            s.parse::<SocketAddr>()
+                .map_err(|e| zerror!("Invalid {} address {}: {}", BIND_SOCKET, s, e).into())
+        })
+        .transpose()
+}
+
 pub fn get_ip_interface_names(addr: &SocketAddr) -> Vec<String> {
     match zenoh_util::net::get_interface_names_by_addr(addr.ip()) {
         Ok(interfaces) => {
@@ -114,3 +170,26 @@ pub fn get_ip_interface_names(addr: &SocketAddr) -> Vec<String> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bind_interfaces() {
+        let endpoint = EndPoint::try_from("tcp/0.0.0.0:7447#iface=eth0|eth1".to_string()).unwrap();
+        let config = endpoint.config();
+        assert_eq!(get_bind_interfaces(&config), vec!["eth0", "eth1"]);
+        assert!(get_bind_interface(&config).is_err());
+
+        let endpoint = EndPoint::try_from("tcp/0.0.0.0:7447#iface=eth0".to_string()).unwrap();
+        let config = endpoint.config();
+        assert_eq!(get_bind_interfaces(&config), vec!["eth0"]);
+        assert_eq!(get_bind_interface(&config).unwrap(), Some("eth0"));
+
+        let endpoint = EndPoint::try_from("tcp/0.0.0.0:7447".to_string()).unwrap();
+        let config = endpoint.config();
+        assert!(get_bind_interfaces(&config).is_empty());
+        assert_eq!(get_bind_interface(&config).unwrap(), None);
+    }
+}
@@ -24,7 +24,9 @@ use zenoh_result::{bail, ZResult};
 #[cfg(feature = "transport_tcp")]
 pub use zenoh_link_tcp as tcp;
 #[cfg(feature = "transport_tcp")]
-use zenoh_link_tcp::{LinkManagerUnicastTcp, TcpLocatorInspector, TCP_LOCATOR_PREFIX};
+use zenoh_link_tcp::{
+    LinkManagerUnicastTcp, TcpConfigurator, TcpLocatorInspector, TCP_LOCATOR_PREFIX,
+};
 
 #[cfg(feature = "transport_udp")]
 pub use zenoh_link_udp as udp;
@@ -50,7 +52,10 @@ use zenoh_link_quic::{
 #[cfg(feature = "transport_ws")]
 pub use zenoh_link_ws as ws;
 #[cfg(feature = "transport_ws")]
-use zenoh_link_ws::{LinkManagerUnicastWs, WsLocatorInspector, WS_LOCATOR_PREFIX};
+use zenoh_link_ws::{
+    LinkManagerUnicastWs, WsLocatorInspector, WssConfigurator, WssLocatorInspector,
+    WSS_LOCATOR_PREFIX, WS_LOCATOR_PREFIX,
+};
 
 #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
 pub use zenoh_link_unixsock_stream as unixsock_stream;
@@ -60,11 +65,24 @@ use zenoh_link_unixsock_stream::{
     UNIXSOCKSTREAM_LOCATOR_PREFIX,
 };
 
+#[cfg(all(feature = "transport_unixsock-seqpacket", target_os = "linux"))]
+pub use zenoh_link_unixsock_seqpacket as unixsock_seqpacket;
+#[cfg(all(feature = "transport_unixsock-seqpacket", target_os = "linux"))]
+use zenoh_link_unixsock_seqpacket::{
+    LinkManagerUnicastUnixSocketSeqpacket, UnixSockSeqpacketLocatorInspector,
+    UNIXSOCKSEQPACKET_LOCATOR_PREFIX,
+};
+
 #[cfg(feature = "transport_serial")]
 pub use zenoh_link_serial as serial;
 #[cfg(feature = "transport_serial")]
 use zenoh_link_serial::{LinkManagerUnicastSerial, SerialLocatorInspector, SERIAL_LOCATOR_PREFIX};
 
+#[cfg(feature = "transport_ble")]
+pub use zenoh_link_ble as ble;
+#[cfg(feature = "transport_ble")]
+use zenoh_link_ble::{BleLocatorInspector, LinkManagerUnicastBle, BLE_LOCATOR_PREFIX};
+
 #[cfg(feature = "transport_unixpipe")]
 pub use zenoh_link_unixpipe as unixpipe;
 #[cfg(feature = "transport_unixpipe")]
@@ -77,6 +95,14 @@ pub use zenoh_link_vsock as vsock;
 #[cfg(all(feature = "transport_vsock", target_os = "linux"))]
 use zenoh_link_vsock::{LinkManagerUnicastVsock, VsockLocatorInspector, VSOCK_LOCATOR_PREFIX};
 
+#[cfg(feature = "transport_custom")]
+pub use zenoh_link_custom as custom;
+#[cfg(feature = "transport_custom")]
+use zenoh_link_custom::{
+    CustomConfigurator, CustomLocatorInspector, LinkManagerMulticastCustom,
+    LinkManagerUnicastCustom, CUSTOM_LOCATOR_PREFIX,
+};
+
 pub use zenoh_link_commons::*;
 pub use zenoh_protocol::core::{EndPoint, Locator};
 
@@ -91,16 +117,48 @@ pub const PROTOCOLS: &[&str] = &[
     udp::UDP_LOCATOR_PREFIX,
     #[cfg(feature = "transport_ws")]
     ws::WS_LOCATOR_PREFIX,
+    #[cfg(feature = "transport_ws")]
+    ws::WSS_LOCATOR_PREFIX,
     #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
     unixsock_stream::UNIXSOCKSTREAM_LOCATOR_PREFIX,
+    #[cfg(all(feature = "transport_unixsock-seqpacket", target_os = "linux"))]
+    unixsock_seqpacket::UNIXSOCKSEQPACKET_LOCATOR_PREFIX,
     #[cfg(feature = "transport_serial")]
     serial::SERIAL_LOCATOR_PREFIX,
+    #[cfg(feature = "transport_ble")]
+    ble::BLE_LOCATOR_PREFIX,
     #[cfg(feature = "transport_unixpipe")]
     unixpipe::UNIXPIPE_LOCATOR_PREFIX,
     #[cfg(all(feature = "transport_vsock", target_os = "linux"))]
     vsock::VSOCK_LOCATOR_PREFIX,
+    #[cfg(feature = "transport_custom")]
+    custom::CUSTOM_LOCATOR_PREFIX,
 ];
 
+/// Registers the values of the enabled transports that can be modified at runtime.
+pub fn register_configurables() {
+    #[cfg(feature = "transport_quic")]
+    quic::register_configurables();
+    #[cfg(feature = "transport_tcp")]
+    tcp::register_configurables();
+    #[cfg(feature = "transport_tls")]
+    tls::register_configurables();
+    #[cfg(feature = "transport_udp")]
+    udp::register_configurables();
+    #[cfg(feature = "transport_ws")]
+    ws::register_configurables();
+    #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
+    unixsock_stream::register_configurables();
+    #[cfg(all(feature = "transport_unixsock-seqpacket", target_os = "linux"))]
+    unixsock_seqpacket::register_configurables();
+    #[cfg(feature = "transport_serial")]
+    serial::register_configurables();
+    #[cfg(feature = "transport_ble")]
+    ble::register_configurables();
+    #[cfg(all(feature = "transport_vsock", target_os = "linux"))]
+    vsock::register_configurables();
+}
+
 #[derive(Default, Clone)]
 pub struct LocatorInspector {
     #[cfg(feature = "transport_quic")]
@@ -113,14 +171,22 @@ pub struct LocatorInspector {
     udp_inspector: UdpLocatorInspector,
     #[cfg(feature = "transport_ws")]
     ws_inspector: WsLocatorInspector,
+    #[cfg(feature = "transport_ws")]
+    wss_inspector: WssLocatorInspector,
     #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
     unixsock_stream_inspector: UnixSockStreamLocatorInspector,
+    #[cfg(all(feature = "transport_unixsock-seqpacket", target_os = "linux"))]
+    unixsock_seqpacket_inspector: UnixSockSeqpacketLocatorInspector,
     #[cfg(feature = "transport_serial")]
     serial_inspector: SerialLocatorInspector,
+    #[cfg(feature = "transport_ble")]
+    ble_inspector: BleLocatorInspector,
     #[cfg(feature = "transport_unixpipe")]
     unixpipe_inspector: UnixPipeLocatorInspector,
     #[cfg(all(feature = "transport_vsock", target_os = "linux"))]
     vsock_inspector: VsockLocatorInspector,
+    #[cfg(feature = "transport_custom")]
+    custom_inspector: CustomLocatorInspector,
 }
 impl LocatorInspector {
     pub async fn is_multicast(&self, locator: &Locator) -> ZResult<bool> {
@@ -140,26 +206,44 @@ impl LocatorInspector {
             UNIXSOCKSTREAM_LOCATOR_PREFIX => {
                 self.unixsock_stream_inspector.is_multicast(locator).await
             }
+            #[cfg(all(feature = "transport_unixsock-seqpacket", target_os = "linux"))]
+            UNIXSOCKSEQPACKET_LOCATOR_PREFIX => {
+                self.unixsock_seqpacket_inspector
+                    .is_multicast(locator)
+                    .await
+            }
             #[cfg(feature = "transport_ws")]
             WS_LOCATOR_PREFIX => self.ws_inspector.is_multicast(locator).await,
+            #[cfg(feature = "transport_ws")]
+            WSS_LOCATOR_PREFIX => self.wss_inspector.is_multicast(locator).await,
             #[cfg(feature = "transport_serial")]
             SERIAL_LOCATOR_PREFIX => self.serial_inspector.is_multicast(locator).await,
+            #[cfg(feature = "transport_ble")]
+            BLE_LOCATOR_PREFIX => self.ble_inspector.is_multicast(locator).await,
             #[cfg(feature = "transport_unixpipe")]
             UNIXPIPE_LOCATOR_PREFIX => self.unixpipe_inspector.is_multicast(locator).await,
             #[cfg(all(feature = "transport_vsock", target_os = "linux"))]
             VSOCK_LOCATOR_PREFIX => self.vsock_inspector.is_multicast(locator).await,
+            #[cfg(feature = "transport_custom")]
+            CUSTOM_LOCATOR_PREFIX => self.custom_inspector.is_multicast(locator).await,
             _ => bail!("Unsupported protocol: {}.", protocol),
         }
     }
 }
 #[derive(Default)]
 pub struct LinkConfigurator {
+    #[cfg(feature = "transport_tcp")]
+    tcp_inspector: TcpConfigurator,
     #[cfg(feature = "transport_quic")]
     quic_inspector: QuicConfigurator,
     #[cfg(feature = "transport_tls")]
     tls_inspector: TlsConfigurator,
     #[cfg(feature = "transport_unixpipe")]
     unixpipe_inspector: UnixPipeConfigurator,
+    #[cfg(feature = "transport_ws")]
+    wss_inspector: WssConfigurator,
+    #[cfg(feature = "transport_custom")]
+    custom_inspector: CustomConfigurator,
 }
 
 impl LinkConfigurator {
@@ -181,6 +265,13 @@ impl LinkConfigurator {
                 errors.insert(proto, e);
             }
         };
+        #[cfg(feature = "transport_tcp")]
+        {
+            insert_config(
+                TCP_LOCATOR_PREFIX.into(),
+                self.tcp_inspector.inspect_config(config),
+            );
+        }
         #[cfg(feature = "transport_quic")]
         {
             insert_config(
@@ -202,6 +293,20 @@ impl LinkConfigurator {
                 self.unixpipe_inspector.inspect_config(config),
             );
         }
+        #[cfg(feature = "transport_ws")]
+        {
+            insert_config(
+                WSS_LOCATOR_PREFIX.into(),
+                self.wss_inspector.inspect_config(config),
+            );
+        }
+        #[cfg(feature = "transport_custom")]
+        {
+            insert_config(
+                CUSTOM_LOCATOR_PREFIX.into(),
+                self.custom_inspector.inspect_config(config),
+            );
+        }
         (configs, errors)
     }
 }
@@ -227,18 +332,30 @@ impl LinkManagerBuilderUnicast {
             UNIXSOCKSTREAM_LOCATOR_PREFIX => Ok(std::sync::Arc::new(
                 LinkManagerUnicastUnixSocketStream::new(_manager),
             )),
+            #[cfg(all(feature = "transport_unixsock-seqpacket", target_os = "linux"))]
+            UNIXSOCKSEQPACKET_LOCATOR_PREFIX => Ok(std::sync::Arc::new(
+                LinkManagerUnicastUnixSocketSeqpacket::new(_manager),
+            )),
             #[cfg(feature = "transport_ws")]
             WS_LOCATOR_PREFIX => Ok(std::sync::Arc::new(LinkManagerUnicastWs::new(_manager))),
+            #[cfg(feature = "transport_ws")]
+            WSS_LOCATOR_PREFIX => Ok(std::sync::Arc::new(LinkManagerUnicastWs::new(_manager))),
             #[cfg(feature = "transport_serial")]
             SERIAL_LOCATOR_PREFIX => {
                 Ok(std::sync::Arc::new(LinkManagerUnicastSerial::new(_manager)))
             }
+            #[cfg(feature = "transport_ble")]
+            BLE_LOCATOR_PREFIX => Ok(std::sync::Arc::new(LinkManagerUnicastBle::new(_manager))),
             #[cfg(feature = "transport_unixpipe")]
             UNIXPIPE_LOCATOR_PREFIX => {
                 Ok(std::sync::Arc::new(LinkManagerUnicastPipe::new(_manager)))
             }
             #[cfg(all(feature = "transport_vsock", target_os = "linux"))]
             VSOCK_LOCATOR_PREFIX => Ok(std::sync::Arc::new(LinkManagerUnicastVsock::new(_manager))),
+            #[cfg(feature = "transport_custom")]
+            CUSTOM_LOCATOR_PREFIX => {
+                Ok(std::sync::Arc::new(LinkManagerUnicastCustom::new(_manager)))
+            }
             _ => bail!("Unicast not supported for {} protocol", protocol),
         }
     }
@@ -255,6 +372,8 @@ impl LinkManagerBuilderMulticast {
         match protocol {
             #[cfg(feature = "transport_udp")]
             UDP_LOCATOR_PREFIX => Ok(std::sync::Arc::new(LinkManagerMulticastUdp)),
+            #[cfg(feature = "transport_custom")]
+            CUSTOM_LOCATOR_PREFIX => Ok(std::sync::Arc::new(LinkManagerMulticastCustom)),
             _ => bail!("Multicast not supported for {} protocol", protocol),
         }
     }
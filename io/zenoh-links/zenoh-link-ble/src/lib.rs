@@ -0,0 +1,200 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This crate is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](../zenoh/index.html)
+#[cfg(feature = "transport_ble")]
+mod unicast;
+
+use async_trait::async_trait;
+use std::str::FromStr;
+#[cfg(feature = "transport_ble")]
+pub use unicast::*;
+use uuid::Uuid;
+use zenoh_core::zconfigurable;
+use zenoh_link_commons::LocatorInspector;
+use zenoh_protocol::core::{endpoint::Address, EndPoint, Locator};
+use zenoh_result::{bail, zerror, ZResult};
+
+pub const BLE_LOCATOR_PREFIX: &str = "ble";
+
+// The GATT service exposed by the BLE peers, e.g. zenoh-pico on a sensor.
+pub const BLE_SERVICE_UUID: Uuid = Uuid::from_u128(0x7a656e6f_6800_4000_8000_000000000001);
+// The characteristic written by the central, i.e. the bytes sent to the peer.
+pub const BLE_RX_CHARACTERISTIC_UUID: Uuid =
+    Uuid::from_u128(0x7a656e6f_6800_4000_8000_000000000002);
+// The characteristic notified by the peer, i.e. the bytes received from the peer.
+pub const BLE_TX_CHARACTERISTIC_UUID: Uuid =
+    Uuid::from_u128(0x7a656e6f_6800_4000_8000_000000000003);
+// The characteristic read by the central, i.e. the ATT_MTU negotiated by the peer (u16 little endian).
+pub const BLE_MTU_CHARACTERISTIC_UUID: Uuid =
+    Uuid::from_u128(0x7a656e6f_6800_4000_8000_000000000004);
+
+// The length of the ATT header of a characteristic write or notification.
+const ATT_HEADER_LEN: u16 = 3;
+
+// Maximum MTU (BLE PDU) in bytes: the maximum length of a GATT attribute value.
+// Each batch is carried by a single characteristic write or notification.
+const BLE_MAX_MTU: u16 = 512;
+
+zconfigurable! {
+    // Default MTU (BLE PDU) in bytes: the ATT payload of the 247 bytes ATT_MTU
+    // negotiated by most BLE 4.2+ stacks supporting the Data Length Extension.
+    static ref BLE_DEFAULT_MTU: u16 = 244;
+}
+
+#[cfg(feature = "transport_ble")]
+zconfigurable! {
+    // Amount of time in milliseconds to scan for the peer before giving up.
+    #[mutable]
+    static ref BLE_SCAN_TIMEOUT: u64 = 10_000;
+    // Number of attempts to reconnect to a peer that went out of range.
+    #[mutable]
+    static ref BLE_RECONNECT_ATTEMPTS: u32 = 5;
+    // Amount of time in milliseconds between two reconnection attempts.
+    #[mutable]
+    static ref BLE_RECONNECT_DELAY: u64 = 500;
+}
+
+/// Registers the values of this crate that can be modified at runtime.
+pub fn register_configurables() {
+    zenoh_core::configurable::register(&BLE_SCAN_TIMEOUT);
+    zenoh_core::configurable::register(&BLE_RECONNECT_ATTEMPTS);
+    zenoh_core::configurable::register(&BLE_RECONNECT_DELAY);
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct BleLocatorInspector;
+#[async_trait]
+impl LocatorInspector for BleLocatorInspector {
+    fn protocol(&self) -> &str {
+        BLE_LOCATOR_PREFIX
+    }
+
+    async fn is_multicast(&self, _locator: &Locator) -> ZResult<bool> {
+        Ok(false)
+    }
+}
+
+/// The bytes of a BLE address, e.g. `ble/AA:BB:CC:DD:EE:FF`.
+pub fn get_ble_address(address: Address<'_>) -> ZResult<[u8; 6]> {
+    let mut bytes = [0u8; 6];
+    let mut parts = address.as_str().split(':');
+    for byte in bytes.iter_mut() {
+        *byte = parts
+            .next()
+            .filter(|part| part.len() == 2)
+            .and_then(|part| u8::from_str_radix(part, 16).ok())
+            .ok_or_else(|| zerror!("Invalid BLE address: {}", address))?;
+    }
+    if parts.next().is_some() {
+        bail!("Invalid BLE address: {}", address);
+    }
+    Ok(bytes)
+}
+
+/// The MTU of a BLE link, which must not exceed the ATT_MTU negotiated with
+/// the peer minus the 3 bytes of the ATT header.
+pub fn get_ble_mtu(endpoint: &EndPoint) -> ZResult<u16> {
+    let Some(mtu) = endpoint.config().get(config::BLE_MTU) else {
+        return Ok(*BLE_DEFAULT_MTU);
+    };
+    match u16::from_str(mtu) {
+        Ok(0) | Err(_) => bail!(
+            "Invalid {} for BLE endpoint {}: {}",
+            config::BLE_MTU,
+            endpoint,
+            mtu
+        ),
+        Ok(mtu) if mtu > BLE_MAX_MTU => {
+            tracing::debug!(
+                "{} of BLE endpoint {} capped to the maximum GATT attribute length: {}",
+                config::BLE_MTU,
+                endpoint,
+                BLE_MAX_MTU
+            );
+            Ok(BLE_MAX_MTU)
+        }
+        Ok(mtu) => Ok(mtu),
+    }
+}
+
+/// The MTU of a BLE link once connected: the configured one, lowered to fit in the ATT_MTU
+/// the peer negotiated with us, as read from its MTU characteristic. A peer not exposing it
+/// is trusted to support the configured MTU.
+pub fn negotiate_ble_mtu(mtu: u16, peer_att_mtu: Option<&[u8]>) -> u16 {
+    match peer_att_mtu.and_then(|value| <[u8; 2]>::try_from(value).ok()) {
+        Some(att_mtu) => {
+            let peer_mtu = u16::from_le_bytes(att_mtu).saturating_sub(ATT_HEADER_LEN);
+            if peer_mtu < mtu {
+                tracing::debug!("BLE MTU lowered from {} to {} by the peer", mtu, peer_mtu);
+            }
+            mtu.min(peer_mtu).max(1)
+        }
+        None => mtu,
+    }
+}
+
+pub mod config {
+    pub const BLE_MTU: &str = "mtu";
+    pub const BLE_ADAPTER: &str = "adapter";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ble_address() {
+        let endpoint: EndPoint = "ble/AA:bb:0C:dd:EE:01".parse().unwrap();
+        assert_eq!(
+            get_ble_address(endpoint.address()).unwrap(),
+            [0xaa, 0xbb, 0x0c, 0xdd, 0xee, 0x01]
+        );
+        for address in [
+            "ble/AA:BB:CC:DD:EE",
+            "ble/AA:BB:CC:DD:EE:FF:00",
+            "ble/AA:BB:CC:DD:EE:GG",
+        ] {
+            let endpoint: EndPoint = address.parse().unwrap();
+            assert!(get_ble_address(endpoint.address()).is_err(), "{address}");
+        }
+    }
+
+    #[test]
+    fn ble_mtu() {
+        let endpoint: EndPoint = "ble/AA:BB:CC:DD:EE:FF".parse().unwrap();
+        assert_eq!(get_ble_mtu(&endpoint).unwrap(), *BLE_DEFAULT_MTU);
+        let endpoint: EndPoint = "ble/AA:BB:CC:DD:EE:FF#mtu=100".parse().unwrap();
+        assert_eq!(get_ble_mtu(&endpoint).unwrap(), 100);
+        let endpoint: EndPoint = "ble/AA:BB:CC:DD:EE:FF#mtu=1000".parse().unwrap();
+        assert_eq!(get_ble_mtu(&endpoint).unwrap(), BLE_MAX_MTU);
+        let endpoint: EndPoint = "ble/AA:BB:CC:DD:EE:FF#mtu=0".parse().unwrap();
+        assert!(get_ble_mtu(&endpoint).is_err());
+    }
+
+    #[test]
+    fn ble_mtu_negotiation() {
+        // The ATT_MTU of the peer, minus the ATT header, lowers the MTU
+        assert_eq!(negotiate_ble_mtu(244, Some(&23u16.to_le_bytes())), 20);
+        // but never raises it
+        assert_eq!(negotiate_ble_mtu(244, Some(&517u16.to_le_bytes())), 244);
+        // A peer without the MTU characteristic, or with a malformed one, keeps the MTU
+        assert_eq!(negotiate_ble_mtu(244, None), 244);
+        assert_eq!(negotiate_ble_mtu(244, Some(&[23])), 244);
+    }
+}
@@ -0,0 +1,380 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use async_trait::async_trait;
+use btleplug::api::{
+    BDAddr, Central, Characteristic, Manager as _, Peripheral as _, ScanFilter, ValueNotification,
+    WriteType,
+};
+use btleplug::platform::{Adapter, Manager, Peripheral};
+use futures::stream::{Stream, StreamExt};
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex as AsyncMutex;
+use zenoh_core::zasynclock;
+use zenoh_link_commons::{
+    ConstructibleLinkManagerUnicast, LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait,
+    NewLinkChannelSender,
+};
+use zenoh_protocol::core::{EndPoint, Locator};
+use zenoh_result::{bail, zerror, ZResult};
+
+use super::{
+    config, get_ble_address, get_ble_mtu, negotiate_ble_mtu, BLE_LOCATOR_PREFIX,
+    BLE_MTU_CHARACTERISTIC_UUID, BLE_RECONNECT_ATTEMPTS, BLE_RECONNECT_DELAY,
+    BLE_RX_CHARACTERISTIC_UUID, BLE_SCAN_TIMEOUT, BLE_SERVICE_UUID, BLE_TX_CHARACTERISTIC_UUID,
+};
+
+type Notifications = Pin<Box<dyn Stream<Item = ValueNotification> + Send>>;
+
+struct LinkUnicastBle {
+    // The remote BLE peripheral
+    peripheral: Peripheral,
+    // The characteristic written to send bytes to the peripheral
+    rx: Characteristic,
+    // The characteristic notified by the peripheral to send bytes to us
+    tx: Characteristic,
+    // The notifications of the tx characteristic, renewed upon reconnection
+    notifications: AsyncMutex<Notifications>,
+    // The name of the local adapter
+    interface: String,
+    // The source locator, i.e. the local adapter
+    src_locator: Locator,
+    // The destination locator, i.e. the peripheral address
+    dst_locator: Locator,
+    // The MTU, capped to the GATT attribute length and to the ATT_MTU of the peer
+    mtu: u16,
+    // Lock for the writing end of the link
+    write_lock: AsyncMutex<()>,
+}
+
+impl LinkUnicastBle {
+    async fn reconnect(&self) -> ZResult<Notifications> {
+        for attempt in 1..=BLE_RECONNECT_ATTEMPTS.get() {
+            tokio::time::sleep(Duration::from_millis(BLE_RECONNECT_DELAY.get())).await;
+            tracing::debug!(
+                "Reconnecting BLE link {} (attempt {}/{})",
+                self,
+                attempt,
+                BLE_RECONNECT_ATTEMPTS.get()
+            );
+            match setup(&self.peripheral).await {
+                Ok(setup) => return Ok(setup.notifications),
+                Err(e) => tracing::debug!("Unable to reconnect BLE link {}: {}", self, e),
+            }
+        }
+        bail!(
+            "BLE link {} lost after {} reconnection attempts",
+            self,
+            BLE_RECONNECT_ATTEMPTS.get()
+        )
+    }
+
+    // Waits for the reading task to reconnect the peripheral after a write failure.
+    async fn wait_connected(&self) -> bool {
+        for _ in 0..=BLE_RECONNECT_ATTEMPTS.get() {
+            tokio::time::sleep(Duration::from_millis(BLE_RECONNECT_DELAY.get())).await;
+            if self.peripheral.is_connected().await.unwrap_or(false) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[async_trait]
+impl LinkUnicastTrait for LinkUnicastBle {
+    async fn close(&self) -> ZResult<()> {
+        tracing::trace!("Closing BLE link: {}", self);
+        self.peripheral.disconnect().await.map_err(|e| {
+            let e = zerror!("Unable to close BLE link {}: {}", self, e);
+            tracing::trace!("{}", e);
+            e.into()
+        })
+    }
+
+    async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
+        let _guard = zasynclock!(self.write_lock);
+        let buffer = &buffer[..buffer.len().min(self.mtu as usize)];
+        if let Err(e) = self
+            .peripheral
+            .write(&self.rx, buffer, WriteType::WithoutResponse)
+            .await
+        {
+            tracing::debug!("Write error on BLE link {}: {}", self, e);
+            if !self.wait_connected().await {
+                let e = zerror!("Write error on BLE link {}: {}", self, e);
+                tracing::trace!("{}", e);
+                return Err(e.into());
+            }
+            self.peripheral
+                .write(&self.rx, buffer, WriteType::WithoutResponse)
+                .await
+                .map_err(|e| {
+                    let e = zerror!("Write error on BLE link {}: {}", self, e);
+                    tracing::trace!("{}", e);
+                    e
+                })?;
+        }
+        Ok(buffer.len())
+    }
+
+    async fn write_all(&self, buffer: &[u8]) -> ZResult<()> {
+        let mut written: usize = 0;
+        while written < buffer.len() {
+            written += self.write(&buffer[written..]).await?;
+        }
+        Ok(())
+    }
+
+    async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
+        let mut notifications = zasynclock!(self.notifications);
+        loop {
+            match notifications.next().await {
+                Some(n) if n.uuid == self.tx.uuid => {
+                    if n.value.len() > buffer.len() {
+                        bail!(
+                            "Read error on BLE link {}: notification of {} bytes exceeds the buffer of {} bytes",
+                            self,
+                            n.value.len(),
+                            buffer.len()
+                        );
+                    }
+                    buffer[..n.value.len()].copy_from_slice(&n.value);
+                    return Ok(n.value.len());
+                }
+                Some(_) => continue,
+                // The notifications stop when the peripheral disconnects
+                None => *notifications = self.reconnect().await?,
+            }
+        }
+    }
+
+    async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()> {
+        let mut read: usize = 0;
+        while read < buffer.len() {
+            let n = self.read(&mut buffer[read..]).await?;
+            read += n;
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn get_src(&self) -> &Locator {
+        &self.src_locator
+    }
+
+    #[inline(always)]
+    fn get_dst(&self) -> &Locator {
+        &self.dst_locator
+    }
+
+    #[inline(always)]
+    fn get_mtu(&self) -> u16 {
+        self.mtu
+    }
+
+    #[inline(always)]
+    fn get_interface_names(&self) -> Vec<String> {
+        vec![self.interface.clone()]
+    }
+
+    #[inline(always)]
+    fn is_reliable(&self) -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn is_streamed(&self) -> bool {
+        false
+    }
+}
+
+impl fmt::Display for LinkUnicastBle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} => {}", self.src_locator, self.dst_locator)?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for LinkUnicastBle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ble")
+            .field("src", &self.src_locator)
+            .field("dst", &self.dst_locator)
+            .field("mtu", &self.mtu)
+            .finish()
+    }
+}
+
+struct Setup {
+    rx: Characteristic,
+    tx: Characteristic,
+    notifications: Notifications,
+    // The value of the MTU characteristic, if the peripheral exposes it
+    att_mtu: Option<Vec<u8>>,
+}
+
+// Connects to the peripheral, reads its ATT_MTU and subscribes to the notifications of its tx
+// characteristic.
+async fn setup(peripheral: &Peripheral) -> ZResult<Setup> {
+    if !peripheral.is_connected().await? {
+        peripheral.connect().await?;
+    }
+    peripheral.discover_services().await?;
+    let characteristics = peripheral.characteristics();
+    let find = |uuid| {
+        characteristics
+            .iter()
+            .find(|c| c.service_uuid == BLE_SERVICE_UUID && c.uuid == uuid)
+            .cloned()
+            .ok_or_else(|| zerror!("BLE characteristic {} not found", uuid))
+    };
+    let rx = find(BLE_RX_CHARACTERISTIC_UUID)?;
+    let tx = find(BLE_TX_CHARACTERISTIC_UUID)?;
+    let att_mtu = match find(BLE_MTU_CHARACTERISTIC_UUID) {
+        Ok(c) => Some(peripheral.read(&c).await?),
+        Err(_) => None,
+    };
+    peripheral.subscribe(&tx).await?;
+    let notifications = peripheral.notifications().await?;
+    Ok(Setup {
+        rx,
+        tx,
+        notifications,
+        att_mtu,
+    })
+}
+
+async fn get_adapter(endpoint: &EndPoint) -> ZResult<(Adapter, String)> {
+    let manager = Manager::new().await?;
+    let wanted = endpoint.config().get(config::BLE_ADAPTER);
+    for adapter in manager.adapters().await? {
+        // e.g. "hci0 (usb:v1D6Bp0246d0540)" on Linux
+        let info = adapter.adapter_info().await?;
+        let name = info
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_owned();
+        if wanted.map_or(true, |w| w == name) {
+            return Ok((adapter, name));
+        }
+    }
+    match wanted {
+        Some(w) => bail!("BLE adapter {} not found", w),
+        None => bail!("No BLE adapter found"),
+    }
+}
+
+async fn find_peripheral(adapter: &Adapter, address: BDAddr) -> ZResult<Peripheral> {
+    adapter
+        .start_scan(ScanFilter {
+            services: vec![BLE_SERVICE_UUID],
+        })
+        .await?;
+    let res = tokio::time::timeout(Duration::from_millis(BLE_SCAN_TIMEOUT.get()), async {
+        loop {
+            for peripheral in adapter.peripherals().await? {
+                if peripheral.address() == address {
+                    return ZResult::Ok(peripheral);
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await;
+    let _ = adapter.stop_scan().await;
+    res.map_err(|_| zerror!("BLE peripheral {} not found", address))?
+}
+
+pub struct LinkManagerUnicastBle {
+    _manager: NewLinkChannelSender,
+}
+
+impl LinkManagerUnicastBle {
+    pub fn new(manager: NewLinkChannelSender) -> Self {
+        Self { _manager: manager }
+    }
+}
+impl ConstructibleLinkManagerUnicast<()> for LinkManagerUnicastBle {
+    fn new(new_link_sender: NewLinkChannelSender, _: ()) -> ZResult<Self> {
+        Ok(Self::new(new_link_sender))
+    }
+}
+
+#[async_trait]
+impl LinkManagerUnicastTrait for LinkManagerUnicastBle {
+    async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
+        let address = BDAddr::from(get_ble_address(endpoint.address())?);
+        let mtu = get_ble_mtu(&endpoint)?;
+        let (adapter, interface) = get_adapter(&endpoint).await.map_err(|e| {
+            let e = zerror!("Can not create a new BLE link to {}: {}", address, e);
+            tracing::warn!("{}", e);
+            e
+        })?;
+        tracing::trace!("Opening BLE link to {address} on adapter {interface} with MTU {mtu}");
+
+        let peripheral = find_peripheral(&adapter, address).await?;
+        let Setup {
+            rx,
+            tx,
+            notifications,
+            att_mtu,
+        } = setup(&peripheral).await.map_err(|e| {
+            let e = zerror!("Can not create a new BLE link to {}: {}", address, e);
+            tracing::warn!("{}", e);
+            e
+        })?;
+        let mtu = negotiate_ble_mtu(mtu, att_mtu.as_deref());
+
+        let link = Arc::new(LinkUnicastBle {
+            peripheral,
+            rx,
+            tx,
+            notifications: AsyncMutex::new(notifications),
+            src_locator: Locator::new(BLE_LOCATOR_PREFIX, &interface, "")?,
+            dst_locator: Locator::new(BLE_LOCATOR_PREFIX, address.to_string(), "")?,
+            interface,
+            mtu,
+            write_lock: AsyncMutex::new(()),
+        });
+
+        Ok(LinkUnicast(link))
+    }
+
+    async fn new_listener(&self, endpoint: EndPoint) -> ZResult<Locator> {
+        bail!(
+            "Can not create a BLE listener on {}: BLE links can only be opened towards a peripheral",
+            endpoint
+        )
+    }
+
+    async fn del_listener(&self, endpoint: &EndPoint) -> ZResult<()> {
+        bail!(
+            "Can not delete the BLE listener because it has not been found: {}",
+            endpoint
+        )
+    }
+
+    async fn get_listeners(&self) -> Vec<EndPoint> {
+        vec![]
+    }
+
+    async fn get_locators(&self) -> Vec<Locator> {
+        vec![]
+    }
+}
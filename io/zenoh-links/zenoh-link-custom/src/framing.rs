@@ -0,0 +1,250 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use async_trait::async_trait;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use zenoh_result::{bail, zerror, ZResult};
+
+use super::{CustomReader, CustomWriter};
+
+/// Delimits the batches written on and read from the stream of a unicast link.
+///
+/// The framing of a link is selected with the `framing` option of its endpoint, either
+/// a built-in one or one registered with [`register_framing`](crate::register_framing).
+#[async_trait]
+pub trait CustomFraming: Send + Sync {
+    /// Write a whole batch on the stream.
+    async fn write_batch(&self, writer: &mut CustomWriter, batch: &[u8]) -> ZResult<()>;
+
+    /// Read a whole batch from the stream into `buffer`, returning its length.
+    async fn read_batch(&self, reader: &mut CustomReader, buffer: &mut [u8]) -> ZResult<usize>;
+}
+
+/// Prefixes each batch with its length (u16 little endian).
+///
+/// These are the same bytes on the wire as a link without framing, whose batches are
+/// prefixed with their length by the transport.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct LengthPrefixFraming;
+
+#[async_trait]
+impl CustomFraming for LengthPrefixFraming {
+    async fn write_batch(&self, writer: &mut CustomWriter, batch: &[u8]) -> ZResult<()> {
+        let len = u16::try_from(batch.len())
+            .map_err(|_| zerror!("Batch of {} bytes is too large", batch.len()))?;
+        writer.write_all(&len.to_le_bytes()).await?;
+        writer.write_all(batch).await?;
+        Ok(())
+    }
+
+    async fn read_batch(&self, reader: &mut CustomReader, buffer: &mut [u8]) -> ZResult<usize> {
+        let len = reader.read_u16_le().await? as usize;
+        if len > buffer.len() {
+            bail!("Batch of {} bytes exceeds {} bytes", len, buffer.len());
+        }
+        reader.read_exact(&mut buffer[..len]).await?;
+        Ok(len)
+    }
+}
+
+/// Encodes each batch with COBS (Consistent Overhead Byte Stuffing) and terminates it
+/// with a 0x00 byte, for a stream where this byte value is reserved.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct CobsFraming;
+
+const COBS_DELIMITER: u8 = 0x00;
+
+impl CobsFraming {
+    fn encode(data: &[u8], out: &mut Vec<u8>) {
+        let mut code_idx = out.len();
+        let mut code = 1u8;
+        out.push(0);
+        for &byte in data {
+            if byte != 0 {
+                out.push(byte);
+                code += 1;
+            }
+            if byte == 0 || code == 0xFF {
+                out[code_idx] = code;
+                code_idx = out.len();
+                code = 1;
+                out.push(0);
+            }
+        }
+        out[code_idx] = code;
+    }
+
+    fn decode(src: &[u8], dst: &mut [u8]) -> Option<usize> {
+        let (mut i, mut n) = (0, 0);
+        while i < src.len() {
+            let code = src[i] as usize;
+            if code == 0 {
+                return None;
+            }
+            let start = i + 1;
+            let end = start + code - 1;
+            let chunk = src.get(start..end)?;
+            dst.get_mut(n..n + chunk.len())?.copy_from_slice(chunk);
+            n += chunk.len();
+            i = end;
+            if code < 0xFF && i < src.len() {
+                *dst.get_mut(n)? = 0;
+                n += 1;
+            }
+        }
+        Some(n)
+    }
+}
+
+#[async_trait]
+impl CustomFraming for CobsFraming {
+    async fn write_batch(&self, writer: &mut CustomWriter, batch: &[u8]) -> ZResult<()> {
+        let mut frame = Vec::with_capacity(batch.len() + batch.len() / 254 + 2);
+        Self::encode(batch, &mut frame);
+        frame.push(COBS_DELIMITER);
+        writer.write_all(&frame).await?;
+        Ok(())
+    }
+
+    async fn read_batch(&self, reader: &mut CustomReader, buffer: &mut [u8]) -> ZResult<usize> {
+        let max = buffer.len() + buffer.len() / 254 + 1;
+        let mut frame = Vec::new();
+        loop {
+            match reader.read_u8().await? {
+                COBS_DELIMITER => break,
+                byte => frame.push(byte),
+            }
+            if frame.len() > max {
+                bail!("Batch of more than {} bytes", buffer.len());
+            }
+        }
+        Self::decode(&frame, buffer).ok_or_else(|| zerror!("Invalid COBS frame").into())
+    }
+}
+
+// The header of a frame: the length of its data (u16 little endian) and its flags
+const FRAME_HEADER_LEN: usize = 3;
+// More frames follow to complete the batch
+const FRAME_FLAG_MORE: u8 = 1;
+
+/// Splits each batch in frames of at most `frame_size` bytes, each one prefixed with
+/// a header: the length of its data (u16 little endian) and its flags.
+///
+/// With [`FrameFraming::fixed`], the frames are padded to exactly `frame_size` bytes,
+/// for a stream only able to carry fixed-size records.
+#[derive(Clone, Copy, Debug)]
+pub struct FrameFraming {
+    frame_size: usize,
+    fixed: bool,
+}
+
+impl FrameFraming {
+    /// Splits the batches in frames of at most `frame_size` bytes.
+    pub fn new(frame_size: usize) -> ZResult<Self> {
+        if frame_size <= FRAME_HEADER_LEN || frame_size > u16::MAX as usize {
+            bail!(
+                "Invalid frame size: {} is not in ]{}, {}]",
+                frame_size,
+                FRAME_HEADER_LEN,
+                u16::MAX
+            );
+        }
+        Ok(FrameFraming {
+            frame_size,
+            fixed: false,
+        })
+    }
+
+    /// Splits the batches in records of exactly `frame_size` bytes.
+    pub fn fixed(frame_size: usize) -> ZResult<Self> {
+        Ok(FrameFraming {
+            fixed: true,
+            ..Self::new(frame_size)?
+        })
+    }
+}
+
+#[async_trait]
+impl CustomFraming for FrameFraming {
+    async fn write_batch(&self, writer: &mut CustomWriter, batch: &[u8]) -> ZResult<()> {
+        let padding = [0u8; 64];
+        let mut chunks = batch.chunks(self.frame_size - FRAME_HEADER_LEN).peekable();
+        while let Some(chunk) = chunks.next() {
+            let len = (chunk.len() as u16).to_le_bytes();
+            let flags = if chunks.peek().is_some() {
+                FRAME_FLAG_MORE
+            } else {
+                0
+            };
+            writer.write_all(&[len[0], len[1], flags]).await?;
+            writer.write_all(chunk).await?;
+            if self.fixed {
+                let mut left = self.frame_size - FRAME_HEADER_LEN - chunk.len();
+                while left > 0 {
+                    let n = left.min(padding.len());
+                    writer.write_all(&padding[..n]).await?;
+                    left -= n;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn read_batch(&self, reader: &mut CustomReader, buffer: &mut [u8]) -> ZResult<usize> {
+        let mut len = 0;
+        loop {
+            let mut header = [0u8; FRAME_HEADER_LEN];
+            reader.read_exact(&mut header).await?;
+            let size = u16::from_le_bytes([header[0], header[1]]) as usize;
+            if len + size > buffer.len() || size > self.frame_size - FRAME_HEADER_LEN {
+                bail!("Batch of more than {} bytes", buffer.len());
+            }
+            reader.read_exact(&mut buffer[len..len + size]).await?;
+            len += size;
+            if self.fixed {
+                let mut padding = [0u8; 64];
+                let mut left = self.frame_size - FRAME_HEADER_LEN - size;
+                while left > 0 {
+                    let n = left.min(padding.len());
+                    reader.read_exact(&mut padding[..n]).await?;
+                    left -= n;
+                }
+            }
+            if header[2] & FRAME_FLAG_MORE == 0 {
+                return Ok(len);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cobs() {
+        let mut long = vec![1u8; 600];
+        long[300] = 0;
+        for data in [&[][..], &[0], &[0, 0], &[1, 0, 2], &[0xFF; 254], &long] {
+            let mut encoded = Vec::new();
+            CobsFraming::encode(data, &mut encoded);
+            assert!(!encoded.contains(&COBS_DELIMITER));
+            let mut decoded = vec![0u8; data.len()];
+            assert_eq!(
+                CobsFraming::decode(&encoded, &mut decoded),
+                Some(data.len())
+            );
+            assert_eq!(decoded.as_slice(), data);
+        }
+    }
+}
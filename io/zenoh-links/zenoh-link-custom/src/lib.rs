@@ -0,0 +1,240 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This crate is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](../zenoh/index.html)
+//!
+//! Implements a link over application-supplied streams.
+//!
+//! The application hands in an already established `(AsyncRead, AsyncWrite)` pair with
+//! [`register_stream`] under a given name, e.g. for a media without addresses like a modem
+//! driver. The stream is then used by zenoh as a link when connecting to or listening on
+//! the `custom/<name>` endpoint.
+//!
+//! Likewise, the application can hand in a datagram medium with [`register_multicast`],
+//! used as a multicast link on the `custom/<name>?multicast=true` endpoint.
+//!
+//! The batches of a unicast link are delimited on the stream by a [`CustomFraming`],
+//! selected with the `framing` option of the endpoint, e.g. `custom/modem#framing=cobs`.
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncRead, AsyncWrite};
+use zenoh_config::Config;
+use zenoh_core::{zconfigurable, zlock};
+use zenoh_link_commons::{proxy::ProxyConfig, ConfigurationInspector, LocatorInspector};
+use zenoh_protocol::core::{Locator, Parameters};
+use zenoh_result::{zerror, ZResult};
+
+mod framing;
+mod multicast;
+mod unicast;
+pub use framing::*;
+pub use multicast::*;
+pub use unicast::*;
+
+pub const CUSTOM_LOCATOR_PREFIX: &str = "custom";
+
+#[derive(Default, Clone, Copy)]
+pub struct CustomLocatorInspector;
+#[async_trait]
+impl LocatorInspector for CustomLocatorInspector {
+    fn protocol(&self) -> &str {
+        CUSTOM_LOCATOR_PREFIX
+    }
+
+    async fn is_multicast(&self, locator: &Locator) -> ZResult<bool> {
+        match locator.metadata().get(config::CUSTOM_MULTICAST) {
+            Some(multicast) => multicast.parse().map_err(|_| {
+                zerror!("Invalid multicast flag for {}: {}", locator, multicast).into()
+            }),
+            None => Ok(false),
+        }
+    }
+}
+
+pub mod config {
+    /// Use the medium registered with [`register_multicast`](crate::register_multicast) as
+    /// a multicast link, e.g. `custom/radio?multicast=true`.
+    pub const CUSTOM_MULTICAST: &str = "multicast";
+    /// The number of hops the datagrams of a multicast link are forwarded, e.g. `custom/radio?multicast=true;ttl=2`.
+    pub const CUSTOM_MULTICAST_TTL: &str = "ttl";
+    /// Split the batches written on a unicast link in frames of at most the given size, e.g.
+    /// `custom/modem#frame_size=256`, for a stream only able to carry small frames.
+    /// It must be set to the same value on both sides of the stream.
+    pub const CUSTOM_FRAME_SIZE: &str = "frame_size";
+    /// The framing of the batches on a unicast link, which must be the same on both sides of the stream:
+    /// - `length`: each batch is prefixed with its 16-bit length, as without framing;
+    /// - `cobs`: each batch is COBS encoded and terminated by a 0x00 byte, e.g. `custom/modem#framing=cobs`;
+    /// - `fixed`: the batches are split in records of exactly `frame_size` bytes,
+    ///   e.g. `custom/modem#framing=fixed;frame_size=64`;
+    /// - the name of a framing registered with [`register_framing`](crate::register_framing).
+    pub const CUSTOM_FRAMING: &str = "framing";
+    /// Read a unicast link through a buffer of the given size in bytes, e.g. `custom/modem#so_rcvbuf=65536`.
+    pub const CUSTOM_SO_RCVBUF: &str = "so_rcvbuf";
+    /// Write a unicast link through a buffer of the given size in bytes, e.g. `custom/modem#so_sndbuf=65536`.
+    pub const CUSTOM_SO_SNDBUF: &str = "so_sndbuf";
+    /// Wait up to the given time in milliseconds for a stream to be registered when opening a unicast link,
+    /// e.g. `custom/modem#connect_timeout=5000`. By default, the link fails to open if no stream is registered.
+    pub const CUSTOM_CONNECT_TIMEOUT: &str = "connect_timeout";
+    /// Close a unicast link not receiving any data for the given time in milliseconds,
+    /// e.g. `custom/modem#keepalive=10000`, for a stream not reporting the loss of the media.
+    pub const CUSTOM_KEEPALIVE: &str = "keepalive";
+    /// Open a unicast link on a stream established through the proxy of the endpoint towards the given
+    /// `<host>:<port>` instead of a registered stream, e.g. `custom/gateway#proxy=socks5://10.0.0.2:1080;proxy_destination=gateway.corp:7447`.
+    pub const CUSTOM_PROXY_DESTINATION: &str = "proxy_destination";
+}
+
+#[derive(Default, Clone, Copy, Debug)]
+pub struct CustomConfigurator;
+
+impl ConfigurationInspector<Config> for CustomConfigurator {
+    fn inspect_config(&self, config: &Config) -> ZResult<String> {
+        let c = config.transport().link().custom();
+        let mut properties: Vec<(&str, String)> = [
+            (
+                config::CUSTOM_SO_RCVBUF,
+                c.so_rcvbuf().map(|v| v.to_string()),
+            ),
+            (
+                config::CUSTOM_SO_SNDBUF,
+                c.so_sndbuf().map(|v| v.to_string()),
+            ),
+            (
+                config::CUSTOM_CONNECT_TIMEOUT,
+                c.connect_timeout().map(|v| v.to_string()),
+            ),
+            (
+                config::CUSTOM_KEEPALIVE,
+                c.keepalive().map(|v| v.to_string()),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(k, v)| v.map(|v| (k, v)))
+        .collect();
+        properties.extend(ProxyConfig::properties(config));
+
+        let mut s = String::new();
+        Parameters::extend(properties.iter().map(|(k, v)| (*k, v.as_str())), &mut s);
+
+        Ok(s)
+    }
+}
+
+zconfigurable! {
+    // Default MTU in bytes.
+    static ref CUSTOM_DEFAULT_MTU: u16 = u16::MAX;
+    // Maximum size in bytes of the batches sent on a multicast link, bounded by the MTU of the medium.
+    static ref CUSTOM_MAX_MTU: u16 = 8_192;
+}
+
+pub type CustomReader = Box<dyn AsyncRead + Send + Unpin>;
+pub type CustomWriter = Box<dyn AsyncWrite + Send + Unpin>;
+
+pub(crate) struct CustomStream {
+    pub(crate) reader: CustomReader,
+    pub(crate) writer: CustomWriter,
+}
+
+type CustomChannel = (flume::Sender<CustomStream>, flume::Receiver<CustomStream>);
+
+lazy_static::lazy_static! {
+    // The streams registered by the application and not used by a link yet, by name
+    static ref CUSTOM_STREAMS: Mutex<HashMap<String, CustomChannel>> = Mutex::new(HashMap::new());
+    // The framings registered by the application, by name
+    static ref CUSTOM_FRAMINGS: Mutex<HashMap<String, Arc<dyn CustomFraming>>> =
+        Mutex::new(HashMap::new());
+    // The multicast media registered by the application, by name
+    static ref CUSTOM_MULTICAST_MEDIA: Mutex<HashMap<String, Arc<dyn CustomMulticastMedium>>> =
+        Mutex::new(HashMap::new());
+}
+
+pub(crate) fn streams(name: &str) -> flume::Receiver<CustomStream> {
+    zlock!(CUSTOM_STREAMS)
+        .entry(name.to_string())
+        .or_insert_with(flume::unbounded)
+        .1
+        .clone()
+}
+
+// Remove the channel of `name` from the registry once no link waits on it and no stream is
+// pending in it. Must be called after dropping the receiver returned by `streams`.
+pub(crate) fn prune_streams(name: &str) {
+    let mut guard = zlock!(CUSTOM_STREAMS);
+    if let Some((_, rx)) = guard.get(name) {
+        if rx.is_empty() && rx.receiver_count() == 1 {
+            guard.remove(name);
+        }
+    }
+}
+
+/// Register an already established stream to be used by the link on the `custom/<name>` endpoint.
+///
+/// The stream is used by the next link opened on `custom/<name>` or, if a listener exists
+/// on `custom/<name>`, it is immediately accepted as a new link.
+pub fn register_stream<R, W>(name: &str, reader: R, writer: W)
+where
+    R: AsyncRead + Send + Unpin + 'static,
+    W: AsyncWrite + Send + Unpin + 'static,
+{
+    let stream = CustomStream {
+        reader: Box::new(reader),
+        writer: Box::new(writer),
+    };
+    let tx = zlock!(CUSTOM_STREAMS)
+        .entry(name.to_string())
+        .or_insert_with(flume::unbounded)
+        .0
+        .clone();
+    // The registry always holds a receiver, the channel can not be disconnected
+    let _ = tx.send(stream);
+}
+
+/// Unregister the streams registered on `name` and not used by a link yet.
+///
+/// Returns the number of streams that have been unregistered.
+pub fn unregister_streams(name: &str) -> usize {
+    let Some((_, rx)) = zlock!(CUSTOM_STREAMS).remove(name) else {
+        return 0;
+    };
+    rx.drain().count()
+}
+
+pub(crate) fn registered_framing(name: &str) -> Option<Arc<dyn CustomFraming>> {
+    zlock!(CUSTOM_FRAMINGS).get(name).cloned()
+}
+
+/// Register a framing to be used by the unicast links on the `custom/<name>#framing=<framing>` endpoints.
+///
+/// The framing replaces the one previously registered under the same name, if any.
+/// The built-in framings (`length`, `cobs` and `fixed`) can not be replaced.
+pub fn register_framing<F>(name: &str, framing: F)
+where
+    F: CustomFraming + 'static,
+{
+    zlock!(CUSTOM_FRAMINGS).insert(name.to_string(), Arc::new(framing));
+}
+
+/// Register a datagram medium to be used by the multicast link on the `custom/<name>?multicast=true` endpoint.
+///
+/// The medium replaces the one previously registered under the same name, if any.
+pub fn register_multicast<M>(name: &str, medium: M)
+where
+    M: CustomMulticastMedium + 'static,
+{
+    zlock!(CUSTOM_MULTICAST_MEDIA).insert(name.to_string(), Arc::new(medium));
+}
@@ -0,0 +1,315 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use async_trait::async_trait;
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+use zenoh_core::zlock;
+use zenoh_link_commons::{LinkManagerMulticastTrait, LinkMulticast, LinkMulticastTrait};
+use zenoh_protocol::core::{EndPoint, Locator};
+use zenoh_result::{bail, zerror, ZResult};
+
+use super::{config, CUSTOM_LOCATOR_PREFIX, CUSTOM_MAX_MTU, CUSTOM_MULTICAST_MEDIA};
+
+/// A datagram medium supplied by the application, e.g. a radio broadcast channel, used by zenoh
+/// as a multicast link when opening the `custom/<name>?multicast=true` endpoint.
+/// See [`register_multicast`](crate::register_multicast).
+#[async_trait]
+pub trait CustomMulticastMedium: Send + Sync {
+    /// The identifier of the local member of the group, used as the source of its datagrams.
+    fn id(&self) -> String;
+
+    /// The maximum size of a datagram on the medium.
+    fn mtu(&self) -> u16 {
+        u16::MAX
+    }
+
+    /// Join the group, the datagrams being forwarded at most `ttl` hops away if set.
+    async fn join(&self, ttl: Option<u8>) -> ZResult<()>;
+
+    /// Leave the group.
+    async fn leave(&self) -> ZResult<()>;
+
+    /// Send a datagram to all the members of the group.
+    async fn send(&self, datagram: &[u8]) -> ZResult<()>;
+
+    /// Receive a datagram from the group, returning its size and the identifier of its sender.
+    async fn recv(&self, buffer: &mut [u8]) -> ZResult<(usize, String)>;
+}
+
+pub struct LinkMulticastCustom {
+    medium: Arc<dyn CustomMulticastMedium>,
+    src_locator: Locator,
+    dst_locator: Locator,
+    mtu: u16,
+}
+
+#[async_trait]
+impl LinkMulticastTrait for LinkMulticastCustom {
+    async fn close(&self) -> ZResult<()> {
+        tracing::trace!("Closing custom multicast link: {}", self);
+        self.medium.leave().await.map_err(|e| {
+            let e = zerror!("custom multicast link leave {}: {}", self, e);
+            tracing::trace!("{}", e);
+            e.into()
+        })
+    }
+
+    async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
+        if buffer.len() > self.mtu as usize {
+            bail!(
+                "Write error on custom multicast link {}: datagram of {} bytes exceeds the MTU of {} bytes",
+                self,
+                buffer.len(),
+                self.mtu
+            );
+        }
+        self.medium.send(buffer).await.map_err(|e| {
+            let e = zerror!("Write error on custom multicast link {}: {}", self, e);
+            tracing::trace!("{}", e);
+            e
+        })?;
+        Ok(buffer.len())
+    }
+
+    async fn write_all(&self, buffer: &[u8]) -> ZResult<()> {
+        self.write(buffer).await.map(|_| ())
+    }
+
+    async fn read<'a>(&'a self, buffer: &mut [u8]) -> ZResult<(usize, Cow<'a, Locator>)> {
+        let (n, src) = self.medium.recv(buffer).await.map_err(|e| {
+            let e = zerror!("Read error on custom multicast link {}: {}", self, e);
+            tracing::trace!("{}", e);
+            e
+        })?;
+        let locator = Locator::new(CUSTOM_LOCATOR_PREFIX, src, "")?;
+        Ok((n, Cow::Owned(locator)))
+    }
+
+    #[inline(always)]
+    fn get_src(&self) -> &Locator {
+        &self.src_locator
+    }
+
+    #[inline(always)]
+    fn get_dst(&self) -> &Locator {
+        &self.dst_locator
+    }
+
+    #[inline(always)]
+    fn get_mtu(&self) -> u16 {
+        self.mtu
+    }
+
+    #[inline(always)]
+    fn is_reliable(&self) -> bool {
+        false
+    }
+}
+
+impl fmt::Display for LinkMulticastCustom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} => {}", self.src_locator, self.dst_locator)
+    }
+}
+
+impl fmt::Debug for LinkMulticastCustom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Custom")
+            .field("src", &self.src_locator)
+            .field("dst", &self.dst_locator)
+            .field("mtu", &self.mtu)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+pub struct LinkManagerMulticastCustom;
+
+#[async_trait]
+impl LinkManagerMulticastTrait for LinkManagerMulticastCustom {
+    async fn new_link(&self, endpoint: &EndPoint) -> ZResult<LinkMulticast> {
+        let name = endpoint.address();
+        let medium = zlock!(CUSTOM_MULTICAST_MEDIA)
+            .get(name.as_str())
+            .cloned()
+            .ok_or_else(|| zerror!("No custom multicast medium registered for {}", endpoint))?;
+        let ttl = match endpoint.metadata().get(config::CUSTOM_MULTICAST_TTL) {
+            Some(ttl) => Some(
+                ttl.parse::<u8>()
+                    .map_err(|e| zerror!("Invalid TTL {} for {}: {}", ttl, endpoint, e))?,
+            ),
+            None => None,
+        };
+        medium.join(ttl).await?;
+
+        let link = Arc::new(LinkMulticastCustom {
+            src_locator: Locator::new(CUSTOM_LOCATOR_PREFIX, medium.id(), "")?,
+            dst_locator: endpoint.to_locator(),
+            mtu: medium.mtu().min(*CUSTOM_MAX_MTU),
+            medium,
+        });
+
+        Ok(LinkMulticast(link))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{register_multicast, CustomLocatorInspector};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+    use tokio::sync::broadcast;
+    use zenoh_link_commons::LocatorInspector;
+
+    type Datagram = (Vec<u8>, String);
+
+    // A member of a group over an in-memory broadcast channel
+    struct TestMedium {
+        id: String,
+        mtu: u16,
+        tx: broadcast::Sender<Datagram>,
+        rx: tokio::sync::Mutex<broadcast::Receiver<Datagram>>,
+        joined: Arc<Mutex<Option<Option<u8>>>>,
+        left: Arc<AtomicBool>,
+    }
+
+    impl TestMedium {
+        fn new(id: &str, mtu: u16, tx: &broadcast::Sender<Datagram>) -> Self {
+            TestMedium {
+                id: id.to_string(),
+                mtu,
+                tx: tx.clone(),
+                rx: tokio::sync::Mutex::new(tx.subscribe()),
+                joined: Arc::default(),
+                left: Arc::default(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl CustomMulticastMedium for TestMedium {
+        fn id(&self) -> String {
+            self.id.clone()
+        }
+
+        fn mtu(&self) -> u16 {
+            self.mtu
+        }
+
+        async fn join(&self, ttl: Option<u8>) -> ZResult<()> {
+            *zlock!(self.joined) = Some(ttl);
+            Ok(())
+        }
+
+        async fn leave(&self) -> ZResult<()> {
+            self.left.store(true, Ordering::Relaxed);
+            Ok(())
+        }
+
+        async fn send(&self, datagram: &[u8]) -> ZResult<()> {
+            self.tx
+                .send((datagram.to_vec(), self.id.clone()))
+                .map_err(|e| zerror!("{}", e))?;
+            Ok(())
+        }
+
+        async fn recv(&self, buffer: &mut [u8]) -> ZResult<(usize, String)> {
+            let mut rx = self.rx.lock().await;
+            loop {
+                let (datagram, src) = rx.recv().await.map_err(|e| zerror!("{}", e))?;
+                // A member doesn't receive its own datagrams
+                if src != self.id {
+                    buffer[..datagram.len()].copy_from_slice(&datagram);
+                    return Ok((datagram.len(), src));
+                }
+            }
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn custom_multicast_link() {
+        let (tx, _) = broadcast::channel(16);
+        let a = TestMedium::new("a", u16::MAX, &tx);
+        let (a_joined, a_left) = (a.joined.clone(), a.left.clone());
+        register_multicast("test_radio_a", a);
+        register_multicast("test_radio_b", TestMedium::new("b", 1_024, &tx));
+
+        let endpoint: EndPoint = "custom/test_radio_a?multicast=true;ttl=2".parse().unwrap();
+        assert!(CustomLocatorInspector
+            .is_multicast(&endpoint.to_locator())
+            .await
+            .unwrap());
+        assert!(!CustomLocatorInspector
+            .is_multicast(&"custom/test_radio_a".parse().unwrap())
+            .await
+            .unwrap());
+
+        let manager = LinkManagerMulticastCustom;
+        let link_a = manager.new_link(&endpoint).await.unwrap();
+        assert_eq!(*zlock!(a_joined), Some(Some(2)));
+        let link_b = manager
+            .new_link(&"custom/test_radio_b?multicast=true".parse().unwrap())
+            .await
+            .unwrap();
+
+        // The MTU is the one of the medium, bounded by the maximum size of the batches
+        assert_eq!(link_a.get_mtu(), *CUSTOM_MAX_MTU);
+        assert_eq!(link_b.get_mtu(), 1_024);
+        assert_eq!(link_a.get_src().to_string(), "custom/a");
+
+        // The datagrams are received by the other members with the locator of their sender
+        link_a.write_all(b"zenoh").await.unwrap();
+        let mut buffer = [0u8; 64];
+        let (n, src) = link_b.read(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..n], b"zenoh");
+        assert_eq!(src.to_string(), "custom/a");
+
+        // The datagrams larger than the MTU are rejected
+        assert!(link_b.write(&[0u8; 1_025]).await.is_err());
+
+        link_a.close().await.unwrap();
+        assert!(a_left.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn custom_multicast_link_errors() {
+        let manager = LinkManagerMulticastCustom;
+
+        // No medium registered under the name
+        assert!(manager
+            .new_link(&"custom/test_radio_none?multicast=true".parse().unwrap())
+            .await
+            .is_err());
+
+        // Invalid TTL
+        let (tx, _) = broadcast::channel(16);
+        register_multicast("test_radio_ttl", TestMedium::new("c", u16::MAX, &tx));
+        assert!(manager
+            .new_link(
+                &"custom/test_radio_ttl?multicast=true;ttl=256"
+                    .parse()
+                    .unwrap()
+            )
+            .await
+            .is_err());
+
+        // Invalid multicast flag
+        assert!(CustomLocatorInspector
+            .is_multicast(&"custom/test_radio_ttl?multicast=maybe".parse().unwrap())
+            .await
+            .is_err());
+    }
+}
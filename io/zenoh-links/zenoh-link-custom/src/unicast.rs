@@ -0,0 +1,781 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::IoSlice;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
+use tokio::sync::{Mutex as AsyncMutex, RwLock as AsyncRwLock};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use zenoh_buffers::ZSlice;
+use zenoh_core::{zasynclock, zasyncread, zasyncwrite};
+use zenoh_link_commons::{
+    get_bind_socket, proxy::ProxyConfig, LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait,
+    NewLinkChannelSender, BIND_SOCKET,
+};
+use zenoh_protocol::core::{EndPoint, Locator};
+use zenoh_result::{bail, zerror, ZResult};
+
+use super::{
+    config, prune_streams, registered_framing, streams, CobsFraming, CustomFraming, CustomReader,
+    CustomStream, CustomWriter, FrameFraming, LengthPrefixFraming, CUSTOM_DEFAULT_MTU,
+};
+
+// The size of the read buffer of the framed links, parsing the stream in small reads
+const FRAMING_DEFAULT_RCVBUF: usize = 8_192;
+
+fn get_config<T: std::str::FromStr>(endpoint: &EndPoint, key: &str) -> ZResult<Option<T>> {
+    match endpoint.config().get(key) {
+        Some(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| zerror!("Invalid {} for {}: {}", key, endpoint, value).into()),
+        None => Ok(None),
+    }
+}
+
+// The options of a unicast link, set in the configuration of its endpoint
+#[derive(Clone, Default)]
+struct CustomLinkConfig {
+    // The framing delimiting the batches on the stream, if any
+    framing: Option<Arc<dyn CustomFraming>>,
+    rcvbuf: Option<usize>,
+    sndbuf: Option<usize>,
+    connect_timeout: Option<Duration>,
+    keepalive: Option<Duration>,
+}
+
+impl CustomLinkConfig {
+    fn new(endpoint: &EndPoint) -> ZResult<CustomLinkConfig> {
+        let frame_size = get_config::<usize>(endpoint, config::CUSTOM_FRAME_SIZE)?;
+        let framing: Option<Arc<dyn CustomFraming>> =
+            match (endpoint.config().get(config::CUSTOM_FRAMING), frame_size) {
+                (None, None) => None,
+                (None, Some(size)) => Some(Arc::new(
+                    FrameFraming::new(size).map_err(|e| zerror!("{}: {}", endpoint, e))?,
+                )),
+                (Some("fixed"), Some(size)) => Some(Arc::new(
+                    FrameFraming::fixed(size).map_err(|e| zerror!("{}: {}", endpoint, e))?,
+                )),
+                (Some("fixed"), None) => bail!(
+                    "Invalid framing for {}: fixed requires {}",
+                    endpoint,
+                    config::CUSTOM_FRAME_SIZE
+                ),
+                (Some(_), Some(_)) => bail!(
+                    "Invalid framing for {}: {} only applies to the fixed framing",
+                    endpoint,
+                    config::CUSTOM_FRAME_SIZE
+                ),
+                (Some("length"), None) => Some(Arc::new(LengthPrefixFraming)),
+                (Some("cobs"), None) => Some(Arc::new(CobsFraming)),
+                (Some(name), None) => Some(registered_framing(name).ok_or_else(|| {
+                    zerror!(
+                        "Invalid framing for {}: {} is not registered",
+                        endpoint,
+                        name
+                    )
+                })?),
+            };
+        let rcvbuf = get_config::<usize>(endpoint, config::CUSTOM_SO_RCVBUF)?;
+        let sndbuf = get_config::<usize>(endpoint, config::CUSTOM_SO_SNDBUF)?;
+        if rcvbuf == Some(0) || sndbuf == Some(0) {
+            bail!("Invalid buffer size for {}: 0", endpoint);
+        }
+        let keepalive = get_config::<u64>(endpoint, config::CUSTOM_KEEPALIVE)?;
+        if keepalive == Some(0) {
+            bail!("Invalid keepalive for {}: 0", endpoint);
+        }
+        Ok(CustomLinkConfig {
+            framing,
+            rcvbuf,
+            sndbuf,
+            connect_timeout: get_config::<u64>(endpoint, config::CUSTOM_CONNECT_TIMEOUT)?
+                .map(Duration::from_millis),
+            keepalive: keepalive.map(Duration::from_millis),
+        })
+    }
+}
+
+// The stream established through the proxy of the endpoint towards its proxy destination, if any
+async fn proxied_stream(endpoint: &EndPoint) -> ZResult<Option<CustomStream>> {
+    let epconf = endpoint.config();
+    let Some(destination) = epconf.get(config::CUSTOM_PROXY_DESTINATION) else {
+        return Ok(None);
+    };
+    let Some(proxy) = ProxyConfig::new(&epconf)? else {
+        bail!(
+            "Invalid {} for {}: no proxy is configured",
+            config::CUSTOM_PROXY_DESTINATION,
+            endpoint
+        );
+    };
+    // The connection to the proxy is bound to the source address of the endpoint, if any
+    let src_addr = get_bind_socket(&epconf)?;
+    let (reader, writer) = proxy
+        .connect_from(destination, src_addr.as_ref())
+        .await
+        .map_err(|e| zerror!("Can not create a new custom link on {}: {}", endpoint, e))?
+        .into_split();
+    Ok(Some(CustomStream {
+        reader: Box::new(reader),
+        writer: Box::new(writer),
+    }))
+}
+
+pub struct LinkUnicastCustom {
+    // The reader and the writer are locked separately to allow concurrent reads and writes
+    reader: AsyncMutex<CustomReader>,
+    writer: AsyncMutex<CustomWriter>,
+    src_locator: Locator,
+    dst_locator: Locator,
+    // The framing delimiting the batches on the stream, if any
+    framing: Option<Arc<dyn CustomFraming>>,
+    // The maximum time to wait for some data before failing a read, if any
+    keepalive: Option<Duration>,
+}
+
+impl LinkUnicastCustom {
+    fn new(stream: CustomStream, locator: Locator, config: CustomLinkConfig) -> LinkUnicastCustom {
+        let rcvbuf = match &config.framing {
+            Some(_) => config.rcvbuf.or(Some(FRAMING_DEFAULT_RCVBUF)),
+            None => config.rcvbuf,
+        };
+        let reader: CustomReader = match rcvbuf {
+            Some(capacity) => Box::new(BufReader::with_capacity(capacity, stream.reader)),
+            None => stream.reader,
+        };
+        let writer: CustomWriter = match config.sndbuf {
+            Some(capacity) => Box::new(BufWriter::with_capacity(capacity, stream.writer)),
+            None => stream.writer,
+        };
+        LinkUnicastCustom {
+            reader: AsyncMutex::new(reader),
+            writer: AsyncMutex::new(writer),
+            src_locator: locator.clone(),
+            dst_locator: locator,
+            framing: config.framing,
+            keepalive: config.keepalive,
+        }
+    }
+
+    // Fail the read if no data is received within the keepalive period, if any
+    async fn with_keepalive<T, F>(&self, read: F) -> ZResult<T>
+    where
+        F: std::future::Future<Output = ZResult<T>>,
+    {
+        match self.keepalive {
+            Some(keepalive) => tokio::time::timeout(keepalive, read)
+                .await
+                .map_err(|_| zerror!("No data received for {:?}", keepalive))?,
+            None => read.await,
+        }
+    }
+
+    // Write the slices with as few writes as possible, without copying them
+    async fn write_vectored_all(&self, slices: &[ZSlice]) -> ZResult<()> {
+        let mut writer = zasynclock!(self.writer);
+        // The index of the slice being written and the offset in it
+        let (mut idx, mut off) = (0, 0);
+        loop {
+            while idx < slices.len() && off == slices[idx].len() {
+                idx += 1;
+                off = 0;
+            }
+            if idx == slices.len() {
+                break;
+            }
+            let bufs: Vec<IoSlice> = std::iter::once(&slices[idx][off..])
+                .chain(slices[idx + 1..].iter().map(|s| s.as_slice()))
+                .map(IoSlice::new)
+                .collect();
+            let mut n = writer.write_vectored(&bufs).await?;
+            if n == 0 {
+                return Err(std::io::Error::from(std::io::ErrorKind::WriteZero).into());
+            }
+            while n > 0 {
+                let left = slices[idx].len() - off;
+                if n < left {
+                    off += n;
+                    break;
+                }
+                n -= left;
+                idx += 1;
+                off = 0;
+            }
+        }
+        writer.flush().await?;
+        Ok(())
+    }
+
+    // Write a whole batch with the framing of the link
+    async fn write_batch(&self, framing: &dyn CustomFraming, buffer: &[u8]) -> ZResult<()> {
+        let mut writer = zasynclock!(self.writer);
+        framing.write_batch(&mut writer, buffer).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LinkUnicastTrait for LinkUnicastCustom {
+    async fn close(&self) -> ZResult<()> {
+        tracing::trace!("Closing custom link: {}", self);
+        zasynclock!(self.writer).shutdown().await.map_err(|e| {
+            let e = zerror!("custom link shutdown {}: {:?}", self, e);
+            tracing::trace!("{}", e);
+            e.into()
+        })
+    }
+
+    async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
+        if self.framing.is_some() {
+            self.write_all(buffer).await?;
+            return Ok(buffer.len());
+        }
+        let mut writer = zasynclock!(self.writer);
+        let n = writer.write(buffer).await.map_err(|e| {
+            let e = zerror!("Write error on custom link {}: {}", self, e);
+            tracing::trace!("{}", e);
+            e
+        })?;
+        writer.flush().await.map_err(|e| {
+            let e = zerror!("Write error on custom link {}: {}", self, e);
+            tracing::trace!("{}", e);
+            e
+        })?;
+        Ok(n)
+    }
+
+    async fn write_all(&self, buffer: &[u8]) -> ZResult<()> {
+        if let Some(framing) = &self.framing {
+            return self
+                .write_batch(framing.as_ref(), buffer)
+                .await
+                .map_err(|e| {
+                    let e = zerror!("Write error on custom link {}: {}", self, e);
+                    tracing::trace!("{}", e);
+                    e.into()
+                });
+        }
+        let mut writer = zasynclock!(self.writer);
+        writer.write_all(buffer).await.map_err(|e| {
+            let e = zerror!("Write error on custom link {}: {}", self, e);
+            tracing::trace!("{}", e);
+            e
+        })?;
+        writer.flush().await.map_err(|e| {
+            let e = zerror!("Write error on custom link {}: {}", self, e);
+            tracing::trace!("{}", e);
+            e.into()
+        })
+    }
+
+    async fn write_zslices(&self, slices: &[ZSlice]) -> ZResult<()> {
+        // The framings need the whole batch
+        if self.framing.is_some() {
+            let mut buffer = Vec::with_capacity(slices.iter().map(|s| s.len()).sum());
+            for slice in slices {
+                buffer.extend_from_slice(slice);
+            }
+            return self.write_all(&buffer).await;
+        }
+        self.write_vectored_all(slices).await.map_err(|e| {
+            let e = zerror!("Write error on custom link {}: {}", self, e);
+            tracing::trace!("{}", e);
+            e.into()
+        })
+    }
+
+    async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
+        let read = async {
+            if let Some(framing) = &self.framing {
+                return framing
+                    .read_batch(&mut *zasynclock!(self.reader), buffer)
+                    .await;
+            }
+            Ok(zasynclock!(self.reader).read(buffer).await?)
+        };
+        self.with_keepalive(read).await.map_err(|e| {
+            let e = zerror!("Read error on custom link {}: {}", self, e);
+            tracing::trace!("{}", e);
+            e.into()
+        })
+    }
+
+    async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()> {
+        let read = async {
+            zasynclock!(self.reader).read_exact(buffer).await?;
+            Ok(())
+        };
+        self.with_keepalive(read).await.map_err(|e| {
+            let e = zerror!("Read error on custom link {}: {}", self, e);
+            tracing::trace!("{}", e);
+            e.into()
+        })
+    }
+
+    #[inline(always)]
+    fn get_src(&self) -> &Locator {
+        &self.src_locator
+    }
+
+    #[inline(always)]
+    fn get_dst(&self) -> &Locator {
+        &self.dst_locator
+    }
+
+    #[inline(always)]
+    fn get_mtu(&self) -> u16 {
+        *CUSTOM_DEFAULT_MTU
+    }
+
+    #[inline(always)]
+    fn get_interface_names(&self) -> Vec<String> {
+        vec![]
+    }
+
+    #[inline(always)]
+    fn is_reliable(&self) -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn is_streamed(&self) -> bool {
+        // The framed links read and write whole batches
+        self.framing.is_none()
+    }
+}
+
+impl fmt::Display for LinkUnicastCustom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.src_locator)
+    }
+}
+
+impl fmt::Debug for LinkUnicastCustom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Custom")
+            .field("src", &self.src_locator)
+            .field("dst", &self.dst_locator)
+            .finish()
+    }
+}
+
+struct ListenerUnicastCustom {
+    endpoint: EndPoint,
+    token: CancellationToken,
+    handle: JoinHandle<ZResult<()>>,
+}
+
+impl ListenerUnicastCustom {
+    fn new(endpoint: EndPoint, token: CancellationToken, handle: JoinHandle<ZResult<()>>) -> Self {
+        Self {
+            endpoint,
+            token,
+            handle,
+        }
+    }
+
+    async fn stop(&self) {
+        self.token.cancel();
+    }
+}
+
+pub struct LinkManagerUnicastCustom {
+    manager: NewLinkChannelSender,
+    listeners: Arc<AsyncRwLock<HashMap<String, ListenerUnicastCustom>>>,
+}
+
+impl LinkManagerUnicastCustom {
+    pub fn new(manager: NewLinkChannelSender) -> Self {
+        Self {
+            manager,
+            listeners: Arc::new(AsyncRwLock::new(HashMap::new())),
+        }
+    }
+}
+
+#[async_trait]
+impl LinkManagerUnicastTrait for LinkManagerUnicastCustom {
+    async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
+        let name = endpoint.address().as_str().to_string();
+        let config = CustomLinkConfig::new(&endpoint)?;
+        if let Some(stream) = proxied_stream(&endpoint).await? {
+            let link = Arc::new(LinkUnicastCustom::new(
+                stream,
+                endpoint.to_locator(),
+                config,
+            ));
+            return Ok(LinkUnicast(link));
+        }
+        // The registered streams are established by the application: it is up to it to bind them
+        if endpoint.config().get(BIND_SOCKET).is_some() {
+            bail!(
+                "Can not create a new custom link on {}: the source address of a registered stream is chosen by the application",
+                endpoint
+            );
+        }
+        let streams = streams(&name);
+        let stream = match config.connect_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, streams.recv_async())
+                .await
+                .ok()
+                .and_then(Result::ok),
+            None => streams.try_recv().ok(),
+        };
+        drop(streams);
+        prune_streams(&name);
+        let Some(stream) = stream else {
+            bail!(
+                "Can not create a new custom link on {}: no stream has been registered",
+                endpoint
+            );
+        };
+        let link = Arc::new(LinkUnicastCustom::new(
+            stream,
+            endpoint.to_locator(),
+            config,
+        ));
+        Ok(LinkUnicast(link))
+    }
+
+    async fn new_listener(&self, endpoint: EndPoint) -> ZResult<Locator> {
+        let name = endpoint.address().as_str().to_string();
+        let config = CustomLinkConfig::new(&endpoint)?;
+        let mut listeners = zasyncwrite!(self.listeners);
+        if listeners.contains_key(&name) {
+            bail!("A custom listener already exists on {}", endpoint);
+        }
+
+        let token = CancellationToken::new();
+        let c_token = token.clone();
+        let c_manager = self.manager.clone();
+        let locator = endpoint.to_locator();
+        let c_locator = locator.clone();
+        let c_listeners = self.listeners.clone();
+        let c_name = name.clone();
+        let task = async move {
+            // Wait for the accept loop to terminate
+            let res = accept_task(&c_name, c_locator, config, c_token, c_manager).await;
+            zasyncwrite!(c_listeners).remove(&c_name);
+            res
+        };
+        let handle = zenoh_runtime::ZRuntime::Acceptor.spawn(task);
+
+        let listener = ListenerUnicastCustom::new(endpoint, token, handle);
+        // Update the list of active listeners on the manager
+        listeners.insert(name, listener);
+        Ok(locator)
+    }
+
+    async fn del_listener(&self, endpoint: &EndPoint) -> ZResult<()> {
+        let name = endpoint.address().as_str();
+
+        let listener = zasyncwrite!(self.listeners).remove(name).ok_or_else(|| {
+            zerror!(
+                "Can not delete the listener because it has not been found: {}",
+                endpoint
+            )
+        })?;
+
+        // Send the stop signal
+        listener.stop().await;
+        listener.handle.await?
+    }
+
+    async fn get_listeners(&self) -> Vec<EndPoint> {
+        zasyncread!(self.listeners)
+            .values()
+            .map(|x| x.endpoint.clone())
+            .collect()
+    }
+
+    async fn get_locators(&self) -> Vec<Locator> {
+        zasyncread!(self.listeners)
+            .values()
+            .map(|x| x.endpoint.to_locator())
+            .collect()
+    }
+}
+
+async fn accept_task(
+    name: &str,
+    locator: Locator,
+    config: CustomLinkConfig,
+    token: CancellationToken,
+    manager: NewLinkChannelSender,
+) -> ZResult<()> {
+    let streams = streams(name);
+
+    tracing::trace!("Ready to accept custom streams on: {}", locator);
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            res = streams.recv_async() => {
+                let Ok(stream) = res else {
+                    break;
+                };
+                tracing::debug!("Accepted custom stream on {}", locator);
+                // Create the new link object
+                let link = Arc::new(LinkUnicastCustom::new(
+                    stream,
+                    locator.clone(),
+                    config.clone(),
+                ));
+
+                // Communicate the new link to the initial transport manager
+                if let Err(e) = manager.send_async(LinkUnicast(link)).await {
+                    tracing::error!("{}-{}: {}", file!(), line!(), e)
+                }
+            }
+        };
+    }
+    drop(streams);
+    prune_streams(name);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{register_framing, register_stream};
+    use zenoh_core::zlock;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn custom_stream_link() {
+        let (a, b) = tokio::io::duplex(1024);
+        let (ra, wa) = tokio::io::split(a);
+        let (rb, wb) = tokio::io::split(b);
+
+        // The accepting side
+        let (tx, rx) = flume::unbounded();
+        let listener = LinkManagerUnicastCustom::new(tx);
+        let endpoint: EndPoint = "custom/test_listen".parse().unwrap();
+        listener.new_listener(endpoint.clone()).await.unwrap();
+        register_stream("test_listen", rb, wb);
+        let accepted = rx.recv_async().await.unwrap();
+
+        // The connecting side
+        let (tx, _rx) = flume::unbounded();
+        let connector = LinkManagerUnicastCustom::new(tx);
+        let connect: EndPoint = "custom/test_connect".parse().unwrap();
+        assert!(connector.new_link(connect.clone()).await.is_err());
+        register_stream("test_connect", ra, wa);
+        let link = connector.new_link(connect).await.unwrap();
+
+        link.write_all(b"zenoh").await.unwrap();
+        let mut buffer = [0u8; 5];
+        accepted.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"zenoh");
+
+        listener.del_listener(&endpoint).await.unwrap();
+
+        // The registry is pruned once the streams are used and the listener is gone
+        for name in ["test_listen", "test_connect"] {
+            assert!(!zlock!(crate::CUSTOM_STREAMS).contains_key(name), "{name}");
+        }
+
+        // The streams never used can be unregistered
+        let (a, _b) = tokio::io::duplex(16);
+        let (ra, wa) = tokio::io::split(a);
+        register_stream("test_unused", ra, wa);
+        assert_eq!(crate::unregister_streams("test_unused"), 1);
+        assert!(!zlock!(crate::CUSTOM_STREAMS).contains_key("test_unused"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn custom_write_zslices() {
+        let (a, b) = tokio::io::duplex(16);
+        let (ra, wa) = tokio::io::split(a);
+        let (rb, wb) = tokio::io::split(b);
+        let (tx, _rx) = flume::unbounded();
+        let manager = LinkManagerUnicastCustom::new(tx);
+        register_stream("test_zslices_a", ra, wa);
+        register_stream("test_zslices_b", rb, wb);
+        let a = manager
+            .new_link("custom/test_zslices_a".parse().unwrap())
+            .await
+            .unwrap();
+        let b = manager
+            .new_link("custom/test_zslices_b".parse().unwrap())
+            .await
+            .unwrap();
+
+        // The slices are larger than the duplex buffer: they are written in several writes
+        let slices: Vec<ZSlice> = [vec![], (0..40).collect(), vec![], (40..100).collect()]
+            .into_iter()
+            .map(ZSlice::from)
+            .collect();
+        let write = tokio::spawn(async move { a.write_zslices(&slices).await });
+        let mut buffer = [0u8; 100];
+        b.read_exact(&mut buffer).await.unwrap();
+        write.await.unwrap().unwrap();
+        assert_eq!(buffer.to_vec(), (0..100).collect::<Vec<u8>>());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn custom_framed_link() {
+        let (a, b) = tokio::io::duplex(1024);
+        let (ra, wa) = tokio::io::split(a);
+        let (rb, wb) = tokio::io::split(b);
+        let (tx, _rx) = flume::unbounded();
+        let manager = LinkManagerUnicastCustom::new(tx);
+
+        let bad: EndPoint = "custom/test_framed#frame_size=2".parse().unwrap();
+        assert!(manager.new_link(bad).await.is_err());
+
+        register_stream("test_framed_a", ra, wa);
+        register_stream("test_framed_b", rb, wb);
+        let a = manager
+            .new_link("custom/test_framed_a#frame_size=16".parse().unwrap())
+            .await
+            .unwrap();
+        let b = manager
+            .new_link("custom/test_framed_b#frame_size=16".parse().unwrap())
+            .await
+            .unwrap();
+        assert!(!a.is_streamed());
+
+        // A batch larger than the frame size is reassembled in a single read
+        let batch: Vec<u8> = (0..100).collect();
+        a.write_all(&batch).await.unwrap();
+        let mut buffer = [0u8; 128];
+        let n = b.read(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..n], batch.as_slice());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn custom_framings() {
+        let (tx, _rx) = flume::unbounded();
+        let manager = LinkManagerUnicastCustom::new(tx);
+
+        for bad in [
+            "custom/test_framing#framing=fixed",
+            "custom/test_framing#framing=cobs;frame_size=16",
+            "custom/test_framing#framing=unknown",
+        ] {
+            assert!(manager.new_link(bad.parse().unwrap()).await.is_err());
+        }
+
+        register_framing("test_length", LengthPrefixFraming);
+        let batch: Vec<u8> = (0..100u8).map(|i| i % 3).collect();
+        for framing in [
+            "framing=length",
+            "framing=cobs",
+            "framing=fixed;frame_size=16",
+            "framing=test_length",
+        ] {
+            let (a, b) = tokio::io::duplex(1024);
+            let (ra, wa) = tokio::io::split(a);
+            let (rb, wb) = tokio::io::split(b);
+            register_stream("test_framing_a", ra, wa);
+            register_stream("test_framing_b", rb, wb);
+            let a = manager
+                .new_link(format!("custom/test_framing_a#{framing}").parse().unwrap())
+                .await
+                .unwrap();
+            let b = manager
+                .new_link(format!("custom/test_framing_b#{framing}").parse().unwrap())
+                .await
+                .unwrap();
+            assert!(!a.is_streamed());
+
+            // Each batch is read whole
+            a.write_all(&batch).await.unwrap();
+            a.write_all(&batch[..10]).await.unwrap();
+            let mut buffer = [0u8; 128];
+            let n = b.read(&mut buffer).await.unwrap();
+            assert_eq!(&buffer[..n], batch.as_slice(), "{framing}");
+            let n = b.read(&mut buffer).await.unwrap();
+            assert_eq!(&buffer[..n], &batch[..10], "{framing}");
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn custom_link_config() {
+        let (tx, _rx) = flume::unbounded();
+        let manager = LinkManagerUnicastCustom::new(tx);
+
+        let bad: EndPoint = "custom/test_config#so_rcvbuf=none".parse().unwrap();
+        assert!(manager.new_link(bad).await.is_err());
+
+        // The link waits for the stream to be registered
+        let (a, _b) = tokio::io::duplex(1024);
+        let (ra, wa) = tokio::io::split(a);
+        let register = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            register_stream("test_config", ra, wa);
+        });
+        let endpoint: EndPoint =
+            "custom/test_config#connect_timeout=5000;keepalive=100;so_rcvbuf=4096;so_sndbuf=4096"
+                .parse()
+                .unwrap();
+        let link = manager.new_link(endpoint).await.unwrap();
+        register.await.unwrap();
+
+        // The read fails as nothing is received
+        let mut buffer = [0u8; 8];
+        assert!(link.read(&mut buffer).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn custom_bind_socket() {
+        use tokio::io::AsyncBufReadExt;
+
+        let (tx, _rx) = flume::unbounded();
+        let manager = LinkManagerUnicastCustom::new(tx);
+
+        // The source address of a registered stream can not be chosen
+        let endpoint: EndPoint = "custom/test_bind#bind=127.0.0.1:0".parse().unwrap();
+        assert!(manager.new_link(endpoint).await.is_err());
+
+        // The connection to the proxy is bound to the source address
+        let proxy = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_addr = proxy.local_addr().unwrap();
+        let src_addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, peer_addr) = proxy.accept().await.unwrap();
+            let mut reader = tokio::io::BufReader::new(stream);
+            let mut request = String::new();
+            while !request.ends_with("\r\n\r\n") {
+                reader.read_line(&mut request).await.unwrap();
+            }
+            let mut stream = reader.into_inner();
+            stream
+                .write_all(b"HTTP/1.1 200 Connection established\r\n\r\nzenoh")
+                .await
+                .unwrap();
+            (peer_addr, stream)
+        });
+        let endpoint: EndPoint = format!(
+            "custom/test_bind#proxy=http://{proxy_addr};proxy_destination=10.0.0.1:7447;bind={src_addr}"
+        )
+        .parse()
+        .unwrap();
+        let link = manager.new_link(endpoint).await.unwrap();
+        let (peer_addr, _stream) = server.await.unwrap();
+        assert_eq!(peer_addr, src_addr);
+        let mut buffer = [0u8; 5];
+        link.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"zenoh");
+    }
+}
@@ -0,0 +1,234 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use crate::{ALPN_QUIC_DATAGRAM, QUIC_DEFAULT_MTU, QUIC_LOCATOR_PREFIX};
+use async_trait::async_trait;
+use std::fmt;
+use std::net::SocketAddr;
+use std::ops::RangeInclusive;
+use zenoh_link_commons::{get_ip_interface_names, LinkUnicastTrait};
+use zenoh_protocol::core::{endpoint::Config, Locator, Priority};
+use zenoh_result::{bail, zerror, ZResult};
+
+/// Parses the priorities of the messages sent on a datagram link, see
+/// [`QUIC_DATAGRAM_PRIORITIES`](crate::config::QUIC_DATAGRAM_PRIORITIES).
+pub(crate) fn datagram_priorities(config: &Config) -> ZResult<RangeInclusive<u8>> {
+    let Some(priorities) = config.get(crate::config::QUIC_DATAGRAM_PRIORITIES) else {
+        return Ok(Priority::MAX as u8..=Priority::MIN as u8);
+    };
+    let (min, max) = priorities
+        .split_once('-')
+        .unwrap_or((priorities, priorities));
+    let parse = |p: &str| {
+        p.trim()
+            .parse::<u8>()
+            .ok()
+            .and_then(|p| Priority::try_from(p).ok())
+            .map(|p| p as u8)
+            .ok_or_else(|| zerror!("Invalid priority {} in {}", p, priorities))
+    };
+    let (min, max) = (parse(min)?, parse(max)?);
+    if min > max {
+        bail!("Invalid priority range {}", priorities);
+    }
+    Ok(min..=max)
+}
+
+/// Returns true if the connection has been established to carry a datagram link.
+pub(crate) fn is_datagram_connection(connection: &quinn::Connection) -> bool {
+    connection
+        .handshake_data()
+        .and_then(|data| data.downcast::<quinn::crypto::rustls::HandshakeData>().ok())
+        .and_then(|data| data.protocol)
+        .is_some_and(|protocol| ALPN_QUIC_DATAGRAM.contains(&protocol.as_slice()))
+}
+
+/// A best-effort link sending each batch in a QUIC unreliable datagram, free of the
+/// head-of-line blocking of the QUIC streams.
+pub struct LinkUnicastQuicDatagram {
+    connection: quinn::Connection,
+    src_addr: SocketAddr,
+    src_locator: Locator,
+    dst_locator: Locator,
+    mtu: u16,
+    priorities: RangeInclusive<u8>,
+}
+
+impl LinkUnicastQuicDatagram {
+    pub(crate) fn new(
+        connection: quinn::Connection,
+        src_addr: SocketAddr,
+        dst_locator: Locator,
+        priorities: RangeInclusive<u8>,
+    ) -> ZResult<LinkUnicastQuicDatagram> {
+        // The datagrams can not be fragmented: the batches must fit in a single one
+        let Some(max_size) = connection.max_datagram_size() else {
+            bail!(
+                "Can not create a QUIC datagram link to {}: datagrams are not supported by the peer",
+                connection.remote_address()
+            );
+        };
+        let mtu = max_size.min(*QUIC_DEFAULT_MTU as usize) as u16;
+        Ok(LinkUnicastQuicDatagram {
+            connection,
+            src_addr,
+            src_locator: Locator::new(QUIC_LOCATOR_PREFIX, src_addr.to_string(), "")?,
+            dst_locator,
+            mtu,
+            priorities,
+        })
+    }
+}
+
+#[async_trait]
+impl LinkUnicastTrait for LinkUnicastQuicDatagram {
+    async fn close(&self) -> ZResult<()> {
+        tracing::trace!("Closing QUIC datagram link: {}", self);
+        self.connection.close(quinn::VarInt::from_u32(0), &[0]);
+        Ok(())
+    }
+
+    async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
+        self.connection
+            .send_datagram(buffer.to_vec().into())
+            .map_err(|e| {
+                let e = zerror!("Write error on QUIC datagram link {}: {}", self, e);
+                tracing::trace!("{}", &e);
+                e
+            })?;
+        Ok(buffer.len())
+    }
+
+    async fn write_all(&self, buffer: &[u8]) -> ZResult<()> {
+        self.write(buffer).await?;
+        Ok(())
+    }
+
+    async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
+        let datagram = self.connection.read_datagram().await.map_err(|e| {
+            let e = zerror!("Read error on QUIC datagram link {}: {}", self, e);
+            tracing::trace!("{}", &e);
+            e
+        })?;
+        if datagram.len() > buffer.len() {
+            bail!(
+                "Read error on QUIC datagram link {}: datagram of {} bytes larger than the {} bytes buffer",
+                self,
+                datagram.len(),
+                buffer.len()
+            );
+        }
+        buffer[..datagram.len()].copy_from_slice(&datagram);
+        Ok(datagram.len())
+    }
+
+    async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()> {
+        let n = self.read(buffer).await?;
+        if n != buffer.len() {
+            bail!(
+                "Read error on QUIC datagram link {}: received {} bytes instead of {}",
+                self,
+                n,
+                buffer.len()
+            );
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn get_src(&self) -> &Locator {
+        &self.src_locator
+    }
+
+    #[inline(always)]
+    fn get_dst(&self) -> &Locator {
+        &self.dst_locator
+    }
+
+    #[inline(always)]
+    fn get_mtu(&self) -> u16 {
+        self.mtu
+    }
+
+    #[inline(always)]
+    fn get_interface_names(&self) -> Vec<String> {
+        get_ip_interface_names(&self.src_addr)
+    }
+
+    #[inline(always)]
+    fn supports_priority(&self, priority: Priority) -> bool {
+        self.priorities.contains(&(priority as u8))
+    }
+
+    #[inline(always)]
+    fn is_reliable(&self) -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn is_streamed(&self) -> bool {
+        false
+    }
+}
+
+impl Drop for LinkUnicastQuicDatagram {
+    fn drop(&mut self) {
+        self.connection.close(quinn::VarInt::from_u32(0), &[0]);
+    }
+}
+
+impl fmt::Display for LinkUnicastQuicDatagram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} => {}",
+            self.src_addr,
+            self.connection.remote_address()
+        )?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for LinkUnicastQuicDatagram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QuicDatagram")
+            .field("src", &self.src_addr)
+            .field("dst", &self.connection.remote_address())
+            .field("priorities", &self.priorities)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn priorities(s: &str) -> ZResult<RangeInclusive<u8>> {
+        let endpoint: zenoh_protocol::core::EndPoint =
+            format!("quic/127.0.0.1:7447#{s}").parse().unwrap();
+        datagram_priorities(&endpoint.config())
+    }
+
+    #[test]
+    fn quic_datagram_priorities() {
+        // All the priorities by default
+        assert_eq!(priorities("datagram=true").unwrap(), 0..=7);
+
+        assert_eq!(priorities("datagram_priorities=5-7").unwrap(), 5..=7);
+        assert_eq!(priorities("datagram_priorities=6").unwrap(), 6..=6);
+
+        assert!(priorities("datagram_priorities=7-5").is_err());
+        assert!(priorities("datagram_priorities=5-8").is_err());
+        assert!(priorities("datagram_priorities=low").is_err());
+    }
+}
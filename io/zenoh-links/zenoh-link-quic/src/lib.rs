@@ -24,14 +24,18 @@ use zenoh_link_commons::LocatorInspector;
 use zenoh_protocol::core::Locator;
 use zenoh_result::ZResult;
 
+mod datagram;
 mod unicast;
 mod utils;
 mod verify;
+pub use datagram::*;
 pub use unicast::*;
 pub use utils::TlsConfigurator as QuicConfigurator;
 
 // Default ALPN protocol
 pub const ALPN_QUIC_HTTP: &[&[u8]] = &[b"hq-29"];
+// ALPN protocol of the connections carrying a datagram link
+pub const ALPN_QUIC_DATAGRAM: &[&[u8]] = &[b"zenoh-datagram"];
 
 // Default MTU (QUIC PDU) in bytes.
 // NOTE: Since QUIC is a byte-stream oriented transport, theoretically it has
@@ -66,9 +70,15 @@ zconfigurable! {
     static ref QUIC_LINGER_TIMEOUT: i32 = 10;
     // Amount of time in microseconds to throttle the accept loop upon an error.
     // Default set to 100 ms.
+    #[mutable]
     static ref QUIC_ACCEPT_THROTTLE_TIME: u64 = 100_000;
 }
 
+/// Registers the values of this crate that can be modified at runtime.
+pub fn register_configurables() {
+    zenoh_core::configurable::register(&QUIC_ACCEPT_THROTTLE_TIME);
+}
+
 pub mod config {
     pub const TLS_ROOT_CA_CERTIFICATE_FILE: &str = "root_ca_certificate_file";
     pub const TLS_ROOT_CA_CERTIFICATE_RAW: &str = "root_ca_certificate_raw";
@@ -94,4 +104,15 @@ pub mod config {
 
     pub const TLS_SERVER_NAME_VERIFICATION: &str = "server_name_verification";
     pub const TLS_SERVER_NAME_VERIFICATION_DEFAULT: &str = "true";
+
+    /// Open a best-effort link sending the batches in QUIC unreliable datagrams instead of a stream,
+    /// e.g. `quic/192.168.1.1:7447#datagram=true`. Together with a stream link on the same endpoint
+    /// (multilink), the best-effort messages avoid the head-of-line blocking of the reliable ones.
+    pub const QUIC_DATAGRAM: &str = "datagram";
+    pub const QUIC_DATAGRAM_DEFAULT: &str = "false";
+    /// The priorities of the best-effort messages sent on a datagram link, as a single priority
+    /// or an inclusive range, e.g. `quic/192.168.1.1:7447#datagram=true;datagram_priorities=5-7`
+    /// (default: all). The best-effort messages of the other priorities are sent on a stream link.
+    /// On a listener, it applies to the datagram links it accepts.
+    pub const QUIC_DATAGRAM_PRIORITIES: &str = "datagram_priorities";
 }
@@ -14,13 +14,16 @@
 
 use crate::{
     config::*,
+    datagram::{datagram_priorities, is_datagram_connection, LinkUnicastQuicDatagram},
     utils::{get_quic_addr, TlsClientConfig, TlsServerConfig},
-    ALPN_QUIC_HTTP, QUIC_ACCEPT_THROTTLE_TIME, QUIC_DEFAULT_MTU, QUIC_LOCATOR_PREFIX,
+    ALPN_QUIC_DATAGRAM, ALPN_QUIC_HTTP, QUIC_ACCEPT_THROTTLE_TIME, QUIC_DEFAULT_MTU,
+    QUIC_LOCATOR_PREFIX,
 };
 use async_trait::async_trait;
 use std::fmt;
 use std::net::IpAddr;
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex as AsyncMutex;
@@ -215,13 +218,25 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
             tracing::warn!("Skipping name verification of servers");
         }
 
+        let datagram: bool = epconf
+            .get(QUIC_DATAGRAM)
+            .unwrap_or(QUIC_DATAGRAM_DEFAULT)
+            .parse()
+            .map_err(|_| zerror!("Invalid {} option for {}", QUIC_DATAGRAM, endpoint))?;
+        let priorities = datagram_priorities(&epconf)?;
+
         // Initialize the QUIC connection
         let mut client_crypto = TlsClientConfig::new(&epconf)
             .await
             .map_err(|e| zerror!("Cannot create a new QUIC client on {addr}: {e}"))?;
 
-        client_crypto.client_config.alpn_protocols =
-            ALPN_QUIC_HTTP.iter().map(|&x| x.into()).collect();
+        // The listener tells the datagram links from the stream links by their ALPN protocol
+        let alpn = if datagram {
+            ALPN_QUIC_DATAGRAM
+        } else {
+            ALPN_QUIC_HTTP
+        };
+        client_crypto.client_config.alpn_protocols = alpn.iter().map(|&x| x.into()).collect();
 
         let ip_addr: IpAddr = if addr.is_ipv4() {
             Ipv4Addr::UNSPECIFIED.into()
@@ -244,6 +259,16 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
             .await
             .map_err(|e| zerror!("Can not create a new QUIC link bound to {}: {}", host, e))?;
 
+        if datagram {
+            let link = Arc::new(LinkUnicastQuicDatagram::new(
+                quic_conn,
+                src_addr,
+                endpoint.into(),
+                priorities,
+            )?);
+            return Ok(LinkUnicast(link));
+        }
+
         let (send, recv) = quic_conn
             .open_bi()
             .await
@@ -269,13 +294,17 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
         };
 
         let addr = get_quic_addr(&epaddr).await?;
+        let priorities = datagram_priorities(&epconf)?;
 
         // Server config
         let mut server_crypto = TlsServerConfig::new(&epconf)
             .await
             .map_err(|e| zerror!("Cannot create a new QUIC listener on {addr}: {e}"))?;
-        server_crypto.server_config.alpn_protocols =
-            ALPN_QUIC_HTTP.iter().map(|&x| x.into()).collect();
+        server_crypto.server_config.alpn_protocols = ALPN_QUIC_HTTP
+            .iter()
+            .chain(ALPN_QUIC_DATAGRAM.iter())
+            .map(|&x| x.into())
+            .collect();
         let mut server_config =
             quinn::ServerConfig::with_crypto(Arc::new(server_crypto.server_config));
 
@@ -310,7 +339,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
 
         let c_manager = self.manager.clone();
 
-        let task = async move { accept_task(quic_endpoint, c_token, c_manager).await };
+        let task = async move { accept_task(quic_endpoint, priorities, c_token, c_manager).await };
 
         // Initialize the QuicAcceptor
         let locator = endpoint.to_locator();
@@ -339,6 +368,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
 
 async fn accept_task(
     endpoint: quinn::Endpoint,
+    priorities: RangeInclusive<u8>,
     token: CancellationToken,
     manager: NewLinkChannelSender,
 ) -> ZResult<()> {
@@ -369,6 +399,27 @@ async fn accept_task(
 
             res = accept(endpoint.accept()) => {
                 match res {
+                    Ok(quic_conn) if is_datagram_connection(&quic_conn) => {
+                        let dst_addr = quic_conn.remote_address();
+                        tracing::debug!("Accepted QUIC datagram connection on {:?}: {:?}", src_addr, dst_addr);
+                        let link = match LinkUnicastQuicDatagram::new(
+                            quic_conn,
+                            src_addr,
+                            Locator::new(QUIC_LOCATOR_PREFIX, dst_addr.to_string(), "")?,
+                            priorities.clone(),
+                        ) {
+                            Ok(link) => Arc::new(link),
+                            Err(e) => {
+                                tracing::warn!("{}", e);
+                                continue;
+                            }
+                        };
+
+                        // Communicate the new link to the initial transport manager
+                        if let Err(e) = manager.send_async(LinkUnicast(link)).await {
+                            tracing::error!("{}-{}: {}", file!(), line!(), e)
+                        }
+                    }
                     Ok(quic_conn) => {
                         // Get the bideractional streams. Note that we don't allow unidirectional streams.
                         let (send, recv) = match quic_conn.accept_bi().await {
@@ -404,7 +455,7 @@ async fn accept_task(
                         //       Linux systems this limit can be changed by using the "ulimit" command line
                         //       tool. In case of systemd-based systems, this can be changed by using the
                         //       "sysctl" command line tool.
-                        tokio::time::sleep(Duration::from_micros(*QUIC_ACCEPT_THROTTLE_TIME)).await;
+                        tokio::time::sleep(Duration::from_micros(QUIC_ACCEPT_THROTTLE_TIME.get())).await;
                     }
                 }
             }
@@ -25,7 +25,7 @@ pub use unicast::*;
 use zenoh_core::zconfigurable;
 use zenoh_link_commons::LocatorInspector;
 use zenoh_protocol::core::{endpoint::Address, EndPoint, Locator};
-use zenoh_result::ZResult;
+use zenoh_result::{bail, ZResult};
 
 // Maximum MTU (Serial PDU) in bytes.
 const SERIAL_MAX_MTU: u16 = z_serial::MAX_MTU as u16;
@@ -34,6 +34,10 @@ const DEFAULT_BAUDRATE: u32 = 9_600;
 
 const DEFAULT_EXCLUSIVE: bool = true;
 
+// The batches are framed with COBS and protected by a CRC by z-serial, which
+// relies on the full bytes of the port: only 8 data bits are supported.
+const SUPPORTED_DATABITS: u8 = 8;
+
 pub const SERIAL_LOCATOR_PREFIX: &str = "serial";
 
 const SERIAL_MTU_LIMIT: u16 = SERIAL_MAX_MTU;
@@ -43,9 +47,15 @@ zconfigurable! {
     static ref SERIAL_DEFAULT_MTU: u16 = SERIAL_MTU_LIMIT;
     // Amount of time in microseconds to throttle the accept loop upon an error.
     // Default set to 100 ms.
+    #[mutable]
     static ref SERIAL_ACCEPT_THROTTLE_TIME: u64 = 100_000;
 }
 
+/// Registers the values of this crate that can be modified at runtime.
+pub fn register_configurables() {
+    zenoh_core::configurable::register(&SERIAL_ACCEPT_THROTTLE_TIME);
+}
+
 #[derive(Default, Clone, Copy)]
 pub struct SerialLocatorInspector;
 #[async_trait]
@@ -75,6 +85,21 @@ pub fn get_exclusive(endpoint: &EndPoint) -> bool {
     }
 }
 
+pub fn check_data_bits(endpoint: &EndPoint) -> ZResult<()> {
+    if let Some(databits) = endpoint.config().get(config::PORT_DATA_BITS_RAW) {
+        match u8::from_str(databits) {
+            Ok(SUPPORTED_DATABITS) => {}
+            _ => bail!(
+                "Invalid {} for Serial endpoint {}: only {} is supported",
+                config::PORT_DATA_BITS_RAW,
+                endpoint,
+                SUPPORTED_DATABITS
+            ),
+        }
+    }
+    Ok(())
+}
+
 pub fn get_unix_path_as_string(address: Address<'_>) -> String {
     address.as_str().to_owned()
 }
@@ -82,4 +107,5 @@ pub fn get_unix_path_as_string(address: Address<'_>) -> String {
 pub mod config {
     pub const PORT_BAUD_RATE_RAW: &str = "baudrate";
     pub const PORT_EXCLUSIVE_RAW: &str = "exclusive";
+    pub const PORT_DATA_BITS_RAW: &str = "databits";
 }
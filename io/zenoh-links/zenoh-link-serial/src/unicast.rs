@@ -37,8 +37,8 @@ use z_serial::ZSerial;
 use crate::get_exclusive;
 
 use super::{
-    get_baud_rate, get_unix_path_as_string, SERIAL_ACCEPT_THROTTLE_TIME, SERIAL_DEFAULT_MTU,
-    SERIAL_LOCATOR_PREFIX,
+    check_data_bits, get_baud_rate, get_unix_path_as_string, SERIAL_ACCEPT_THROTTLE_TIME,
+    SERIAL_DEFAULT_MTU, SERIAL_LOCATOR_PREFIX,
 };
 
 struct LinkUnicastSerial {
@@ -272,6 +272,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastSerial {
         let path = get_unix_path_as_string(endpoint.address());
         let baud_rate = get_baud_rate(&endpoint);
         let exclusive = get_exclusive(&endpoint);
+        check_data_bits(&endpoint)?;
         tracing::trace!("Opening Serial Link on device {path:?}, with baudrate {baud_rate} and exclusive set as {exclusive}");
         let port = ZSerial::new(path.clone(), baud_rate, exclusive).map_err(|e| {
             let e = zerror!(
@@ -298,6 +299,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastSerial {
         let path = get_unix_path_as_string(endpoint.address());
         let baud_rate = get_baud_rate(&endpoint);
         let exclusive = get_exclusive(&endpoint);
+        check_data_bits(&endpoint)?;
         tracing::trace!("Creating Serial listener on device {path:?}, with baudrate {baud_rate} and exclusive set as {exclusive}");
         let port = ZSerial::new(path.clone(), baud_rate, exclusive).map_err(|e| {
             let e = zerror!(
@@ -392,7 +394,7 @@ async fn accept_read_task(
     ) -> ZResult<Arc<LinkUnicastSerial>> {
         while !is_connected.load(Ordering::Acquire) && !link.is_ready() {
             // Waiting to be ready, if not sleep some time.
-            tokio::time::sleep(Duration::from_micros(*SERIAL_ACCEPT_THROTTLE_TIME)).await;
+            tokio::time::sleep(Duration::from_micros(SERIAL_ACCEPT_THROTTLE_TIME.get())).await;
         }
 
         tracing::trace!("Creating serial link from {:?}", src_path);
@@ -421,7 +423,7 @@ async fn accept_read_task(
                     }
                     Err(e) =>  {
                         tracing::warn!("{}. Hint: Is the serial cable connected?", e);
-                        tokio::time::sleep(Duration::from_micros(*SERIAL_ACCEPT_THROTTLE_TIME)).await;
+                        tokio::time::sleep(Duration::from_micros(SERIAL_ACCEPT_THROTTLE_TIME.get())).await;
                         continue;
 
                     }
@@ -19,10 +19,16 @@
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
 use async_trait::async_trait;
 use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
+use zenoh_config::Config;
 use zenoh_core::zconfigurable;
-use zenoh_link_commons::LocatorInspector;
-use zenoh_protocol::core::{endpoint::Address, Locator};
-use zenoh_result::{zerror, ZResult};
+use zenoh_link_commons::{proxy::ProxyConfig, ConfigurationInspector, LocatorInspector};
+use zenoh_protocol::core::{
+    endpoint::{Address, Config as EndPointConfig},
+    Locator, Parameters,
+};
+use zenoh_result::{bail, zerror, ZResult};
 
 mod unicast;
 pub use unicast::*;
@@ -50,6 +56,32 @@ impl LocatorInspector for TcpLocatorInspector {
     }
 }
 
+#[derive(Default, Clone, Copy, Debug)]
+pub struct TcpConfigurator;
+
+impl ConfigurationInspector<Config> for TcpConfigurator {
+    fn inspect_config(&self, config: &Config) -> ZResult<String> {
+        let c = config.transport().link().tcp();
+        let mut properties: Vec<(&str, String)> = [
+            (config::TCP_NODELAY, c.nodelay().map(|v| v.to_string())),
+            (
+                config::TCP_USER_TIMEOUT,
+                c.user_timeout_ms().map(|v| v.to_string()),
+            ),
+            (config::TCP_MSS, c.mss().map(|v| v.to_string())),
+        ]
+        .into_iter()
+        .filter_map(|(k, v)| v.map(|v| (k, v)))
+        .collect();
+        properties.extend(ProxyConfig::properties(config));
+
+        let mut s = String::new();
+        Parameters::extend(properties.iter().map(|(k, v)| (*k, v.as_str())), &mut s);
+
+        Ok(s)
+    }
+}
+
 zconfigurable! {
     // Default MTU (TCP PDU) in bytes.
     static ref TCP_DEFAULT_MTU: u16 = TCP_MAX_MTU;
@@ -60,9 +92,47 @@ zconfigurable! {
     static ref TCP_LINGER_TIMEOUT: i32 = 10;
     // Amount of time in microseconds to throttle the accept loop upon an error.
     // Default set to 100 ms.
+    #[mutable]
     static ref TCP_ACCEPT_THROTTLE_TIME: u64 = 100_000;
 }
 
+/// Registers the values of this crate that can be modified at runtime.
+pub fn register_configurables() {
+    zenoh_core::configurable::register(&TCP_ACCEPT_THROTTLE_TIME);
+}
+
+/// The options of the sockets of the TCP links, see [`config`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TcpSocketConfig {
+    pub(crate) nodelay: bool,
+    pub(crate) user_timeout: Option<Duration>,
+    pub(crate) mss: Option<u32>,
+}
+
+impl TcpSocketConfig {
+    pub(crate) fn new(config: &EndPointConfig<'_>) -> ZResult<Self> {
+        fn parse<T: FromStr>(config: &EndPointConfig<'_>, key: &str) -> ZResult<Option<T>> {
+            config
+                .get(key)
+                .map(|v| {
+                    v.parse::<T>()
+                        .map_err(|_| zerror!("Invalid TCP {}: {}", key, v).into())
+                })
+                .transpose()
+        }
+
+        let mss = parse::<u32>(config, config::TCP_MSS)?;
+        if mss == Some(0) {
+            bail!("Invalid TCP {}: 0", config::TCP_MSS);
+        }
+        Ok(TcpSocketConfig {
+            nodelay: parse(config, config::TCP_NODELAY)?.unwrap_or(true),
+            user_timeout: parse(config, config::TCP_USER_TIMEOUT)?.map(Duration::from_millis),
+            mss,
+        })
+    }
+}
+
 pub async fn get_tcp_addrs(address: Address<'_>) -> ZResult<impl Iterator<Item = SocketAddr>> {
     let iter = tokio::net::lookup_host(address.as_str().to_string())
         .await
@@ -70,3 +140,43 @@ pub async fn get_tcp_addrs(address: Address<'_>) -> ZResult<impl Iterator<Item =
         .filter(|x| !x.ip().is_multicast());
     Ok(iter)
 }
+
+pub mod config {
+    /// Whether to disable Nagle's algorithm (default: true), e.g. `tcp/10.0.0.1:7447#nodelay=false`.
+    pub const TCP_NODELAY: &str = "nodelay";
+    /// The time in milliseconds the transmitted data may remain unacknowledged before the
+    /// connection is closed, e.g. `tcp/10.0.0.1:7447#user_timeout_ms=5000`. Only supported on Linux.
+    pub const TCP_USER_TIMEOUT: &str = "user_timeout_ms";
+    /// The maximum segment size in bytes, e.g. `tcp/10.0.0.1:7447#mss=1200`. Only supported on Unix.
+    pub const TCP_MSS: &str = "mss";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zenoh_protocol::core::EndPoint;
+
+    fn socket_config(endpoint: &str) -> ZResult<TcpSocketConfig> {
+        let endpoint = EndPoint::from_str(endpoint).unwrap();
+        TcpSocketConfig::new(&endpoint.config())
+    }
+
+    #[test]
+    fn tcp_socket_config() {
+        let c = socket_config("tcp/127.0.0.1:7447").unwrap();
+        assert!(c.nodelay);
+        assert_eq!(c.user_timeout, None);
+        assert_eq!(c.mss, None);
+
+        let c = socket_config("tcp/127.0.0.1:7447#nodelay=false;user_timeout_ms=5000;mss=1200")
+            .unwrap();
+        assert!(!c.nodelay);
+        assert_eq!(c.user_timeout, Some(Duration::from_millis(5000)));
+        assert_eq!(c.mss, Some(1200));
+
+        assert!(socket_config("tcp/127.0.0.1:7447#nodelay=no").is_err());
+        assert!(socket_config("tcp/127.0.0.1:7447#user_timeout_ms=-1").is_err());
+        assert!(socket_config("tcp/127.0.0.1:7447#mss=0").is_err());
+        assert!(socket_config("tcp/127.0.0.1:7447#mss=abc").is_err());
+    }
+}
@@ -21,14 +21,15 @@ use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio_util::sync::CancellationToken;
 use zenoh_link_commons::{
-    get_ip_interface_names, LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait,
-    ListenersUnicastIP, NewLinkChannelSender, BIND_INTERFACE,
+    get_bind_interface, get_bind_socket, get_ip_interface_names, proxy::ProxyConfig,
+    LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait, ListenersUnicastIP,
+    NewLinkChannelSender, BIND_INTERFACE,
 };
 use zenoh_protocol::core::{EndPoint, Locator};
 use zenoh_result::{bail, zerror, Error as ZError, ZResult};
 
 use super::{
-    get_tcp_addrs, TCP_ACCEPT_THROTTLE_TIME, TCP_DEFAULT_MTU, TCP_LINGER_TIMEOUT,
+    get_tcp_addrs, TcpSocketConfig, TCP_ACCEPT_THROTTLE_TIME, TCP_DEFAULT_MTU, TCP_LINGER_TIMEOUT,
     TCP_LOCATOR_PREFIX,
 };
 use tokio::net::{TcpListener, TcpSocket, TcpStream};
@@ -47,9 +48,14 @@ pub struct LinkUnicastTcp {
 unsafe impl Sync for LinkUnicastTcp {}
 
 impl LinkUnicastTcp {
-    fn new(socket: TcpStream, src_addr: SocketAddr, dst_addr: SocketAddr) -> LinkUnicastTcp {
+    fn new(
+        socket: TcpStream,
+        src_addr: SocketAddr,
+        dst_addr: SocketAddr,
+        config: &TcpSocketConfig,
+    ) -> LinkUnicastTcp {
         // Set the TCP nodelay option
-        if let Err(err) = socket.set_nodelay(true) {
+        if let Err(err) = socket.set_nodelay(config.nodelay) {
             tracing::warn!(
                 "Unable to set NODEALY option on TCP link {} => {}: {}",
                 src_addr,
@@ -70,6 +76,18 @@ impl LinkUnicastTcp {
             );
         }
 
+        // Set the TCP user timeout option
+        if let Some(timeout) = config.user_timeout {
+            if let Err(err) = set_user_timeout(&socket, timeout) {
+                tracing::warn!(
+                    "Unable to set USER_TIMEOUT option on TCP link {} => {}: {}",
+                    src_addr,
+                    dst_addr,
+                    err
+                );
+            }
+        }
+
         // Build the Tcp object
         LinkUnicastTcp {
             socket: UnsafeCell::new(socket),
@@ -165,6 +183,32 @@ impl LinkUnicastTrait for LinkUnicastTcp {
     }
 }
 
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn set_user_timeout(socket: &TcpStream, timeout: Duration) -> ZResult<()> {
+    socket2::SockRef::from(socket).set_tcp_user_timeout(Some(timeout))?;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn set_user_timeout(_socket: &TcpStream, _timeout: Duration) -> ZResult<()> {
+    bail!(
+        "{} is only supported on Linux",
+        super::config::TCP_USER_TIMEOUT
+    )
+}
+
+// The maximum segment size must be set before the socket is connected or listening.
+#[cfg(unix)]
+fn set_mss(socket: &TcpSocket, mss: u32) -> ZResult<()> {
+    socket2::SockRef::from(socket).set_mss(mss)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_mss(_socket: &TcpSocket, _mss: u32) -> ZResult<()> {
+    bail!("{} is only supported on Unix", super::config::TCP_MSS)
+}
+
 // // WARN: This sometimes causes timeout in routing test
 // // WARN assume the drop of TcpStream would clean itself
 // // https://docs.rs/tokio/latest/tokio/net/struct.TcpStream.html#method.into_split
@@ -212,16 +256,37 @@ impl LinkManagerUnicastTcp {
         &self,
         dst_addr: &SocketAddr,
         iface: Option<&str>,
+        src_addr: Option<&SocketAddr>,
+        config: &TcpSocketConfig,
     ) -> ZResult<(TcpStream, SocketAddr, SocketAddr)> {
         let socket = match dst_addr {
             SocketAddr::V4(_) => TcpSocket::new_v4(),
             SocketAddr::V6(_) => TcpSocket::new_v6(),
         }?;
 
+        if let Some(mss) = config.mss {
+            set_mss(&socket, mss).map_err(|e| zerror!("{}: {}", dst_addr, e))?;
+        }
+
         if let Some(iface) = iface {
             zenoh_util::net::set_bind_to_device_tcp_socket(&socket, iface)?;
         }
 
+        if let Some(src_addr) = src_addr {
+            if src_addr.is_ipv4() != dst_addr.is_ipv4() {
+                bail!(
+                    "{}: can not bind to {} of a different address family",
+                    dst_addr,
+                    src_addr
+                );
+            }
+            // Allow to reconnect from the same port while the previous connection is in TIME_WAIT
+            socket.set_reuseaddr(true)?;
+            socket
+                .bind(*src_addr)
+                .map_err(|e| zerror!("{}: can not bind to {}: {}", dst_addr, src_addr, e))?;
+        }
+
         // Build a TcpStream from TcpSocket
         // https://docs.rs/tokio/latest/tokio/net/struct.TcpSocket.html
         let stream = socket
@@ -244,12 +309,18 @@ impl LinkManagerUnicastTcp {
         &self,
         addr: &SocketAddr,
         iface: Option<&str>,
+        config: &TcpSocketConfig,
     ) -> ZResult<(TcpListener, SocketAddr)> {
         let socket = match addr {
             SocketAddr::V4(_) => TcpSocket::new_v4(),
             SocketAddr::V6(_) => TcpSocket::new_v6(),
         }?;
 
+        // The accepted sockets inherit the maximum segment size of the listening socket
+        if let Some(mss) = config.mss {
+            set_mss(&socket, mss).map_err(|e| zerror!("{}: {}", addr, e))?;
+        }
+
         if let Some(iface) = iface {
             zenoh_util::net::set_bind_to_device_tcp_socket(&socket, iface)?;
         }
@@ -274,15 +345,55 @@ impl LinkManagerUnicastTcp {
 #[async_trait]
 impl LinkManagerUnicastTrait for LinkManagerUnicastTcp {
     async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
-        let dst_addrs = get_tcp_addrs(endpoint.address()).await?;
         let config = endpoint.config();
-        let iface = config.get(BIND_INTERFACE);
+        let src_addr = get_bind_socket(&config)?;
+        if let Some(proxy) = ProxyConfig::new(&config)? {
+            // The proxy resolves the destination: only the connection to the proxy is bound
+            if config.get(BIND_INTERFACE).is_some() {
+                bail!(
+                    "Can not create a new TCP link bound to {}: a link through a proxy can not be bound to an interface",
+                    endpoint
+                );
+            }
+            let socket_config = TcpSocketConfig::new(&config)?;
+            let stream = proxy
+                .connect_from(endpoint.address().as_str(), src_addr.as_ref())
+                .await
+                .map_err(|e| {
+                    zerror!("Can not create a new TCP link bound to {}: {}", endpoint, e)
+                })?;
+            let src_addr = stream
+                .local_addr()
+                .map_err(|e| zerror!("{}: {}", endpoint, e))?;
+            let dst_addr = stream
+                .peer_addr()
+                .map_err(|e| zerror!("{}: {}", endpoint, e))?;
+            let link = Arc::new(LinkUnicastTcp::new(
+                stream,
+                src_addr,
+                dst_addr,
+                &socket_config,
+            ));
+            return Ok(LinkUnicast(link));
+        }
+
+        let dst_addrs = get_tcp_addrs(endpoint.address()).await?;
+        let iface = get_bind_interface(&config)?;
+        let socket_config = TcpSocketConfig::new(&config)?;
 
         let mut errs: Vec<ZError> = vec![];
         for da in dst_addrs {
-            match self.new_link_inner(&da, iface).await {
+            match self
+                .new_link_inner(&da, iface, src_addr.as_ref(), &socket_config)
+                .await
+            {
                 Ok((stream, src_addr, dst_addr)) => {
-                    let link = Arc::new(LinkUnicastTcp::new(stream, src_addr, dst_addr));
+                    let link = Arc::new(LinkUnicastTcp::new(
+                        stream,
+                        src_addr,
+                        dst_addr,
+                        &socket_config,
+                    ));
                     return Ok(LinkUnicast(link));
                 }
                 Err(e) => {
@@ -305,11 +416,12 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastTcp {
     async fn new_listener(&self, mut endpoint: EndPoint) -> ZResult<Locator> {
         let addrs = get_tcp_addrs(endpoint.address()).await?;
         let config = endpoint.config();
-        let iface = config.get(BIND_INTERFACE);
+        let iface = get_bind_interface(&config)?;
+        let socket_config = TcpSocketConfig::new(&config)?;
 
         let mut errs: Vec<ZError> = vec![];
         for da in addrs {
-            match self.new_listener_inner(&da, iface).await {
+            match self.new_listener_inner(&da, iface, &socket_config).await {
                 Ok((socket, local_addr)) => {
                     // Update the endpoint locator address
                     endpoint = EndPoint::new(
@@ -323,7 +435,8 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastTcp {
                     let c_token = token.clone();
 
                     let c_manager = self.manager.clone();
-                    let task = async move { accept_task(socket, c_token, c_manager).await };
+                    let task =
+                        async move { accept_task(socket, socket_config, c_token, c_manager).await };
 
                     let locator = endpoint.to_locator();
                     self.listeners
@@ -388,6 +501,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastTcp {
 
 async fn accept_task(
     socket: TcpListener,
+    config: TcpSocketConfig,
     token: CancellationToken,
     manager: NewLinkChannelSender,
 ) -> ZResult<()> {
@@ -411,7 +525,7 @@ async fn accept_task(
                     Ok((stream, dst_addr)) => {
                         tracing::debug!("Accepted TCP connection on {:?}: {:?}", src_addr, dst_addr);
                         // Create the new link object
-                        let link = Arc::new(LinkUnicastTcp::new(stream, src_addr, dst_addr));
+                        let link = Arc::new(LinkUnicastTcp::new(stream, src_addr, dst_addr, &config));
 
                         // Communicate the new link to the initial transport manager
                         if let Err(e) = manager.send_async(LinkUnicast(link)).await {
@@ -426,7 +540,7 @@ async fn accept_task(
                         //       Linux systems this limit can be changed by using the "ulimit" command line
                         //       tool. In case of systemd-based systems, this can be changed by using the
                         //       "sysctl" command line tool.
-                        tokio::time::sleep(Duration::from_micros(*TCP_ACCEPT_THROTTLE_TIME)).await;
+                        tokio::time::sleep(Duration::from_micros(TCP_ACCEPT_THROTTLE_TIME.get())).await;
                     }
 
                 }
@@ -436,3 +550,82 @@ async fn accept_task(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tcp_socket_options() {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()
+            .unwrap();
+        let _guard = rt.enter();
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let stream = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        stream.set_nonblocking(true).unwrap();
+        let (src_addr, dst_addr) = (stream.local_addr().unwrap(), stream.peer_addr().unwrap());
+
+        let config = TcpSocketConfig {
+            nodelay: false,
+            user_timeout: Some(Duration::from_millis(5000)),
+            mss: None,
+        };
+        let link = LinkUnicastTcp::new(
+            TcpStream::from_std(stream).unwrap(),
+            src_addr,
+            dst_addr,
+            &config,
+        );
+        let socket = socket2::SockRef::from(&*link.get_mut_socket());
+        assert!(!socket.nodelay().unwrap());
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        assert_eq!(
+            socket.tcp_user_timeout().unwrap(),
+            Some(Duration::from_millis(5000))
+        );
+
+        // The maximum segment size is set before connecting or listening
+        let socket = TcpSocket::new_v4().unwrap();
+        #[cfg(unix)]
+        {
+            set_mss(&socket, 1200).unwrap();
+            assert_eq!(socket2::SockRef::from(&socket).mss().unwrap(), 1200);
+        }
+        #[cfg(not(unix))]
+        assert!(set_mss(&socket, 1200).is_err());
+    }
+
+    #[test]
+    fn tcp_bind_socket() {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        rt.block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let dst_addr = listener.local_addr().unwrap();
+            // A free local port to bind the outgoing link to
+            let src_addr = std::net::TcpListener::bind("127.0.0.1:0")
+                .unwrap()
+                .local_addr()
+                .unwrap();
+
+            let (tx, _rx) = flume::unbounded();
+            let manager = LinkManagerUnicastTcp::new(tx);
+            let endpoint: EndPoint = format!("tcp/{dst_addr}#bind={src_addr}").parse().unwrap();
+            let link = manager.new_link(endpoint).await.unwrap();
+            let (_stream, peer_addr) = listener.accept().await.unwrap();
+            assert_eq!(peer_addr, src_addr);
+            assert_eq!(link.get_src().address().as_str(), src_addr.to_string());
+
+            // The source address must be of the family of the destination
+            let endpoint: EndPoint = format!("tcp/{dst_addr}#bind=[::1]:0").parse().unwrap();
+            assert!(manager.new_link(endpoint).await.is_err());
+            let endpoint: EndPoint = format!("tcp/{dst_addr}#bind=localhost").parse().unwrap();
+            assert!(manager.new_link(endpoint).await.is_err());
+        });
+    }
+}
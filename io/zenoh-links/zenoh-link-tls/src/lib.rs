@@ -26,7 +26,7 @@ use zenoh_result::ZResult;
 mod unicast;
 mod utils;
 pub use unicast::*;
-pub use utils::TlsConfigurator;
+pub use utils::{get_tls_server_name, TlsClientConfig, TlsConfigurator, TlsServerConfig};
 
 // Default MTU (TLS PDU) in bytes.
 // NOTE: Since TLS is a byte-stream oriented transport, theoretically it has
@@ -60,9 +60,15 @@ zconfigurable! {
     static ref TLS_LINGER_TIMEOUT: i32 = 10;
     // Amount of time in microseconds to throttle the accept loop upon an error.
     // Default set to 100 ms.
+    #[mutable]
     static ref TLS_ACCEPT_THROTTLE_TIME: u64 = 100_000;
 }
 
+/// Registers the values of this crate that can be modified at runtime.
+pub fn register_configurables() {
+    zenoh_core::configurable::register(&TLS_ACCEPT_THROTTLE_TIME);
+}
+
 pub mod config {
     pub const TLS_ROOT_CA_CERTIFICATE_FILE: &str = "root_ca_certificate_file";
     pub const TLS_ROOT_CA_CERTIFICATE_RAW: &str = "root_ca_certificate_raw";
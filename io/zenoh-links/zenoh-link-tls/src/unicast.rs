@@ -30,8 +30,8 @@ use tokio_rustls::{TlsAcceptor, TlsConnector, TlsStream};
 use tokio_util::sync::CancellationToken;
 use zenoh_core::zasynclock;
 use zenoh_link_commons::{
-    get_ip_interface_names, LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait,
-    ListenersUnicastIP, NewLinkChannelSender,
+    get_ip_interface_names, proxy::ProxyConfig, LinkManagerUnicastTrait, LinkUnicast,
+    LinkUnicastTrait, ListenersUnicastIP, NewLinkChannelSender,
 };
 use zenoh_protocol::core::{EndPoint, Locator};
 use zenoh_result::{zerror, ZResult};
@@ -236,7 +236,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastTls {
         let epconf = endpoint.config();
 
         let server_name = get_tls_server_name(&epaddr)?;
-        let addr = get_tls_addr(&epaddr).await?;
+        let proxy = ProxyConfig::new(&epconf)?;
 
         // Initialize the TLS Config
         let client_config = TlsClientConfig::new(&epconf)
@@ -245,8 +245,14 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastTls {
         let config = Arc::new(client_config.client_config);
         let connector = TlsConnector::from(config);
 
-        // Initialize the TcpStream
-        let tcp_stream = TcpStream::connect(addr).await.map_err(|e| {
+        // Initialize the TcpStream, through the proxy resolving the server name if any
+        let tcp_stream = match &proxy {
+            Some(proxy) => proxy.connect(epaddr.as_str()).await,
+            None => TcpStream::connect(get_tls_addr(&epaddr).await?)
+                .await
+                .map_err(|e| e.into()),
+        }
+        .map_err(|e| {
             zerror!(
                 "Can not create a new TLS link bound to {:?}: {}",
                 server_name,
@@ -399,7 +405,7 @@ async fn accept_task(
                         //       Linux systems this limit can be changed by using the "ulimit" command line
                         //       tool. In case of systemd-based systems, this can be changed by using the
                         //       "sysctl" command line tool.
-                        tokio::time::sleep(Duration::from_micros(*TLS_ACCEPT_THROTTLE_TIME)).await;
+                        tokio::time::sleep(Duration::from_micros(TLS_ACCEPT_THROTTLE_TIME.get())).await;
                     }
                 }
             }
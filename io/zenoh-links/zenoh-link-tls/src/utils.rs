@@ -29,7 +29,9 @@ use std::{
 };
 use webpki::anchor_from_trusted_cert;
 use zenoh_config::Config as ZenohConfig;
-use zenoh_link_commons::{tls::WebPkiVerifierAnyServerName, ConfigurationInspector};
+use zenoh_link_commons::{
+    proxy::ProxyConfig, tls::WebPkiVerifierAnyServerName, ConfigurationInspector,
+};
 use zenoh_protocol::core::endpoint::Config;
 use zenoh_protocol::core::endpoint::{self, Address};
 use zenoh_result::{bail, zerror, ZError, ZResult};
@@ -137,6 +139,9 @@ impl ConfigurationInspector<ZenohConfig> for TlsConfigurator {
             };
         }
 
+        let proxy = ProxyConfig::properties(config);
+        ps.extend(proxy.iter().map(|(k, v)| (*k, v.as_str())));
+
         let mut s = String::new();
         endpoint::Parameters::extend(ps.drain(..), &mut s);
 
@@ -144,8 +149,8 @@ impl ConfigurationInspector<ZenohConfig> for TlsConfigurator {
     }
 }
 
-pub(crate) struct TlsServerConfig {
-    pub(crate) server_config: ServerConfig,
+pub struct TlsServerConfig {
+    pub server_config: ServerConfig,
 }
 
 impl TlsServerConfig {
@@ -232,8 +237,8 @@ impl TlsServerConfig {
     }
 }
 
-pub(crate) struct TlsClientConfig {
-    pub(crate) client_config: ClientConfig,
+pub struct TlsClientConfig {
+    pub client_config: ClientConfig,
 }
 
 impl TlsClientConfig {
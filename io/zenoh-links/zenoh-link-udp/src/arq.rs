@@ -0,0 +1,223 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+//! # Automatic Repeat reQuest
+//!
+//! When enabled on a unicast UDP link, every datagram is prefixed with the ARQ header:
+//!
+//! ```text
+//!  7 6 5 4 3 2 1 0
+//! +-+-+-+-+-+-+-+-+
+//! |     kind      |  DATA or ACK
+//! +---------------+
+//! %      sn       %  (u32 little endian)
+//! +---------------+
+//! ~    payload    ~  DATA only
+//! +---------------+
+//! ```
+//!
+//! The DATA datagrams carry consecutive sequence numbers and are kept by the sender until
+//! acknowledged. The receiver acknowledges every DATA datagram with the sequence number of the
+//! next DATA datagram it expects, i.e. all the previous ones have been received, and delivers
+//! them in order. The DATA datagrams not acknowledged within [`UDP_ARQ_RTO`] are retransmitted,
+//! the link being considered broken after [`UDP_ARQ_MAX_RETRANSMISSIONS`] retransmissions.
+use super::{UDP_ARQ_MAX_RETRANSMISSIONS, UDP_ARQ_RTO, UDP_ARQ_WINDOW, UDP_MAX_MTU};
+use crate::unicast::LinkUnicastUdp;
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex as AsyncMutex, Notify};
+use zenoh_core::zasynclock;
+use zenoh_result::{bail, ZResult};
+
+const KIND_DATA: u8 = 0;
+const KIND_ACK: u8 = 1;
+
+/// The number of bytes added by the ARQ header to every datagram.
+pub(crate) const ARQ_HEADER_SIZE: usize = 5;
+
+fn header(kind: u8, sn: u32) -> [u8; ARQ_HEADER_SIZE] {
+    let sn = sn.to_le_bytes();
+    [kind, sn[0], sn[1], sn[2], sn[3]]
+}
+
+struct Unacked {
+    sn: u32,
+    datagram: Vec<u8>,
+    sent_at: Instant,
+    retransmissions: usize,
+}
+
+struct ArqTx {
+    sn: u32,
+    unacked: VecDeque<Unacked>,
+}
+
+struct ArqRx {
+    // The sequence number of the next expected DATA datagram
+    sn: u32,
+    // The DATA datagrams received out of order
+    pending: BTreeMap<u32, Vec<u8>>,
+    buffer: Vec<u8>,
+}
+
+pub(crate) struct Arq {
+    tx: AsyncMutex<ArqTx>,
+    acked: Notify,
+    rx: AsyncMutex<ArqRx>,
+}
+
+impl Arq {
+    pub(crate) fn new() -> Self {
+        Self {
+            tx: AsyncMutex::new(ArqTx {
+                sn: 0,
+                unacked: VecDeque::new(),
+            }),
+            acked: Notify::new(),
+            rx: AsyncMutex::new(ArqRx {
+                sn: 0,
+                pending: BTreeMap::new(),
+                buffer: vec![0; UDP_MAX_MTU as usize],
+            }),
+        }
+    }
+
+    pub(crate) async fn write(&self, link: &LinkUnicastUdp, buffer: &[u8]) -> ZResult<usize> {
+        let datagram = loop {
+            // Register for the acknowledgements before checking the window
+            let acked = self.acked.notified();
+            {
+                let mut tx = zasynclock!(self.tx);
+                if tx.unacked.len() < *UDP_ARQ_WINDOW {
+                    let sn = tx.sn;
+                    tx.sn = sn.wrapping_add(1);
+                    let mut datagram = Vec::with_capacity(ARQ_HEADER_SIZE + buffer.len());
+                    datagram.extend_from_slice(&header(KIND_DATA, sn));
+                    datagram.extend_from_slice(buffer);
+                    tx.unacked.push_back(Unacked {
+                        sn,
+                        datagram: datagram.clone(),
+                        sent_at: Instant::now(),
+                        retransmissions: 0,
+                    });
+                    break datagram;
+                }
+            }
+            acked.await;
+        };
+        link.write_raw(&datagram).await?;
+        Ok(buffer.len())
+    }
+
+    pub(crate) async fn read(&self, link: &LinkUnicastUdp, buffer: &mut [u8]) -> ZResult<usize> {
+        let rto = Duration::from_millis(UDP_ARQ_RTO.get());
+        let mut guard = zasynclock!(self.rx);
+        let rx = &mut *guard;
+        loop {
+            if let Some(payload) = rx.pending.remove(&rx.sn) {
+                rx.sn = rx.sn.wrapping_add(1);
+                return Self::deliver(&payload, buffer);
+            }
+
+            let n = tokio::select! {
+                res = link.read_raw(&mut rx.buffer) => res?,
+                _ = tokio::time::sleep(rto) => {
+                    self.retransmit(link, rto).await?;
+                    continue;
+                }
+            };
+            // The DATA datagrams are also retransmitted while receiving
+            self.retransmit(link, rto).await?;
+            if n < ARQ_HEADER_SIZE {
+                tracing::trace!("Invalid ARQ datagram on UDP link {}: {} bytes", link, n);
+                continue;
+            }
+            let sn = u32::from_le_bytes([rx.buffer[1], rx.buffer[2], rx.buffer[3], rx.buffer[4]]);
+            match rx.buffer[0] {
+                KIND_ACK => {
+                    let mut tx = zasynclock!(self.tx);
+                    while tx
+                        .unacked
+                        .front()
+                        .is_some_and(|u| (u.sn.wrapping_sub(sn) as i32) < 0)
+                    {
+                        tx.unacked.pop_front();
+                    }
+                    drop(tx);
+                    self.acked.notify_waiters();
+                }
+                KIND_DATA => {
+                    let distance = sn.wrapping_sub(rx.sn) as i32;
+                    let payload = &rx.buffer[ARQ_HEADER_SIZE..n];
+                    let res = if distance == 0 {
+                        rx.sn = rx.sn.wrapping_add(1);
+                        Some(Self::deliver(payload, buffer))
+                    } else {
+                        // Keep the DATA datagrams received in advance, drop the duplicates
+                        if distance > 0 && distance as usize <= 2 * *UDP_ARQ_WINDOW {
+                            rx.pending.entry(sn).or_insert_with(|| payload.to_vec());
+                        }
+                        None
+                    };
+                    // Acknowledge the DATA datagrams received so far
+                    let mut next = rx.sn;
+                    while rx.pending.contains_key(&next) {
+                        next = next.wrapping_add(1);
+                    }
+                    link.write_raw(&header(KIND_ACK, next)).await?;
+                    if let Some(res) = res {
+                        return res;
+                    }
+                }
+                kind => {
+                    tracing::trace!("Invalid ARQ datagram on UDP link {}: kind {}", link, kind);
+                }
+            }
+        }
+    }
+
+    fn deliver(payload: &[u8], buffer: &mut [u8]) -> ZResult<usize> {
+        if payload.len() > buffer.len() {
+            bail!(
+                "ARQ datagram of {} bytes exceeds the read buffer of {} bytes",
+                payload.len(),
+                buffer.len()
+            );
+        }
+        buffer[..payload.len()].copy_from_slice(payload);
+        Ok(payload.len())
+    }
+
+    // Retransmit the DATA datagrams not acknowledged in time
+    async fn retransmit(&self, link: &LinkUnicastUdp, rto: Duration) -> ZResult<()> {
+        let mut tx = zasynclock!(self.tx);
+        let now = Instant::now();
+        for unacked in tx.unacked.iter_mut() {
+            if now.duration_since(unacked.sent_at) < rto {
+                continue;
+            }
+            if unacked.retransmissions >= UDP_ARQ_MAX_RETRANSMISSIONS.get() {
+                bail!(
+                    "UDP link {}: datagram {} not acknowledged after {} retransmissions",
+                    link,
+                    unacked.sn,
+                    unacked.retransmissions
+                );
+            }
+            link.write_raw(&unacked.datagram).await?;
+            unacked.sent_at = now;
+            unacked.retransmissions += 1;
+        }
+        Ok(())
+    }
+}
@@ -17,6 +17,7 @@
 //! This crate is intended for Zenoh's internal use.
 //!
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
+mod arq;
 mod multicast;
 mod unicast;
 
@@ -64,7 +65,24 @@ zconfigurable! {
     static ref UDP_DEFAULT_MTU: u16 = UDP_MTU_LIMIT;
     // Amount of time in microseconds to throttle the accept loop upon an error.
     // Default set to 100 ms.
+    #[mutable]
     static ref UDP_ACCEPT_THROTTLE_TIME: u64 = 100_000;
+    // Amount of time in milliseconds after which a datagram not acknowledged is retransmitted
+    // on a UDP link with ARQ.
+    #[mutable]
+    static ref UDP_ARQ_RTO: u64 = 100;
+    // Maximum number of datagrams not acknowledged yet on a UDP link with ARQ.
+    static ref UDP_ARQ_WINDOW: usize = 64;
+    // Maximum number of retransmissions of a datagram before a UDP link with ARQ is closed.
+    #[mutable]
+    static ref UDP_ARQ_MAX_RETRANSMISSIONS: usize = 16;
+}
+
+/// Registers the values of this crate that can be modified at runtime.
+pub fn register_configurables() {
+    zenoh_core::configurable::register(&UDP_ACCEPT_THROTTLE_TIME);
+    zenoh_core::configurable::register(&UDP_ARQ_RTO);
+    zenoh_core::configurable::register(&UDP_ARQ_MAX_RETRANSMISSIONS);
 }
 
 #[derive(Default, Clone, Copy)]
@@ -86,6 +104,21 @@ impl LocatorInspector for UdpLocatorInspector {
 pub mod config {
     pub const UDP_MULTICAST_IFACE: &str = "iface";
     pub const UDP_MULTICAST_JOIN: &str = "join";
+    /// The interface (name or address) the datagrams are sent on, when different from the
+    /// `iface` the multicast groups are joined on, e.g. `udp/224.0.0.224:7447#iface=eth0;out_iface=eth1`.
+    pub const UDP_MULTICAST_OUT_IFACE: &str = "out_iface";
+    /// The time-to-live (IPv4) or hop limit (IPv6) of the datagrams sent on the multicast group,
+    /// e.g. `udp/224.0.0.224:7447#ttl=8`. It defaults to [`UDP_MULTICAST_DEFAULT_TTL`].
+    pub const UDP_MULTICAST_TTL: &str = "ttl";
+    /// The default time-to-live of the datagrams sent on the multicast group, keeping them on the
+    /// local network.
+    pub const UDP_MULTICAST_DEFAULT_TTL: u32 = 1;
+    /// The sources the multicast groups are joined for (source-specific multicast), separated by `|`,
+    /// e.g. `udp/232.1.1.1:7447#source=10.0.0.1|10.0.0.2`. The datagrams from other sources are dropped.
+    pub const UDP_MULTICAST_SOURCE: &str = "source";
+    /// Enable the reliability layer (ARQ) on a unicast UDP link, e.g. `udp/192.168.1.1:7447#arq=true`.
+    /// It must be enabled on both the connecting and the listening endpoints.
+    pub const UDP_ARQ: &str = "arq";
 }
 
 pub async fn get_udp_addrs(address: Address<'_>) -> ZResult<impl Iterator<Item = SocketAddr>> {
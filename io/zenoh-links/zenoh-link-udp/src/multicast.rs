@@ -14,7 +14,8 @@
 use super::{config::*, UDP_DEFAULT_MTU};
 use crate::{get_udp_addrs, socket_addr_to_udp_locator};
 use async_trait::async_trait;
-use socket2::{Domain, Protocol, Socket, Type};
+use socket2::{Domain, Protocol, SockRef, Socket, Type};
+use std::io;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::sync::Arc;
 use std::{borrow::Cow, fmt};
@@ -34,6 +35,10 @@ pub struct LinkMulticastUdp {
     multicast_locator: Locator,
     // The multicast UDP socket used for read operations
     mcast_sock: UdpSocket,
+    // The address of the interface the multicast groups are joined on
+    iface_addr: IpAddr,
+    // The sources the multicast groups are joined for, if source-specific
+    sources: Vec<IpAddr>,
 }
 
 impl LinkMulticastUdp {
@@ -42,6 +47,8 @@ impl LinkMulticastUdp {
         unicast_socket: UdpSocket,
         multicast_addr: SocketAddr,
         mcast_sock: UdpSocket,
+        iface_addr: IpAddr,
+        sources: Vec<IpAddr>,
     ) -> LinkMulticastUdp {
         LinkMulticastUdp {
             unicast_locator: socket_addr_to_udp_locator(&unicast_addr),
@@ -50,6 +57,8 @@ impl LinkMulticastUdp {
             unicast_socket,
             multicast_addr,
             mcast_sock,
+            iface_addr,
+            sources,
         }
     }
 }
@@ -58,12 +67,28 @@ impl LinkMulticastUdp {
 impl LinkMulticastTrait for LinkMulticastUdp {
     async fn close(&self) -> ZResult<()> {
         tracing::trace!("Closing UDP link: {}", self);
-        match self.multicast_addr.ip() {
-            IpAddr::V4(dst_ip4) => match self.multicast_addr.ip() {
-                IpAddr::V4(src_ip4) => self.mcast_sock.leave_multicast_v4(dst_ip4, src_ip4),
-                IpAddr::V6(_) => unreachable!(),
-            },
-            IpAddr::V6(dst_ip6) => self.mcast_sock.leave_multicast_v6(&dst_ip6, 0),
+        match (self.multicast_addr.ip(), self.iface_addr) {
+            (IpAddr::V4(dst_ip4), IpAddr::V4(src_ip4)) if !self.sources.is_empty() => {
+                let sock = SockRef::from(&self.mcast_sock);
+                self.sources.iter().try_for_each(|s| match s {
+                    IpAddr::V4(s) => sock.leave_ssm_v4(s, &dst_ip4, &src_ip4),
+                    IpAddr::V6(_) => Ok(()),
+                })
+            }
+            (IpAddr::V4(dst_ip4), IpAddr::V4(src_ip4)) => {
+                self.mcast_sock.leave_multicast_v4(dst_ip4, src_ip4)
+            }
+            (IpAddr::V4(_), IpAddr::V6(_)) => unreachable!(),
+            (IpAddr::V6(dst_ip6), iface_addr) if !self.sources.is_empty() => {
+                let iface = zenoh_util::net::get_index_of_interface(iface_addr).unwrap_or(0);
+                self.sources.iter().try_for_each(|s| match s {
+                    IpAddr::V6(s) => {
+                        ssm_v6(SockRef::from(&self.mcast_sock), false, &dst_ip6, s, iface)
+                    }
+                    IpAddr::V4(_) => Ok(()),
+                })
+            }
+            (IpAddr::V6(dst_ip6), _) => self.mcast_sock.leave_multicast_v6(&dst_ip6, 0),
         }
         .map_err(|e| {
             let e = zerror!("Close error on UDP link {}: {}", self, e);
@@ -101,6 +126,9 @@ impl LinkMulticastTrait for LinkMulticastUdp {
 
             if self.unicast_addr == addr {
                 continue; // We are reading our own messages, skip it
+            } else if !self.sources.is_empty() && !self.sources.contains(&addr.ip()) {
+                tracing::trace!("Datagram from {} dropped on UDP link {}", addr, self);
+                continue; // Not one of the allowed sources
             } else {
                 let locator = socket_addr_to_udp_locator(&addr);
                 break Ok((n, Cow::Owned(locator)));
@@ -151,34 +179,118 @@ impl fmt::Debug for LinkMulticastUdp {
 #[derive(Default)]
 pub struct LinkManagerMulticastUdp;
 
+// The address of the interface, given by name or address, matching the family of the multicast address.
+fn get_iface_addr(iface: &str, mcast_addr: &SocketAddr) -> ZResult<Option<IpAddr>> {
+    match iface.parse() {
+        Ok(addr) => Ok(Some(addr)),
+        Err(_) => Ok(zenoh_util::net::get_unicast_addresses_of_interface(iface)?
+            .into_iter()
+            .find(|x| match mcast_addr.ip() {
+                IpAddr::V4(_) => x.is_ipv4(),
+                IpAddr::V6(_) => x.is_ipv6(),
+            })),
+    }
+}
+
+fn set_multicast_if(sock: &Socket, addr: &IpAddr, mcast_addr: &SocketAddr) -> ZResult<()> {
+    match addr {
+        IpAddr::V4(addr) => sock
+            .set_multicast_if_v4(addr)
+            .map_err(|e| zerror!("{}: {}", mcast_addr, e))?,
+        IpAddr::V6(_) => match zenoh_util::net::get_index_of_interface(*addr) {
+            Ok(idx) => sock
+                .set_multicast_if_v6(idx)
+                .map_err(|e| zerror!("{}: {}", mcast_addr, e))?,
+            Err(e) => bail!("{}: {}", mcast_addr, e),
+        },
+    }
+    Ok(())
+}
+
+// Joins, or leaves, the IPv6 multicast group for the source on the interface of index iface,
+// which socket2 only supports for IPv4.
+#[cfg(target_os = "linux")]
+fn ssm_v6(
+    sock: SockRef<'_>,
+    join: bool,
+    group: &Ipv6Addr,
+    source: &Ipv6Addr,
+    iface: u32,
+) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    // The protocol independent socket options of RFC 3678
+    const MCAST_JOIN_SOURCE_GROUP: libc::c_int = 46;
+    const MCAST_LEAVE_SOURCE_GROUP: libc::c_int = 47;
+
+    #[repr(C)]
+    struct GroupSourceReq {
+        gsr_interface: u32,
+        gsr_group: libc::sockaddr_storage,
+        gsr_source: libc::sockaddr_storage,
+    }
+
+    fn storage(addr: &Ipv6Addr) -> libc::sockaddr_storage {
+        let addr = socket2::SockAddr::from(SocketAddr::new(IpAddr::V6(*addr), 0));
+        // SAFETY: a SockAddr is backed by a sockaddr_storage
+        unsafe { std::ptr::read(addr.as_ptr() as *const libc::sockaddr_storage) }
+    }
+
+    let req = GroupSourceReq {
+        gsr_interface: iface,
+        gsr_group: storage(group),
+        gsr_source: storage(source),
+    };
+    let opt = match join {
+        true => MCAST_JOIN_SOURCE_GROUP,
+        false => MCAST_LEAVE_SOURCE_GROUP,
+    };
+    // SAFETY: req is a group_source_req living until the call returns
+    let res = unsafe {
+        libc::setsockopt(
+            sock.as_raw_fd(),
+            libc::IPPROTO_IPV6,
+            opt,
+            &req as *const GroupSourceReq as *const libc::c_void,
+            std::mem::size_of::<GroupSourceReq>() as libc::socklen_t,
+        )
+    };
+    match res {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn ssm_v6(
+    _sock: SockRef<'_>,
+    _join: bool,
+    _group: &Ipv6Addr,
+    _source: &Ipv6Addr,
+    _iface: u32,
+) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "IPv6 source-specific multicast is only supported on Linux",
+    ))
+}
+
 impl LinkManagerMulticastUdp {
     async fn new_link_inner(
         &self,
         mcast_addr: &SocketAddr,
         config: Config<'_>,
-    ) -> ZResult<(UdpSocket, UdpSocket, SocketAddr)> {
+    ) -> ZResult<(UdpSocket, UdpSocket, SocketAddr, IpAddr, Vec<IpAddr>)> {
         let domain = match mcast_addr.ip() {
             IpAddr::V4(_) => Domain::IPV4,
             IpAddr::V6(_) => Domain::IPV6,
         };
 
         // Get default iface address to bind the socket on if provided
-        let mut iface_addr: Option<IpAddr> = None;
-        if let Some(iface) = config.get(UDP_MULTICAST_IFACE) {
-            iface_addr = match iface.parse() {
-                Ok(addr) => Some(addr),
-                Err(_) => zenoh_util::net::get_unicast_addresses_of_interface(iface)?
-                    .into_iter()
-                    .filter(|x| match mcast_addr.ip() {
-                        IpAddr::V4(_) => x.is_ipv4(),
-                        IpAddr::V6(_) => x.is_ipv6(),
-                    })
-                    .take(1)
-                    .collect::<Vec<IpAddr>>()
-                    .first()
-                    .copied(),
-            };
-        }
+        let iface_addr = match config.get(UDP_MULTICAST_IFACE) {
+            Some(iface) => get_iface_addr(iface, mcast_addr)?,
+            None => None,
+        };
 
         // Get local unicast address to bind the socket on
         let local_addr = match iface_addr {
@@ -208,25 +320,49 @@ impl LinkManagerMulticastUdp {
             }
         };
 
+        // Get the address of the interface to send on, if different
+        let out_addr = match config.get(UDP_MULTICAST_OUT_IFACE) {
+            Some(iface) => get_iface_addr(iface, mcast_addr)?.ok_or_else(|| {
+                zerror!("{}: no address on outgoing interface {}", mcast_addr, iface)
+            })?,
+            None => local_addr,
+        };
+
+        // Get the sources of the source-specific multicast
+        let sources = config
+            .values(UDP_MULTICAST_SOURCE)
+            .map(|s| {
+                s.parse::<IpAddr>()
+                    .map_err(|e| zerror!("{}: invalid source {}: {}", mcast_addr, s, e))
+            })
+            .collect::<Result<Vec<IpAddr>, _>>()?;
+
         // Establish a unicast UDP socket
         let ucast_sock = Socket::new(domain, Type::DGRAM, Some(Protocol::UDP))
             .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
-        match &local_addr {
-            IpAddr::V4(addr) => {
-                ucast_sock
-                    .set_multicast_if_v4(addr)
-                    .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
-            }
-            IpAddr::V6(_) => match zenoh_util::net::get_index_of_interface(local_addr) {
-                Ok(idx) => ucast_sock
-                    .set_multicast_if_v6(idx)
-                    .map_err(|e| zerror!("{}: {}", mcast_addr, e))?,
-                Err(e) => bail!("{}: {}", mcast_addr, e),
-            },
+        set_multicast_if(&ucast_sock, &out_addr, mcast_addr)?;
+
+        // Limit the scope of the multicast datagrams
+        let ttl = match config.get(UDP_MULTICAST_TTL) {
+            Some(ttl) => ttl.parse().map_err(|e| {
+                zerror!(
+                    "{}: invalid {} {}: {}",
+                    mcast_addr,
+                    UDP_MULTICAST_TTL,
+                    ttl,
+                    e
+                )
+            })?,
+            None => UDP_MULTICAST_DEFAULT_TTL,
+        };
+        match mcast_addr.ip() {
+            IpAddr::V4(_) => ucast_sock.set_multicast_ttl_v4(ttl),
+            IpAddr::V6(_) => ucast_sock.set_multicast_hops_v6(ttl),
         }
+        .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
 
         ucast_sock
-            .bind(&SocketAddr::new(local_addr, 0).into())
+            .bind(&SocketAddr::new(out_addr, 0).into())
             .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
 
         // Must set to nonblocking according to the doc of tokio
@@ -262,32 +398,53 @@ impl LinkManagerMulticastUdp {
         match mcast_addr.ip() {
             IpAddr::V4(dst_ip4) => match local_addr {
                 IpAddr::V4(src_ip4) => {
+                    let join_v4 = |g: &Ipv4Addr| -> ZResult<()> {
+                        match sources.is_empty() {
+                            true => mcast_sock.join_multicast_v4(g, &src_ip4),
+                            // Join the group for the given sources only
+                            false => sources.iter().try_for_each(|s| match s {
+                                IpAddr::V4(s) => mcast_sock.join_ssm_v4(s, g, &src_ip4),
+                                IpAddr::V6(s) => Err(io::Error::new(
+                                    io::ErrorKind::InvalidInput,
+                                    format!("unexpected IPv6 source {s}"),
+                                )),
+                            }),
+                        }
+                        .map_err(|e| zerror!("{}: {}", mcast_addr, e).into())
+                    };
                     // Join default multicast group
-                    mcast_sock
-                        .join_multicast_v4(&dst_ip4, &src_ip4)
-                        .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
+                    join_v4(&dst_ip4)?;
                     // Join any additional multicast group
                     for g in join {
                         let g: Ipv4Addr =
                             g.parse().map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
-                        mcast_sock
-                            .join_multicast_v4(&g, &src_ip4)
-                            .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
+                        join_v4(&g)?;
                     }
                 }
                 IpAddr::V6(src_ip6) => bail!("{}: unexepcted IPv6 source address", src_ip6),
             },
             IpAddr::V6(dst_ip6) => {
+                let iface = zenoh_util::net::get_index_of_interface(local_addr).unwrap_or(0);
+                let join_v6 = |g: &Ipv6Addr| -> ZResult<()> {
+                    match sources.is_empty() {
+                        true => mcast_sock.join_multicast_v6(g, 0),
+                        // Join the group for the given sources only
+                        false => sources.iter().try_for_each(|s| match s {
+                            IpAddr::V6(s) => ssm_v6(SockRef::from(&mcast_sock), true, g, s, iface),
+                            IpAddr::V4(s) => Err(io::Error::new(
+                                io::ErrorKind::InvalidInput,
+                                format!("unexpected IPv4 source {s}"),
+                            )),
+                        }),
+                    }
+                    .map_err(|e| zerror!("{}: {}", mcast_addr, e).into())
+                };
                 // Join default multicast group
-                mcast_sock
-                    .join_multicast_v6(&dst_ip6, 0)
-                    .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
+                join_v6(&dst_ip6)?;
                 // Join any additional multicast group
                 for g in join {
                     let g: Ipv6Addr = g.parse().map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
-                    mcast_sock
-                        .join_multicast_v6(&g, 0)
-                        .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
+                    join_v6(&g)?;
                 }
             }
         };
@@ -302,9 +459,9 @@ impl LinkManagerMulticastUdp {
         let ucast_addr = ucast_sock
             .local_addr()
             .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
-        assert_eq!(ucast_addr.ip(), local_addr);
+        assert_eq!(ucast_addr.ip(), out_addr);
 
-        Ok((mcast_sock, ucast_sock, ucast_addr))
+        Ok((mcast_sock, ucast_sock, ucast_addr, local_addr, sources))
     }
 }
 
@@ -319,9 +476,9 @@ impl LinkManagerMulticastTrait for LinkManagerMulticastUdp {
         let mut errs: Vec<ZError> = vec![];
         for maddr in mcast_addrs {
             match self.new_link_inner(&maddr, endpoint.config()).await {
-                Ok((mcast_sock, ucast_sock, ucast_addr)) => {
+                Ok((mcast_sock, ucast_sock, ucast_addr, iface_addr, sources)) => {
                     let link = Arc::new(LinkMulticastUdp::new(
-                        ucast_addr, ucast_sock, maddr, mcast_sock,
+                        ucast_addr, ucast_sock, maddr, mcast_sock, iface_addr, sources,
                     ));
 
                     return Ok(LinkMulticast(link));
@@ -343,3 +500,61 @@ impl LinkManagerMulticastTrait for LinkManagerMulticastUdp {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn new_link(
+        endpoint: &str,
+    ) -> ZResult<(UdpSocket, UdpSocket, SocketAddr, IpAddr, Vec<IpAddr>)> {
+        let endpoint: EndPoint = endpoint.parse().unwrap();
+        let addr = get_udp_addrs(endpoint.address()).await?.next().unwrap();
+        LinkManagerMulticastUdp
+            .new_link_inner(&addr, endpoint.config())
+            .await
+    }
+
+    #[tokio::test]
+    async fn multicast_ttl() {
+        // The datagrams are kept on the local network by default
+        let (_, ucast_sock, ..) = new_link("udp/224.0.0.224:17450#iface=127.0.0.1")
+            .await
+            .unwrap();
+        let ttl = SockRef::from(&ucast_sock).multicast_ttl_v4().unwrap();
+        assert_eq!(ttl, UDP_MULTICAST_DEFAULT_TTL);
+
+        let (_, ucast_sock, ..) = new_link("udp/224.0.0.224:17451#iface=127.0.0.1;ttl=8")
+            .await
+            .unwrap();
+        assert_eq!(SockRef::from(&ucast_sock).multicast_ttl_v4().unwrap(), 8);
+
+        assert!(new_link("udp/224.0.0.224:17452#iface=127.0.0.1;ttl=none")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn multicast_source() {
+        let (_, _, _, iface_addr, sources) =
+            new_link("udp/232.1.1.1:17453#iface=127.0.0.1;source=127.0.0.1|127.0.0.2")
+                .await
+                .unwrap();
+        assert_eq!(iface_addr, IpAddr::from([127, 0, 0, 1]));
+        assert_eq!(
+            sources,
+            vec![IpAddr::from([127, 0, 0, 1]), IpAddr::from([127, 0, 0, 2])]
+        );
+
+        // The sources must be addresses of the family of the group
+        assert!(new_link("udp/232.1.1.1:17454#iface=127.0.0.1;source=local")
+            .await
+            .is_err());
+        assert!(new_link("udp/232.1.1.1:17455#iface=127.0.0.1;source=::1")
+            .await
+            .is_err());
+        assert!(new_link("udp/[ff3e::1]:17456#source=127.0.0.1")
+            .await
+            .is_err());
+    }
+}
@@ -12,6 +12,8 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use super::{
+    arq::{Arq, ARQ_HEADER_SIZE},
+    config::UDP_ARQ,
     get_udp_addrs, socket_addr_to_udp_locator, UDP_ACCEPT_THROTTLE_TIME, UDP_DEFAULT_MTU,
     UDP_MAX_MTU,
 };
@@ -26,8 +28,9 @@ use tokio::sync::Mutex as AsyncMutex;
 use tokio_util::sync::CancellationToken;
 use zenoh_core::{zasynclock, zlock};
 use zenoh_link_commons::{
-    get_ip_interface_names, ConstructibleLinkManagerUnicast, LinkManagerUnicastTrait, LinkUnicast,
-    LinkUnicastTrait, ListenersUnicastIP, NewLinkChannelSender, BIND_INTERFACE,
+    get_bind_interface, get_ip_interface_names, ConstructibleLinkManagerUnicast,
+    LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait, ListenersUnicastIP,
+    NewLinkChannelSender,
 };
 use zenoh_protocol::core::{EndPoint, Locator};
 use zenoh_result::{bail, zerror, Error as ZError, ZResult};
@@ -128,6 +131,8 @@ pub struct LinkUnicastUdp {
     dst_locator: Locator,
     // The UDP socket is connected to the peer
     variant: LinkUnicastUdpVariant,
+    // The reliability layer, if enabled
+    arq: Option<Arq>,
 }
 
 impl LinkUnicastUdp {
@@ -135,6 +140,7 @@ impl LinkUnicastUdp {
         src_addr: SocketAddr,
         dst_addr: SocketAddr,
         variant: LinkUnicastUdpVariant,
+        arq: bool,
     ) -> LinkUnicastUdp {
         LinkUnicastUdp {
             src_locator: socket_addr_to_udp_locator(&src_addr),
@@ -142,8 +148,32 @@ impl LinkUnicastUdp {
             src_addr,
             dst_addr,
             variant,
+            arq: arq.then(Arq::new),
         }
     }
+
+    pub(crate) async fn write_raw(&self, buffer: &[u8]) -> ZResult<usize> {
+        match &self.variant {
+            LinkUnicastUdpVariant::Connected(link) => link.write(buffer).await,
+            LinkUnicastUdpVariant::Unconnected(link) => link.write(buffer, self.dst_addr).await,
+        }
+    }
+
+    pub(crate) async fn read_raw(&self, buffer: &mut [u8]) -> ZResult<usize> {
+        match &self.variant {
+            LinkUnicastUdpVariant::Connected(link) => link.read(buffer).await,
+            LinkUnicastUdpVariant::Unconnected(link) => link.read(buffer).await,
+        }
+    }
+}
+
+fn is_arq(endpoint: &EndPoint) -> ZResult<bool> {
+    match endpoint.config().get(UDP_ARQ) {
+        None => Ok(false),
+        Some(arq) => arq
+            .parse()
+            .map_err(|_| zerror!("Invalid {} value on {}: {}", UDP_ARQ, endpoint, arq).into()),
+    }
 }
 
 #[async_trait]
@@ -159,9 +189,9 @@ impl LinkUnicastTrait for LinkUnicastUdp {
     }
 
     async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
-        match &self.variant {
-            LinkUnicastUdpVariant::Connected(link) => link.write(buffer).await,
-            LinkUnicastUdpVariant::Unconnected(link) => link.write(buffer, self.dst_addr).await,
+        match &self.arq {
+            Some(arq) => arq.write(self, buffer).await,
+            None => self.write_raw(buffer).await,
         }
     }
 
@@ -174,9 +204,9 @@ impl LinkUnicastTrait for LinkUnicastUdp {
     }
 
     async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
-        match &self.variant {
-            LinkUnicastUdpVariant::Connected(link) => link.read(buffer).await,
-            LinkUnicastUdpVariant::Unconnected(link) => link.read(buffer).await,
+        match &self.arq {
+            Some(arq) => arq.read(self, buffer).await,
+            None => self.read_raw(buffer).await,
         }
     }
 
@@ -201,7 +231,10 @@ impl LinkUnicastTrait for LinkUnicastUdp {
 
     #[inline(always)]
     fn get_mtu(&self) -> u16 {
-        *UDP_DEFAULT_MTU
+        match &self.arq {
+            Some(_) => *UDP_DEFAULT_MTU - ARQ_HEADER_SIZE as u16,
+            None => *UDP_DEFAULT_MTU,
+        }
     }
 
     #[inline(always)]
@@ -211,7 +244,7 @@ impl LinkUnicastTrait for LinkUnicastUdp {
 
     #[inline(always)]
     fn is_reliable(&self) -> bool {
-        false
+        self.arq.is_some()
     }
 
     #[inline(always)]
@@ -337,7 +370,8 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastUdp {
             .await?
             .filter(|a| !a.ip().is_multicast());
         let config = endpoint.config();
-        let iface = config.get(BIND_INTERFACE);
+        let iface = get_bind_interface(&config)?;
+        let arq = is_arq(&endpoint)?;
 
         let mut errs: Vec<ZError> = vec![];
         for da in dst_addrs {
@@ -350,6 +384,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastUdp {
                         LinkUnicastUdpVariant::Connected(LinkUnicastUdpConnected {
                             socket: Arc::new(socket),
                         }),
+                        arq,
                     ));
 
                     return Ok(LinkUnicast(link));
@@ -376,7 +411,8 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastUdp {
             .await?
             .filter(|a| !a.ip().is_multicast());
         let config = endpoint.config();
-        let iface = config.get(BIND_INTERFACE);
+        let iface = get_bind_interface(&config)?;
+        let arq = is_arq(&endpoint)?;
 
         let mut errs: Vec<ZError> = vec![];
         for da in addrs {
@@ -394,7 +430,8 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastUdp {
                     let c_token = token.clone();
                     let c_manager = self.manager.clone();
 
-                    let task = async move { accept_read_task(socket, c_token, c_manager).await };
+                    let task =
+                        async move { accept_read_task(socket, c_token, c_manager, arq).await };
 
                     let locator = endpoint.to_locator();
                     self.listeners
@@ -463,6 +500,7 @@ async fn accept_read_task(
     socket: UdpSocket,
     token: CancellationToken,
     manager: NewLinkChannelSender,
+    arq: bool,
 ) -> ZResult<()> {
     let socket = Arc::new(socket);
     let links: LinkHashMap = Arc::new(Mutex::new(HashMap::new()));
@@ -527,6 +565,7 @@ async fn accept_read_task(
                                         src_addr,
                                         dst_addr,
                                         LinkUnicastUdpVariant::Unconnected(unconnected),
+                                        arq,
                                     ));
                                     // Add the new link to the set of connected peers
                                     if let Err(e) = manager.send_async(LinkUnicast(link)).await {
@@ -554,7 +593,7 @@ async fn accept_read_task(
                         //       Linux systems this limit can be changed by using the "ulimit" command line
                         //       tool. In case of systemd-based systems, this can be changed by using the
                         //       "sysctl" command line tool.
-                        tokio::time::sleep(Duration::from_micros(*UDP_ACCEPT_THROTTLE_TIME)).await;
+                        tokio::time::sleep(Duration::from_micros(UDP_ACCEPT_THROTTLE_TIME.get())).await;
                     }
                 }
             }
@@ -563,3 +602,80 @@ async fn accept_read_task(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Connect a link with ARQ to a socket through a relay dropping every `loss`-th datagram
+    async fn lossy_links(loss: usize) -> (Arc<LinkUnicastUdp>, Arc<LinkUnicastUdp>) {
+        let relay = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let relay_addr = relay.local_addr().unwrap();
+        let mut links = vec![];
+        let mut addrs = vec![];
+        for _ in 0..2 {
+            let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            socket.connect(relay_addr).await.unwrap();
+            let src_addr = socket.local_addr().unwrap();
+            addrs.push(src_addr);
+            links.push(Arc::new(LinkUnicastUdp::new(
+                src_addr,
+                relay_addr,
+                LinkUnicastUdpVariant::Connected(LinkUnicastUdpConnected {
+                    socket: Arc::new(socket),
+                }),
+                true,
+            )));
+        }
+
+        tokio::spawn(async move {
+            let mut buffer = vec![0u8; UDP_MAX_MTU as usize];
+            let mut count = 0;
+            while let Ok((n, from)) = relay.recv_from(&mut buffer).await {
+                count += 1;
+                if count % loss == 0 {
+                    continue;
+                }
+                let to = if from == addrs[0] { addrs[1] } else { addrs[0] };
+                let _ = relay.send_to(&buffer[..n], to).await;
+            }
+        });
+
+        let b = links.pop().unwrap();
+        let a = links.pop().unwrap();
+        (a, b)
+    }
+
+    #[tokio::test]
+    async fn udp_arq_lossy() {
+        const COUNT: u32 = 200;
+        let (a, b) = lossy_links(4).await;
+
+        // The acknowledgements are processed, and the datagrams retransmitted, by the reader
+        let c_a = a.clone();
+        let reader = tokio::spawn(async move {
+            let mut buffer = vec![0u8; UDP_MAX_MTU as usize];
+            loop {
+                let _ = c_a.read(&mut buffer).await;
+            }
+        });
+        let c_a = a.clone();
+        let writer = tokio::spawn(async move {
+            for i in 0..COUNT {
+                c_a.write(&i.to_le_bytes()).await.unwrap();
+            }
+        });
+
+        // All the datagrams are received in order despite a quarter of them being lost
+        let mut buffer = vec![0u8; UDP_MAX_MTU as usize];
+        for i in 0..COUNT {
+            let n = tokio::time::timeout(Duration::from_secs(30), b.read(&mut buffer))
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(&buffer[..n], &i.to_le_bytes());
+        }
+        writer.await.unwrap();
+        reader.abort();
+    }
+}
@@ -0,0 +1,68 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This crate is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](../zenoh/index.html)
+//!
+//! Implements a link over Unix domain sockets of type `SOCK_SEQPACKET`: like the stream sockets, they
+//! are connection-oriented and reliable, but they preserve the boundaries of the batches, which are
+//! therefore sent without the length prefix of the streamed links.
+use async_trait::async_trait;
+use zenoh_core::zconfigurable;
+use zenoh_link_commons::LocatorInspector;
+use zenoh_protocol::core::{endpoint::Address, Locator};
+use zenoh_result::ZResult;
+
+#[cfg(target_os = "linux")]
+mod unicast;
+#[cfg(target_os = "linux")]
+pub use unicast::*;
+
+pub const UNIXSOCKSEQPACKET_LOCATOR_PREFIX: &str = "unixseq";
+
+zconfigurable! {
+    // Default MTU in bytes.
+    // NOTE: A batch is sent in a single packet, whose size is bounded by the send buffer of the socket.
+    //       The default send buffer of the Unix domain sockets is large enough for 2^16 - 1 bytes.
+    static ref UNIXSOCKSEQPACKET_DEFAULT_MTU: u16 = u16::MAX;
+    // Amount of time in microseconds to throttle the accept loop upon an error.
+    // Default set to 100 ms.
+    #[mutable]
+    static ref UNIXSOCKSEQPACKET_ACCEPT_THROTTLE_TIME: u64 = 100_000;
+}
+
+/// Registers the values of this crate that can be modified at runtime.
+pub fn register_configurables() {
+    zenoh_core::configurable::register(&UNIXSOCKSEQPACKET_ACCEPT_THROTTLE_TIME);
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct UnixSockSeqpacketLocatorInspector;
+#[async_trait]
+impl LocatorInspector for UnixSockSeqpacketLocatorInspector {
+    fn protocol(&self) -> &str {
+        UNIXSOCKSEQPACKET_LOCATOR_PREFIX
+    }
+
+    async fn is_multicast(&self, _locator: &Locator) -> ZResult<bool> {
+        Ok(false)
+    }
+}
+
+pub fn get_unix_path_as_string(address: Address<'_>) -> String {
+    address.to_string()
+}
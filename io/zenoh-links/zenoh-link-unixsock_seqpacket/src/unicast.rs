@@ -0,0 +1,518 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use async_trait::async_trait;
+use socket2::{Domain, SockAddr, Socket, Type};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::remove_file;
+use std::io::{self, Read, Write};
+use std::net::Shutdown;
+use std::os::unix::io::RawFd;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::unix::AsyncFd;
+use tokio::sync::RwLock as AsyncRwLock;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+use zenoh_core::{zasyncread, zasyncwrite};
+use zenoh_link_commons::{
+    LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait, NewLinkChannelSender,
+};
+use zenoh_protocol::core::{EndPoint, Locator};
+use zenoh_result::{bail, zerror, ZResult};
+
+use super::{
+    get_unix_path_as_string, UNIXSOCKSEQPACKET_ACCEPT_THROTTLE_TIME, UNIXSOCKSEQPACKET_DEFAULT_MTU,
+    UNIXSOCKSEQPACKET_LOCATOR_PREFIX,
+};
+
+// The maximum number of pending connections of a listener
+const UNIXSOCKSEQPACKET_BACKLOG: i32 = 128;
+
+fn new_socket() -> io::Result<Socket> {
+    let socket = Socket::new(Domain::UNIX, Type::SEQPACKET, None)?;
+    socket.set_nonblocking(true)?;
+    Ok(socket)
+}
+
+pub struct LinkUnicastUnixSocketSeqpacket {
+    // The underlying socket, registered in the tokio reactor
+    socket: AsyncFd<Socket>,
+    // The Unix domain socket source path
+    src_locator: Locator,
+    // The Unix domain socket destination path (random UUIDv4)
+    dst_locator: Locator,
+}
+
+impl LinkUnicastUnixSocketSeqpacket {
+    fn new(
+        socket: AsyncFd<Socket>,
+        src_path: &str,
+        dst_path: &str,
+    ) -> LinkUnicastUnixSocketSeqpacket {
+        LinkUnicastUnixSocketSeqpacket {
+            socket,
+            src_locator: Locator::new(UNIXSOCKSEQPACKET_LOCATOR_PREFIX, src_path, "").unwrap(),
+            dst_locator: Locator::new(UNIXSOCKSEQPACKET_LOCATOR_PREFIX, dst_path, "").unwrap(),
+        }
+    }
+
+    // Send a whole packet
+    async fn send(&self, buffer: &[u8]) -> io::Result<usize> {
+        loop {
+            let mut guard = self.socket.writable().await?;
+            match guard.try_io(|inner| {
+                let mut socket: &Socket = inner.get_ref();
+                socket.write(buffer)
+            }) {
+                Ok(res) => return res,
+                Err(_would_block) => continue,
+            }
+        }
+    }
+
+    // Receive a whole packet
+    async fn recv(&self, buffer: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let mut guard = self.socket.readable().await?;
+            match guard.try_io(|inner| {
+                let mut socket: &Socket = inner.get_ref();
+                socket.read(buffer)
+            }) {
+                Ok(res) => return res,
+                Err(_would_block) => continue,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl LinkUnicastTrait for LinkUnicastUnixSocketSeqpacket {
+    async fn close(&self) -> ZResult<()> {
+        tracing::trace!("Closing UnixSocketSeqpacket link: {}", self);
+        let res = self.socket.get_ref().shutdown(Shutdown::Both);
+        tracing::trace!("UnixSocketSeqpacket link shutdown {}: {:?}", self, res);
+        res.map_err(|e| zerror!(e).into())
+    }
+
+    async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
+        self.send(buffer).await.map_err(|e| {
+            let e = zerror!("Write error on UnixSocketSeqpacket link {}: {}", self, e);
+            tracing::trace!("{}", e);
+            e.into()
+        })
+    }
+
+    async fn write_all(&self, buffer: &[u8]) -> ZResult<()> {
+        // A packet is either sent as a whole or not at all
+        let n = self.write(buffer).await?;
+        if n != buffer.len() {
+            bail!(
+                "Write error on UnixSocketSeqpacket link {}: sent {} bytes instead of {}",
+                self,
+                n,
+                buffer.len()
+            );
+        }
+        Ok(())
+    }
+
+    async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
+        let n = self.recv(buffer).await.map_err(|e| {
+            let e = zerror!("Read error on UnixSocketSeqpacket link {}: {}", self, e);
+            tracing::trace!("{}", e);
+            e
+        })?;
+        // An empty packet is only received when the peer closed the connection
+        if n == 0 {
+            bail!(
+                "Read error on UnixSocketSeqpacket link {}: connection closed",
+                self
+            );
+        }
+        Ok(n)
+    }
+
+    async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()> {
+        let n = self.read(buffer).await?;
+        if n != buffer.len() {
+            bail!(
+                "Read error on UnixSocketSeqpacket link {}: received {} bytes instead of {}",
+                self,
+                n,
+                buffer.len()
+            );
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn get_src(&self) -> &Locator {
+        &self.src_locator
+    }
+
+    #[inline(always)]
+    fn get_dst(&self) -> &Locator {
+        &self.dst_locator
+    }
+
+    #[inline(always)]
+    fn get_mtu(&self) -> u16 {
+        *UNIXSOCKSEQPACKET_DEFAULT_MTU
+    }
+
+    #[inline(always)]
+    fn get_interface_names(&self) -> Vec<String> {
+        // @TODO: Not supported for now
+        tracing::debug!(
+            "The get_interface_names for LinkUnicastUnixSocketSeqpacket is not supported"
+        );
+        vec![]
+    }
+
+    #[inline(always)]
+    fn is_reliable(&self) -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn is_streamed(&self) -> bool {
+        false
+    }
+}
+
+impl Drop for LinkUnicastUnixSocketSeqpacket {
+    fn drop(&mut self) {
+        // Close the underlying UnixSocketSeqpacket socket
+        let _ = self.socket.get_ref().shutdown(Shutdown::Both);
+    }
+}
+
+impl fmt::Display for LinkUnicastUnixSocketSeqpacket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} => {}", &self.src_locator, &self.dst_locator)?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for LinkUnicastUnixSocketSeqpacket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnixSocketSeqpacket")
+            .field("src", &self.src_locator)
+            .field("dst", &self.dst_locator)
+            .finish()
+    }
+}
+
+/*************************************/
+/*          LISTENER                 */
+/*************************************/
+struct ListenerUnixSocketSeqpacket {
+    endpoint: EndPoint,
+    token: CancellationToken,
+    handle: JoinHandle<ZResult<()>>,
+    lock_fd: RawFd,
+}
+
+impl ListenerUnixSocketSeqpacket {
+    fn new(
+        endpoint: EndPoint,
+        token: CancellationToken,
+        handle: JoinHandle<ZResult<()>>,
+        lock_fd: RawFd,
+    ) -> ListenerUnixSocketSeqpacket {
+        ListenerUnixSocketSeqpacket {
+            endpoint,
+            token,
+            handle,
+            lock_fd,
+        }
+    }
+
+    async fn stop(&self) {
+        self.token.cancel();
+    }
+}
+
+pub struct LinkManagerUnicastUnixSocketSeqpacket {
+    manager: NewLinkChannelSender,
+    listeners: Arc<AsyncRwLock<HashMap<String, ListenerUnixSocketSeqpacket>>>,
+}
+
+impl LinkManagerUnicastUnixSocketSeqpacket {
+    pub fn new(manager: NewLinkChannelSender) -> Self {
+        Self {
+            manager,
+            listeners: Arc::new(AsyncRwLock::new(HashMap::new())),
+        }
+    }
+}
+
+#[async_trait]
+impl LinkManagerUnicastTrait for LinkManagerUnicastUnixSocketSeqpacket {
+    async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
+        let path = get_unix_path_as_string(endpoint.address());
+
+        // Create the UnixSocketSeqpacket connection
+        let socket = SockAddr::unix(&path)
+            .and_then(|addr| {
+                let socket = new_socket()?;
+                socket.connect(&addr)?;
+                AsyncFd::new(socket)
+            })
+            .map_err(|e| {
+                let e = zerror!(
+                    "Can not create a new UnixSocketSeqpacket link bound to {:?}: {}",
+                    path,
+                    e
+                );
+                tracing::warn!("{}", e);
+                e
+            })?;
+
+        // The connecting socket is not bound to a path
+        let local_path = format!("{}", Uuid::new_v4());
+
+        let link = Arc::new(LinkUnicastUnixSocketSeqpacket::new(
+            socket,
+            &local_path,
+            &path,
+        ));
+
+        Ok(LinkUnicast(link))
+    }
+
+    async fn new_listener(&self, endpoint: EndPoint) -> ZResult<Locator> {
+        let path = get_unix_path_as_string(endpoint.address());
+
+        // Because of the lack of SO_REUSEADDR we have to check if the
+        // file is still there and if it is not used by another process.
+        // In order to do so we use a separate lock file: see the
+        // UnixSocketStream listener.
+        let lock_file_path = format!("{path}.lock");
+
+        let mut open_flags = nix::fcntl::OFlag::empty();
+        open_flags.insert(nix::fcntl::OFlag::O_CREAT);
+        open_flags.insert(nix::fcntl::OFlag::O_RDONLY);
+
+        let mut open_mode = nix::sys::stat::Mode::empty();
+        open_mode.insert(nix::sys::stat::Mode::S_IRUSR);
+        open_mode.insert(nix::sys::stat::Mode::S_IWUSR);
+
+        let lock_fd = nix::fcntl::open(
+            std::path::Path::new(&lock_file_path),
+            open_flags,
+            open_mode,
+        ).map_err(|e| {
+            let e = zerror!(
+                "Can not create a new UnixSocketSeqpacket listener on {} - Unable to open lock file: {}",
+                path, e
+            );
+            tracing::warn!("{}", e);
+            e
+        })?;
+
+        // We try to acquire the lock
+        nix::fcntl::flock(lock_fd, nix::fcntl::FlockArg::LockExclusiveNonblock).map_err(|e| {
+            let _ = nix::unistd::close(lock_fd);
+            let e = zerror!(
+                "Can not create a new UnixSocketSeqpacket listener on {} - Unable to acquire lock: {}",
+                path,
+                e
+            );
+            tracing::warn!("{}", e);
+            e
+        })?;
+
+        // Lock is acquired we can remove the socket file
+        let _ = remove_file(path.clone());
+
+        // Bind the Unix socket
+        let socket = SockAddr::unix(&path)
+            .and_then(|addr| {
+                let socket = new_socket()?;
+                socket.bind(&addr)?;
+                socket.listen(UNIXSOCKSEQPACKET_BACKLOG)?;
+                AsyncFd::new(socket)
+            })
+            .map_err(|e| {
+                let _ = nix::unistd::close(lock_fd);
+                let e = zerror!(
+                    "Can not create a new UnixSocketSeqpacket listener on {}: {}",
+                    path,
+                    e
+                );
+                tracing::warn!("{}", e);
+                e
+            })?;
+
+        // Spawn the accept loop for the listener
+        let token = CancellationToken::new();
+        let c_token = token.clone();
+        let mut listeners = zasyncwrite!(self.listeners);
+
+        let c_manager = self.manager.clone();
+        let c_listeners = self.listeners.clone();
+        let c_path = path.clone();
+
+        let task = async move {
+            // Wait for the accept loop to terminate
+            let res = accept_task(socket, &c_path, c_token, c_manager).await;
+            zasyncwrite!(c_listeners).remove(&c_path);
+            res
+        };
+        let handle = zenoh_runtime::ZRuntime::Acceptor.spawn(task);
+
+        let locator = endpoint.to_locator();
+        let listener = ListenerUnixSocketSeqpacket::new(endpoint, token, handle, lock_fd);
+        listeners.insert(path, listener);
+
+        Ok(locator)
+    }
+
+    async fn del_listener(&self, endpoint: &EndPoint) -> ZResult<()> {
+        let path = get_unix_path_as_string(endpoint.address());
+
+        // Stop the listener
+        let listener = zasyncwrite!(self.listeners).remove(&path).ok_or_else(|| {
+            let e = zerror!(
+                "Can not delete the UnixSocketSeqpacket listener because it has not been found: {}",
+                path
+            );
+            tracing::trace!("{}", e);
+            e
+        })?;
+
+        // Send the stop signal
+        listener.stop().await;
+        listener.handle.await??;
+
+        // Release the lock
+        let _ = nix::fcntl::flock(listener.lock_fd, nix::fcntl::FlockArg::UnlockNonblock);
+        let _ = nix::unistd::close(listener.lock_fd);
+        let _ = remove_file(path.clone());
+
+        // Remove the lock file
+        let lock_file_path = format!("{path}.lock");
+        let tmp = remove_file(lock_file_path);
+        tracing::trace!("UnixSocketSeqpacket lock file removal result: {:?}", tmp);
+
+        Ok(())
+    }
+
+    async fn get_listeners(&self) -> Vec<EndPoint> {
+        zasyncread!(self.listeners)
+            .values()
+            .map(|x| x.endpoint.clone())
+            .collect()
+    }
+
+    async fn get_locators(&self) -> Vec<Locator> {
+        zasyncread!(self.listeners)
+            .values()
+            .map(|x| x.endpoint.to_locator())
+            .collect()
+    }
+}
+
+async fn accept_task(
+    socket: AsyncFd<Socket>,
+    src_path: &str,
+    token: CancellationToken,
+    manager: NewLinkChannelSender,
+) -> ZResult<()> {
+    async fn accept(socket: &AsyncFd<Socket>) -> ZResult<AsyncFd<Socket>> {
+        loop {
+            let mut guard = socket.readable().await.map_err(|e| zerror!(e))?;
+            if let Ok(res) = guard.try_io(|inner| inner.get_ref().accept()) {
+                let (stream, _) = res.map_err(|e| zerror!(e))?;
+                stream.set_nonblocking(true).map_err(|e| zerror!(e))?;
+                return AsyncFd::new(stream).map_err(|e| zerror!(e).into());
+            }
+        }
+    }
+
+    tracing::trace!(
+        "Ready to accept UnixSocketSeqpacket connections on: {}",
+        src_path
+    );
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+
+            res = accept(&socket) => {
+                match res {
+                    Ok(stream) => {
+                        let dst_path = format!("{}", Uuid::new_v4());
+
+                        tracing::debug!("Accepted UnixSocketSeqpacket connection on: {}", src_path);
+
+                        // Create the new link object
+                        let link = Arc::new(LinkUnicastUnixSocketSeqpacket::new(
+                            stream, src_path, &dst_path,
+                        ));
+
+                        // Communicate the new link to the initial transport manager
+                        if let Err(e) = manager.send_async(LinkUnicast(link)).await {
+                            tracing::error!("{}-{}: {}", file!(), line!(), e)
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("{}. Hint: increase the system open file limit.", e);
+                        // Throttle the accept loop upon an error
+                        tokio::time::sleep(Duration::from_micros(UNIXSOCKSEQPACKET_ACCEPT_THROTTLE_TIME.get())).await;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn unixsock_seqpacket_boundaries() {
+        let path = std::env::temp_dir().join(format!("zenoh-seqpacket-{}", Uuid::new_v4()));
+        let endpoint: EndPoint = format!("{UNIXSOCKSEQPACKET_LOCATOR_PREFIX}/{}", path.display())
+            .parse()
+            .unwrap();
+
+        let (tx, rx) = flume::unbounded();
+        let listener = LinkManagerUnicastUnixSocketSeqpacket::new(tx);
+        listener.new_listener(endpoint.clone()).await.unwrap();
+
+        let (tx, _rx) = flume::unbounded();
+        let connector = LinkManagerUnicastUnixSocketSeqpacket::new(tx);
+        let link = connector.new_link(endpoint.clone()).await.unwrap();
+        let accepted = rx.recv_async().await.unwrap();
+        assert!(!link.is_streamed());
+
+        // Each write is received by a single read, without any framing
+        link.write_all(b"zenoh").await.unwrap();
+        link.write_all(b"seqpacket").await.unwrap();
+        let mut buffer = [0u8; 64];
+        let n = accepted.read(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..n], b"zenoh");
+        let n = accepted.read(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..n], b"seqpacket");
+
+        listener.del_listener(&endpoint).await.unwrap();
+    }
+}
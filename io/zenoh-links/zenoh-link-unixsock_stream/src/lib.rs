@@ -42,9 +42,15 @@ zconfigurable! {
     static ref UNIXSOCKSTREAM_DEFAULT_MTU: u16 = UNIXSOCKSTREAM_MAX_MTU;
     // Amount of time in microseconds to throttle the accept loop upon an error.
     // Default set to 100 ms.
+    #[mutable]
     static ref UNIXSOCKSTREAM_ACCEPT_THROTTLE_TIME: u64 = 100_000;
 }
 
+/// Registers the values of this crate that can be modified at runtime.
+pub fn register_configurables() {
+    zenoh_core::configurable::register(&UNIXSOCKSTREAM_ACCEPT_THROTTLE_TIME);
+}
+
 #[derive(Default, Clone, Copy)]
 pub struct UnixSockStreamLocatorInspector;
 #[async_trait]
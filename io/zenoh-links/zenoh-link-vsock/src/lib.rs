@@ -50,5 +50,11 @@ zconfigurable! {
     static ref VSOCK_DEFAULT_MTU: u16 = u16::MAX;
     // Amount of time in microseconds to throttle the accept loop upon an error.
     // Default set to 100 ms.
+    #[mutable]
     static ref VSOCK_ACCEPT_THROTTLE_TIME: u64 = 100_000;
 }
+
+/// Registers the values of this crate that can be modified at runtime.
+pub fn register_configurables() {
+    zenoh_core::configurable::register(&VSOCK_ACCEPT_THROTTLE_TIME);
+}
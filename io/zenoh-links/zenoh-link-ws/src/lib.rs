@@ -22,10 +22,12 @@ use std::net::SocketAddr;
 use url::Url;
 use zenoh_core::zconfigurable;
 use zenoh_link_commons::LocatorInspector;
-use zenoh_protocol::core::{endpoint::Address, Locator};
+use zenoh_protocol::core::{endpoint::Address, EndPoint, Locator};
 use zenoh_result::{bail, ZResult};
 mod unicast;
 pub use unicast::*;
+// The wss links use the same certificates as the tls links
+pub use zenoh_link_tls::TlsConfigurator as WssConfigurator;
 
 // Default MTU (WSS PDU) in bytes.
 // NOTE: Since TCP is a byte-stream oriented transport, theoretically it has
@@ -36,6 +38,7 @@ pub use unicast::*;
 const WS_MAX_MTU: u16 = u16::MAX;
 
 pub const WS_LOCATOR_PREFIX: &str = "ws";
+pub const WSS_LOCATOR_PREFIX: &str = "wss";
 
 #[derive(Default, Clone, Copy)]
 pub struct WsLocatorInspector;
@@ -49,12 +52,30 @@ impl LocatorInspector for WsLocatorInspector {
     }
 }
 
+#[derive(Default, Clone, Copy)]
+pub struct WssLocatorInspector;
+#[async_trait]
+impl LocatorInspector for WssLocatorInspector {
+    fn protocol(&self) -> &str {
+        WSS_LOCATOR_PREFIX
+    }
+    async fn is_multicast(&self, _locator: &Locator) -> ZResult<bool> {
+        Ok(false)
+    }
+}
+
 zconfigurable! {
     // Default MTU (TCP PDU) in bytes.
     static ref WS_DEFAULT_MTU: u16 = WS_MAX_MTU;
     // Amount of time in microseconds to throttle the accept loop upon an error.
     // Default set to 100 ms.
-    static ref TCP_ACCEPT_THROTTLE_TIME: u64 = 100_000;
+    #[mutable]
+    static ref WS_ACCEPT_THROTTLE_TIME: u64 = 100_000;
+}
+
+/// Registers the values of this crate that can be modified at runtime.
+pub fn register_configurables() {
+    zenoh_core::configurable::register(&WS_ACCEPT_THROTTLE_TIME);
 }
 
 pub async fn get_ws_addr(address: Address<'_>) -> ZResult<SocketAddr> {
@@ -64,12 +85,8 @@ pub async fn get_ws_addr(address: Address<'_>) -> ZResult<SocketAddr> {
     }
 }
 
-pub async fn get_ws_url(address: Address<'_>) -> ZResult<Url> {
-    match Url::parse(&format!(
-        "{}://{}",
-        WS_LOCATOR_PREFIX,
-        get_ws_addr(address).await?
-    )) {
+pub async fn get_ws_url(protocol: &str, address: Address<'_>) -> ZResult<Url> {
+    match Url::parse(&format!("{}://{}", protocol, get_ws_addr(address).await?)) {
         Ok(url) => Ok(url),
         Err(e) => bail!(
             "Couldn't resolve WebSocket locator address: {}: {}",
@@ -78,3 +95,24 @@ pub async fn get_ws_url(address: Address<'_>) -> ZResult<Url> {
         ),
     }
 }
+
+pub fn get_ws_max_frame_size(endpoint: &EndPoint) -> ZResult<Option<usize>> {
+    match endpoint.config().get(config::WS_MAX_FRAME_SIZE) {
+        Some(size) => match size.parse() {
+            Ok(size) => Ok(Some(size)),
+            Err(_) => bail!(
+                "Invalid {} for WebSocket endpoint {}: {}",
+                config::WS_MAX_FRAME_SIZE,
+                endpoint,
+                size
+            ),
+        },
+        None => Ok(None),
+    }
+}
+
+pub mod config {
+    /// The maximum size in bytes of the frames accepted on the link.
+    /// The MTU of the link is reduced accordingly.
+    pub const WS_MAX_FRAME_SIZE: &str = "max_frame_size";
+}
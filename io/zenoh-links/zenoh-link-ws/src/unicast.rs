@@ -22,53 +22,68 @@ use std::fmt;
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::{Mutex as AsyncMutex, RwLock as AsyncRwLock};
 use tokio::task::JoinHandle;
-use tokio_tungstenite::accept_async;
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
 use tokio_tungstenite::tungstenite::Message;
-use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::{accept_async_with_config, client_async_with_config};
 use tokio_util::sync::CancellationToken;
 use zenoh_core::{zasynclock, zasyncread, zasyncwrite};
 use zenoh_link_commons::{
     LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait, NewLinkChannelSender,
 };
+use zenoh_link_tls::{get_tls_server_name, TlsClientConfig, TlsServerConfig};
 use zenoh_protocol::core::{EndPoint, Locator};
 use zenoh_result::{bail, zerror, ZResult};
 
-use super::{get_ws_addr, get_ws_url, TCP_ACCEPT_THROTTLE_TIME, WS_DEFAULT_MTU, WS_LOCATOR_PREFIX};
+use super::{
+    get_ws_addr, get_ws_max_frame_size, get_ws_url, WSS_LOCATOR_PREFIX, WS_ACCEPT_THROTTLE_TIME,
+    WS_DEFAULT_MTU,
+};
+
+// The stream carrying the WebSocket: a TCP stream for ws, a TLS stream for wss
+trait WsStream: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> WsStream for T {}
+
+type WsSocket = WebSocketStream<Box<dyn WsStream>>;
+
+fn ws_config(max_frame_size: Option<usize>) -> WebSocketConfig {
+    let mut config = WebSocketConfig::default();
+    if let Some(size) = max_frame_size {
+        config.max_frame_size = Some(size);
+        config.max_message_size = Some(size);
+    }
+    config
+}
 
 pub struct LinkUnicastWs {
     // The inbound message stream as returned from the futures_util::stream::StreamExt::split method
-    recv: AsyncMutex<SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>>,
+    recv: AsyncMutex<SplitStream<WsSocket>>,
     // // The outbound message stream as returned from the futures_util::stream::StreamExt::split method
-    send: AsyncMutex<SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>>,
+    send: AsyncMutex<SplitSink<WsSocket, Message>>,
     // The source socket address of this link (address used on the local host)
     src_addr: SocketAddr,
     src_locator: Locator,
     // The destination socket address of this link (address used on the remote host)
     dst_addr: SocketAddr,
     dst_locator: Locator,
+    mtu: u16,
     // The leftovers if reading less than what available on the web socket.
     leftovers: AsyncMutex<Option<(Vec<u8>, usize, usize)>>,
 }
 
 impl LinkUnicastWs {
     fn new(
-        socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        socket: WsSocket,
+        protocol: &str,
         src_addr: SocketAddr,
         dst_addr: SocketAddr,
+        max_frame_size: Option<usize>,
     ) -> LinkUnicastWs {
-        // Set the TCP nodelay option
-        if let Err(err) = get_stream(&socket).set_nodelay(true) {
-            tracing::warn!(
-                "Unable to set NODEALY option on TCP link {} => {}: {}",
-                src_addr,
-                dst_addr,
-                err
-            );
-        }
-
         let (send, recv) = socket.split();
         let send = AsyncMutex::new(send);
         let recv = AsyncMutex::new(recv);
@@ -79,9 +94,14 @@ impl LinkUnicastWs {
             recv,
             send,
             src_addr,
-            src_locator: Locator::new(WS_LOCATOR_PREFIX, src_addr.to_string(), "").unwrap(),
+            src_locator: Locator::new(protocol, src_addr.to_string(), "").unwrap(),
             dst_addr,
-            dst_locator: Locator::new(WS_LOCATOR_PREFIX, dst_addr.to_string(), "").unwrap(),
+            dst_locator: Locator::new(protocol, dst_addr.to_string(), "").unwrap(),
+            // The batch size negotiated with the peer doesn't exceed the MTU, so that the
+            // frames it sends are accepted
+            mtu: max_frame_size.map_or(*WS_DEFAULT_MTU, |size| {
+                size.min(*WS_DEFAULT_MTU as usize) as u16
+            }),
             leftovers: AsyncMutex::new(None),
         }
     }
@@ -201,7 +221,7 @@ impl LinkUnicastTrait for LinkUnicastWs {
 
     #[inline(always)]
     fn get_mtu(&self) -> u16 {
-        *WS_DEFAULT_MTU
+        self.mtu
     }
 
     #[inline(always)]
@@ -294,9 +314,11 @@ impl LinkManagerUnicastWs {
 #[async_trait]
 impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
     async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
-        let dst_url = get_ws_url(endpoint.address()).await?;
+        let protocol = endpoint.protocol();
+        let dst_url = get_ws_url(protocol.as_str(), endpoint.address()).await?;
+        let max_frame_size = get_ws_max_frame_size(&endpoint)?;
 
-        let (stream, _) = tokio_tungstenite::connect_async(&dst_url)
+        let tcp_stream = TcpStream::connect(get_ws_addr(endpoint.address()).await?)
             .await
             .map_err(|e| {
                 zerror!(
@@ -306,7 +328,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
                 )
             })?;
 
-        let src_addr = get_stream(&stream).local_addr().map_err(|e| {
+        let src_addr = tcp_stream.local_addr().map_err(|e| {
             zerror!(
                 "Can not create a new WebSocket link bound to {}: {}",
                 dst_url,
@@ -314,7 +336,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
             )
         })?;
 
-        let dst_addr = get_stream(&stream).peer_addr().map_err(|e| {
+        let dst_addr = tcp_stream.peer_addr().map_err(|e| {
             zerror!(
                 "Can not create a new WebSocket link bound to {}: {}",
                 dst_url,
@@ -322,13 +344,64 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
             )
         })?;
 
-        let link = Arc::new(LinkUnicastWs::new(stream, src_addr, dst_addr));
+        set_nodelay(&tcp_stream, src_addr, dst_addr);
+
+        let stream: Box<dyn WsStream> = if protocol.as_str() == WSS_LOCATOR_PREFIX {
+            let client_config = TlsClientConfig::new(&endpoint.config())
+                .await
+                .map_err(|e| zerror!("Cannot create a new WebSocket link to {endpoint}: {e}"))?;
+            let connector = TlsConnector::from(Arc::new(client_config.client_config));
+            let server_name = get_tls_server_name(&endpoint.address())?.to_owned();
+            let tls_stream = connector
+                .connect(server_name, tcp_stream)
+                .await
+                .map_err(|e| {
+                    zerror!(
+                        "Can not create a new WebSocket link bound to {}: {}",
+                        dst_url,
+                        e
+                    )
+                })?;
+            Box::new(tls_stream)
+        } else {
+            Box::new(tcp_stream)
+        };
+
+        let (stream, _) =
+            client_async_with_config(&dst_url, stream, Some(ws_config(max_frame_size)))
+                .await
+                .map_err(|e| {
+                    zerror!(
+                        "Can not create a new WebSocket link bound to {}: {}",
+                        dst_url,
+                        e
+                    )
+                })?;
+
+        let link = Arc::new(LinkUnicastWs::new(
+            stream,
+            protocol.as_str(),
+            src_addr,
+            dst_addr,
+            max_frame_size,
+        ));
 
         Ok(LinkUnicast(link))
     }
 
     async fn new_listener(&self, mut endpoint: EndPoint) -> ZResult<Locator> {
         let addr = get_ws_addr(endpoint.address()).await?;
+        let max_frame_size = get_ws_max_frame_size(&endpoint)?;
+
+        // Initialize the TlsAcceptor for wss
+        let acceptor = if endpoint.protocol().as_str() == WSS_LOCATOR_PREFIX {
+            let server_config = TlsServerConfig::new(&endpoint.config())
+                .await
+                .map_err(|e| zerror!("Cannot create a new WebSocket listener on {addr}. {e}"))?;
+            Some(TlsAcceptor::from(Arc::new(server_config.server_config)))
+        } else {
+            None
+        };
 
         // Bind the TCP socket
         let socket = TcpListener::bind(addr).await.map_err(|e| {
@@ -361,10 +434,19 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
         let c_manager = self.manager.clone();
         let c_listeners = self.listeners.clone();
         let c_addr = local_addr;
+        let c_protocol = endpoint.protocol().to_string();
 
         let task = async move {
             // Wait for the accept loop to terminate
-            let res = accept_task(socket, c_token, c_manager).await;
+            let res = accept_task(
+                socket,
+                c_protocol,
+                acceptor,
+                max_frame_size,
+                c_token,
+                c_manager,
+            )
+            .await;
             zasyncwrite!(c_listeners).remove(&c_addr);
             res
         };
@@ -417,7 +499,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
                         for ipaddr in ipaddrs {
                             if !ipaddr.is_loopback() && !ipaddr.is_multicast() && ipaddr.is_ipv4() {
                                 let l = Locator::new(
-                                    WS_LOCATOR_PREFIX,
+                                    value.endpoint.protocol(),
                                     SocketAddr::new(ipaddr, key.port()).to_string(),
                                     value.endpoint.metadata(),
                                 )
@@ -434,7 +516,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
                         for ipaddr in ipaddrs {
                             if !ipaddr.is_loopback() && !ipaddr.is_multicast() && ipaddr.is_ipv6() {
                                 let l = Locator::new(
-                                    WS_LOCATOR_PREFIX,
+                                    value.endpoint.protocol(),
                                     SocketAddr::new(ipaddr, key.port()).to_string(),
                                     value.endpoint.metadata(),
                                 )
@@ -457,6 +539,9 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
 
 async fn accept_task(
     socket: TcpListener,
+    protocol: String,
+    acceptor: Option<TlsAcceptor>,
+    max_frame_size: Option<usize>,
     token: CancellationToken,
     manager: NewLinkChannelSender,
 ) -> ZResult<()> {
@@ -489,7 +574,7 @@ async fn accept_task(
                         //       Linux systems this limit can be changed by using the "ulimit" command line
                         //       tool. In case of systemd-based systems, this can be changed by using the
                         //       "sysctl" command line tool.
-                        tokio::time::sleep(Duration::from_micros(*TCP_ACCEPT_THROTTLE_TIME)).await;
+                        tokio::time::sleep(Duration::from_micros(WS_ACCEPT_THROTTLE_TIME.get())).await;
                         continue;
                     }
                 }
@@ -504,15 +589,34 @@ async fn accept_task(
             dst_addr
         );
 
-        let stream = accept_async(MaybeTlsStream::Plain(stream))
-            .await
-            .map_err(|e| {
-                let e = zerror!("Error when creating the WebSocket session: {}", e);
-                tracing::trace!("{}", e);
-                e
-            })?;
+        set_nodelay(&stream, src_addr, dst_addr);
+
+        let stream: Box<dyn WsStream> = match &acceptor {
+            Some(acceptor) => match acceptor.accept(stream).await {
+                Ok(tls_stream) => Box::new(tls_stream),
+                Err(e) => {
+                    tracing::warn!("Error when accepting the TLS connection: {}", e);
+                    continue;
+                }
+            },
+            None => Box::new(stream),
+        };
+
+        let stream = match accept_async_with_config(stream, Some(ws_config(max_frame_size))).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                tracing::warn!("Error when creating the WebSocket session: {}", e);
+                continue;
+            }
+        };
         // Create the new link object
-        let link = Arc::new(LinkUnicastWs::new(stream, src_addr, dst_addr));
+        let link = Arc::new(LinkUnicastWs::new(
+            stream,
+            &protocol,
+            src_addr,
+            dst_addr,
+            max_frame_size,
+        ));
 
         // Communicate the new link to the initial transport manager
         if let Err(e) = manager.send_async(LinkUnicast(link)).await {
@@ -523,13 +627,14 @@ async fn accept_task(
     Ok(())
 }
 
-fn get_stream(ws_stream: &WebSocketStream<MaybeTlsStream<TcpStream>>) -> &TcpStream {
-    match ws_stream.get_ref() {
-        MaybeTlsStream::Plain(s) => s,
-        // This two are available only if the TLS features are enabled for
-        // tokio_tungstenite.
-        // MaybeTlsStream::NativeTls(s) => s.get_ref().get_ref(),
-        // MaybeTlsStream::Rustls(s) => s.get_ref().0
-        _ => panic!(),
+fn set_nodelay(stream: &TcpStream, src_addr: SocketAddr, dst_addr: SocketAddr) {
+    // Set the TCP nodelay option
+    if let Err(err) = stream.set_nodelay(true) {
+        tracing::warn!(
+            "Unable to set NODEALY option on TCP link {} => {}: {}",
+            src_addr,
+            dst_addr,
+            err
+        );
     }
 }
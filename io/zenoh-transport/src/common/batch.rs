@@ -28,7 +28,7 @@ use zenoh_protocol::{
 };
 use zenoh_result::{zerror, ZResult};
 #[cfg(feature = "transport_compression")]
-use {std::sync::Arc, zenoh_protocol::common::imsg};
+use {std::str::FromStr, std::sync::Arc, zenoh_protocol::common::imsg, zenoh_result::bail};
 
 const L_LEN: usize = (BatchSize::BITS / 8) as usize;
 const H_LEN: usize = BatchHeader::SIZE;
@@ -76,6 +76,44 @@ macro_rules! zsplit_mut {
     }};
 }
 
+// Compression algorithm
+#[cfg(feature = "transport_compression")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CompressionAlgorithm {
+    #[default]
+    Lz4,
+    Zstd,
+}
+
+#[cfg(feature = "transport_compression")]
+impl CompressionAlgorithm {
+    /// The mask of the algorithms this node is able to decompress, advertised during the establishment.
+    pub const SUPPORTED: u64 = Self::Lz4.mask() | Self::Zstd.mask();
+
+    pub const fn mask(&self) -> u64 {
+        match self {
+            Self::Lz4 => 1, // 1 << 0
+            Self::Zstd => 1 << 1,
+        }
+    }
+}
+
+#[cfg(feature = "transport_compression")]
+impl FromStr for CompressionAlgorithm {
+    type Err = zenoh_result::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lz4" => Ok(Self::Lz4),
+            "zstd" => Ok(Self::Zstd),
+            _ => bail!(
+                "Unknown compression algorithm: {} (lz4 or zstd expected)",
+                s
+            ),
+        }
+    }
+}
+
 // Batch config
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct BatchConfig {
@@ -83,6 +121,8 @@ pub struct BatchConfig {
     pub is_streamed: bool,
     #[cfg(feature = "transport_compression")]
     pub is_compression: bool,
+    #[cfg(feature = "transport_compression")]
+    pub compression_algorithm: CompressionAlgorithm,
 }
 
 impl Default for BatchConfig {
@@ -92,6 +132,8 @@ impl Default for BatchConfig {
             is_streamed: false,
             #[cfg(feature = "transport_compression")]
             is_compression: false,
+            #[cfg(feature = "transport_compression")]
+            compression_algorithm: CompressionAlgorithm::default(),
         }
     }
 }
@@ -116,7 +158,10 @@ impl BatchConfig {
         #[cfg(feature = "transport_compression")]
         {
             self.is_compression
-                .then_some(BatchHeader::new(BatchHeader::COMPRESSION))
+                .then_some(BatchHeader::new(match self.compression_algorithm {
+                    CompressionAlgorithm::Lz4 => BatchHeader::COMPRESSION,
+                    CompressionAlgorithm::Zstd => BatchHeader::COMPRESSION | BatchHeader::ZSTD,
+                }))
         }
     }
 
@@ -138,6 +183,8 @@ impl BatchHeader {
     const SIZE: usize = 1;
     #[cfg(feature = "transport_compression")]
     const COMPRESSION: u8 = 1; // 1 << 0
+    #[cfg(feature = "transport_compression")]
+    const ZSTD: u8 = 1 << 1; // if COMPRESSION==1: compressed with zstd instead of lz4
 
     #[cfg(feature = "transport_compression")]
     const fn new(h: u8) -> Self {
@@ -155,6 +202,12 @@ impl BatchHeader {
     pub fn is_compression(&self) -> bool {
         imsg::has_flag(self.as_u8(), Self::COMPRESSION)
     }
+
+    #[cfg(feature = "transport_compression")]
+    #[inline(always)]
+    pub fn is_zstd(&self) -> bool {
+        imsg::has_flag(self.as_u8(), Self::ZSTD)
+    }
 }
 
 // WRITE BATCH
@@ -321,20 +374,27 @@ impl WBatch {
         // Compress the actual content
         let (_length, _header, payload) = Self::split(self.buffer.as_slice(), &self.config);
         let mut writer = support.writer();
-        writer
-            .with_slot(writer.remaining(), |b| {
-                lz4_flex::block::compress_into(payload, b).unwrap_or(0)
+        let algorithm = self.config.compression_algorithm;
+        let is_compressed = writer
+            .with_slot(writer.remaining(), |b| match algorithm {
+                CompressionAlgorithm::Lz4 => {
+                    lz4_flex::block::compress_into(payload, b).unwrap_or(0)
+                }
+                CompressionAlgorithm::Zstd => {
+                    zstd::bulk::compress_to_buffer(payload, b, zstd::DEFAULT_COMPRESSION_LEVEL)
+                        .unwrap_or(0)
+                }
             })
-            .map_err(|_| zerror!("Compression error"))?;
+            .is_ok();
 
-        // Verify wether the resulting compressed data is smaller than the initial input
-        if support.len() < self.buffer.len() {
+        // Verify wether the data has been compressed in less bytes than the initial input
+        if is_compressed && support.len() < self.buffer.len() {
             Ok(Finalize::Buffer)
         } else {
             // Keep the original uncompressed buffer and unset the compression flag from the header
             let (_l, h, _p) = Self::split_mut(self.buffer.as_mut_slice(), &self.config);
             let h = h.first_mut().ok_or_else(|| zerror!("Empty BatchHeader"))?;
-            *h &= !BatchHeader::COMPRESSION;
+            *h &= !(BatchHeader::COMPRESSION | BatchHeader::ZSTD);
             Ok(Finalize::Batch)
         }
     }
@@ -437,7 +497,7 @@ impl RBatch {
                 let header = BatchHeader::new(b);
 
                 if header.is_compression() {
-                    let zslice = self.decompress(p, buff)?;
+                    let zslice = self.decompress(header, p, buff)?;
                     self.buffer = zslice;
                     return Ok(());
                 }
@@ -453,13 +513,23 @@ impl RBatch {
     }
 
     #[cfg(feature = "transport_compression")]
-    fn decompress<T>(&self, payload: &[u8], mut buff: impl FnMut() -> T) -> ZResult<ZSlice>
+    fn decompress<T>(
+        &self,
+        header: BatchHeader,
+        payload: &[u8],
+        mut buff: impl FnMut() -> T,
+    ) -> ZResult<ZSlice>
     where
         T: ZSliceBuffer + 'static,
     {
         let mut into = (buff)();
-        let n = lz4_flex::block::decompress_into(payload, into.as_mut_slice())
-            .map_err(|_| zerror!("Decompression error"))?;
+        let n = if header.is_zstd() {
+            zstd::bulk::decompress_to_buffer(payload, into.as_mut_slice())
+                .map_err(|_| zerror!("Decompression error"))?
+        } else {
+            lz4_flex::block::decompress_into(payload, into.as_mut_slice())
+                .map_err(|_| zerror!("Decompression error"))?
+        };
         let zslice = ZSlice::make(Arc::new(into), 0, n)
             .map_err(|_| zerror!("Invalid decompression buffer length"))?;
         Ok(zslice)
@@ -527,6 +597,12 @@ mod tests {
                     is_streamed: rng.gen_bool(0.5),
                     #[cfg(feature = "transport_compression")]
                     is_compression: rng.gen_bool(0.5),
+                    #[cfg(feature = "transport_compression")]
+                    compression_algorithm: if rng.gen_bool(0.5) {
+                        CompressionAlgorithm::Zstd
+                    } else {
+                        CompressionAlgorithm::Lz4
+                    },
                 };
                 let mut wbatch = WBatch::new(config);
                 wbatch.encode(&msg_in).unwrap();
@@ -568,15 +644,17 @@ mod tests {
             is_streamed: false,
             #[cfg(feature = "transport_compression")]
             is_compression: false,
+            ..Default::default()
         };
         let mut batch = WBatch::new(config);
 
-        let tmsg: TransportMessage = KeepAlive.into();
+        let tmsg: TransportMessage = KeepAlive::default().into();
         let nmsg: NetworkMessage = Push {
             wire_expr: WireExpr::empty(),
             ext_qos: ext::QoSType::new(Priority::default(), CongestionControl::Block, false),
             ext_tstamp: None,
             ext_nodeid: ext::NodeIdType::default(),
+            ext_hoplimit: None,
             payload: PushBody::Put(Put {
                 timestamp: None,
                 encoding: Encoding::default(),
@@ -584,6 +662,7 @@ mod tests {
                 #[cfg(feature = "shared-memory")]
                 ext_shm: None,
                 ext_attachment: None,
+                ext_tbound: None,
                 ext_unknown: vec![],
                 payload: ZBuf::from(vec![0u8; 8]),
             }),
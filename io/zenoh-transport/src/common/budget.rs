@@ -0,0 +1,214 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc, RwLock,
+};
+use zenoh_core::{zread, zwrite};
+
+/// An event notified by a [`MemoryBudget`] when its usage crosses its limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryBudgetEvent {
+    /// The bytes buffered in the transmission queues exceed the limit:
+    /// the droppable (i.e. best-effort) messages are shed until the usage falls back below it.
+    Exceeded { used: usize, limit: usize },
+    /// The bytes buffered in the transmission queues are back below the limit.
+    Recovered { used: usize, limit: usize },
+}
+
+type MemoryBudgetCallback = Arc<dyn Fn(MemoryBudgetEvent) + Send + Sync>;
+
+/// A memory budget shared by all the transports of a [`TransportManager`](crate::TransportManager),
+/// accounting the bytes serialized in their transmission queues and not yet sent.
+///
+/// When the budget is exceeded, the droppable messages are shed before being serialized,
+/// while the non-droppable ones keep being enqueued.
+#[derive(Default)]
+pub struct MemoryBudget {
+    limit: Option<usize>,
+    used: AtomicUsize,
+    exceeded: AtomicBool,
+    callbacks: RwLock<Vec<MemoryBudgetCallback>>,
+}
+
+impl MemoryBudget {
+    pub fn new(limit: Option<usize>) -> Self {
+        Self {
+            limit,
+            ..Default::default()
+        }
+    }
+
+    /// The maximum number of bytes to buffer, `None` if unlimited.
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// The number of bytes currently buffered.
+    pub fn used(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    /// Whether the droppable messages are currently being shed.
+    pub fn is_exceeded(&self) -> bool {
+        self.exceeded.load(Ordering::Relaxed)
+    }
+
+    /// Register a callback notified every time the budget is exceeded or recovered.
+    pub fn on_event<F>(&self, callback: F)
+    where
+        F: Fn(MemoryBudgetEvent) + Send + Sync + 'static,
+    {
+        zwrite!(self.callbacks).push(Arc::new(callback));
+    }
+
+    pub(crate) fn alloc(&self, bytes: usize) {
+        let Some(limit) = self.limit else {
+            return;
+        };
+        let used = self.used.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        if used > limit && !self.exceeded.swap(true, Ordering::Relaxed) {
+            tracing::warn!(
+                "Memory budget exceeded ({} > {} bytes): shedding best-effort traffic",
+                used,
+                limit
+            );
+            self.notify(MemoryBudgetEvent::Exceeded { used, limit });
+        }
+    }
+
+    pub(crate) fn free(&self, bytes: usize) {
+        let Some(limit) = self.limit else {
+            return;
+        };
+        let used = self
+            .used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |u| {
+                Some(u.saturating_sub(bytes))
+            })
+            .unwrap_or_default()
+            .saturating_sub(bytes);
+        if used <= limit && self.exceeded.swap(false, Ordering::Relaxed) {
+            tracing::info!(
+                "Memory budget recovered ({} <= {} bytes): resuming best-effort traffic",
+                used,
+                limit
+            );
+            self.notify(MemoryBudgetEvent::Recovered { used, limit });
+        }
+    }
+
+    fn notify(&self, event: MemoryBudgetEvent) {
+        for callback in zread!(self.callbacks).iter() {
+            callback(event);
+        }
+    }
+}
+
+/// The share of a [`MemoryBudget`] used by the batches queued in a transmission pipeline.
+///
+/// The bytes of the batches still queued when the pipeline is dropped (e.g. when its TX task
+/// failed before draining it) are given back to the budget.
+pub(crate) struct PipelineBudget {
+    budget: Arc<MemoryBudget>,
+    queued: AtomicUsize,
+}
+
+impl PipelineBudget {
+    pub(crate) fn new(budget: Arc<MemoryBudget>) -> Self {
+        Self {
+            budget,
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn is_exceeded(&self) -> bool {
+        self.budget.is_exceeded()
+    }
+
+    pub(crate) fn alloc(&self, bytes: usize) {
+        self.queued.fetch_add(bytes, Ordering::Relaxed);
+        self.budget.alloc(bytes);
+    }
+
+    pub(crate) fn free(&self, bytes: usize) {
+        let _ = self
+            .queued
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |q| {
+                Some(q.saturating_sub(bytes))
+            });
+        self.budget.free(bytes);
+    }
+}
+
+impl Drop for PipelineBudget {
+    fn drop(&mut self) {
+        let queued = *self.queued.get_mut();
+        if queued > 0 {
+            self.budget.free(queued);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_budget() {
+        let budget = MemoryBudget::new(Some(100));
+        let events = Arc::new(std::sync::Mutex::new(vec![]));
+        let c_events = events.clone();
+        budget.on_event(move |e| c_events.lock().unwrap().push(e));
+
+        budget.alloc(60);
+        assert!(!budget.is_exceeded());
+        budget.alloc(60);
+        assert!(budget.is_exceeded());
+        budget.alloc(10);
+        budget.free(20);
+        assert!(budget.is_exceeded());
+        budget.free(60);
+        assert!(!budget.is_exceeded());
+        assert_eq!(budget.used(), 50);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                MemoryBudgetEvent::Exceeded {
+                    used: 120,
+                    limit: 100
+                },
+                MemoryBudgetEvent::Recovered {
+                    used: 50,
+                    limit: 100
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn pipeline_budget() {
+        let budget = Arc::new(MemoryBudget::new(Some(100)));
+        let pipeline = PipelineBudget::new(budget.clone());
+        pipeline.alloc(80);
+        pipeline.free(30);
+        budget.alloc(10);
+        assert_eq!(budget.used(), 60);
+
+        // The bytes still queued in the pipeline are given back on drop
+        drop(pipeline);
+        assert_eq!(budget.used(), 10);
+    }
+}
@@ -12,9 +12,13 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 pub mod batch;
+pub mod budget;
 pub(crate) mod defragmentation;
 pub(crate) mod pipeline;
 pub(crate) mod priority;
+pub(crate) mod rate_limit;
+pub(crate) mod rtt;
+pub(crate) mod scheduler;
 pub(crate) mod seq_num;
 #[cfg(feature = "stats")]
 pub mod stats;
@@ -15,6 +15,7 @@ use crate::common::batch::BatchConfig;
 //
 use super::{
     batch::{Encode, WBatch},
+    budget::{MemoryBudget, PipelineBudget},
     priority::{TransportChannelTx, TransportPriorityTx},
 };
 use flume::{bounded, Receiver, Sender};
@@ -22,7 +23,7 @@ use ringbuffer_spsc::{RingBuffer, RingBufferReader, RingBufferWriter};
 use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::Duration;
 use std::{
-    sync::atomic::{AtomicBool, AtomicU16, Ordering},
+    sync::atomic::{AtomicBool, AtomicU16, AtomicU32, AtomicUsize, Ordering},
     time::Instant,
 };
 use zenoh_buffers::{
@@ -51,6 +52,18 @@ type NanoSeconds = u32;
 const RBLEN: usize = QueueSizeConf::MAX;
 const TSLOT: NanoSeconds = 100;
 
+// Serialize a message on a batch, accounting the serialized bytes in the memory budget
+#[inline]
+fn encode<M, O>(batch: &mut WBatch, budget: &PipelineBudget, msg: M) -> O
+where
+    for<'b> &'b mut WBatch: Encode<M, Output = O>,
+{
+    let len = batch.len();
+    let res = (&mut *batch).encode(msg);
+    budget.alloc(batch.len().saturating_sub(len) as usize);
+    res
+}
+
 // Inner structure to reuse serialization batches
 struct StageInRefill {
     n_ref_r: Receiver<()>,
@@ -71,17 +84,62 @@ impl StageInRefill {
     }
 }
 
+// Inner structure to estimate the arrival rate of the messages,
+// as a moving average of the interval between them
+struct ArrivalRate {
+    last: Option<Instant>,
+    interval: Arc<AtomicU32>,
+}
+
+impl ArrivalRate {
+    // The weight of a new interval in the moving average, as a power of 2
+    const WEIGHT: u32 = 3;
+
+    fn new(interval: Arc<AtomicU32>) -> Self {
+        Self {
+            last: None,
+            interval,
+        }
+    }
+
+    #[inline]
+    fn arrival(&mut self) {
+        let now = Instant::now();
+        if let Some(last) = self.last.replace(now) {
+            let sample = now
+                .duration_since(last)
+                .as_nanos()
+                .min(NanoSeconds::MAX as u128) as NanoSeconds;
+            let average = self.interval.load(Ordering::Relaxed);
+            // No estimation yet
+            let average = if average == NanoSeconds::MAX {
+                sample
+            } else {
+                average - (average >> Self::WEIGHT) + (sample >> Self::WEIGHT)
+            };
+            self.interval.store(average, Ordering::Relaxed);
+        }
+    }
+}
+
 // Inner structure to link the initial stage with the final stage of the pipeline
 struct StageInOut {
     n_out_w: Sender<()>,
     s_out_w: RingBufferWriter<WBatch, RBLEN>,
     bytes: Arc<AtomicU16>,
     backoff: Arc<AtomicBool>,
+    // Only tracked when a latency target is configured
+    arrivals: Option<ArrivalRate>,
+    // The number of batches handed to the stage out and not yet written on the link
+    in_flight: Arc<AtomicUsize>,
 }
 
 impl StageInOut {
     #[inline]
-    fn notify(&self, bytes: BatchSize) {
+    fn notify(&mut self, bytes: BatchSize) {
+        if let Some(arrivals) = self.arrivals.as_mut() {
+            arrivals.arrival();
+        }
         self.bytes.store(bytes, Ordering::Relaxed);
         if !self.backoff.load(Ordering::Relaxed) {
             let _ = self.n_out_w.try_send(());
@@ -90,6 +148,7 @@ impl StageInOut {
 
     #[inline]
     fn move_batch(&mut self, batch: WBatch) {
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
         let _ = self.s_out_w.push(batch);
         self.bytes.store(0, Ordering::Relaxed);
         let _ = self.n_out_w.try_send(());
@@ -124,6 +183,7 @@ struct StageIn {
     s_out: StageInOut,
     mutex: StageInMutex,
     fragbuf: ZBuf,
+    budget: Arc<PipelineBudget>,
 }
 
 impl StageIn {
@@ -190,7 +250,7 @@ impl StageIn {
         // Get the current serialization batch.
         let mut batch = zgetbatch_rets!(false, {});
         // Attempt the serialization on the current batch
-        let e = match batch.encode(&*msg) {
+        let e = match encode(&mut batch, &self.budget, &*msg) {
             Ok(_) => zretok!(batch),
             Err(e) => e,
         };
@@ -210,7 +270,7 @@ impl StageIn {
 
         if let BatchError::NewFrame = e {
             // Attempt a serialization with a new frame
-            if batch.encode((&*msg, &frame)).is_ok() {
+            if encode(&mut batch, &self.budget, (&*msg, &frame)).is_ok() {
                 zretok!(batch);
             }
         }
@@ -222,7 +282,7 @@ impl StageIn {
         }
 
         // Attempt a second serialization on fully empty batch
-        if batch.encode((&*msg, &frame)).is_ok() {
+        if encode(&mut batch, &self.budget, (&*msg, &frame)).is_ok() {
             zretok!(batch);
         }
 
@@ -252,7 +312,7 @@ impl StageIn {
             batch = zgetbatch_rets!(true, tch.sn.set(sn).unwrap());
 
             // Serialize the message fragmnet
-            match batch.encode((&mut reader, &mut fragment)) {
+            match encode(&mut batch, &self.budget, (&mut reader, &mut fragment)) {
                 Ok(_) => {
                     // Update the SN
                     fragment.sn = tch.sn.get();
@@ -321,7 +381,7 @@ impl StageIn {
         let mut batch = zgetbatch_rets!();
         // Attempt the serialization on the current batch
         // Attempt the serialization on the current batch
-        match batch.encode(&msg) {
+        match encode(&mut batch, &self.budget, &msg) {
             Ok(_) => zretok!(batch),
             Err(_) => {
                 if !batch.is_empty() {
@@ -333,7 +393,7 @@ impl StageIn {
 
         // The first serialization attempt has failed. This means that the current
         // batch is full. Therefore, we move the current batch to stage out.
-        batch.encode(&msg).is_ok()
+        encode(&mut batch, &self.budget, &msg).is_ok()
     }
 }
 
@@ -351,6 +411,8 @@ struct Backoff {
     last_bytes: BatchSize,
     bytes: Arc<AtomicU16>,
     backoff: Arc<AtomicBool>,
+    // The beginning of the current backoff
+    started: Option<Instant>,
 }
 
 impl Backoff {
@@ -360,12 +422,14 @@ impl Backoff {
             last_bytes: 0,
             bytes,
             backoff,
+            started: None,
         }
     }
 
     fn next(&mut self) {
         if self.retry_time == 0 {
             self.retry_time = TSLOT;
+            self.started = Some(Instant::now());
             self.backoff.store(true, Ordering::Relaxed);
         } else {
             match self.retry_time.checked_mul(2) {
@@ -385,18 +449,60 @@ impl Backoff {
 
     fn stop(&mut self) {
         self.retry_time = 0;
+        self.started = None;
         self.backoff.store(false, Ordering::Relaxed);
     }
 }
 
+// Inner structure to flush the batches within a latency target: the current batch is flushed
+// as soon as the next message is not expected before the target, instead of backing off until
+// the batch stops growing. Under low load the messages are thus sent right away, and under high
+// load the batching is bounded by the target.
+struct LatencyTarget {
+    target: NanoSeconds,
+    // The moving average of the interval between the messages, NanoSeconds::MAX if unknown
+    interval: Arc<AtomicU32>,
+}
+
+impl LatencyTarget {
+    fn new(target: Duration, interval: Arc<AtomicU32>) -> Self {
+        Self {
+            target: target.as_nanos().min(NanoSeconds::MAX as u128) as NanoSeconds,
+            interval,
+        }
+    }
+
+    // Whether the current batch, waited for `elapsed`, is to be flushed
+    fn is_due(&self, elapsed: NanoSeconds) -> bool {
+        elapsed.saturating_add(self.interval.load(Ordering::Relaxed)) >= self.target
+    }
+
+    // The time left to wait for the current batch, waited for `elapsed`
+    fn remaining(&self, elapsed: NanoSeconds) -> NanoSeconds {
+        self.target.saturating_sub(elapsed).max(TSLOT)
+    }
+}
+
 // Inner structure to link the final stage with the initial stage of the pipeline
 struct StageOutIn {
     s_out_r: RingBufferReader<WBatch, RBLEN>,
     current: Arc<Mutex<Option<WBatch>>>,
     backoff: Backoff,
+    latency_target: Option<LatencyTarget>,
+    in_flight: Arc<AtomicUsize>,
 }
 
 impl StageOutIn {
+    // Take the current batch from the stage in, whose lock is held
+    #[inline]
+    fn take_current(&self, current: &mut Option<WBatch>) -> Option<WBatch> {
+        let batch = current.take();
+        if batch.is_some() {
+            self.in_flight.fetch_add(1, Ordering::AcqRel);
+        }
+        batch
+    }
+
     #[inline]
     fn try_pull(&mut self) -> Pull {
         if let Some(batch) = self.s_out_r.pull() {
@@ -412,6 +518,18 @@ impl StageOutIn {
         let old_bytes = self.backoff.last_bytes;
         self.backoff.last_bytes = new_bytes;
 
+        // The time waited for the current batch, and whether it is to be flushed within
+        // the latency target if any
+        let elapsed = self
+            .backoff
+            .started
+            .map(|s| s.elapsed().as_nanos().min(NanoSeconds::MAX as u128) as NanoSeconds)
+            .unwrap_or(0);
+        let is_due = self
+            .latency_target
+            .as_ref()
+            .map_or(true, |latency_target| latency_target.is_due(elapsed));
+
         match new_bytes.cmp(&old_bytes) {
             std::cmp::Ordering::Equal => {
                 // No new bytes have been written on the batch, try to pull
@@ -422,15 +540,18 @@ impl StageOutIn {
                         return Pull::Some(batch);
                     }
 
-                    // An incomplete (non-empty) batch is available in the state IN pipeline.
-                    match g.take() {
-                        Some(batch) => {
-                            self.backoff.stop();
-                            return Pull::Some(batch);
-                        }
-                        None => {
-                            self.backoff.stop();
-                            return Pull::None;
+                    // An incomplete (non-empty) batch is available in the state IN pipeline,
+                    // it keeps growing while the next message is expected within the latency target
+                    if g.is_none() || is_due {
+                        match self.take_current(&mut g) {
+                            Some(batch) => {
+                                self.backoff.stop();
+                                return Pull::Some(batch);
+                            }
+                            None => {
+                                self.backoff.stop();
+                                return Pull::None;
+                            }
                         }
                     }
                 }
@@ -449,6 +570,25 @@ impl StageOutIn {
             }
         }
 
+        if let Some(latency_target) = self.latency_target.as_ref() {
+            if is_due {
+                // Flush the current batch rather than waiting for the next message
+                if let Ok(mut g) = self.current.try_lock() {
+                    if let Some(batch) = self.s_out_r.pull().or_else(|| self.take_current(&mut g)) {
+                        self.backoff.stop();
+                        return Pull::Some(batch);
+                    }
+                }
+            }
+            // Do backoff, without exceeding the latency target
+            self.backoff.next();
+            self.backoff.retry_time = self
+                .backoff
+                .retry_time
+                .min(latency_target.remaining(elapsed));
+            return Pull::Backoff(self.backoff.retry_time);
+        }
+
         // Do backoff
         self.backoff.next();
         Pull::Backoff(self.backoff.retry_time)
@@ -480,6 +620,7 @@ impl StageOut {
 
     #[inline]
     fn refill(&mut self, batch: WBatch) {
+        self.s_in.in_flight.fetch_sub(1, Ordering::AcqRel);
         self.s_ref.refill(batch);
     }
 
@@ -490,7 +631,7 @@ impl StageOut {
             batches.push(batch);
         }
         // Take the current batch
-        if let Some(batch) = guard.take() {
+        if let Some(batch) = self.s_in.take_current(guard) {
             batches.push(batch);
         }
         batches
@@ -503,6 +644,43 @@ pub(crate) struct TransmissionPipelineConf {
     pub(crate) queue_size: [usize; Priority::NUM],
     pub(crate) wait_before_drop: Duration,
     pub(crate) backoff: Duration,
+    // The maximum time a message waits for its batch to be filled, if any
+    pub(crate) latency_target: Option<Duration>,
+    // The weights of the priorities drained with a weighted round robin, if any
+    pub(crate) wrr_weights: Option<[usize; Priority::NUM]>,
+}
+
+// Inner structure to drain the priority queues with a weighted round robin:
+// each priority sends up to its weight in batches before giving its turn to the next one
+struct WeightedRoundRobin {
+    weights: [usize; Priority::NUM],
+    // The priority whose turn it is, and the number of batches it may still send
+    current: usize,
+    credit: usize,
+}
+
+impl WeightedRoundRobin {
+    fn new(weights: [usize; Priority::NUM]) -> Self {
+        let weights = weights.map(|w| w.max(1));
+        Self {
+            weights,
+            current: 0,
+            credit: weights[0],
+        }
+    }
+
+    fn sent(&mut self, priority: usize) {
+        // The priorities before it had nothing to send
+        if priority != self.current {
+            self.current = priority;
+            self.credit = self.weights[priority];
+        }
+        self.credit -= 1;
+        if self.credit == 0 {
+            self.current = (priority + 1) % Priority::NUM;
+            self.credit = self.weights[self.current];
+        }
+    }
 }
 
 // A 2-stage transmission pipeline
@@ -512,7 +690,9 @@ impl TransmissionPipeline {
     pub(crate) fn make(
         config: TransmissionPipelineConf,
         priority: &[TransportPriorityTx],
+        budget: Arc<MemoryBudget>,
     ) -> (TransmissionPipelineProducer, TransmissionPipelineConsumer) {
+        let budget = Arc::new(PipelineBudget::new(budget));
         let mut stage_in = vec![];
         let mut stage_out = vec![];
 
@@ -548,6 +728,8 @@ impl TransmissionPipeline {
             let current = Arc::new(Mutex::new(None));
             let bytes = Arc::new(AtomicU16::new(0));
             let backoff = Arc::new(AtomicBool::new(false));
+            let interval = Arc::new(AtomicU32::new(NanoSeconds::MAX));
+            let in_flight = Arc::new(AtomicUsize::new(0));
 
             stage_in.push(Mutex::new(StageIn {
                 s_ref: StageInRefill { n_ref_r, s_ref_r },
@@ -556,12 +738,17 @@ impl TransmissionPipeline {
                     s_out_w,
                     bytes: bytes.clone(),
                     backoff: backoff.clone(),
+                    arrivals: config
+                        .latency_target
+                        .map(|_| ArrivalRate::new(interval.clone())),
+                    in_flight: in_flight.clone(),
                 },
                 mutex: StageInMutex {
                     current: current.clone(),
                     priority: priority[prio].clone(),
                 },
                 fragbuf: ZBuf::empty(),
+                budget: budget.clone(),
             }));
 
             // The stage out for this priority
@@ -570,6 +757,10 @@ impl TransmissionPipeline {
                     s_out_r,
                     current,
                     backoff: Backoff::new(bytes, backoff),
+                    latency_target: config
+                        .latency_target
+                        .map(|target| LatencyTarget::new(target, interval)),
+                    in_flight,
                 },
                 s_ref: StageOutRefill { n_ref_w, s_ref_w },
             });
@@ -580,11 +771,19 @@ impl TransmissionPipeline {
             stage_in: stage_in.into_boxed_slice().into(),
             active: active.clone(),
             wait_before_drop: config.wait_before_drop,
+            budget: budget.clone(),
         };
+        // The round robin only applies to the QoS pipelines
+        let wrr = config
+            .wrr_weights
+            .filter(|_| stage_out.len() == Priority::NUM)
+            .map(WeightedRoundRobin::new);
         let consumer = TransmissionPipelineConsumer {
             stage_out: stage_out.into_boxed_slice(),
+            wrr,
             n_out_r,
             active,
+            budget,
         };
 
         (producer, consumer)
@@ -597,6 +796,7 @@ pub(crate) struct TransmissionPipelineProducer {
     stage_in: Arc<[Mutex<StageIn>]>,
     active: Arc<AtomicBool>,
     wait_before_drop: Duration,
+    budget: Arc<PipelineBudget>,
 }
 
 impl TransmissionPipelineProducer {
@@ -609,6 +809,11 @@ impl TransmissionPipelineProducer {
         } else {
             (0, Priority::default())
         };
+        // If message is droppable, shed it when the memory budget is exceeded
+        if msg.is_droppable() && self.budget.is_exceeded() {
+            tracing::trace!("Memory budget exceeded: dropping message {:?}", msg);
+            return false;
+        }
         // If message is droppable, compute a deadline after which the sample could be dropped
         let deadline_before_drop = if msg.is_droppable() {
             Some(Instant::now() + self.wait_before_drop)
@@ -620,6 +825,25 @@ impl TransmissionPipelineProducer {
         queue.push_network_message(&mut msg, priority, deadline_before_drop)
     }
 
+    /// Whether all the messages pushed with `priority` have been written on the link, so that the
+    /// next ones may be sent on another link without being reordered.
+    pub(crate) fn is_flushed(&self, priority: Priority) -> bool {
+        let idx = if self.stage_in.len() > 1 {
+            priority as usize
+        } else {
+            0
+        };
+        // A message being pushed is not flushed yet
+        let Ok(queue) = self.stage_in[idx].try_lock() else {
+            return false;
+        };
+        // The stage out takes the current batch with its lock held: lock it before reading the
+        // batches in flight
+        let current = queue.mutex.current();
+        current.as_ref().map_or(true, |batch| batch.is_empty())
+            && queue.s_out.in_flight.load(Ordering::Acquire) == 0
+    }
+
     #[inline]
     pub(crate) fn push_transport_message(&self, msg: TransportMessage, priority: Priority) -> bool {
         // If the queue is not QoS, it means that we only have one priority with index 0.
@@ -651,8 +875,10 @@ impl TransmissionPipelineProducer {
 pub(crate) struct TransmissionPipelineConsumer {
     // A single Mutex for all the priority queues
     stage_out: Box<[StageOut]>,
+    wrr: Option<WeightedRoundRobin>,
     n_out_r: Receiver<()>,
     active: Arc<AtomicBool>,
+    budget: Arc<PipelineBudget>,
 }
 
 impl TransmissionPipelineConsumer {
@@ -660,9 +886,17 @@ impl TransmissionPipelineConsumer {
         while self.active.load(Ordering::Relaxed) {
             // Calculate the backoff maximum
             let mut bo = NanoSeconds::MAX;
-            for (prio, queue) in self.stage_out.iter_mut().enumerate() {
-                match queue.try_pull() {
+            // The priorities are pulled in order, from the one whose turn it is with a round robin
+            let len = self.stage_out.len();
+            let start = self.wrr.as_ref().map_or(0, |wrr| wrr.current);
+            for i in 0..len {
+                let prio = (start + i) % len;
+                match self.stage_out[prio].try_pull() {
                     Pull::Some(batch) => {
+                        self.budget.free(batch.len() as usize);
+                        if let Some(wrr) = self.wrr.as_mut() {
+                            wrr.sent(prio);
+                        }
                         return Some((batch, prio));
                     }
                     Pull::Backoff(b) => {
@@ -703,6 +937,7 @@ impl TransmissionPipelineConsumer {
         for (prio, s_out) in self.stage_out.iter_mut().enumerate() {
             let mut bs = s_out.drain(&mut currents[prio]);
             for b in bs.drain(..) {
+                self.budget.free(b.len() as usize);
                 batches.push((b, prio));
             }
         }
@@ -746,10 +981,13 @@ mod tests {
             is_streamed: true,
             #[cfg(feature = "transport_compression")]
             is_compression: true,
+            ..Default::default()
         },
         queue_size: [1; Priority::NUM],
         wait_before_drop: Duration::from_millis(1),
         backoff: Duration::from_micros(1),
+        latency_target: None,
+        wrr_weights: None,
     };
 
     const CONFIG_NOT_STREAMED: TransmissionPipelineConf = TransmissionPipelineConf {
@@ -758,10 +996,13 @@ mod tests {
             is_streamed: false,
             #[cfg(feature = "transport_compression")]
             is_compression: false,
+            ..Default::default()
         },
         queue_size: [1; Priority::NUM],
         wait_before_drop: Duration::from_millis(1),
         backoff: Duration::from_micros(1),
+        latency_target: None,
+        wrr_weights: None,
     };
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
@@ -776,6 +1017,7 @@ mod tests {
                 ext_qos: ext::QoSType::new(Priority::Control, CongestionControl::Block, false),
                 ext_tstamp: None,
                 ext_nodeid: ext::NodeIdType::default(),
+                ext_hoplimit: None,
                 payload: PushBody::Put(Put {
                     timestamp: None,
                     encoding: Encoding::default(),
@@ -783,6 +1025,7 @@ mod tests {
                     #[cfg(feature = "shared-memory")]
                     ext_shm: None,
                     ext_attachment: None,
+                    ext_tbound: None,
                     ext_unknown: vec![],
                     payload,
                 }),
@@ -868,8 +1111,11 @@ mod tests {
             // Compute the number of messages to send
             let num_msg = max_msgs.min(bytes / ps);
 
-            let (producer, consumer) =
-                TransmissionPipeline::make(CONFIG_NOT_STREAMED, priorities.as_slice());
+            let (producer, consumer) = TransmissionPipeline::make(
+                CONFIG_NOT_STREAMED,
+                priorities.as_slice(),
+                Arc::new(MemoryBudget::default()),
+            );
 
             let t_c = task::spawn(async move {
                 consume(consumer, num_msg).await;
@@ -904,6 +1150,7 @@ mod tests {
                 ext_qos: ext::QoSType::new(Priority::Control, CongestionControl::Block, false),
                 ext_tstamp: None,
                 ext_nodeid: ext::NodeIdType::default(),
+                ext_hoplimit: None,
                 payload: PushBody::Put(Put {
                     timestamp: None,
                     encoding: Encoding::default(),
@@ -911,6 +1158,7 @@ mod tests {
                     #[cfg(feature = "shared-memory")]
                     ext_shm: None,
                     ext_attachment: None,
+                    ext_tbound: None,
                     ext_unknown: vec![],
                     payload,
                 }),
@@ -937,8 +1185,11 @@ mod tests {
         // Pipeline
         let tct = TransportPriorityTx::make(Bits::from(TransportSn::MAX))?;
         let priorities = vec![tct];
-        let (producer, mut consumer) =
-            TransmissionPipeline::make(CONFIG_NOT_STREAMED, priorities.as_slice());
+        let (producer, mut consumer) = TransmissionPipeline::make(
+            CONFIG_NOT_STREAMED,
+            priorities.as_slice(),
+            Arc::new(MemoryBudget::default()),
+        );
 
         let counter = Arc::new(AtomicUsize::new(0));
 
@@ -987,14 +1238,236 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn tx_pipeline_budget() -> ZResult<()> {
+        let budget = Arc::new(MemoryBudget::new(Some(usize::MAX)));
+        let tct = TransportPriorityTx::make(Bits::from(TransportSn::MAX))?;
+        let priorities = vec![tct];
+        let (producer, mut consumer) =
+            TransmissionPipeline::make(CONFIG_NOT_STREAMED, priorities.as_slice(), budget.clone());
+
+        let message: NetworkMessage = Push {
+            wire_expr: "test".into(),
+            ext_qos: ext::QoSType::new(Priority::Control, CongestionControl::Block, false),
+            ext_tstamp: None,
+            ext_nodeid: ext::NodeIdType::default(),
+            ext_hoplimit: None,
+            payload: PushBody::Put(Put {
+                timestamp: None,
+                encoding: Encoding::default(),
+                ext_sinfo: None,
+                #[cfg(feature = "shared-memory")]
+                ext_shm: None,
+                ext_attachment: None,
+                ext_tbound: None,
+                ext_unknown: vec![],
+                payload: ZBuf::from(vec![0_u8; 1_024]),
+            }),
+        }
+        .into();
+        for _ in 0..4 {
+            assert!(producer.push_network_message(message.clone()));
+        }
+        assert!(budget.used() > 0);
+
+        // The drained batches are accounted as sent
+        let batches = consumer.drain();
+        assert!(!batches.is_empty());
+        assert_eq!(budget.used(), 0);
+        for (batch, priority) in batches {
+            consumer.refill(batch, priority);
+        }
+
+        // The batches still queued when the pipeline is dropped are given back to the budget
+        for _ in 0..4 {
+            assert!(producer.push_network_message(message.clone()));
+        }
+        assert!(budget.used() > 0);
+        drop(consumer);
+        assert!(budget.used() > 0);
+        drop(producer);
+        assert_eq!(budget.used(), 0);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn tx_pipeline_flushed() -> ZResult<()> {
+        let budget = Arc::new(MemoryBudget::new(None));
+        let tct = TransportPriorityTx::make(Bits::from(TransportSn::MAX))?;
+        let priorities = vec![tct];
+        let (producer, mut consumer) =
+            TransmissionPipeline::make(CONFIG_NOT_STREAMED, priorities.as_slice(), budget);
+
+        let message: NetworkMessage = Push {
+            wire_expr: "test".into(),
+            ext_qos: ext::QoSType::new(Priority::Control, CongestionControl::Block, false),
+            ext_tstamp: None,
+            ext_nodeid: ext::NodeIdType::default(),
+            ext_hoplimit: None,
+            payload: PushBody::Put(Put {
+                timestamp: None,
+                encoding: Encoding::default(),
+                ext_sinfo: None,
+                #[cfg(feature = "shared-memory")]
+                ext_shm: None,
+                ext_attachment: None,
+                ext_tbound: None,
+                ext_unknown: vec![],
+                payload: ZBuf::from(vec![0_u8; 8]),
+            }),
+        }
+        .into();
+        assert!(producer.is_flushed(Priority::Control));
+
+        // Serialized in the current batch
+        assert!(producer.push_network_message(message));
+        assert!(!producer.is_flushed(Priority::Control));
+
+        // Pulled by the link, but not written yet
+        let (batch, priority) = timeout(TIMEOUT, consumer.pull()).await?.unwrap();
+        assert!(!producer.is_flushed(Priority::Control));
+
+        // Written
+        consumer.refill(batch, priority);
+        assert!(producer.is_flushed(Priority::Control));
+        Ok(())
+    }
+
+    #[test]
+    fn tx_pipeline_latency_target() {
+        let interval = Arc::new(AtomicU32::new(NanoSeconds::MAX));
+        let target = LatencyTarget::new(Duration::from_micros(100), interval.clone());
+        // No estimation of the arrival rate yet: flush right away
+        assert!(target.is_due(0));
+
+        // High load: the batch is filled up to the latency target
+        interval.store(1_000, Ordering::Relaxed);
+        assert!(!target.is_due(0));
+        assert!(!target.is_due(50_000));
+        assert!(target.is_due(99_000));
+        assert_eq!(target.remaining(60_000), 40_000);
+        assert_eq!(target.remaining(200_000), TSLOT);
+
+        // Low load: the next message is not expected within the latency target
+        interval.store(200_000, Ordering::Relaxed);
+        assert!(target.is_due(0));
+
+        // The moving average of the arrival interval converges
+        let mut arrivals = ArrivalRate::new(interval.clone());
+        interval.store(NanoSeconds::MAX, Ordering::Relaxed);
+        arrivals.arrival();
+        assert_eq!(interval.load(Ordering::Relaxed), NanoSeconds::MAX);
+        arrivals.arrival();
+        assert!(interval.load(Ordering::Relaxed) < NanoSeconds::MAX);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn tx_pipeline_latency_target_flush() -> ZResult<()> {
+        // Pushes the messages at the given interval, returning the number of messages of each batch
+        async fn batches(target: Duration, interval: Duration, num_msg: usize) -> Vec<usize> {
+            let tct = TransportPriorityTx::make(Bits::from(TransportSn::MAX)).unwrap();
+            let (producer, mut consumer) = TransmissionPipeline::make(
+                TransmissionPipelineConf {
+                    latency_target: Some(target),
+                    ..CONFIG_NOT_STREAMED
+                },
+                &[tct],
+                Arc::new(MemoryBudget::default()),
+            );
+
+            let message: NetworkMessage = Push {
+                wire_expr: "test".into(),
+                ext_qos: ext::QoSType::new(Priority::Control, CongestionControl::Block, false),
+                ext_tstamp: None,
+                ext_nodeid: ext::NodeIdType::default(),
+                ext_hoplimit: None,
+                payload: PushBody::Put(Put {
+                    timestamp: None,
+                    encoding: Encoding::default(),
+                    ext_sinfo: None,
+                    #[cfg(feature = "shared-memory")]
+                    ext_shm: None,
+                    ext_attachment: None,
+                    ext_tbound: None,
+                    ext_unknown: vec![],
+                    payload: ZBuf::from(vec![0_u8; 8]),
+                }),
+            }
+            .into();
+            let t_s = task::spawn_blocking(move || {
+                for _ in 0..num_msg {
+                    producer.push_network_message(message.clone());
+                    std::thread::sleep(interval);
+                }
+            });
+
+            let mut batches = vec![];
+            while batches.iter().sum::<usize>() != num_msg {
+                let (batch, priority) = timeout(TIMEOUT, consumer.pull()).await.unwrap().unwrap();
+                let mut reader = batch.as_slice().reader();
+                let codec = Zenoh080::new();
+                let mut msgs = 0;
+                loop {
+                    let res: Result<TransportMessage, DidntRead> = codec.read(&mut reader);
+                    match res {
+                        Ok(msg) => {
+                            if let TransportBody::Frame(Frame { payload, .. }) = msg.body {
+                                msgs += payload.len();
+                            }
+                        }
+                        Err(_) => break,
+                    }
+                }
+                batches.push(msgs);
+                consumer.refill(batch, priority);
+            }
+            t_s.await.unwrap();
+            batches
+        }
+
+        // High load: the messages expected within the latency target are batched together,
+        // although the batch stops growing between them
+        let high = batches(Duration::from_millis(500), Duration::from_millis(2), 20).await;
+        println!("Pipeline Latency Target [high load]: {high:?}");
+        assert!(high.len() <= 5);
+
+        // Low load: the messages not expected within the latency target are sent right away
+        let low = batches(Duration::from_millis(1), Duration::from_millis(50), 5).await;
+        println!("Pipeline Latency Target [low load]: {low:?}");
+        assert_eq!(low, [1; 5]);
+        Ok(())
+    }
+
+    #[test]
+    fn tx_pipeline_wrr() {
+        let mut wrr = WeightedRoundRobin::new([3, 2, 1, 1, 1, 1, 1, 0]);
+        let mut turns = vec![];
+        // All the priorities have batches to send
+        for _ in 0..11 {
+            turns.push(wrr.current);
+            wrr.sent(wrr.current);
+        }
+        assert_eq!(turns, [0, 0, 0, 1, 1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(wrr.current, 0);
+
+        // The priority 0 has nothing to send, its turn goes to the next one
+        wrr.sent(1);
+        assert_eq!((wrr.current, wrr.credit), (1, 1));
+        wrr.sent(1);
+        assert_eq!((wrr.current, wrr.credit), (2, 1));
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
     #[ignore]
     async fn tx_pipeline_thr() {
         // Queue
         let tct = TransportPriorityTx::make(Bits::from(TransportSn::MAX)).unwrap();
         let priorities = vec![tct];
-        let (producer, mut consumer) =
-            TransmissionPipeline::make(CONFIG_STREAMED, priorities.as_slice());
+        let (producer, mut consumer) = TransmissionPipeline::make(
+            CONFIG_STREAMED,
+            priorities.as_slice(),
+            Arc::new(MemoryBudget::default()),
+        );
         let count = Arc::new(AtomicUsize::new(0));
         let size = Arc::new(AtomicUsize::new(0));
 
@@ -1021,6 +1494,7 @@ mod tests {
                         ),
                         ext_tstamp: None,
                         ext_nodeid: ext::NodeIdType::default(),
+                        ext_hoplimit: None,
                         payload: PushBody::Put(Put {
                             timestamp: None,
                             encoding: Encoding::default(),
@@ -1028,6 +1502,7 @@ mod tests {
                             #[cfg(feature = "shared-memory")]
                             ext_shm: None,
                             ext_attachment: None,
+                            ext_tbound: None,
                             ext_unknown: vec![],
                             payload,
                         }),
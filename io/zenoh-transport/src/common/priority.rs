@@ -13,10 +13,15 @@
 //
 use super::defragmentation::DefragBuffer;
 use super::seq_num::{SeqNum, SeqNumGenerator};
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use zenoh_buffers::ZSlice;
 use zenoh_core::zlock;
 use zenoh_protocol::{
     core::{Bits, Reliability},
+    network::NetworkMessage,
     transport::{PrioritySn, TransportSn},
 };
 use zenoh_result::ZResult;
@@ -39,10 +44,19 @@ impl TransportChannelTx {
     }
 }
 
+// The content of a frame or a fragment received on a link
+#[derive(Debug)]
+pub(crate) enum Received {
+    Frame(Vec<NetworkMessage>),
+    Fragment { more: bool, payload: ZSlice },
+}
+
 #[derive(Debug)]
 pub(crate) struct TransportChannelRx {
     pub(crate) sn: SeqNum,
     pub(crate) defrag: DefragBuffer,
+    // The frames and fragments received on a parallel link ahead of a missing one, by SN
+    pub(crate) reordered: HashMap<TransportSn, Received>,
 }
 
 impl TransportChannelRx {
@@ -53,7 +67,11 @@ impl TransportChannelRx {
     ) -> ZResult<TransportChannelRx> {
         let sn = SeqNum::make(0, resolution)?;
         let defrag = DefragBuffer::make(reliability, resolution, defrag_buff_size)?;
-        let tch = TransportChannelRx { sn, defrag };
+        let tch = TransportChannelRx {
+            sn,
+            defrag,
+            reordered: HashMap::new(),
+        };
         Ok(tch)
     }
 
@@ -66,6 +84,7 @@ impl TransportChannelRx {
         };
 
         self.sn.set(sn)?;
+        self.reordered.clear();
         self.defrag.sync(sn)
     }
 }
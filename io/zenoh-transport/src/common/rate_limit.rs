@@ -0,0 +1,106 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::time::{Duration, Instant};
+use zenoh_result::{bail, ZResult};
+
+// The burst allowed by a rate limiter, as an amount of time at the limited rate
+const RATE_LIMIT_BURST: Duration = Duration::from_millis(100);
+
+/// Parses a rate limit in bits per second, e.g. `512kbps`, `10mbps` or `1gbps`,
+/// into bytes per second.
+pub(crate) fn parse_rate_limit(s: &str) -> ZResult<u64> {
+    let lower = s.trim().to_ascii_lowercase();
+    let (value, unit) = match lower.find(|c: char| !c.is_ascii_digit() && c != '.') {
+        Some(i) => lower.split_at(i),
+        None => (lower.as_str(), "bps"),
+    };
+    let factor = match unit.trim() {
+        "bps" => 1.0,
+        "kbps" => 1e3,
+        "mbps" => 1e6,
+        "gbps" => 1e9,
+        _ => bail!("Invalid rate limit {}: unknown unit {}", s, unit),
+    };
+    let bits = match value.parse::<f64>() {
+        Ok(v) if v.is_finite() => v * factor,
+        _ => bail!("Invalid rate limit {}", s),
+    };
+    let bytes = (bits / 8.0) as u64;
+    if bytes == 0 {
+        bail!("Invalid rate limit {}: less than one byte per second", s);
+    }
+    Ok(bytes)
+}
+
+/// A token bucket limiting the bytes sent on a link.
+pub(crate) struct RateLimiter {
+    // The rate in bytes per second
+    rate: f64,
+    // The capacity of the bucket in bytes
+    burst: f64,
+    // The bytes available to send, negative when the last batch exceeded them
+    tokens: f64,
+    last: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(rate: u64) -> Self {
+        let rate = rate as f64;
+        let burst = (rate * RATE_LIMIT_BURST.as_secs_f64()).max(1.0);
+        Self {
+            rate,
+            burst,
+            tokens: burst,
+            last: Instant::now(),
+        }
+    }
+
+    /// Takes `bytes` from the bucket, returning how long to wait for it to refill
+    /// if they exceed the available ones.
+    pub(crate) fn take(&mut self, bytes: usize) -> Option<Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.last = now;
+        // The batch is sent as a whole: the debt is repaid by waiting before the next one
+        self.tokens -= bytes as f64;
+        (self.tokens < 0.0).then(|| Duration::from_secs_f64(-self.tokens / self.rate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limit() {
+        assert_eq!(parse_rate_limit("10mbps").unwrap(), 1_250_000);
+        assert_eq!(parse_rate_limit("1.5 Mbps").unwrap(), 187_500);
+        assert_eq!(parse_rate_limit("512kbps").unwrap(), 64_000);
+        assert_eq!(parse_rate_limit("8000").unwrap(), 1_000);
+        assert!(parse_rate_limit("10mb").is_err());
+        assert!(parse_rate_limit("mbps").is_err());
+        assert!(parse_rate_limit("1bps").is_err());
+
+        // 100 ms of burst, then 100 ms to repay the next 1000 bytes
+        let mut limiter = RateLimiter::new(10_000);
+        assert!(limiter.take(1_000).is_none());
+        let wait = limiter.take(1_000).unwrap();
+        assert!(wait <= Duration::from_millis(100) && wait > Duration::from_millis(90));
+
+        // The debt is carried over: a batch charged without waiting delays the next one
+        let wait = limiter.take(1_000).unwrap();
+        assert!(wait <= Duration::from_millis(200) && wait > Duration::from_millis(190));
+    }
+}
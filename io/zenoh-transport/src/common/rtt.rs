@@ -0,0 +1,176 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+use tokio::sync::Notify;
+use zenoh_link::LinkMetrics;
+
+// No estimate or no pending echo request
+const NONE: u64 = u64::MAX;
+
+/// Estimates the round-trip time of a link from the echo of its keep-alive messages,
+/// smoothed as the retransmission timer of TCP (RFC 6298), and the ratio of echo
+/// requests not replied before the next one.
+///
+/// The timestamps carried by the echo requests are the microseconds elapsed since the
+/// creation of the estimator: they are only meaningful to the peer sending them.
+pub(crate) struct RttEstimator {
+    start: Instant,
+    // The timestamp of the last echo request received and not replied yet
+    pending: AtomicU64,
+    reply: Notify,
+    // The timestamp of the last echo request sent and not replied yet
+    outstanding: AtomicU64,
+    // The smoothed round-trip time and its mean deviation in microseconds
+    srtt: AtomicU64,
+    rttvar: AtomicU64,
+    // The smoothed loss ratio in parts per million
+    loss: AtomicU64,
+}
+
+impl RttEstimator {
+    pub(crate) fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            pending: AtomicU64::new(NONE),
+            reply: Notify::new(),
+            outstanding: AtomicU64::new(NONE),
+            srtt: AtomicU64::new(NONE),
+            rttvar: AtomicU64::new(NONE),
+            loss: AtomicU64::new(0),
+        }
+    }
+
+    fn now(&self) -> u64 {
+        self.start.elapsed().as_micros() as u64
+    }
+
+    /// The timestamp of a new echo request.
+    pub(crate) fn request(&self) -> u64 {
+        let timestamp = self.now();
+        if self.outstanding.swap(timestamp, Ordering::AcqRel) != NONE {
+            self.sample_loss(true);
+        }
+        timestamp
+    }
+
+    fn sample_loss(&self, lost: bool) {
+        let sample = if lost { 1_000_000 } else { 0 };
+        let _ = self
+            .loss
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |loss| {
+                Some((7 * loss + sample) / 8)
+            });
+    }
+
+    /// An echo request has been received: it is replied by the TX task of the link.
+    pub(crate) fn on_request(&self, timestamp: u64) {
+        self.pending.store(timestamp, Ordering::Release);
+        self.reply.notify_one();
+    }
+
+    /// Waits for an echo request to reply to, returning its timestamp.
+    pub(crate) async fn next_reply(&self) -> u64 {
+        loop {
+            let timestamp = self.pending.swap(NONE, Ordering::AcqRel);
+            if timestamp != NONE {
+                return timestamp;
+            }
+            self.reply.notified().await;
+        }
+    }
+
+    /// The echo of a request sent at `timestamp` has been received.
+    pub(crate) fn on_reply(&self, timestamp: u64) {
+        let now = self.now();
+        if timestamp > now {
+            tracing::trace!(
+                "Ignoring the echo of a request from the future: {}",
+                timestamp
+            );
+            return;
+        }
+        if self
+            .outstanding
+            .compare_exchange(timestamp, NONE, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+        {
+            self.sample_loss(false);
+        }
+        // The replies are only handled by the RX task of the link
+        let rtt = now - timestamp;
+        let (srtt, rttvar) = match self.srtt.load(Ordering::Acquire) {
+            NONE => (rtt, rtt / 2),
+            srtt => {
+                let rttvar = self.rttvar.load(Ordering::Acquire);
+                ((7 * srtt + rtt) / 8, (3 * rttvar + srtt.abs_diff(rtt)) / 4)
+            }
+        };
+        self.rttvar.store(rttvar, Ordering::Release);
+        self.srtt.store(srtt, Ordering::Release);
+    }
+
+    /// The smoothed round-trip time, if measured.
+    #[inline]
+    pub(crate) fn srtt(&self) -> Option<Duration> {
+        match self.srtt.load(Ordering::Acquire) {
+            NONE => None,
+            srtt => Some(Duration::from_micros(srtt)),
+        }
+    }
+
+    pub(crate) fn get(&self) -> Option<LinkMetrics> {
+        self.srtt().map(|srtt| LinkMetrics {
+            srtt,
+            jitter: Duration::from_micros(self.rttvar.load(Ordering::Acquire)),
+            loss: (self.loss.load(Ordering::Acquire) / 1_000) as u16,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rtt_smoothing() {
+        let rtt = RttEstimator::new();
+        assert!(rtt.get().is_none());
+
+        std::thread::sleep(Duration::from_millis(10));
+        rtt.on_reply(0);
+        let first = rtt.get().unwrap();
+        assert!(first.srtt >= Duration::from_millis(10));
+        assert_eq!(first.jitter.as_micros(), first.srtt.as_micros() / 2);
+
+        // A sample is weighted by 1/8 in the smoothed round-trip time
+        let now = rtt.request();
+        std::thread::sleep(first.srtt);
+        rtt.on_reply(now);
+        let second = rtt.get().unwrap();
+        assert!(second.srtt >= first.srtt);
+
+        // Echo requests of the future are ignored
+        rtt.on_reply(u64::MAX - 1);
+        assert_eq!(rtt.get(), Some(second));
+
+        // An echo request not replied before the next one is lost
+        assert_eq!(second.loss, 0);
+        rtt.request();
+        rtt.request();
+        assert_eq!(rtt.get().unwrap().loss, 125);
+    }
+}
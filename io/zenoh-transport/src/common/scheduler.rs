@@ -0,0 +1,181 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use super::{batch::WBatch, pipeline::TransmissionPipelineConsumer};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+use zenoh_core::zlock;
+
+// The pass of a transport with weight 1 advances by STRIDE for every batch it sends
+const STRIDE: u64 = 1 << 20;
+
+/// The weighted scheduling of the transports sharing the TX threads (stride scheduling).
+///
+/// Every batch sent by a transport advances its pass by the inverse of its weight, and a transport
+/// may only send while its pass is not ahead of the pass of the other active transports: the
+/// transports with batches to send then share the TX in proportion to their weights.
+#[derive(Default)]
+pub(crate) struct TxScheduler {
+    // The passes of the transports with batches to send
+    active: Mutex<HashMap<usize, u64>>,
+    next_id: AtomicUsize,
+    notify: Notify,
+}
+
+impl TxScheduler {
+    pub(crate) fn slot(self: &Arc<Self>, weight: usize) -> TxSlot {
+        TxSlot {
+            scheduler: self.clone(),
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            stride: STRIDE / weight.max(1) as u64,
+            pass: 0,
+            active: false,
+        }
+    }
+}
+
+/// The place of a transport in the [`TxScheduler`].
+pub(crate) struct TxSlot {
+    scheduler: Arc<TxScheduler>,
+    id: usize,
+    stride: u64,
+    pass: u64,
+    active: bool,
+}
+
+impl TxSlot {
+    /// Wait for the turn of the transport to send a batch.
+    pub(crate) async fn acquire(&mut self) {
+        loop {
+            let notified = self.scheduler.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            {
+                let mut active = zlock!(self.scheduler.active);
+                let min = active
+                    .iter()
+                    .filter(|(id, _)| **id != self.id)
+                    .map(|(_, pass)| *pass)
+                    .min();
+                if !self.active {
+                    // A transport doesn't get any credit for the time it had nothing to send
+                    if let Some(min) = min {
+                        self.pass = self.pass.max(min);
+                    }
+                    self.active = true;
+                }
+                if min.map_or(true, |min| self.pass <= min) {
+                    self.pass += self.stride;
+                    active.insert(self.id, self.pass);
+                    drop(active);
+                    self.scheduler.notify.notify_waiters();
+                    return;
+                }
+                active.insert(self.id, self.pass);
+            }
+            notified.await;
+        }
+    }
+
+    /// Leave the scheduling while the transport has nothing to send.
+    pub(crate) fn release(&mut self) {
+        if self.active {
+            self.active = false;
+            zlock!(self.scheduler.active).remove(&self.id);
+            self.scheduler.notify.notify_waiters();
+        }
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        self.active
+    }
+}
+
+impl Drop for TxSlot {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Pull the next batch of a pipeline, leaving the scheduling while the pipeline has nothing to send.
+pub(crate) async fn pull(
+    pipeline: &mut TransmissionPipelineConsumer,
+    slot: Option<&mut TxSlot>,
+) -> Option<(WBatch, usize)> {
+    if let Some(slot) = slot.filter(|slot| slot.is_active()) {
+        tokio::select! {
+            biased;
+            res = pipeline.pull() => return res,
+            _ = std::future::ready(()) => slot.release(),
+        }
+    }
+    pipeline.pull().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn tx_scheduler() {
+        let scheduler = Arc::new(TxScheduler::default());
+        let stop = Arc::new(AtomicBool::new(false));
+        let sent: Vec<Arc<AtomicUsize>> = (0..2).map(|_| Arc::default()).collect();
+
+        let tasks: Vec<_> = [1, 4]
+            .into_iter()
+            .zip(sent.iter().cloned())
+            .map(|(weight, sent)| {
+                let mut slot = scheduler.slot(weight);
+                let stop = stop.clone();
+                tokio::spawn(async move {
+                    while !stop.load(Ordering::Relaxed) {
+                        slot.acquire().await;
+                        sent.fetch_add(1, Ordering::Relaxed);
+                        tokio::task::yield_now().await;
+                    }
+                })
+            })
+            .collect();
+        while sent
+            .iter()
+            .map(|s| s.load(Ordering::Relaxed))
+            .sum::<usize>()
+            < 5_000
+        {
+            tokio::task::yield_now().await;
+        }
+        stop.store(true, Ordering::Relaxed);
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        // The transports share the TX in proportion to their weights
+        let light = sent[0].load(Ordering::Relaxed) as f64;
+        let heavy = sent[1].load(Ordering::Relaxed) as f64;
+        let ratio = heavy / light;
+        assert!((3.5..=4.5).contains(&ratio), "ratio: {ratio}");
+
+        // A released transport doesn't hold back the others
+        let mut idle = scheduler.slot(1);
+        idle.acquire().await;
+        idle.release();
+        let mut busy = scheduler.slot(1);
+        for _ in 0..10 {
+            busy.acquire().await;
+        }
+    }
+}
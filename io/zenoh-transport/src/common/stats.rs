@@ -196,6 +196,10 @@ stats_struct! {
         # TYPE "counter"
         pub tx_n_dropped,
 
+        # HELP "Counter of network messages not forwarded as their hop limit was exhausted."
+        # TYPE "counter"
+        pub tx_n_expired,
+
         # HELP "Counter of sent zenoh put messages."
         # TYPE "counter"
         pub tx_z_put_msgs DiscriminatedStats,
@@ -240,6 +244,10 @@ stats_struct! {
         # TYPE "counter"
         pub rx_n_dropped,
 
+        # HELP "Counter of batches recovered by forward error correction on multicast links."
+        # TYPE "counter"
+        pub rx_fec_recovered,
+
         # HELP "Counter of received zenoh put messages."
         # TYPE "counter"
         pub rx_z_put_msgs DiscriminatedStats,
@@ -118,6 +118,10 @@ pub trait TransportPeerEventHandler: Send + Sync {
     fn handle_message(&self, msg: NetworkMessage) -> ZResult<()>;
     fn new_link(&self, src: Link);
     fn del_link(&self, link: Link);
+    /// Called before `closing` when the reason of the closure is known, i.e. when the peer
+    /// sent a Close message or when the lease of the transport expired.
+    /// See [`close::reason`](zenoh_protocol::transport::close::reason) for the possible values.
+    fn close_reason(&self, _reason: u8) {}
     fn closing(&self);
     fn closed(&self);
     fn as_any(&self) -> &dyn Any;
@@ -15,6 +15,7 @@ use super::unicast::manager::{
     TransportManagerBuilderUnicast, TransportManagerConfigUnicast, TransportManagerStateUnicast,
 };
 use super::TransportEventHandler;
+use crate::common::{budget::MemoryBudget, scheduler::TxScheduler};
 use crate::multicast::manager::{
     TransportManagerBuilderMulticast, TransportManagerConfigMulticast,
     TransportManagerStateMulticast,
@@ -24,6 +25,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex as AsyncMutex;
+use zenoh_codec::CodecLimits;
 use zenoh_config::{Config, LinkRxConf, QueueConf, QueueSizeConf};
 use zenoh_crypto::{BlockCipher, PseudoRng};
 use zenoh_link::NewLinkChannelSender;
@@ -99,14 +101,32 @@ pub struct TransportManagerConfig {
     pub queue_backoff: Duration,
     pub defrag_buff_size: usize,
     pub link_rx_buffer_size: usize,
+    pub codec_limits: CodecLimits,
+    pub memory_budget: Arc<MemoryBudget>,
     pub unicast: TransportManagerConfigUnicast,
     pub multicast: TransportManagerConfigMulticast,
     pub endpoints: HashMap<String, String>, // (protocol, config)
     pub handler: Arc<dyn TransportEventHandler>,
     pub tx_threads: usize,
+    pub tx_weights: TxWeights,
     pub protocols: Vec<String>,
 }
 
+/// The scheduling weights of the transports sharing the TX threads: the transports with batches
+/// of non-control priorities to send share the TX in proportion to their weights.
+#[derive(Debug, Clone, Default)]
+pub struct TxWeights {
+    pub default: Option<usize>,
+    pub zids: HashMap<ZenohId, usize>,
+}
+
+impl TxWeights {
+    /// The weight of the transport with the given remote zenoh instance, `None` if not scheduled.
+    pub fn get(&self, zid: &ZenohId) -> Option<usize> {
+        self.zids.get(zid).copied().or(self.default)
+    }
+}
+
 pub struct TransportManagerState {
     pub unicast: TransportManagerStateUnicast,
     pub multicast: TransportManagerStateMulticast,
@@ -128,10 +148,13 @@ pub struct TransportManagerBuilder {
     queue_backoff: Duration,
     defrag_buff_size: usize,
     link_rx_buffer_size: usize,
+    codec_limits: CodecLimits,
+    memory_budget: Option<usize>,
     unicast: TransportManagerBuilderUnicast,
     multicast: TransportManagerBuilderMulticast,
     endpoints: HashMap<String, String>, // (protocol, config)
     tx_threads: usize,
+    tx_weights: TxWeights,
     protocols: Option<Vec<String>>,
 }
 
@@ -181,6 +204,16 @@ impl TransportManagerBuilder {
         self
     }
 
+    pub fn codec_limits(mut self, codec_limits: CodecLimits) -> Self {
+        self.codec_limits = codec_limits;
+        self
+    }
+
+    pub fn memory_budget(mut self, memory_budget: Option<usize>) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
     pub fn endpoints(mut self, endpoints: HashMap<String, String>) -> Self {
         self.endpoints = endpoints;
         self
@@ -201,6 +234,11 @@ impl TransportManagerBuilder {
         self
     }
 
+    pub fn tx_weights(mut self, tx_weights: TxWeights) -> Self {
+        self.tx_weights = tx_weights;
+        self
+    }
+
     pub fn protocols(mut self, protocols: Option<Vec<String>>) -> Self {
         self.protocols = protocols;
         self
@@ -219,12 +257,23 @@ impl TransportManagerBuilder {
         self = self.batch_size(*link.tx().batch_size());
         self = self.defrag_buff_size(*link.rx().max_message_size());
         self = self.link_rx_buffer_size(*link.rx().buffer_size());
+        self = self.codec_limits(codec_limits(link.rx()));
+        self = self.memory_budget(*link.memory_budget());
         self = self.wait_before_drop(Duration::from_micros(
             *link.tx().queue().congestion_control().wait_before_drop(),
         ));
         self = self.queue_size(link.tx().queue().size().clone());
         self = self.queue_backoff(Duration::from_nanos(*link.tx().queue().backoff()));
         self = self.tx_threads(*link.tx().threads());
+        let scheduling = link.tx().scheduling();
+        self = self.tx_weights(TxWeights {
+            default: *scheduling.default_weight(),
+            zids: scheduling
+                .weights()
+                .iter()
+                .flat_map(|w| w.zids.iter().map(|zid| (*zid, w.weight)))
+                .collect(),
+        });
         self = self.protocols(link.protocols().clone());
 
         let (c, errors) = zenoh_link::LinkConfigurator::default().configurations(config);
@@ -275,11 +324,14 @@ impl TransportManagerBuilder {
             queue_backoff: self.queue_backoff,
             defrag_buff_size: self.defrag_buff_size,
             link_rx_buffer_size: self.link_rx_buffer_size,
+            codec_limits: self.codec_limits,
+            memory_budget: Arc::new(MemoryBudget::new(self.memory_budget)),
             unicast: unicast.config,
             multicast: multicast.config,
             endpoints: self.endpoints,
             handler,
             tx_threads: self.tx_threads,
+            tx_weights: self.tx_weights,
             protocols: self.protocols.unwrap_or_else(|| {
                 zenoh_link::PROTOCOLS
                     .iter()
@@ -316,15 +368,28 @@ impl Default for TransportManagerBuilder {
             queue_backoff: Duration::from_nanos(backoff),
             defrag_buff_size: *link_rx.max_message_size(),
             link_rx_buffer_size: *link_rx.buffer_size(),
+            codec_limits: codec_limits(&link_rx),
+            memory_budget: None,
             endpoints: HashMap::new(),
             unicast: TransportManagerBuilderUnicast::default(),
             multicast: TransportManagerBuilderMulticast::default(),
             tx_threads: 1,
+            tx_weights: TxWeights::default(),
             protocols: None,
         }
     }
 }
 
+// The limits of the codec enforced when decoding the messages received by the transports
+fn codec_limits(rx: &LinkRxConf) -> CodecLimits {
+    CodecLimits {
+        max_locators: *rx.max_locators(),
+        max_properties: *rx.max_properties(),
+        max_payload: *rx.max_message_size(),
+        max_links: *rx.max_links(),
+    }
+}
+
 #[derive(Clone)]
 pub struct TransportManager {
     pub config: Arc<TransportManagerConfig>,
@@ -336,6 +401,7 @@ pub struct TransportManager {
     #[cfg(feature = "stats")]
     pub(crate) stats: Arc<crate::stats::TransportStats>,
     pub(crate) task_controller: TaskController,
+    pub(crate) tx_scheduler: Arc<TxScheduler>,
 }
 
 impl TransportManager {
@@ -358,6 +424,7 @@ impl TransportManager {
             #[cfg(feature = "stats")]
             stats: std::sync::Arc::new(crate::stats::TransportStats::default()),
             task_controller: TaskController::default(),
+            tx_scheduler: Arc::new(TxScheduler::default()),
         };
 
         // @TODO: this should be moved into the unicast module
@@ -390,6 +457,11 @@ impl TransportManager {
         self.config.zid
     }
 
+    /// The memory budget of the transmission queues of all the transports.
+    pub fn memory_budget(&self) -> &Arc<MemoryBudget> {
+        &self.config.memory_budget
+    }
+
     #[cfg(feature = "stats")]
     pub fn get_stats(&self) -> std::sync::Arc<crate::stats::TransportStats> {
         self.stats.clone()
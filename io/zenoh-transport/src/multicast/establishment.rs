@@ -14,6 +14,7 @@
 use crate::{
     common::{batch::BatchConfig, seq_num},
     multicast::{
+        fec::FEC_OVERHEAD,
         link::{TransportLinkMulticast, TransportLinkMulticastConfig},
         transport::TransportMulticastInner,
         TransportConfigMulticast, TransportMulticast,
@@ -26,7 +27,7 @@ use zenoh_core::zasynclock;
 use zenoh_link::LinkMulticast;
 use zenoh_protocol::{
     core::{Field, Priority},
-    transport::PrioritySn,
+    transport::{BatchSize, PrioritySn},
 };
 use zenoh_result::{bail, ZResult};
 
@@ -61,13 +62,20 @@ pub(crate) async fn open_link(
 
     // Create the transport
     let locator = link.get_dst().to_owned();
+    let fec = manager.config.multicast.fec;
+    // Leave room for the FEC header in the datagrams
+    let mtu = match fec {
+        Some(_) => link.get_mtu().saturating_sub(FEC_OVERHEAD as BatchSize),
+        None => link.get_mtu(),
+    };
     let config = TransportLinkMulticastConfig {
         batch: BatchConfig {
-            mtu: link.get_mtu(),
+            mtu,
             #[cfg(feature = "transport_compression")]
             is_compression: manager.config.multicast.is_compression,
             ..Default::default()
         },
+        fec,
     };
     let link = TransportLinkMulticast::new(link, config);
 
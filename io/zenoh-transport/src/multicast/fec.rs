@@ -0,0 +1,334 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+//! # Forward Error Correction
+//!
+//! When FEC is enabled on a multicast link, the batches are sent by groups of `k` and every
+//! group is followed by a parity batch allowing the receivers to recover one lost batch per group.
+//! Each batch sent on the link is prefixed with the FEC header:
+//!
+//! ```text
+//!  7 6 5 4 3 2 1 0
+//! +-+-+-+-+-+-+-+-+
+//! |     group     |  (u16 little endian)
+//! |               |
+//! +---------------+
+//! |     index     |  index == k for the parity batch
+//! +---------------+
+//! |       k       |
+//! +---------------+
+//! ~    payload    ~
+//! +---------------+
+//! ```
+//!
+//! The payload of the parity batch is the XOR of the `k` data batches of the group, each of them
+//! being prefixed with its length (u16 little endian) and padded with zeros. The data batches are
+//! bounded by the batch size, so that no datagram exceeds the batch size plus [`FEC_OVERHEAD`].
+use zenoh_buffers::ZSlice;
+use zenoh_protocol::transport::BatchSize;
+use zenoh_result::{bail, ZResult};
+
+const L_LEN: usize = (BatchSize::BITS / 8) as usize;
+
+/// The number of bytes added by FEC to the MTU of the link.
+pub(crate) const FEC_OVERHEAD: usize = FecHeader::SIZE + L_LEN;
+
+/// The maximum size of the datagrams sent for batches of at most `batch_size` bytes.
+pub(crate) const fn max_frame_size(batch_size: BatchSize) -> usize {
+    batch_size as usize + FEC_OVERHEAD
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct FecHeader {
+    group: u16,
+    index: u8,
+    k: u8,
+}
+
+impl FecHeader {
+    const SIZE: usize = 4;
+
+    fn write(&self, into: &mut Vec<u8>) {
+        into.extend_from_slice(&self.group.to_le_bytes());
+        into.push(self.index);
+        into.push(self.k);
+    }
+
+    fn read(bytes: &[u8]) -> ZResult<Self> {
+        if bytes.len() < Self::SIZE {
+            bail!("Invalid FEC header: {} bytes", bytes.len());
+        }
+        let header = FecHeader {
+            group: u16::from_le_bytes([bytes[0], bytes[1]]),
+            index: bytes[2],
+            k: bytes[3],
+        };
+        if header.k == 0 || header.index > header.k {
+            bail!("Invalid FEC header: {:?}", header);
+        }
+        Ok(header)
+    }
+}
+
+fn xor_into(parity: &mut Vec<u8>, bytes: &[u8]) {
+    // The length is part of the parity to restore the exact size of a recovered batch
+    let len = (bytes.len() as BatchSize).to_le_bytes();
+    let total = L_LEN + bytes.len();
+    if parity.len() < total {
+        parity.resize(total, 0);
+    }
+    for (p, b) in parity.iter_mut().zip(len.iter().chain(bytes.iter())) {
+        *p ^= b;
+    }
+}
+
+/*************************************/
+/*              ENCODER              */
+/*************************************/
+pub(crate) struct FecEncoder {
+    k: u8,
+    batch_size: BatchSize,
+    group: u16,
+    index: u8,
+    parity: Vec<u8>,
+    buffer: Vec<u8>,
+}
+
+impl FecEncoder {
+    pub(crate) fn new(k: u8, batch_size: BatchSize) -> Self {
+        Self {
+            k: k.max(1),
+            batch_size,
+            group: 0,
+            index: 0,
+            parity: Vec::new(),
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Prefix a data batch with the FEC header.
+    pub(crate) fn encode(&mut self, bytes: &[u8]) -> ZResult<&[u8]> {
+        if bytes.len() > self.batch_size as usize {
+            bail!(
+                "FEC batch of {} bytes exceeds the batch size of {} bytes",
+                bytes.len(),
+                self.batch_size
+            );
+        }
+        self.buffer.clear();
+        FecHeader {
+            group: self.group,
+            index: self.index,
+            k: self.k,
+        }
+        .write(&mut self.buffer);
+        self.buffer.extend_from_slice(bytes);
+
+        xor_into(&mut self.parity, bytes);
+        self.index += 1;
+        Ok(&self.buffer)
+    }
+
+    /// Returns the parity batch to be sent once the current group is complete.
+    pub(crate) fn parity(&mut self) -> Option<&[u8]> {
+        if self.index < self.k {
+            return None;
+        }
+        self.buffer.clear();
+        FecHeader {
+            group: self.group,
+            index: self.k,
+            k: self.k,
+        }
+        .write(&mut self.buffer);
+        self.buffer.extend_from_slice(&self.parity);
+
+        self.parity.clear();
+        self.index = 0;
+        self.group = self.group.wrapping_add(1);
+        Some(&self.buffer)
+    }
+}
+
+/*************************************/
+/*              DECODER              */
+/*************************************/
+pub(crate) struct FecDecoder {
+    batch_size: BatchSize,
+    group: Option<u16>,
+    // The data batches of the current group, None if not received (or recovered) yet
+    slots: Vec<Option<ZSlice>>,
+    // The index of the next data batch to deliver
+    next: usize,
+    parity: Option<ZSlice>,
+}
+
+impl FecDecoder {
+    pub(crate) fn new(batch_size: BatchSize) -> Self {
+        Self {
+            batch_size,
+            group: None,
+            slots: Vec::new(),
+            next: 0,
+            parity: None,
+        }
+    }
+
+    /// Decode a batch received on the link and push in `out`, in order, the data batches
+    /// that can be delivered. The batches following a lost one are held until the lost one
+    /// is recovered or the group is over.
+    ///
+    /// Returns the number of recovered batches.
+    pub(crate) fn decode(&mut self, batch: ZSlice, out: &mut Vec<ZSlice>) -> ZResult<usize> {
+        let header = FecHeader::read(batch.as_slice())?;
+        let Some(payload) = batch.subslice(FecHeader::SIZE, batch.len()) else {
+            bail!("Invalid FEC batch");
+        };
+        let max = match header.index == header.k {
+            true => L_LEN + self.batch_size as usize,
+            false => self.batch_size as usize,
+        };
+        if payload.len() > max {
+            bail!(
+                "FEC batch of {} bytes exceeds the batch size of {} bytes",
+                payload.len(),
+                self.batch_size
+            );
+        }
+
+        if self.group != Some(header.group) || self.slots.len() != header.k as usize {
+            // A new group: deliver what is left of the previous one
+            self.flush(out);
+            self.group = Some(header.group);
+            self.slots = vec![None; header.k as usize];
+            self.next = 0;
+            self.parity = None;
+        }
+
+        let index = header.index as usize;
+        if index == self.slots.len() {
+            self.parity = Some(payload);
+        } else if index >= self.next && self.slots[index].is_none() {
+            self.slots[index] = Some(payload);
+        }
+
+        let recovered = self.recover();
+        while let Some(Some(b)) = self.slots.get(self.next) {
+            out.push(b.clone());
+            self.next += 1;
+        }
+        Ok(recovered)
+    }
+
+    // Recover the missing data batch when it is the only one missing in the group
+    fn recover(&mut self) -> usize {
+        let Some(parity) = self.parity.as_ref() else {
+            return 0;
+        };
+        let mut missing = self.slots.iter().enumerate().filter(|(_, s)| s.is_none());
+        let (Some((index, _)), None) = (missing.next(), missing.next()) else {
+            return 0;
+        };
+
+        let mut bytes = parity.as_slice().to_vec();
+        for slot in self.slots.iter().flatten() {
+            xor_into(&mut bytes, slot.as_slice());
+        }
+        if bytes.len() < L_LEN {
+            return 0;
+        }
+        let len = BatchSize::from_le_bytes([bytes[0], bytes[1]]) as usize;
+        if L_LEN + len > bytes.len() || len > self.batch_size as usize {
+            return 0;
+        }
+        bytes.truncate(L_LEN + len);
+        bytes.drain(..L_LEN);
+        self.slots[index] = Some(bytes.into());
+        self.parity = None;
+        1
+    }
+
+    // Deliver the remaining data batches of the current group, skipping the lost ones
+    fn flush(&mut self, out: &mut Vec<ZSlice>) {
+        out.extend(self.slots.iter().skip(self.next).flatten().cloned());
+        self.next = self.slots.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fec_recover() {
+        const K: u8 = 4;
+        let batches: Vec<Vec<u8>> = (0..K as usize)
+            .map(|i| (0..(10 + 7 * i)).map(|b| (b * i) as u8).collect())
+            .collect();
+
+        for lost in 0..K as usize {
+            let mut encoder = FecEncoder::new(K, BatchSize::MAX);
+            let mut decoder = FecDecoder::new(BatchSize::MAX);
+            let mut out = vec![];
+            let mut recovered = 0;
+            for (i, b) in batches.iter().enumerate() {
+                let sent = ZSlice::from(encoder.encode(b).unwrap().to_vec());
+                if i != lost {
+                    recovered += decoder.decode(sent, &mut out).unwrap();
+                }
+            }
+            let parity = ZSlice::from(encoder.parity().unwrap().to_vec());
+            recovered += decoder.decode(parity, &mut out).unwrap();
+
+            assert_eq!(recovered, 1);
+            let out: Vec<Vec<u8>> = out.iter().map(|b| b.as_slice().to_vec()).collect();
+            assert_eq!(out, batches);
+        }
+    }
+
+    #[test]
+    fn fec_full_size_batches() {
+        const K: u8 = 3;
+        const BATCH_SIZE: BatchSize = 1_024;
+        let batches: Vec<Vec<u8>> = (0..K).map(|i| vec![i + 1; BATCH_SIZE as usize]).collect();
+
+        let mut encoder = FecEncoder::new(K, BATCH_SIZE);
+        let mut decoder = FecDecoder::new(BATCH_SIZE);
+        let mut out = vec![];
+        let mut recovered = 0;
+        for (i, b) in batches.iter().enumerate() {
+            let sent = encoder.encode(b).unwrap().to_vec();
+            assert!(sent.len() <= max_frame_size(BATCH_SIZE));
+            if i != 1 {
+                recovered += decoder.decode(sent.into(), &mut out).unwrap();
+            }
+        }
+        // The parity of full size batches still fits in the RX buffers
+        let parity = encoder.parity().unwrap().to_vec();
+        assert_eq!(parity.len(), max_frame_size(BATCH_SIZE));
+        recovered += decoder.decode(parity.into(), &mut out).unwrap();
+
+        assert_eq!(recovered, 1);
+        let out: Vec<Vec<u8>> = out.iter().map(|b| b.as_slice().to_vec()).collect();
+        assert_eq!(out, batches);
+
+        // Batches exceeding the batch size are rejected on both sides
+        assert!(encoder.encode(&[0; BATCH_SIZE as usize + 1]).is_err());
+        let mut oversized = FecEncoder::new(K, BatchSize::MAX);
+        let sent = oversized
+            .encode(&[0; BATCH_SIZE as usize + 1])
+            .unwrap()
+            .to_vec();
+        assert!(decoder.decode(sent.into(), &mut out).is_err());
+    }
+}
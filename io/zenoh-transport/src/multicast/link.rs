@@ -21,10 +21,15 @@ use crate::{
             TransmissionPipelineProducer,
         },
         priority::TransportPriorityTx,
+        scheduler::{self, TxSlot},
+    },
+    multicast::{
+        fec::{self, FecDecoder, FecEncoder},
+        transport::TransportMulticastInner,
     },
-    multicast::transport::TransportMulticastInner,
 };
 use std::{
+    collections::HashMap,
     convert::TryInto,
     fmt,
     sync::Arc,
@@ -47,6 +52,8 @@ use zenoh_sync::{RecyclingObject, RecyclingObjectPool, Signal};
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub(crate) struct TransportLinkMulticastConfig {
     pub(crate) batch: BatchConfig,
+    // The number of batches per FEC group, if FEC is enabled
+    pub(crate) fec: Option<u8>,
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -77,6 +84,10 @@ impl TransportLinkMulticast {
                     )),
                 None
             ),
+            fec: self
+                .config
+                .fec
+                .map(|k| FecEncoder::new(k, self.config.batch.mtu)),
         }
     }
 
@@ -141,6 +152,7 @@ impl From<TransportLinkMulticast> for Link {
 pub(crate) struct TransportLinkMulticastTx {
     pub(crate) inner: TransportLinkMulticast,
     pub(crate) buffer: Option<BBuf>,
+    pub(crate) fec: Option<FecEncoder>,
 }
 
 impl TransportLinkMulticastTx {
@@ -161,7 +173,15 @@ impl TransportLinkMulticastTx {
         };
 
         // Send the message on the link
-        self.inner.link.write_all(bytes).await?;
+        match self.fec.as_mut() {
+            Some(fec) => {
+                self.inner.link.write_all(fec.encode(bytes)?).await?;
+                if let Some(parity) = fec.parity() {
+                    self.inner.link.write_all(parity).await?;
+                }
+            }
+            None => self.inner.link.write_all(bytes).await?,
+        }
 
         Ok(())
     }
@@ -202,19 +222,27 @@ pub(crate) struct TransportLinkMulticastRx {
 }
 
 impl TransportLinkMulticastRx {
-    pub async fn recv_batch<C, T>(&self, buff: C) -> ZResult<(RBatch, Locator)>
+    pub async fn recv<C, T>(&self, buff: C) -> ZResult<(ZSlice, Locator)>
     where
         C: Fn() -> T + Copy,
         T: ZSliceBuffer + 'static,
     {
-        const ERR: &str = "Read error from link: ";
-
         let mut into = (buff)();
         let (n, locator) = self.inner.link.read(into.as_mut_slice()).await?;
         let buffer = ZSlice::make(Arc::new(into), 0, n).map_err(|_| zerror!("Error"))?;
+        Ok((buffer, locator.into_owned()))
+    }
+
+    pub fn batch<C, T>(&self, buffer: ZSlice, buff: C) -> ZResult<RBatch>
+    where
+        C: Fn() -> T + Copy,
+        T: ZSliceBuffer + 'static,
+    {
+        const ERR: &str = "Read error from link: ";
+
         let mut batch = RBatch::new(self.inner.config.batch, buffer);
         batch.initialize(buff).map_err(|_| zerror!("{ERR}{self}"))?;
-        Ok((batch, locator.into_owned()))
+        Ok(batch)
     }
 
     // pub async fn recv(&mut self) -> ZResult<(TransportMessage, Locator)> {
@@ -322,14 +350,28 @@ impl TransportLinkMulticastUniversal {
                 queue_size: self.transport.manager.config.queue_size,
                 wait_before_drop: self.transport.manager.config.wait_before_drop,
                 backoff: self.transport.manager.config.queue_backoff,
+                latency_target: None,
+                wrr_weights: None,
             };
             // The pipeline
-            let (producer, consumer) = TransmissionPipeline::make(tpc, &priority_tx);
+            let (producer, consumer) = TransmissionPipeline::make(
+                tpc,
+                &priority_tx,
+                self.transport.manager.config.memory_budget.clone(),
+            );
             self.pipeline = Some(producer);
 
             // Spawn the TX task
             let c_link = self.link.clone();
             let c_transport = self.transport.clone();
+            // The multicast transports have no remote zenoh instance and get the default weight
+            let manager = &self.transport.manager;
+            let slot = manager
+                .config
+                .tx_weights
+                .default
+                .map(|weight| manager.tx_scheduler.slot(weight));
+            let is_qos = priority_tx.len() > 1;
 
             let handle = zenoh_runtime::ZRuntime::TX.spawn(async move {
                 let res = tx_task(
@@ -337,6 +379,8 @@ impl TransportLinkMulticastUniversal {
                     c_link.tx(),
                     config,
                     initial_sns,
+                    slot,
+                    is_qos,
                     #[cfg(feature = "stats")]
                     c_transport.stats.clone(),
                 )
@@ -420,6 +464,8 @@ async fn tx_task(
     mut link: TransportLinkMulticastTx,
     config: TransportLinkMulticastConfigUniversal,
     mut last_sns: Vec<PrioritySn>,
+    mut slot: Option<TxSlot>,
+    is_qos: bool,
     #[cfg(feature = "stats")] stats: Arc<TransportStats>,
 ) -> ZResult<()> {
     async fn join(last_join: Instant, join_interval: Duration) {
@@ -434,9 +480,15 @@ async fn tx_task(
     let mut last_join = Instant::now().checked_sub(config.join_interval).unwrap();
     loop {
         tokio::select! {
-            res = pipeline.pull() => {
+            res = scheduler::pull(&mut pipeline, slot.as_mut()) => {
                 match res {
                     Some((mut batch, priority)) => {
+                        // Wait for the turn of the transport among the ones sharing the TX threads
+                        if let Some(slot) = slot.as_mut() {
+                            if !is_qos || priority != Priority::Control as usize {
+                                slot.acquire().await;
+                            }
+                        }
                         // Send the buffer on the link
                         link.send_batch(&mut batch).await?;
                         // Keep track of next SNs
@@ -535,43 +587,67 @@ async fn rx_task(
     async fn read<T, F>(
         link: &mut TransportLinkMulticastRx,
         pool: &RecyclingObjectPool<T, F>,
-    ) -> ZResult<(RBatch, Locator)>
+    ) -> ZResult<(ZSlice, Locator)>
     where
         T: ZSliceBuffer + 'static,
         F: Fn() -> T,
         RecyclingObject<T>: ZSliceBuffer,
     {
-        let (rbatch, locator) = link
-            .recv_batch(|| pool.try_take().unwrap_or_else(|| pool.alloc()))
-            .await?;
-        Ok((rbatch, locator))
+        link.recv(|| pool.try_take().unwrap_or_else(|| pool.alloc()))
+            .await
     }
 
-    // The pool of buffers
-    let mtu = link.inner.config.batch.max_buffer_size();
+    // The pool of buffers, leaving room for the FEC header and parity of full size batches
+    let mtu = match link.inner.config.fec {
+        Some(_) => fec::max_frame_size(link.inner.config.batch.mtu),
+        None => link.inner.config.batch.max_buffer_size(),
+    };
     let mut n = rx_buffer_size / mtu;
     if rx_buffer_size % mtu != 0 {
         n += 1;
     }
 
     let pool = RecyclingObjectPool::new(n, || vec![0_u8; mtu].into_boxed_slice());
+    // The FEC decoders of the remote locators
+    let mut decoders: HashMap<Locator, FecDecoder> = HashMap::new();
+    let mut buffers = Vec::with_capacity(1);
     loop {
         tokio::select! {
             _ = signal.wait() => break,
             res = read(&mut link, &pool) => {
-                let (batch, locator) = res?;
+                let (buffer, locator) = res?;
 
                 #[cfg(feature = "stats")]
-                transport.stats.inc_rx_bytes(batch.len());
+                transport.stats.inc_rx_bytes(buffer.len());
+
+                if link.inner.config.fec.is_some() {
+                    let decoder = decoders
+                        .entry(locator.clone())
+                        .or_insert_with(|| FecDecoder::new(link.inner.config.batch.mtu));
+                    match decoder.decode(buffer, &mut buffers) {
+                        #[allow(unused_variables)] // Used when stats feature is enabled
+                        Ok(recovered) => {
+                            #[cfg(feature = "stats")]
+                            transport.stats.inc_rx_fec_recovered(recovered);
+                        }
+                        Err(e) => tracing::debug!("{}: {}", link, e),
+                    }
+                } else {
+                    buffers.push(buffer);
+                }
 
-                // Deserialize all the messages from the current ZBuf
-                transport.read_messages(
-                    batch,
-                    locator,
-                    batch_size,
-                    #[cfg(feature = "stats")]
-                    &transport,
-                )?;
+                for buffer in buffers.drain(..) {
+                    let batch = link
+                        .batch(buffer, || pool.try_take().unwrap_or_else(|| pool.alloc()))?;
+                    // Deserialize all the messages from the current ZBuf
+                    transport.read_messages(
+                        batch,
+                        locator.clone(),
+                        batch_size,
+                        #[cfg(feature = "stats")]
+                        &transport,
+                    )?;
+                }
             }
         }
     }
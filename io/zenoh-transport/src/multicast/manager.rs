@@ -40,6 +40,7 @@ pub struct TransportManagerConfigMulticast {
     pub is_shm: bool,
     #[cfg(feature = "transport_compression")]
     pub is_compression: bool,
+    pub fec: Option<u8>,
 }
 
 pub struct TransportManagerBuilderMulticast {
@@ -52,6 +53,7 @@ pub struct TransportManagerBuilderMulticast {
     is_shm: bool,
     #[cfg(feature = "transport_compression")]
     is_compression: bool,
+    fec: Option<u8>,
 }
 
 pub struct TransportManagerStateMulticast {
@@ -107,6 +109,11 @@ impl TransportManagerBuilderMulticast {
         self
     }
 
+    pub fn fec(mut self, fec: Option<u8>) -> Self {
+        self.fec = fec;
+        self
+    }
+
     pub fn from_config(mut self, config: &Config) -> ZResult<TransportManagerBuilderMulticast> {
         self = self.lease(Duration::from_millis(
             *config.transport().link().tx().lease(),
@@ -117,6 +124,8 @@ impl TransportManagerBuilderMulticast {
         ));
         self = self.max_sessions(config.transport().multicast().max_sessions().unwrap());
         self = self.qos(*config.transport().multicast().qos().enabled());
+        let fec = config.transport().multicast().fec();
+        self = self.fec(fec.enabled().then_some(*fec.group_size()));
         #[cfg(feature = "shared-memory")]
         {
             self = self.shm(*config.transport().shared_memory().enabled());
@@ -136,6 +145,7 @@ impl TransportManagerBuilderMulticast {
             is_shm: self.is_shm,
             #[cfg(feature = "transport_compression")]
             is_compression: self.is_compression,
+            fec: self.fec,
         };
 
         let state = TransportManagerStateMulticast {
@@ -169,6 +179,7 @@ impl Default for TransportManagerBuilderMulticast {
             is_shm: *shm.enabled(),
             #[cfg(feature = "transport_compression")]
             is_compression: *compression.enabled(),
+            fec: None,
         };
         tmb.from_config(&Config::default()).unwrap()
     }
@@ -11,6 +11,7 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 pub(crate) mod establishment;
+pub(crate) mod fec;
 pub(crate) mod link;
 pub(crate) mod manager;
 pub(crate) mod rx;
@@ -254,51 +254,54 @@ impl TransportMulticastInner {
         batch_size: BatchSize,
         #[cfg(feature = "stats")] transport: &TransportMulticastInner,
     ) -> ZResult<()> {
-        while !batch.is_empty() {
-            let msg: TransportMessage = batch
-                .decode()
-                .map_err(|_| zerror!("{}: decoding error", locator))?;
+        // The messages, and the network messages they carry, are decoded with the limits of
+        // the configuration of this transport
+        self.manager.config.codec_limits.scope(|| {
+            while !batch.is_empty() {
+                let msg: TransportMessage = batch
+                    .decode()
+                    .map_err(|_| zerror!("{}: decoding error", locator))?;
 
-            tracing::trace!("Received: {:?}", msg);
+                tracing::trace!("Received: {:?}", msg);
 
-            #[cfg(feature = "stats")]
-            {
-                transport.stats.inc_rx_t_msgs(1);
-            }
+                #[cfg(feature = "stats")]
+                {
+                    transport.stats.inc_rx_t_msgs(1);
+                }
 
-            let r_guard = zread!(self.peers);
-            match r_guard.get(&locator) {
-                Some(peer) => {
-                    peer.set_active();
-                    match msg.body {
-                        TransportBody::Frame(msg) => self.handle_frame(msg, peer)?,
-                        TransportBody::Fragment(fragment) => {
-                            self.handle_fragment(fragment, peer)?
-                        }
-                        TransportBody::Join(join) => self.handle_join_from_peer(join, peer)?,
-                        TransportBody::KeepAlive(KeepAlive { .. }) => {}
-                        TransportBody::Close(Close { reason, .. }) => {
-                            drop(r_guard);
-                            self.del_peer(&locator, reason)?;
-                        }
-                        _ => {
-                            tracing::debug!(
-                                "Transport: {}. Message handling not implemented: {:?}",
-                                self.manager.config.zid,
-                                msg
-                            );
+                let r_guard = zread!(self.peers);
+                match r_guard.get(&locator) {
+                    Some(peer) => {
+                        peer.set_active();
+                        match msg.body {
+                            TransportBody::Frame(msg) => self.handle_frame(msg, peer)?,
+                            TransportBody::Fragment(fragment) => {
+                                self.handle_fragment(fragment, peer)?
+                            }
+                            TransportBody::Join(join) => self.handle_join_from_peer(join, peer)?,
+                            TransportBody::KeepAlive(KeepAlive { .. }) => {}
+                            TransportBody::Close(Close { reason, .. }) => {
+                                drop(r_guard);
+                                self.del_peer(&locator, reason)?;
+                            }
+                            _ => {
+                                tracing::debug!(
+                                    "Transport: {}. Message handling not implemented: {:?}",
+                                    self.manager.config.zid,
+                                    msg
+                                );
+                            }
                         }
                     }
-                }
-                None => {
-                    drop(r_guard);
-                    if let TransportBody::Join(join) = msg.body {
-                        self.handle_join_from_unknown(join, &locator, batch_size)?;
+                    None => {
+                        drop(r_guard);
+                        if let TransportBody::Join(join) = msg.body {
+                            self.handle_join_from_unknown(join, &locator, batch_size)?;
+                        }
                     }
                 }
             }
-        }
-
-        Ok(())
+            Ok(())
+        })
     }
 }
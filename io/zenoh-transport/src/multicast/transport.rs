@@ -427,6 +427,7 @@ impl TransportMulticastInner {
 
             // TODO(yuyuan): Unify the termination
             peer.token.cancel();
+            peer.handler.close_reason(reason);
             peer.handler.closing();
             drop(guard);
             peer.handler.closed();
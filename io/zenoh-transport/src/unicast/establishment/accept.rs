@@ -235,7 +235,11 @@ impl<'a, 'b: 'a> AcceptFsm for &'a mut AcceptLink<'b> {
         // Extension Compression
         #[cfg(feature = "transport_compression")]
         self.ext_compression
-            .recv_init_syn((&mut state.link.ext_compression, init_syn.ext_compression))
+            .recv_init_syn((
+                &mut state.link.ext_compression,
+                init_syn.ext_compression,
+                init_syn.ext_compression_algorithms,
+            ))
             .await
             .map_err(|e| (e, Some(close::reason::GENERIC)))?;
 
@@ -301,14 +305,14 @@ impl<'a, 'b: 'a> AcceptFsm for &'a mut AcceptLink<'b> {
             .await
             .map_err(|e| (e, Some(close::reason::GENERIC)))?;
 
-        // Extension MultiLink
-        let ext_compression = zcondfeat!(
+        // Extension Compression
+        let (ext_compression, ext_compression_algorithms) = zcondfeat!(
             "transport_compression",
             self.ext_compression
                 .send_init_ack(&state.link.ext_compression)
                 .await
                 .map_err(|e| (e, Some(close::reason::GENERIC)))?,
-            None
+            (None, None)
         );
 
         // Create the cookie
@@ -360,6 +364,7 @@ impl<'a, 'b: 'a> AcceptFsm for &'a mut AcceptLink<'b> {
             ext_mlink,
             ext_lowlatency,
             ext_compression,
+            ext_compression_algorithms,
         }
         .into();
 
@@ -586,7 +591,11 @@ impl<'a, 'b: 'a> AcceptFsm for &'a mut AcceptLink<'b> {
     }
 }
 
-pub(crate) async fn accept_link(link: LinkUnicast, manager: &TransportManager) -> ZResult<()> {
+pub(crate) async fn accept_link(
+    link: LinkUnicast,
+    rate_limit: Option<u64>,
+    manager: &TransportManager,
+) -> ZResult<()> {
     let mtu = link.get_mtu();
     let is_streamed = link.is_streamed();
     let config = TransportLinkUnicastConfig {
@@ -596,7 +605,9 @@ pub(crate) async fn accept_link(link: LinkUnicast, manager: &TransportManager) -
             is_streamed,
             #[cfg(feature = "transport_compression")]
             is_compression: false,
+            ..Default::default()
         },
+        rate_limit,
     };
     let mut link = TransportLinkUnicast::new(link, config);
     let mut fsm = AcceptLink {
@@ -658,6 +669,7 @@ pub(crate) async fn accept_link(link: LinkUnicast, manager: &TransportManager) -
                 #[cfg(feature = "transport_compression")]
                 ext_compression: ext::compression::StateAccept::new(
                     manager.config.unicast.is_compression,
+                    manager.config.unicast.compression_algorithm,
                 ),
             },
         };
@@ -717,7 +729,10 @@ pub(crate) async fn accept_link(link: LinkUnicast, manager: &TransportManager) -
             is_streamed,
             #[cfg(feature = "transport_compression")]
             is_compression: state.link.ext_compression.is_compression(),
+            #[cfg(feature = "transport_compression")]
+            compression_algorithm: state.link.ext_compression.algorithm(),
         },
+        rate_limit,
     };
     let a_link = link.reconfigure(a_config);
     let s_link = format!("{:?}", a_link);
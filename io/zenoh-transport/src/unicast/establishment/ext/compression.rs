@@ -11,6 +11,7 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+use crate::common::batch::CompressionAlgorithm;
 use crate::unicast::establishment::{AcceptFsm, OpenFsm};
 use async_trait::async_trait;
 use core::marker::PhantomData;
@@ -33,22 +34,73 @@ impl<'a> CompressionFsm<'a> {
     }
 }
 
+// Select the algorithm to compress the batches with among the ones the other side is able to
+// decompress: the configured one if possible, lz4 otherwise. Each side picks its own algorithm,
+// as the batch header tells which one has been used.
+fn negotiate(
+    is_compression: &mut bool,
+    algorithm: &mut CompressionAlgorithm,
+    other_ext: Option<init::ext::Compression>,
+    other_algorithms: Option<init::ext::CompressionAlgorithms>,
+) {
+    if other_ext.is_none() {
+        *is_compression = false;
+        return;
+    }
+    // The other side predates the negotiation of the algorithm: it only decompresses lz4
+    let other_mask = other_algorithms.map_or(CompressionAlgorithm::Lz4.mask(), |ext| ext.value);
+    if other_mask & algorithm.mask() == 0 {
+        if other_mask & CompressionAlgorithm::Lz4.mask() != 0 {
+            *algorithm = CompressionAlgorithm::Lz4;
+        } else {
+            *is_compression = false;
+        }
+    }
+}
+
+// The extensions announcing the compression and the algorithms this side is able to decompress
+fn exts(
+    is_compression: bool,
+) -> (
+    Option<init::ext::Compression>,
+    Option<init::ext::CompressionAlgorithms>,
+) {
+    if is_compression {
+        (
+            Some(init::ext::Compression::new()),
+            Some(init::ext::CompressionAlgorithms::new(
+                CompressionAlgorithm::SUPPORTED,
+            )),
+        )
+    } else {
+        (None, None)
+    }
+}
+
 /*************************************/
 /*              OPEN                 */
 /*************************************/
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) struct StateOpen {
     is_compression: bool,
+    algorithm: CompressionAlgorithm,
 }
 
 impl StateOpen {
-    pub(crate) const fn new(is_compression: bool) -> Self {
-        Self { is_compression }
+    pub(crate) const fn new(is_compression: bool, algorithm: CompressionAlgorithm) -> Self {
+        Self {
+            is_compression,
+            algorithm,
+        }
     }
 
     pub(crate) const fn is_compression(&self) -> bool {
         self.is_compression
     }
+
+    pub(crate) const fn algorithm(&self) -> CompressionAlgorithm {
+        self.algorithm
+    }
 }
 
 #[async_trait]
@@ -56,25 +108,34 @@ impl<'a> OpenFsm for &'a CompressionFsm<'a> {
     type Error = ZError;
 
     type SendInitSynIn = &'a StateOpen;
-    type SendInitSynOut = Option<init::ext::Compression>;
+    type SendInitSynOut = (
+        Option<init::ext::Compression>,
+        Option<init::ext::CompressionAlgorithms>,
+    );
     async fn send_init_syn(
         self,
         state: Self::SendInitSynIn,
     ) -> Result<Self::SendInitSynOut, Self::Error> {
-        let output = state
-            .is_compression
-            .then_some(init::ext::Compression::new());
-        Ok(output)
+        Ok(exts(state.is_compression))
     }
 
-    type RecvInitAckIn = (&'a mut StateOpen, Option<init::ext::Compression>);
+    type RecvInitAckIn = (
+        &'a mut StateOpen,
+        Option<init::ext::Compression>,
+        Option<init::ext::CompressionAlgorithms>,
+    );
     type RecvInitAckOut = ();
     async fn recv_init_ack(
         self,
         input: Self::RecvInitAckIn,
     ) -> Result<Self::RecvInitAckOut, Self::Error> {
-        let (state, other_ext) = input;
-        state.is_compression &= other_ext.is_some();
+        let (state, other_ext, other_algorithms) = input;
+        negotiate(
+            &mut state.is_compression,
+            &mut state.algorithm,
+            other_ext,
+            other_algorithms,
+        );
         Ok(())
     }
 
@@ -103,22 +164,35 @@ impl<'a> OpenFsm for &'a CompressionFsm<'a> {
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) struct StateAccept {
     is_compression: bool,
+    algorithm: CompressionAlgorithm,
 }
 
 impl StateAccept {
-    pub(crate) const fn new(is_compression: bool) -> Self {
-        Self { is_compression }
+    pub(crate) const fn new(is_compression: bool, algorithm: CompressionAlgorithm) -> Self {
+        Self {
+            is_compression,
+            algorithm,
+        }
     }
 
     pub(crate) const fn is_compression(&self) -> bool {
         self.is_compression
     }
 
+    pub(crate) const fn algorithm(&self) -> CompressionAlgorithm {
+        self.algorithm
+    }
+
     #[cfg(test)]
     pub(crate) fn rand() -> Self {
         use rand::Rng;
         let mut rng = rand::thread_rng();
-        Self::new(rng.gen_bool(0.5))
+        let algorithm = if rng.gen_bool(0.5) {
+            CompressionAlgorithm::Zstd
+        } else {
+            CompressionAlgorithm::Lz4
+        };
+        Self::new(rng.gen_bool(0.5), algorithm)
     }
 }
 
@@ -132,6 +206,8 @@ where
     fn write(self, writer: &mut W, x: &StateAccept) -> Self::Output {
         let is_compression = u8::from(x.is_compression);
         self.write(&mut *writer, is_compression)?;
+        let algorithm = u8::from(x.algorithm == CompressionAlgorithm::Zstd);
+        self.write(&mut *writer, algorithm)?;
         Ok(())
     }
 }
@@ -145,7 +221,16 @@ where
     fn read(self, reader: &mut R) -> Result<StateAccept, Self::Error> {
         let is_compression: u8 = self.read(&mut *reader)?;
         let is_compression = is_compression == 1;
-        Ok(StateAccept { is_compression })
+        let algorithm: u8 = self.read(&mut *reader)?;
+        let algorithm = if algorithm == 1 {
+            CompressionAlgorithm::Zstd
+        } else {
+            CompressionAlgorithm::Lz4
+        };
+        Ok(StateAccept {
+            is_compression,
+            algorithm,
+        })
     }
 }
 
@@ -153,27 +238,36 @@ where
 impl<'a> AcceptFsm for &'a CompressionFsm<'a> {
     type Error = ZError;
 
-    type RecvInitSynIn = (&'a mut StateAccept, Option<init::ext::Compression>);
+    type RecvInitSynIn = (
+        &'a mut StateAccept,
+        Option<init::ext::Compression>,
+        Option<init::ext::CompressionAlgorithms>,
+    );
     type RecvInitSynOut = ();
     async fn recv_init_syn(
         self,
         input: Self::RecvInitSynIn,
     ) -> Result<Self::RecvInitSynOut, Self::Error> {
-        let (state, other_ext) = input;
-        state.is_compression &= other_ext.is_some();
+        let (state, other_ext, other_algorithms) = input;
+        negotiate(
+            &mut state.is_compression,
+            &mut state.algorithm,
+            other_ext,
+            other_algorithms,
+        );
         Ok(())
     }
 
     type SendInitAckIn = &'a StateAccept;
-    type SendInitAckOut = Option<init::ext::Compression>;
+    type SendInitAckOut = (
+        Option<init::ext::Compression>,
+        Option<init::ext::CompressionAlgorithms>,
+    );
     async fn send_init_ack(
         self,
         state: Self::SendInitAckIn,
     ) -> Result<Self::SendInitAckOut, Self::Error> {
-        let output = state
-            .is_compression
-            .then_some(init::ext::Compression::new());
-        Ok(output)
+        Ok(exts(state.is_compression))
     }
 
     type RecvOpenSynIn = (&'a mut StateAccept, Option<open::ext::Compression>);
@@ -194,3 +288,33 @@ impl<'a> AcceptFsm for &'a CompressionFsm<'a> {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compression_negotiation() {
+        let zstd = Some(init::ext::CompressionAlgorithms::new(
+            CompressionAlgorithm::SUPPORTED,
+        ));
+
+        // Both sides support zstd
+        let (mut is_compression, mut algorithm) = (true, CompressionAlgorithm::Zstd);
+        let (ext, _) = exts(true);
+        negotiate(&mut is_compression, &mut algorithm, ext, zstd);
+        assert!(is_compression);
+        assert_eq!(algorithm, CompressionAlgorithm::Zstd);
+
+        // The other side only sends the Compression extension: it predates zstd
+        let (mut is_compression, mut algorithm) = (true, CompressionAlgorithm::Zstd);
+        negotiate(&mut is_compression, &mut algorithm, ext, None);
+        assert!(is_compression);
+        assert_eq!(algorithm, CompressionAlgorithm::Lz4);
+
+        // The other side doesn't compress
+        let (mut is_compression, mut algorithm) = (true, CompressionAlgorithm::Lz4);
+        negotiate(&mut is_compression, &mut algorithm, None, None);
+        assert!(!is_compression);
+    }
+}
@@ -175,13 +175,13 @@ impl<'a, 'b: 'a> OpenFsm for &'a mut OpenLink<'b> {
             .map_err(|e| (e, Some(close::reason::GENERIC)))?;
 
         // Extension Compression
-        let ext_compression = zcondfeat!(
+        let (ext_compression, ext_compression_algorithms) = zcondfeat!(
             "transport_compression",
             self.ext_compression
                 .send_init_syn(&state.link.ext_compression)
                 .await
                 .map_err(|e| (e, Some(close::reason::GENERIC)))?,
-            None
+            (None, None)
         );
 
         let msg: TransportMessage = InitSyn {
@@ -196,6 +196,7 @@ impl<'a, 'b: 'a> OpenFsm for &'a mut OpenLink<'b> {
             ext_mlink,
             ext_lowlatency,
             ext_compression,
+            ext_compression_algorithms,
         }
         .into();
 
@@ -324,7 +325,11 @@ impl<'a, 'b: 'a> OpenFsm for &'a mut OpenLink<'b> {
         // Extension Compression
         #[cfg(feature = "transport_compression")]
         self.ext_compression
-            .recv_init_ack((&mut state.link.ext_compression, init_ack.ext_compression))
+            .recv_init_ack((
+                &mut state.link.ext_compression,
+                init_ack.ext_compression,
+                init_ack.ext_compression_algorithms,
+            ))
             .await
             .map_err(|e| (e, Some(close::reason::GENERIC)))?;
 
@@ -513,6 +518,7 @@ impl<'a, 'b: 'a> OpenFsm for &'a mut OpenLink<'b> {
 
 pub(crate) async fn open_link(
     link: LinkUnicast,
+    rate_limit: Option<u64>,
     manager: &TransportManager,
 ) -> ZResult<TransportUnicast> {
     let is_streamed = link.is_streamed();
@@ -523,7 +529,9 @@ pub(crate) async fn open_link(
             is_streamed,
             #[cfg(feature = "transport_compression")]
             is_compression: false, // Perform the exchange Init/Open exchange with no compression
+            ..Default::default()
         },
+        rate_limit,
     };
     let mut link = TransportLinkUnicast::new(link, config);
     let mut fsm = OpenLink {
@@ -570,6 +578,7 @@ pub(crate) async fn open_link(
             #[cfg(feature = "transport_compression")]
             ext_compression: ext::compression::StateOpen::new(
                 manager.config.unicast.is_compression,
+                manager.config.unicast.compression_algorithm,
             ),
         },
     };
@@ -630,7 +639,10 @@ pub(crate) async fn open_link(
             is_streamed,
             #[cfg(feature = "transport_compression")]
             is_compression: state.link.ext_compression.is_compression(),
+            #[cfg(feature = "transport_compression")]
+            compression_algorithm: state.link.ext_compression.algorithm(),
         },
+        rate_limit,
     };
     let o_link = link.reconfigure(o_config);
     let s_link = format!("{:?}", o_link);
@@ -31,6 +31,8 @@ pub(crate) struct TransportLinkUnicastConfig {
     // Inbound / outbound
     pub(crate) direction: TransportLinkUnicastDirection,
     pub(crate) batch: BatchConfig,
+    // The maximum bandwidth in bytes per second
+    pub(crate) rate_limit: Option<u64>,
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -56,10 +56,12 @@ impl TransportUnicastLowlatency {
         link: &LinkUnicast,
     ) -> ZResult<()> {
         let codec = Zenoh080::new();
+        // The messages are decoded with the limits of the configuration of this transport
+        let limits = self.manager.config.codec_limits;
         let mut reader = zslice.reader();
         while reader.can_read() {
-            let msg: TransportMessageLowLatency = codec
-                .read(&mut reader)
+            let msg: TransportMessageLowLatency = limits
+                .scope(|| codec.read(&mut reader))
                 .map_err(|_| zerror!("{}: decoding error", link))?;
 
             tracing::trace!("Received: {:?}", msg);
@@ -70,12 +72,15 @@ impl TransportUnicastLowlatency {
             }
 
             match msg.body {
-                zenoh_protocol::transport::TransportBodyLowLatency::Close(_) => {
+                zenoh_protocol::transport::TransportBodyLowLatency::Close(close) => {
+                    if let Some(cb) = zread!(self.callback).as_ref() {
+                        cb.close_reason(close.reason);
+                    }
                     let _ = self.delete().await;
                 }
                 zenoh_protocol::transport::TransportBodyLowLatency::KeepAlive(_) => {}
                 zenoh_protocol::transport::TransportBodyLowLatency::Network(msg) => {
-                    let _ = self.trigger_callback(msg);
+                    let _ = limits.scope(|| self.trigger_callback(msg));
                 }
             }
         }
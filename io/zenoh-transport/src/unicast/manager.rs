@@ -14,11 +14,14 @@
 #[cfg(feature = "shared-memory")]
 use super::shared_memory_unicast::SharedMemoryUnicast;
 use super::{link::LinkUnicastWithOpenAck, transport_unicast_inner::InitTransportResult};
+#[cfg(feature = "transport_compression")]
+use crate::common::batch::CompressionAlgorithm;
 #[cfg(feature = "transport_auth")]
 use crate::unicast::establishment::ext::auth::Auth;
 #[cfg(feature = "transport_multilink")]
 use crate::unicast::establishment::ext::multilink::MultiLink;
 use crate::{
+    common::rate_limit::parse_rate_limit,
     unicast::{
         lowlatency::transport::TransportUnicastLowlatency,
         transport_unicast_inner::{InitTransportError, TransportUnicastTrait},
@@ -29,6 +32,7 @@ use crate::{
 };
 use std::{
     collections::HashMap,
+    net::IpAddr,
     sync::{
         atomic::{AtomicUsize, Ordering::SeqCst},
         Arc,
@@ -40,12 +44,15 @@ use tokio::sync::{Mutex as AsyncMutex, MutexGuard as AsyncMutexGuard};
 use zenoh_config::CompressionUnicastConf;
 #[cfg(feature = "shared-memory")]
 use zenoh_config::SharedMemoryConf;
-use zenoh_config::{Config, LinkTxConf, QoSUnicastConf, TransportUnicastConf};
+use zenoh_config::{
+    Config, HibernationUnicastConf, LinkTxConf, QoSUnicastConf, TransportUnicastConf,
+    TxQueueUnicastConf,
+};
 use zenoh_core::{zasynclock, zcondfeat};
 use zenoh_crypto::PseudoRng;
 use zenoh_link::*;
 use zenoh_protocol::{
-    core::{endpoint, ZenohId},
+    core::{endpoint, Priority, ZenohId},
     transport::{close, TransportSn},
 };
 use zenoh_result::{bail, zerror, ZResult};
@@ -56,17 +63,27 @@ use zenoh_result::{bail, zerror, ZResult};
 pub struct TransportManagerConfigUnicast {
     pub lease: Duration,
     pub keep_alive: usize,
+    pub echo_interval: Option<Duration>,
+    pub rate_limit: Option<u64>,
     pub accept_timeout: Duration,
     pub accept_pending: usize,
     pub max_sessions: usize,
     pub is_qos: bool,
     pub is_lowlatency: bool,
+    pub hibernation_timeout: Option<Duration>,
+    pub hibernation_retention: Duration,
+    pub latency_target: Option<Duration>,
+    pub wrr_weights: Option<[usize; Priority::NUM]>,
     #[cfg(feature = "transport_multilink")]
     pub max_links: usize,
+    #[cfg(feature = "transport_multilink")]
+    pub max_links_per_session: usize,
     #[cfg(feature = "shared-memory")]
     pub is_shm: bool,
     #[cfg(feature = "transport_compression")]
     pub is_compression: bool,
+    #[cfg(feature = "transport_compression")]
+    pub compression_algorithm: CompressionAlgorithm,
 }
 
 pub struct TransportManagerStateUnicast {
@@ -100,19 +117,29 @@ pub struct TransportManagerBuilderUnicast {
     //       target interval.
     pub(super) lease: Duration,
     pub(super) keep_alive: usize,
+    pub(super) echo_interval: Option<Duration>,
+    pub(super) rate_limit: Option<u64>,
     pub(super) accept_timeout: Duration,
     pub(super) accept_pending: usize,
     pub(super) max_sessions: usize,
     pub(super) is_qos: bool,
     #[cfg(feature = "transport_multilink")]
     pub(super) max_links: usize,
+    #[cfg(feature = "transport_multilink")]
+    pub(super) max_links_per_session: usize,
     #[cfg(feature = "shared-memory")]
     pub(super) is_shm: bool,
     #[cfg(feature = "transport_auth")]
     pub(super) authenticator: Auth,
     pub(super) is_lowlatency: bool,
+    pub(super) hibernation_timeout: Option<Duration>,
+    pub(super) hibernation_retention: Duration,
+    pub(super) latency_target: Option<Duration>,
+    pub(super) wrr_weights: Option<[usize; Priority::NUM]>,
     #[cfg(feature = "transport_compression")]
     pub(super) is_compression: bool,
+    #[cfg(feature = "transport_compression")]
+    pub(super) compression_algorithm: CompressionAlgorithm,
 }
 
 impl TransportManagerBuilderUnicast {
@@ -126,6 +153,17 @@ impl TransportManagerBuilderUnicast {
         self
     }
 
+    pub fn echo_interval(mut self, echo_interval: Option<Duration>) -> Self {
+        self.echo_interval = echo_interval;
+        self
+    }
+
+    /// The maximum bandwidth of each link in bytes per second.
+    pub fn rate_limit(mut self, rate_limit: Option<u64>) -> Self {
+        self.rate_limit = rate_limit;
+        self
+    }
+
     pub fn accept_timeout(mut self, accept_timeout: Duration) -> Self {
         self.accept_timeout = accept_timeout;
         self
@@ -151,12 +189,45 @@ impl TransportManagerBuilderUnicast {
         self
     }
 
+    /// Hibernate the transports on which no data has been exchanged for `hibernation_timeout`.
+    /// Hibernation is disabled when `None`.
+    pub fn hibernation_timeout(mut self, hibernation_timeout: Option<Duration>) -> Self {
+        self.hibernation_timeout = hibernation_timeout;
+        self
+    }
+
+    pub fn hibernation_retention(mut self, hibernation_retention: Duration) -> Self {
+        self.hibernation_retention = hibernation_retention;
+        self
+    }
+
+    /// Flush the batches so that a message does not wait more than `latency_target` for its batch
+    /// to be filled, adapting to the arrival rate of the messages. The batches are otherwise flushed
+    /// once they stop growing or are full.
+    pub fn latency_target(mut self, latency_target: Option<Duration>) -> Self {
+        self.latency_target = latency_target;
+        self
+    }
+
+    /// Drain the priority queues with a weighted round robin: up to `wrr_weights[priority]` batches
+    /// of each priority are sent in turn. The highest priority is always drained first when `None`.
+    pub fn wrr_weights(mut self, wrr_weights: Option<[usize; Priority::NUM]>) -> Self {
+        self.wrr_weights = wrr_weights;
+        self
+    }
+
     #[cfg(feature = "transport_multilink")]
     pub fn max_links(mut self, max_links: usize) -> Self {
         self.max_links = max_links;
         self
     }
 
+    #[cfg(feature = "transport_multilink")]
+    pub fn max_links_per_session(mut self, max_links_per_session: usize) -> Self {
+        self.max_links_per_session = max_links_per_session;
+        self
+    }
+
     #[cfg(feature = "transport_auth")]
     pub fn authenticator(mut self, authenticator: Auth) -> Self {
         self.authenticator = authenticator;
@@ -175,11 +246,35 @@ impl TransportManagerBuilderUnicast {
         self
     }
 
+    #[cfg(feature = "transport_compression")]
+    pub fn compression_algorithm(mut self, compression_algorithm: CompressionAlgorithm) -> Self {
+        self.compression_algorithm = compression_algorithm;
+        self
+    }
+
     pub async fn from_config(mut self, config: &Config) -> ZResult<TransportManagerBuilderUnicast> {
         self = self.lease(Duration::from_millis(
             *config.transport().link().tx().lease(),
         ));
         self = self.keep_alive(*config.transport().link().tx().keep_alive());
+        self = self.echo_interval(
+            config
+                .transport()
+                .link()
+                .tx()
+                .echo_interval()
+                .map(Duration::from_millis),
+        );
+        self = self.rate_limit(
+            config
+                .transport()
+                .link()
+                .tx()
+                .rate_limit()
+                .as_deref()
+                .map(parse_rate_limit)
+                .transpose()?,
+        );
         self = self.accept_timeout(Duration::from_millis(
             *config.transport().unicast().accept_timeout(),
         ));
@@ -187,10 +282,28 @@ impl TransportManagerBuilderUnicast {
         self = self.max_sessions(*config.transport().unicast().max_sessions());
         self = self.qos(*config.transport().unicast().qos().enabled());
         self = self.lowlatency(*config.transport().unicast().lowlatency());
+        let hibernation = config.transport().unicast().hibernation();
+        self = self.hibernation_timeout(
+            hibernation
+                .enabled()
+                .then(|| Duration::from_millis(*hibernation.idle_timeout())),
+        );
+        self = self.hibernation_retention(Duration::from_millis(*hibernation.retention()));
+        self = self.latency_target(
+            config
+                .transport()
+                .unicast()
+                .tx()
+                .latency_target_us()
+                .map(Duration::from_micros),
+        );
+        self = self.wrr_weights(wrr_weights(config.transport().unicast().tx().queue())?);
 
         #[cfg(feature = "transport_multilink")]
         {
             self = self.max_links(*config.transport().unicast().max_links());
+            self =
+                self.max_links_per_session(*config.transport().unicast().max_links_per_session());
         }
         #[cfg(feature = "shared-memory")]
         {
@@ -202,7 +315,9 @@ impl TransportManagerBuilderUnicast {
         }
         #[cfg(feature = "transport_compression")]
         {
-            self = self.compression(*config.transport().unicast().compression().enabled());
+            let compression = config.transport().unicast().compression();
+            self = self.compression(*compression.enabled());
+            self = self.compression_algorithm(compression.algorithm().parse()?);
         }
 
         Ok(self)
@@ -215,21 +330,39 @@ impl TransportManagerBuilderUnicast {
         if self.is_qos && self.is_lowlatency {
             bail!("'qos' and 'lowlatency' options are incompatible");
         }
+        #[cfg(feature = "transport_multilink")]
+        if self.max_links_per_session == 0 || self.max_links_per_session > self.max_links.max(1) {
+            bail!(
+                "'max_links_per_session' must be in [1, max_links = {}]: {}",
+                self.max_links.max(1),
+                self.max_links_per_session
+            );
+        }
 
         let config = TransportManagerConfigUnicast {
             lease: self.lease,
             keep_alive: self.keep_alive,
+            echo_interval: self.echo_interval,
+            rate_limit: self.rate_limit,
             accept_timeout: self.accept_timeout,
             accept_pending: self.accept_pending,
             max_sessions: self.max_sessions,
             is_qos: self.is_qos,
             #[cfg(feature = "transport_multilink")]
             max_links: self.max_links,
+            #[cfg(feature = "transport_multilink")]
+            max_links_per_session: self.max_links_per_session,
             #[cfg(feature = "shared-memory")]
             is_shm: self.is_shm,
             is_lowlatency: self.is_lowlatency,
+            hibernation_timeout: self.hibernation_timeout,
+            hibernation_retention: self.hibernation_retention,
+            latency_target: self.latency_target,
+            wrr_weights: self.wrr_weights,
             #[cfg(feature = "transport_compression")]
             is_compression: self.is_compression,
+            #[cfg(feature = "transport_compression")]
+            compression_algorithm: self.compression_algorithm,
         };
 
         let state = TransportManagerStateUnicast {
@@ -255,6 +388,7 @@ impl Default for TransportManagerBuilderUnicast {
         let transport = TransportUnicastConf::default();
         let link_tx = LinkTxConf::default();
         let qos = QoSUnicastConf::default();
+        let hibernation = HibernationUnicastConf::default();
         #[cfg(feature = "shared-memory")]
         let shm = SharedMemoryConf::default();
         #[cfg(feature = "transport_compression")]
@@ -263,23 +397,46 @@ impl Default for TransportManagerBuilderUnicast {
         Self {
             lease: Duration::from_millis(*link_tx.lease()),
             keep_alive: *link_tx.keep_alive(),
+            echo_interval: link_tx.echo_interval().map(Duration::from_millis),
+            rate_limit: None,
             accept_timeout: Duration::from_millis(*transport.accept_timeout()),
             accept_pending: *transport.accept_pending(),
             max_sessions: *transport.max_sessions(),
             is_qos: *qos.enabled(),
             #[cfg(feature = "transport_multilink")]
             max_links: *transport.max_links(),
+            #[cfg(feature = "transport_multilink")]
+            max_links_per_session: *transport.max_links_per_session(),
             #[cfg(feature = "shared-memory")]
             is_shm: *shm.enabled(),
             #[cfg(feature = "transport_auth")]
             authenticator: Auth::default(),
             is_lowlatency: *transport.lowlatency(),
+            hibernation_timeout: hibernation
+                .enabled()
+                .then(|| Duration::from_millis(*hibernation.idle_timeout())),
+            hibernation_retention: Duration::from_millis(*hibernation.retention()),
+            latency_target: transport
+                .tx()
+                .latency_target_us()
+                .map(Duration::from_micros),
+            wrr_weights: wrr_weights(transport.tx().queue()).unwrap_or_default(),
             #[cfg(feature = "transport_compression")]
             is_compression: *compression.enabled(),
+            #[cfg(feature = "transport_compression")]
+            compression_algorithm: compression.algorithm().parse().unwrap_or_default(),
         }
     }
 }
 
+fn wrr_weights(queue: &TxQueueUnicastConf) -> ZResult<Option<[usize; Priority::NUM]>> {
+    match queue.scheduling().as_str() {
+        "strict" => Ok(None),
+        "wrr" => Ok(Some(queue.weights().as_array())),
+        s => bail!("Unknown TX queue scheduling: {}", s),
+    }
+}
+
 /*************************************/
 /*         TRANSPORT MANAGER         */
 /*************************************/
@@ -406,6 +563,49 @@ impl TransportManager {
         vec
     }
 
+    // The rate limit of the links established with an endpoint, by default the configured one
+    fn endpoint_rate_limit(&self, endpoint: &EndPoint) -> ZResult<Option<u64>> {
+        match endpoint.config().get(RATE_LIMIT) {
+            Some(rate_limit) => parse_rate_limit(rate_limit).map(Some),
+            None => Ok(self.config.unicast.rate_limit),
+        }
+    }
+
+    // The rate limit of a link accepted by a listener, i.e. the listener with the same
+    // address as the source of the link or listening on all the addresses of its port
+    async fn listener_rate_limit(&self, link: &LinkUnicast) -> Option<u64> {
+        let src = link.get_src();
+        let listener = self.get_listeners_unicast().await.into_iter().find(|ep| {
+            if ep.protocol() != src.protocol() {
+                return false;
+            }
+            if ep.address() == src.address() {
+                return true;
+            }
+            let ep_addr = ep.address();
+            let src_addr = src.address();
+            let (Some((host, port)), Some((_, src_port))) = (
+                ep_addr.as_str().rsplit_once(':'),
+                src_addr.as_str().rsplit_once(':'),
+            ) else {
+                return false;
+            };
+            port == src_port
+                && host
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .parse::<IpAddr>()
+                    .is_ok_and(|ip| ip.is_unspecified())
+        });
+        match listener {
+            Some(ep) => self.endpoint_rate_limit(&ep).unwrap_or_else(|e| {
+                tracing::warn!("{}", e);
+                self.config.unicast.rate_limit
+            }),
+            None => self.config.unicast.rate_limit,
+        }
+    }
+
     pub async fn get_locators_unicast(&self) -> Vec<Locator> {
         let mut vec: Vec<Locator> = vec![];
         for p in zasynclock!(self.state.unicast.protocols).values() {
@@ -695,10 +895,28 @@ impl TransportManager {
                 .extend(endpoint::Parameters::iter(config))?;
         };
 
+        let rate_limit = self.endpoint_rate_limit(&endpoint)?;
+
         // Create a new link associated by calling the Link Manager
-        let link = manager.new_link(endpoint).await?;
+        let link = manager.new_link(endpoint.clone()).await?;
         // Open the link
-        super::establishment::open::open_link(link, self).await
+        let transport = super::establishment::open::open_link(link, rate_limit, self).await?;
+
+        // Open the parallel links the batches are striped across
+        #[cfg(feature = "transport_multilink")]
+        for _ in transport.get_links()?.len()..self.config.unicast.max_links_per_session {
+            let res: ZResult<TransportUnicast> = async {
+                let link = manager.new_link(endpoint.clone()).await?;
+                super::establishment::open::open_link(link, rate_limit, self).await
+            }
+            .await;
+            if let Err(e) = res {
+                tracing::debug!("Can not open a parallel link to {}: {}", endpoint, e);
+                break;
+            }
+        }
+
+        Ok(transport)
     }
 
     pub async fn get_transport_unicast(&self, peer: &ZenohId) -> Option<TransportUnicast> {
@@ -746,9 +964,10 @@ impl TransportManager {
         let c_manager = self.clone();
         self.task_controller
             .spawn_with_rt(zenoh_runtime::ZRuntime::Acceptor, async move {
+                let rate_limit = c_manager.listener_rate_limit(&link).await;
                 if let Err(e) = tokio::time::timeout(
                     c_manager.config.unicast.accept_timeout,
-                    super::establishment::accept::accept_link(link, &c_manager),
+                    super::establishment::accept::accept_link(link, rate_limit, &c_manager),
                 )
                 .await
                 {
@@ -12,6 +12,8 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use super::transport::TransportUnicastUniversal;
+#[cfg(feature = "stats")]
+use crate::common::stats::TransportStats;
 use crate::{
     common::{
         batch::{BatchConfig, RBatch},
@@ -20,17 +22,23 @@ use crate::{
             TransmissionPipelineProducer,
         },
         priority::TransportPriorityTx,
+        rate_limit::RateLimiter,
+        rtt::RttEstimator,
+        scheduler::{self, TxSlot},
     },
     unicast::link::{TransportLinkUnicast, TransportLinkUnicastRx, TransportLinkUnicastTx},
 };
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 use zenoh_buffers::ZSliceBuffer;
-use zenoh_protocol::transport::{KeepAlive, TransportMessage};
-use zenoh_result::{zerror, ZResult};
+use zenoh_core::zread;
+use zenoh_protocol::{
+    common::ZExtZ64,
+    core::Priority,
+    transport::{close, KeepAlive, TransportMessage},
+};
+use zenoh_result::{bail, zerror, ZResult};
 use zenoh_sync::{RecyclingObject, RecyclingObjectPool};
-#[cfg(feature = "stats")]
-use {crate::common::stats::TransportStats, std::sync::Arc};
 
 #[derive(Clone)]
 pub(super) struct TransportLinkUnicastUniversal {
@@ -38,6 +46,8 @@ pub(super) struct TransportLinkUnicastUniversal {
     pub(super) link: TransportLinkUnicast,
     // The transmission pipeline
     pub(super) pipeline: TransmissionPipelineProducer,
+    // The round-trip time estimation
+    pub(super) rtt: Arc<RttEstimator>,
     // The task handling substruct
     tracker: TaskTracker,
     token: CancellationToken,
@@ -57,18 +67,27 @@ impl TransportLinkUnicastUniversal {
                 is_streamed: link.link.is_streamed(),
                 #[cfg(feature = "transport_compression")]
                 is_compression: link.config.batch.is_compression,
+                #[cfg(feature = "transport_compression")]
+                compression_algorithm: link.config.batch.compression_algorithm,
             },
             queue_size: transport.manager.config.queue_size,
             wait_before_drop: transport.manager.config.wait_before_drop,
             backoff: transport.manager.config.queue_backoff,
+            latency_target: transport.manager.config.unicast.latency_target,
+            wrr_weights: transport.manager.config.unicast.wrr_weights,
         };
 
         // The pipeline
-        let (producer, consumer) = TransmissionPipeline::make(config, priority_tx);
+        let (producer, consumer) = TransmissionPipeline::make(
+            config,
+            priority_tx,
+            transport.manager.config.memory_budget.clone(),
+        );
 
         let result = Self {
             link,
             pipeline: producer,
+            rtt: Arc::new(RttEstimator::new()),
             tracker: TaskTracker::new(),
             token: CancellationToken::new(),
         };
@@ -85,11 +104,24 @@ impl TransportLinkUnicastUniversal {
         // Spawn the TX task
         let mut tx = self.link.tx();
         let token = self.token.clone();
+        let slot = transport
+            .manager
+            .config
+            .tx_weights
+            .get(&transport.config.zid)
+            .map(|weight| transport.manager.tx_scheduler.slot(weight));
+        let is_qos = transport.config.is_qos;
+        let echo_interval = transport.manager.config.unicast.echo_interval;
+        let rtt = self.rtt.clone();
         let task = async move {
             let res = tx_task(
                 consumer,
                 &mut tx,
                 keep_alive,
+                echo_interval,
+                &rtt,
+                slot,
+                is_qos,
                 token,
                 #[cfg(feature = "stats")]
                 transport.stats.clone(),
@@ -112,11 +144,13 @@ impl TransportLinkUnicastUniversal {
     pub(super) fn start_rx(&mut self, transport: TransportUnicastUniversal, lease: Duration) {
         let mut rx = self.link.rx();
         let token = self.token.clone();
+        let rtt = self.rtt.clone();
         let task = async move {
             // Start the consume task
             let res = rx_task(
                 &mut rx,
                 transport.clone(),
+                &rtt,
                 lease,
                 transport.manager.config.link_rx_buffer_size,
                 token,
@@ -145,7 +179,7 @@ impl TransportLinkUnicastUniversal {
         self.tracker.spawn_on(task, &zenoh_runtime::ZRuntime::RX);
     }
 
-    pub(super) async fn close(self) -> ZResult<()> {
+    pub(super) async fn close(self, reason: Option<u8>) -> ZResult<()> {
         tracing::trace!("{}: closing", self.link);
 
         self.tracker.close();
@@ -153,6 +187,20 @@ impl TransportLinkUnicastUniversal {
         self.pipeline.disable();
         self.tracker.wait().await;
 
+        self.link.close(reason).await
+    }
+
+    // Close the link once its pipeline is flushed on the wire: the TX task drains the pipeline
+    // when cancelled, which is only disabled afterwards so that the messages pushed on the link
+    // until then are not dropped
+    pub(super) async fn retire(self) -> ZResult<()> {
+        tracing::trace!("{}: retiring", self.link);
+
+        self.tracker.close();
+        self.token.cancel();
+        self.tracker.wait().await;
+        self.pipeline.disable();
+
         self.link.close(None).await
     }
 }
@@ -164,15 +212,47 @@ async fn tx_task(
     mut pipeline: TransmissionPipelineConsumer,
     link: &mut TransportLinkUnicastTx,
     keep_alive: Duration,
+    echo_interval: Option<Duration>,
+    rtt: &RttEstimator,
+    mut slot: Option<TxSlot>,
+    is_qos: bool,
     token: CancellationToken,
     #[cfg(feature = "stats")] stats: Arc<TransportStats>,
 ) -> ZResult<()> {
     let mut interval =
         tokio::time::interval_at(tokio::time::Instant::now() + keep_alive, keep_alive);
+    let mut echo = echo_interval.map(tokio::time::interval);
+    let mut limiter = link.inner.config.rate_limit.map(RateLimiter::new);
     loop {
         tokio::select! {
-            res = pipeline.pull() => {
+            res = scheduler::pull(&mut pipeline, slot.as_mut()) => {
                 if let Some((mut batch, priority)) = res {
+                    // Wait for the turn of the transport among the ones sharing the TX threads
+                    if let Some(slot) = slot.as_mut() {
+                        if !is_qos || priority != Priority::Control as usize {
+                            slot.acquire().await;
+                        }
+                    }
+                    // Control batches are charged but never delayed, and keep-alives go on
+                    // while a throttled batch waits so that the lease does not expire
+                    let wait = limiter
+                        .as_mut()
+                        .and_then(|l| l.take(batch.len() as usize))
+                        .filter(|_| !is_qos || priority != Priority::Control as usize);
+                    if let Some(wait) = wait {
+                        let sleep = tokio::time::sleep(wait);
+                        tokio::pin!(sleep);
+                        loop {
+                            tokio::select! {
+                                _ = &mut sleep => break,
+                                _ = interval.tick() => send_keep_alive(
+                                    link,
+                                    #[cfg(feature = "stats")]
+                                    &stats,
+                                ).await?,
+                            }
+                        }
+                    }
                     link.send_batch(&mut batch).await?;
 
                     #[cfg(feature = "stats")]
@@ -188,8 +268,35 @@ async fn tx_task(
                 }
             }
 
-            _ = interval.tick() => {
-                let message: TransportMessage = KeepAlive.into();
+            _ = interval.tick() => send_keep_alive(
+                link,
+                #[cfg(feature = "stats")]
+                &stats,
+            ).await?,
+
+            _ = async { echo.as_mut().unwrap().tick().await }, if echo.is_some() => {
+                let message: TransportMessage = KeepAlive {
+                    ext_echo_request: Some(ZExtZ64::new(rtt.request())),
+                    ..Default::default()
+                }
+                .into();
+
+                #[allow(unused_variables)] // Used when stats feature is enabled
+                let n = link.send(&message).await?;
+
+                #[cfg(feature = "stats")]
+                {
+                    stats.inc_tx_t_msgs(1);
+                    stats.inc_tx_bytes(n);
+                }
+            }
+
+            timestamp = rtt.next_reply() => {
+                let message: TransportMessage = KeepAlive {
+                    ext_echo_reply: Some(ZExtZ64::new(timestamp)),
+                    ..Default::default()
+                }
+                .into();
 
                 #[allow(unused_variables)] // Used when stats feature is enabled
                 let n = link.send(&message).await?;
@@ -222,9 +329,28 @@ async fn tx_task(
     Ok(())
 }
 
+async fn send_keep_alive(
+    link: &mut TransportLinkUnicastTx,
+    #[cfg(feature = "stats")] stats: &TransportStats,
+) -> ZResult<()> {
+    let message: TransportMessage = KeepAlive::default().into();
+
+    #[allow(unused_variables)] // Used when stats feature is enabled
+    let n = link.send(&message).await?;
+
+    #[cfg(feature = "stats")]
+    {
+        stats.inc_tx_t_msgs(1);
+        stats.inc_tx_bytes(n);
+    }
+
+    Ok(())
+}
+
 async fn rx_task(
     link: &mut TransportLinkUnicastRx,
     transport: TransportUnicastUniversal,
+    rtt: &RttEstimator,
     lease: Duration,
     rx_buffer_size: usize,
     token: CancellationToken,
@@ -257,13 +383,22 @@ async fn rx_task(
     loop {
         tokio::select! {
             batch = tokio::time::timeout(lease, read(link, &pool)) => {
-                let batch = batch.map_err(|_| zerror!("{}: expired after {} milliseconds", link, lease.as_millis()))??;
+                let batch = match batch {
+                    Ok(batch) => batch?,
+                    Err(_) => {
+                        // The transport is closed when its last link expires
+                        if zread!(transport.links).len() == 1 {
+                            transport.notify_close_reason(close::reason::EXPIRED);
+                        }
+                        bail!("{}: expired after {} milliseconds", link, lease.as_millis());
+                    }
+                };
                 #[cfg(feature = "stats")]
                 {
 
                     transport.stats.inc_rx_bytes(2 + batch.len()); // Account for the batch len encoding (16 bits)
                 }
-                transport.read_messages(batch, &l)?;
+                transport.read_messages(batch, &l, rtt)?;
             }
 
             _ = token.cancelled() => break
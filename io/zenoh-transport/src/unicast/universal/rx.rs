@@ -15,21 +15,28 @@ use super::transport::TransportUnicastUniversal;
 use crate::{
     common::{
         batch::{Decode, RBatch},
-        priority::TransportChannelRx,
+        priority::{Received, TransportChannelRx},
+        rtt::RttEstimator,
     },
     unicast::transport_unicast_inner::TransportUnicastTrait,
     TransportPeerEventHandler,
 };
-use std::sync::MutexGuard;
+use std::sync::{atomic::Ordering, MutexGuard};
+use zenoh_buffers::ZSlice;
 use zenoh_core::{zlock, zread};
 use zenoh_link::Link;
 use zenoh_protocol::{
     core::{Priority, Reliability},
     network::NetworkMessage,
-    transport::{Close, Fragment, Frame, KeepAlive, TransportBody, TransportMessage, TransportSn},
+    transport::{
+        close, Close, Fragment, Frame, KeepAlive, TransportBody, TransportMessage, TransportSn,
+    },
 };
 use zenoh_result::{bail, zerror, ZResult};
 
+// The maximum number of frames and fragments held back while waiting for a missing one
+const REORDER_WINDOW: usize = 256;
+
 /*************************************/
 /*            TRANSPORT RX           */
 /*************************************/
@@ -46,10 +53,15 @@ impl TransportUnicastUniversal {
                 crate::shm::map_zmsg_to_shmbuf(&mut msg, &self.manager.state.unicast.shm.reader)?;
             }
         }
+        self.is_active.store(true, Ordering::Relaxed);
         callback.handle_message(msg)
     }
 
-    fn handle_close(&self, link: &Link, _reason: u8, session: bool) -> ZResult<()> {
+    fn handle_close(&self, link: &Link, reason: u8, session: bool) -> ZResult<()> {
+        if session {
+            self.notify_close_reason(reason);
+        }
+
         // Delete and clean up
         let c_transport = self.clone();
         let c_link = link.clone();
@@ -58,6 +70,9 @@ impl TransportUnicastUniversal {
         zenoh_runtime::ZRuntime::Net.spawn(async move {
             if session {
                 let _ = c_transport.delete().await;
+            } else if reason == close::reason::HIBERNATE {
+                // Keep the transport as the peer does
+                c_transport.hibernate(false).await;
             } else {
                 let _ = c_transport.del_link(c_link).await;
             }
@@ -71,10 +86,39 @@ impl TransportUnicastUniversal {
             reliability,
             sn,
             ext_qos,
-            mut payload,
+            payload,
         } = frame;
+        self.handle_received(
+            reliability,
+            ext_qos.priority(),
+            sn,
+            Received::Frame(payload),
+        )
+    }
+
+    fn handle_fragment(&self, fragment: Fragment) -> ZResult<()> {
+        let Fragment {
+            reliability,
+            more,
+            sn,
+            ext_qos,
+            payload,
+        } = fragment;
+        self.handle_received(
+            reliability,
+            ext_qos.priority(),
+            sn,
+            Received::Fragment { more, payload },
+        )
+    }
 
-        let priority = ext_qos.priority();
+    fn handle_received(
+        &self,
+        reliability: Reliability,
+        priority: Priority,
+        sn: TransportSn,
+        received: Received,
+    ) -> ZResult<()> {
         let c = if self.is_qos() {
             &self.priority_rx[priority as usize]
         } else if priority == Priority::default() {
@@ -92,8 +136,23 @@ impl TransportUnicastUniversal {
             Reliability::BestEffort => zlock!(c.best_effort),
         };
 
-        self.verify_sn(sn, &mut guard)?;
+        for (sn, received) in self.reorder(sn, received, &mut guard)? {
+            // The fragments of a message can not be defragmented across missing ones
+            if sn != guard.sn.next() && !guard.defrag.is_empty() {
+                guard.defrag.clear();
+            }
+            self.verify_sn(sn, &mut guard)?;
+            match received {
+                Received::Frame(payload) => self.handle_frame_payload(payload)?,
+                Received::Fragment { more, payload } => {
+                    self.handle_fragment_payload(sn, more, payload, &mut guard)?
+                }
+            }
+        }
+        Ok(())
+    }
 
+    fn handle_frame_payload(&self, mut payload: Vec<NetworkMessage>) -> ZResult<()> {
         let callback = zread!(self.callback).clone();
         if let Some(callback) = callback.as_ref() {
             for msg in payload.drain(..) {
@@ -109,34 +168,13 @@ impl TransportUnicastUniversal {
         Ok(())
     }
 
-    fn handle_fragment(&self, fragment: Fragment) -> ZResult<()> {
-        let Fragment {
-            reliability,
-            more,
-            sn,
-            ext_qos: qos,
-            payload,
-        } = fragment;
-
-        let c = if self.is_qos() {
-            &self.priority_rx[qos.priority() as usize]
-        } else if qos.priority() == Priority::default() {
-            &self.priority_rx[0]
-        } else {
-            bail!(
-                "Transport: {}. Unknown priority: {:?}.",
-                self.config.zid,
-                qos.priority()
-            );
-        };
-
-        let mut guard = match reliability {
-            Reliability::Reliable => zlock!(c.reliable),
-            Reliability::BestEffort => zlock!(c.best_effort),
-        };
-
-        self.verify_sn(sn, &mut guard)?;
-
+    fn handle_fragment_payload(
+        &self,
+        sn: TransportSn,
+        more: bool,
+        payload: ZSlice,
+        guard: &mut MutexGuard<'_, TransportChannelRx>,
+    ) -> ZResult<()> {
         if guard.defrag.is_empty() {
             let _ = guard.defrag.sync(sn);
         }
@@ -163,6 +201,48 @@ impl TransportUnicastUniversal {
         Ok(())
     }
 
+    // The frames and fragments to handle, in the order of their SN, once the one with sn is
+    // received. The messages are striped across the parallel links of the transport, so the ones
+    // received ahead of a missing one are held back until it is received on another link. The
+    // missing ones are given up on when more than REORDER_WINDOW are held back, or when the
+    // transport has a single link left.
+    fn reorder(
+        &self,
+        sn: TransportSn,
+        received: Received,
+        guard: &mut MutexGuard<'_, TransportChannelRx>,
+    ) -> ZResult<Vec<(TransportSn, Received)>> {
+        let striped = zread!(self.links).len() > 1;
+        if striped && sn != guard.sn.next() && guard.sn.precedes(sn)? {
+            guard.reordered.insert(sn, received);
+            if guard.reordered.len() <= REORDER_WINDOW {
+                return Ok(vec![]);
+            }
+        } else if striped || guard.reordered.is_empty() {
+            let mut ready = vec![(sn, received)];
+            let mut next = sn;
+            loop {
+                next = next.wrapping_add(1) & guard.sn.resolution();
+                match guard.reordered.remove(&next) {
+                    Some(received) => ready.push((next, received)),
+                    None => return Ok(ready),
+                }
+            }
+        } else {
+            guard.reordered.insert(sn, received);
+        }
+
+        tracing::debug!(
+            "Transport: {}. Frames missing after SN {} are given up on.",
+            self.config.zid,
+            guard.sn.get()
+        );
+        let (last, mask) = (guard.sn.get(), guard.sn.resolution());
+        let mut ready: Vec<_> = guard.reordered.drain().collect();
+        ready.sort_by_key(|(sn, _)| sn.wrapping_sub(last) & mask);
+        Ok(ready)
+    }
+
     fn verify_sn(
         &self,
         sn: TransportSn,
@@ -187,38 +267,54 @@ impl TransportUnicastUniversal {
         Ok(())
     }
 
-    pub(super) fn read_messages(&self, mut batch: RBatch, link: &Link) -> ZResult<()> {
-        while !batch.is_empty() {
-            let msg: TransportMessage = batch
-                .decode()
-                .map_err(|_| zerror!("{}: decoding error", link))?;
+    pub(super) fn read_messages(
+        &self,
+        mut batch: RBatch,
+        link: &Link,
+        rtt: &RttEstimator,
+    ) -> ZResult<()> {
+        // The messages, and the network messages they carry, are decoded with the limits of
+        // the configuration of this transport
+        self.manager.config.codec_limits.scope(|| {
+            while !batch.is_empty() {
+                let msg: TransportMessage = batch
+                    .decode()
+                    .map_err(|_| zerror!("{}: decoding error", link))?;
 
-            tracing::trace!("Received: {:?}", msg);
+                tracing::trace!("Received: {:?}", msg);
 
-            #[cfg(feature = "stats")]
-            {
-                self.stats.inc_rx_t_msgs(1);
-            }
-
-            match msg.body {
-                TransportBody::Frame(msg) => self.handle_frame(msg)?,
-                TransportBody::Fragment(fragment) => self.handle_fragment(fragment)?,
-                TransportBody::Close(Close { reason, session }) => {
-                    self.handle_close(link, reason, session)?
+                #[cfg(feature = "stats")]
+                {
+                    self.stats.inc_rx_t_msgs(1);
                 }
-                TransportBody::KeepAlive(KeepAlive { .. }) => {}
-                _ => {
-                    tracing::debug!(
-                        "Transport: {}. Message handling not implemented: {:?}",
-                        self.config.zid,
-                        msg
-                    );
+
+                match msg.body {
+                    TransportBody::Frame(msg) => self.handle_frame(msg)?,
+                    TransportBody::Fragment(fragment) => self.handle_fragment(fragment)?,
+                    TransportBody::Close(Close { reason, session }) => {
+                        self.handle_close(link, reason, session)?
+                    }
+                    TransportBody::KeepAlive(KeepAlive {
+                        ext_echo_request,
+                        ext_echo_reply,
+                    }) => {
+                        if let Some(request) = ext_echo_request {
+                            rtt.on_request(request.value);
+                        }
+                        if let Some(reply) = ext_echo_reply {
+                            rtt.on_reply(reply.value);
+                        }
+                    }
+                    _ => {
+                        tracing::debug!(
+                            "Transport: {}. Message handling not implemented: {:?}",
+                            self.config.zid,
+                            msg
+                        );
+                    }
                 }
             }
-        }
-
-        // Process the received message
-
-        Ok(())
+            Ok(())
+        })
     }
 }
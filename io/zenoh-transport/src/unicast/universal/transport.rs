@@ -24,12 +24,17 @@ use crate::{
     TransportManager, TransportPeerEventHandler,
 };
 use async_trait::async_trait;
+use std::collections::VecDeque;
 use std::fmt::DebugStruct;
-use std::sync::{Arc, RwLock};
-use std::time::Duration;
+#[cfg(feature = "transport_multilink")]
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 use tokio::sync::{Mutex as AsyncMutex, MutexGuard as AsyncMutexGuard};
-use zenoh_core::{zasynclock, zcondfeat, zread, zwrite};
-use zenoh_link::Link;
+use tokio_util::sync::CancellationToken;
+use zenoh_core::{zasynclock, zcondfeat, zlock, zread, zwrite};
+use zenoh_link::{EndPoint, Link, LinkUnicast};
 use zenoh_protocol::{
     core::{Priority, WhatAmI, ZenohId},
     network::NetworkMessage,
@@ -37,6 +42,9 @@ use zenoh_protocol::{
 };
 use zenoh_result::{bail, zerror, ZResult};
 
+// The maximum number of messages kept while a transport is hibernated
+const HIBERNATION_PENDING_MAX: usize = 1_024;
+
 macro_rules! zlinkindex {
     ($guard:expr, $link:expr) => {
         // Compare LinkUnicast link to not compare TransportLinkUnicast direction
@@ -47,6 +55,15 @@ macro_rules! zlinkindex {
 /*************************************/
 /*        UNIVERSAL TRANSPORT        */
 /*************************************/
+struct Hibernation {
+    // The time at which the transport has been hibernated
+    since: Instant,
+    // The endpoints to reconnect to when some message is scheduled
+    endpoints: Vec<EndPoint>,
+    // The messages scheduled while hibernated
+    pending: VecDeque<NetworkMessage>,
+}
+
 #[derive(Clone)]
 pub(crate) struct TransportUnicastUniversal {
     // Transport Manager
@@ -59,12 +76,25 @@ pub(crate) struct TransportUnicastUniversal {
     pub(super) priority_rx: Arc<[TransportPriorityRx]>,
     // The links associated to the channel
     pub(super) links: Arc<RwLock<Box<[TransportLinkUnicastUniversal]>>>,
+    // The link each priority, reliable or not, is currently sent on
+    pub(super) pinned: Arc<[Mutex<Option<LinkUnicast>>]>,
+    // The number of messages striped across the parallel links
+    #[cfg(feature = "transport_multilink")]
+    pub(super) striped: Arc<AtomicUsize>,
     // The callback
     pub(super) callback: Arc<RwLock<Option<Arc<dyn TransportPeerEventHandler>>>>,
     // Lock used to ensure no race in add_link method
     add_link_lock: Arc<AsyncMutex<()>>,
     // Mutex for notification
     pub(super) alive: Arc<AsyncMutex<bool>>,
+    // Whether some data has been exchanged since the last hibernation check
+    pub(super) is_active: Arc<AtomicBool>,
+    // The hibernation state, if the transport is hibernated
+    hibernated: Arc<Mutex<Option<Hibernation>>>,
+    // Whether a hibernated transport is reconnecting to the peer
+    reconnecting: Arc<AtomicBool>,
+    // Token to stop the hibernation task
+    token: CancellationToken,
     // Transport statistics
     #[cfg(feature = "stats")]
     pub(super) stats: Arc<TransportStats>,
@@ -101,25 +131,217 @@ impl TransportUnicastUniversal {
         #[cfg(feature = "stats")]
         let stats = Arc::new(TransportStats::new(Some(manager.get_stats().clone())));
 
-        let t = Arc::new(TransportUnicastUniversal {
+        let token = manager.task_controller.get_cancellation_token();
+        let t = TransportUnicastUniversal {
             manager,
             config,
             priority_tx: priority_tx.into_boxed_slice().into(),
             priority_rx: priority_rx.into_boxed_slice().into(),
             links: Arc::new(RwLock::new(vec![].into_boxed_slice())),
+            pinned: (0..2 * Priority::NUM).map(|_| Mutex::new(None)).collect(),
+            #[cfg(feature = "transport_multilink")]
+            striped: Arc::new(AtomicUsize::new(0)),
             add_link_lock: Arc::new(AsyncMutex::new(())),
             callback: Arc::new(RwLock::new(None)),
             alive: Arc::new(AsyncMutex::new(false)),
+            is_active: Arc::new(AtomicBool::new(false)),
+            hibernated: Arc::new(Mutex::new(None)),
+            reconnecting: Arc::new(AtomicBool::new(false)),
+            token,
             #[cfg(feature = "stats")]
             stats,
+        };
+
+        if let Some(idle_timeout) = t.manager.config.unicast.hibernation_timeout {
+            t.start_hibernation(idle_timeout, t.manager.config.unicast.hibernation_retention);
+        }
+
+        Ok(Arc::new(t))
+    }
+
+    /*************************************/
+    /*            HIBERNATION            */
+    /*************************************/
+    fn start_hibernation(&self, idle_timeout: Duration, retention: Duration) {
+        let c_self = self.clone();
+        let c_token = self.token.clone();
+        let task = async move {
+            let mut interval =
+                tokio::time::interval_at(tokio::time::Instant::now() + idle_timeout, idle_timeout);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        let since = zlock!(c_self.hibernated).as_ref().map(|h| h.since);
+                        match since {
+                            Some(since) if since.elapsed() >= retention => {
+                                tracing::debug!(
+                                    "[{}] Hibernated transport with peer {} expired after {:?}",
+                                    c_self.manager.config.zid,
+                                    c_self.config.zid,
+                                    retention
+                                );
+                                let _ = c_self.delete().await;
+                                break;
+                            }
+                            Some(_) => {}
+                            None => {
+                                if !c_self.is_active.swap(false, Ordering::AcqRel) {
+                                    c_self.hibernate(true).await;
+                                }
+                            }
+                        }
+                    }
+                    _ = c_token.cancelled() => break
+                }
+            }
+        };
+
+        self.manager
+            .task_controller
+            .spawn_with_rt(zenoh_runtime::ZRuntime::Acceptor, task);
+    }
+
+    // Close all the links of an idle transport while keeping the transport itself,
+    // so that it can be resumed when either side reconnects. The peer is told about
+    // the hibernation with a Close message so that it keeps the transport as well.
+    pub(super) async fn hibernate(&self, notify: bool) {
+        // Prevent concurrent links from being added while hibernating
+        let _add_link_guard = zasynclock!(self.add_link_lock);
+        let mut a_guard = zasynclock!(self.alive);
+        if !*a_guard {
+            // The transport is not synched yet or is being closed
+            return;
+        }
+
+        let links = {
+            let mut l_guard = zwrite!(self.links);
+            let links = l_guard.to_vec();
+            *l_guard = vec![].into_boxed_slice();
+            links
+        };
+        if links.is_empty() {
+            return;
+        }
+
+        tracing::debug!(
+            "[{}] Hibernating idle transport with peer: {}",
+            self.manager.config.zid,
+            self.config.zid
+        );
+        // Only the side which has opened the links knows where to reconnect
+        let endpoints = links
+            .iter()
+            .filter(|l| l.link.config.direction == TransportLinkUnicastDirection::Outbound)
+            .map(|l| EndPoint::from(l.link.link.get_dst().clone()))
+            .collect();
+        *zlock!(self.hibernated) = Some(Hibernation {
+            since: Instant::now(),
+            endpoints,
+            pending: VecDeque::new(),
         });
+        // The RX sequence numbers are synched again when the transport is resumed
+        *a_guard = false;
+        drop(a_guard);
+
+        let reason = notify.then_some(close::reason::HIBERNATE);
+        let callback = zread!(self.callback).clone();
+        for l in links {
+            if let Some(cb) = callback.as_ref() {
+                cb.del_link(l.link.link());
+            }
+            let _ = l.close(reason).await;
+        }
+    }
+
+    // Keep a message scheduled on a hibernated transport until the transport is resumed,
+    // reconnecting to the peer if this side has opened the links.
+    // Gives the message back if the transport is not hibernated.
+    pub(super) fn schedule_hibernated(&self, msg: NetworkMessage) -> Result<bool, NetworkMessage> {
+        let mut h_guard = zlock!(self.hibernated);
+        let Some(hibernation) = h_guard.as_mut() else {
+            return Err(msg);
+        };
+        if hibernation.pending.len() >= HIBERNATION_PENDING_MAX {
+            tracing::trace!(
+                "Message dropped because the hibernated transport has too many pending messages: {}",
+                msg
+            );
+            return Ok(false);
+        }
+        hibernation.pending.push_back(msg);
+
+        if !hibernation.endpoints.is_empty() && !self.reconnecting.swap(true, Ordering::AcqRel) {
+            let endpoints = hibernation.endpoints.clone();
+            let c_self = self.clone();
+            zenoh_runtime::ZRuntime::Net.spawn(async move {
+                for endpoint in endpoints {
+                    match c_self
+                        .manager
+                        .open_transport_unicast(endpoint.clone())
+                        .await
+                    {
+                        Ok(_) => break,
+                        Err(e) => tracing::debug!(
+                            "[{}] Can not resume hibernated transport with peer {} on {}: {}",
+                            c_self.manager.config.zid,
+                            c_self.config.zid,
+                            endpoint,
+                            e
+                        ),
+                    }
+                }
+                c_self.reconnecting.store(false, Ordering::Release);
+            });
+        }
+
+        Ok(true)
+    }
+
+    // Restart the TX sequence numbers of a hibernated transport, if it is, from the initial SN
+    // the peer has been told about during the establishment.
+    fn resume(&self) -> ZResult<()> {
+        let h_guard = zlock!(self.hibernated);
+        if h_guard.is_none() {
+            return Ok(());
+        }
+
+        tracing::debug!(
+            "[{}] Resuming hibernated transport with peer: {}",
+            self.manager.config.zid,
+            self.config.zid
+        );
+        let initial_sn = PrioritySn {
+            reliable: self.config.tx_initial_sn,
+            best_effort: self.config.tx_initial_sn,
+        };
+        for c in self.priority_tx.iter() {
+            c.sync(initial_sn)?;
+        }
+
+        Ok(())
+    }
 
-        Ok(t)
+    // Wake up a hibernated transport, if it is, once its first link has been added back,
+    // sending the messages scheduled in the meantime.
+    fn wake_up(&self) {
+        let mut h_guard = zlock!(self.hibernated);
+        if let Some(hibernation) = h_guard.take() {
+            self.is_active.store(true, Ordering::Release);
+            for msg in hibernation.pending {
+                self.schedule_on_link(msg);
+            }
+        }
     }
 
     /*************************************/
     /*           TERMINATION             */
     /*************************************/
+    pub(super) fn notify_close_reason(&self, reason: u8) {
+        if let Some(cb) = zread!(self.callback).as_ref() {
+            cb.close_reason(reason);
+        }
+    }
+
     pub(super) async fn delete(&self) -> ZResult<()> {
         tracing::debug!(
             "[{}] Closing transport with peer: {}",
@@ -132,6 +354,9 @@ impl TransportUnicastUniversal {
         let mut a_guard = self.get_alive().await;
         *a_guard = false;
 
+        // Stop the hibernation task, if any
+        self.token.cancel();
+
         // Notify the callback that we are going to close the transport
         let callback = zwrite!(self.callback).take();
         if let Some(cb) = callback.as_ref() {
@@ -149,7 +374,7 @@ impl TransportUnicastUniversal {
             links
         };
         for l in links.drain(..) {
-            let _ = l.close().await;
+            let _ = l.close(None).await;
         }
 
         // Notify the callback that we have closed the transport
@@ -200,8 +425,41 @@ impl TransportUnicastUniversal {
 
         match target {
             Target::Transport => self.delete().await,
-            Target::Link(stl) => stl.close().await,
+            Target::Link(stl) => stl.close(None).await,
+        }
+    }
+
+    // Remove a link still in use from the transport and close it once the messages already
+    // scheduled on it are flushed, while the new messages are scheduled on the other links
+    pub(crate) async fn retire_link(&self, link: Link) -> ZResult<()> {
+        let stl = {
+            let mut guard = zwrite!(self.links);
+            let Some(index) = zlinkindex!(guard, link) else {
+                bail!(
+                    "Can not retire Link {} with peer: {}",
+                    link,
+                    self.config.zid
+                )
+            };
+            if guard.len() == 1 {
+                bail!(
+                    "Can not retire the last Link {} with peer: {}",
+                    link,
+                    self.config.zid
+                )
+            }
+            let mut links = guard.to_vec();
+            let stl = links.remove(index);
+            *guard = links.into_boxed_slice();
+            stl
+        };
+
+        // Notify the callback
+        if let Some(callback) = zread!(self.callback).as_ref() {
+            callback.del_link(link);
         }
+
+        stl.retire().await
     }
 
     async fn sync(&self, initial_sn_rx: TransportSn) -> ZResult<()> {
@@ -241,8 +499,42 @@ impl TransportUnicastTrait for TransportUnicastUniversal {
     ) -> AddLinkResult {
         let add_link_guard = zasynclock!(self.add_link_lock);
 
+        // Resolve a simultaneous open, i.e. both peers opening a link to each other at the same time
+        // on a transport with a single link: both peers keep the link opened by the one with the
+        // greatest ZenohId. The other link is only closed by this one, once both links have been
+        // added on both sides, so that none of the peers loses its last link and closes the transport.
+        let mut kept_first = false;
+        let mut retire_new = false;
+        let mut retired = None;
+        if zcondfeat!("transport_multilink", self.config.multilink.is_none(), true) {
+            let guard = zread!(self.links);
+            let direction = link.inner_config().direction;
+            if let Some(other) = guard.iter().find(|l| l.link.config.direction != direction) {
+                let greatest = self.manager.config.zid > self.config.zid;
+                let is_kept = (direction == TransportLinkUnicastDirection::Outbound) == greatest;
+                match (is_kept, greatest) {
+                    // The peer adds the new link before receiving its ack
+                    (false, true) => retire_new = true,
+                    // The peer has already added the new link when acknowledging it
+                    (true, true) => retired = Some(other.link.link()),
+                    // The peer will close the other link
+                    (true, false) => kept_first = true,
+                    (false, false) => {}
+                }
+                tracing::debug!(
+                    "Simultaneous open with peer {}: keep Link {}",
+                    self.config.zid,
+                    if is_kept {
+                        link.to_string()
+                    } else {
+                        other.link.to_string()
+                    }
+                );
+            }
+        }
+
         // Check if we can add more inbound links
-        {
+        if !retire_new {
             let guard = zread!(self.links);
             if let TransportLinkUnicastDirection::Inbound = link.inner_config().direction {
                 let count = guard
@@ -272,11 +564,19 @@ impl TransportUnicastTrait for TransportUnicastUniversal {
             }
         }
 
+        // Resume the transport if it has been hibernated
+        if let Err(e) = self.resume() {
+            return Err((e, link.fail(), close::reason::GENERIC));
+        }
+
         // sync the RX sequence number
         let _ = self.sync(other_initial_sn).await;
 
         // Wrap the link
         let (link, ack) = link.unpack();
+        if retire_new {
+            retired = Some(link.link());
+        }
         let (mut link, consumer) =
             TransportLinkUnicastUniversal::new(self, link, &self.priority_tx);
 
@@ -284,10 +584,16 @@ impl TransportUnicastTrait for TransportUnicastUniversal {
         let mut guard = zwrite!(self.links);
         let mut links = Vec::with_capacity(guard.len() + 1);
         links.extend_from_slice(&guard);
-        links.push(link.clone());
+        // The messages are scheduled on the first link
+        if kept_first {
+            links.insert(0, link.clone());
+        } else {
+            links.push(link.clone());
+        }
         *guard = links.into_boxed_slice();
 
         drop(guard);
+        self.wake_up();
         drop(add_link_guard);
 
         // create a callback to start the link
@@ -299,7 +605,13 @@ impl TransportUnicastTrait for TransportUnicastUniversal {
             link.start_tx(transport.clone(), consumer, keep_alive);
 
             // Start the RX loop
-            link.start_rx(transport, other_lease);
+            link.start_rx(transport.clone(), other_lease);
+
+            // Close the link discarded by the simultaneous open resolution
+            if let Some(retired) = retired {
+                zenoh_runtime::ZRuntime::Net
+                    .spawn(async move { transport.retire_link(retired).await });
+            }
         });
 
         Ok((start_link, ack))
@@ -374,7 +686,14 @@ impl TransportUnicastTrait for TransportUnicastUniversal {
     }
 
     fn get_links(&self) -> Vec<Link> {
-        zread!(self.links).iter().map(|l| l.link.link()).collect()
+        zread!(self.links)
+            .iter()
+            .map(|l| {
+                let mut link = l.link.link();
+                link.set_metrics(l.rtt.get());
+                link
+            })
+            .collect()
     }
 
     /*************************************/
@@ -11,12 +11,36 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use super::transport::TransportUnicastUniversal;
-use zenoh_core::zread;
-use zenoh_protocol::network::NetworkMessage;
+use super::{link::TransportLinkUnicastUniversal, transport::TransportUnicastUniversal};
+use std::{sync::atomic::Ordering, time::Duration};
+use zenoh_core::{zlock, zread};
+use zenoh_protocol::{core::Priority, network::NetworkMessage};
 
 impl TransportUnicastUniversal {
-    fn schedule_on_link(&self, msg: NetworkMessage) -> bool {
+    // The link to send a message of `priority` on, among the ones of `links`: the link the previous
+    // messages of the priority were sent on, until it has written them all. Only then are the
+    // messages moved to the `best` link, so that they are never reordered.
+    fn pinned_link<'a>(
+        &self,
+        links: &'a [TransportLinkUnicastUniversal],
+        best: &'a TransportLinkUnicastUniversal,
+        priority: Priority,
+        is_reliable: bool,
+    ) -> &'a TransportLinkUnicastUniversal {
+        let mut pinned = zlock!(self.pinned[2 * priority as usize + usize::from(is_reliable)]);
+        let current = pinned
+            .as_ref()
+            .and_then(|link| links.iter().find(|tl| tl.link.link == *link));
+        if let Some(current) = current {
+            if current.link.link == best.link.link || !current.pipeline.is_flushed(priority) {
+                return current;
+            }
+        }
+        *pinned = Some(best.link.link.clone());
+        best
+    }
+
+    pub(super) fn schedule_on_link(&self, msg: NetworkMessage) -> bool {
         macro_rules! zpush {
             ($guard:expr, $pipeline:expr, $msg:expr) => {
                 // Drop the guard before the push_zenoh_message since
@@ -30,23 +54,58 @@ impl TransportUnicastUniversal {
         }
 
         let guard = zread!(self.links);
-        // First try to find the best match between msg and link reliability
-        if let Some(pl) = guard.iter().find_map(|tl| {
-            if msg.is_reliable() == tl.link.link.is_reliable() {
-                Some(&tl.pipeline)
-            } else {
-                None
+        // The links carrying the messages of the priority of msg, e.g. a datagram link may only
+        // carry some of the best-effort priorities
+        let supported =
+            |tl: &&TransportLinkUnicastUniversal| tl.link.link.supports_priority(msg.priority());
+        // Stripe the messages across the parallel links round-robin, whatever their priority,
+        // the receiver putting them back in order
+        #[cfg(feature = "transport_multilink")]
+        if self.manager.config.unicast.max_links_per_session > 1 {
+            let links = || {
+                guard
+                    .iter()
+                    .filter(supported)
+                    .filter(|tl| msg.is_reliable() == tl.link.link.is_reliable())
+            };
+            let count = links().count();
+            if count > 0 {
+                let n = self.striped.fetch_add(1, Ordering::Relaxed);
+                if let Some(pl) = links().nth(n % count).map(|tl| &tl.pipeline) {
+                    zpush!(guard, pl, msg);
+                }
             }
-        }) {
-            zpush!(guard, pl, msg);
         }
 
-        // No best match found, take the first available link
-        if let Some(pl) = guard.iter().map(|tl| &tl.pipeline).next() {
+        // First try to find the best match between msg and link reliability,
+        // preferring the link with the lowest round-trip time when measured
+        if let Some(best) = guard
+            .iter()
+            .filter(supported)
+            .filter(|tl| msg.is_reliable() == tl.link.link.is_reliable())
+            .min_by_key(|tl| tl.rtt.srtt().unwrap_or(Duration::MAX))
+        {
+            let tl = self.pinned_link(&guard, best, msg.priority(), msg.is_reliable());
+            zpush!(guard, tl.pipeline, msg);
+        }
+
+        // No best match found, take the first available link, carrying the priority of msg if any
+        if let Some(pl) = guard
+            .iter()
+            .find(supported)
+            .or_else(|| guard.first())
+            .map(|tl| &tl.pipeline)
+        {
             zpush!(guard, pl, msg);
         }
 
-        // No Link found
+        drop(guard);
+
+        // No Link found, keep the message if the transport is hibernated
+        let msg = match self.schedule_hibernated(msg) {
+            Ok(res) => return res,
+            Err(msg) => msg,
+        };
         tracing::trace!(
             "Message dropped because the transport has no links: {}",
             msg
@@ -73,6 +132,9 @@ impl TransportUnicastUniversal {
         }
 
         let res = self.schedule_on_link(msg);
+        if res {
+            self.is_active.store(true, Ordering::Relaxed);
+        }
 
         #[cfg(feature = "stats")]
         if res {
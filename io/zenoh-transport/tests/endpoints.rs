@@ -152,6 +152,9 @@ async fn endpoint_ws() {
         format!("ws/127.0.0.1:{}", 7020).parse().unwrap(),
         format!("ws/[::1]:{}", 7021).parse().unwrap(),
         format!("ws/localhost:{}", 7022).parse().unwrap(),
+        format!("ws/127.0.0.1:{}#max_frame_size=8192", 7023)
+            .parse()
+            .unwrap(),
     ];
     run(&endpoints).await;
 }
@@ -263,6 +263,7 @@ mod tests {
             ext_qos: QoSType::new(channel.priority, CongestionControl::Block, false),
             ext_tstamp: None,
             ext_nodeid: NodeIdType::default(),
+            ext_hoplimit: None,
             payload: Put {
                 payload: vec![0u8; msg_size].into(),
                 timestamp: None,
@@ -271,6 +272,7 @@ mod tests {
                 #[cfg(feature = "shared-memory")]
                 ext_shm: None,
                 ext_attachment: None,
+                ext_tbound: None,
                 ext_unknown: vec![],
             }
             .into(),
@@ -189,6 +189,7 @@ async fn transport_concurrent(endpoint01: Vec<EndPoint>, endpoint02: Vec<EndPoin
             ext_qos: QoSType::new(Priority::default(), CongestionControl::Block, false),
             ext_tstamp: None,
             ext_nodeid: NodeIdType::default(),
+            ext_hoplimit: None,
             payload: Put {
                 payload: vec![0u8; MSG_SIZE].into(),
                 timestamp: None,
@@ -197,6 +198,7 @@ async fn transport_concurrent(endpoint01: Vec<EndPoint>, endpoint02: Vec<EndPoin
                 #[cfg(feature = "shared-memory")]
                 ext_shm: None,
                 ext_attachment: None,
+                ext_tbound: None,
                 ext_unknown: vec![],
             }
             .into(),
@@ -294,6 +296,7 @@ async fn transport_concurrent(endpoint01: Vec<EndPoint>, endpoint02: Vec<EndPoin
             ext_qos: QoSType::new(Priority::default(), CongestionControl::Block, false),
             ext_tstamp: None,
             ext_nodeid: NodeIdType::default(),
+            ext_hoplimit: None,
             payload: Put {
                 payload: vec![0u8; MSG_SIZE].into(),
                 timestamp: None,
@@ -302,6 +305,7 @@ async fn transport_concurrent(endpoint01: Vec<EndPoint>, endpoint02: Vec<EndPoin
                 #[cfg(feature = "shared-memory")]
                 ext_shm: None,
                 ext_attachment: None,
+                ext_tbound: None,
                 ext_unknown: vec![],
             }
             .into(),
@@ -75,6 +75,7 @@ async fn run(endpoint: &EndPoint, channel: Channel, msg_size: usize) {
         ext_qos: QoSType::new(channel.priority, CongestionControl::Block, false),
         ext_tstamp: None,
         ext_nodeid: NodeIdType::default(),
+        ext_hoplimit: None,
         payload: Put {
             payload: vec![0u8; msg_size].into(),
             timestamp: None,
@@ -83,6 +84,7 @@ async fn run(endpoint: &EndPoint, channel: Channel, msg_size: usize) {
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
             ext_attachment: None,
+            ext_tbound: None,
             ext_unknown: vec![],
         }
         .into(),
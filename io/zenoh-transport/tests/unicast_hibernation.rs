@@ -0,0 +1,220 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::any::Any;
+use std::convert::TryFrom;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh_core::ztimeout;
+use zenoh_link::Link;
+use zenoh_protocol::{
+    core::{CongestionControl, Encoding, EndPoint, Priority, WhatAmI, ZenohId},
+    network::{
+        push::{
+            ext::{NodeIdType, QoSType},
+            Push,
+        },
+        NetworkMessage,
+    },
+    zenoh::Put,
+};
+use zenoh_result::ZResult;
+use zenoh_transport::{
+    multicast::TransportMulticast,
+    unicast::{test_helpers::make_transport_manager_builder, TransportUnicast},
+    DummyTransportPeerEventHandler, TransportEventHandler, TransportManager,
+    TransportMulticastEventHandler, TransportPeer, TransportPeerEventHandler,
+};
+
+const MSG_COUNT: usize = 10;
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_millis(100);
+const HIBERNATION_TIMEOUT: Duration = Duration::from_secs(1);
+
+// Transport Handler for the router
+struct SHRouterHibernation {
+    counter: Arc<AtomicUsize>,
+}
+
+impl TransportEventHandler for SHRouterHibernation {
+    fn new_unicast(
+        &self,
+        _peer: TransportPeer,
+        _transport: TransportUnicast,
+    ) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
+        Ok(Arc::new(SCRouterHibernation {
+            counter: self.counter.clone(),
+        }))
+    }
+
+    fn new_multicast(
+        &self,
+        _transport: TransportMulticast,
+    ) -> ZResult<Arc<dyn TransportMulticastEventHandler>> {
+        panic!();
+    }
+}
+
+// Transport Callback for the router
+struct SCRouterHibernation {
+    counter: Arc<AtomicUsize>,
+}
+
+impl TransportPeerEventHandler for SCRouterHibernation {
+    fn handle_message(&self, _message: NetworkMessage) -> ZResult<()> {
+        self.counter.fetch_add(1, Ordering::AcqRel);
+        Ok(())
+    }
+
+    fn new_link(&self, _link: Link) {}
+    fn del_link(&self, _link: Link) {}
+    fn closing(&self) {}
+    fn closed(&self) {}
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+// Transport Handler for the client
+#[derive(Default)]
+struct SHClientHibernation;
+
+impl TransportEventHandler for SHClientHibernation {
+    fn new_unicast(
+        &self,
+        _peer: TransportPeer,
+        _transport: TransportUnicast,
+    ) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
+        Ok(Arc::new(DummyTransportPeerEventHandler))
+    }
+
+    fn new_multicast(
+        &self,
+        _transport: TransportMulticast,
+    ) -> ZResult<Arc<dyn TransportMulticastEventHandler>> {
+        panic!();
+    }
+}
+
+fn make_manager(
+    zid: ZenohId,
+    whatami: WhatAmI,
+    handler: Arc<dyn TransportEventHandler>,
+) -> TransportManager {
+    let unicast = make_transport_manager_builder(
+        #[cfg(feature = "transport_multilink")]
+        1,
+        #[cfg(feature = "shared-memory")]
+        false,
+        false,
+    )
+    .hibernation_timeout(Some(HIBERNATION_TIMEOUT))
+    .hibernation_retention(TIMEOUT);
+    TransportManager::builder()
+        .whatami(whatami)
+        .zid(zid)
+        .unicast(unicast)
+        .build(handler)
+        .unwrap()
+}
+
+fn make_message() -> NetworkMessage {
+    Push {
+        wire_expr: "test".into(),
+        ext_qos: QoSType::new(Priority::default(), CongestionControl::Block, false),
+        ext_tstamp: None,
+        ext_nodeid: NodeIdType::default(),
+        ext_hoplimit: None,
+        payload: Put {
+            payload: vec![0u8; 8].into(),
+            timestamp: None,
+            encoding: Encoding::default(),
+            ext_sinfo: None,
+            #[cfg(feature = "shared-memory")]
+            ext_shm: None,
+            ext_attachment: None,
+            ext_tbound: None,
+            ext_unknown: vec![],
+        }
+        .into(),
+    }
+    .into()
+}
+
+async fn wait_links(manager: &TransportManager, zid: &ZenohId, num: usize) {
+    ztimeout!(async {
+        loop {
+            let transport = manager
+                .get_transport_unicast(zid)
+                .await
+                .expect("The transport has been closed instead of hibernated");
+            if transport.get_links().unwrap().len() == num {
+                break;
+            }
+            tokio::time::sleep(SLEEP).await;
+        }
+    });
+}
+
+async fn transport_hibernation(endpoint: &EndPoint) {
+    let router_id = ZenohId::try_from([1]).unwrap();
+    let client_id = ZenohId::try_from([2]).unwrap();
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let router_manager = make_manager(
+        router_id,
+        WhatAmI::Router,
+        Arc::new(SHRouterHibernation {
+            counter: counter.clone(),
+        }),
+    );
+    let client_manager = make_manager(client_id, WhatAmI::Client, Arc::new(SHClientHibernation));
+
+    let _ = ztimeout!(router_manager.add_listener(endpoint.clone())).unwrap();
+    let transport = ztimeout!(client_manager.open_transport_unicast(endpoint.clone())).unwrap();
+
+    // Both sides keep the transport without any link once idle
+    wait_links(&client_manager, &router_id, 0).await;
+    wait_links(&router_manager, &client_id, 0).await;
+
+    // Scheduling on the hibernated transport reconnects and delivers the messages
+    for _ in 0..MSG_COUNT {
+        transport.schedule(make_message()).unwrap();
+    }
+    wait_links(&client_manager, &router_id, 1).await;
+    wait_links(&router_manager, &client_id, 1).await;
+    ztimeout!(async {
+        while counter.load(Ordering::Acquire) < MSG_COUNT {
+            tokio::time::sleep(SLEEP).await;
+        }
+    });
+    assert_eq!(counter.load(Ordering::Acquire), MSG_COUNT);
+
+    // The resumed transport hibernates again once idle
+    wait_links(&client_manager, &router_id, 0).await;
+    wait_links(&router_manager, &client_id, 0).await;
+
+    ztimeout!(transport.close()).unwrap();
+    ztimeout!(router_manager.close());
+    ztimeout!(client_manager.close());
+}
+
+#[cfg(feature = "transport_tcp")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn transport_tcp_hibernation() {
+    zenoh_util::try_init_log_from_env();
+    let endpoint: EndPoint = format!("tcp/127.0.0.1:{}", 18200).parse().unwrap();
+    transport_hibernation(&endpoint).await;
+}
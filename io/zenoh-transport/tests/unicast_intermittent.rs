@@ -294,6 +294,7 @@ async fn transport_intermittent(endpoint: &EndPoint, lowlatency_transport: bool)
             ext_qos: QoSType::new(Priority::default(), CongestionControl::Block, false),
             ext_tstamp: None,
             ext_nodeid: NodeIdType::default(),
+            ext_hoplimit: None,
             payload: Put {
                 payload: vec![0u8; MSG_SIZE].into(),
                 timestamp: None,
@@ -302,6 +303,7 @@ async fn transport_intermittent(endpoint: &EndPoint, lowlatency_transport: bool)
                 #[cfg(feature = "shared-memory")]
                 ext_shm: None,
                 ext_attachment: None,
+                ext_tbound: None,
                 ext_unknown: vec![],
             }
             .into(),
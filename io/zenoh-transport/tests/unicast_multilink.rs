@@ -731,4 +731,244 @@ R+IdLiXcyIkg0m9N8I17p0ljCSkbrgGMD3bbePRTfg==
         let endpoint: EndPoint = "vsock/VMADDR_CID_LOCAL:17000".parse().unwrap();
         multilink_transport(&endpoint).await;
     }
+
+    #[cfg(feature = "transport_tcp")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn multilink_parallel_links_tcp_only() {
+        zenoh_util::try_init_log_from_env();
+
+        let endpoint: EndPoint = format!("tcp/127.0.0.1:{}", 18001).parse().unwrap();
+
+        let router_manager = TransportManager::builder()
+            .whatami(WhatAmI::Router)
+            .zid(ZenohId::try_from([1]).unwrap())
+            .unicast(TransportManager::config_unicast().max_links(4))
+            .build(Arc::new(SHRouterOpenClose))
+            .unwrap();
+        let client_manager = TransportManager::builder()
+            .whatami(WhatAmI::Client)
+            .zid(ZenohId::try_from([2]).unwrap())
+            .unicast(
+                TransportManager::config_unicast()
+                    .max_links(4)
+                    .max_links_per_session(3),
+            )
+            .build(Arc::new(SHClientOpenClose::new()))
+            .unwrap();
+
+        let _ = ztimeout!(router_manager.add_listener(endpoint.clone())).unwrap();
+        let transport = ztimeout!(client_manager.open_transport_unicast(endpoint.clone())).unwrap();
+        assert_eq!(transport.get_links().unwrap().len(), 3);
+
+        ztimeout!(transport.close()).unwrap();
+        ztimeout!(router_manager.del_listener(&endpoint)).unwrap();
+        ztimeout!(router_manager.close());
+        ztimeout!(client_manager.close());
+    }
+
+    #[cfg(feature = "transport_tcp")]
+    mod striping {
+        use super::*;
+        use std::{
+            any::Any,
+            sync::{
+                atomic::{AtomicUsize, Ordering},
+                Mutex,
+            },
+        };
+        use tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::{TcpListener, TcpStream},
+        };
+        use zenoh_buffers::buffer::SplitBuffer;
+        use zenoh_link::Link;
+        use zenoh_protocol::{
+            core::{CongestionControl, Encoding, Priority},
+            network::{
+                push::ext::{NodeIdType, QoSType},
+                NetworkBody, NetworkMessage, Push,
+            },
+            zenoh::{PushBody, Put},
+        };
+
+        const MSG_COUNT: usize = 1_000;
+        const MSG_SIZE: usize = 1_024;
+
+        // Transport Handler for the router, checking the messages are received in order
+        #[derive(Default)]
+        struct SHRouterOrdered {
+            count: Arc<AtomicUsize>,
+        }
+
+        impl TransportEventHandler for SHRouterOrdered {
+            fn new_unicast(
+                &self,
+                _peer: TransportPeer,
+                _transport: TransportUnicast,
+            ) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
+                Ok(Arc::new(SCRouterOrdered {
+                    count: self.count.clone(),
+                }))
+            }
+
+            fn new_multicast(
+                &self,
+                _transport: TransportMulticast,
+            ) -> ZResult<Arc<dyn TransportMulticastEventHandler>> {
+                panic!();
+            }
+        }
+
+        struct SCRouterOrdered {
+            count: Arc<AtomicUsize>,
+        }
+
+        impl TransportPeerEventHandler for SCRouterOrdered {
+            fn handle_message(&self, message: NetworkMessage) -> ZResult<()> {
+                let payload = match message.body {
+                    NetworkBody::Push(Push {
+                        payload: PushBody::Put(Put { payload, .. }),
+                        ..
+                    }) => payload.contiguous().into_owned(),
+                    _ => panic!("Unsolicited message"),
+                };
+                let mut index = [0_u8; 8];
+                index.copy_from_slice(&payload[0..8]);
+                let count = self.count.fetch_add(1, Ordering::SeqCst);
+                assert_eq!(u64::from_le_bytes(index) as usize, count);
+                Ok(())
+            }
+
+            fn new_link(&self, _link: Link) {}
+            fn del_link(&self, _link: Link) {}
+            fn closing(&self) {}
+            fn closed(&self) {}
+
+            fn as_any(&self) -> &dyn Any {
+                self
+            }
+        }
+
+        // Relays the connections accepted on listener to target, counting the bytes each one
+        // carries towards target
+        async fn relay(
+            listener: TcpListener,
+            target: String,
+            sent: Arc<Mutex<Vec<Arc<AtomicUsize>>>>,
+        ) {
+            while let Ok((mut inbound, _)) = listener.accept().await {
+                let mut outbound = TcpStream::connect(&target).await.unwrap();
+                let count = Arc::new(AtomicUsize::new(0));
+                sent.lock().unwrap().push(count.clone());
+                tokio::spawn(async move {
+                    let (mut in_rx, mut in_tx) = inbound.split();
+                    let (mut out_rx, mut out_tx) = outbound.split();
+                    let upstream = async {
+                        let mut buffer = vec![0_u8; 65_536];
+                        while let Ok(n) = in_rx.read(&mut buffer).await {
+                            if n == 0 || out_tx.write_all(&buffer[..n]).await.is_err() {
+                                break;
+                            }
+                            count.fetch_add(n, Ordering::SeqCst);
+                        }
+                    };
+                    let downstream = tokio::io::copy(&mut out_rx, &mut in_tx);
+                    let _ = tokio::join!(upstream, downstream);
+                });
+            }
+        }
+
+        #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+        async fn multilink_parallel_links_striping_tcp_only() {
+            zenoh_util::try_init_log_from_env();
+
+            let endpoint: EndPoint = format!("tcp/127.0.0.1:{}", 18002).parse().unwrap();
+            let relayed: EndPoint = format!("tcp/127.0.0.1:{}", 18003).parse().unwrap();
+
+            let router_handler = Arc::new(SHRouterOrdered::default());
+            let router_manager = TransportManager::builder()
+                .whatami(WhatAmI::Router)
+                .zid(ZenohId::try_from([1]).unwrap())
+                .unicast(TransportManager::config_unicast().max_links(4))
+                .build(router_handler.clone())
+                .unwrap();
+            let client_manager = TransportManager::builder()
+                .whatami(WhatAmI::Client)
+                .zid(ZenohId::try_from([2]).unwrap())
+                .unicast(
+                    TransportManager::config_unicast()
+                        .max_links(4)
+                        .max_links_per_session(3),
+                )
+                .build(Arc::new(SHClientOpenClose::new()))
+                .unwrap();
+
+            let _ = ztimeout!(router_manager.add_listener(endpoint.clone())).unwrap();
+            let sent = Arc::new(Mutex::new(vec![]));
+            let listener = TcpListener::bind("127.0.0.1:18003").await.unwrap();
+            let task = tokio::spawn(relay(listener, "127.0.0.1:18002".into(), sent.clone()));
+            let transport = ztimeout!(client_manager.open_transport_unicast(relayed)).unwrap();
+            assert_eq!(transport.get_links().unwrap().len(), 3);
+
+            for index in 0..MSG_COUNT {
+                let mut payload = vec![0_u8; MSG_SIZE];
+                payload[0..8].copy_from_slice(&(index as u64).to_le_bytes());
+                let message: NetworkMessage = Push {
+                    wire_expr: "test".into(),
+                    ext_qos: QoSType::new(Priority::Data, CongestionControl::Block, false),
+                    ext_tstamp: None,
+                    ext_nodeid: NodeIdType::default(),
+                    ext_hoplimit: None,
+                    payload: Put {
+                        payload: payload.into(),
+                        timestamp: None,
+                        encoding: Encoding::default(),
+                        ext_sinfo: None,
+                        #[cfg(feature = "shared-memory")]
+                        ext_shm: None,
+                        ext_attachment: None,
+                        ext_tbound: None,
+                        ext_unknown: vec![],
+                    }
+                    .into(),
+                }
+                .into();
+                transport.schedule(message).unwrap();
+            }
+            ztimeout!(async {
+                while router_handler.count.load(Ordering::SeqCst) != MSG_COUNT {
+                    tokio::time::sleep(SLEEP).await;
+                }
+            });
+
+            // The messages of the same priority are spread evenly across the links
+            let sent: Vec<usize> = sent
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|count| count.load(Ordering::SeqCst))
+                .collect();
+            assert_eq!(sent.len(), 3);
+            for bytes in sent {
+                assert!(bytes > MSG_COUNT * MSG_SIZE / 4, "{bytes} bytes sent");
+            }
+
+            ztimeout!(transport.close()).unwrap();
+            task.abort();
+            ztimeout!(router_manager.del_listener(&endpoint)).unwrap();
+            ztimeout!(router_manager.close());
+            ztimeout!(client_manager.close());
+        }
+    }
+
+    #[test]
+    fn multilink_parallel_links_exceeding_max_links() {
+        let unicast = TransportManager::config_unicast()
+            .max_links(2)
+            .max_links_per_session(3);
+        assert!(TransportManager::builder()
+            .unicast(unicast)
+            .build(Arc::new(SHClientOpenClose::new()))
+            .is_err());
+    }
 }
@@ -290,6 +290,7 @@ async fn single_run(router_handler: Arc<SHRouter>, client_transport: TransportUn
                 ext_qos: QoSType::new(*p, CongestionControl::Block, false),
                 ext_tstamp: None,
                 ext_nodeid: NodeIdType::default(),
+                ext_hoplimit: None,
                 payload: Put {
                     payload: vec![0u8; *ms].into(),
                     timestamp: None,
@@ -298,6 +299,7 @@ async fn single_run(router_handler: Arc<SHRouter>, client_transport: TransportUn
                     #[cfg(feature = "shared-memory")]
                     ext_shm: None,
                     ext_attachment: None,
+                    ext_tbound: None,
                     ext_unknown: vec![],
                 }
                 .into(),
@@ -264,6 +264,7 @@ mod tests {
                 ext_qos: QoSType::new(Priority::default(), CongestionControl::Block, false),
                 ext_tstamp: None,
                 ext_nodeid: NodeIdType::default(),
+                ext_hoplimit: None,
                 payload: Put {
                     payload: sbuf.into(),
                     timestamp: None,
@@ -271,6 +272,7 @@ mod tests {
                     ext_sinfo: None,
                     ext_shm: None,
                     ext_attachment: None,
+                    ext_tbound: None,
                     ext_unknown: vec![],
                 }
                 .into(),
@@ -312,6 +314,7 @@ mod tests {
                 ext_qos: QoSType::new(Priority::default(), CongestionControl::Block, false),
                 ext_tstamp: None,
                 ext_nodeid: NodeIdType::default(),
+                ext_hoplimit: None,
                 payload: Put {
                     payload: sbuf.into(),
                     timestamp: None,
@@ -319,6 +322,7 @@ mod tests {
                     ext_sinfo: None,
                     ext_shm: None,
                     ext_attachment: None,
+                    ext_tbound: None,
                     ext_unknown: vec![],
                 }
                 .into(),
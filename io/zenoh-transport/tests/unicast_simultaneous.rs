@@ -40,6 +40,29 @@ mod tests {
     const MSG_COUNT: usize = 16;
     const MSG_SIZE: usize = 1_024;
 
+    fn message() -> NetworkMessage {
+        Push {
+            wire_expr: "test".into(),
+            ext_qos: QoSType::new(Priority::Control, CongestionControl::Block, false),
+            ext_tstamp: None,
+            ext_nodeid: NodeIdType::default(),
+            ext_hoplimit: None,
+            payload: Put {
+                payload: vec![0u8; MSG_SIZE].into(),
+                timestamp: None,
+                encoding: Encoding::default(),
+                ext_sinfo: None,
+                #[cfg(feature = "shared-memory")]
+                ext_shm: None,
+                ext_attachment: None,
+                ext_tbound: None,
+                ext_unknown: vec![],
+            }
+            .into(),
+        }
+        .into()
+    }
+
     // Transport Handler for the router
     struct SHPeer {
         zid: ZenohId,
@@ -66,24 +89,7 @@ mod tests {
             transport: TransportUnicast,
         ) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
             // Create the message to send
-            let message: NetworkMessage = Push {
-                wire_expr: "test".into(),
-                ext_qos: QoSType::new(Priority::Control, CongestionControl::Block, false),
-                ext_tstamp: None,
-                ext_nodeid: NodeIdType::default(),
-                payload: Put {
-                    payload: vec![0u8; MSG_SIZE].into(),
-                    timestamp: None,
-                    encoding: Encoding::default(),
-                    ext_sinfo: None,
-                    #[cfg(feature = "shared-memory")]
-                    ext_shm: None,
-                    ext_attachment: None,
-                    ext_unknown: vec![],
-                }
-                .into(),
-            }
-            .into();
+            let message = message();
 
             println!("[Simultaneous {}] Sending {}...", self.zid, MSG_COUNT);
             for _ in 0..MSG_COUNT {
@@ -174,6 +180,15 @@ mod tests {
         println!("[Simultaneous 02b] => Getting endpoints: {endpoint02:?} {locs:?}");
         assert_eq!(endpoint02.len(), locs.len());
 
+        // With a single link per transport, the simultaneous open is resolved by keeping one link.
+        // The messages sent while the other link is retired must not be lost.
+        let single_link = endpoint01.len() == 1 && endpoint02.len() == 1;
+        let expected_count = if single_link {
+            2 * MSG_COUNT
+        } else {
+            MSG_COUNT
+        };
+
         // Endpoints
         let c_ep01 = endpoint01.clone();
         let c_ep02 = endpoint02.clone();
@@ -185,14 +200,21 @@ mod tests {
             // These open should succeed
             for e in c_ep02.iter() {
                 println!("[Simultaneous 01c] => Opening transport with {e:?}...");
-                let _ = ztimeout!(c_p01m.open_transport_unicast(e.clone())).unwrap();
+                let transport = ztimeout!(c_p01m.open_transport_unicast(e.clone())).unwrap();
+                if single_link {
+                    for _ in 0..MSG_COUNT {
+                        transport.schedule(message()).unwrap();
+                    }
+                }
             }
 
             // These open should fails
-            for e in c_ep02.iter() {
-                println!("[Simultaneous 01d] => Exceeding transport with {e:?}...");
-                let res = ztimeout!(c_p01m.open_transport_unicast(e.clone()));
-                assert!(res.is_err());
+            if !single_link {
+                for e in c_ep02.iter() {
+                    println!("[Simultaneous 01d] => Exceeding transport with {e:?}...");
+                    let res = ztimeout!(c_p01m.open_transport_unicast(e.clone()));
+                    assert!(res.is_err());
+                }
             }
 
             tokio::time::sleep(SLEEP).await;
@@ -213,7 +235,11 @@ mod tests {
 
             // Wait for the links to be properly established
             ztimeout!(async {
-                let expected = endpoint01.len() + c_ep02.len();
+                let expected = if single_link {
+                    1
+                } else {
+                    endpoint01.len() + c_ep02.len()
+                };
                 let mut tl02 = vec![];
                 while tl02.len() != expected {
                     tokio::time::sleep(SLEEP).await;
@@ -225,10 +251,10 @@ mod tests {
             // Wait for the messages to arrive to peer 01
             ztimeout!(async {
                 let mut check = 0;
-                while check != MSG_COUNT {
+                while check != expected_count {
                     tokio::time::sleep(SLEEP).await;
                     check = peer_sh01.get_count();
-                    println!("[Simultaneous 01g] => Received {check:?}/{MSG_COUNT:?}");
+                    println!("[Simultaneous 01g] => Received {check:?}/{expected_count:?}");
                 }
             });
         });
@@ -240,14 +266,21 @@ mod tests {
             // These open should succeed
             for e in c_ep01.iter() {
                 println!("[Simultaneous 02c] => Opening transport with {e:?}...");
-                let _ = ztimeout!(c_p02m.open_transport_unicast(e.clone())).unwrap();
+                let transport = ztimeout!(c_p02m.open_transport_unicast(e.clone())).unwrap();
+                if single_link {
+                    for _ in 0..MSG_COUNT {
+                        transport.schedule(message()).unwrap();
+                    }
+                }
             }
 
             // These open should fails
-            for e in c_ep01.iter() {
-                println!("[Simultaneous 02d] => Exceeding transport with {e:?}...");
-                let res = ztimeout!(c_p02m.open_transport_unicast(e.clone()));
-                assert!(res.is_err());
+            if !single_link {
+                for e in c_ep01.iter() {
+                    println!("[Simultaneous 02d] => Exceeding transport with {e:?}...");
+                    let res = ztimeout!(c_p02m.open_transport_unicast(e.clone()));
+                    assert!(res.is_err());
+                }
             }
 
             // Wait a little bit
@@ -268,7 +301,11 @@ mod tests {
 
             // Wait for the links to be properly established
             ztimeout!(async {
-                let expected = c_ep01.len() + endpoint02.len();
+                let expected = if single_link {
+                    1
+                } else {
+                    c_ep01.len() + endpoint02.len()
+                };
                 let mut tl01 = vec![];
                 while tl01.len() != expected {
                     tokio::time::sleep(SLEEP).await;
@@ -280,10 +317,10 @@ mod tests {
             // Wait for the messages to arrive to peer 02
             ztimeout!(async {
                 let mut check = 0;
-                while check != MSG_COUNT {
+                while check != expected_count {
                     tokio::time::sleep(SLEEP).await;
                     check = peer_sh02.get_count();
-                    println!("[Simultaneous 02g] => Received {check:?}/{MSG_COUNT:?}");
+                    println!("[Simultaneous 02g] => Received {check:?}/{expected_count:?}");
                 }
             });
         });
@@ -316,6 +353,16 @@ mod tests {
         transport_simultaneous(endpoint01, endpoint02).await;
     }
 
+    #[cfg(feature = "transport_tcp")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn transport_tcp_simultaneous_single_link() {
+        zenoh_util::try_init_log_from_env();
+        let endpoint01: Vec<EndPoint> = vec![format!("tcp/127.0.0.1:{}", 15040).parse().unwrap()];
+        let endpoint02: Vec<EndPoint> = vec![format!("tcp/127.0.0.1:{}", 15050).parse().unwrap()];
+
+        transport_simultaneous(endpoint01, endpoint02).await;
+    }
+
     #[cfg(feature = "transport_unixpipe")]
     #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
     #[ignore]
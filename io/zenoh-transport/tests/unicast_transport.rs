@@ -476,6 +476,7 @@ async fn test_transport(
         ext_qos: QoSType::new(channel.priority, cctrl, false),
         ext_tstamp: None,
         ext_nodeid: NodeIdType::default(),
+        ext_hoplimit: None,
         payload: Put {
             payload: vec![0u8; msg_size].into(),
             timestamp: None,
@@ -484,6 +485,7 @@ async fn test_transport(
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
             ext_attachment: None,
+            ext_tbound: None,
             ext_unknown: vec![],
         }
         .into(),
@@ -677,6 +679,29 @@ async fn transport_unicast_udp_only() {
     run_with_universal_transport(&endpoints, &endpoints, &channel, &MSG_SIZE_NOFRAG).await;
 }
 
+#[cfg(feature = "transport_udp")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn transport_unicast_udp_arq_only() {
+    zenoh_util::try_init_log_from_env();
+
+    // Define the locator
+    let endpoints: Vec<EndPoint> =
+        vec![format!("udp/127.0.0.1:{}#arq=true", 16012).parse().unwrap()];
+    // Define the reliability and congestion control
+    let channel = [
+        Channel {
+            priority: Priority::default(),
+            reliability: Reliability::Reliable,
+        },
+        Channel {
+            priority: Priority::RealTime,
+            reliability: Reliability::Reliable,
+        },
+    ];
+    // Run
+    run_with_universal_transport(&endpoints, &endpoints, &channel, &MSG_SIZE_NOFRAG).await;
+}
+
 #[cfg(feature = "transport_udp")]
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn transport_unicast_udp_only_with_lowlatency_transport() {
@@ -1043,6 +1068,92 @@ async fn transport_unicast_tls_only_server() {
     run_with_universal_transport(&endpoints, &endpoints, &channel, &MSG_SIZE_ALL).await;
 }
 
+#[cfg(all(
+    feature = "transport_ws",
+    feature = "transport_tls",
+    target_family = "unix"
+))]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn transport_unicast_wss_only_server() {
+    use zenoh_link::tls::config::*;
+
+    zenoh_util::try_init_log_from_env();
+
+    // Define the locator
+    let mut endpoint: EndPoint = format!("wss/localhost:{}", 16075).parse().unwrap();
+    endpoint
+        .config_mut()
+        .extend(
+            [
+                (TLS_ROOT_CA_CERTIFICATE_RAW, SERVER_CA),
+                (TLS_SERVER_CERTIFICATE_RAW, SERVER_CERT),
+                (TLS_SERVER_PRIVATE_KEY_RAW, SERVER_KEY),
+            ]
+            .iter()
+            .map(|(k, v)| ((*k).to_owned(), (*v).to_owned())),
+        )
+        .unwrap();
+
+    // Define the reliability and congestion control
+    let channel = [
+        Channel {
+            priority: Priority::default(),
+            reliability: Reliability::Reliable,
+        },
+        Channel {
+            priority: Priority::default(),
+            reliability: Reliability::BestEffort,
+        },
+        Channel {
+            priority: Priority::RealTime,
+            reliability: Reliability::Reliable,
+        },
+        Channel {
+            priority: Priority::RealTime,
+            reliability: Reliability::BestEffort,
+        },
+    ];
+    // Run
+    let endpoints = vec![endpoint];
+    run_with_universal_transport(&endpoints, &endpoints, &channel, &MSG_SIZE_ALL).await;
+}
+
+#[cfg(all(
+    feature = "transport_ws",
+    feature = "transport_tls",
+    target_family = "unix"
+))]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn transport_unicast_wss_max_frame_size() {
+    use zenoh_link::tls::config::*;
+
+    zenoh_util::try_init_log_from_env();
+
+    // The frames are limited below the default batch size: the messages must be fragmented
+    let mut endpoint: EndPoint = format!("wss/localhost:{}#max_frame_size=8192", 16076)
+        .parse()
+        .unwrap();
+    endpoint
+        .config_mut()
+        .extend(
+            [
+                (TLS_ROOT_CA_CERTIFICATE_RAW, SERVER_CA),
+                (TLS_SERVER_CERTIFICATE_RAW, SERVER_CERT),
+                (TLS_SERVER_PRIVATE_KEY_RAW, SERVER_KEY),
+            ]
+            .iter()
+            .map(|(k, v)| ((*k).to_owned(), (*v).to_owned())),
+        )
+        .unwrap();
+
+    let channel = [Channel {
+        priority: Priority::default(),
+        reliability: Reliability::Reliable,
+    }];
+    let endpoints = vec![endpoint];
+    run_with_universal_transport(&endpoints, &endpoints, &channel, &MSG_SIZE_ALL).await;
+}
+
 #[cfg(feature = "transport_quic")]
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn transport_unicast_quic_only_server() {
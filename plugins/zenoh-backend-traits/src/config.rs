@@ -54,6 +54,8 @@ pub struct StorageConfig {
     pub name: String,
     pub key_expr: OwnedKeyExpr,
     pub complete: bool,
+    // Also forward the queries to the live queryables and merge their replies with the stored data
+    pub merge_live: bool,
     pub strip_prefix: Option<OwnedKeyExpr>,
     pub volume_id: String,
     pub volume_cfg: Value,
@@ -374,6 +376,18 @@ impl StorageConfig {
             }
             None => false,
         };
+        let merge_live = match config.get("merge_live").and_then(|x| x.as_str()) {
+            Some(s) => {
+                match s {
+                    "true" => true,
+                    "false" => false,
+                    e => {
+                        bail!("merge_live='{}' is not a valid value. Accepted values: ['true', 'false']", e)
+                    }
+                }
+            }
+            None => false,
+        };
         let strip_prefix: Option<OwnedKeyExpr> = match config.get("strip_prefix") {
             Some(Value::String(s)) => {
                 if !key_expr.starts_with(s) {
@@ -483,6 +497,7 @@ impl StorageConfig {
             name: storage_name.into(),
             key_expr,
             complete,
+            merge_live,
             strip_prefix,
             volume_id,
             volume_cfg,
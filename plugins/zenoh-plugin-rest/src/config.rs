@@ -27,6 +27,8 @@ pub struct Config {
     __path__: Option<Vec<String>>,
     __required__: Option<bool>,
     __config__: Option<String>,
+    __depends__: Option<Vec<String>>,
+    __retries__: Option<u32>,
 }
 
 impl From<&Config> for serde_json::Value {
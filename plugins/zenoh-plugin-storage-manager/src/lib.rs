@@ -264,7 +264,17 @@ impl From<StorageRuntimeInner> for StorageRuntime {
 
 impl PluginControl for StorageRuntime {
     fn report(&self) -> PluginReport {
-        PluginReport::default()
+        let guard = self.0.lock().unwrap();
+        let mut report = PluginReport::default();
+        report.set_metric(
+            "volumes",
+            guard.plugins_manager.started_plugins_iter().count(),
+        );
+        report.set_metric(
+            "storages",
+            guard.storages.values().map(HashMap::len).sum::<usize>(),
+        );
+        report
     }
     fn plugins_status(&self, names: &keyexpr) -> Vec<PluginStatusRec> {
         let guard = self.0.lock().unwrap();
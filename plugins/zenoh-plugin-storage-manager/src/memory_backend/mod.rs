@@ -19,6 +19,7 @@ use zenoh::prelude::r#async::*;
 use zenoh::time::Timestamp;
 use zenoh_backend_traits::config::{StorageConfig, VolumeConfig};
 use zenoh_backend_traits::*;
+use zenoh_keyexpr::key_expr::{KeyDict, KeyId};
 use zenoh_plugin_trait::{plugin_long_version, plugin_version, Plugin};
 use zenoh_result::ZResult;
 
@@ -90,16 +91,72 @@ impl Drop for MemoryBackend {
     }
 }
 
+// The minimal number of deleted keys before the dictionary is rebuilt
+const MIN_DELETED_KEYS: usize = 1024;
+
+// The stored keys are interned in a dictionary, the keys of a storage usually sharing deep prefixes
+#[derive(Default)]
+struct MemoryMap {
+    keys: KeyDict,
+    entries: HashMap<Option<KeyId>, StoredData>,
+    // The keys deleted since the dictionary was built, which it still holds
+    deleted: usize,
+}
+
+impl MemoryMap {
+    // The identifier of a key, `None` if it is unknown
+    fn id(&self, key: &Option<OwnedKeyExpr>) -> Option<Option<KeyId>> {
+        match key {
+            Some(k) => self.keys.get_id(k).map(Some),
+            None => Some(None),
+        }
+    }
+
+    fn remove(&mut self, key: &Option<OwnedKeyExpr>) {
+        if let Some(id) = self.id(key) {
+            if self.entries.remove(&id).is_some() && id.is_some() {
+                self.deleted += 1;
+                if self.deleted >= MIN_DELETED_KEYS.max(self.entries.len()) {
+                    self.rebuild();
+                }
+            }
+        }
+    }
+
+    // Rebuild the dictionary from the stored keys only, dropping the deleted ones
+    fn rebuild(&mut self) {
+        tracing::trace!(
+            "Rebuild the dictionary of {} keys after {} deletions",
+            self.entries.len(),
+            self.deleted
+        );
+        let old = std::mem::take(&mut self.keys);
+        let mut entries = HashMap::with_capacity(self.entries.len());
+        for (id, data) in self.entries.drain() {
+            let id = match id {
+                Some(id) => match old.get(id) {
+                    Some(key) => Some(self.keys.insert(&key)),
+                    None => continue,
+                },
+                None => None,
+            };
+            entries.insert(id, data);
+        }
+        self.entries = entries;
+        self.deleted = 0;
+    }
+}
+
 struct MemoryStorage {
     config: StorageConfig,
-    map: Arc<RwLock<HashMap<Option<OwnedKeyExpr>, StoredData>>>,
+    map: Arc<RwLock<MemoryMap>>,
 }
 
 impl MemoryStorage {
     async fn new(properties: StorageConfig) -> ZResult<MemoryStorage> {
         Ok(MemoryStorage {
             config: properties,
-            map: Arc::new(RwLock::new(HashMap::new())),
+            map: Arc::new(RwLock::new(MemoryMap::default())),
         })
     }
 }
@@ -118,7 +175,8 @@ impl Storage for MemoryStorage {
     ) -> ZResult<StorageInsertionResult> {
         tracing::trace!("put for {:?}", key);
         let mut map = self.map.write().await;
-        match map.entry(key) {
+        let key = key.map(|k| map.keys.insert(&k));
+        match map.entries.entry(key) {
             std::collections::hash_map::Entry::Occupied(mut e) => {
                 e.insert(StoredData { value, timestamp });
                 return Ok(StorageInsertionResult::Replaced);
@@ -136,7 +194,7 @@ impl Storage for MemoryStorage {
         _timestamp: Timestamp,
     ) -> ZResult<StorageInsertionResult> {
         tracing::trace!("delete for {:?}", key);
-        self.map.write().await.remove_entry(&key);
+        self.map.write().await.remove(&key);
         return Ok(StorageInsertionResult::Deleted);
     }
 
@@ -147,7 +205,8 @@ impl Storage for MemoryStorage {
     ) -> ZResult<Vec<StoredData>> {
         tracing::trace!("get for {:?}", key);
         // @TODO: use parameters???
-        match self.map.read().await.get(&key) {
+        let map = self.map.read().await;
+        match map.id(&key).and_then(|id| map.entries.get(&id)) {
             Some(v) => Ok(vec![v.clone()]),
             None => Err(format!("Key {:?} is not present", key).into()),
         }
@@ -155,9 +214,9 @@ impl Storage for MemoryStorage {
 
     async fn get_all_entries(&self) -> ZResult<Vec<(Option<OwnedKeyExpr>, Timestamp)>> {
         let map = self.map.read().await;
-        let mut result = Vec::with_capacity(map.len());
-        for (k, v) in map.iter() {
-            result.push((k.clone(), v.timestamp));
+        let mut result = Vec::with_capacity(map.entries.len());
+        for (k, v) in map.entries.iter() {
+            result.push((k.and_then(|k| map.keys.get(k)), v.timestamp));
         }
         Ok(result)
     }
@@ -20,7 +20,7 @@ use flume::{Receiver, Sender};
 use futures::select;
 use std::collections::{HashMap, HashSet};
 use std::str::{self, FromStr};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use zenoh::buffers::ZBuf;
 use zenoh::prelude::r#async::*;
 use zenoh::query::ConsolidationMode;
@@ -28,7 +28,7 @@ use zenoh::time::{Timestamp, NTP64};
 use zenoh::{Result as ZResult, Session};
 use zenoh_backend_traits::config::{GarbageCollectionConfig, StorageConfig};
 use zenoh_backend_traits::{Capability, History, Persistence, StorageInsertionResult, StoredData};
-use zenoh_keyexpr::key_expr::OwnedKeyExpr;
+use zenoh_keyexpr::key_expr::{longest_common_prefix, OwnedKeyExpr};
 use zenoh_keyexpr::keyexpr_tree::impls::KeyedSetProvider;
 use zenoh_keyexpr::keyexpr_tree::{support::NonWild, support::UnknownWildness, KeBoxTree};
 use zenoh_keyexpr::keyexpr_tree::{IKeyExprTree, IKeyExprTreeMut};
@@ -37,6 +37,11 @@ use zenoh_util::{zenoh_home, Timed, TimedEvent, Timer};
 
 pub const WILDCARD_UPDATES_FILENAME: &str = "wildcard_updates";
 pub const TOMBSTONE_FILENAME: &str = "tombstones";
+// The attachment of the queries forwarded to the live queryables by a storage merging their replies,
+// identifying the storage so that it doesn't answer its own queries
+const MERGE_LIVE_ATTACHMENT: &str = "storage_merge";
+// The time kept, out of the deadline of a merged query, to send the merged replies to the querier
+const MERGE_LIVE_REPLY_MARGIN: Duration = Duration::from_millis(50);
 
 #[derive(Clone)]
 struct Update {
@@ -54,6 +59,7 @@ pub struct StorageService {
     session: Arc<Session>,
     key_expr: OwnedKeyExpr,
     complete: bool,
+    merge_live: bool,
     name: String,
     strip_prefix: Option<OwnedKeyExpr>,
     storage: Mutex<Box<dyn zenoh_backend_traits::Storage>>,
@@ -79,6 +85,7 @@ impl StorageService {
             session,
             key_expr: config.key_expr,
             complete: config.complete,
+            merge_live: config.merge_live,
             name: name.to_string(),
             strip_prefix: config.strip_prefix,
             storage: Mutex::new(store_intercept.storage),
@@ -499,6 +506,39 @@ impl StorageService {
             }
         };
         tracing::trace!("[STORAGE] Processing query on key_expr: {}", q.key_expr());
+        let merge_id = format!("{}/{}", self.session.zid(), self.name);
+        let merge_origin = q
+            .attachment()
+            .and_then(|attachment| attachment.get(&MERGE_LIVE_ATTACHMENT));
+        if merge_origin.is_some_and(|origin| origin.as_slice() == merge_id.as_bytes()) {
+            // The query has been forwarded by this storage: its data are already in the result
+            return;
+        }
+        // The queries forwarded by other storages are answered with the stored data only
+        if self.merge_live && merge_origin.is_none() {
+            let stored = self.get_stored_samples(&q).await;
+            let session = self.session.clone();
+            let name = self.name.clone();
+            // The forwarded query reaches this storage as well: it must not wait for it
+            async_std::task::spawn(async move {
+                merge_live_replies(&session, &name, &merge_id, q, stored).await
+            });
+            return;
+        }
+        for sample in self.get_stored_samples(&q).await {
+            if let Err(e) = q.reply(Ok(sample)).res().await {
+                tracing::warn!(
+                    "Storage '{}' raised an error replying a query: {}",
+                    self.name,
+                    e
+                )
+            }
+        }
+    }
+
+    // The stored samples matching the query
+    async fn get_stored_samples(&self, q: &zenoh::queryable::Query) -> Vec<Sample> {
+        let mut samples = Vec::new();
         if q.key_expr().is_wild() {
             // resolve key expr into individual keys
             let matching_keys = self.get_matching_keys(q.key_expr()).await;
@@ -509,7 +549,7 @@ impl StorageService {
                     Err(e) => {
                         tracing::error!("{}", e);
                         // @TODO: return error when it is supported
-                        return;
+                        return samples;
                     }
                 };
                 match storage.get(stripped_key, q.parameters()).await {
@@ -523,13 +563,7 @@ impl StorageService {
                             } else {
                                 sample
                             };
-                            if let Err(e) = q.reply(Ok(sample)).res().await {
-                                tracing::warn!(
-                                    "Storage '{}' raised an error replying a query: {}",
-                                    self.name,
-                                    e
-                                )
-                            }
+                            samples.push(sample);
                         }
                     }
                     Err(e) => {
@@ -544,7 +578,7 @@ impl StorageService {
                 Err(e) => {
                     tracing::error!("{}", e);
                     // @TODO: return error when it is supported
-                    return;
+                    return samples;
                 }
             };
             let mut storage = self.storage.lock().await;
@@ -559,13 +593,7 @@ impl StorageService {
                         } else {
                             sample
                         };
-                        if let Err(e) = q.reply(Ok(sample)).res().await {
-                            tracing::warn!(
-                                "Storage '{}' raised an error replying a query: {}",
-                                self.name,
-                                e
-                            )
-                        }
+                        samples.push(sample);
                     }
                 }
                 Err(e) => {
@@ -573,15 +601,25 @@ impl StorageService {
                 }
             };
         }
+        samples
     }
 
     async fn get_matching_keys(&self, key_expr: &KeyExpr<'_>) -> Vec<OwnedKeyExpr> {
         let mut result = Vec::new();
+        // The stripped keys matching the query all start with its stripped verbatim prefix
+        let query_prefix = key_expr
+            .get_nonwild_prefix()
+            .and_then(|p| self.strip_prefix(&p.into()).ok().flatten());
         // @TODO: if cache exists, use that to get the list
         let storage = self.storage.lock().await;
         match storage.get_all_entries().await {
             Ok(entries) => {
                 for (k, _ts) in entries {
+                    if let (Some(key), Some(prefix)) = (&k, &query_prefix) {
+                        if longest_common_prefix([&**key, &**prefix]) != Some(&**prefix) {
+                            continue;
+                        }
+                    }
                     // @TODO: optimize adding back the prefix (possible inspiration from https://github.com/eclipse-zenoh/zenoh/blob/0.5.0-beta.9/backends/traits/src/utils.rs#L79)
                     let full_key = match k {
                         Some(key) => StorageService::get_prefixed(&self.strip_prefix, &key.into()),
@@ -670,6 +708,84 @@ impl StorageService {
     }
 }
 
+// Forward the query to the live queryables, and reply with the stored samples plus the live
+// replies newer than the latest stored sample of their key
+async fn merge_live_replies(
+    session: &Session,
+    name: &str,
+    merge_id: &str,
+    q: zenoh::queryable::Query,
+    stored: Vec<Sample>,
+) {
+    let mut latest: HashMap<OwnedKeyExpr, Option<Timestamp>> = HashMap::new();
+    for sample in &stored {
+        let entry = latest
+            .entry(sample.key_expr.clone().into())
+            .or_insert(sample.timestamp);
+        if sample.timestamp > *entry {
+            *entry = sample.timestamp;
+        }
+    }
+
+    let mut attachment = zenoh::sample::Attachment::new();
+    attachment.insert(MERGE_LIVE_ATTACHMENT, merge_id);
+    let mut live: HashMap<OwnedKeyExpr, Sample> = HashMap::new();
+    // The live replies must be collected before the original querier stops waiting for them
+    let timeout = q.deadline().map(|deadline| {
+        deadline
+            .saturating_duration_since(Instant::now())
+            .saturating_sub(MERGE_LIVE_REPLY_MARGIN)
+    });
+    let get = session
+        .get(q.selector())
+        .target(QueryTarget::All)
+        .consolidation(ConsolidationMode::None)
+        .with_attachment(attachment);
+    let get = match timeout {
+        Some(timeout) => get.timeout(timeout),
+        None => get,
+    };
+    let replies = match timeout {
+        // No time is left to query the live queryables
+        Some(timeout) if timeout.is_zero() => flume::bounded(0).1,
+        _ => match get.res().await {
+            Ok(replies) => replies,
+            Err(e) => {
+                tracing::warn!(
+                    "Storage '{}' raised an error forwarding a query: {}",
+                    name,
+                    e
+                );
+                // Reply with the stored samples only
+                flume::bounded(0).1
+            }
+        },
+    };
+    while let Ok(reply) = replies.recv_async().await {
+        let Ok(sample) = reply.sample else {
+            continue;
+        };
+        let key: OwnedKeyExpr = sample.key_expr.clone().into();
+        // Only the freshest live sample of each key, if newer than the stored ones
+        let newer_than_stored = match latest.get(&key) {
+            Some(stored) => sample.timestamp.is_some() && sample.timestamp > *stored,
+            None => true,
+        };
+        let newer_than_live = live
+            .get(&key)
+            .map_or(true, |other| sample.timestamp > other.timestamp);
+        if newer_than_stored && newer_than_live {
+            live.insert(key, sample);
+        }
+    }
+
+    for sample in stored.into_iter().chain(live.into_values()) {
+        if let Err(e) = q.reply(Ok(sample)).res().await {
+            tracing::warn!("Storage '{}' raised an error replying a query: {}", name, e)
+        }
+    }
+}
+
 fn serialize_update(update: &Update) -> String {
     let result = (
         update.kind.to_string(),
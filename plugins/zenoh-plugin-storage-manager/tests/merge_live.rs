@@ -0,0 +1,141 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test the merge of the stored data with the live replies -
+// 1. a live value newer than the stored one wins over it
+// 2. a live value older than the stored one is not replied
+
+use std::str::FromStr;
+use std::thread::sleep;
+
+use async_std::task;
+use zenoh::prelude::r#async::*;
+use zenoh::query::{ConsolidationMode, Reply};
+use zenoh::{prelude::Config, time::Timestamp};
+use zenoh_core::zasync_executor_init;
+use zenoh_plugin_trait::Plugin;
+
+async fn get_values(
+    session: &zenoh::Session,
+    key_expr: &str,
+    consolidation: ConsolidationMode,
+) -> Vec<String> {
+    let replies: Vec<Reply> = session
+        .get(key_expr)
+        .consolidation(consolidation)
+        .res()
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+    let mut values: Vec<String> = replies
+        .into_iter()
+        .filter_map(|reply| reply.sample.ok())
+        .map(|sample| format!("{}", sample.value))
+        .collect();
+    values.sort();
+    println!("Getting Data on '{key_expr}': '{values:?}'...");
+    values
+}
+
+async fn test_merge_live() {
+    task::block_on(async {
+        zasync_executor_init!();
+    });
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "timestamping",
+            r#"{ enabled: { router: true, peer: true, client: true } }"#,
+        )
+        .unwrap();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            r#"{
+                    storages: {
+                        merge_test: {
+                            key_expr: "merge/test/**",
+                            merge_live: "true",
+                            volume: {
+                                id: "memory"
+                            }
+                        }
+                    }
+                }"#,
+        )
+        .unwrap();
+
+    let runtime = zenoh::runtime::RuntimeBuilder::new(config)
+        .build()
+        .await
+        .unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::init(runtime).res().await.unwrap();
+
+    sleep(std::time::Duration::from_secs(1));
+
+    session.put("merge/test/a", "old").res().await.unwrap();
+    session.put("merge/test/b", "stored").res().await.unwrap();
+
+    sleep(std::time::Duration::from_millis(10));
+
+    // The live queryable replies with a value of a more recent than the stored one, and with a
+    // value of b older than the stored one. It only answers the queries forwarded by the storage,
+    // so that the live replies are received through the storage only.
+    let stale =
+        Timestamp::from_str("2022-01-17T10:42:10.418555997Z/BC779A06D7E049BD88C3FF3DB0C17FCC")
+            .unwrap();
+    let _queryable = session
+        .declare_queryable("merge/test/**")
+        .callback(move |query| {
+            if query.attachment().is_none() {
+                return;
+            }
+            let samples = [
+                Sample::new("merge/test/a", "new")
+                    .with_timestamp(zenoh::time::new_reception_timestamp()),
+                Sample::new("merge/test/b", "stale").with_timestamp(stale),
+            ];
+            task::spawn(async move {
+                for sample in samples {
+                    if query.key_expr().intersects(&sample.key_expr) {
+                        query.reply(Ok(sample)).res().await.unwrap();
+                    }
+                }
+            });
+        })
+        .res()
+        .await
+        .unwrap();
+
+    sleep(std::time::Duration::from_millis(10));
+
+    // The live value of a wins over the stored one
+    let data = get_values(&session, "merge/test/a", ConsolidationMode::Latest).await;
+    assert_eq!(data, ["new"]);
+
+    // The stale live value of b is not replied
+    let data = get_values(&session, "merge/test/b", ConsolidationMode::None).await;
+    assert_eq!(data, ["stored"]);
+
+    drop(storage);
+}
+
+#[test]
+fn merge_live_test() {
+    task::block_on(async { test_merge_live().await });
+}
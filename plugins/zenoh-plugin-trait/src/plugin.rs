@@ -51,11 +51,16 @@ impl BitOrAssign for PluginReportLevel {
 
 /// A plugin report contains a severity level and a list of messages
 /// describing the plugin's situation (for the Declared state - dynamic library loading errors, for the Loaded state - plugin start errors, etc)
+/// A running plugin may also report its own metrics (number of requests served, connections, etc).
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Default, Deserialize)]
 pub struct PluginReport {
     level: PluginReportLevel,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     messages: Vec<Cow<'static, str>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_error: Option<Cow<'static, str>>,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    metrics: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Trait allowing getting all information about the plugin
@@ -75,7 +80,9 @@ pub trait PluginStatus {
     fn report(&self) -> PluginReport;
 }
 
-/// The structure which contains all information about the plugin status in a single cloneable structure
+/// The structure which contains all information about the plugin status in a single cloneable structure.
+/// This is the uniform status replied by the admin space for every plugin under
+/// `@/<whatami>/<zid>/plugins/<name>/status`.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PluginStatusRec<'a> {
     pub name: Cow<'a, str>,
@@ -196,8 +203,10 @@ impl PluginReport {
         self.level
     }
     pub fn add_error<S: Into<Cow<'static, str>>>(&mut self, error: S) {
+        let error = error.into();
         self.level |= PluginReportLevel::Error;
-        self.messages.push(error.into());
+        self.last_error = Some(error.clone());
+        self.messages.push(error);
     }
     pub fn add_warning<S: Into<Cow<'static, str>>>(&mut self, warning: S) {
         self.level |= PluginReportLevel::Warning;
@@ -210,6 +219,17 @@ impl PluginReport {
     pub fn messages(&self) -> &[Cow<'static, str>] {
         &self.messages
     }
+    /// Returns the last error reported, if any
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+    /// Sets the value of a plugin specific metric
+    pub fn set_metric<K: Into<String>, V: Into<serde_json::Value>>(&mut self, name: K, value: V) {
+        self.metrics.insert(name.into(), value.into());
+    }
+    pub fn metrics(&self) -> &serde_json::Map<String, serde_json::Value> {
+        &self.metrics
+    }
 }
 
 pub trait PluginConditionSetter {
@@ -21,7 +21,7 @@ use zenoh_ext_examples::CommonArgs;
 #[tokio::main]
 async fn main() {
     // Initiate logging
-    zenoh_util::try_init_log_from_env();
+    zenoh::try_init_log_from_env();
 
     let (config, key_expr, value, history, prefix, complete) = parse_args();
 
@@ -0,0 +1,292 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use crate::{PublicationCache, SessionExt};
+use std::convert::TryInto;
+use std::future::Ready;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh::publication::Publisher;
+use zenoh::sample::Attachment;
+use zenoh::SessionRef;
+use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
+use zenoh_result::ZResult;
+use zenoh_task::TerminatableTask;
+use zenoh_util::core::ResolveFuture;
+
+/// The attachment key of the source of the samples published by an [`AdvancedPublisher`].
+pub const ADVANCED_SOURCE_ATTACHMENT: &str = "adv_source";
+/// The attachment key of the sequence number of the samples published by an [`AdvancedPublisher`],
+/// encoded as a little-endian u64.
+pub const ADVANCED_SN_ATTACHMENT: &str = "adv_sn";
+/// The key space of the heartbeats of the [`AdvancedPublisher`]s: the heartbeats of a publisher on
+/// `<key_expr>` are published on `@adv/heartbeat/<key_expr>`, with the same attachments as its samples.
+pub const ADVANCED_HEARTBEAT_KEY_SPACE: &str = "@adv/heartbeat";
+
+// Distinguishes the advanced publishers of a same session
+static SOURCE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn sequence_attachment(source: &str, sn: u64) -> Attachment {
+    let mut attachment = Attachment::new();
+    attachment.insert(ADVANCED_SOURCE_ATTACHMENT, source);
+    attachment.insert(ADVANCED_SN_ATTACHMENT, &sn.to_le_bytes());
+    attachment
+}
+
+/// Returns the source and the sequence number of a sample published by an [`AdvancedPublisher`].
+pub(crate) fn sequence_info(attachment: Option<&Attachment>) -> Option<(String, u64)> {
+    let attachment = attachment?;
+    let source = attachment.get(&ADVANCED_SOURCE_ATTACHMENT)?;
+    let source = String::from_utf8(source.as_slice().to_vec()).ok()?;
+    let sn = attachment.get(&ADVANCED_SN_ATTACHMENT)?;
+    let sn = u64::from_le_bytes(sn.as_slice().try_into().ok()?);
+    Some((source, sn))
+}
+
+/// The builder of an [`AdvancedPublisher`], allowing to configure it.
+///
+/// An [`AdvancedPublisher`] tags its publications with a sequence number, keeps the last ones in a
+/// [`PublicationCache`], and optionally publishes periodic heartbeats announcing its last sequence number.
+/// This allows the [`AdvancedSubscriber`](crate::AdvancedSubscriber)s to detect the missed samples
+/// and to retrieve them from the cache.
+///
+/// As the heartbeats are published by a background task, the [`AdvancedPublisher`] can only be declared
+/// on a `'static` session (e.g. an `Arc<Session>`). The session must be configured with timestamping enabled.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use std::time::Duration;
+/// use zenoh::prelude::r#async::*;
+/// use zenoh_ext::SessionExt;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+/// let publisher = session
+///     .declare_advanced_publisher("key/expression")
+///     .history(16)
+///     .heartbeat(Duration::from_secs(1))
+///     .res()
+///     .await
+///     .unwrap();
+/// publisher.put("value").res().await.unwrap();
+/// # }
+/// ```
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct AdvancedPublisherBuilder<'a, 'b> {
+    session: SessionRef<'a>,
+    key_expr: ZResult<KeyExpr<'b>>,
+    history: usize,
+    queryable_prefix: Option<ZResult<KeyExpr<'b>>>,
+    heartbeat: Option<Duration>,
+}
+
+impl<'a, 'b> AdvancedPublisherBuilder<'a, 'b> {
+    pub(crate) fn new(
+        session: SessionRef<'a>,
+        key_expr: ZResult<KeyExpr<'b>>,
+    ) -> AdvancedPublisherBuilder<'a, 'b> {
+        AdvancedPublisherBuilder {
+            session,
+            key_expr,
+            history: 1,
+            queryable_prefix: None,
+            heartbeat: None,
+        }
+    }
+
+    /// Change the number of publications kept in the cache (default: 1),
+    /// i.e. the maximum number of consecutive samples a subscriber can recover.
+    #[inline]
+    pub fn history(mut self, history: usize) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Change the prefix of the queryable of the cache.
+    /// See [`PublicationCacheBuilder::queryable_prefix`](crate::PublicationCacheBuilder::queryable_prefix).
+    #[inline]
+    pub fn queryable_prefix<TryIntoKeyExpr>(mut self, queryable_prefix: TryIntoKeyExpr) -> Self
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        self.queryable_prefix = Some(queryable_prefix.try_into().map_err(Into::into));
+        self
+    }
+
+    /// Publish a heartbeat with the last sequence number at the given period (default: none),
+    /// allowing the subscribers to detect the loss of the last samples of a burst.
+    #[inline]
+    pub fn heartbeat(mut self, period: Duration) -> Self {
+        self.heartbeat = Some(period);
+        self
+    }
+}
+
+impl Resolvable for AdvancedPublisherBuilder<'static, '_> {
+    type To = ZResult<AdvancedPublisher>;
+}
+
+impl SyncResolve for AdvancedPublisherBuilder<'static, '_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        AdvancedPublisher::new(self)
+    }
+}
+
+impl AsyncResolve for AdvancedPublisherBuilder<'static, '_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A publisher tagging its publications with sequence numbers, allowing the
+/// [`AdvancedSubscriber`](crate::AdvancedSubscriber)s to detect and recover the missed ones.
+/// See [`AdvancedPublisherBuilder`].
+pub struct AdvancedPublisher {
+    publisher: Publisher<'static>,
+    cache: PublicationCache<'static>,
+    source: Arc<str>,
+    // The sequence number of the next publication
+    sn: Arc<AtomicU64>,
+    heartbeat: Option<TerminatableTask>,
+}
+
+impl AdvancedPublisher {
+    fn new(conf: AdvancedPublisherBuilder<'static, '_>) -> ZResult<AdvancedPublisher> {
+        let key_expr = conf.key_expr?.into_owned();
+        let source: Arc<str> = format!(
+            "{}:{}",
+            conf.session.zid(),
+            SOURCE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        )
+        .into();
+        tracing::debug!(
+            "Create AdvancedPublisher on {} with source={} history={} heartbeat={:?}",
+            key_expr,
+            source,
+            conf.history,
+            conf.heartbeat
+        );
+
+        let mut cache = conf
+            .session
+            .declare_publication_cache(&key_expr)
+            .history(conf.history);
+        if let Some(prefix) = conf.queryable_prefix {
+            cache = cache.queryable_prefix(prefix?);
+        }
+        let cache = cache.res_sync()?;
+        let publisher = conf.session.declare_publisher(&key_expr).res_sync()?;
+        let sn = Arc::new(AtomicU64::new(0));
+
+        let heartbeat = match conf.heartbeat {
+            Some(period) => {
+                let hb_publisher = conf
+                    .session
+                    .declare_publisher(
+                        KeyExpr::try_from(ADVANCED_HEARTBEAT_KEY_SPACE)?.join(&key_expr)?,
+                    )
+                    .res_sync()?;
+                let c_source = source.clone();
+                let c_sn = sn.clone();
+                let token = TerminatableTask::create_cancellation_token();
+                let c_token = token.clone();
+                Some(TerminatableTask::spawn(
+                    zenoh_runtime::ZRuntime::Application,
+                    async move {
+                        let mut interval = tokio::time::interval(period);
+                        loop {
+                            tokio::select! {
+                                _ = interval.tick() => {
+                                    // Nothing to announce before the first publication
+                                    let next = c_sn.load(Ordering::Relaxed);
+                                    if next == 0 {
+                                        continue;
+                                    }
+                                    if let Err(e) = hb_publisher
+                                        .put(Value::empty())
+                                        .with_attachment(sequence_attachment(&c_source, next - 1))
+                                        .res_async()
+                                        .await
+                                    {
+                                        tracing::warn!("Error publishing heartbeat: {}", e);
+                                    }
+                                },
+                                _ = c_token.cancelled() => return
+                            }
+                        }
+                    },
+                    token,
+                ))
+            }
+            None => None,
+        };
+
+        Ok(AdvancedPublisher {
+            publisher,
+            cache,
+            source,
+            sn,
+            heartbeat,
+        })
+    }
+
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        self.publisher.key_expr()
+    }
+
+    /// Put data, tagged with the next sequence number.
+    #[inline]
+    pub fn put<IntoValue>(&self, value: IntoValue) -> impl Resolve<ZResult<()>> + '_
+    where
+        IntoValue: Into<Value>,
+    {
+        let sn = self.sn.fetch_add(1, Ordering::Relaxed);
+        self.publisher
+            .put(value)
+            .with_attachment(sequence_attachment(&self.source, sn))
+    }
+
+    /// Delete data, tagged with the next sequence number.
+    #[inline]
+    pub fn delete(&self) -> impl Resolve<ZResult<()>> + '_ {
+        let sn = self.sn.fetch_add(1, Ordering::Relaxed);
+        self.publisher
+            .delete()
+            .with_attachment(sequence_attachment(&self.source, sn))
+    }
+
+    /// Close this AdvancedPublisher, its cache and its heartbeat.
+    #[inline]
+    pub fn close(self) -> impl Resolve<ZResult<()>> {
+        ResolveFuture::new(async move {
+            let AdvancedPublisher {
+                publisher,
+                cache,
+                heartbeat,
+                ..
+            } = self;
+            if let Some(heartbeat) = heartbeat {
+                heartbeat.terminate(Duration::from_secs(10));
+            }
+            publisher.undeclare().res_async().await?;
+            cache.close().res_async().await?;
+            Ok(())
+        })
+    }
+}
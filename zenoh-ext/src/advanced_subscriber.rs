@@ -0,0 +1,561 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use crate::advanced_publisher::{sequence_info, ADVANCED_HEARTBEAT_KEY_SPACE};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::convert::TryInto;
+use std::future::Ready;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use zenoh::handlers::{locked, DefaultHandler};
+use zenoh::prelude::r#async::*;
+use zenoh::query::{ConsolidationMode, QueryTarget, ReplyKeyExpr};
+use zenoh::subscriber::Subscriber;
+use zenoh::SessionRef;
+use zenoh_core::{zlock, AsyncResolve, Resolvable, SyncResolve};
+use zenoh_result::ZResult;
+use zenoh_util::core::ResolveFuture;
+
+/// A notification of samples missed by an [`AdvancedSubscriber`]: the samples that could
+/// not be recovered from the cache of their [`AdvancedPublisher`](crate::AdvancedPublisher).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Miss {
+    /// The source of the missed samples.
+    pub source: String,
+    /// The number of missed samples.
+    pub nb: u64,
+}
+
+/// The builder of an [`AdvancedSubscriber`], allowing to configure it.
+///
+/// An [`AdvancedSubscriber`] delivers the samples of each [`AdvancedPublisher`](crate::AdvancedPublisher)
+/// in their publication order. When it detects a gap in the sequence numbers of a source, either from the
+/// samples or from the heartbeats of the source, it holds the following samples and queries the cache of the
+/// source for the missing ones. The samples that could not be recovered are reported to the
+/// [`sample_miss_listener`](AdvancedSubscriber::sample_miss_listener)s before delivering the held ones.
+///
+/// The samples not published by an [`AdvancedPublisher`](crate::AdvancedPublisher) are delivered as is.
+///
+/// As the missing samples are queried from the subscription callback, the [`AdvancedSubscriber`]
+/// can only be declared on a `'static` session (e.g. an `Arc<Session>`).
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+/// use zenoh_ext::SessionExt;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+/// let subscriber = session
+///     .declare_advanced_subscriber("key/expression")
+///     .res()
+///     .await
+///     .unwrap();
+/// subscriber.sample_miss_listener(|miss| println!("Missed {} samples from {}", miss.nb, miss.source));
+/// while let Ok(sample) = subscriber.recv_async().await {
+///     println!("Received: {:?}", sample);
+/// }
+/// # }
+/// ```
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct AdvancedSubscriberBuilder<'a, 'b, Handler> {
+    session: SessionRef<'a>,
+    key_expr: ZResult<KeyExpr<'b>>,
+    query_prefix: Option<ZResult<KeyExpr<'b>>>,
+    query_timeout: Duration,
+    heartbeats: bool,
+    handler: Handler,
+}
+
+impl<'a, 'b> AdvancedSubscriberBuilder<'a, 'b, DefaultHandler> {
+    pub(crate) fn new(
+        session: SessionRef<'a>,
+        key_expr: ZResult<KeyExpr<'b>>,
+    ) -> AdvancedSubscriberBuilder<'a, 'b, DefaultHandler> {
+        AdvancedSubscriberBuilder {
+            session,
+            key_expr,
+            query_prefix: None,
+            query_timeout: Duration::from_secs(10),
+            heartbeats: true,
+            handler: DefaultHandler,
+        }
+    }
+
+    /// Add callback to [`AdvancedSubscriber`].
+    #[inline]
+    pub fn callback<Callback>(
+        self,
+        callback: Callback,
+    ) -> AdvancedSubscriberBuilder<'a, 'b, Callback>
+    where
+        Callback: Fn(Sample) + Send + Sync + 'static,
+    {
+        self.with(callback)
+    }
+
+    /// Add callback to [`AdvancedSubscriber`].
+    ///
+    /// Using this guarantees that your callback will never be called concurrently.
+    /// If your callback is also accepted by the [`callback`](AdvancedSubscriberBuilder::callback)
+    /// method, we suggest you use it instead of `callback_mut`
+    #[inline]
+    pub fn callback_mut<CallbackMut>(
+        self,
+        callback: CallbackMut,
+    ) -> AdvancedSubscriberBuilder<'a, 'b, impl Fn(Sample) + Send + Sync + 'static>
+    where
+        CallbackMut: FnMut(Sample) + Send + Sync + 'static,
+    {
+        self.callback(locked(callback))
+    }
+
+    /// Use the given handler to receive Samples.
+    #[inline]
+    pub fn with<Handler>(self, handler: Handler) -> AdvancedSubscriberBuilder<'a, 'b, Handler>
+    where
+        Handler: IntoCallbackReceiverPair<'static, Sample>,
+    {
+        let AdvancedSubscriberBuilder {
+            session,
+            key_expr,
+            query_prefix,
+            query_timeout,
+            heartbeats,
+            handler: _,
+        } = self;
+        AdvancedSubscriberBuilder {
+            session,
+            key_expr,
+            query_prefix,
+            query_timeout,
+            heartbeats,
+            handler,
+        }
+    }
+}
+
+impl<'a, 'b, Handler> AdvancedSubscriberBuilder<'a, 'b, Handler> {
+    /// Query the missing samples on `<query_prefix>/<key_expr>`, i.e. from the
+    /// [`AdvancedPublisher`](crate::AdvancedPublisher)s declared with the same
+    /// [`queryable_prefix`](crate::AdvancedPublisherBuilder::queryable_prefix).
+    #[inline]
+    pub fn query_prefix<TryIntoKeyExpr>(mut self, query_prefix: TryIntoKeyExpr) -> Self
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        self.query_prefix = Some(query_prefix.try_into().map_err(Into::into));
+        self
+    }
+
+    /// Change the timeout of the queries for the missing samples (default: 10s).
+    /// The held samples of a source are not delivered before the end of its query.
+    #[inline]
+    pub fn query_timeout(mut self, query_timeout: Duration) -> Self {
+        self.query_timeout = query_timeout;
+        self
+    }
+
+    /// Whether to subscribe to the heartbeats of the publishers (default: true).
+    #[inline]
+    pub fn heartbeats(mut self, heartbeats: bool) -> Self {
+        self.heartbeats = heartbeats;
+        self
+    }
+}
+
+impl<Handler> Resolvable for AdvancedSubscriberBuilder<'static, '_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample>,
+    Handler::Receiver: Send,
+{
+    type To = ZResult<AdvancedSubscriber<Handler::Receiver>>;
+}
+
+impl<Handler> SyncResolve for AdvancedSubscriberBuilder<'static, '_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+{
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        AdvancedSubscriber::new(self)
+    }
+}
+
+impl<Handler> AsyncResolve for AdvancedSubscriberBuilder<'static, '_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+{
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+type MissCallback = Arc<dyn Fn(Miss) + Send + Sync>;
+
+struct SourceState {
+    // The key expression the source publishes on
+    key_expr: OwnedKeyExpr,
+    // The sequence number of the last delivered sample
+    last: u64,
+    // The highest sequence number known to be published
+    latest: u64,
+    // The samples received after a gap, by sequence number
+    pending: BTreeMap<u64, Sample>,
+    recovering: bool,
+}
+
+// A notification to deliver to the user callbacks
+enum Notification {
+    Sample(Sample),
+    Miss(Miss),
+}
+
+struct State {
+    sources: HashMap<String, SourceState>,
+    callback: Arc<dyn Fn(Sample) + Send + Sync>,
+    miss_callbacks: Vec<MissCallback>,
+    // The notifications not delivered yet, in order
+    notifications: VecDeque<Notification>,
+    // Whether a thread is delivering the notifications
+    delivering: bool,
+}
+
+impl State {
+    // Deliver the pending samples following the last delivered one
+    fn flush(&mut self, source: &str) {
+        let Some(src) = self.sources.get_mut(source) else {
+            return;
+        };
+        while let Some(sample) = src.pending.remove(&(src.last + 1)) {
+            src.last += 1;
+            self.notifications.push_back(Notification::Sample(sample));
+        }
+    }
+
+    // Give up on the samples missing before the pending ones (or before the latest sequence number),
+    // and deliver the pending ones
+    fn skip(&mut self, source: &str) {
+        loop {
+            self.flush(source);
+            let Some(src) = self.sources.get_mut(source) else {
+                return;
+            };
+            let target = match src.pending.keys().next() {
+                Some(sn) => sn - 1,
+                None => src.latest,
+            };
+            if target <= src.last {
+                return;
+            }
+            let miss = Miss {
+                source: source.to_string(),
+                nb: target - src.last,
+            };
+            tracing::debug!("Missed {} samples from {}", miss.nb, miss.source);
+            src.last = target;
+            self.notifications.push_back(Notification::Miss(miss));
+        }
+    }
+}
+
+struct Inner {
+    session: SessionRef<'static>,
+    query_prefix: Option<KeyExpr<'static>>,
+    query_timeout: Duration,
+    state: Mutex<State>,
+}
+
+impl Inner {
+    // Deliver the notifications of the state to the user callbacks, without holding the lock so
+    // that the callbacks may use the subscriber. A single thread delivers at a time to keep the order.
+    fn deliver(&self, mut state: MutexGuard<'_, State>) {
+        if std::mem::replace(&mut state.delivering, true) {
+            return;
+        }
+        while let Some(notification) = state.notifications.pop_front() {
+            match notification {
+                Notification::Sample(sample) => {
+                    let callback = state.callback.clone();
+                    drop(state);
+                    callback(sample);
+                }
+                Notification::Miss(miss) => {
+                    let miss_callbacks = state.miss_callbacks.clone();
+                    drop(state);
+                    for callback in miss_callbacks.iter() {
+                        callback(miss.clone());
+                    }
+                }
+            }
+            state = zlock!(self.state);
+        }
+        state.delivering = false;
+    }
+
+    fn on_sample(self: &Arc<Self>, sample: Sample) {
+        let mut state = zlock!(self.state);
+        let Some((source, sn)) = sequence_info(sample.attachment()) else {
+            state.notifications.push_back(Notification::Sample(sample));
+            self.deliver(state);
+            return;
+        };
+        let Some(src) = state.sources.get_mut(&source) else {
+            // The first sample of a source is the start of its sequence
+            state.sources.insert(
+                source,
+                SourceState {
+                    key_expr: sample.key_expr.clone().into(),
+                    last: sn,
+                    latest: sn,
+                    pending: BTreeMap::new(),
+                    recovering: false,
+                },
+            );
+            state.notifications.push_back(Notification::Sample(sample));
+            self.deliver(state);
+            return;
+        };
+        if sn <= src.last || src.pending.contains_key(&sn) {
+            tracing::trace!("Drop duplicate sample {} from {}", sn, source);
+            return;
+        }
+        src.latest = src.latest.max(sn);
+        if sn == src.last + 1 {
+            src.last = sn;
+            state.notifications.push_back(Notification::Sample(sample));
+            state.flush(&source);
+            self.deliver(state);
+            return;
+        }
+        src.pending.insert(sn, sample);
+        let recover = !std::mem::replace(&mut src.recovering, true);
+        drop(state);
+        if recover {
+            self.recover(source);
+        }
+    }
+
+    fn on_heartbeat(self: &Arc<Self>, heartbeat: Sample) {
+        let Some((source, sn)) = sequence_info(heartbeat.attachment()) else {
+            return;
+        };
+        let mut state = zlock!(self.state);
+        let Some(src) = state.sources.get_mut(&source) else {
+            // The samples published before the subscription are not recovered
+            let Some(key_expr) = heartbeat
+                .key_expr
+                .as_str()
+                .strip_prefix(ADVANCED_HEARTBEAT_KEY_SPACE)
+                .and_then(|ke| ke.strip_prefix('/'))
+                .and_then(|ke| OwnedKeyExpr::try_from(ke).ok())
+            else {
+                return;
+            };
+            state.sources.insert(
+                source,
+                SourceState {
+                    key_expr,
+                    last: sn,
+                    latest: sn,
+                    pending: BTreeMap::new(),
+                    recovering: false,
+                },
+            );
+            return;
+        };
+        if sn <= src.latest {
+            return;
+        }
+        src.latest = sn;
+        let recover = !std::mem::replace(&mut src.recovering, true);
+        drop(state);
+        if recover {
+            self.recover(source);
+        }
+    }
+
+    // Query the cache of the source for its missing samples
+    fn recover(self: &Arc<Self>, source: String) {
+        let key_expr = match zlock!(self.state).sources.get(&source) {
+            Some(src) => KeyExpr::from(src.key_expr.clone()),
+            None => return,
+        };
+        let selector = match &self.query_prefix {
+            Some(prefix) => prefix.join(&key_expr),
+            None => Ok(key_expr),
+        };
+        tracing::debug!(
+            "Recover the samples missed from {} on {:?}",
+            source,
+            selector
+        );
+        let recovery = Recovery {
+            inner: self.clone(),
+            source,
+        };
+        // The caches reply with the key expressions of the samples, i.e. without the query prefix
+        let accept_replies = match &self.query_prefix {
+            Some(_) => ReplyKeyExpr::Any,
+            None => ReplyKeyExpr::MatchingQuery,
+        };
+        let res = selector.and_then(|selector| {
+            self.session
+                .get(selector)
+                .callback(move |reply| {
+                    if let Ok(sample) = reply.sample {
+                        recovery.inner.on_sample(sample);
+                    }
+                })
+                .accept_replies(accept_replies)
+                .target(QueryTarget::All)
+                .consolidation(ConsolidationMode::None)
+                .timeout(self.query_timeout)
+                .res_sync()
+        });
+        if let Err(e) = res {
+            tracing::warn!("Error querying missed samples: {}", e);
+        }
+    }
+}
+
+// Ends the recovery of a source when the query callback is dropped, i.e. once the query is complete
+struct Recovery {
+    inner: Arc<Inner>,
+    source: String,
+}
+
+impl Drop for Recovery {
+    fn drop(&mut self) {
+        let mut state = zlock!(self.inner.state);
+        if let Some(src) = state.sources.get_mut(&self.source) {
+            src.recovering = false;
+        }
+        state.skip(&self.source);
+        self.inner.deliver(state);
+    }
+}
+
+/// A subscriber detecting and recovering the samples missed from [`AdvancedPublisher`](crate::AdvancedPublisher)s.
+/// See [`AdvancedSubscriberBuilder`].
+pub struct AdvancedSubscriber<Receiver> {
+    subscriber: Subscriber<'static, ()>,
+    heartbeat_subscriber: Option<Subscriber<'static, ()>>,
+    inner: Arc<Inner>,
+    receiver: Receiver,
+}
+
+impl<Receiver> std::ops::Deref for AdvancedSubscriber<Receiver> {
+    type Target = Receiver;
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+impl<Receiver> std::ops::DerefMut for AdvancedSubscriber<Receiver> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.receiver
+    }
+}
+
+impl<Receiver> AdvancedSubscriber<Receiver> {
+    fn new<Handler>(
+        conf: AdvancedSubscriberBuilder<'static, '_, Handler>,
+    ) -> ZResult<AdvancedSubscriber<Receiver>>
+    where
+        Handler: IntoCallbackReceiverPair<'static, Sample, Receiver = Receiver> + Send,
+    {
+        let key_expr = conf.key_expr?.into_owned();
+        let query_prefix = conf
+            .query_prefix
+            .transpose()?
+            .map(|prefix| prefix.into_owned());
+        let (callback, receiver) = conf.handler.into_cb_receiver_pair();
+        let inner = Arc::new(Inner {
+            session: conf.session.clone(),
+            query_prefix,
+            query_timeout: conf.query_timeout,
+            state: Mutex::new(State {
+                sources: HashMap::new(),
+                callback,
+                miss_callbacks: Vec::new(),
+                notifications: VecDeque::new(),
+                delivering: false,
+            }),
+        });
+
+        let c_inner = inner.clone();
+        let subscriber = conf
+            .session
+            .declare_subscriber(&key_expr)
+            .callback(move |sample| c_inner.on_sample(sample))
+            .res_sync()?;
+        let heartbeat_subscriber = if conf.heartbeats {
+            let c_inner = inner.clone();
+            Some(
+                conf.session
+                    .declare_subscriber(
+                        KeyExpr::try_from(ADVANCED_HEARTBEAT_KEY_SPACE)?.join(&key_expr)?,
+                    )
+                    .callback(move |heartbeat| c_inner.on_heartbeat(heartbeat))
+                    .res_sync()?,
+            )
+        } else {
+            None
+        };
+
+        Ok(AdvancedSubscriber {
+            subscriber,
+            heartbeat_subscriber,
+            inner,
+            receiver,
+        })
+    }
+
+    /// Return the key expression of this AdvancedSubscriber
+    #[inline]
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        self.subscriber.key_expr()
+    }
+
+    /// Register a callback notified of the samples that could not be recovered.
+    pub fn sample_miss_listener<F>(&self, callback: F)
+    where
+        F: Fn(Miss) + Send + Sync + 'static,
+    {
+        zlock!(self.inner.state)
+            .miss_callbacks
+            .push(Arc::new(callback));
+    }
+
+    /// Close this AdvancedSubscriber
+    #[inline]
+    pub fn close(self) -> impl Resolve<ZResult<()>> {
+        ResolveFuture::new(async move {
+            let AdvancedSubscriber {
+                subscriber,
+                heartbeat_subscriber,
+                ..
+            } = self;
+            if let Some(heartbeat_subscriber) = heartbeat_subscriber {
+                heartbeat_subscriber.undeclare().res_async().await?;
+            }
+            subscriber.undeclare().res_async().await
+        })
+    }
+}
@@ -0,0 +1,305 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use futures::future::join_all;
+use std::convert::TryInto;
+use std::future::Ready;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use zenoh::prelude::r#async::*;
+use zenoh::queryable::{Query, Queryable};
+use zenoh::SessionRef;
+use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
+use zenoh_result::ZResult;
+use zenoh_task::TaskController;
+use zenoh_util::core::ResolveFuture;
+
+type AggregateFn = dyn Fn(&Query, Vec<Sample>) -> Vec<Sample> + Send + Sync;
+
+/// The builder of an [`Aggregator`], allowing to configure it.
+///
+/// An [`Aggregator`] is a queryable answering each of its queries by querying its sources
+/// with the same parameters and value, and replying with the aggregation of the collected samples.
+/// The sources are queried concurrently, on the remote queryables only so that an [`Aggregator`]
+/// whose key expression intersects one of its sources does not query itself. The error replies of
+/// the sources are forwarded as is. A source whose query completes before the timeout answered,
+/// even without any sample.
+///
+/// By default, the collected samples are replied as is: the [`aggregate`](AggregatorBuilder::aggregate)
+/// function must be used to re-key the samples whose key expressions don't intersect with the
+/// queries' ones, as those are not accepted by the queriers.
+///
+/// As the queries are processed in background tasks, the [`Aggregator`] can only be declared
+/// on a `'static` session (e.g. an `Arc<Session>`).
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use std::time::Duration;
+/// use zenoh::prelude::r#async::*;
+/// use zenoh_ext::SessionExt;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+/// let aggregator = session
+///     .declare_aggregator("building/**")
+///     .source("building/floor1/**")
+///     .source("building/floor2/**")
+///     .timeout(Duration::from_secs(1))
+///     .res()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct AggregatorBuilder<'a, 'b> {
+    session: SessionRef<'a>,
+    key_expr: ZResult<KeyExpr<'b>>,
+    sources: Vec<ZResult<KeyExpr<'b>>>,
+    timeout: Option<Duration>,
+    allow_partial: bool,
+    aggregate: Option<Arc<AggregateFn>>,
+}
+
+impl<'a, 'b> AggregatorBuilder<'a, 'b> {
+    pub(crate) fn new(
+        session: SessionRef<'a>,
+        key_expr: ZResult<KeyExpr<'b>>,
+    ) -> AggregatorBuilder<'a, 'b> {
+        AggregatorBuilder {
+            session,
+            key_expr,
+            sources: Vec::new(),
+            timeout: None,
+            allow_partial: true,
+            aggregate: None,
+        }
+    }
+
+    /// Add a key expression to query when answering a query.
+    #[inline]
+    pub fn source<TryIntoKeyExpr>(mut self, key_expr: TryIntoKeyExpr) -> Self
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        self.sources.push(key_expr.try_into().map_err(Into::into));
+        self
+    }
+
+    /// Set the timeout of the queries to the sources.
+    /// By default, the session's `queries_default_timeout` is used.
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Whether to reply with the samples of the sources that answered when the queries to some
+    /// sources timed out (default: true). When false, an error is replied instead.
+    #[inline]
+    pub fn allow_partial(mut self, allow_partial: bool) -> Self {
+        self.allow_partial = allow_partial;
+        self
+    }
+
+    /// Set the function computing the replies to a query from the samples collected from the sources.
+    #[inline]
+    pub fn aggregate<F>(mut self, aggregate: F) -> Self
+    where
+        F: Fn(&Query, Vec<Sample>) -> Vec<Sample> + Send + Sync + 'static,
+    {
+        self.aggregate = Some(Arc::new(aggregate));
+        self
+    }
+}
+
+impl Resolvable for AggregatorBuilder<'static, '_> {
+    type To = ZResult<Aggregator>;
+}
+
+impl SyncResolve for AggregatorBuilder<'static, '_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        Aggregator::new(self)
+    }
+}
+
+impl AsyncResolve for AggregatorBuilder<'static, '_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+// The replies of a source to a query
+struct SourceReplies {
+    samples: Vec<Sample>,
+    errors: Vec<Value>,
+    // Whether the query completed before the timeout, possibly without any reply
+    answered: bool,
+}
+
+struct AggregatorState {
+    session: SessionRef<'static>,
+    sources: Vec<KeyExpr<'static>>,
+    timeout: Duration,
+    allow_partial: bool,
+    aggregate: Option<Arc<AggregateFn>>,
+}
+
+impl AggregatorState {
+    // Query a source, returning its samples and error replies
+    async fn query(&self, source: &KeyExpr<'static>, query: &Query) -> SourceReplies {
+        let mut get = self
+            .session
+            .get(Selector::from(source).with_parameters(query.parameters()))
+            .allowed_destination(Locality::Remote)
+            .timeout(self.timeout);
+        if let Some(value) = query.value() {
+            get = get.with_value(value.clone());
+        }
+        let mut replies = SourceReplies {
+            samples: Vec::new(),
+            errors: Vec::new(),
+            answered: false,
+        };
+        let start = Instant::now();
+        match get.with(flume::unbounded()).res_async().await {
+            Ok(receiver) => {
+                while let Ok(reply) = receiver.recv_async().await {
+                    match reply.sample {
+                        Ok(sample) => replies.samples.push(sample),
+                        Err(value) => replies.errors.push(value),
+                    }
+                }
+                // The replies are closed early once all the queryables sent their final reply
+                replies.answered = start.elapsed() < self.timeout;
+            }
+            Err(e) => tracing::warn!("Aggregator failed to query {}: {}", source, e),
+        }
+        replies
+    }
+
+    async fn handle(&self, query: Query) {
+        let results = join_all(self.sources.iter().map(|s| self.query(s, &query))).await;
+
+        let unanswered: Vec<&KeyExpr> = self
+            .sources
+            .iter()
+            .zip(results.iter())
+            .filter_map(|(source, replies)| (!replies.answered).then_some(source))
+            .collect();
+        if !self.allow_partial && !unanswered.is_empty() {
+            let e = format!("Timeout querying sources {:?}", unanswered);
+            tracing::debug!("Aggregator on {}: {}", query.key_expr(), e);
+            if let Err(e) = query.reply(Err(e.into())).res_async().await {
+                tracing::warn!("Error replying to query: {}", e);
+            }
+            return;
+        }
+
+        let mut samples = Vec::new();
+        for replies in results {
+            samples.extend(replies.samples);
+            for value in replies.errors {
+                if let Err(e) = query.reply(Err(value)).res_async().await {
+                    tracing::warn!("Error replying to query: {}", e);
+                }
+            }
+        }
+        if let Some(aggregate) = self.aggregate.as_ref() {
+            samples = aggregate(&query, samples);
+        }
+        for sample in samples {
+            if let Err(e) = query.reply(Ok(sample)).res_async().await {
+                tracing::warn!("Error replying to query: {}", e);
+            }
+        }
+    }
+}
+
+/// A queryable answering its queries with the aggregated replies of other key expressions.
+/// See [`AggregatorBuilder`].
+pub struct Aggregator {
+    key_expr: KeyExpr<'static>,
+    queryable: Queryable<'static, ()>,
+    task_controller: TaskController,
+}
+
+impl Aggregator {
+    fn new(conf: AggregatorBuilder<'static, '_>) -> ZResult<Aggregator> {
+        let key_expr = conf.key_expr?.into_owned();
+        let sources = conf
+            .sources
+            .into_iter()
+            .map(|s| s.map(KeyExpr::into_owned))
+            .collect::<ZResult<Vec<_>>>()?;
+        tracing::debug!("Create Aggregator on {} from {:?}", key_expr, sources);
+
+        let timeout = conf.timeout.unwrap_or_else(|| {
+            let config = conf.session.config().lock();
+            Duration::from_millis(
+                config
+                    .queries_default_timeout()
+                    .unwrap_or(zenoh::config::defaults::queries_default_timeout),
+            )
+        });
+        let state = Arc::new(AggregatorState {
+            session: conf.session.clone(),
+            sources,
+            timeout,
+            allow_partial: conf.allow_partial,
+            aggregate: conf.aggregate,
+        });
+        let task_controller = TaskController::default();
+        let tasks = task_controller.clone();
+        let queryable = conf
+            .session
+            .declare_queryable(&key_expr)
+            .callback(move |query| {
+                let state = state.clone();
+                tasks.spawn_abortable_with_rt(zenoh_runtime::ZRuntime::Application, async move {
+                    state.handle(query).await
+                });
+            })
+            .res_sync()?;
+
+        Ok(Aggregator {
+            key_expr,
+            queryable,
+            task_controller,
+        })
+    }
+
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        &self.key_expr
+    }
+
+    /// Close this Aggregator, aborting the queries being processed.
+    #[inline]
+    pub fn close(self) -> impl Resolve<ZResult<()>> {
+        ResolveFuture::new(async move {
+            let Aggregator {
+                queryable,
+                task_controller,
+                ..
+            } = self;
+            queryable.undeclare().res_async().await?;
+            task_controller
+                .terminate_all_async(Duration::from_secs(10))
+                .await;
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,293 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::hash_map::DefaultHasher;
+use std::future::Ready;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use zenoh::prelude::r#async::*;
+use zenoh::publication::Publisher;
+use zenoh::SessionRef;
+use zenoh_core::{zlock, AsyncResolve, Resolvable, ResolveClosure, SyncResolve};
+use zenoh_result::ZResult;
+use zenoh_task::TerminatableTask;
+use zenoh_util::core::ResolveFuture;
+
+/// The builder of a [`ChangePublisher`], allowing to configure it.
+///
+/// A [`ChangePublisher`] only publishes the values differing from the last one it published,
+/// e.g. to publish a slowly-changing state on a timer without wasting bandwidth.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use std::time::Duration;
+/// use zenoh::prelude::r#async::*;
+/// use zenoh_ext::SessionExt;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+/// let publisher = session
+///     .declare_change_publisher("key/expression")
+///     .max_silence(Duration::from_secs(10))
+///     .res()
+///     .await
+///     .unwrap();
+/// loop {
+///     // Only published when changed, or after 10s of silence
+///     publisher.put("state").res().await.unwrap();
+///     tokio::time::sleep(Duration::from_secs(1)).await;
+/// }
+/// # }
+/// ```
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct ChangePublisherBuilder<'a, 'b> {
+    session: SessionRef<'a>,
+    key_expr: ZResult<KeyExpr<'b>>,
+    congestion_control: CongestionControl,
+    priority: Priority,
+    max_silence: Option<(Duration, SessionRef<'static>)>,
+}
+
+impl<'a, 'b> ChangePublisherBuilder<'a, 'b> {
+    pub(crate) fn new(
+        session: SessionRef<'a>,
+        key_expr: ZResult<KeyExpr<'b>>,
+    ) -> ChangePublisherBuilder<'a, 'b> {
+        ChangePublisherBuilder {
+            session,
+            key_expr,
+            congestion_control: CongestionControl::default(),
+            priority: Priority::default(),
+            max_silence: None,
+        }
+    }
+
+    /// Change the `congestion_control` to apply when routing the data.
+    #[inline]
+    pub fn congestion_control(mut self, congestion_control: CongestionControl) -> Self {
+        self.congestion_control = congestion_control;
+        self
+    }
+
+    /// Change the priority of the written data.
+    #[inline]
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+impl<'b> ChangePublisherBuilder<'static, 'b> {
+    /// Publish the last value again when nothing has been published for the given time (default: never),
+    /// so that the late joiners and the subscribers that lost it eventually receive it.
+    ///
+    /// As the value is republished by a background task, this is only available on a `'static`
+    /// session (e.g. an `Arc<Session>`).
+    pub fn max_silence(mut self, max_silence: Duration) -> Self {
+        self.max_silence = Some((max_silence, self.session.clone()));
+        self
+    }
+}
+
+impl<'a> Resolvable for ChangePublisherBuilder<'a, '_> {
+    type To = ZResult<ChangePublisher<'a>>;
+}
+
+impl<'a> SyncResolve for ChangePublisherBuilder<'a, '_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        ChangePublisher::new(self)
+    }
+}
+
+impl<'a> AsyncResolve for ChangePublisherBuilder<'a, '_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+fn value_hash(value: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.encoding.to_string().hash(&mut hasher);
+    value.payload.contiguous().hash(&mut hasher);
+    hasher.finish()
+}
+
+// The hashes only tell apart the values quickly: the values with the same hash are compared
+fn same_value(a: &Value, b: &Value) -> bool {
+    a.encoding == b.encoding && a.payload.contiguous() == b.payload.contiguous()
+}
+
+// The last published value
+#[derive(Default)]
+struct LastPublication {
+    hash: Option<u64>,
+    value: Option<Value>,
+    time: Option<Instant>,
+    // Incremented on every change, to tell whether a failed publication is still the last one
+    version: u64,
+}
+
+/// A publisher suppressing the publications of a value unchanged since the last one it published.
+/// See [`ChangePublisherBuilder`].
+pub struct ChangePublisher<'a> {
+    publisher: Publisher<'a>,
+    last: Arc<Mutex<LastPublication>>,
+    task: Option<TerminatableTask>,
+}
+
+impl<'a> ChangePublisher<'a> {
+    fn new(conf: ChangePublisherBuilder<'a, '_>) -> ZResult<ChangePublisher<'a>> {
+        let key_expr = conf.key_expr?.into_owned();
+        tracing::debug!(
+            "Create ChangePublisher on {} with max_silence={:?}",
+            key_expr,
+            conf.max_silence.as_ref().map(|(period, _)| period)
+        );
+        let publisher = conf
+            .session
+            .declare_publisher(key_expr.clone())
+            .congestion_control(conf.congestion_control)
+            .priority(conf.priority)
+            .res_sync()?;
+        let last = Arc::new(Mutex::new(LastPublication::default()));
+
+        let task = conf.max_silence.map(|(max_silence, session)| {
+            let c_last = last.clone();
+            let (congestion_control, priority) = (conf.congestion_control, conf.priority);
+            let token = TerminatableTask::create_cancellation_token();
+            let c_token = token.clone();
+            TerminatableTask::spawn(
+                zenoh_runtime::ZRuntime::Application,
+                async move {
+                    let period = (max_silence / 2).max(Duration::from_millis(1));
+                    let mut interval = tokio::time::interval(period);
+                    loop {
+                        tokio::select! {
+                            _ = interval.tick() => {
+                                let value = {
+                                    let mut last = zlock!(c_last);
+                                    match (&last.value, last.time) {
+                                        (Some(value), Some(time)) if time.elapsed() >= max_silence => {
+                                            let value = value.clone();
+                                            last.time = Some(Instant::now());
+                                            value
+                                        }
+                                        _ => continue,
+                                    }
+                                };
+                                if let Err(e) = session
+                                    .put(&key_expr, value)
+                                    .congestion_control(congestion_control)
+                                    .priority(priority)
+                                    .res_async()
+                                    .await
+                                {
+                                    tracing::warn!("Error republishing on {}: {}", key_expr, e);
+                                }
+                            },
+                            _ = c_token.cancelled() => return
+                        }
+                    }
+                },
+                token,
+            )
+        });
+
+        Ok(ChangePublisher {
+            publisher,
+            last,
+            task,
+        })
+    }
+
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        self.publisher.key_expr()
+    }
+
+    /// Put data, unless it is equal to the last published value.
+    /// Resolves to `true` if the data has been published.
+    #[inline]
+    pub fn put<IntoValue>(&self, value: IntoValue) -> impl Resolve<ZResult<bool>> + '_
+    where
+        IntoValue: Into<Value>,
+    {
+        let value: Value = value.into();
+        ResolveClosure::new(move || {
+            let hash = value_hash(&value);
+            // The value is recorded before being published so that the lock is not held while
+            // publishing: the values put concurrently are published in an unspecified order
+            let version = {
+                let mut last = zlock!(self.last);
+                if last.hash == Some(hash)
+                    && last.value.as_ref().is_some_and(|v| same_value(v, &value))
+                {
+                    return Ok(false);
+                }
+                let version = last.version.wrapping_add(1);
+                *last = LastPublication {
+                    hash: Some(hash),
+                    value: Some(value.clone()),
+                    time: Some(Instant::now()),
+                    version,
+                };
+                version
+            };
+            if let Err(e) = self.publisher.put(value).res_sync() {
+                // The next put must be published if this one was not
+                let mut last = zlock!(self.last);
+                if last.version == version {
+                    *last = LastPublication {
+                        version,
+                        ..LastPublication::default()
+                    };
+                }
+                return Err(e);
+            }
+            Ok(true)
+        })
+    }
+
+    /// Delete data. The next put is always published.
+    #[inline]
+    pub fn delete(&self) -> impl Resolve<ZResult<()>> + '_ {
+        ResolveClosure::new(move || {
+            {
+                let mut last = zlock!(self.last);
+                *last = LastPublication {
+                    version: last.version.wrapping_add(1),
+                    ..LastPublication::default()
+                };
+            }
+            self.publisher.delete().res_sync()
+        })
+    }
+
+    /// Close this ChangePublisher.
+    #[inline]
+    pub fn close(self) -> impl Resolve<ZResult<()>> + 'a {
+        ResolveFuture::new(async move {
+            let ChangePublisher {
+                publisher, task, ..
+            } = self;
+            if let Some(task) = task {
+                task.terminate(Duration::from_secs(10));
+            }
+            publisher.undeclare().res_async().await?;
+            Ok(())
+        })
+    }
+}
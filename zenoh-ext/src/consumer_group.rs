@@ -0,0 +1,401 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::BTreeSet;
+use std::convert::TryInto;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use zenoh::handlers::{locked, DefaultHandler};
+use zenoh::key_expr::keyexpr;
+use zenoh::liveliness::LivelinessToken;
+use zenoh::prelude::r#async::*;
+use zenoh::subscriber::Subscriber;
+use zenoh::SessionRef;
+use zenoh_core::{zread, zwrite, AsyncResolve, Resolvable, SyncResolve};
+use zenoh_result::{bail, ZResult};
+use zenoh_util::core::ResolveFuture;
+
+/// The key space of the liveliness tokens of the members of the consumer groups:
+/// each member of a group declares a token on `@cgroup/<group>/<member>`.
+pub const CONSUMER_GROUP_KEY_SPACE: &str = "@cgroup";
+
+// Distinguishes the members of a same session
+static MEMBER_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+// FNV-1a, so that all the members compute the same hashes whatever their platform or toolchain
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+// Identify a sample identically on all the members: by its key expression and its timestamp
+// when it has one, by its key expression and its payload otherwise
+fn sample_hash(sample: &Sample) -> u64 {
+    let hash = fnv1a(0xcbf2_9ce4_8422_2325, sample.key_expr.as_str().as_bytes());
+    match &sample.timestamp {
+        Some(timestamp) => fnv1a(hash, timestamp.to_string().as_bytes()),
+        None => fnv1a(hash, &sample.value.payload.contiguous()),
+    }
+}
+
+/// The builder of a [`ConsumerGroupSubscriber`], allowing to configure it.
+///
+/// The [`ConsumerGroupSubscriber`]s declared with the same group name share the samples published on
+/// their key expression: each sample is delivered to a single member of the group. The members track
+/// each other with liveliness tokens, and deliver the samples they own by rendezvous hashing on the live members,
+/// so that the membership changes only move the samples of the joining or leaving members.
+///
+/// The members of a group must subscribe to the same key expression. While the members don't have the same view
+/// of the group (i.e. for the time the liveliness of a member takes to propagate), a sample may be delivered
+/// to several members or to none. The samples are identified by their key expression and their timestamp:
+/// without timestamping, the samples with the same key expression and payload are delivered to the same member.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+/// use zenoh_ext::SessionExt;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let subscriber = session
+///     .declare_consumer_group_subscriber("jobs/**", "workers")
+///     .res()
+///     .await
+///     .unwrap();
+/// while let Ok(sample) = subscriber.recv_async().await {
+///     println!("Processing: {:?}", sample);
+/// }
+/// # }
+/// ```
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct ConsumerGroupSubscriberBuilder<'a, 'b, Handler> {
+    session: SessionRef<'a>,
+    key_expr: ZResult<KeyExpr<'b>>,
+    group: String,
+    reliability: Reliability,
+    membership_timeout: Duration,
+    handler: Handler,
+}
+
+impl<'a, 'b> ConsumerGroupSubscriberBuilder<'a, 'b, DefaultHandler> {
+    pub(crate) fn new(
+        session: SessionRef<'a>,
+        key_expr: ZResult<KeyExpr<'b>>,
+        group: String,
+    ) -> ConsumerGroupSubscriberBuilder<'a, 'b, DefaultHandler> {
+        ConsumerGroupSubscriberBuilder {
+            session,
+            key_expr,
+            group,
+            reliability: Reliability::default(),
+            membership_timeout: Duration::from_secs(10),
+            handler: DefaultHandler,
+        }
+    }
+
+    /// Add callback to [`ConsumerGroupSubscriber`].
+    #[inline]
+    pub fn callback<Callback>(
+        self,
+        callback: Callback,
+    ) -> ConsumerGroupSubscriberBuilder<'a, 'b, Callback>
+    where
+        Callback: Fn(Sample) + Send + Sync + 'static,
+    {
+        self.with(callback)
+    }
+
+    /// Add callback to [`ConsumerGroupSubscriber`].
+    ///
+    /// Using this guarantees that your callback will never be called concurrently.
+    /// If your callback is also accepted by the [`callback`](ConsumerGroupSubscriberBuilder::callback)
+    /// method, we suggest you use it instead of `callback_mut`
+    #[inline]
+    pub fn callback_mut<CallbackMut>(
+        self,
+        callback: CallbackMut,
+    ) -> ConsumerGroupSubscriberBuilder<'a, 'b, impl Fn(Sample) + Send + Sync + 'static>
+    where
+        CallbackMut: FnMut(Sample) + Send + Sync + 'static,
+    {
+        self.callback(locked(callback))
+    }
+
+    /// Use the given handler to receive Samples.
+    #[inline]
+    pub fn with<Handler>(self, handler: Handler) -> ConsumerGroupSubscriberBuilder<'a, 'b, Handler>
+    where
+        Handler: IntoCallbackReceiverPair<'static, Sample>,
+    {
+        let ConsumerGroupSubscriberBuilder {
+            session,
+            key_expr,
+            group,
+            reliability,
+            membership_timeout,
+            handler: _,
+        } = self;
+        ConsumerGroupSubscriberBuilder {
+            session,
+            key_expr,
+            group,
+            reliability,
+            membership_timeout,
+            handler,
+        }
+    }
+}
+
+impl<'a, 'b, Handler> ConsumerGroupSubscriberBuilder<'a, 'b, Handler> {
+    /// Change the subscription reliability.
+    #[inline]
+    pub fn reliability(mut self, reliability: Reliability) -> Self {
+        self.reliability = reliability;
+        self
+    }
+
+    /// Change the subscription reliability to `Reliable`.
+    #[inline]
+    pub fn reliable(mut self) -> Self {
+        self.reliability = Reliability::Reliable;
+        self
+    }
+
+    /// Change the subscription reliability to `BestEffort`.
+    #[inline]
+    pub fn best_effort(mut self) -> Self {
+        self.reliability = Reliability::BestEffort;
+        self
+    }
+
+    /// Change the timeout of the query of the current members of the group (default: 10s).
+    #[inline]
+    pub fn membership_timeout(mut self, timeout: Duration) -> Self {
+        self.membership_timeout = timeout;
+        self
+    }
+}
+
+impl<'a, Handler> Resolvable for ConsumerGroupSubscriberBuilder<'a, '_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample>,
+    Handler::Receiver: Send,
+{
+    type To = ZResult<ConsumerGroupSubscriber<'a, Handler::Receiver>>;
+}
+
+impl<Handler> SyncResolve for ConsumerGroupSubscriberBuilder<'_, '_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+{
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        zenoh_runtime::ZRuntime::Application.block_in_place(ConsumerGroupSubscriber::new(self))
+    }
+}
+
+impl<'a, 'b, Handler> AsyncResolve for ConsumerGroupSubscriberBuilder<'a, 'b, Handler>
+where
+    'b: 'a,
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send + 'a,
+    Handler::Receiver: Send + 'a,
+{
+    type Future = Pin<Box<dyn Future<Output = Self::To> + Send + 'a>>;
+
+    // The current members of the group are queried without blocking the executor
+    fn res_async(self) -> Self::Future {
+        Box::pin(ConsumerGroupSubscriber::new(self))
+    }
+}
+
+struct Membership {
+    member: String,
+    members: RwLock<BTreeSet<String>>,
+}
+
+impl Membership {
+    // Whether this member owns the sample, i.e. has the highest weight for it among the live members
+    fn owns(&self, sample: &Sample) -> bool {
+        let hash = sample_hash(sample);
+        let members = zread!(self.members);
+        members
+            .iter()
+            .max_by_key(|member| fnv1a(hash, member.as_bytes()))
+            .map_or(true, |owner| *owner == self.member)
+    }
+
+    fn on_token(&self, prefix: &str, token: &Sample) {
+        let Some(member) = token.key_expr.as_str().strip_prefix(prefix) else {
+            return;
+        };
+        match token.kind {
+            SampleKind::Put => {
+                if zwrite!(self.members).insert(member.to_string()) {
+                    tracing::debug!("Consumer group member joined: {}", member);
+                }
+            }
+            SampleKind::Delete => {
+                // This member is always part of its own group
+                if member != self.member && zwrite!(self.members).remove(member) {
+                    tracing::debug!("Consumer group member left: {}", member);
+                }
+            }
+        }
+    }
+}
+
+/// A subscriber sharing the samples of its key expression with the other members of its group.
+/// See [`ConsumerGroupSubscriberBuilder`].
+pub struct ConsumerGroupSubscriber<'a, Receiver> {
+    subscriber: Subscriber<'a, ()>,
+    members_subscriber: Subscriber<'a, ()>,
+    token: LivelinessToken<'a>,
+    membership: Arc<Membership>,
+    receiver: Receiver,
+}
+
+impl<Receiver> std::ops::Deref for ConsumerGroupSubscriber<'_, Receiver> {
+    type Target = Receiver;
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+impl<Receiver> std::ops::DerefMut for ConsumerGroupSubscriber<'_, Receiver> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.receiver
+    }
+}
+
+impl<'a, Receiver> ConsumerGroupSubscriber<'a, Receiver> {
+    async fn new<Handler>(
+        conf: ConsumerGroupSubscriberBuilder<'a, '_, Handler>,
+    ) -> ZResult<ConsumerGroupSubscriber<'a, Receiver>>
+    where
+        Handler: IntoCallbackReceiverPair<'static, Sample, Receiver = Receiver> + Send,
+    {
+        let key_expr = conf.key_expr?.into_owned();
+        let group = keyexpr::new(&conf.group)?;
+        if group.is_wild() || group.as_str().contains('/') {
+            bail!(
+                "Invalid consumer group name '{}': expected a single chunk without wildcard",
+                group
+            );
+        }
+        let prefix = format!("{}/{}/", CONSUMER_GROUP_KEY_SPACE, group);
+        let member = format!(
+            "{}_{}",
+            conf.session.zid(),
+            MEMBER_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        tracing::debug!(
+            "Join consumer group {} on {} as {}",
+            group,
+            key_expr,
+            member
+        );
+        let membership = Arc::new(Membership {
+            member: member.clone(),
+            members: RwLock::new(BTreeSet::from([member.clone()])),
+        });
+
+        // track the members before querying the current ones, not to miss a member joining meanwhile
+        let c_membership = membership.clone();
+        let c_prefix = prefix.clone();
+        let members_subscriber = conf
+            .session
+            .liveliness()
+            .declare_subscriber(format!("{}*", prefix))
+            .callback(move |token| c_membership.on_token(&c_prefix, &token))
+            .res_async()
+            .await?;
+        let replies = conf
+            .session
+            .liveliness()
+            .get(format!("{}*", prefix))
+            .timeout(conf.membership_timeout)
+            .res_async()
+            .await?;
+        while let Ok(reply) = replies.recv_async().await {
+            if let Ok(token) = reply.sample {
+                membership.on_token(&prefix, &token);
+            }
+        }
+        let token = conf
+            .session
+            .liveliness()
+            .declare_token(format!("{}{}", prefix, member))
+            .res_async()
+            .await?;
+
+        let (callback, receiver) = conf.handler.into_cb_receiver_pair();
+        let c_membership = membership.clone();
+        let subscriber = conf
+            .session
+            .declare_subscriber(&key_expr)
+            .callback(move |sample| {
+                if c_membership.owns(&sample) {
+                    callback(sample);
+                }
+            })
+            .reliability(conf.reliability)
+            .res_async()
+            .await?;
+
+        Ok(ConsumerGroupSubscriber {
+            subscriber,
+            members_subscriber,
+            token,
+            membership,
+            receiver,
+        })
+    }
+
+    /// Return the key expression of this ConsumerGroupSubscriber
+    #[inline]
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        self.subscriber.key_expr()
+    }
+
+    /// Return the identifier of this member in its group.
+    #[inline]
+    pub fn member_id(&self) -> &str {
+        &self.membership.member
+    }
+
+    /// Return the identifiers of the live members of the group, including this one.
+    pub fn members(&self) -> Vec<String> {
+        zread!(self.membership.members).iter().cloned().collect()
+    }
+
+    /// Leave the group and close this ConsumerGroupSubscriber
+    #[inline]
+    pub fn close(self) -> impl Resolve<ZResult<()>> + 'a {
+        let ConsumerGroupSubscriber {
+            subscriber,
+            members_subscriber,
+            token,
+            ..
+        } = self;
+        ResolveFuture::new(async move {
+            subscriber.undeclare().res_async().await?;
+            token.undeclare().res_async().await?;
+            members_subscriber.undeclare().res_async().await
+        })
+    }
+}
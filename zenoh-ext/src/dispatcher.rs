@@ -0,0 +1,271 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::convert::TryInto;
+use std::future::Ready;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh::subscriber::Subscriber;
+use zenoh::SessionRef;
+use zenoh_core::{AsyncResolve, Resolvable, Resolve, SyncResolve};
+use zenoh_result::ZResult;
+use zenoh_task::TerminatableTask;
+use zenoh_util::core::ResolveFuture;
+
+/// The default capacity of the queue of each handler of a [`Dispatcher`].
+pub const DEFAULT_DISPATCHER_CAPACITY: usize = 256;
+
+/// The condition a sample must satisfy to be routed to a handler of a [`Dispatcher`].
+pub enum DispatchRule {
+    /// The JSON payload has the given value at the given [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901) (e.g. `/type`).
+    Field {
+        pointer: String,
+        value: serde_json::Value,
+    },
+    /// The attachment has the given value for the given key.
+    Attachment { key: Vec<u8>, value: Vec<u8> },
+    /// The given predicate holds.
+    Predicate(Box<dyn Fn(&Sample) -> bool + Send + Sync>),
+    /// Any sample not routed by the other rules.
+    Fallback,
+}
+
+impl DispatchRule {
+    fn matches(&self, sample: &Sample, json: &Option<serde_json::Value>) -> bool {
+        match self {
+            DispatchRule::Field { pointer, value } => json
+                .as_ref()
+                .and_then(|json| json.pointer(pointer))
+                .is_some_and(|v| v == value),
+            DispatchRule::Attachment { key, value } => sample
+                .attachment()
+                .and_then(|attachment| attachment.get(key))
+                .is_some_and(|v| v.as_slice() == value.as_slice()),
+            DispatchRule::Predicate(predicate) => predicate(sample),
+            DispatchRule::Fallback => true,
+        }
+    }
+}
+
+type DispatchHandler = Box<dyn Fn(Sample) + Send + Sync>;
+
+/// The builder of [`Dispatcher`], allowing to register its handlers.
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct DispatcherBuilder<'a, 'b> {
+    session: SessionRef<'a>,
+    key_expr: ZResult<KeyExpr<'b>>,
+    routes: Vec<(DispatchRule, DispatchHandler)>,
+    capacity: usize,
+}
+
+impl<'a, 'b> DispatcherBuilder<'a, 'b> {
+    pub(crate) fn new(
+        session: SessionRef<'a>,
+        key_expr: ZResult<KeyExpr<'b>>,
+    ) -> DispatcherBuilder<'a, 'b> {
+        DispatcherBuilder {
+            session,
+            key_expr,
+            routes: vec![],
+            capacity: DEFAULT_DISPATCHER_CAPACITY,
+        }
+    }
+
+    /// Route the samples satisfying `rule` to `handler`.
+    ///
+    /// The rules are evaluated in registration order and a sample is routed to the first
+    /// handler whose rule it satisfies.
+    pub fn route<F>(mut self, rule: DispatchRule, handler: F) -> Self
+    where
+        F: Fn(Sample) + Send + Sync + 'static,
+    {
+        self.routes.push((rule, Box::new(handler)));
+        self
+    }
+
+    /// Route the samples whose JSON payload has `value` at `pointer` (e.g. `/type`) to `handler`.
+    pub fn route_field<V, F>(self, pointer: &str, value: V, handler: F) -> Self
+    where
+        V: Into<serde_json::Value>,
+        F: Fn(Sample) + Send + Sync + 'static,
+    {
+        let rule = DispatchRule::Field {
+            pointer: pointer.to_string(),
+            value: value.into(),
+        };
+        self.route(rule, handler)
+    }
+
+    /// Route the samples whose attachment has `value` for `key` to `handler`.
+    pub fn route_attachment<K, V, F>(self, key: K, value: V, handler: F) -> Self
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+        F: Fn(Sample) + Send + Sync + 'static,
+    {
+        let rule = DispatchRule::Attachment {
+            key: key.as_ref().to_vec(),
+            value: value.as_ref().to_vec(),
+        };
+        self.route(rule, handler)
+    }
+
+    /// Route the samples not routed to any other handler to `handler`.
+    pub fn fallback<F>(self, handler: F) -> Self
+    where
+        F: Fn(Sample) + Send + Sync + 'static,
+    {
+        self.route(DispatchRule::Fallback, handler)
+    }
+
+    /// Change the capacity of the queue of each handler.
+    ///
+    /// The samples routed to a handler whose queue is full are dropped.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+}
+
+impl<'a> Resolvable for DispatcherBuilder<'a, '_> {
+    type To = ZResult<Dispatcher<'a>>;
+}
+
+impl SyncResolve for DispatcherBuilder<'_, '_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        Dispatcher::new(self)
+    }
+}
+
+impl<'a> AsyncResolve for DispatcherBuilder<'a, '_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A subscriber routing the samples received on a key expression to the handlers whose
+/// [`DispatchRule`] they satisfy, each handler being called from its own task with its own
+/// bounded queue so that a slow handler does not delay the others.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+/// use zenoh_ext::SessionExt;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let dispatcher = session
+///     .declare_dispatcher("key/expression")
+///     .route_field("/type", "alarm", |sample| println!("Alarm: {}", sample.value))
+///     .route_attachment("kind", "log", |sample| println!("Log: {}", sample.value))
+///     .fallback(|sample| println!("Other: {}", sample.value))
+///     .res()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+pub struct Dispatcher<'a> {
+    subscriber: Subscriber<'a, ()>,
+    tasks: Vec<TerminatableTask>,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl<'a> Dispatcher<'a> {
+    fn new(conf: DispatcherBuilder<'a, '_>) -> ZResult<Dispatcher<'a>> {
+        let key_expr = conf.key_expr?;
+        let capacity = conf.capacity;
+        let parse_json = conf
+            .routes
+            .iter()
+            .any(|(rule, _)| matches!(rule, DispatchRule::Field { .. }));
+
+        let mut rules = Vec::with_capacity(conf.routes.len());
+        let mut tasks = Vec::with_capacity(conf.routes.len());
+        for (rule, handler) in conf.routes {
+            let (tx, rx) = flume::bounded::<Sample>(capacity);
+            rules.push((rule, tx));
+            tasks.push(TerminatableTask::spawn_abortable(
+                zenoh_runtime::ZRuntime::Application,
+                async move {
+                    while let Ok(sample) = rx.recv_async().await {
+                        handler(sample);
+                    }
+                },
+            ));
+        }
+
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let c_dropped = dropped.clone();
+        let c_key_expr = key_expr.clone().into_owned();
+        let subscriber = conf
+            .session
+            .declare_subscriber(&key_expr)
+            .callback(move |sample| {
+                let json = parse_json
+                    .then(|| serde_json::from_slice(&sample.value.payload.contiguous()).ok())
+                    .flatten();
+                let Some((_, tx)) = rules.iter().find(|(rule, _)| rule.matches(&sample, &json))
+                else {
+                    tracing::trace!(
+                        "Dispatcher on {}: no handler for {}",
+                        c_key_expr,
+                        sample.key_expr
+                    );
+                    return;
+                };
+                if tx.try_send(sample).is_err() {
+                    c_dropped.fetch_add(1, Ordering::Relaxed);
+                    tracing::warn!(
+                        "Dispatcher on {}: handler queue is full, sample dropped",
+                        c_key_expr
+                    );
+                }
+            })
+            .res_sync()?;
+
+        Ok(Dispatcher {
+            subscriber,
+            tasks,
+            dropped,
+        })
+    }
+
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        self.subscriber.key_expr()
+    }
+
+    /// The number of samples dropped because the queue of their handler was full.
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Close this Dispatcher
+    #[inline]
+    pub fn close(self) -> impl Resolve<ZResult<()>> + 'a {
+        ResolveFuture::new(async move {
+            let Dispatcher {
+                subscriber, tasks, ..
+            } = self;
+            subscriber.undeclare().res_async().await?;
+            for task in tasks {
+                task.terminate(Duration::from_secs(10));
+            }
+            Ok(())
+        })
+    }
+}
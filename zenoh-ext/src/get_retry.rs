@@ -0,0 +1,230 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::convert::TryInto;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh::query::{QueryConsolidation, QueryTarget, Reply};
+use zenoh::SessionRef;
+use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
+use zenoh_result::{bail, zerror, ZResult};
+
+/// The builder of a `get` with retries, allowing to configure it.
+///
+/// Each try issues a regular [`get`](zenoh::Session::get) and collects its replies.
+/// A try is considered as answered as soon as at least one of its replies carries a [`Sample`].
+/// Unanswered tries are retried after an exponentially growing backoff, until the configured
+/// number of retries is exhausted. If a fallback selector was set, it is then queried once.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use std::time::Duration;
+/// use zenoh::prelude::r#async::*;
+/// use zenoh_ext::SessionExt;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let replies = session
+///     .get_retry("service/status")
+///     .retries(3)
+///     .backoff(Duration::from_millis(100))
+///     .fallback("cache/service/status")
+///     .res()
+///     .await
+///     .unwrap();
+/// for reply in replies {
+///     println!("Received {:?}", reply.sample);
+/// }
+/// # }
+/// ```
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct GetRetryBuilder<'a, 'b> {
+    session: SessionRef<'a>,
+    selector: ZResult<Selector<'b>>,
+    fallback: Option<ZResult<Selector<'b>>>,
+    target: QueryTarget,
+    consolidation: QueryConsolidation,
+    timeout: Option<Duration>,
+    retries: usize,
+    backoff: Duration,
+    backoff_factor: u32,
+    max_backoff: Duration,
+}
+
+impl<'a, 'b> GetRetryBuilder<'a, 'b> {
+    pub(crate) fn new(
+        session: SessionRef<'a>,
+        selector: ZResult<Selector<'b>>,
+    ) -> GetRetryBuilder<'a, 'b> {
+        GetRetryBuilder {
+            session,
+            selector,
+            fallback: None,
+            target: QueryTarget::default(),
+            consolidation: QueryConsolidation::default(),
+            timeout: None,
+            retries: 3,
+            backoff: Duration::from_millis(100),
+            backoff_factor: 2,
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+
+    /// Change the target of the queries.
+    #[inline]
+    pub fn target(mut self, target: QueryTarget) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Change the consolidation mode of the queries.
+    #[inline]
+    pub fn consolidation<QC: Into<QueryConsolidation>>(mut self, consolidation: QC) -> Self {
+        self.consolidation = consolidation.into();
+        self
+    }
+
+    /// Set the timeout of each individual try.
+    /// By default, the session's `queries_default_timeout` is used.
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Change the number of retries after the first unanswered try (default: 3).
+    #[inline]
+    pub fn retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Change the delay before the first retry (default: 100ms).
+    #[inline]
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Change the factor applied to the delay after each retry (default: 2).
+    #[inline]
+    pub fn backoff_factor(mut self, factor: u32) -> Self {
+        self.backoff_factor = factor;
+        self
+    }
+
+    /// Change the upper bound of the delay between two retries (default: 10s).
+    #[inline]
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Set a selector to query once when all the tries on the main selector remain unanswered
+    /// (e.g. a cache of the live service).
+    #[inline]
+    pub fn fallback<TryIntoSelector>(mut self, fallback: TryIntoSelector) -> Self
+    where
+        TryIntoSelector: TryInto<Selector<'b>>,
+        <TryIntoSelector as TryInto<Selector<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        self.fallback = Some(fallback.try_into().map_err(Into::into));
+        self
+    }
+
+    async fn try_get(&self, selector: &Selector<'static>) -> ZResult<Vec<Reply>> {
+        let mut get = self
+            .session
+            .get(selector.clone())
+            .target(self.target)
+            .consolidation(self.consolidation);
+        if let Some(timeout) = self.timeout {
+            get = get.timeout(timeout);
+        }
+        let replies = get.with(flume::unbounded()).res_async().await?;
+        let mut result = Vec::new();
+        while let Ok(reply) = replies.recv_async().await {
+            result.push(reply);
+        }
+        Ok(result)
+    }
+
+    async fn run(self) -> ZResult<Vec<Reply>> {
+        let selector = self
+            .selector
+            .as_ref()
+            .map_err(|e| zerror!("{}", e))?
+            .to_owned();
+        let fallback = match &self.fallback {
+            Some(Ok(s)) => Some(s.to_owned()),
+            Some(Err(e)) => bail!("Invalid fallback selector: {}", e),
+            None => None,
+        };
+
+        let mut backoff = self.backoff;
+        let mut replies = Vec::new();
+        for attempt in 0..=self.retries {
+            if attempt > 0 {
+                tracing::debug!(
+                    "No reply for {} after {} tries: retrying in {:?}",
+                    selector,
+                    attempt,
+                    backoff
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = backoff
+                    .saturating_mul(self.backoff_factor)
+                    .min(self.max_backoff);
+            }
+            replies = self.try_get(&selector).await?;
+            if replies.iter().any(|r| r.sample.is_ok()) {
+                return Ok(replies);
+            }
+        }
+
+        if let Some(fallback) = fallback {
+            tracing::debug!(
+                "No reply for {} after {} tries: querying fallback {}",
+                selector,
+                self.retries + 1,
+                fallback
+            );
+            return self.try_get(&fallback).await;
+        }
+        Ok(replies)
+    }
+}
+
+impl Resolvable for GetRetryBuilder<'_, '_> {
+    type To = ZResult<Vec<Reply>>;
+}
+
+impl SyncResolve for GetRetryBuilder<'_, '_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        zenoh_runtime::ZRuntime::Application.block_in_place(self.run())
+    }
+}
+
+impl<'a, 'b> AsyncResolve for GetRetryBuilder<'a, 'b>
+where
+    'b: 'a,
+{
+    type Future = Pin<Box<dyn Future<Output = Self::To> + Send + 'a>>;
+
+    fn res_async(self) -> Self::Future {
+        Box::pin(self.run())
+    }
+}
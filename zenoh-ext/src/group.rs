@@ -24,18 +24,23 @@ use std::ops::Add;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
+use zenoh::liveliness::LivelinessToken;
 use zenoh::prelude::r#async::*;
 use zenoh::publication::Publisher;
-use zenoh::query::ConsolidationMode;
+use zenoh::query::{ConsolidationMode, Reply};
+use zenoh::queryable::{Query, Queryable};
+use zenoh::subscriber::FlumeSubscriber;
 use zenoh::Error as ZError;
 use zenoh::Result as ZResult;
 use zenoh::Session;
-use zenoh_result::bail;
+use zenoh_result::{bail, zerror};
 use zenoh_sync::Condition;
 use zenoh_task::TaskController;
 
 const GROUP_PREFIX: &str = "zenoh/ext/net/group";
 const EVENT_POSTFIX: &str = "evt";
+// The members declare a liveliness token on `@group/<group>/<member>`
+const LIVELINESS_PREFIX: &str = "@group";
 const VIEW_REFRESH_LEASE_RATIO: f32 = 0.75f32;
 const DEFAULT_LEASE: Duration = Duration::from_secs(18);
 const DEFAULT_PRIORITY: Priority = Priority::DataHigh;
@@ -163,6 +168,7 @@ struct GroupState {
 
 pub struct Group {
     state: Arc<GroupState>,
+    _token: LivelinessToken<'static>,
     task_controller: TaskController,
 }
 
@@ -173,10 +179,11 @@ impl Drop for Group {
     }
 }
 
-async fn keep_alive_task(state: Arc<GroupState>) {
-    let mid = state.local_member.mid.clone();
-    let evt = GroupNetEvent::KeepAlive(KeepAliveEvent { mid });
-    let buf = bincode::serialize(&evt).unwrap();
+fn serialize<T: Serialize>(value: &T) -> ZResult<Vec<u8>> {
+    bincode::serialize(value).map_err(|e| zerror!("Unable to serialize {}", e).into())
+}
+
+async fn keep_alive_task(state: Arc<GroupState>, buf: Vec<u8>) {
     let period = state
         .local_member
         .lease
@@ -209,41 +216,136 @@ async fn watchdog_task(s: Arc<GroupState>, period: Duration) {
             let u_evt = &*s.user_events_tx.lock().await;
             for e in expired_members {
                 if let Some(tx) = u_evt {
-                    tx.send(GroupEvent::LeaseExpired(LeaseExpiredEvent { mid: e }))
-                        .unwrap()
+                    let _ = tx.send(GroupEvent::LeaseExpired(LeaseExpiredEvent { mid: e }));
                 }
             }
         }
     }
 }
 
-async fn query_handler(z: Arc<Session>, state: Arc<GroupState>) {
-    let qres: KeyExpr = format!(
-        "{}/{}/{}",
-        GROUP_PREFIX, &state.gid, &state.local_member.mid
-    )
-    .try_into()
-    .unwrap();
+async fn query_handler(queryable: Queryable<'static, flume::Receiver<Query>>, buf: Vec<u8>) {
+    let qres = queryable.key_expr().clone();
     tracing::debug!("Started query handler for: {}", &qres);
-    let buf = bincode::serialize(&state.local_member).unwrap();
-    let queryable = z.declare_queryable(&qres).res().await.unwrap();
-
     while let Ok(query) = queryable.recv_async().await {
         tracing::trace!("Serving query for: {}", &qres);
-        query
+        if let Err(e) = query
             .reply(Ok(Sample::new(qres.clone(), buf.clone())))
             .res()
             .await
-            .unwrap();
+        {
+            tracing::warn!("Unable to reply the Member info of {}: {}", &qres, e);
+        }
+    }
+}
+
+// Query the information of a member from its queryable
+async fn fetch_member(z: &Session, state: &GroupState, mid: &keyexpr) -> Option<Member> {
+    let qres = format!("{}/{}/{}", GROUP_PREFIX, &state.gid, mid);
+    // @TODO: we could also send this member info
+    let qc = ConsolidationMode::None;
+    tracing::trace!("Issuing Query for {}", &qres);
+    let receiver = match z.get(&qres).consolidation(qc).res().await {
+        Ok(receiver) => receiver,
+        Err(e) => {
+            tracing::warn!("Unable to query the Member info of {}: {}", mid, e);
+            return None;
+        }
+    };
+
+    let mut member = None;
+    while let Ok(reply) = receiver.recv_async().await {
+        match reply.sample {
+            Ok(sample) => match bincode::deserialize::<Member>(&sample.payload.contiguous()) {
+                Ok(m) => {
+                    tracing::debug!("Received member information: {:?}", &m);
+                    member = Some(m);
+                }
+                Err(e) => {
+                    tracing::warn!("Unable to deserialize the Member info received: {}", e);
+                }
+            },
+            Err(e) => {
+                tracing::warn!("Error received: {}", e);
+            }
+        }
+    }
+    member
+}
+
+// Handle a liveliness token of a member, either alive or lost
+async fn on_liveliness(z: &Session, state: &GroupState, prefix: &str, s: Sample) {
+    let Some(mid) = s
+        .key_expr
+        .as_str()
+        .strip_prefix(prefix)
+        .and_then(|mid| OwnedKeyExpr::try_from(mid).ok())
+    else {
+        return;
+    };
+    if mid == state.local_member.mid {
+        return;
+    }
+    match s.kind {
+        SampleKind::Put => {
+            if state.members.lock().await.contains_key(&mid) {
+                return;
+            }
+            tracing::debug!("Liveliness token of unknown member: {}", &mid);
+            if let Some(m) = fetch_member(z, state, &mid).await {
+                let expiry = Instant::now().add(m.lease);
+                let mut ms = state.members.lock().await;
+                if ms.insert(mid, (m.clone(), expiry)).is_some() {
+                    // Joined meanwhile
+                    return;
+                }
+                tracing::debug!("Other members list: {:?}", ms.keys());
+                state.cond.notify_all();
+                drop(ms);
+                let u_evt = &*state.user_events_tx.lock().await;
+                if let Some(tx) = u_evt {
+                    let _ = tx.send(GroupEvent::Join(JoinEvent { member: m }));
+                }
+            }
+        }
+        SampleKind::Delete => {
+            let mut ms = state.members.lock().await;
+            if ms.remove(&mid).is_none() {
+                return;
+            }
+            tracing::debug!("Member lost liveliness: {}", &mid);
+            tracing::debug!("Other members list: {:?}", ms.keys());
+            drop(ms);
+            let u_evt = &*state.user_events_tx.lock().await;
+            if let Some(tx) = u_evt {
+                let _ = tx.send(GroupEvent::Leave(LeaveEvent { mid }));
+            }
+        }
     }
 }
 
-async fn net_event_handler(z: Arc<Session>, state: Arc<GroupState>) {
-    let sub = z
-        .declare_subscriber(state.group_publisher.key_expr())
-        .res()
-        .await
-        .unwrap();
+// Track the membership from the liveliness tokens of the members:
+// a member whose session is closed or unreachable leaves the group without waiting for its lease to expire.
+// The tokens of the members already alive are first received from the initial liveliness query, which is
+// issued after the declaration of the subscriber so that no token is missed in between.
+async fn liveliness_handler(
+    z: Arc<Session>,
+    state: Arc<GroupState>,
+    sub: FlumeSubscriber<'static>,
+    replies: flume::Receiver<Reply>,
+) {
+    let prefix = format!("{}/{}/", LIVELINESS_PREFIX, &state.gid);
+    while let Ok(reply) = replies.recv_async().await {
+        match reply.sample {
+            Ok(s) => on_liveliness(&z, &state, &prefix, s).await,
+            Err(e) => tracing::warn!("Error received: {}", e),
+        }
+    }
+    while let Ok(s) = sub.recv_async().await {
+        on_liveliness(&z, &state, &prefix, s).await;
+    }
+}
+
+async fn net_event_handler(z: Arc<Session>, state: Arc<GroupState>, sub: FlumeSubscriber<'static>) {
     while let Ok(s) = sub.recv_async().await {
         match bincode::deserialize::<GroupNetEvent>(&(s.value.payload.contiguous())) {
             Ok(evt) => match evt {
@@ -257,7 +359,7 @@ async fn net_event_handler(z: Arc<Session>, state: Arc<GroupState>) {
                     drop(ms);
                     let u_evt = &*state.user_events_tx.lock().await;
                     if let Some(tx) = u_evt {
-                        tx.send(GroupEvent::Join(je)).unwrap()
+                        let _ = tx.send(GroupEvent::Join(je));
                     }
                 }
                 GroupNetEvent::Leave(le) => {
@@ -268,7 +370,7 @@ async fn net_event_handler(z: Arc<Session>, state: Arc<GroupState>) {
                     drop(ms);
                     let u_evt = &*state.user_events_tx.lock().await;
                     if let Some(tx) = u_evt {
-                        tx.send(GroupEvent::Leave(le)).unwrap()
+                        let _ = tx.send(GroupEvent::Leave(le));
                     }
                 }
                 GroupNetEvent::KeepAlive(kae) => {
@@ -295,48 +397,15 @@ async fn net_event_handler(z: Arc<Session>, state: Arc<GroupState>) {
                                     "Received Keep Alive from unknown member: {}",
                                     &kae.mid
                                 );
-                                let qres = format!("{}/{}/{}", GROUP_PREFIX, &state.gid, kae.mid);
-                                // @TODO: we could also send this member info
-                                let qc = ConsolidationMode::None;
-                                tracing::trace!("Issuing Query for {}", &qres);
-                                let receiver = z.get(&qres).consolidation(qc).res().await.unwrap();
-
-                                while let Ok(reply) = receiver.recv_async().await {
-                                    match reply.sample {
-                                        Ok(sample) => {
-                                            match bincode::deserialize::<Member>(
-                                                &sample.payload.contiguous(),
-                                            ) {
-                                                Ok(m) => {
-                                                    let mut expiry = Instant::now();
-                                                    expiry = expiry.add(m.lease);
-                                                    tracing::debug!(
-                                                        "Received member information: {:?}",
-                                                        &m
-                                                    );
-                                                    mm.insert(kae.mid.clone(), (m.clone(), expiry));
-                                                    tracing::debug!(
-                                                        "Other members list: {:?}",
-                                                        mm.keys()
-                                                    );
-                                                    // Advertise a JoinEvent
-                                                    let u_evt = &*state.user_events_tx.lock().await;
-                                                    if let Some(tx) = u_evt {
-                                                        let je = JoinEvent { member: m };
-                                                        tx.send_async(GroupEvent::Join(je))
-                                                            .await
-                                                            .unwrap()
-                                                    }
-                                                }
-                                                Err(e) => {
-                                                    tracing::warn!(
-                                                        "Unable to deserialize the Member info received: {}", e);
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                            tracing::warn!("Error received: {}", e);
-                                        }
+                                if let Some(m) = fetch_member(&z, &state, &kae.mid).await {
+                                    let expiry = Instant::now().add(m.lease);
+                                    mm.insert(kae.mid.clone(), (m.clone(), expiry));
+                                    tracing::debug!("Other members list: {:?}", mm.keys());
+                                    // Advertise a JoinEvent
+                                    let u_evt = &*state.user_events_tx.lock().await;
+                                    if let Some(tx) = u_evt {
+                                        let je = JoinEvent { member: m };
+                                        let _ = tx.send_async(GroupEvent::Join(je)).await;
                                     }
                                 }
                                 state.cond.notify_all();
@@ -370,8 +439,27 @@ impl Group {
             .declare_publisher(event_expr)
             .priority(with.priority)
             .res()
-            .await
-            .unwrap();
+            .await?;
+        // Declare the subscribers and the queryable before announcing the member and querying the
+        // members already alive, so that none of their events is missed
+        let event_sub = z
+            .declare_subscriber(publisher.key_expr().clone())
+            .res()
+            .await?;
+        let liveliness_expr = format!("{LIVELINESS_PREFIX}/{group}");
+        let liveliness_sub = z
+            .liveliness()
+            .declare_subscriber(format!("{liveliness_expr}/**"))
+            .res()
+            .await?;
+        let member_buf = serialize(&with)?;
+        let queryable = z
+            .declare_queryable(format!("{GROUP_PREFIX}/{group}/{}", &with.mid))
+            .res()
+            .await?;
+        let keep_alive_buf = serialize(&GroupNetEvent::KeepAlive(KeepAliveEvent {
+            mid: with.mid.clone(),
+        }))?;
         let state = Arc::new(GroupState {
             gid: String::from(group),
             local_member: with.clone(),
@@ -385,19 +473,36 @@ impl Group {
         // announce the member:
         tracing::debug!("Sending Join Message for local member: {:?}", &with);
         let join_evt = GroupNetEvent::Join(JoinEvent { member: with });
-        let buf = bincode::serialize(&join_evt).unwrap();
-        let _ = state.group_publisher.put(buf).res().await;
+        let buf = serialize(&join_evt)?;
+        state.group_publisher.put(buf).res().await?;
+        let token = z
+            .liveliness()
+            .declare_token(format!("{liveliness_expr}/{}", &state.local_member.mid))
+            .res()
+            .await?;
+        let replies = z
+            .liveliness()
+            .get(format!("{liveliness_expr}/**"))
+            .res()
+            .await?;
 
         let task_controller = TaskController::default();
         // If the liveliness is manual it is the user who has to assert it.
         if is_auto_liveliness {
-            task_controller.spawn_abortable(keep_alive_task(state.clone()));
+            task_controller.spawn_abortable(keep_alive_task(state.clone(), keep_alive_buf));
         }
-        task_controller.spawn_abortable(net_event_handler(z.clone(), state.clone()));
-        task_controller.spawn_abortable(query_handler(z.clone(), state.clone()));
+        task_controller.spawn_abortable(net_event_handler(z.clone(), state.clone(), event_sub));
+        task_controller.spawn_abortable(query_handler(queryable, member_buf));
+        task_controller.spawn_abortable(liveliness_handler(
+            z.clone(),
+            state.clone(),
+            liveliness_sub,
+            replies,
+        ));
         task_controller.spawn_abortable(watchdog_task(state.clone(), Duration::from_secs(1)));
         Ok(Group {
             state,
+            _token: token,
             task_controller,
         })
     }
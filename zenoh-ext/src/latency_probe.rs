@@ -0,0 +1,520 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::Serialize;
+use std::convert::TryInto;
+use std::future::Ready;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zenoh::prelude::r#async::*;
+use zenoh::publication::Publisher;
+use zenoh::sample::Attachment;
+use zenoh::subscriber::Subscriber;
+use zenoh::SessionRef;
+use zenoh_core::{zlock, AsyncResolve, Resolvable, SyncResolve};
+use zenoh_result::ZResult;
+use zenoh_task::TerminatableTask;
+use zenoh_util::core::ResolveFuture;
+
+/// The attachment key of the send time of the samples published by a [`LatencyProbePublisher`],
+/// encoded as the little-endian u64 number of nanoseconds since the UNIX epoch.
+pub const LATENCY_PROBE_ATTACHMENT: &str = "lat_ts";
+/// The key space of the statistics of the [`LatencyProbe`]s: the statistics of a probe on
+/// `<key_expr>` are published in JSON on `@latency/<key_expr>`. See [`LatencyProbeBuilder::stats_publication`].
+pub const LATENCY_STATS_KEY_SPACE: &str = "@latency";
+
+fn now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+fn send_time_attachment() -> Attachment {
+    let mut attachment = Attachment::new();
+    attachment.insert(
+        LATENCY_PROBE_ATTACHMENT,
+        &(now().as_nanos() as u64).to_le_bytes(),
+    );
+    attachment
+}
+
+/// Returns the send time of a sample: the one attached by a [`LatencyProbePublisher`] if any,
+/// or else its timestamp, e.g. the publication timestamp of a session with timestamping enabled.
+fn send_time(sample: &Sample) -> Option<Duration> {
+    let attached = sample.attachment().and_then(|a| {
+        let ts = a.get(&LATENCY_PROBE_ATTACHMENT)?;
+        Some(Duration::from_nanos(u64::from_le_bytes(
+            ts.as_slice().try_into().ok()?,
+        )))
+    });
+    attached.or_else(|| {
+        sample
+            .timestamp
+            .as_ref()
+            .map(|ts| ts.get_time().to_duration())
+    })
+}
+
+// The number of linear sub-buckets of the histogram: the values are recorded
+// with a relative error of at most 1/64, i.e. 2 significant decimal digits.
+const SUB_BUCKET_BITS: u32 = 7;
+const SUB_BUCKETS: u64 = 1 << SUB_BUCKET_BITS;
+const HALF_SUB_BUCKETS: u64 = SUB_BUCKETS / 2;
+
+/// A histogram of latencies in microseconds with a bounded relative error (HDR histogram):
+/// each power of 2 range of values is split in linear sub-buckets.
+#[derive(Clone)]
+struct Histogram {
+    counts: Vec<u64>,
+    count: u64,
+    sum: u64,
+    min: u64,
+    max: u64,
+}
+
+impl Histogram {
+    fn new() -> Self {
+        Histogram {
+            counts: vec![0; Self::index(u64::MAX) + 1],
+            count: 0,
+            sum: 0,
+            min: u64::MAX,
+            max: 0,
+        }
+    }
+
+    fn index(value: u64) -> usize {
+        if value < SUB_BUCKETS {
+            return value as usize;
+        }
+        let shift = u64::BITS - value.leading_zeros() - SUB_BUCKET_BITS;
+        (shift as u64 * HALF_SUB_BUCKETS + (value >> shift)) as usize
+    }
+
+    // The middle of the range of values of the bucket at the given index.
+    fn value(index: usize) -> u64 {
+        let index = index as u64;
+        if index < SUB_BUCKETS {
+            return index;
+        }
+        let shift = index / HALF_SUB_BUCKETS - 1;
+        let low = (index - shift * HALF_SUB_BUCKETS) << shift;
+        low + ((1 << shift) >> 1)
+    }
+
+    fn record(&mut self, value: u64) {
+        self.counts[Self::index(value)] += 1;
+        self.count += 1;
+        self.sum = self.sum.saturating_add(value);
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    fn percentile(&self, q: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let rank = ((q / 100.0 * self.count as f64).ceil() as u64).clamp(1, self.count);
+        let mut total = 0;
+        for (index, count) in self.counts.iter().enumerate() {
+            total += count;
+            if total >= rank {
+                return Self::value(index).clamp(self.min, self.max);
+            }
+        }
+        self.max
+    }
+
+    fn stats(&self, skewed: u64) -> LatencyStats {
+        if self.count == 0 {
+            return LatencyStats {
+                skewed,
+                ..Default::default()
+            };
+        }
+        LatencyStats {
+            count: self.count,
+            skewed,
+            min_us: self.min,
+            max_us: self.max,
+            mean_us: self.sum / self.count,
+            p50_us: self.percentile(50.0),
+            p90_us: self.percentile(90.0),
+            p99_us: self.percentile(99.0),
+            p999_us: self.percentile(99.9),
+        }
+    }
+}
+
+/// The end-to-end latency statistics of a [`LatencyProbe`], in microseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct LatencyStats {
+    /// The number of samples whose latency was measured.
+    pub count: u64,
+    /// The number of samples received before their send time, i.e. with a clock skew
+    /// between the publisher and the subscriber. Their latency is recorded as 0.
+    pub skewed: u64,
+    pub min_us: u64,
+    pub max_us: u64,
+    pub mean_us: u64,
+    pub p50_us: u64,
+    pub p90_us: u64,
+    pub p99_us: u64,
+    pub p999_us: u64,
+}
+
+struct ProbeState {
+    histogram: Histogram,
+    skewed: u64,
+}
+
+impl ProbeState {
+    fn new() -> Self {
+        ProbeState {
+            histogram: Histogram::new(),
+            skewed: 0,
+        }
+    }
+
+    fn on_sample(&mut self, sample: &Sample) {
+        let Some(sent) = send_time(sample) else {
+            return;
+        };
+        let latency = match now().checked_sub(sent) {
+            Some(latency) => latency,
+            None => {
+                self.skewed += 1;
+                Duration::ZERO
+            }
+        };
+        self.histogram.record(latency.as_micros() as u64);
+    }
+
+    fn stats(&self) -> LatencyStats {
+        self.histogram.stats(self.skewed)
+    }
+}
+
+/// The builder of a [`LatencyProbePublisher`].
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct LatencyProbePublisherBuilder<'a, 'b> {
+    session: SessionRef<'a>,
+    key_expr: ZResult<KeyExpr<'b>>,
+}
+
+impl<'a, 'b> LatencyProbePublisherBuilder<'a, 'b> {
+    pub(crate) fn new(
+        session: SessionRef<'a>,
+        key_expr: ZResult<KeyExpr<'b>>,
+    ) -> LatencyProbePublisherBuilder<'a, 'b> {
+        LatencyProbePublisherBuilder { session, key_expr }
+    }
+}
+
+impl<'a> Resolvable for LatencyProbePublisherBuilder<'a, '_> {
+    type To = ZResult<LatencyProbePublisher<'a>>;
+}
+
+impl<'a> SyncResolve for LatencyProbePublisherBuilder<'a, '_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        let key_expr = self.key_expr?.into_owned();
+        tracing::debug!("Create LatencyProbePublisher on {}", key_expr);
+        let publisher = self.session.declare_publisher(key_expr).res_sync()?;
+        Ok(LatencyProbePublisher { publisher })
+    }
+}
+
+impl<'a> AsyncResolve for LatencyProbePublisherBuilder<'a, '_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A publisher attaching their send time to its publications, allowing the [`LatencyProbe`]s
+/// to measure their end-to-end latency without relying on the timestamping of the sessions.
+pub struct LatencyProbePublisher<'a> {
+    publisher: Publisher<'a>,
+}
+
+impl<'a> LatencyProbePublisher<'a> {
+    pub fn key_expr(&self) -> &KeyExpr<'a> {
+        self.publisher.key_expr()
+    }
+
+    /// Put data, tagged with the current time.
+    #[inline]
+    pub fn put<IntoValue>(&self, value: IntoValue) -> impl Resolve<ZResult<()>> + '_
+    where
+        IntoValue: Into<Value>,
+    {
+        self.publisher
+            .put(value)
+            .with_attachment(send_time_attachment())
+    }
+
+    /// Delete data, tagged with the current time.
+    #[inline]
+    pub fn delete(&self) -> impl Resolve<ZResult<()>> + '_ {
+        self.publisher
+            .delete()
+            .with_attachment(send_time_attachment())
+    }
+
+    /// Undeclare this LatencyProbePublisher.
+    #[inline]
+    pub fn undeclare(self) -> impl Resolve<ZResult<()>> + 'a {
+        self.publisher.undeclare()
+    }
+}
+
+/// The builder of a [`LatencyProbe`], allowing to configure it.
+///
+/// A [`LatencyProbe`] subscribes to a key expression and measures the end-to-end latency of
+/// the samples it receives, either from the send time attached by a [`LatencyProbePublisher`]
+/// or from their timestamp. The latency of an existing topic can thus be measured without
+/// modifying its publishers, as long as their session (or a router on the path) has timestamping
+/// enabled. The clocks of the publishers and the subscriber are assumed to be synchronized.
+///
+/// As the statistics are published by a background task, the [`LatencyProbe`] can only be declared
+/// on a `'static` session (e.g. an `Arc<Session>`).
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use std::time::Duration;
+/// use zenoh::prelude::r#async::*;
+/// use zenoh_ext::SessionExt;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+/// let probe = session
+///     .declare_latency_probe("key/expression")
+///     .stats_publication(Duration::from_secs(1))
+///     .res()
+///     .await
+///     .unwrap();
+/// tokio::time::sleep(Duration::from_secs(10)).await;
+/// println!("p99 latency: {}us", probe.stats().p99_us);
+/// # }
+/// ```
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct LatencyProbeBuilder<'a, 'b> {
+    session: SessionRef<'a>,
+    key_expr: ZResult<KeyExpr<'b>>,
+    stats_publication: Option<Duration>,
+}
+
+impl<'a, 'b> LatencyProbeBuilder<'a, 'b> {
+    pub(crate) fn new(
+        session: SessionRef<'a>,
+        key_expr: ZResult<KeyExpr<'b>>,
+    ) -> LatencyProbeBuilder<'a, 'b> {
+        LatencyProbeBuilder {
+            session,
+            key_expr,
+            stats_publication: None,
+        }
+    }
+
+    /// Publish the [`stats`](LatencyProbe::stats) of the probe in JSON on `@latency/<key_expr>`
+    /// at the given period (default: none).
+    #[inline]
+    pub fn stats_publication(mut self, period: Duration) -> Self {
+        self.stats_publication = Some(period);
+        self
+    }
+}
+
+impl Resolvable for LatencyProbeBuilder<'static, '_> {
+    type To = ZResult<LatencyProbe>;
+}
+
+impl SyncResolve for LatencyProbeBuilder<'static, '_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        LatencyProbe::new(self)
+    }
+}
+
+impl AsyncResolve for LatencyProbeBuilder<'static, '_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A subscriber measuring the end-to-end latency of the samples published on a key expression.
+/// See [`LatencyProbeBuilder`].
+pub struct LatencyProbe {
+    subscriber: Subscriber<'static, ()>,
+    state: Arc<Mutex<ProbeState>>,
+    stats_task: Option<TerminatableTask>,
+}
+
+impl LatencyProbe {
+    fn new(conf: LatencyProbeBuilder<'static, '_>) -> ZResult<LatencyProbe> {
+        let key_expr = conf.key_expr?.into_owned();
+        tracing::debug!(
+            "Create LatencyProbe on {} with stats_publication={:?}",
+            key_expr,
+            conf.stats_publication
+        );
+
+        let state = Arc::new(Mutex::new(ProbeState::new()));
+        let c_state = state.clone();
+        let subscriber = conf
+            .session
+            .declare_subscriber(&key_expr)
+            .callback(move |sample| zlock!(c_state).on_sample(&sample))
+            .res_sync()?;
+
+        let stats_task = match conf.stats_publication {
+            Some(period) => {
+                let stats_key_expr = KeyExpr::try_from(LATENCY_STATS_KEY_SPACE)?.join(&key_expr)?;
+                let session = conf.session.clone();
+                let c_state = state.clone();
+                let token = TerminatableTask::create_cancellation_token();
+                let c_token = token.clone();
+                Some(TerminatableTask::spawn(
+                    zenoh_runtime::ZRuntime::Application,
+                    async move {
+                        let mut interval = tokio::time::interval(period);
+                        loop {
+                            tokio::select! {
+                                _ = interval.tick() => {
+                                    let stats = zlock!(c_state).stats();
+                                    if let Err(e) = session
+                                        .put(&stats_key_expr, serde_json::json!(stats))
+                                        .res_async()
+                                        .await
+                                    {
+                                        tracing::warn!(
+                                            "LatencyProbe on {}: error publishing stats: {}",
+                                            key_expr,
+                                            e
+                                        );
+                                    }
+                                },
+                                _ = c_token.cancelled() => return
+                            }
+                        }
+                    },
+                    token,
+                ))
+            }
+            None => None,
+        };
+
+        Ok(LatencyProbe {
+            subscriber,
+            state,
+            stats_task,
+        })
+    }
+
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        self.subscriber.key_expr()
+    }
+
+    /// The latency statistics of the samples received since the declaration or the last [`reset`](Self::reset).
+    pub fn stats(&self) -> LatencyStats {
+        zlock!(self.state).stats()
+    }
+
+    /// Forget the latencies measured so far.
+    pub fn reset(&self) {
+        *zlock!(self.state) = ProbeState::new();
+    }
+
+    /// Close this LatencyProbe and its statistics publication.
+    #[inline]
+    pub fn close(self) -> impl Resolve<ZResult<()>> {
+        ResolveFuture::new(async move {
+            let LatencyProbe {
+                subscriber,
+                stats_task,
+                ..
+            } = self;
+            if let Some(stats_task) = stats_task {
+                stats_task.terminate(Duration::from_secs(10));
+            }
+            subscriber.undeclare().res_async().await?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_buckets() {
+        assert_eq!(Histogram::index(0), 0);
+        assert_eq!(Histogram::index(SUB_BUCKETS - 1), SUB_BUCKETS as usize - 1);
+        let mut last = 0;
+        for shift in 0..u64::BITS {
+            for value in [
+                1u64 << shift,
+                (1 << shift) + (1 << shift) / 3,
+                u64::MAX >> shift,
+            ] {
+                let index = Histogram::index(value);
+                assert!(index < Histogram::index(u64::MAX) + 1);
+                // The middle of the bucket is within 1/64 of the recorded value
+                let middle = Histogram::value(index);
+                assert!(middle.abs_diff(value) <= value / 64, "{value} {middle}");
+            }
+            // The buckets are ordered like the values
+            let index = Histogram::index(1 << shift);
+            assert!(index >= last);
+            last = index;
+        }
+    }
+
+    #[test]
+    fn histogram_stats() {
+        let histogram = Histogram::new();
+        assert_eq!(histogram.stats(0), LatencyStats::default());
+
+        let mut histogram = Histogram::new();
+        for value in 1..=10_000 {
+            histogram.record(value);
+        }
+        let stats = histogram.stats(2);
+        assert_eq!(stats.count, 10_000);
+        assert_eq!(stats.skewed, 2);
+        assert_eq!(stats.min_us, 1);
+        assert_eq!(stats.max_us, 10_000);
+        assert_eq!(stats.mean_us, 5_000);
+        for (percentile, expected) in [
+            (stats.p50_us, 5_000),
+            (stats.p90_us, 9_000),
+            (stats.p99_us, 9_900),
+            (stats.p999_us, 9_990),
+        ] {
+            assert!(
+                percentile.abs_diff(expected) <= expected / 64,
+                "{percentile}"
+            );
+        }
+
+        // The percentiles are bounded by the recorded values
+        let mut histogram = Histogram::new();
+        histogram.record(1_000_001);
+        let stats = histogram.stats(0);
+        assert_eq!((stats.p50_us, stats.p999_us), (1_000_001, 1_000_001));
+    }
+}
@@ -0,0 +1,333 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::{HashMap, VecDeque};
+use std::convert::TryInto;
+use std::future::Ready;
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh::queryable::{Query, Queryable};
+use zenoh::subscriber::FlumeSubscriber;
+use zenoh::SessionRef;
+use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
+use zenoh_result::{bail, ZResult};
+use zenoh_task::TerminatableTask;
+use zenoh_util::core::ResolveFuture;
+
+/// The builder of LatestValueCache, allowing to configure it.
+///
+/// A [`LatestValueCache`] only keeps the latest publication of each key expression, and forgets it
+/// when the key expression is deleted. Its replies share the payloads of the cached publications
+/// without copying them. This makes it a much cheaper alternative to a [`PublicationCache`](crate::PublicationCache)
+/// to distribute a state, when the history of the publications doesn't matter.
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct LatestValueCacheBuilder<'a, 'b, 'c> {
+    session: SessionRef<'a>,
+    key_expr: ZResult<KeyExpr<'b>>,
+    queryable_prefix: Option<ZResult<KeyExpr<'c>>>,
+    subscriber_origin: Locality,
+    complete: Option<bool>,
+    resources_limit: Option<usize>,
+    republish: Option<(Duration, SessionRef<'static>)>,
+    republish_limit: Option<usize>,
+}
+
+impl<'a, 'b, 'c> LatestValueCacheBuilder<'a, 'b, 'c> {
+    pub(crate) fn new(
+        session: SessionRef<'a>,
+        key_expr: ZResult<KeyExpr<'b>>,
+    ) -> LatestValueCacheBuilder<'a, 'b, 'c> {
+        LatestValueCacheBuilder {
+            session,
+            key_expr,
+            queryable_prefix: None,
+            subscriber_origin: Locality::default(),
+            complete: None,
+            resources_limit: None,
+            republish: None,
+            republish_limit: None,
+        }
+    }
+
+    /// Change the prefix used for queryable.
+    pub fn queryable_prefix<TryIntoKeyExpr>(mut self, queryable_prefix: TryIntoKeyExpr) -> Self
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'c>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'c>>>::Error: Into<zenoh_result::Error>,
+    {
+        self.queryable_prefix = Some(queryable_prefix.try_into().map_err(Into::into));
+        self
+    }
+
+    /// Restrict the cached publications to the ones published with the given
+    /// [`Locality`](zenoh::prelude::Locality) (default: any).
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn subscriber_allowed_origin(mut self, origin: Locality) -> Self {
+        self.subscriber_origin = origin;
+        self
+    }
+
+    /// Set completeness option for the queryable.
+    pub fn queryable_complete(mut self, complete: bool) -> Self {
+        self.complete = Some(complete);
+        self
+    }
+
+    /// Change the limit number of cached key expressions.
+    /// The publications on new key expressions are ignored once the limit is reached.
+    pub fn resources_limit(mut self, limit: usize) -> Self {
+        self.resources_limit = Some(limit);
+        self
+    }
+}
+
+impl<'b, 'c> LatestValueCacheBuilder<'static, 'b, 'c> {
+    /// Republish the cached publications at the given period (default: never), with their timestamp,
+    /// so that the subscribers converge to the latest state even if they missed some publications.
+    ///
+    /// As the publications are republished by a background task, this is only available on a `'static`
+    /// session (e.g. an `Arc<Session>`).
+    pub fn republish(mut self, period: Duration) -> Self {
+        self.republish = Some((period, self.session.clone()));
+        self
+    }
+
+    /// Change the maximum number of publications republished at each period (default: all of them).
+    /// The next period resumes the republication where the previous one stopped, so that a large cache
+    /// is republished over several periods instead of in a single burst.
+    pub fn republish_limit(mut self, limit: usize) -> Self {
+        self.republish_limit = Some(limit);
+        self
+    }
+}
+
+impl<'a> Resolvable for LatestValueCacheBuilder<'a, '_, '_> {
+    type To = ZResult<LatestValueCache<'a>>;
+}
+
+impl SyncResolve for LatestValueCacheBuilder<'_, '_, '_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        LatestValueCache::new(self)
+    }
+}
+
+impl<'a> AsyncResolve for LatestValueCacheBuilder<'a, '_, '_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+// The latest publication, by key expression of the queryable
+struct Cache {
+    queryable_prefix: Option<OwnedKeyExpr>,
+    limit: usize,
+    samples: HashMap<OwnedKeyExpr, Sample>,
+    republish_limit: usize,
+    // The key expressions remaining to republish in the current round
+    republish_queue: VecDeque<OwnedKeyExpr>,
+}
+
+impl Cache {
+    fn store(&mut self, sample: Sample) {
+        let queryable_key_expr: OwnedKeyExpr = match &self.queryable_prefix {
+            Some(prefix) => match prefix.join(&sample.key_expr) {
+                Ok(key_expr) => key_expr,
+                Err(e) => {
+                    tracing::warn!("Error caching publication on {}: {}", sample.key_expr, e);
+                    return;
+                }
+            },
+            None => sample.key_expr.clone().into(),
+        };
+        if sample.kind == SampleKind::Delete {
+            if queryable_key_expr.is_wild() {
+                // a wildcard deletion deletes all the key expressions it includes
+                self.samples.retain(|k, _| !queryable_key_expr.includes(k));
+            } else {
+                self.samples.remove(&queryable_key_expr);
+            }
+            return;
+        }
+        match self.samples.get_mut(&queryable_key_expr) {
+            Some(latest) => {
+                // keep the latest publication when the publications are received out of order
+                if let (Some(new), Some(old)) = (&sample.timestamp, &latest.timestamp) {
+                    if new <= old {
+                        return;
+                    }
+                }
+                *latest = sample;
+            }
+            None if self.samples.len() >= self.limit => {
+                tracing::debug!(
+                    "LatestValueCache full: ignore publication on {}",
+                    sample.key_expr
+                );
+            }
+            None => {
+                self.samples.insert(queryable_key_expr, sample);
+            }
+        }
+    }
+
+    async fn reply(&self, query: &Query) {
+        for (key_expr, sample) in self.samples.iter() {
+            if query.selector().key_expr.intersects(key_expr) {
+                if let Err(e) = query.reply(Ok(sample.clone())).res_async().await {
+                    tracing::warn!("Error replying to query: {}", e);
+                }
+            }
+        }
+    }
+
+    async fn republish(&mut self, session: &SessionRef<'static>) {
+        if self.republish_queue.is_empty() {
+            self.republish_queue.extend(self.samples.keys().cloned());
+        }
+        let count = self.republish_limit.min(self.republish_queue.len());
+        let key_exprs: Vec<OwnedKeyExpr> = self.republish_queue.drain(..count).collect();
+        // the publications deleted since the start of the round are skipped
+        for sample in key_exprs.iter().filter_map(|k| self.samples.get(k)) {
+            let mut put = session
+                .put(&sample.key_expr, sample.value.clone())
+                .priority(sample.qos.priority())
+                .congestion_control(sample.qos.congestion_control());
+            if let Some(timestamp) = sample.timestamp {
+                put = put.with_timestamp(timestamp);
+            }
+            if let Some(attachment) = sample.attachment() {
+                put = put.with_attachment(attachment.clone());
+            }
+            if let Err(e) = put.res_async().await {
+                tracing::warn!("Error republishing on {}: {}", sample.key_expr, e);
+            }
+        }
+    }
+}
+
+/// A cache of the latest publication of each key expression, replying to the queries.
+/// See [`LatestValueCacheBuilder`].
+pub struct LatestValueCache<'a> {
+    subscriber: FlumeSubscriber<'a>,
+    _queryable: Queryable<'a, flume::Receiver<Query>>,
+    task: TerminatableTask,
+}
+
+impl<'a> LatestValueCache<'a> {
+    fn new(conf: LatestValueCacheBuilder<'a, '_, '_>) -> ZResult<LatestValueCache<'a>> {
+        let key_expr = conf.key_expr?;
+        // the queryable_prefix (optional), and the key_expr for LatestValueCache's queryable ("[<queryable_prefix>]/<key_expr>")
+        let (queryable_prefix, queryable_key_expr): (Option<OwnedKeyExpr>, KeyExpr) =
+            match conf.queryable_prefix {
+                None => (None, key_expr.clone()),
+                Some(Ok(ke)) => {
+                    let queryable_key_expr = ke.checked_join(&key_expr)?;
+                    (Some(ke.into()), queryable_key_expr)
+                }
+                Some(Err(e)) => bail!("Invalid key expression for queryable_prefix: {}", e),
+            };
+        tracing::debug!(
+            "Create LatestValueCache on {} with resource_limit={:?} republish={:?}",
+            &key_expr,
+            conf.resources_limit,
+            conf.republish.as_ref().map(|(period, _)| period)
+        );
+
+        let mut cache = Cache {
+            queryable_prefix,
+            limit: conf.resources_limit.unwrap_or(usize::MAX),
+            samples: HashMap::with_capacity(conf.resources_limit.unwrap_or(32)),
+            republish_limit: conf.republish_limit.unwrap_or(usize::MAX).max(1),
+            republish_queue: VecDeque::new(),
+        };
+
+        let subscriber = conf
+            .session
+            .declare_subscriber(&key_expr)
+            .allowed_origin(conf.subscriber_origin)
+            .res_sync()?;
+
+        let mut queryable = conf.session.declare_queryable(&queryable_key_expr);
+        if let Some(complete) = conf.complete {
+            queryable = queryable.complete(complete);
+        }
+        let queryable = queryable.res_sync()?;
+
+        let sub_recv = subscriber.receiver.clone();
+        let quer_recv = queryable.receiver.clone();
+        let (republish_period, republish_session) = conf.republish.unzip();
+
+        let token = TerminatableTask::create_cancellation_token();
+        let c_token = token.clone();
+        let task = TerminatableTask::spawn(
+            zenoh_runtime::ZRuntime::Application,
+            async move {
+                let mut republish =
+                    tokio::time::interval(republish_period.unwrap_or(Duration::from_secs(1)));
+                loop {
+                    tokio::select! {
+                        sample = sub_recv.recv_async() => {
+                            if let Ok(sample) = sample {
+                                cache.store(sample);
+                            }
+                        },
+
+                        query = quer_recv.recv_async() => {
+                            if let Ok(query) = query {
+                                cache.reply(&query).await;
+                            }
+                        },
+
+                        _ = republish.tick(), if republish_session.is_some() => {
+                            if let Some(session) = &republish_session {
+                                cache.republish(session).await;
+                            }
+                        },
+
+                        _ = c_token.cancelled() => return
+                    }
+                }
+            },
+            token,
+        );
+
+        Ok(LatestValueCache {
+            subscriber,
+            _queryable: queryable,
+            task,
+        })
+    }
+
+    /// Close this LatestValueCache
+    #[inline]
+    pub fn close(self) -> impl Resolve<ZResult<()>> + 'a {
+        ResolveFuture::new(async move {
+            let LatestValueCache {
+                subscriber,
+                _queryable,
+                task,
+            } = self;
+            _queryable.undeclare().res_async().await?;
+            subscriber.undeclare().res_async().await?;
+            task.terminate(Duration::from_secs(10));
+            Ok(())
+        })
+    }
+
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        self.subscriber.key_expr()
+    }
+}
@@ -11,17 +11,64 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+mod advanced_publisher;
+mod advanced_subscriber;
+mod aggregator;
+mod change_publisher;
+mod consumer_group;
+mod dispatcher;
+mod get_retry;
 pub mod group;
+mod latency_probe;
+mod latest_value_cache;
+mod mock;
+mod offline_publisher;
 mod publication_cache;
 mod querying_subscriber;
+mod rate_limit;
+mod schema;
+mod serialization;
 mod session_ext;
 mod subscriber_ext;
-pub use publication_cache::{PublicationCache, PublicationCacheBuilder};
+pub use advanced_publisher::{
+    AdvancedPublisher, AdvancedPublisherBuilder, ADVANCED_HEARTBEAT_KEY_SPACE,
+    ADVANCED_SN_ATTACHMENT, ADVANCED_SOURCE_ATTACHMENT,
+};
+pub use advanced_subscriber::{AdvancedSubscriber, AdvancedSubscriberBuilder, Miss};
+pub use aggregator::{Aggregator, AggregatorBuilder};
+pub use change_publisher::{ChangePublisher, ChangePublisherBuilder};
+pub use consumer_group::{
+    ConsumerGroupSubscriber, ConsumerGroupSubscriberBuilder, CONSUMER_GROUP_KEY_SPACE,
+};
+pub use dispatcher::{DispatchRule, Dispatcher, DispatcherBuilder, DEFAULT_DISPATCHER_CAPACITY};
+pub use get_retry::GetRetryBuilder;
+pub use latency_probe::{
+    LatencyProbe, LatencyProbeBuilder, LatencyProbePublisher, LatencyProbePublisherBuilder,
+    LatencyStats, LATENCY_PROBE_ATTACHMENT, LATENCY_STATS_KEY_SPACE,
+};
+pub use latest_value_cache::{LatestValueCache, LatestValueCacheBuilder};
+pub use mock::{
+    MockGetBuilder, MockPutBuilder, MockQuery, MockQueryable, MockQueryableBuilder, MockSession,
+    MockSubscriber, MockSubscriberBuilder, TestableQuery, TestableSession,
+};
+pub use offline_publisher::{OfflinePublisher, OfflinePublisherBuilder};
+pub use publication_cache::{
+    PublicationCache, PublicationCacheBuilder, PublicationCacheStats, CACHE_STATS_KEY_SPACE,
+};
 pub use querying_subscriber::{
     FetchingSubscriber, FetchingSubscriberBuilder, QueryingSubscriberBuilder,
 };
+pub use rate_limit::{RateLimit, RateLimitStrategy};
+#[cfg(feature = "protobuf")]
+pub use schema::ProtobufSchemaConf;
+pub use schema::{
+    PublisherSchemaExt, SchemaRegistry, SchemaRegistryConf, ValidationPolicy, SCHEMAS_KEY_SPACE,
+    SCHEMA_ERROR_ATTACHMENT,
+};
+pub use serialization::{z_deserialize, z_serialize, PublisherSerializeExt, SampleDeserializeExt};
 pub use session_ext::SessionExt;
 pub use subscriber_ext::SubscriberBuilderExt;
+pub use subscriber_ext::SubscriberBuilderRateLimitExt;
 pub use subscriber_ext::SubscriberForward;
 
 /// The space of keys to use in a [`FetchingSubscriber`].
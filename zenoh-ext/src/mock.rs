@@ -0,0 +1,912 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::convert::TryInto;
+use std::future::Ready;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+use zenoh::handlers::{locked, Callback, DefaultHandler};
+use zenoh::prelude::SessionDeclarations;
+use zenoh::prelude::{
+    Encoding, IntoCallbackReceiverPair, KeyExpr, OwnedKeyExpr, Sample, SampleKind, Selector, Value,
+    ZenohId,
+};
+use zenoh::query::Reply;
+use zenoh::queryable::{Query, Queryable};
+use zenoh::subscriber::Subscriber;
+use zenoh::Session;
+use zenoh_core::{zlock, AsyncResolve, Resolvable, Resolve, ResolveClosure, SyncResolve};
+use zenoh_result::{bail, ZResult};
+
+/// The operations of a session that the code of an application can be written against,
+/// so that it runs on an [`Arc<Session>`](zenoh::Session) and is unit tested on a [`MockSession`].
+///
+/// # Examples
+/// ```
+/// use zenoh::prelude::sync::*;
+/// use zenoh_ext::{MockSession, TestableQuery, TestableSession};
+///
+/// // Publishes a temperature and returns the number of replies to a query on the sensors
+/// fn publish<S: TestableSession>(session: &S, temp: i64) -> zenoh::Result<usize> {
+///     session.put("sensors/temp".try_into()?, temp.into())?;
+///     Ok(session.get("sensors/**".try_into()?)?.iter().count())
+/// }
+///
+/// let session = MockSession::new();
+/// let subscriber = session.declare_subscriber("sensors/**").res().unwrap();
+/// assert_eq!(publish(&session, 21).unwrap(), 0);
+/// assert_eq!(subscriber.try_recv().unwrap().value.to_string(), "21");
+/// ```
+pub trait TestableSession: Send + Sync {
+    /// The queries received by the queryables of the session.
+    type Query: TestableQuery;
+    /// A subscriber, undeclared when dropped.
+    type Subscriber: Deref<Target = flume::Receiver<Sample>> + Send;
+    /// A queryable, undeclared when dropped.
+    type Queryable: Deref<Target = flume::Receiver<Self::Query>> + Send;
+
+    /// The id of the session.
+    fn zid(&self) -> ZenohId;
+
+    /// Declare a subscriber on `key_expr`.
+    fn subscribe(&self, key_expr: KeyExpr<'_>) -> ZResult<Self::Subscriber>;
+
+    /// Declare a queryable on `key_expr`.
+    fn queryable(&self, key_expr: KeyExpr<'_>) -> ZResult<Self::Queryable>;
+
+    /// Put `value` on `key_expr`.
+    fn put(&self, key_expr: KeyExpr<'_>, value: Value) -> ZResult<()>;
+
+    /// Delete `key_expr`.
+    fn delete(&self, key_expr: KeyExpr<'_>) -> ZResult<()>;
+
+    /// Query `selector`, the replies being received until the returned receiver is closed.
+    fn get(&self, selector: Selector<'_>) -> ZResult<flume::Receiver<Reply>>;
+}
+
+/// The operations of a query received by a [`TestableSession`].
+pub trait TestableQuery: Send + Sync {
+    /// The key selector part of this query.
+    fn key_expr(&self) -> &KeyExpr<'static>;
+
+    /// This query's selector parameters.
+    fn parameters(&self) -> &str;
+
+    /// This query's value.
+    fn value(&self) -> Option<&Value>;
+
+    /// Send a reply to this query.
+    fn reply(&self, result: Result<Sample, Value>) -> ZResult<()>;
+}
+
+impl TestableSession for Arc<Session> {
+    type Query = Query;
+    type Subscriber = Subscriber<'static, flume::Receiver<Sample>>;
+    type Queryable = Queryable<'static, flume::Receiver<Query>>;
+
+    fn zid(&self) -> ZenohId {
+        Session::zid(self)
+    }
+
+    fn subscribe(&self, key_expr: KeyExpr<'_>) -> ZResult<Self::Subscriber> {
+        SessionDeclarations::declare_subscriber(self, key_expr).res_sync()
+    }
+
+    fn queryable(&self, key_expr: KeyExpr<'_>) -> ZResult<Self::Queryable> {
+        SessionDeclarations::declare_queryable(self, key_expr).res_sync()
+    }
+
+    fn put(&self, key_expr: KeyExpr<'_>, value: Value) -> ZResult<()> {
+        Session::put(self, key_expr, value).res_sync()
+    }
+
+    fn delete(&self, key_expr: KeyExpr<'_>) -> ZResult<()> {
+        Session::delete(self, key_expr).res_sync()
+    }
+
+    fn get(&self, selector: Selector<'_>) -> ZResult<flume::Receiver<Reply>> {
+        Session::get(self, selector).res_sync()
+    }
+}
+
+impl TestableQuery for Query {
+    fn key_expr(&self) -> &KeyExpr<'static> {
+        Query::key_expr(self)
+    }
+
+    fn parameters(&self) -> &str {
+        Query::parameters(self)
+    }
+
+    fn value(&self) -> Option<&Value> {
+        Query::value(self)
+    }
+
+    fn reply(&self, result: Result<Sample, Value>) -> ZResult<()> {
+        Query::reply(self, result).res_sync()
+    }
+}
+
+struct MockSubscription {
+    id: usize,
+    key_expr: OwnedKeyExpr,
+    callback: Callback<'static, Sample>,
+}
+
+struct MockQueryableState {
+    id: usize,
+    key_expr: OwnedKeyExpr,
+    callback: Callback<'static, MockQuery>,
+}
+
+#[derive(Default)]
+struct MockState {
+    next_id: usize,
+    subscribers: Vec<MockSubscription>,
+    queryables: Vec<MockQueryableState>,
+}
+
+/// An in-memory session for the unit tests of applications, with the same declare/put/get
+/// surface as a [`Session`](zenoh::Session).
+///
+/// It opens no socket and spawns no task: the publications and the queries are delivered
+/// to the matching subscribers and queryables of the session, in their declaration order,
+/// on the thread putting or querying, before [`res`](zenoh::prelude::sync::SyncResolve::res)
+/// returns. The replies of a query are delivered as soon as they are sent, and its receiver
+/// is closed once all the [`MockQuery`]s delivered to the queryables are dropped.
+///
+/// # Examples
+/// ```
+/// use zenoh::prelude::sync::*;
+/// use zenoh_ext::MockSession;
+///
+/// let session = MockSession::new();
+/// let subscriber = session.declare_subscriber("sensors/**").res().unwrap();
+/// let _queryable = session
+///     .declare_queryable("sensors/temp")
+///     .callback(|query| {
+///         query
+///             .reply(Ok(Sample::new(query.key_expr().clone(), 21)))
+///             .res()
+///             .unwrap()
+///     })
+///     .res()
+///     .unwrap();
+///
+/// session.put("sensors/temp", 22).res().unwrap();
+/// assert_eq!(subscriber.try_recv().unwrap().key_expr.as_str(), "sensors/temp");
+///
+/// let replies = session.get("sensors/**").res().unwrap();
+/// assert_eq!(replies.iter().count(), 1);
+/// ```
+#[derive(Clone)]
+pub struct MockSession {
+    zid: ZenohId,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockSession {
+    /// Create an empty session.
+    pub fn new() -> Self {
+        MockSession {
+            zid: ZenohId::rand(),
+            state: Arc::new(Mutex::new(MockState::default())),
+        }
+    }
+
+    /// The id of the session, used as the replier id of the replies of its queryables.
+    pub fn zid(&self) -> ZenohId {
+        self.zid
+    }
+
+    /// Declare a [`MockSubscriber`] receiving the publications of this session on `key_expr`.
+    pub fn declare_subscriber<'b, TryIntoKeyExpr>(
+        &self,
+        key_expr: TryIntoKeyExpr,
+    ) -> MockSubscriberBuilder<'b, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        MockSubscriberBuilder {
+            session: self.clone(),
+            key_expr: key_expr.try_into().map_err(Into::into),
+            handler: DefaultHandler,
+        }
+    }
+
+    /// Declare a [`MockQueryable`] answering the queries of this session on `key_expr`.
+    pub fn declare_queryable<'b, TryIntoKeyExpr>(
+        &self,
+        key_expr: TryIntoKeyExpr,
+    ) -> MockQueryableBuilder<'b, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        MockQueryableBuilder {
+            session: self.clone(),
+            key_expr: key_expr.try_into().map_err(Into::into),
+            handler: DefaultHandler,
+        }
+    }
+
+    /// Put `value` on `key_expr`.
+    pub fn put<'b, TryIntoKeyExpr, IntoValue>(
+        &self,
+        key_expr: TryIntoKeyExpr,
+        value: IntoValue,
+    ) -> MockPutBuilder<'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+        IntoValue: Into<Value>,
+    {
+        MockPutBuilder {
+            session: self.clone(),
+            key_expr: key_expr.try_into().map_err(Into::into),
+            value: value.into(),
+            kind: SampleKind::Put,
+        }
+    }
+
+    /// Delete `key_expr`.
+    pub fn delete<'b, TryIntoKeyExpr>(&self, key_expr: TryIntoKeyExpr) -> MockPutBuilder<'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        MockPutBuilder {
+            session: self.clone(),
+            key_expr: key_expr.try_into().map_err(Into::into),
+            value: Value::empty(),
+            kind: SampleKind::Delete,
+        }
+    }
+
+    /// Query the queryables of this session matching `selector`.
+    pub fn get<'b, TryIntoSelector>(
+        &self,
+        selector: TryIntoSelector,
+    ) -> MockGetBuilder<'b, DefaultHandler>
+    where
+        TryIntoSelector: TryInto<Selector<'b>>,
+        <TryIntoSelector as TryInto<Selector<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        MockGetBuilder {
+            session: self.clone(),
+            selector: selector.try_into().map_err(Into::into),
+            value: None,
+            handler: DefaultHandler,
+        }
+    }
+
+    fn next_id(state: &mut MockState) -> usize {
+        state.next_id += 1;
+        state.next_id
+    }
+}
+
+impl Default for MockSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestableSession for MockSession {
+    type Query = MockQuery;
+    type Subscriber = MockSubscriber<flume::Receiver<Sample>>;
+    type Queryable = MockQueryable<flume::Receiver<MockQuery>>;
+
+    fn zid(&self) -> ZenohId {
+        self.zid
+    }
+
+    fn subscribe(&self, key_expr: KeyExpr<'_>) -> ZResult<Self::Subscriber> {
+        self.declare_subscriber(key_expr).res_sync()
+    }
+
+    fn queryable(&self, key_expr: KeyExpr<'_>) -> ZResult<Self::Queryable> {
+        self.declare_queryable(key_expr).res_sync()
+    }
+
+    fn put(&self, key_expr: KeyExpr<'_>, value: Value) -> ZResult<()> {
+        MockSession::put(self, key_expr, value).res_sync()
+    }
+
+    fn delete(&self, key_expr: KeyExpr<'_>) -> ZResult<()> {
+        MockSession::delete(self, key_expr).res_sync()
+    }
+
+    fn get(&self, selector: Selector<'_>) -> ZResult<flume::Receiver<Reply>> {
+        MockSession::get(self, selector).res_sync()
+    }
+}
+
+/// A builder for initializing a [`MockSubscriber`].
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct MockSubscriberBuilder<'b, Handler> {
+    session: MockSession,
+    key_expr: ZResult<KeyExpr<'b>>,
+    handler: Handler,
+}
+
+impl<'b> MockSubscriberBuilder<'b, DefaultHandler> {
+    /// Receive the samples for this subscription with a callback.
+    pub fn callback<Callback>(self, callback: Callback) -> MockSubscriberBuilder<'b, Callback>
+    where
+        Callback: Fn(Sample) + Send + Sync + 'static,
+    {
+        self.with(callback)
+    }
+
+    /// Receive the samples for this subscription with a mutable callback.
+    pub fn callback_mut<CallbackMut>(
+        self,
+        callback: CallbackMut,
+    ) -> MockSubscriberBuilder<'b, impl Fn(Sample) + Send + Sync + 'static>
+    where
+        CallbackMut: FnMut(Sample) + Send + Sync + 'static,
+    {
+        self.callback(locked(callback))
+    }
+
+    /// Receive the samples for this subscription with a [`Handler`](IntoCallbackReceiverPair).
+    pub fn with<Handler>(self, handler: Handler) -> MockSubscriberBuilder<'b, Handler>
+    where
+        Handler: IntoCallbackReceiverPair<'static, Sample>,
+    {
+        MockSubscriberBuilder {
+            session: self.session,
+            key_expr: self.key_expr,
+            handler,
+        }
+    }
+}
+
+impl<Handler> Resolvable for MockSubscriberBuilder<'_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+{
+    type To = ZResult<MockSubscriber<Handler::Receiver>>;
+}
+
+impl<Handler> SyncResolve for MockSubscriberBuilder<'_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+{
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        let key_expr: OwnedKeyExpr = self.key_expr?.into();
+        let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        let mut state = zlock!(self.session.state);
+        let id = MockSession::next_id(&mut state);
+        state.subscribers.push(MockSubscription {
+            id,
+            key_expr,
+            callback,
+        });
+        drop(state);
+        Ok(MockSubscriber {
+            session: self.session,
+            id,
+            receiver,
+        })
+    }
+}
+
+impl<Handler> AsyncResolve for MockSubscriberBuilder<'_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+{
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A subscriber of a [`MockSession`], undeclared when dropped.
+pub struct MockSubscriber<Receiver> {
+    session: MockSession,
+    id: usize,
+    pub receiver: Receiver,
+}
+
+impl<Receiver> MockSubscriber<Receiver>
+where
+    Receiver: Send,
+{
+    /// Undeclare the subscriber.
+    pub fn undeclare(self) -> impl Resolve<ZResult<()>> {
+        ResolveClosure::new(move || {
+            drop(self);
+            Ok(())
+        })
+    }
+}
+
+impl<Receiver> Drop for MockSubscriber<Receiver> {
+    fn drop(&mut self) {
+        zlock!(self.session.state)
+            .subscribers
+            .retain(|s| s.id != self.id);
+    }
+}
+
+impl<Receiver> Deref for MockSubscriber<Receiver> {
+    type Target = Receiver;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+impl<Receiver> DerefMut for MockSubscriber<Receiver> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.receiver
+    }
+}
+
+/// A builder for initializing a [`MockQueryable`].
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct MockQueryableBuilder<'b, Handler> {
+    session: MockSession,
+    key_expr: ZResult<KeyExpr<'b>>,
+    handler: Handler,
+}
+
+impl<'b> MockQueryableBuilder<'b, DefaultHandler> {
+    /// Receive the queries for this queryable with a callback.
+    pub fn callback<Callback>(self, callback: Callback) -> MockQueryableBuilder<'b, Callback>
+    where
+        Callback: Fn(MockQuery) + Send + Sync + 'static,
+    {
+        self.with(callback)
+    }
+
+    /// Receive the queries for this queryable with a mutable callback.
+    pub fn callback_mut<CallbackMut>(
+        self,
+        callback: CallbackMut,
+    ) -> MockQueryableBuilder<'b, impl Fn(MockQuery) + Send + Sync + 'static>
+    where
+        CallbackMut: FnMut(MockQuery) + Send + Sync + 'static,
+    {
+        self.callback(locked(callback))
+    }
+
+    /// Receive the queries for this queryable with a [`Handler`](IntoCallbackReceiverPair).
+    pub fn with<Handler>(self, handler: Handler) -> MockQueryableBuilder<'b, Handler>
+    where
+        Handler: IntoCallbackReceiverPair<'static, MockQuery>,
+    {
+        MockQueryableBuilder {
+            session: self.session,
+            key_expr: self.key_expr,
+            handler,
+        }
+    }
+}
+
+impl<Handler> Resolvable for MockQueryableBuilder<'_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, MockQuery> + Send,
+    Handler::Receiver: Send,
+{
+    type To = ZResult<MockQueryable<Handler::Receiver>>;
+}
+
+impl<Handler> SyncResolve for MockQueryableBuilder<'_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, MockQuery> + Send,
+    Handler::Receiver: Send,
+{
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        let key_expr: OwnedKeyExpr = self.key_expr?.into();
+        let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        let mut state = zlock!(self.session.state);
+        let id = MockSession::next_id(&mut state);
+        state.queryables.push(MockQueryableState {
+            id,
+            key_expr,
+            callback,
+        });
+        drop(state);
+        Ok(MockQueryable {
+            session: self.session,
+            id,
+            receiver,
+        })
+    }
+}
+
+impl<Handler> AsyncResolve for MockQueryableBuilder<'_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, MockQuery> + Send,
+    Handler::Receiver: Send,
+{
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A queryable of a [`MockSession`], undeclared when dropped.
+pub struct MockQueryable<Receiver> {
+    session: MockSession,
+    id: usize,
+    pub receiver: Receiver,
+}
+
+impl<Receiver> MockQueryable<Receiver>
+where
+    Receiver: Send,
+{
+    /// Undeclare the queryable.
+    pub fn undeclare(self) -> impl Resolve<ZResult<()>> {
+        ResolveClosure::new(move || {
+            drop(self);
+            Ok(())
+        })
+    }
+}
+
+impl<Receiver> Drop for MockQueryable<Receiver> {
+    fn drop(&mut self) {
+        zlock!(self.session.state)
+            .queryables
+            .retain(|q| q.id != self.id);
+    }
+}
+
+impl<Receiver> Deref for MockQueryable<Receiver> {
+    type Target = Receiver;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+impl<Receiver> DerefMut for MockQueryable<Receiver> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.receiver
+    }
+}
+
+/// A query received by a [`MockQueryable`], the counterpart of a [`Query`](zenoh::queryable::Query).
+#[derive(Clone)]
+pub struct MockQuery {
+    selector: Selector<'static>,
+    value: Option<Value>,
+    replier_id: ZenohId,
+    callback: Callback<'static, Reply>,
+}
+
+impl MockQuery {
+    /// The full [`Selector`] of this query.
+    pub fn selector(&self) -> Selector<'_> {
+        self.selector.clone()
+    }
+
+    /// The key selector part of this query.
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        &self.selector.key_expr
+    }
+
+    /// This query's selector parameters.
+    pub fn parameters(&self) -> &str {
+        self.selector.parameters()
+    }
+
+    /// This query's value.
+    pub fn value(&self) -> Option<&Value> {
+        self.value.as_ref()
+    }
+
+    /// Send a reply to this query, failing if the key expression of the sample does not
+    /// intersect the queried key expression.
+    pub fn reply(&self, result: Result<Sample, Value>) -> impl Resolve<ZResult<()>> + '_ {
+        ResolveClosure::new(move || {
+            if let Ok(sample) = &result {
+                if !self.key_expr().intersects(&sample.key_expr) {
+                    bail!(
+                        "Attempted to reply on `{}`, which does not intersect with query `{}`",
+                        sample.key_expr,
+                        self.key_expr()
+                    );
+                }
+            }
+            (self.callback)(Reply::new(result, self.replier_id));
+            Ok(())
+        })
+    }
+
+    /// Send an error reply with the application error `code` to this query.
+    pub fn reply_err<IntoValue>(
+        &self,
+        code: u16,
+        value: IntoValue,
+    ) -> impl Resolve<ZResult<()>> + '_
+    where
+        IntoValue: Into<Value> + Send,
+    {
+        ResolveClosure::new(move || {
+            let reply = Reply::new(Err(value.into()), self.replier_id);
+            (self.callback)(reply.with_error_code(code));
+            Ok(())
+        })
+    }
+}
+
+impl TestableQuery for MockQuery {
+    fn key_expr(&self) -> &KeyExpr<'static> {
+        MockQuery::key_expr(self)
+    }
+
+    fn parameters(&self) -> &str {
+        MockQuery::parameters(self)
+    }
+
+    fn value(&self) -> Option<&Value> {
+        MockQuery::value(self)
+    }
+
+    fn reply(&self, result: Result<Sample, Value>) -> ZResult<()> {
+        MockQuery::reply(self, result).res_sync()
+    }
+}
+
+/// A builder for initializing a [`put`](MockSession::put) or a [`delete`](MockSession::delete).
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct MockPutBuilder<'b> {
+    session: MockSession,
+    key_expr: ZResult<KeyExpr<'b>>,
+    value: Value,
+    kind: SampleKind,
+}
+
+impl MockPutBuilder<'_> {
+    /// Change the encoding of the written data.
+    pub fn encoding<IntoEncoding>(mut self, encoding: IntoEncoding) -> Self
+    where
+        IntoEncoding: Into<Encoding>,
+    {
+        self.value.encoding = encoding.into();
+        self
+    }
+}
+
+impl Resolvable for MockPutBuilder<'_> {
+    type To = ZResult<()>;
+}
+
+impl SyncResolve for MockPutBuilder<'_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        let mut sample = Sample::new(self.key_expr?.into_owned(), self.value);
+        sample.kind = self.kind;
+        // The callbacks are called without holding the lock, so that they can use the session
+        let callbacks: Vec<Callback<'static, Sample>> = zlock!(self.session.state)
+            .subscribers
+            .iter()
+            .filter(|s| s.key_expr.intersects(&sample.key_expr))
+            .map(|s| s.callback.clone())
+            .collect();
+        for callback in callbacks {
+            callback(sample.clone());
+        }
+        Ok(())
+    }
+}
+
+impl AsyncResolve for MockPutBuilder<'_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A builder for initializing a [`get`](MockSession::get).
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct MockGetBuilder<'b, Handler> {
+    session: MockSession,
+    selector: ZResult<Selector<'b>>,
+    value: Option<Value>,
+    handler: Handler,
+}
+
+impl<'b> MockGetBuilder<'b, DefaultHandler> {
+    /// Receive the replies for this query with a callback.
+    pub fn callback<Callback>(self, callback: Callback) -> MockGetBuilder<'b, Callback>
+    where
+        Callback: Fn(Reply) + Send + Sync + 'static,
+    {
+        self.with(callback)
+    }
+
+    /// Receive the replies for this query with a mutable callback.
+    pub fn callback_mut<CallbackMut>(
+        self,
+        callback: CallbackMut,
+    ) -> MockGetBuilder<'b, impl Fn(Reply) + Send + Sync + 'static>
+    where
+        CallbackMut: FnMut(Reply) + Send + Sync + 'static,
+    {
+        self.callback(locked(callback))
+    }
+
+    /// Receive the replies for this query with a [`Handler`](IntoCallbackReceiverPair).
+    pub fn with<Handler>(self, handler: Handler) -> MockGetBuilder<'b, Handler>
+    where
+        Handler: IntoCallbackReceiverPair<'static, Reply>,
+    {
+        MockGetBuilder {
+            session: self.session,
+            selector: self.selector,
+            value: self.value,
+            handler,
+        }
+    }
+}
+
+impl<Handler> MockGetBuilder<'_, Handler> {
+    /// Set the value of the query.
+    pub fn with_value<IntoValue>(mut self, value: IntoValue) -> Self
+    where
+        IntoValue: Into<Value>,
+    {
+        self.value = Some(value.into());
+        self
+    }
+}
+
+impl<Handler> Resolvable for MockGetBuilder<'_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Reply> + Send,
+    Handler::Receiver: Send,
+{
+    type To = ZResult<Handler::Receiver>;
+}
+
+impl<Handler> SyncResolve for MockGetBuilder<'_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Reply> + Send,
+    Handler::Receiver: Send,
+{
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        let selector = self.selector?.into_owned();
+        let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        // The callbacks are called without holding the lock, so that they can use the session
+        let queryables: Vec<Callback<'static, MockQuery>> = zlock!(self.session.state)
+            .queryables
+            .iter()
+            .filter(|q| q.key_expr.intersects(&selector.key_expr))
+            .map(|q| q.callback.clone())
+            .collect();
+        for queryable in queryables {
+            queryable(MockQuery {
+                selector: selector.clone(),
+                value: self.value.clone(),
+                replier_id: self.session.zid,
+                callback: callback.clone(),
+            });
+        }
+        Ok(receiver)
+    }
+}
+
+impl<Handler> AsyncResolve for MockGetBuilder<'_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Reply> + Send,
+    Handler::Receiver: Send,
+{
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    fn ke(s: &str) -> KeyExpr<'static> {
+        KeyExpr::try_from(s.to_string()).unwrap()
+    }
+
+    // Answers each query received with the samples received so far matching it
+    fn serve<S: TestableSession>(subscriber: &S::Subscriber, queryable: &S::Queryable) -> usize {
+        let samples: Vec<Sample> = subscriber.try_iter().collect();
+        let mut replies = 0;
+        for query in queryable.try_iter() {
+            for sample in samples.iter() {
+                if query.key_expr().intersects(&sample.key_expr) {
+                    query.reply(Ok(sample.clone())).unwrap();
+                    replies += 1;
+                }
+            }
+        }
+        replies
+    }
+
+    #[test]
+    fn mock_pubsub() {
+        let session = MockSession::new();
+        let subscriber = session.subscribe(ke("test/mock/**")).unwrap();
+        let other = session.subscribe(ke("test/other")).unwrap();
+
+        TestableSession::put(&session, ke("test/mock/a"), Value::from("a")).unwrap();
+        TestableSession::delete(&session, ke("test/mock/b")).unwrap();
+        let sample = subscriber.try_recv().unwrap();
+        assert_eq!(sample.key_expr.as_str(), "test/mock/a");
+        assert_eq!(sample.kind, SampleKind::Put);
+        assert_eq!(sample.value.to_string(), "a");
+        let sample = subscriber.try_recv().unwrap();
+        assert_eq!(sample.key_expr.as_str(), "test/mock/b");
+        assert_eq!(sample.kind, SampleKind::Delete);
+        assert!(subscriber.try_recv().is_err());
+        assert!(other.try_recv().is_err());
+
+        // An undeclared subscriber does not receive anything anymore
+        let receiver = subscriber.receiver.clone();
+        drop(subscriber);
+        TestableSession::put(&session, ke("test/mock/a"), Value::from("a")).unwrap();
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn mock_query() {
+        let session = MockSession::new();
+        let subscriber = session.subscribe(ke("test/mock/**")).unwrap();
+        let queryable = session.queryable(ke("test/mock/**")).unwrap();
+        TestableSession::put(&session, ke("test/mock/a"), Value::from("a")).unwrap();
+        TestableSession::put(&session, ke("test/mock/b"), Value::from("b")).unwrap();
+
+        let replies =
+            TestableSession::get(&session, "test/mock/a?p=1".try_into().unwrap()).unwrap();
+        let query = queryable.try_recv().unwrap();
+        assert_eq!(TestableQuery::key_expr(&query).as_str(), "test/mock/a");
+        assert_eq!(TestableQuery::parameters(&query), "p=1");
+        assert!(TestableQuery::value(&query).is_none());
+        // A reply must intersect the query
+        let sample = Sample::new(ke("test/mock/b"), "b");
+        assert!(TestableQuery::reply(&query, Ok(sample)).is_err());
+        drop(query);
+        assert!(replies.try_recv().is_err());
+        assert!(replies.is_disconnected());
+
+        // The replies are received until all the queries are dropped
+        let replies = TestableSession::get(&session, "test/mock/**".try_into().unwrap()).unwrap();
+        assert_eq!(serve::<MockSession>(&subscriber, &queryable), 2);
+        let replies: Vec<Reply> = replies.iter().collect();
+        assert_eq!(replies.len(), 2);
+        for reply in replies.iter() {
+            assert_eq!(reply.replier_id, TestableSession::zid(&session));
+        }
+
+        // A query with a value, without queryable
+        drop(queryable);
+        let replies = session
+            .get("test/mock/**")
+            .with_value("value")
+            .res_sync()
+            .unwrap();
+        assert!(replies.iter().next().is_none());
+    }
+}
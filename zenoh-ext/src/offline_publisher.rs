@@ -0,0 +1,396 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::future::Ready;
+use std::io::{BufReader, ErrorKind, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use zenoh::prelude::r#sync::*;
+use zenoh::time::Timestamp;
+use zenoh::SessionRef;
+use zenoh_core::{zlock, AsyncResolve, Resolvable, Resolve, ResolveClosure, SyncResolve};
+use zenoh_result::{bail, zerror, ZResult};
+
+/// The builder of [`OfflinePublisher`], allowing to configure it.
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct OfflinePublisherBuilder<'a, 'b> {
+    session: SessionRef<'a>,
+    key_expr: ZResult<KeyExpr<'b>>,
+    path: PathBuf,
+    max_samples: Option<usize>,
+    congestion_control: CongestionControl,
+    priority: Priority,
+}
+
+impl<'a, 'b> OfflinePublisherBuilder<'a, 'b> {
+    pub(crate) fn new(
+        session: SessionRef<'a>,
+        key_expr: ZResult<KeyExpr<'b>>,
+        path: PathBuf,
+    ) -> OfflinePublisherBuilder<'a, 'b> {
+        OfflinePublisherBuilder {
+            session,
+            key_expr,
+            path,
+            max_samples: None,
+            congestion_control: CongestionControl::default(),
+            priority: Priority::default(),
+        }
+    }
+
+    /// Change the maximum number of samples kept in the outbox while offline (default: unlimited).
+    /// When the limit is reached, new publications are rejected with an error.
+    #[inline]
+    pub fn max_samples(mut self, max_samples: usize) -> Self {
+        self.max_samples = Some(max_samples);
+        self
+    }
+
+    /// Change the `congestion_control` to apply when routing the data.
+    #[inline]
+    pub fn congestion_control(mut self, congestion_control: CongestionControl) -> Self {
+        self.congestion_control = congestion_control;
+        self
+    }
+
+    /// Change the priority of the written data.
+    #[inline]
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+impl<'a> Resolvable for OfflinePublisherBuilder<'a, '_> {
+    type To = ZResult<OfflinePublisher<'a>>;
+}
+
+impl<'a> SyncResolve for OfflinePublisherBuilder<'a, '_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        OfflinePublisher::new(self)
+    }
+}
+
+impl<'a> AsyncResolve for OfflinePublisherBuilder<'a, '_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+// A publication stored in the outbox.
+#[derive(Debug, Serialize, Deserialize)]
+struct SpooledSample {
+    delete: bool,
+    encoding: String,
+    payload: Vec<u8>,
+    timestamp: Option<Timestamp>,
+}
+
+// The file-backed queue of the publications made while offline.
+struct Outbox {
+    path: PathBuf,
+    file: File,
+    len: usize,
+}
+
+impl Outbox {
+    fn open(path: PathBuf) -> ZResult<Outbox> {
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)
+            .map_err(|e| zerror!("Failed to open outbox {}: {}", path.display(), e))?;
+        let mut outbox = Outbox { path, file, len: 0 };
+        // Publications left over by a previous run will be replayed as well, without the
+        // publication it was writing if it crashed in the middle of it
+        outbox.len = outbox.read_all()?.len();
+        Ok(outbox)
+    }
+
+    fn read_all(&mut self) -> ZResult<Vec<SpooledSample>> {
+        self.file.seek(SeekFrom::Start(0))?;
+        let mut reader = BufReader::new(&self.file);
+        let mut samples = Vec::new();
+        // The end of the last complete record
+        let mut end = 0;
+        loop {
+            match bincode::deserialize_from(&mut reader) {
+                Ok(sample) => {
+                    samples.push(sample);
+                    end = reader.stream_position()?;
+                }
+                Err(e) => match *e {
+                    bincode::ErrorKind::Io(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                    e => bail!("Corrupted outbox {}: {}", self.path.display(), e),
+                },
+            }
+        }
+        drop(reader);
+        // The records appended after one torn by a crash could not be read anymore
+        if self.file.metadata()?.len() > end {
+            tracing::warn!(
+                "Truncating the incomplete last record of outbox {}",
+                self.path.display()
+            );
+            self.file.set_len(end)?;
+            self.file.sync_data()?;
+        }
+        Ok(samples)
+    }
+
+    fn push(&mut self, sample: &SpooledSample) -> ZResult<()> {
+        let bytes = bincode::serialize(sample)?;
+        self.file.write_all(&bytes)?;
+        self.file.sync_data()?;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn reset(&mut self, samples: &[SpooledSample]) -> ZResult<()> {
+        self.file.set_len(0)?;
+        self.len = 0;
+        for sample in samples {
+            self.push(sample)?;
+        }
+        Ok(())
+    }
+}
+
+/// A publisher that keeps its publications in a local file-backed outbox while the session
+/// has no connectivity, and replays them in order and with their original timestamps once
+/// the session is connected again.
+///
+/// The outbox is replayed when the publisher is declared on a connected session, and on the
+/// first publication or [`flush`](OfflinePublisher::flush) made after the session reconnected.
+/// Publications left in the outbox by a previous run (e.g. after a reboot of the device) are
+/// thus replayed as soon as the publisher is declared again.
+/// The original timestamps are only available when timestamping is enabled on the session.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+/// use zenoh_ext::SessionExt;
+///
+/// let session = zenoh::open(config::client()).res().await.unwrap();
+/// let publisher = session
+///     .declare_offline_publisher("sensor/temperature", "/var/lib/sensor/outbox")
+///     .res()
+///     .await
+///     .unwrap();
+/// publisher.put("21.5").res().await.unwrap();
+/// # }
+/// ```
+pub struct OfflinePublisher<'a> {
+    session: SessionRef<'a>,
+    key_expr: KeyExpr<'static>,
+    congestion_control: CongestionControl,
+    priority: Priority,
+    max_samples: Option<usize>,
+    outbox: Mutex<Outbox>,
+}
+
+impl<'a> OfflinePublisher<'a> {
+    fn new(conf: OfflinePublisherBuilder<'a, '_>) -> ZResult<OfflinePublisher<'a>> {
+        let key_expr = conf.key_expr?.into_owned();
+        let outbox = Outbox::open(conf.path)?;
+        let publisher = OfflinePublisher {
+            session: conf.session,
+            key_expr,
+            congestion_control: conf.congestion_control,
+            priority: conf.priority,
+            max_samples: conf.max_samples,
+            outbox: Mutex::new(outbox),
+        };
+
+        // Replay the publications left over by a previous run without waiting for a new one
+        if publisher.is_connected() {
+            let mut outbox = zlock!(publisher.outbox);
+            if let Err(e) = publisher.replay(&mut outbox) {
+                tracing::warn!(
+                    "Failed to replay outbox of offline publisher on {}: {}",
+                    publisher.key_expr,
+                    e
+                );
+            }
+        }
+
+        Ok(publisher)
+    }
+
+    /// Returns the key expression of this publisher.
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        &self.key_expr
+    }
+
+    /// Returns the number of publications waiting in the outbox.
+    pub fn pending(&self) -> usize {
+        zlock!(self.outbox).len
+    }
+
+    /// Put data, or store it in the outbox if the session has no connectivity.
+    pub fn put<IntoValue>(&self, value: IntoValue) -> impl Resolve<ZResult<()>> + '_
+    where
+        IntoValue: Into<Value>,
+    {
+        let value = value.into();
+        ResolveClosure::new(move || self.publish(false, value))
+    }
+
+    /// Delete data, or store the deletion in the outbox if the session has no connectivity.
+    pub fn delete(&self) -> impl Resolve<ZResult<()>> + '_ {
+        ResolveClosure::new(move || self.publish(true, Value::empty()))
+    }
+
+    /// Replay the publications waiting in the outbox if the session has connectivity.
+    pub fn flush(&self) -> impl Resolve<ZResult<()>> + '_ {
+        ResolveClosure::new(move || {
+            let mut outbox = zlock!(self.outbox);
+            if self.is_connected() {
+                self.replay(&mut outbox)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn is_connected(&self) -> bool {
+        let info = self.session.info();
+        let connected = info.routers_zid().res_sync().next().is_some()
+            || info.peers_zid().res_sync().next().is_some();
+        connected
+    }
+
+    fn publish(&self, delete: bool, value: Value) -> ZResult<()> {
+        let mut outbox = zlock!(self.outbox);
+        if self.is_connected() {
+            self.replay(&mut outbox)?;
+            if outbox.len == 0 {
+                return self.send(delete, value, None);
+            }
+        }
+
+        if let Some(max) = self.max_samples {
+            if outbox.len >= max {
+                bail!(
+                    "Outbox of offline publisher on {} is full ({} samples)",
+                    self.key_expr,
+                    max
+                );
+            }
+        }
+        let sample = SpooledSample {
+            delete,
+            encoding: value.encoding.to_string(),
+            payload: value.payload.contiguous().into_owned(),
+            timestamp: self.session.hlc().map(|hlc| hlc.new_timestamp()),
+        };
+        tracing::trace!("Storing publication on {} in outbox", self.key_expr);
+        outbox.push(&sample)
+    }
+
+    fn replay(&self, outbox: &mut Outbox) -> ZResult<()> {
+        if outbox.len == 0 {
+            return Ok(());
+        }
+        let samples = outbox.read_all()?;
+        tracing::debug!(
+            "Replaying {} publications on {} from outbox",
+            samples.len(),
+            self.key_expr
+        );
+        let mut sent = 0;
+        let mut res = Ok(());
+        for sample in samples.iter() {
+            let value = Value::from(sample.payload.clone())
+                .encoding(Encoding::from(sample.encoding.clone()));
+            res = self.send(sample.delete, value, sample.timestamp);
+            if res.is_err() {
+                break;
+            }
+            sent += 1;
+        }
+        outbox.reset(&samples[sent..])?;
+        res
+    }
+
+    fn send(&self, delete: bool, value: Value, timestamp: Option<Timestamp>) -> ZResult<()> {
+        let mut put = self
+            .session
+            .put(&self.key_expr, value)
+            .congestion_control(self.congestion_control)
+            .priority(self.priority);
+        if delete {
+            put = put.kind(SampleKind::Delete);
+        }
+        if let Some(timestamp) = timestamp {
+            put = put.with_timestamp(timestamp);
+        }
+        put.res_sync()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(payload: &str) -> SpooledSample {
+        SpooledSample {
+            delete: false,
+            encoding: String::new(),
+            payload: payload.as_bytes().to_vec(),
+            timestamp: None,
+        }
+    }
+
+    fn payloads(outbox: &mut Outbox) -> Vec<String> {
+        outbox
+            .read_all()
+            .unwrap()
+            .into_iter()
+            .map(|s| String::from_utf8(s.payload).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn outbox_torn_record() {
+        let path = std::env::temp_dir().join(format!(
+            "zenoh-ext-offline-publisher-{}.outbox",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let mut outbox = Outbox::open(path.clone()).unwrap();
+        outbox.push(&sample("a")).unwrap();
+        outbox.push(&sample("b")).unwrap();
+        // A crash in the middle of the write of a record
+        let bytes = bincode::serialize(&sample("torn")).unwrap();
+        outbox.file.write_all(&bytes[..bytes.len() / 2]).unwrap();
+        drop(outbox);
+
+        let mut outbox = Outbox::open(path.clone()).unwrap();
+        assert_eq!(outbox.len, 2);
+        outbox.push(&sample("c")).unwrap();
+        drop(outbox);
+
+        let mut outbox = Outbox::open(path.clone()).unwrap();
+        assert_eq!(outbox.len, 3);
+        assert_eq!(payloads(&mut outbox), ["a", "b", "c"]);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -11,20 +11,39 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::convert::TryInto;
+use std::fs::{File, OpenOptions};
 use std::future::Ready;
-use std::time::Duration;
+use std::io::{BufReader, ErrorKind, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 use zenoh::prelude::r#async::*;
 use zenoh::queryable::{Query, Queryable};
+use zenoh::selector::{TimeRange, TIME_RANGE_KEY};
 use zenoh::subscriber::FlumeSubscriber;
+use zenoh::time::Timestamp;
 use zenoh::SessionRef;
 use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
-use zenoh_result::{bail, ZResult};
+use zenoh_result::{bail, zerror, ZResult};
 use zenoh_task::TerminatableTask;
 use zenoh_util::core::ResolveFuture;
 
+/// The key space of the statistics of the [`PublicationCache`]s: the statistics of each cache of
+/// a session are published on `@cache/<zid>/<cache id>/stats`. See [`PublicationCacheBuilder::stats_publication`].
+pub const CACHE_STATS_KEY_SPACE: &str = "@cache";
+
+// The ids of the caches in the key expressions of their statistics
+static NEXT_CACHE_ID: AtomicUsize = AtomicUsize::new(0);
+
 /// The builder of PublicationCache, allowing to configure it.
+///
+/// The queries may select the cached publications by timestamp with the `_time` parameter
+/// (e.g. `demo/**?_time=[now(-5m)..]`). The publications without timestamp are selected by the
+/// time at which they were cached.
 #[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
 pub struct PublicationCacheBuilder<'a, 'b, 'c> {
     session: SessionRef<'a>,
@@ -34,6 +53,12 @@ pub struct PublicationCacheBuilder<'a, 'b, 'c> {
     complete: Option<bool>,
     history: usize,
     resources_limit: Option<usize>,
+    max_age: Option<Duration>,
+    persistence: Option<PathBuf>,
+    reply_congestion_control: CongestionControl,
+    reply_priority: Priority,
+    tombstone_retention: Option<Duration>,
+    stats_publication: Option<(Duration, SessionRef<'static>)>,
 }
 
 impl<'a, 'b, 'c> PublicationCacheBuilder<'a, 'b, 'c> {
@@ -49,6 +74,12 @@ impl<'a, 'b, 'c> PublicationCacheBuilder<'a, 'b, 'c> {
             complete: None,
             history: 1,
             resources_limit: None,
+            max_age: None,
+            persistence: None,
+            reply_congestion_control: CongestionControl::Block,
+            reply_priority: Priority::Data,
+            tombstone_retention: None,
+            stats_publication: None,
         }
     }
 
@@ -88,6 +119,56 @@ impl<'a, 'b, 'c> PublicationCacheBuilder<'a, 'b, 'c> {
         self.resources_limit = Some(limit);
         self
     }
+
+    /// Change the maximum age of the cached publications (default: unlimited).
+    /// Older publications are evicted from the cache and never replied to queries.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Persist the cached publications in an append-only log at the given path,
+    /// from which the cache is restored when re-created (e.g. after a restart of the node).
+    pub fn persistence<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.persistence = Some(path.into());
+        self
+    }
+
+    /// Change the congestion control of the replies to the queries (default: `Block`),
+    /// e.g. `Drop` so that transferring a large history doesn't block the live publications.
+    pub fn reply_congestion_control(mut self, congestion_control: CongestionControl) -> Self {
+        self.reply_congestion_control = congestion_control;
+        self
+    }
+
+    /// Change the priority of the replies to the queries (default: `Data`),
+    /// e.g. `Background` so that transferring a large history doesn't delay the live publications.
+    pub fn reply_priority(mut self, priority: Priority) -> Self {
+        self.reply_priority = priority;
+        self
+    }
+
+    /// Keep the deletions on wildcard key expressions (default: not kept) for the given duration,
+    /// so that the queries received during this time are replied the deletion.
+    ///
+    /// A deletion on a wildcard key expression removes all the cached publications it includes.
+    pub fn tombstone_retention(mut self, retention: Duration) -> Self {
+        self.tombstone_retention = Some(retention);
+        self
+    }
+}
+
+impl<'b, 'c> PublicationCacheBuilder<'static, 'b, 'c> {
+    /// Publish the [`stats`](PublicationCache::stats) of the cache in JSON on `@cache/<zid>/<cache id>/stats`
+    /// at the given period (default: never), for monitoring. The cache id is unique in the process,
+    /// and the published JSON also carries the key expression of the cache.
+    ///
+    /// As the statistics are published by a background task, this is only available on a `'static`
+    /// session (e.g. an `Arc<Session>`).
+    pub fn stats_publication(mut self, period: Duration) -> Self {
+        self.stats_publication = Some((period, self.session.clone()));
+        self
+    }
 }
 
 impl<'a> Resolvable for PublicationCacheBuilder<'a, '_, '_> {
@@ -108,10 +189,471 @@ impl<'a> AsyncResolve for PublicationCacheBuilder<'a, '_, '_> {
     }
 }
 
+// A cached publication persisted on disk.
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedSample {
+    key_expr: String,
+    delete: bool,
+    encoding: String,
+    payload: Vec<u8>,
+    timestamp: Option<Timestamp>,
+}
+
+impl From<&Sample> for PersistedSample {
+    fn from(sample: &Sample) -> Self {
+        PersistedSample {
+            key_expr: sample.key_expr.to_string(),
+            delete: sample.kind == SampleKind::Delete,
+            encoding: sample.value.encoding.to_string(),
+            payload: sample.value.payload.contiguous().into_owned(),
+            timestamp: sample.timestamp,
+        }
+    }
+}
+
+impl PersistedSample {
+    fn into_sample(self) -> ZResult<Sample> {
+        let value = Value::from(self.payload).encoding(Encoding::from(self.encoding));
+        let mut sample = Sample::new(KeyExpr::try_from(self.key_expr)?, value);
+        if self.delete {
+            sample.kind = SampleKind::Delete;
+        }
+        sample.timestamp = self.timestamp;
+        Ok(sample)
+    }
+}
+
+// The append-only log where the cached publications are persisted.
+struct CacheLog {
+    path: PathBuf,
+    file: File,
+    // The number of records, including the ones of the publications evicted since the last compaction
+    records: usize,
+}
+
+impl CacheLog {
+    // The minimum number of records before compacting the log
+    const COMPACTION_THRESHOLD: usize = 1024;
+
+    fn open(path: PathBuf) -> ZResult<CacheLog> {
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)
+            .map_err(|e| zerror!("Failed to open cache log {}: {}", path.display(), e))?;
+        Ok(CacheLog {
+            path,
+            file,
+            records: 0,
+        })
+    }
+
+    fn read_all(&mut self) -> ZResult<Vec<Sample>> {
+        self.file.seek(SeekFrom::Start(0))?;
+        let mut reader = BufReader::new(&self.file);
+        let mut samples = Vec::new();
+        loop {
+            match bincode::deserialize_from::<_, PersistedSample>(&mut reader) {
+                Ok(sample) => samples.push(sample.into_sample()?),
+                // A record truncated by a crash ends the log
+                Err(e) => match *e {
+                    bincode::ErrorKind::Io(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                    e => bail!("Corrupted cache log {}: {}", self.path.display(), e),
+                },
+            }
+        }
+        self.records = samples.len();
+        Ok(samples)
+    }
+
+    fn push(&mut self, sample: &Sample) -> ZResult<()> {
+        let bytes = bincode::serialize(&PersistedSample::from(sample))?;
+        self.file.write_all(&bytes)?;
+        self.records += 1;
+        Ok(())
+    }
+
+    // Rewrite the log with the given publications only
+    fn compact<'s>(&mut self, samples: impl Iterator<Item = &'s Sample>) -> ZResult<()> {
+        self.file.set_len(0)?;
+        self.records = 0;
+        for sample in samples {
+            self.push(sample)?;
+        }
+        self.file.sync_data()?;
+        Ok(())
+    }
+
+    // Compact the log, only warning of a failure
+    fn rewrite(&mut self, pub_key_expr: &KeyExpr<'_>, samples: &[Sample]) {
+        if let Err(e) = self.compact(samples.iter()) {
+            tracing::warn!(
+                "PublicationCache on {}: failed to compact {}: {}",
+                pub_key_expr,
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// The statistics of a [`PublicationCache`], see [`PublicationCache::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct PublicationCacheStats {
+    /// The number of cached key expressions.
+    pub resources: usize,
+    /// The total number of cached publications.
+    pub samples: usize,
+    /// An estimate of the memory used by the cached publications, in bytes.
+    pub memory: usize,
+    /// The number of queries replied by the cache.
+    pub queries_served: u64,
+    /// The number of publications evicted from the cache, because of its history or of their age.
+    pub samples_evicted: u64,
+}
+
+// The statistics updated by the task of the cache and read by its handle
+#[derive(Default)]
+struct CacheCounters {
+    resources: AtomicUsize,
+    samples: AtomicUsize,
+    memory: AtomicUsize,
+    queries_served: AtomicU64,
+    samples_evicted: AtomicU64,
+}
+
+impl CacheCounters {
+    fn snapshot(&self) -> PublicationCacheStats {
+        PublicationCacheStats {
+            resources: self.resources.load(Ordering::Relaxed),
+            samples: self.samples.load(Ordering::Relaxed),
+            memory: self.memory.load(Ordering::Relaxed),
+            queries_served: self.queries_served.load(Ordering::Relaxed),
+            samples_evicted: self.samples_evicted.load(Ordering::Relaxed),
+        }
+    }
+}
+
+// An estimate of the memory used by a cached publication
+fn sample_size(sample: &Sample) -> usize {
+    std::mem::size_of::<(Instant, Sample)>()
+        + sample.key_expr.as_str().len()
+        + sample.value.payload.len()
+}
+
+// The cached publications, by key expression of the queryable, with the instant they were cached at
+struct Cache {
+    pub_key_expr: KeyExpr<'static>,
+    queryable_prefix: Option<OwnedKeyExpr>,
+    history: usize,
+    limit: usize,
+    max_age: Option<Duration>,
+    samples: HashMap<OwnedKeyExpr, VecDeque<(Instant, Sample)>>,
+    len: usize,
+    memory: usize,
+    // The deletions on wildcard key expressions, by key expression of the queryable
+    tombstones: HashMap<OwnedKeyExpr, VecDeque<(Instant, Sample)>>,
+    tombstone_retention: Option<Duration>,
+    log: Option<CacheLog>,
+    reply_congestion_control: CongestionControl,
+    reply_priority: Priority,
+    counters: Arc<CacheCounters>,
+}
+
+impl Cache {
+    fn insert(&mut self, time: Instant, sample: Sample) {
+        let queryable_key_expr: KeyExpr<'_> = if let Some(prefix) = &self.queryable_prefix {
+            prefix.join(&sample.key_expr).unwrap().into()
+        } else {
+            sample.key_expr.clone()
+        };
+
+        if sample.kind == SampleKind::Delete && queryable_key_expr.is_wild() {
+            self.delete_all(time, queryable_key_expr.into(), sample);
+            return;
+        }
+
+        let size = sample_size(&sample);
+        if let Some(queue) = self.samples.get_mut(queryable_key_expr.as_keyexpr()) {
+            if queue.len() >= self.history {
+                if let Some((_, evicted)) = queue.pop_front() {
+                    self.memory -= sample_size(&evicted);
+                    self.counters
+                        .samples_evicted
+                        .fetch_add(1, Ordering::Relaxed);
+                }
+                self.len -= 1;
+            }
+            queue.push_back((time, sample));
+        } else if self.samples.len() >= self.limit {
+            tracing::error!("PublicationCache on {}: resource_limit exceeded - can't cache publication for a new resource",
+            self.pub_key_expr);
+            return;
+        } else {
+            let mut queue: VecDeque<(Instant, Sample)> = VecDeque::new();
+            queue.push_back((time, sample));
+            self.samples.insert(queryable_key_expr.into(), queue);
+        }
+        self.len += 1;
+        self.memory += size;
+        self.update_counters();
+    }
+
+    // Remove all the cached publications included in the key expression of a wildcard deletion,
+    // and keep the deletion as a tombstone if configured
+    fn delete_all(&mut self, time: Instant, key_expr: OwnedKeyExpr, sample: Sample) {
+        let mut removed = 0;
+        let mut memory = 0;
+        self.samples.retain(|k, queue| {
+            if key_expr.includes(k) {
+                removed += queue.len();
+                memory += queue.iter().map(|(_, s)| sample_size(s)).sum::<usize>();
+                false
+            } else {
+                true
+            }
+        });
+        self.len -= removed;
+        self.memory -= memory;
+        self.tombstones.retain(|k, _| !key_expr.includes(k));
+        if self.tombstone_retention.is_some() {
+            self.tombstones
+                .insert(key_expr, VecDeque::from([(time, sample)]));
+        }
+        self.update_counters();
+    }
+
+    fn update_counters(&self) {
+        self.counters
+            .resources
+            .store(self.samples.len(), Ordering::Relaxed);
+        self.counters.samples.store(self.len, Ordering::Relaxed);
+        self.counters.memory.store(self.memory, Ordering::Relaxed);
+    }
+
+    // Run a blocking operation on the log, if any, out of the task of the cache
+    async fn with_log<T: Send + 'static>(
+        &mut self,
+        f: impl FnOnce(&mut CacheLog) -> T + Send + 'static,
+    ) -> Option<T> {
+        let mut log = self.log.take()?;
+        match tokio::task::spawn_blocking(move || {
+            let res = f(&mut log);
+            (log, res)
+        })
+        .await
+        {
+            Ok((log, res)) => {
+                self.log = Some(log);
+                Some(res)
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "PublicationCache on {}: persistence stopped: {}",
+                    self.pub_key_expr,
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    // Cache a publication received by the local subscriber and persist it
+    async fn store(&mut self, mut sample: Sample) {
+        // stamp the publications without timestamp with their reception time, so that they can be
+        // selected by time range
+        if sample.timestamp.is_none() {
+            sample.timestamp = Some(zenoh::time::new_reception_timestamp());
+        }
+        let persisted = sample.clone();
+        let pub_key_expr = self.pub_key_expr.clone();
+        self.with_log(move |log| {
+            if let Err(e) = log.push(&persisted) {
+                tracing::warn!(
+                    "PublicationCache on {}: failed to persist publication: {}",
+                    pub_key_expr,
+                    e
+                );
+            }
+        })
+        .await;
+        self.insert(Instant::now(), sample);
+        let records = self.log.as_ref().map_or(0, |log| log.records);
+        if records >= CacheLog::COMPACTION_THRESHOLD && records > 2 * self.len {
+            let samples = self.persisted();
+            let pub_key_expr = self.pub_key_expr.clone();
+            self.with_log(move |log| log.rewrite(&pub_key_expr, &samples))
+                .await;
+        }
+    }
+
+    // Restore the publications persisted by a previous cache
+    fn restore(&mut self) -> ZResult<()> {
+        let Some(log) = self.log.as_mut() else {
+            return Ok(());
+        };
+        let samples = log.read_all()?;
+        tracing::debug!(
+            "PublicationCache on {}: restoring {} publications from {}",
+            self.pub_key_expr,
+            samples.len(),
+            log.path.display()
+        );
+        let now = SystemTime::now();
+        for sample in samples {
+            // The restored publications are as old as their timestamp
+            let age = sample
+                .timestamp
+                .and_then(|t| now.duration_since(t.get_time().to_system_time()).ok())
+                .unwrap_or_default();
+            let time = Instant::now().checked_sub(age).unwrap_or_else(Instant::now);
+            self.insert(time, sample);
+        }
+        self.evict();
+        let samples = self.persisted();
+        if let Some(log) = self.log.as_mut() {
+            log.rewrite(&self.pub_key_expr, &samples);
+        }
+        Ok(())
+    }
+
+    // The publications kept when compacting the log
+    fn persisted(&self) -> Vec<Sample> {
+        // the tombstones are written first: the cached publications they include were removed
+        // when they were inserted, so the remaining ones are more recent and must survive their replay
+        self.tombstones
+            .values()
+            .chain(self.samples.values())
+            .flatten()
+            .map(|(_, s)| s.clone())
+            .collect()
+    }
+
+    fn is_expired(&self, time: &Instant) -> bool {
+        self.max_age.is_some_and(|max_age| time.elapsed() > max_age)
+    }
+
+    // Evict the publications older than max_age and the tombstones older than tombstone_retention
+    fn evict(&mut self) {
+        if let Some(retention) = self.tombstone_retention {
+            self.tombstones
+                .retain(|_, queue| queue.iter().all(|(t, _)| t.elapsed() <= retention));
+        }
+        let Some(max_age) = self.max_age else {
+            return;
+        };
+        let mut evicted = 0;
+        let mut memory = 0;
+        self.samples.retain(|_, queue| {
+            while queue.front().is_some_and(|(t, _)| t.elapsed() > max_age) {
+                if let Some((_, sample)) = queue.pop_front() {
+                    memory += sample_size(&sample);
+                    evicted += 1;
+                }
+            }
+            !queue.is_empty()
+        });
+        self.len -= evicted;
+        self.memory -= memory;
+        self.counters
+            .samples_evicted
+            .fetch_add(evicted as u64, Ordering::Relaxed);
+        self.update_counters();
+    }
+
+    async fn reply(&self, query: &Query) {
+        self.counters.queries_served.fetch_add(1, Ordering::Relaxed);
+        // resolve the `_time` parameter once, so that all the replies are selected in the same window
+        let time_range = match query.selector().time_range() {
+            Ok(time_range) => time_range.map(TimeRange::resolve),
+            Err(e) => {
+                let e = format!("Invalid {} parameter: {}", TIME_RANGE_KEY, e);
+                if let Err(e) = query
+                    .reply(Err(e.into()))
+                    .congestion_control(self.reply_congestion_control)
+                    .priority(self.reply_priority)
+                    .res_async()
+                    .await
+                {
+                    tracing::warn!("Error replying to query: {}", e);
+                }
+                return;
+            }
+        };
+        let time_range = time_range.as_ref();
+
+        if !query.selector().key_expr.as_str().contains('*') {
+            if let Some(queue) = self.samples.get(query.selector().key_expr.as_keyexpr()) {
+                self.reply_queue(query, time_range, queue).await;
+            }
+        } else {
+            for (key_expr, queue) in self.samples.iter() {
+                if query
+                    .selector()
+                    .key_expr
+                    .intersects(unsafe { keyexpr::from_str_unchecked(key_expr) })
+                {
+                    self.reply_queue(query, time_range, queue).await;
+                }
+            }
+        }
+        // the deletions on wildcard key expressions may concern any query
+        for (key_expr, queue) in self.tombstones.iter() {
+            if query.selector().key_expr.intersects(key_expr) {
+                self.reply_queue(query, time_range, queue).await;
+            }
+        }
+    }
+
+    async fn publish_stats(&self, session: &SessionRef<'static>, key_expr: &KeyExpr<'static>) {
+        let stats = serde_json::json!({
+            "key_expr": self.pub_key_expr.as_str(),
+            "stats": self.counters.snapshot(),
+        });
+        if let Err(e) = session.put(key_expr, stats).res_async().await {
+            tracing::warn!(
+                "PublicationCache on {}: error publishing stats: {}",
+                self.pub_key_expr,
+                e
+            );
+        }
+    }
+
+    async fn reply_queue(
+        &self,
+        query: &Query,
+        time_range: Option<&TimeRange<SystemTime>>,
+        queue: &VecDeque<(Instant, Sample)>,
+    ) {
+        for (time, sample) in queue {
+            if self.is_expired(time) {
+                continue;
+            }
+            if let Some(time_range) = time_range {
+                match sample.timestamp {
+                    Some(t) if time_range.contains(t.get_time().to_system_time()) => {}
+                    _ => continue,
+                }
+            }
+            if let Err(e) = query
+                .reply(Ok(sample.clone()))
+                .congestion_control(self.reply_congestion_control)
+                .priority(self.reply_priority)
+                .res_async()
+                .await
+            {
+                tracing::warn!("Error replying to query: {}", e);
+            }
+        }
+    }
+}
+
 pub struct PublicationCache<'a> {
     local_sub: FlumeSubscriber<'a>,
     _queryable: Queryable<'a, flume::Receiver<Query>>,
     task: TerminatableTask,
+    counters: Arc<CacheCounters>,
 }
 
 impl<'a> PublicationCache<'a> {
@@ -122,16 +664,17 @@ impl<'a> PublicationCache<'a> {
             match conf.queryable_prefix {
                 None => (None, key_expr.clone()),
                 Some(Ok(ke)) => {
-                    let queryable_key_expr = (&ke) / &key_expr;
+                    let queryable_key_expr = ke.checked_join(&key_expr)?;
                     (Some(ke.into()), queryable_key_expr)
                 }
                 Some(Err(e)) => bail!("Invalid key expression for queryable_prefix: {}", e),
             };
         tracing::debug!(
-            "Create PublicationCache on {} with history={} resource_limit={:?}",
+            "Create PublicationCache on {} with history={} resource_limit={:?} max_age={:?}",
             &key_expr,
             conf.history,
-            conf.resources_limit
+            conf.resources_limit,
+            conf.max_age
         );
 
         if conf.session.hlc().is_none() {
@@ -142,6 +685,25 @@ impl<'a> PublicationCache<'a> {
             )
         }
 
+        let counters = Arc::new(CacheCounters::default());
+        let mut cache = Cache {
+            pub_key_expr: key_expr.clone().into_owned(),
+            queryable_prefix,
+            history: conf.history,
+            limit: conf.resources_limit.unwrap_or(usize::MAX),
+            max_age: conf.max_age,
+            samples: HashMap::with_capacity(conf.resources_limit.unwrap_or(32)),
+            len: 0,
+            memory: 0,
+            tombstones: HashMap::new(),
+            tombstone_retention: conf.tombstone_retention,
+            log: conf.persistence.map(CacheLog::open).transpose()?,
+            reply_congestion_control: conf.reply_congestion_control,
+            reply_priority: conf.reply_priority,
+            counters: counters.clone(),
+        };
+        cache.restore()?;
+
         // declare the local subscriber that will store the local publications
         let local_sub = conf
             .session
@@ -162,9 +724,23 @@ impl<'a> PublicationCache<'a> {
         // take local ownership of stuff to be moved into task
         let sub_recv = local_sub.receiver.clone();
         let quer_recv = queryable.receiver.clone();
-        let pub_key_expr = key_expr.into_owned();
-        let resources_limit = conf.resources_limit;
-        let history = conf.history;
+        // evict the expired publications and tombstones often enough to not keep them much longer
+        // than max_age and tombstone_retention
+        let eviction_period = [conf.max_age, conf.tombstone_retention]
+            .into_iter()
+            .flatten()
+            .min()
+            .map(|age| (age / 2).max(Duration::from_millis(10)));
+        let (stats_period, stats_session) = conf.stats_publication.unzip();
+        let stats_key_expr = match &stats_session {
+            Some(session) => Some(KeyExpr::try_from(format!(
+                "{}/{}/{}/stats",
+                CACHE_STATS_KEY_SPACE,
+                session.zid(),
+                NEXT_CACHE_ID.fetch_add(1, Ordering::Relaxed)
+            ))?),
+            None => None,
+        };
 
         // TODO(yuyuan): use CancellationToken to manage it
         let token = TerminatableTask::create_cancellation_token();
@@ -172,70 +748,36 @@ impl<'a> PublicationCache<'a> {
         let task = TerminatableTask::spawn(
             zenoh_runtime::ZRuntime::Application,
             async move {
-                let mut cache: HashMap<OwnedKeyExpr, VecDeque<Sample>> =
-                    HashMap::with_capacity(resources_limit.unwrap_or(32));
-                let limit = resources_limit.unwrap_or(usize::MAX);
+                let mut eviction =
+                    tokio::time::interval(eviction_period.unwrap_or(Duration::from_secs(1)));
+                let mut stats =
+                    tokio::time::interval(stats_period.unwrap_or(Duration::from_secs(1)));
                 loop {
                     tokio::select! {
                         // on publication received by the local subscriber, store it
                         sample = sub_recv.recv_async() => {
                             if let Ok(sample) = sample {
-                                let queryable_key_expr: KeyExpr<'_> = if let Some(prefix) = &queryable_prefix {
-                                    prefix.join(&sample.key_expr).unwrap().into()
-                                } else {
-                                    sample.key_expr.clone()
-                                };
-
-                                if let Some(queue) = cache.get_mut(queryable_key_expr.as_keyexpr()) {
-                                    if queue.len() >= history {
-                                        queue.pop_front();
-                                    }
-                                    queue.push_back(sample);
-                                } else if cache.len() >= limit {
-                                    tracing::error!("PublicationCache on {}: resource_limit exceeded - can't cache publication for a new resource",
-                                    pub_key_expr);
-                                } else {
-                                    let mut queue: VecDeque<Sample> = VecDeque::new();
-                                    queue.push_back(sample);
-                                    cache.insert(queryable_key_expr.into(), queue);
-                                }
+                                cache.store(sample).await;
                             }
                         },
 
                         // on query, reply with cach content
                         query = quer_recv.recv_async() => {
                             if let Ok(query) = query {
-                                if !query.selector().key_expr.as_str().contains('*') {
-                                    if let Some(queue) = cache.get(query.selector().key_expr.as_keyexpr()) {
-                                        for sample in queue {
-                                            if let (Ok(Some(time_range)), Some(timestamp)) = (query.selector().time_range(), sample.timestamp) {
-                                                if !time_range.contains(timestamp.get_time().to_system_time()){
-                                                    continue;
-                                                }
-                                            }
-                                            if let Err(e) = query.reply(Ok(sample.clone())).res_async().await {
-                                                tracing::warn!("Error replying to query: {}", e);
-                                            }
-                                        }
-                                    }
-                                } else {
-                                    for (key_expr, queue) in cache.iter() {
-                                        if query.selector().key_expr.intersects(unsafe{ keyexpr::from_str_unchecked(key_expr) }) {
-                                            for sample in queue {
-                                                if let (Ok(Some(time_range)), Some(timestamp)) = (query.selector().time_range(), sample.timestamp) {
-                                                    if !time_range.contains(timestamp.get_time().to_system_time()){
-                                                        continue;
-                                                    }
-                                                }
-                                                if let Err(e) = query.reply(Ok(sample.clone())).res_async().await {
-                                                    tracing::warn!("Error replying to query: {}", e);
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
+                                cache.reply(&query).await;
                             }
                         },
+
+                        // periodically evict the expired publications
+                        _ = eviction.tick(), if eviction_period.is_some() => cache.evict(),
+
+                        // periodically publish the statistics of the cache
+                        _ = stats.tick(), if stats_session.is_some() => {
+                            if let (Some(session), Some(key_expr)) = (&stats_session, &stats_key_expr) {
+                                cache.publish_stats(session, key_expr).await;
+                            }
+                        },
+
                         _ = token2.cancelled() => return
                     }
                 }
@@ -247,6 +789,7 @@ impl<'a> PublicationCache<'a> {
             local_sub,
             _queryable: queryable,
             task,
+            counters,
         })
     }
 
@@ -258,6 +801,7 @@ impl<'a> PublicationCache<'a> {
                 _queryable,
                 local_sub,
                 task,
+                ..
             } = self;
             _queryable.undeclare().res_async().await?;
             local_sub.undeclare().res_async().await?;
@@ -269,4 +813,9 @@ impl<'a> PublicationCache<'a> {
     pub fn key_expr(&self) -> &KeyExpr<'static> {
         self.local_sub.key_expr()
     }
+
+    /// Returns the current statistics of this PublicationCache.
+    pub fn stats(&self) -> PublicationCacheStats {
+        self.counters.snapshot()
+    }
 }
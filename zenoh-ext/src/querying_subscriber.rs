@@ -27,6 +27,11 @@ use zenoh::SessionRef;
 use zenoh_core::{zlock, AsyncResolve, Resolvable, SyncResolve};
 
 /// The builder of [`FetchingSubscriber`], allowing to configure it.
+///
+/// At declaration, the subscriber queries the history of publications (by default on its own key expression,
+/// see [`query_prefix`](QueryingSubscriberBuilder::query_prefix) and [`query_selector`](QueryingSubscriberBuilder::query_selector)).
+/// The replies and the publications received meanwhile are merged: they are delivered sorted by timestamp,
+/// and the samples with the same timestamp are delivered only once.
 #[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
 pub struct QueryingSubscriberBuilder<'a, 'b, KeySpace, Handler> {
     pub(crate) session: SessionRef<'a>,
@@ -35,6 +40,7 @@ pub struct QueryingSubscriberBuilder<'a, 'b, KeySpace, Handler> {
     pub(crate) reliability: Reliability,
     pub(crate) origin: Locality,
     pub(crate) query_selector: Option<ZResult<Selector<'b>>>,
+    pub(crate) query_prefix: Option<ZResult<KeyExpr<'b>>>,
     pub(crate) query_target: QueryTarget,
     pub(crate) query_consolidation: QueryConsolidation,
     pub(crate) query_accept_replies: ReplyKeyExpr,
@@ -42,6 +48,33 @@ pub struct QueryingSubscriberBuilder<'a, 'b, KeySpace, Handler> {
     pub(crate) handler: Handler,
 }
 
+impl<'a, 'b, Handler> QueryingSubscriberBuilder<'a, 'b, crate::UserSpace, Handler> {
+    // The builder with the defaults of the querying subscribers in the user key space
+    pub(crate) fn new(
+        session: SessionRef<'a>,
+        key_expr: ZResult<KeyExpr<'b>>,
+        handler: Handler,
+    ) -> QueryingSubscriberBuilder<'a, 'b, crate::UserSpace, Handler> {
+        QueryingSubscriberBuilder {
+            session,
+            key_expr,
+            key_space: crate::UserSpace,
+            reliability: Reliability::default(),
+            origin: Locality::default(),
+            query_selector: None,
+            query_prefix: None,
+            // By default query all matching publication caches and storages
+            query_target: QueryTarget::All,
+            // By default no query consolidation, to receive more than 1 sample per-resource
+            // (if history of publications is available)
+            query_consolidation: QueryConsolidation::from(zenoh::query::ConsolidationMode::None),
+            query_accept_replies: ReplyKeyExpr::default(),
+            query_timeout: Duration::from_secs(10),
+            handler,
+        }
+    }
+}
+
 impl<'a, 'b, KeySpace> QueryingSubscriberBuilder<'a, 'b, KeySpace, DefaultHandler> {
     /// Add callback to [`FetchingSubscriber`].
     #[inline]
@@ -59,6 +92,7 @@ impl<'a, 'b, KeySpace> QueryingSubscriberBuilder<'a, 'b, KeySpace, DefaultHandle
             reliability,
             origin,
             query_selector,
+            query_prefix,
             query_target,
             query_consolidation,
             query_accept_replies,
@@ -72,6 +106,7 @@ impl<'a, 'b, KeySpace> QueryingSubscriberBuilder<'a, 'b, KeySpace, DefaultHandle
             reliability,
             origin,
             query_selector,
+            query_prefix,
             query_target,
             query_consolidation,
             query_accept_replies,
@@ -112,6 +147,7 @@ impl<'a, 'b, KeySpace> QueryingSubscriberBuilder<'a, 'b, KeySpace, DefaultHandle
             reliability,
             origin,
             query_selector,
+            query_prefix,
             query_target,
             query_consolidation,
             query_accept_replies,
@@ -125,6 +161,7 @@ impl<'a, 'b, KeySpace> QueryingSubscriberBuilder<'a, 'b, KeySpace, DefaultHandle
             reliability,
             origin,
             query_selector,
+            query_prefix,
             query_target,
             query_consolidation,
             query_accept_replies,
@@ -176,6 +213,21 @@ impl<'a, 'b, Handler> QueryingSubscriberBuilder<'a, 'b, crate::UserSpace, Handle
         self
     }
 
+    /// Query the history on `<query_prefix>/<key_expr>`, i.e. from the
+    /// [`PublicationCache`](crate::PublicationCache)s declared with the same
+    /// [`queryable_prefix`](crate::PublicationCacheBuilder::queryable_prefix).
+    /// Ignored if a [`query_selector`](QueryingSubscriberBuilder::query_selector) is set.
+    /// The replies are accepted whatever their key expression, as they are on `<key_expr>`.
+    #[inline]
+    pub fn query_prefix<TryIntoKeyExpr>(mut self, query_prefix: TryIntoKeyExpr) -> Self
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        self.query_prefix = Some(query_prefix.try_into().map_err(Into::into));
+        self
+    }
+
     /// Change the target to be used for queries.
     #[inline]
     pub fn query_target(mut self, query_target: QueryTarget) -> Self {
@@ -228,13 +280,15 @@ where
         let session = self.session.clone();
         let key_expr = self.key_expr?;
         let key_space = self.key_space.clone().into();
-        let query_selector = match self.query_selector {
-            Some(s) => Some(s?),
-            None => None,
+        let (query_selector, query_accept_replies) = match (self.query_selector, self.query_prefix)
+        {
+            (Some(s), _) => (Some(s?), self.query_accept_replies),
+            // The replies to <query_prefix>/<key_expr> are on <key_expr>, outside of the query
+            (None, Some(prefix)) => (Some(prefix?.join(&key_expr)?.into()), ReplyKeyExpr::Any),
+            (None, None) => (None, self.query_accept_replies),
         };
         let query_target = self.query_target;
         let query_consolidation = self.query_consolidation;
-        let query_accept_replies = self.query_accept_replies;
         let query_timeout = self.query_timeout;
         FetchingSubscriberBuilder {
             session: self.session,
@@ -0,0 +1,310 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use zenoh::handlers::{Callback, IntoCallbackReceiverPair};
+use zenoh::prelude::{OwnedKeyExpr, Sample, SampleKind};
+use zenoh_core::zlock;
+
+// The longest period between two samples of a key, for a tiny max_hz
+const RATE_LIMIT_MAX_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The samples forwarded by a [`RateLimit`] for a key receiving more samples than allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateLimitStrategy {
+    /// Coalesce the samples: the latest sample received during a period is forwarded at
+    /// the end of the period, so that the freshest value is always delivered.
+    #[default]
+    Latest,
+    /// Forward the first sample received in a period and drop the others, but for a trailing
+    /// [`SampleKind::Delete`](zenoh::prelude::SampleKind::Delete) forwarded at the end of the period.
+    First,
+}
+
+/// A handler wrapper forwarding at most `max_hz` samples per second and per key expression
+/// to the wrapped handler, e.g. for a UI not able to render every update of a fast publisher.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+/// use zenoh_ext::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let subscriber = session
+///     .declare_subscriber("sensors/**")
+///     .with(RateLimit::new(
+///         30.0,
+///         RateLimitStrategy::Latest,
+///         |sample: Sample| println!("Received: {:?}", sample),
+///     ))
+///     .res()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+pub struct RateLimit<Handler> {
+    period: Duration,
+    strategy: RateLimitStrategy,
+    handler: Handler,
+}
+
+impl<Handler> RateLimit<Handler> {
+    /// Forward at most `max_hz` samples per second and per key expression to `handler`.
+    /// The samples are not limited if `max_hz` is not strictly positive, and at least one sample
+    /// per day and per key expression is forwarded whatever `max_hz`.
+    pub fn new(max_hz: f64, strategy: RateLimitStrategy, handler: Handler) -> Self {
+        let period = match max_hz > 0.0 {
+            true => Duration::try_from_secs_f64(1.0 / max_hz)
+                .map_or(RATE_LIMIT_MAX_PERIOD, |p| p.min(RATE_LIMIT_MAX_PERIOD)),
+            false => Duration::ZERO,
+        };
+        RateLimit {
+            period,
+            strategy,
+            handler,
+        }
+    }
+}
+
+#[derive(Default)]
+struct KeyState {
+    // The time the last sample was forwarded
+    forwarded: Option<Instant>,
+    // The sample to forward at the end of the period
+    pending: Option<Sample>,
+}
+
+#[derive(Default)]
+struct Keys {
+    states: HashMap<OwnedKeyExpr, KeyState>,
+    // The keys by the time their last sample was forwarded, oldest first
+    forwarded: VecDeque<(Instant, OwnedKeyExpr)>,
+}
+
+impl Keys {
+    fn forward(&mut self, key: &OwnedKeyExpr, now: Instant) {
+        if let Some(state) = self.states.get_mut(key) {
+            state.forwarded = Some(now);
+            self.forwarded.push_back((now, key.clone()));
+        }
+    }
+
+    // Forget the keys with no sample forwarded in the last period nor pending
+    fn prune(&mut self, now: Instant, period: Duration) {
+        while let Some((time, key)) = self.forwarded.front() {
+            if now.duration_since(*time) < period {
+                break;
+            }
+            if let Some(state) = self.states.get(key) {
+                if state.forwarded == Some(*time) && state.pending.is_none() {
+                    self.states.remove(key);
+                }
+            }
+            self.forwarded.pop_front();
+        }
+    }
+}
+
+struct RateLimiter {
+    period: Duration,
+    strategy: RateLimitStrategy,
+    callback: Callback<'static, Sample>,
+    keys: Mutex<Keys>,
+}
+
+impl RateLimiter {
+    fn on_sample(self: &Arc<Self>, sample: Sample) {
+        let now = Instant::now();
+        let key: OwnedKeyExpr = sample.key_expr.clone().into();
+        let mut keys = zlock!(self.keys);
+        keys.prune(now, self.period);
+        let state = keys.states.entry(key.clone()).or_default();
+        let elapsed = state
+            .forwarded
+            .map_or(true, |t| now.duration_since(t) >= self.period);
+        if elapsed && state.pending.is_none() {
+            keys.forward(&key, now);
+            drop(keys);
+            (self.callback)(sample);
+            return;
+        }
+        // A Delete is never dropped, nor the samples following it, so that the last state of the
+        // key is always delivered
+        if self.strategy == RateLimitStrategy::First
+            && sample.kind != SampleKind::Delete
+            && state.pending.is_none()
+        {
+            return;
+        }
+        // A task is already scheduled to forward the pending sample
+        if state.pending.replace(sample).is_some() {
+            return;
+        }
+        let deadline = state.forwarded.map_or(now, |t| t + self.period);
+        drop(keys);
+        let limiter = self.clone();
+        zenoh_runtime::ZRuntime::Application.spawn(async move {
+            tokio::time::sleep_until(deadline.into()).await;
+            limiter.flush(&key);
+        });
+    }
+
+    fn flush(&self, key: &OwnedKeyExpr) {
+        let mut keys = zlock!(self.keys);
+        let sample = keys
+            .states
+            .get_mut(key)
+            .and_then(|state| state.pending.take());
+        if sample.is_some() {
+            keys.forward(key, Instant::now());
+        }
+        drop(keys);
+        if let Some(sample) = sample {
+            (self.callback)(sample);
+        }
+    }
+}
+
+impl<Handler> IntoCallbackReceiverPair<'static, Sample> for RateLimit<Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample>,
+{
+    type Receiver = Handler::Receiver;
+
+    fn into_cb_receiver_pair(self) -> (Callback<'static, Sample>, Self::Receiver) {
+        let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        if self.period.is_zero() {
+            return (callback, receiver);
+        }
+        let limiter = Arc::new(RateLimiter {
+            period: self.period,
+            strategy: self.strategy,
+            callback,
+            keys: Mutex::new(Keys::default()),
+        });
+        (Arc::new(move |sample| limiter.on_sample(sample)), receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+    use zenoh::prelude::KeyExpr;
+
+    const PERIOD: Duration = Duration::from_millis(200);
+
+    fn limiter(strategy: RateLimitStrategy) -> (Arc<RateLimiter>, Arc<Mutex<Vec<String>>>) {
+        let forwarded = Arc::new(Mutex::new(vec![]));
+        let c_forwarded = forwarded.clone();
+        let limiter = Arc::new(RateLimiter {
+            period: PERIOD,
+            strategy,
+            callback: Arc::new(move |sample: Sample| {
+                let value = match sample.kind {
+                    SampleKind::Put => sample.value.to_string(),
+                    SampleKind::Delete => "-".to_string(),
+                };
+                zlock!(c_forwarded).push(format!("{}={}", sample.key_expr, value));
+            }),
+            keys: Mutex::new(Keys::default()),
+        });
+        (limiter, forwarded)
+    }
+
+    fn put(key_expr: &str, value: &str) -> Sample {
+        Sample::new(KeyExpr::try_from(key_expr.to_string()).unwrap(), value)
+    }
+
+    fn delete(key_expr: &str) -> Sample {
+        let mut sample = put(key_expr, "");
+        sample.kind = SampleKind::Delete;
+        sample
+    }
+
+    #[test]
+    fn rate_limit_latest() {
+        let (limiter, forwarded) = limiter(RateLimitStrategy::Latest);
+        for value in ["1", "2", "3"] {
+            limiter.on_sample(put("a", value));
+        }
+        limiter.on_sample(put("b", "1"));
+        assert_eq!(*zlock!(forwarded), ["a=1", "b=1"]);
+
+        std::thread::sleep(3 * PERIOD);
+        assert_eq!(*zlock!(forwarded), ["a=1", "b=1", "a=3"]);
+    }
+
+    #[test]
+    fn rate_limit_first() {
+        let (limiter, forwarded) = limiter(RateLimitStrategy::First);
+        for value in ["1", "2", "3"] {
+            limiter.on_sample(put("a", value));
+        }
+        std::thread::sleep(3 * PERIOD);
+        assert_eq!(*zlock!(forwarded), ["a=1"]);
+
+        // A trailing Delete is forwarded at the end of the period
+        limiter.on_sample(put("a", "4"));
+        limiter.on_sample(put("a", "5"));
+        limiter.on_sample(delete("a"));
+        assert_eq!(*zlock!(forwarded), ["a=1", "a=4"]);
+        std::thread::sleep(3 * PERIOD);
+        assert_eq!(*zlock!(forwarded), ["a=1", "a=4", "a=-"]);
+
+        // As well as the samples following it
+        limiter.on_sample(put("a", "6"));
+        limiter.on_sample(delete("a"));
+        limiter.on_sample(put("a", "7"));
+        std::thread::sleep(3 * PERIOD);
+        assert_eq!(*zlock!(forwarded), ["a=1", "a=4", "a=-", "a=6", "a=7"]);
+    }
+
+    #[test]
+    fn rate_limit_prune() {
+        let (limiter, forwarded) = limiter(RateLimitStrategy::Latest);
+        for i in 0..1_000 {
+            limiter.on_sample(put(&format!("a/{i}"), "1"));
+        }
+        limiter.on_sample(put("a/0", "2"));
+        assert_eq!(zlock!(limiter.keys).states.len(), 1_000);
+
+        // The idle keys are forgotten, but the one with a pending sample
+        std::thread::sleep(PERIOD / 2);
+        limiter.on_sample(put("b", "1"));
+        assert_eq!(zlock!(limiter.keys).states.len(), 1_001);
+        std::thread::sleep(PERIOD * 3 / 4);
+        limiter.on_sample(put("c", "1"));
+        let keys = zlock!(limiter.keys);
+        let mut states: Vec<_> = keys.states.keys().map(|k| k.to_string()).collect();
+        states.sort();
+        assert_eq!(states, ["a/0", "b", "c"]);
+        assert_eq!(keys.forwarded.len(), 3);
+        assert_eq!(zlock!(forwarded).len(), 1_003);
+    }
+
+    #[test]
+    fn rate_limit_period() {
+        let period = |max_hz| RateLimit::new(max_hz, RateLimitStrategy::Latest, ()).period;
+        assert_eq!(period(10.0), Duration::from_millis(100));
+        assert_eq!(period(0.0), Duration::ZERO);
+        assert_eq!(period(-1.0), Duration::ZERO);
+        assert_eq!(period(f64::NAN), Duration::ZERO);
+        assert_eq!(period(f64::MIN_POSITIVE), RATE_LIMIT_MAX_PERIOD);
+        assert_eq!(period(1e-9), RATE_LIMIT_MAX_PERIOD);
+    }
+}
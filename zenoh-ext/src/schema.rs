@@ -0,0 +1,554 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::Deserialize;
+use serde_json::Value as Json;
+use std::collections::HashMap;
+use std::convert::TryInto;
+#[cfg(feature = "protobuf")]
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use zenoh::prelude::r#async::*;
+use zenoh::publication::{Publication, Publisher, PutBuilder};
+use zenoh::sample::Attachment;
+use zenoh::subscriber::Subscriber;
+use zenoh::Session;
+use zenoh_core::{zread, zwrite};
+use zenoh_result::{bail, zerror, ZResult};
+
+/// The reserved key space where schemas can be published, see [`SchemaRegistry::listen`].
+/// A schema published on `@schemas/<key_expr>` is registered for `<key_expr>`, and unregistered
+/// when deleted.
+pub const SCHEMAS_KEY_SPACE: &str = "@schemas";
+
+/// The attachment key of the validation error of a sample tagged by [`ValidationPolicy::Tag`].
+pub const SCHEMA_ERROR_ATTACHMENT: &str = "schema_error";
+
+/// What to do with the payloads not matching their schema.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationPolicy {
+    /// Reject the outgoing publications and drop the incoming samples.
+    #[default]
+    Reject,
+    /// Let the payload through with the validation error attached under [`SCHEMA_ERROR_ATTACHMENT`].
+    Tag,
+    /// Let the payload through after logging the validation error.
+    Log,
+}
+
+/// The configuration of a [`SchemaRegistry`], typically deserialized from a JSON5 file, e.g.:
+/// ```json5
+/// {
+///   policy: "reject",
+///   schemas: {
+///     "robot/*/pose": { type: "object", required: ["x", "y"], properties: { x: { type: "number" }, y: { type: "number" } } },
+///   },
+///   // With the "protobuf" feature
+///   protobuf: {
+///     "robot/*/state": { descriptor_set: "robot.desc", message: "robot.State" },
+///   },
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SchemaRegistryConf {
+    #[serde(default)]
+    pub policy: ValidationPolicy,
+    #[serde(default)]
+    pub schemas: HashMap<String, Json>,
+    #[cfg(feature = "protobuf")]
+    #[serde(default)]
+    pub protobuf: HashMap<String, ProtobufSchemaConf>,
+}
+
+/// A Protobuf message type, see [`SchemaRegistry::register_protobuf`].
+#[cfg(feature = "protobuf")]
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProtobufSchemaConf {
+    /// The path of a file descriptor set (e.g. generated with `protoc --descriptor_set_out`).
+    pub descriptor_set: PathBuf,
+    /// The fully qualified name of the message type in the descriptor set.
+    pub message: String,
+}
+
+// A schema registered for a key expression
+enum Schema {
+    Json(Json),
+    #[cfg(feature = "protobuf")]
+    Protobuf(prost_reflect::MessageDescriptor),
+}
+
+/// A registry of schemas per key expression, validating the payloads published or received on them.
+///
+/// The payloads published or received on a key expression included in a registered one must match its
+/// schema: either a JSON document matching a JSON schema, or, with the `protobuf` feature, a Protobuf
+/// message of a given type. The supported subset of JSON Schema is: `type`, `enum`, `const`,
+/// `minimum`, `maximum`, `minLength`, `maxLength`, `properties`, `required`, `additionalProperties`,
+/// `items`, `minItems` and `maxItems`. The payloads published or received on the other key expressions
+/// are not validated.
+///
+/// The publications are validated with [`put`](Self::put) on a session or with
+/// [`PublisherSchemaExt::put_checked`] on a publisher, the received samples with
+/// [`validating`](Self::validating).
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use std::sync::Arc;
+/// use zenoh::prelude::r#async::*;
+/// use zenoh_ext::{SchemaRegistry, ValidationPolicy};
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let registry = Arc::new(SchemaRegistry::new(ValidationPolicy::Reject));
+/// registry
+///     .register("robot/*/speed", serde_json::json!({ "type": "number", "minimum": 0 }))
+///     .unwrap();
+///
+/// let subscriber = session
+///     .declare_subscriber("robot/**")
+///     .callback(registry.validating(|sample| println!("Received {}", sample.key_expr)))
+///     .res()
+///     .await
+///     .unwrap();
+///
+/// assert!(registry.put(&session, "robot/1/speed", "-1").is_err());
+/// registry.put(&session, "robot/1/speed", "1").unwrap().res().await.unwrap();
+/// # }
+/// ```
+pub struct SchemaRegistry {
+    policy: ValidationPolicy,
+    schemas: RwLock<HashMap<OwnedKeyExpr, Arc<Schema>>>,
+}
+
+impl SchemaRegistry {
+    pub fn new(policy: ValidationPolicy) -> SchemaRegistry {
+        SchemaRegistry {
+            policy,
+            schemas: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Create a registry with the policy and the schemas of the given configuration.
+    pub fn from_config(conf: SchemaRegistryConf) -> ZResult<SchemaRegistry> {
+        let registry = SchemaRegistry::new(conf.policy);
+        for (key_expr, schema) in conf.schemas {
+            registry.register(key_expr, schema)?;
+        }
+        #[cfg(feature = "protobuf")]
+        for (key_expr, schema) in conf.protobuf {
+            let descriptor_set = std::fs::read(&schema.descriptor_set).map_err(|e| {
+                zerror!(
+                    "Failed to read the descriptor set {}: {}",
+                    schema.descriptor_set.display(),
+                    e
+                )
+            })?;
+            registry.register_protobuf(key_expr, &descriptor_set, &schema.message)?;
+        }
+        Ok(registry)
+    }
+
+    pub fn policy(&self) -> ValidationPolicy {
+        self.policy
+    }
+
+    /// Register the JSON schema of the payloads published on the given key expression,
+    /// replacing the schema previously registered for it, if any.
+    pub fn register<TryIntoKeyExpr>(&self, key_expr: TryIntoKeyExpr, schema: Json) -> ZResult<()>
+    where
+        TryIntoKeyExpr: TryInto<OwnedKeyExpr>,
+        <TryIntoKeyExpr as TryInto<OwnedKeyExpr>>::Error: Into<zenoh_result::Error>,
+    {
+        let key_expr = key_expr
+            .try_into()
+            .map_err(Into::<zenoh_result::Error>::into)?;
+        if !schema.is_object() && !schema.is_boolean() {
+            bail!("Invalid schema for {}: {}", key_expr, schema);
+        }
+        tracing::debug!("Register schema for {}", key_expr);
+        zwrite!(self.schemas).insert(key_expr, Arc::new(Schema::Json(schema)));
+        Ok(())
+    }
+
+    /// Register the Protobuf message type of the payloads published on the given key expression,
+    /// replacing the schema previously registered for it, if any. `message` is the fully qualified
+    /// name of the type in the encoded file descriptor set `descriptor_set`.
+    #[cfg(feature = "protobuf")]
+    pub fn register_protobuf<TryIntoKeyExpr>(
+        &self,
+        key_expr: TryIntoKeyExpr,
+        descriptor_set: &[u8],
+        message: &str,
+    ) -> ZResult<()>
+    where
+        TryIntoKeyExpr: TryInto<OwnedKeyExpr>,
+        <TryIntoKeyExpr as TryInto<OwnedKeyExpr>>::Error: Into<zenoh_result::Error>,
+    {
+        let key_expr = key_expr
+            .try_into()
+            .map_err(Into::<zenoh_result::Error>::into)?;
+        let pool = prost_reflect::DescriptorPool::decode(descriptor_set)
+            .map_err(|e| zerror!("Invalid descriptor set for {}: {}", key_expr, e))?;
+        let descriptor = pool
+            .get_message_by_name(message)
+            .ok_or_else(|| zerror!("Unknown message type {} for {}", message, key_expr))?;
+        tracing::debug!("Register Protobuf schema {} for {}", message, key_expr);
+        zwrite!(self.schemas).insert(key_expr, Arc::new(Schema::Protobuf(descriptor)));
+        Ok(())
+    }
+
+    /// Unregister the schema of the given key expression.
+    pub fn unregister(&self, key_expr: &keyexpr) -> bool {
+        zwrite!(self.schemas).remove(key_expr).is_some()
+    }
+
+    /// Validate a payload published on the given key expression against the schemas of the
+    /// registered key expressions including it.
+    pub fn validate(&self, key_expr: &keyexpr, payload: &[u8]) -> ZResult<()> {
+        let schemas: Vec<Arc<Schema>> = zread!(self.schemas)
+            .iter()
+            .filter(|(k, _)| k.includes(key_expr))
+            .map(|(_, s)| s.clone())
+            .collect();
+        if schemas.is_empty() {
+            return Ok(());
+        }
+        // The payload is parsed once for all the JSON schemas
+        let json: Option<Json> = match schemas.iter().any(|s| matches!(**s, Schema::Json(_))) {
+            true => Some(
+                serde_json::from_slice(payload)
+                    .map_err(|e| zerror!("Payload on {} is not valid JSON: {}", key_expr, e))?,
+            ),
+            false => None,
+        };
+        for schema in schemas {
+            let res = match &*schema {
+                Schema::Json(schema) => json
+                    .as_ref()
+                    .map_or(Ok(()), |json| validate(schema, json, "")),
+                #[cfg(feature = "protobuf")]
+                Schema::Protobuf(descriptor) => {
+                    prost_reflect::DynamicMessage::decode(descriptor.clone(), payload)
+                        .map(|_| ())
+                        .map_err(|e| format!("invalid {} message: {}", descriptor.full_name(), e))
+                }
+            };
+            if let Err(e) = res {
+                bail!("Payload on {} doesn't match its schema: {}", key_expr, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Check a value to be published on the given key expression, applying the policy on mismatch:
+    /// returns an error with [`ValidationPolicy::Reject`], and the [`Attachment`] to publish the value with
+    /// with [`ValidationPolicy::Tag`].
+    pub fn check<'a, TryIntoKeyExpr>(
+        &self,
+        key_expr: TryIntoKeyExpr,
+        value: &Value,
+    ) -> ZResult<Option<Attachment>>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'a>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'a>>>::Error: Into<zenoh_result::Error>,
+    {
+        let key_expr = key_expr
+            .try_into()
+            .map_err(Into::<zenoh_result::Error>::into)?;
+        match self.validate(&key_expr, &value.payload.contiguous()) {
+            Ok(()) => Ok(None),
+            Err(e) => match self.policy {
+                ValidationPolicy::Reject => Err(e),
+                ValidationPolicy::Tag => {
+                    let mut attachment = Attachment::new();
+                    attachment.insert(SCHEMA_ERROR_ATTACHMENT, &e.to_string());
+                    Ok(Some(attachment))
+                }
+                ValidationPolicy::Log => {
+                    tracing::warn!("{}", e);
+                    Ok(None)
+                }
+            },
+        }
+    }
+
+    /// Put a value on the given key expression once checked with [`check`](Self::check):
+    /// returns an error if the value is rejected, and the builder of the publication otherwise.
+    pub fn put<'a, 'b: 'a, TryIntoKeyExpr, IntoValue>(
+        &self,
+        session: &'a Session,
+        key_expr: TryIntoKeyExpr,
+        value: IntoValue,
+    ) -> ZResult<PutBuilder<'a, 'b>>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+        IntoValue: Into<Value>,
+    {
+        let key_expr = key_expr
+            .try_into()
+            .map_err(Into::<zenoh_result::Error>::into)?;
+        let value = value.into();
+        let attachment = self.check(&key_expr, &value)?;
+        let put = session.put(key_expr, value);
+        Ok(match attachment {
+            Some(attachment) => put.with_attachment(attachment),
+            None => put,
+        })
+    }
+
+    /// Validate a received sample, applying the policy on mismatch: returns `None` if the sample
+    /// must be dropped.
+    pub fn intercept(&self, mut sample: Sample) -> Option<Sample> {
+        match self.validate(&sample.key_expr, &sample.value.payload.contiguous()) {
+            Ok(()) => Some(sample),
+            Err(e) => match self.policy {
+                ValidationPolicy::Reject => {
+                    tracing::debug!("Dropping sample: {}", e);
+                    None
+                }
+                ValidationPolicy::Tag => {
+                    let mut attachment = sample.attachment.take().unwrap_or_default();
+                    attachment.insert(SCHEMA_ERROR_ATTACHMENT, &e.to_string());
+                    sample.attachment = Some(attachment);
+                    Some(sample)
+                }
+                ValidationPolicy::Log => {
+                    tracing::warn!("{}", e);
+                    Some(sample)
+                }
+            },
+        }
+    }
+
+    /// Wrap a subscriber callback so that it only receives the samples let through by [`intercept`](Self::intercept).
+    pub fn validating<F>(self: &Arc<Self>, callback: F) -> impl Fn(Sample) + Send + Sync + 'static
+    where
+        F: Fn(Sample) + Send + Sync + 'static,
+    {
+        let registry = self.clone();
+        move |sample| {
+            if let Some(sample) = registry.intercept(sample) {
+                callback(sample)
+            }
+        }
+    }
+
+    /// Keep the registry in sync with the schemas published in the [`SCHEMAS_KEY_SPACE`] until the
+    /// returned subscriber is undeclared.
+    pub async fn listen<'a>(self: &Arc<Self>, session: &'a Session) -> ZResult<Subscriber<'a, ()>> {
+        let registry = self.clone();
+        let prefix = format!("{SCHEMAS_KEY_SPACE}/");
+        session
+            .declare_subscriber(format!("{SCHEMAS_KEY_SPACE}/**"))
+            .callback(move |sample| {
+                let Some(key_expr) = sample.key_expr.as_str().strip_prefix(&prefix) else {
+                    return;
+                };
+                let res = match sample.kind {
+                    SampleKind::Put => serde_json::from_slice(&sample.value.payload.contiguous())
+                        .map_err(Into::into)
+                        .and_then(|schema| registry.register(key_expr, schema)),
+                    SampleKind::Delete => keyexpr::new(key_expr).map(|k| {
+                        registry.unregister(k);
+                    }),
+                };
+                if let Err(e) = res {
+                    tracing::warn!("Invalid schema published on {}: {}", sample.key_expr, e);
+                }
+            })
+            .res_async()
+            .await
+    }
+}
+
+/// Allows writing `publisher.put_checked(&registry, value)` to validate the publications of a
+/// publisher against the schemas of a [`SchemaRegistry`].
+pub trait PublisherSchemaExt {
+    /// Put a value once checked with [`SchemaRegistry::check`]: returns an error if the value
+    /// is rejected, and the publication otherwise.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh_ext::{PublisherSchemaExt, SchemaRegistry, ValidationPolicy};
+    ///
+    /// let registry = SchemaRegistry::new(ValidationPolicy::Reject);
+    /// registry
+    ///     .register("robot/*/speed", serde_json::json!({ "type": "number" }))
+    ///     .unwrap();
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let publisher = session.declare_publisher("robot/1/speed").res().await.unwrap();
+    /// publisher.put_checked(&registry, "1.5").unwrap().res().await.unwrap();
+    /// # }
+    /// ```
+    fn put_checked<IntoValue>(
+        &self,
+        registry: &SchemaRegistry,
+        value: IntoValue,
+    ) -> ZResult<Publication>
+    where
+        IntoValue: Into<Value>;
+}
+
+impl PublisherSchemaExt for Publisher<'_> {
+    fn put_checked<IntoValue>(
+        &self,
+        registry: &SchemaRegistry,
+        value: IntoValue,
+    ) -> ZResult<Publication>
+    where
+        IntoValue: Into<Value>,
+    {
+        let value = value.into();
+        let attachment = registry.check(self.key_expr(), &value)?;
+        let put = self.put(value);
+        Ok(match attachment {
+            Some(attachment) => put.with_attachment(attachment),
+            None => put,
+        })
+    }
+}
+
+fn json_type(value: &Json) -> &'static str {
+    match value {
+        Json::Null => "null",
+        Json::Bool(_) => "boolean",
+        // As per JSON Schema, a number with a zero fractional part (e.g. 1.0) is an integer
+        Json::Number(n)
+            if n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0) =>
+        {
+            "integer"
+        }
+        Json::Number(_) => "number",
+        Json::String(_) => "string",
+        Json::Array(_) => "array",
+        Json::Object(_) => "object",
+    }
+}
+
+fn has_type(value: &Json, ty: &str) -> bool {
+    let actual = json_type(value);
+    actual == ty || (ty == "number" && actual == "integer")
+}
+
+// Validate a JSON value against a schema, returning the path and the reason of the first mismatch
+fn validate(schema: &Json, value: &Json, path: &str) -> Result<(), String> {
+    let path = if path.is_empty() { "$" } else { path };
+    let schema = match schema {
+        Json::Bool(true) => return Ok(()),
+        Json::Bool(false) => return Err(format!("{path}: no value allowed")),
+        Json::Object(schema) => schema,
+        _ => return Err(format!("{path}: invalid schema")),
+    };
+
+    if let Some(ty) = schema.get("type") {
+        let ok = match ty {
+            Json::String(ty) => has_type(value, ty),
+            Json::Array(tys) => tys
+                .iter()
+                .any(|t| t.as_str().is_some_and(|t| has_type(value, t))),
+            _ => false,
+        };
+        if !ok {
+            return Err(format!(
+                "{path}: expected type {ty}, found {}",
+                json_type(value)
+            ));
+        }
+    }
+    if let Some(Json::Array(values)) = schema.get("enum") {
+        if !values.contains(value) {
+            return Err(format!("{path}: {value} is not one of {values:?}"));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            return Err(format!("{path}: expected {expected}, found {value}"));
+        }
+    }
+
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(Json::as_f64) {
+            if n < min {
+                return Err(format!("{path}: {n} is lower than {min}"));
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(Json::as_f64) {
+            if n > max {
+                return Err(format!("{path}: {n} is greater than {max}"));
+            }
+        }
+    }
+
+    if let Some(s) = value.as_str() {
+        let len = s.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(Json::as_u64) {
+            if len < min {
+                return Err(format!("{path}: string shorter than {min}"));
+            }
+        }
+        if let Some(max) = schema.get("maxLength").and_then(Json::as_u64) {
+            if len > max {
+                return Err(format!("{path}: string longer than {max}"));
+            }
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        if let Some(Json::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Json::as_str) {
+                if !object.contains_key(name) {
+                    return Err(format!("{path}: missing property {name}"));
+                }
+            }
+        }
+        let properties = schema.get("properties").and_then(Json::as_object);
+        for (name, v) in object {
+            let p = format!("{path}.{name}");
+            match properties.and_then(|p| p.get(name)) {
+                Some(s) => validate(s, v, &p)?,
+                None => {
+                    if let Some(s) = schema.get("additionalProperties") {
+                        validate(s, v, &p)?
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(array) = value.as_array() {
+        let len = array.len() as u64;
+        if let Some(min) = schema.get("minItems").and_then(Json::as_u64) {
+            if len < min {
+                return Err(format!("{path}: less than {min} items"));
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(Json::as_u64) {
+            if len > max {
+                return Err(format!("{path}: more than {max} items"));
+            }
+        }
+        if let Some(items) = schema.get("items") {
+            for (i, v) in array.iter().enumerate() {
+                validate(items, v, &format!("{path}[{i}]"))?;
+            }
+        }
+    }
+
+    Ok(())
+}
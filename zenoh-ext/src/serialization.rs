@@ -0,0 +1,127 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::{de::DeserializeOwned, Serialize};
+use zenoh::prelude::{Encoding, Sample, Value};
+use zenoh::publication::{Publication, Publisher};
+use zenoh_result::{zerror, ZResult};
+
+// The suffix of the `application/custom` encoding of the serialized values
+const CBOR_ENCODING_SUFFIX: &str = ";cbor";
+
+/// Serialize a value into a compact binary payload, to be deserialized with [`z_deserialize`].
+///
+/// The payload is encoded with [CBOR](https://cbor.io), a self-describing format: the struct fields are
+/// written with their names, so the publishers and the subscribers may evolve their types independently
+/// (e.g. reorder the fields, or add optional ones). The value is published with the `application/custom;cbor`
+/// encoding.
+pub fn z_serialize<T>(value: &T) -> ZResult<Value>
+where
+    T: Serialize + ?Sized,
+{
+    let mut payload = Vec::new();
+    ciborium::ser::into_writer(value, &mut payload)
+        .map_err(|e| zerror!("Serialization failed: {}", e))?;
+    Ok(Value::from(payload).encoding(Encoding::APP_CUSTOM.with_suffix(CBOR_ENCODING_SUFFIX)?))
+}
+
+/// Deserialize a value serialized with [`z_serialize`].
+pub fn z_deserialize<T>(value: &Value) -> ZResult<T>
+where
+    T: DeserializeOwned,
+{
+    ciborium::de::from_reader(&*value.payload.contiguous())
+        .map_err(|e| zerror!("Deserialization failed: {}", e).into())
+}
+
+/// Allows writing `publisher.put_serialized(&value)` instead of `publisher.put(z_serialize(&value)?)`.
+pub trait PublisherSerializeExt {
+    /// Put a value serialized with [`z_serialize`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use serde::Serialize;
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh_ext::PublisherSerializeExt;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Position {
+    ///     x: f64,
+    ///     y: f64,
+    /// }
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let publisher = session.declare_publisher("key/expression").res().await.unwrap();
+    /// publisher
+    ///     .put_serialized(&Position { x: 1.0, y: 2.0 })
+    ///     .unwrap()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    fn put_serialized<T>(&self, value: &T) -> ZResult<Publication>
+    where
+        T: Serialize + ?Sized;
+}
+
+impl PublisherSerializeExt for Publisher<'_> {
+    fn put_serialized<T>(&self, value: &T) -> ZResult<Publication>
+    where
+        T: Serialize + ?Sized,
+    {
+        Ok(self.put(z_serialize(value)?))
+    }
+}
+
+/// Allows writing `sample.deserialize_into::<T>()` instead of `z_deserialize::<T>(&sample.value)`.
+pub trait SampleDeserializeExt {
+    /// Deserialize the value of this sample, serialized with [`z_serialize`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use serde::Deserialize;
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh_ext::SampleDeserializeExt;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Position {
+    ///     x: f64,
+    ///     y: f64,
+    /// }
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("key/expression").res().await.unwrap();
+    /// while let Ok(sample) = subscriber.recv_async().await {
+    ///     let position: Position = sample.deserialize_into().unwrap();
+    ///     println!("x={} y={}", position.x, position.y);
+    /// }
+    /// # }
+    /// ```
+    fn deserialize_into<T>(&self) -> ZResult<T>
+    where
+        T: DeserializeOwned;
+}
+
+impl SampleDeserializeExt for Sample {
+    fn deserialize_into<T>(&self) -> ZResult<T>
+    where
+        T: DeserializeOwned,
+    {
+        z_deserialize(&self.value)
+    }
+}
@@ -11,10 +11,17 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use super::PublicationCacheBuilder;
+use super::{
+    AdvancedPublisherBuilder, AdvancedSubscriberBuilder, AggregatorBuilder, ChangePublisherBuilder,
+    ConsumerGroupSubscriberBuilder, DispatcherBuilder, GetRetryBuilder, LatencyProbeBuilder,
+    LatencyProbePublisherBuilder, LatestValueCacheBuilder, OfflinePublisherBuilder,
+    PublicationCacheBuilder, QueryingSubscriberBuilder, UserSpace,
+};
 use std::convert::TryInto;
+use std::path::PathBuf;
 use std::sync::Arc;
-use zenoh::prelude::KeyExpr;
+use zenoh::handlers::DefaultHandler;
+use zenoh::prelude::{KeyExpr, Selector};
 use zenoh::{Session, SessionRef};
 
 /// Some extensions to the [`zenoh::Session`](zenoh::Session)
@@ -26,6 +33,130 @@ pub trait SessionExt<'s, 'a> {
     where
         TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
         <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>;
+
+    /// Declare a [`LatestValueCache`](super::LatestValueCache) keeping the latest publication
+    /// of each key expression matching `key_expr`.
+    fn declare_latest_value_cache<'b, 'c, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> LatestValueCacheBuilder<'a, 'b, 'c>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>;
+
+    /// Query `selector`, retrying with an exponential backoff while no reply is received.
+    fn get_retry<'b, TryIntoSelector>(
+        &'s self,
+        selector: TryIntoSelector,
+    ) -> GetRetryBuilder<'a, 'b>
+    where
+        TryIntoSelector: TryInto<Selector<'b>>,
+        <TryIntoSelector as TryInto<Selector<'b>>>::Error: Into<zenoh_result::Error>;
+
+    /// Declare a publisher on `key_expr` that stores its publications in the file at `path`
+    /// while the session has no connectivity, and replays them once connected again.
+    fn declare_offline_publisher<'b, TryIntoKeyExpr, IntoPath>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+        path: IntoPath,
+    ) -> OfflinePublisherBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+        IntoPath: Into<PathBuf>;
+
+    /// Declare a [`Dispatcher`](super::Dispatcher) routing the samples received on `key_expr`
+    /// to the handlers registered on the returned builder.
+    fn declare_dispatcher<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> DispatcherBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>;
+
+    /// Declare an [`Aggregator`](super::Aggregator) answering the queries on `key_expr` with the
+    /// aggregated replies of the sources registered on the returned builder.
+    fn declare_aggregator<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> AggregatorBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>;
+
+    /// Declare a subscriber on `key_expr` that queries the history of publications at declaration,
+    /// and delivers it merged with the live publications, sorted by timestamp and without duplicates.
+    /// See [`QueryingSubscriberBuilder`].
+    fn declare_querying_subscriber<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> QueryingSubscriberBuilder<'a, 'b, UserSpace, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>;
+
+    /// Declare an [`AdvancedPublisher`](super::AdvancedPublisher) on `key_expr`, tagging its publications
+    /// with sequence numbers and caching them for the [`AdvancedSubscriber`](super::AdvancedSubscriber)s.
+    fn declare_advanced_publisher<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> AdvancedPublisherBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>;
+
+    /// Declare an [`AdvancedSubscriber`](super::AdvancedSubscriber) on `key_expr`, detecting and recovering
+    /// the samples missed from the [`AdvancedPublisher`](super::AdvancedPublisher)s.
+    fn declare_advanced_subscriber<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> AdvancedSubscriberBuilder<'a, 'b, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>;
+
+    /// Declare a [`ChangePublisher`](super::ChangePublisher) on `key_expr`, only publishing the values
+    /// differing from the last published one.
+    fn declare_change_publisher<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> ChangePublisherBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>;
+
+    /// Declare a [`ConsumerGroupSubscriber`](super::ConsumerGroupSubscriber) on `key_expr`, sharing its samples
+    /// with the other subscribers of the same `group`: each sample is delivered to a single member of the group.
+    fn declare_consumer_group_subscriber<'b, TryIntoKeyExpr, IntoGroup>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+        group: IntoGroup,
+    ) -> ConsumerGroupSubscriberBuilder<'a, 'b, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+        IntoGroup: Into<String>;
+
+    /// Declare a [`LatencyProbePublisher`](super::LatencyProbePublisher) on `key_expr`, attaching
+    /// their send time to its publications.
+    fn declare_latency_probe_publisher<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> LatencyProbePublisherBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>;
+
+    /// Declare a [`LatencyProbe`](super::LatencyProbe) on `key_expr`, measuring the end-to-end
+    /// latency of the samples published on it.
+    fn declare_latency_probe<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> LatencyProbeBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>;
 }
 
 impl<'s, 'a> SessionExt<'s, 'a> for SessionRef<'a> {
@@ -39,6 +170,154 @@ impl<'s, 'a> SessionExt<'s, 'a> for SessionRef<'a> {
     {
         PublicationCacheBuilder::new(self.clone(), pub_key_expr.try_into().map_err(Into::into))
     }
+
+    fn declare_latest_value_cache<'b, 'c, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> LatestValueCacheBuilder<'a, 'b, 'c>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        LatestValueCacheBuilder::new(self.clone(), key_expr.try_into().map_err(Into::into))
+    }
+
+    fn get_retry<'b, TryIntoSelector>(
+        &'s self,
+        selector: TryIntoSelector,
+    ) -> GetRetryBuilder<'a, 'b>
+    where
+        TryIntoSelector: TryInto<Selector<'b>>,
+        <TryIntoSelector as TryInto<Selector<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        GetRetryBuilder::new(self.clone(), selector.try_into().map_err(Into::into))
+    }
+
+    fn declare_offline_publisher<'b, TryIntoKeyExpr, IntoPath>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+        path: IntoPath,
+    ) -> OfflinePublisherBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+        IntoPath: Into<PathBuf>,
+    {
+        OfflinePublisherBuilder::new(
+            self.clone(),
+            key_expr.try_into().map_err(Into::into),
+            path.into(),
+        )
+    }
+
+    fn declare_dispatcher<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> DispatcherBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        DispatcherBuilder::new(self.clone(), key_expr.try_into().map_err(Into::into))
+    }
+
+    fn declare_aggregator<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> AggregatorBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        AggregatorBuilder::new(self.clone(), key_expr.try_into().map_err(Into::into))
+    }
+
+    fn declare_querying_subscriber<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> QueryingSubscriberBuilder<'a, 'b, UserSpace, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        QueryingSubscriberBuilder::new(
+            self.clone(),
+            key_expr.try_into().map_err(Into::into),
+            DefaultHandler,
+        )
+    }
+
+    fn declare_advanced_publisher<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> AdvancedPublisherBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        AdvancedPublisherBuilder::new(self.clone(), key_expr.try_into().map_err(Into::into))
+    }
+
+    fn declare_advanced_subscriber<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> AdvancedSubscriberBuilder<'a, 'b, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        AdvancedSubscriberBuilder::new(self.clone(), key_expr.try_into().map_err(Into::into))
+    }
+
+    fn declare_change_publisher<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> ChangePublisherBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        ChangePublisherBuilder::new(self.clone(), key_expr.try_into().map_err(Into::into))
+    }
+
+    fn declare_consumer_group_subscriber<'b, TryIntoKeyExpr, IntoGroup>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+        group: IntoGroup,
+    ) -> ConsumerGroupSubscriberBuilder<'a, 'b, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+        IntoGroup: Into<String>,
+    {
+        ConsumerGroupSubscriberBuilder::new(
+            self.clone(),
+            key_expr.try_into().map_err(Into::into),
+            group.into(),
+        )
+    }
+
+    fn declare_latency_probe_publisher<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> LatencyProbePublisherBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        LatencyProbePublisherBuilder::new(self.clone(), key_expr.try_into().map_err(Into::into))
+    }
+
+    fn declare_latency_probe<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> LatencyProbeBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        LatencyProbeBuilder::new(self.clone(), key_expr.try_into().map_err(Into::into))
+    }
 }
 
 impl<'a> SessionExt<'a, 'a> for Session {
@@ -52,6 +331,142 @@ impl<'a> SessionExt<'a, 'a> for Session {
     {
         SessionRef::Borrow(self).declare_publication_cache(pub_key_expr)
     }
+
+    fn declare_latest_value_cache<'b, 'c, TryIntoKeyExpr>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+    ) -> LatestValueCacheBuilder<'a, 'b, 'c>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Borrow(self).declare_latest_value_cache(key_expr)
+    }
+
+    fn get_retry<'b, TryIntoSelector>(
+        &'a self,
+        selector: TryIntoSelector,
+    ) -> GetRetryBuilder<'a, 'b>
+    where
+        TryIntoSelector: TryInto<Selector<'b>>,
+        <TryIntoSelector as TryInto<Selector<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Borrow(self).get_retry(selector)
+    }
+
+    fn declare_offline_publisher<'b, TryIntoKeyExpr, IntoPath>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+        path: IntoPath,
+    ) -> OfflinePublisherBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+        IntoPath: Into<PathBuf>,
+    {
+        SessionRef::Borrow(self).declare_offline_publisher(key_expr, path)
+    }
+
+    fn declare_dispatcher<'b, TryIntoKeyExpr>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+    ) -> DispatcherBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Borrow(self).declare_dispatcher(key_expr)
+    }
+
+    fn declare_aggregator<'b, TryIntoKeyExpr>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+    ) -> AggregatorBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Borrow(self).declare_aggregator(key_expr)
+    }
+
+    fn declare_querying_subscriber<'b, TryIntoKeyExpr>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+    ) -> QueryingSubscriberBuilder<'a, 'b, UserSpace, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Borrow(self).declare_querying_subscriber(key_expr)
+    }
+
+    fn declare_advanced_publisher<'b, TryIntoKeyExpr>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+    ) -> AdvancedPublisherBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Borrow(self).declare_advanced_publisher(key_expr)
+    }
+
+    fn declare_advanced_subscriber<'b, TryIntoKeyExpr>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+    ) -> AdvancedSubscriberBuilder<'a, 'b, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Borrow(self).declare_advanced_subscriber(key_expr)
+    }
+
+    fn declare_change_publisher<'b, TryIntoKeyExpr>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+    ) -> ChangePublisherBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Borrow(self).declare_change_publisher(key_expr)
+    }
+
+    fn declare_consumer_group_subscriber<'b, TryIntoKeyExpr, IntoGroup>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+        group: IntoGroup,
+    ) -> ConsumerGroupSubscriberBuilder<'a, 'b, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+        IntoGroup: Into<String>,
+    {
+        SessionRef::Borrow(self).declare_consumer_group_subscriber(key_expr, group)
+    }
+
+    fn declare_latency_probe_publisher<'b, TryIntoKeyExpr>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+    ) -> LatencyProbePublisherBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Borrow(self).declare_latency_probe_publisher(key_expr)
+    }
+
+    fn declare_latency_probe<'b, TryIntoKeyExpr>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+    ) -> LatencyProbeBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Borrow(self).declare_latency_probe(key_expr)
+    }
 }
 
 impl<'s> SessionExt<'s, 'static> for Arc<Session> {
@@ -82,4 +497,140 @@ impl<'s> SessionExt<'s, 'static> for Arc<Session> {
     {
         SessionRef::Shared(self.clone()).declare_publication_cache(pub_key_expr)
     }
+
+    fn declare_latest_value_cache<'b, 'c, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> LatestValueCacheBuilder<'static, 'b, 'c>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Shared(self.clone()).declare_latest_value_cache(key_expr)
+    }
+
+    fn get_retry<'b, TryIntoSelector>(
+        &'s self,
+        selector: TryIntoSelector,
+    ) -> GetRetryBuilder<'static, 'b>
+    where
+        TryIntoSelector: TryInto<Selector<'b>>,
+        <TryIntoSelector as TryInto<Selector<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Shared(self.clone()).get_retry(selector)
+    }
+
+    fn declare_offline_publisher<'b, TryIntoKeyExpr, IntoPath>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+        path: IntoPath,
+    ) -> OfflinePublisherBuilder<'static, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+        IntoPath: Into<PathBuf>,
+    {
+        SessionRef::Shared(self.clone()).declare_offline_publisher(key_expr, path)
+    }
+
+    fn declare_dispatcher<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> DispatcherBuilder<'static, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Shared(self.clone()).declare_dispatcher(key_expr)
+    }
+
+    fn declare_aggregator<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> AggregatorBuilder<'static, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Shared(self.clone()).declare_aggregator(key_expr)
+    }
+
+    fn declare_querying_subscriber<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> QueryingSubscriberBuilder<'static, 'b, UserSpace, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Shared(self.clone()).declare_querying_subscriber(key_expr)
+    }
+
+    fn declare_advanced_publisher<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> AdvancedPublisherBuilder<'static, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Shared(self.clone()).declare_advanced_publisher(key_expr)
+    }
+
+    fn declare_advanced_subscriber<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> AdvancedSubscriberBuilder<'static, 'b, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Shared(self.clone()).declare_advanced_subscriber(key_expr)
+    }
+
+    fn declare_change_publisher<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> ChangePublisherBuilder<'static, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Shared(self.clone()).declare_change_publisher(key_expr)
+    }
+
+    fn declare_consumer_group_subscriber<'b, TryIntoKeyExpr, IntoGroup>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+        group: IntoGroup,
+    ) -> ConsumerGroupSubscriberBuilder<'static, 'b, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+        IntoGroup: Into<String>,
+    {
+        SessionRef::Shared(self.clone()).declare_consumer_group_subscriber(key_expr, group)
+    }
+
+    fn declare_latency_probe_publisher<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> LatencyProbePublisherBuilder<'static, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Shared(self.clone()).declare_latency_probe_publisher(key_expr)
+    }
+
+    fn declare_latency_probe<'b, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> LatencyProbeBuilder<'static, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        SessionRef::Shared(self.clone()).declare_latency_probe(key_expr)
+    }
 }
@@ -24,7 +24,10 @@ use zenoh::{
     subscriber::{PushMode, Reliability, Subscriber, SubscriberBuilder},
 };
 
-use crate::{querying_subscriber::QueryingSubscriberBuilder, FetchingSubscriberBuilder};
+use crate::{
+    querying_subscriber::QueryingSubscriberBuilder, FetchingSubscriberBuilder, RateLimit,
+    RateLimitStrategy,
+};
 
 /// Allows writing `subscriber.forward(receiver)` instead of `subscriber.stream().map(Ok).forward(publisher)`
 pub trait SubscriberForward<'a, S> {
@@ -219,20 +222,9 @@ impl<'a, 'b, Handler> SubscriberBuilderExt<'a, 'b, Handler>
     /// ```
     fn querying(self) -> QueryingSubscriberBuilder<'a, 'b, Self::KeySpace, Handler> {
         QueryingSubscriberBuilder {
-            session: self.session,
-            key_expr: self.key_expr,
-            key_space: crate::UserSpace,
             reliability: self.reliability,
             origin: self.origin,
-            query_selector: None,
-            // By default query all matching publication caches and storages
-            query_target: QueryTarget::All,
-            // By default no query consolidation, to receive more than 1 sample per-resource
-            // (if history of publications is available)
-            query_consolidation: QueryConsolidation::from(zenoh::query::ConsolidationMode::None),
-            query_accept_replies: ReplyKeyExpr::default(),
-            query_timeout: Duration::from_secs(10),
-            handler: self.handler,
+            ..QueryingSubscriberBuilder::new(self.session, self.key_expr, self.handler)
         }
     }
 }
@@ -343,6 +335,7 @@ impl<'a, 'b, Handler> SubscriberBuilderExt<'a, 'b, Handler>
             reliability: Reliability::default(),
             origin: Locality::default(),
             query_selector: None,
+            query_prefix: None,
             query_target: QueryTarget::default(),
             query_consolidation: QueryConsolidation::default(),
             query_accept_replies: ReplyKeyExpr::MatchingQuery,
@@ -351,3 +344,64 @@ impl<'a, 'b, Handler> SubscriberBuilderExt<'a, 'b, Handler>
         }
     }
 }
+
+/// Some extensions to the [`zenoh::subscriber::SubscriberBuilder`] and
+/// [`zenoh::liveliness::LivelinessSubscriberBuilder`] limiting the rate of their samples.
+pub trait SubscriberBuilderRateLimitExt<Handler> {
+    type RateLimited;
+
+    /// Forward at most `max_hz` samples per second and per key expression to the handler of the subscriber,
+    /// either coalescing the others to deliver the freshest value or dropping them, see [`RateLimit`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh_ext::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expr")
+    ///     .rate_limit(30.0, RateLimitStrategy::Latest)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// while let Ok(sample) = subscriber.recv_async().await {
+    ///     println!("Received: {:?}", sample);
+    /// }
+    /// # }
+    /// ```
+    fn rate_limit(self, max_hz: f64, strategy: RateLimitStrategy) -> Self::RateLimited;
+}
+
+impl<'a, 'b, Handler> SubscriberBuilderRateLimitExt<Handler>
+    for SubscriberBuilder<'a, 'b, PushMode, Handler>
+{
+    type RateLimited = SubscriberBuilder<'a, 'b, PushMode, RateLimit<Handler>>;
+
+    fn rate_limit(self, max_hz: f64, strategy: RateLimitStrategy) -> Self::RateLimited {
+        SubscriberBuilder {
+            session: self.session,
+            key_expr: self.key_expr,
+            reliability: self.reliability,
+            mode: self.mode,
+            origin: self.origin,
+            handler: RateLimit::new(max_hz, strategy, self.handler),
+        }
+    }
+}
+
+impl<'a, 'b, Handler> SubscriberBuilderRateLimitExt<Handler>
+    for LivelinessSubscriberBuilder<'a, 'b, Handler>
+{
+    type RateLimited = LivelinessSubscriberBuilder<'a, 'b, RateLimit<Handler>>;
+
+    fn rate_limit(self, max_hz: f64, strategy: RateLimitStrategy) -> Self::RateLimited {
+        LivelinessSubscriberBuilder {
+            session: self.session,
+            key_expr: self.key_expr,
+            handler: RateLimit::new(max_hz, strategy, self.handler),
+        }
+    }
+}
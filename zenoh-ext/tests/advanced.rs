@@ -0,0 +1,131 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh::sample::Attachment;
+use zenoh_core::ztimeout;
+use zenoh_ext::{Miss, SessionExt, ADVANCED_SN_ATTACHMENT, ADVANCED_SOURCE_ATTACHMENT};
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_millis(500);
+
+async fn open_session() -> Arc<Session> {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    ztimeout!(zenoh::open(config).res_async())
+        .unwrap()
+        .into_arc()
+}
+
+fn sequenced(key_expr: &'static str, sn: u64) -> Sample {
+    let mut attachment = Attachment::new();
+    attachment.insert(ADVANCED_SOURCE_ATTACHMENT, "source");
+    attachment.insert(ADVANCED_SN_ATTACHMENT, &sn.to_le_bytes());
+    Sample::new(keyexpr::new(key_expr).unwrap(), sn.to_string()).with_attachment(attachment)
+}
+
+async fn put_sequenced(session: &Session, key_expr: &'static str, sn: u64) {
+    let sample = sequenced(key_expr, sn);
+    ztimeout!(session
+        .put(key_expr, sample.value)
+        .with_attachment(sample.attachment.unwrap())
+        .res_async())
+    .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn advanced_subscriber_recovery() {
+    let session = open_session().await;
+
+    // A cache behind a query prefix, replying on the key expression of its samples
+    let _cache = ztimeout!(session
+        .declare_queryable("prefix/test/advanced/recovery")
+        .callback(|query| {
+            query
+                .reply(Ok(sequenced("test/advanced/recovery", 2)))
+                .res_sync()
+                .unwrap();
+        })
+        .res_async())
+    .unwrap();
+
+    let received = Arc::new(Mutex::new(vec![]));
+    let c_received = received.clone();
+    let subscriber = ztimeout!(session
+        .declare_advanced_subscriber("test/advanced/recovery")
+        .query_prefix("prefix")
+        .heartbeats(false)
+        .callback(move |sample| {
+            c_received.lock().unwrap().push(sample.value.to_string());
+        })
+        .res_async())
+    .unwrap();
+    let misses = Arc::new(Mutex::new(vec![]));
+    let c_misses = misses.clone();
+    subscriber.sample_miss_listener(move |miss| c_misses.lock().unwrap().push(miss));
+
+    // The missing sample 2 is recovered from the cache through the query prefix
+    put_sequenced(&session, "test/advanced/recovery", 1).await;
+    put_sequenced(&session, "test/advanced/recovery", 3).await;
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(*received.lock().unwrap(), ["1", "2", "3"]);
+
+    // The missing samples 4 and 5 are not in the cache and are reported as missed
+    put_sequenced(&session, "test/advanced/recovery", 6).await;
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(*received.lock().unwrap(), ["1", "2", "3", "6"]);
+    assert_eq!(
+        *misses.lock().unwrap(),
+        [Miss {
+            source: "source".to_string(),
+            nb: 2
+        }]
+    );
+
+    ztimeout!(subscriber.close().res_async()).unwrap();
+    ztimeout!(session.close().res_async()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn advanced_subscriber_reentrant_callback() {
+    let session = open_session().await;
+
+    // The callback publishes on a key expression of the subscriber itself
+    let received = Arc::new(Mutex::new(vec![]));
+    let c_received = received.clone();
+    let c_session = session.clone();
+    let subscriber = ztimeout!(session
+        .declare_advanced_subscriber("test/advanced/reentrant/**")
+        .heartbeats(false)
+        .callback(move |sample| {
+            if sample.key_expr.as_str() != "test/advanced/reentrant/echo" {
+                c_session
+                    .put("test/advanced/reentrant/echo", sample.value.clone())
+                    .res_sync()
+                    .unwrap();
+            }
+            c_received.lock().unwrap().push(sample.value.to_string());
+        })
+        .res_async())
+    .unwrap();
+
+    put_sequenced(&session, "test/advanced/reentrant/data", 1).await;
+    put_sequenced(&session, "test/advanced/reentrant/data", 2).await;
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(*received.lock().unwrap(), ["1", "1", "2", "2"]);
+
+    ztimeout!(subscriber.close().res_async()).unwrap();
+    ztimeout!(session.close().res_async()).unwrap();
+}
@@ -0,0 +1,112 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh::queryable::Query;
+use zenoh_core::ztimeout;
+use zenoh_ext::SessionExt;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_secs(1);
+const SOURCE_TIMEOUT: Duration = Duration::from_secs(1);
+
+async fn create_session_pair(locator: &str) -> (Arc<Session>, Session) {
+    let config1 = {
+        let mut config = zenoh::config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config
+            .listen
+            .set_endpoints(vec![locator.parse().unwrap()])
+            .unwrap();
+        config
+    };
+    let config2 = zenoh::config::client([Locator::from_str(locator).unwrap()]);
+
+    let session1 = ztimeout!(zenoh::open(config1).res_async()).unwrap();
+    let session2 = ztimeout!(zenoh::open(config2).res_async()).unwrap();
+    (session1.into_arc(), session2)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn aggregator_sources() {
+    let (session1, session2) = create_session_pair("tcp/127.0.0.1:17700").await;
+
+    // One source replies a sample, one replies nothing, one never completes the queries
+    let _full = ztimeout!(session2
+        .declare_queryable("test/aggregator/full/**")
+        .callback(|query| {
+            query
+                .reply(Ok(Sample::new("test/aggregator/full/1", "full")))
+                .res_sync()
+                .unwrap();
+        })
+        .res_async())
+    .unwrap();
+    let _empty = ztimeout!(session2
+        .declare_queryable("test/aggregator/empty/**")
+        .callback(|_| {})
+        .res_async())
+    .unwrap();
+    let pending: Arc<Mutex<Vec<Query>>> = Arc::default();
+    let c_pending = pending.clone();
+    let _silent = ztimeout!(session2
+        .declare_queryable("test/aggregator/silent/**")
+        .callback(move |query| c_pending.lock().unwrap().push(query))
+        .res_async())
+    .unwrap();
+
+    let complete = ztimeout!(session1
+        .declare_aggregator("test/aggregator/complete/**")
+        .source("test/aggregator/full/**")
+        .source("test/aggregator/empty/**")
+        .timeout(SOURCE_TIMEOUT)
+        .allow_partial(false)
+        .aggregate(|_, samples| {
+            samples
+                .into_iter()
+                .map(|s| Sample::new("test/aggregator/complete/1", s.value))
+                .collect()
+        })
+        .res_async())
+    .unwrap();
+    let partial = ztimeout!(session1
+        .declare_aggregator("test/aggregator/partial/**")
+        .source("test/aggregator/full/**")
+        .source("test/aggregator/silent/**")
+        .timeout(SOURCE_TIMEOUT)
+        .allow_partial(false)
+        .res_async())
+    .unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // A source replying no sample answered
+    let replies = ztimeout!(session2.get("test/aggregator/complete/**").res_async()).unwrap();
+    let reply = ztimeout!(replies.recv_async()).unwrap();
+    let sample = reply.sample.unwrap();
+    assert_eq!(sample.key_expr.as_str(), "test/aggregator/complete/1");
+    assert_eq!(sample.value.to_string(), "full");
+    assert!(ztimeout!(replies.recv_async()).is_err());
+
+    // A source whose query timed out did not
+    let replies = ztimeout!(session2.get("test/aggregator/partial/**").res_async()).unwrap();
+    let reply = ztimeout!(replies.recv_async()).unwrap();
+    assert!(reply.sample.is_err());
+    assert!(ztimeout!(replies.recv_async()).is_err());
+    pending.lock().unwrap().clear();
+
+    ztimeout!(complete.close().res_async()).unwrap();
+    ztimeout!(partial.close().res_async()).unwrap();
+}
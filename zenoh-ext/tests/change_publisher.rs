@@ -0,0 +1,91 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_core::ztimeout;
+use zenoh_ext::SessionExt;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_millis(500);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn change_publisher_suppression() {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session = ztimeout!(zenoh::open(config).res_async())
+        .unwrap()
+        .into_arc();
+
+    let subscriber = ztimeout!(session.declare_subscriber("test/change").res_async()).unwrap();
+    let publisher = ztimeout!(session.declare_change_publisher("test/change").res_async()).unwrap();
+
+    // The unchanged values are not published
+    assert!(ztimeout!(publisher.put("a").res_async()).unwrap());
+    assert!(!ztimeout!(publisher.put("a").res_async()).unwrap());
+    assert!(ztimeout!(publisher.put("b").res_async()).unwrap());
+
+    // The same payload with another encoding is a change
+    let value = Value::from("b").encoding(KnownEncoding::AppJson.into());
+    assert!(ztimeout!(publisher.put(value.clone()).res_async()).unwrap());
+    assert!(!ztimeout!(publisher.put(value).res_async()).unwrap());
+
+    // The next put is published after a deletion
+    ztimeout!(publisher.delete().res_async()).unwrap();
+    assert!(ztimeout!(publisher.put("b").res_async()).unwrap());
+
+    let mut received = vec![];
+    while received.len() < 5 {
+        let sample = ztimeout!(subscriber.recv_async()).unwrap();
+        received.push((sample.kind, sample.value.to_string()));
+    }
+    assert_eq!(
+        received,
+        [
+            (SampleKind::Put, "a".to_string()),
+            (SampleKind::Put, "b".to_string()),
+            (SampleKind::Put, "b".to_string()),
+            (SampleKind::Delete, String::new()),
+            (SampleKind::Put, "b".to_string()),
+        ]
+    );
+    tokio::time::sleep(SLEEP).await;
+    assert!(subscriber.try_recv().is_err());
+
+    ztimeout!(publisher.close().res_async()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn change_publisher_max_silence() {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session = ztimeout!(zenoh::open(config).res_async())
+        .unwrap()
+        .into_arc();
+
+    let subscriber = ztimeout!(session.declare_subscriber("test/silence").res_async()).unwrap();
+    let publisher = ztimeout!(session
+        .declare_change_publisher("test/silence")
+        .max_silence(Duration::from_millis(100))
+        .res_async())
+    .unwrap();
+
+    // The last value is republished after max_silence, although unchanged
+    assert!(ztimeout!(publisher.put("a").res_async()).unwrap());
+    for _ in 0..3 {
+        let sample = ztimeout!(subscriber.recv_async()).unwrap();
+        assert_eq!(sample.value.to_string(), "a");
+    }
+
+    ztimeout!(publisher.close().res_async()).unwrap();
+}
@@ -0,0 +1,137 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_core::ztimeout;
+use zenoh_ext::SessionExt;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_millis(100);
+const COUNT: usize = 100;
+
+async fn open_sessions(locator: &str) -> (Session, Session, Session) {
+    let config = {
+        let mut config = zenoh::config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config
+            .listen
+            .set_endpoints(vec![locator.parse().unwrap()])
+            .unwrap();
+        config
+    };
+    let client = || zenoh::config::client([Locator::from_str(locator).unwrap()]);
+
+    let session1 = ztimeout!(zenoh::open(config).res_async()).unwrap();
+    let session2 = ztimeout!(zenoh::open(client()).res_async()).unwrap();
+    let session3 = ztimeout!(zenoh::open(client()).res_async()).unwrap();
+    (session1, session2, session3)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn consumer_group_sharing() {
+    let (session1, session2, session3) = open_sessions("tcp/127.0.0.1:17702").await;
+
+    let counters: Vec<Arc<AtomicUsize>> = (0..2).map(|_| Arc::default()).collect();
+    let c_counter = counters[0].clone();
+    let member1 = ztimeout!(session2
+        .declare_consumer_group_subscriber("test/cgroup/**", "workers")
+        .callback(move |_| {
+            c_counter.fetch_add(1, Ordering::Relaxed);
+        })
+        .res_async())
+    .unwrap();
+    // The member joining later learns about the current members before returning
+    let c_counter = counters[1].clone();
+    let member2 = ztimeout!(session3
+        .declare_consumer_group_subscriber("test/cgroup/**", "workers")
+        .callback(move |_| {
+            c_counter.fetch_add(1, Ordering::Relaxed);
+        })
+        .res_async())
+    .unwrap();
+    assert_eq!(member2.members().len(), 2);
+    ztimeout!(async {
+        while member1.members().len() < 2 {
+            tokio::time::sleep(SLEEP).await;
+        }
+    });
+    tokio::time::sleep(SLEEP).await;
+
+    // Each sample is delivered to a single member, and both members get their share
+    for i in 0..COUNT {
+        ztimeout!(session1
+            .put(format!("test/cgroup/{i}"), i.to_string())
+            .res_async())
+        .unwrap();
+    }
+    ztimeout!(async {
+        while counters
+            .iter()
+            .map(|c| c.load(Ordering::Relaxed))
+            .sum::<usize>()
+            < COUNT
+        {
+            tokio::time::sleep(SLEEP).await;
+        }
+    });
+    tokio::time::sleep(SLEEP).await;
+    let received: Vec<usize> = counters.iter().map(|c| c.load(Ordering::Relaxed)).collect();
+    assert_eq!(received.iter().sum::<usize>(), COUNT);
+    assert!(received.iter().all(|n| *n > 0), "received: {received:?}");
+
+    // The remaining member gets all the samples once the other one left
+    ztimeout!(member2.close().res_async()).unwrap();
+    ztimeout!(async {
+        while member1.members().len() > 1 {
+            tokio::time::sleep(SLEEP).await;
+        }
+    });
+    let before = counters[0].load(Ordering::Relaxed);
+    for i in 0..COUNT {
+        ztimeout!(session1
+            .put(format!("test/cgroup/{i}"), i.to_string())
+            .res_async())
+        .unwrap();
+    }
+    ztimeout!(async {
+        while counters[0].load(Ordering::Relaxed) < before + COUNT {
+            tokio::time::sleep(SLEEP).await;
+        }
+    });
+
+    ztimeout!(member1.close().res_async()).unwrap();
+    ztimeout!(session3.close().res_async()).unwrap();
+    ztimeout!(session2.close().res_async()).unwrap();
+    ztimeout!(session1.close().res_async()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn consumer_group_invalid() {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+    assert!(ztimeout!(session
+        .declare_consumer_group_subscriber("test/cgroup/**", "a/b")
+        .res_async())
+    .is_err());
+    assert!(ztimeout!(session
+        .declare_consumer_group_subscriber("test/cgroup/**", "*")
+        .res_async())
+    .is_err());
+    ztimeout!(session.close().res_async()).unwrap();
+}
@@ -0,0 +1,144 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh::sample::Attachment;
+use zenoh_core::ztimeout;
+use zenoh_ext::SessionExt;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_millis(100);
+
+async fn open_session() -> Session {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    ztimeout!(zenoh::open(config).res_async()).unwrap()
+}
+
+fn payload(sample: &Sample) -> String {
+    String::from_utf8(sample.value.payload.contiguous().to_vec()).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn dispatcher_routes() {
+    let session = open_session().await;
+
+    let (alarm_tx, alarm_rx) = flume::unbounded();
+    let (log_tx, log_rx) = flume::unbounded();
+    let (other_tx, other_rx) = flume::unbounded();
+    let dispatcher = ztimeout!(session
+        .declare_dispatcher("test/dispatcher/routes")
+        .route_field("/type", "alarm", move |s| alarm_tx
+            .send(payload(&s))
+            .unwrap())
+        .route_attachment("kind", "log", move |s| log_tx.send(payload(&s)).unwrap())
+        .fallback(move |s| other_tx.send(payload(&s)).unwrap())
+        .res_async())
+    .unwrap();
+
+    let alarm = r#"{"type":"alarm"}"#;
+    let info = r#"{"type":"info"}"#;
+    ztimeout!(session.put("test/dispatcher/routes", alarm).res_async()).unwrap();
+    // The attachment rule matches although the JSON field does not
+    let mut attachment = Attachment::new();
+    attachment.insert("kind", "log");
+    ztimeout!(session
+        .put("test/dispatcher/routes", info)
+        .with_attachment(attachment)
+        .res_async())
+    .unwrap();
+    ztimeout!(session.put("test/dispatcher/routes", "plain").res_async()).unwrap();
+
+    assert_eq!(ztimeout!(alarm_rx.recv_async()).unwrap(), alarm);
+    assert_eq!(ztimeout!(log_rx.recv_async()).unwrap(), info);
+    assert_eq!(ztimeout!(other_rx.recv_async()).unwrap(), "plain");
+    tokio::time::sleep(SLEEP).await;
+    // Each sample is routed to the first matching handler only
+    assert!(alarm_rx.is_empty());
+    assert!(log_rx.is_empty());
+    assert!(other_rx.is_empty());
+    assert_eq!(dispatcher.dropped(), 0);
+
+    ztimeout!(dispatcher.close().res_async()).unwrap();
+    ztimeout!(session.close().res_async()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn dispatcher_unrouted() {
+    let session = open_session().await;
+
+    let (tx, rx) = flume::unbounded();
+    let dispatcher = ztimeout!(session
+        .declare_dispatcher("test/dispatcher/unrouted")
+        .route_field("/type", "alarm", move |s| tx.send(payload(&s)).unwrap())
+        .res_async())
+    .unwrap();
+
+    // Without fallback the samples matching no rule are ignored
+    ztimeout!(session.put("test/dispatcher/unrouted", "plain").res_async()).unwrap();
+    ztimeout!(session
+        .put("test/dispatcher/unrouted", r#"{"type":"alarm"}"#)
+        .res_async())
+    .unwrap();
+    assert_eq!(ztimeout!(rx.recv_async()).unwrap(), r#"{"type":"alarm"}"#);
+    tokio::time::sleep(SLEEP).await;
+    assert!(rx.is_empty());
+
+    ztimeout!(dispatcher.close().res_async()).unwrap();
+    ztimeout!(session.close().res_async()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn dispatcher_full_queue() {
+    const COUNT: usize = 10;
+    let session = open_session().await;
+
+    // The slow handler is blocked until released, the fast one is not delayed by it
+    let (release_tx, release_rx) = flume::unbounded::<()>();
+    let (slow_tx, slow_rx) = flume::unbounded();
+    let (fast_tx, fast_rx) = flume::unbounded();
+    let dispatcher = ztimeout!(session
+        .declare_dispatcher("test/dispatcher/full")
+        .route_field("/type", "slow", move |s| {
+            let _ = release_rx.recv();
+            slow_tx.send(payload(&s)).unwrap();
+        })
+        .fallback(move |s| fast_tx.send(payload(&s)).unwrap())
+        .capacity(1)
+        .res_async())
+    .unwrap();
+
+    for _ in 0..COUNT {
+        ztimeout!(session
+            .put("test/dispatcher/full", r#"{"type":"slow"}"#)
+            .res_async())
+        .unwrap();
+    }
+    ztimeout!(session.put("test/dispatcher/full", "fast").res_async()).unwrap();
+    assert_eq!(ztimeout!(fast_rx.recv_async()).unwrap(), "fast");
+
+    // At most one sample is being handled and one is queued, the others are dropped
+    assert!(dispatcher.dropped() >= COUNT - 2);
+    drop(release_tx);
+    let mut received = 0;
+    while received + dispatcher.dropped() < COUNT {
+        ztimeout!(slow_rx.recv_async()).unwrap();
+        received += 1;
+    }
+    assert!(received >= 1);
+    assert_eq!(received + dispatcher.dropped(), COUNT);
+
+    ztimeout!(dispatcher.close().res_async()).unwrap();
+    ztimeout!(session.close().res_async()).unwrap();
+}
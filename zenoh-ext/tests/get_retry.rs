@@ -0,0 +1,108 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use zenoh::prelude::r#async::*;
+use zenoh_core::ztimeout;
+use zenoh_ext::SessionExt;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+async fn open_session() -> Arc<Session> {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    ztimeout!(zenoh::open(config).res_async())
+        .unwrap()
+        .into_arc()
+}
+
+fn values(replies: &[zenoh::query::Reply]) -> Vec<String> {
+    replies
+        .iter()
+        .filter_map(|r| r.sample.as_ref().ok())
+        .map(|s| s.value.to_string())
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn get_retry_late_queryable() {
+    let session = open_session().await;
+
+    // The queryable appears after the first tries
+    let c_session = session.clone();
+    let queryable = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        c_session
+            .declare_queryable("test/retry/late")
+            .callback(|query| {
+                query
+                    .reply(Ok(Sample::new("test/retry/late", "late")))
+                    .res_sync()
+                    .unwrap();
+            })
+            .res_async()
+            .await
+            .unwrap()
+    });
+
+    let start = Instant::now();
+    let replies = ztimeout!(session
+        .get_retry("test/retry/late")
+        .retries(10)
+        .backoff(Duration::from_millis(50))
+        .backoff_factor(2)
+        .max_backoff(Duration::from_millis(200))
+        .res_async())
+    .unwrap();
+    assert_eq!(values(&replies), ["late"]);
+    assert!(start.elapsed() >= Duration::from_millis(300));
+    drop(ztimeout!(queryable).unwrap());
+
+    // Without queryable, the tries are exhausted after the backoffs: 50 + 100 + 200 + 200ms
+    let start = Instant::now();
+    let replies = ztimeout!(session
+        .get_retry("test/retry/none")
+        .retries(4)
+        .backoff(Duration::from_millis(50))
+        .max_backoff(Duration::from_millis(200))
+        .res_async())
+    .unwrap();
+    assert!(values(&replies).is_empty());
+    assert!(start.elapsed() >= Duration::from_millis(550));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn get_retry_fallback() {
+    let session = open_session().await;
+    let _queryable = ztimeout!(session
+        .declare_queryable("test/retry/fallback")
+        .callback(|query| {
+            query
+                .reply(Ok(Sample::new("test/retry/fallback", "fallback")))
+                .res_sync()
+                .unwrap();
+        })
+        .res_async())
+    .unwrap();
+
+    // The fallback is queried once the tries are exhausted
+    let replies = ztimeout!(session
+        .get_retry("test/retry/missing")
+        .retries(1)
+        .backoff(Duration::from_millis(10))
+        .fallback("test/retry/fallback")
+        .res_async())
+    .unwrap();
+    assert_eq!(values(&replies), ["fallback"]);
+}
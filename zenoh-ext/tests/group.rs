@@ -0,0 +1,82 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_core::ztimeout;
+use zenoh_ext::group::{Group, GroupEvent, Member};
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+// Long enough for the keep alives not to be the ones making the members known
+const LEASE: Duration = Duration::from_secs(30);
+
+async fn create_session_pair(locator: &str) -> (Arc<Session>, Arc<Session>) {
+    let config1 = {
+        let mut config = zenoh::config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config
+            .listen
+            .set_endpoints(vec![locator.parse().unwrap()])
+            .unwrap();
+        config
+    };
+    let config2 = zenoh::config::client([Locator::from_str(locator).unwrap()]);
+
+    let session1 = ztimeout!(zenoh::open(config1).res_async()).unwrap();
+    let session2 = ztimeout!(zenoh::open(config2).res_async()).unwrap();
+    (session1.into_arc(), session2.into_arc())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn group_membership() {
+    let (session1, session2) = create_session_pair("tcp/127.0.0.1:17701").await;
+
+    let member1 = Member::new("member1").unwrap().lease(LEASE);
+    let group1 = ztimeout!(Group::join(session1.clone(), "test_group", member1)).unwrap();
+    let events = ztimeout!(group1.subscribe());
+
+    // The member joining later learns about the members already in the group from their
+    // liveliness tokens, without waiting for their keep alives
+    let member2 = Member::new("member2").unwrap().lease(LEASE);
+    let group2 = ztimeout!(Group::join(session2.clone(), "test_group", member2)).unwrap();
+    assert!(ztimeout!(group2.wait_for_view_size(2, TIMEOUT)));
+    assert!(ztimeout!(group1.wait_for_view_size(2, TIMEOUT)));
+    match ztimeout!(events.recv_async()).unwrap() {
+        GroupEvent::Join(je) => assert_eq!(je.member.id().as_str(), "member2"),
+        e => panic!("Unexpected event: {e:?}"),
+    }
+
+    // The member whose session is closed leaves the group
+    drop(group2);
+    ztimeout!(session2.close().res_async()).unwrap();
+    match ztimeout!(events.recv_async()).unwrap() {
+        GroupEvent::Leave(le) => assert_eq!(le.mid.as_str(), "member2"),
+        e => panic!("Unexpected event: {e:?}"),
+    }
+    assert_eq!(ztimeout!(group1.size()), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn group_invalid() {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session = ztimeout!(zenoh::open(config).res_async())
+        .unwrap()
+        .into_arc();
+
+    let member = Member::new("member").unwrap();
+    assert!(ztimeout!(Group::join(session.clone(), "test/*", member)).is_err());
+    assert!(Member::new("member/**").is_err());
+}
@@ -0,0 +1,99 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_core::ztimeout;
+use zenoh_ext::SessionExt;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_millis(500);
+
+async fn open_session() -> Arc<Session> {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    ztimeout!(zenoh::open(config).res_async())
+        .unwrap()
+        .into_arc()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn latency_probe() {
+    let session = open_session().await;
+    let probe = ztimeout!(session
+        .declare_latency_probe("test/latency_probe/attachment")
+        .res_async())
+    .unwrap();
+    let publisher = ztimeout!(session
+        .declare_latency_probe_publisher("test/latency_probe/attachment")
+        .res_async())
+    .unwrap();
+
+    for i in 0..10 {
+        ztimeout!(publisher.put(i.to_string()).res_async()).unwrap();
+    }
+    ztimeout!(publisher.delete().res_async()).unwrap();
+    // Samples without send time are not measured
+    ztimeout!(session
+        .put("test/latency_probe/attachment", "-")
+        .res_async())
+    .unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let stats = probe.stats();
+    assert_eq!(stats.count, 11);
+    assert_eq!(stats.skewed, 0);
+    assert!(stats.min_us <= stats.p50_us && stats.p50_us <= stats.p999_us);
+    assert!(stats.p999_us <= stats.max_us && stats.max_us < TIMEOUT.as_micros() as u64);
+
+    probe.reset();
+    assert_eq!(probe.stats().count, 0);
+
+    ztimeout!(probe.close().res_async()).unwrap();
+    ztimeout!(publisher.undeclare().res_async()).unwrap();
+    ztimeout!(session.close().res_async()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn latency_probe_stats_publication() {
+    let session = open_session().await;
+    let stats_sub = ztimeout!(session
+        .declare_subscriber("@latency/test/latency_probe/stats")
+        .res_async())
+    .unwrap();
+    let probe = ztimeout!(session
+        .declare_latency_probe("test/latency_probe/stats")
+        .stats_publication(Duration::from_millis(100))
+        .res_async())
+    .unwrap();
+    let publisher = ztimeout!(session
+        .declare_latency_probe_publisher("test/latency_probe/stats")
+        .res_async())
+    .unwrap();
+
+    ztimeout!(publisher.put("0").res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // The last published statistics account for the sample
+    let mut count = None;
+    while let Ok(sample) = stats_sub.try_recv() {
+        let stats: serde_json::Value = serde_json::from_str(&sample.value.to_string()).unwrap();
+        count = stats["count"].as_u64();
+    }
+    assert_eq!(count, Some(1));
+
+    ztimeout!(probe.close().res_async()).unwrap();
+    ztimeout!(publisher.undeclare().res_async()).unwrap();
+    ztimeout!(session.close().res_async()).unwrap();
+}
@@ -0,0 +1,133 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_core::ztimeout;
+use zenoh_ext::SessionExt;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_millis(500);
+
+async fn open_session() -> Arc<Session> {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    ztimeout!(zenoh::open(config).res_async())
+        .unwrap()
+        .into_arc()
+}
+
+async fn get(session: &Session, selector: &str) -> Vec<(String, String)> {
+    let replies = ztimeout!(session.get(selector).res_async()).unwrap();
+    let mut values = vec![];
+    while let Ok(reply) = ztimeout!(replies.recv_async()) {
+        let sample = reply.sample.unwrap();
+        values.push((sample.key_expr.to_string(), sample.value.to_string()));
+    }
+    values.sort();
+    values
+}
+
+fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn latest_value_cache_deletions() {
+    let session = open_session().await;
+    let cache = ztimeout!(session
+        .declare_latest_value_cache("test/lvc/deletions/**")
+        .res_async())
+    .unwrap();
+
+    for (key_expr, value) in [("a/1", "1"), ("a/2", "2"), ("b/1", "3"), ("a/1", "4")] {
+        ztimeout!(session
+            .put(format!("test/lvc/deletions/{key_expr}"), value)
+            .res_async())
+        .unwrap();
+    }
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(
+        get(&session, "test/lvc/deletions/**").await,
+        pairs(&[
+            ("test/lvc/deletions/a/1", "4"),
+            ("test/lvc/deletions/a/2", "2"),
+            ("test/lvc/deletions/b/1", "3"),
+        ])
+    );
+
+    // A wildcard deletion deletes all the key expressions it includes
+    ztimeout!(session.delete("test/lvc/deletions/a/**").res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(
+        get(&session, "test/lvc/deletions/**").await,
+        pairs(&[("test/lvc/deletions/b/1", "3")])
+    );
+    ztimeout!(session.delete("test/lvc/deletions/b/1").res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert!(get(&session, "test/lvc/deletions/**").await.is_empty());
+
+    ztimeout!(cache.close().res_async()).unwrap();
+    ztimeout!(session.close().res_async()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn latest_value_cache_republish_limit() {
+    const PERIOD: Duration = Duration::from_millis(100);
+    const KEYS: usize = 3;
+
+    let session = open_session().await;
+    let received: Arc<Mutex<HashMap<String, usize>>> = Arc::default();
+    let c_received = received.clone();
+    let subscriber = ztimeout!(session
+        .declare_subscriber("test/lvc/republish/**")
+        .callback(move |sample| {
+            *c_received
+                .lock()
+                .unwrap()
+                .entry(sample.key_expr.to_string())
+                .or_default() += 1;
+        })
+        .res_async())
+    .unwrap();
+    let cache = ztimeout!(session
+        .declare_latest_value_cache("test/lvc/republish/**")
+        .republish(PERIOD)
+        .republish_limit(1)
+        .res_async())
+    .unwrap();
+
+    for i in 0..KEYS {
+        ztimeout!(session
+            .put(format!("test/lvc/republish/{i}"), i.to_string())
+            .res_async())
+        .unwrap();
+    }
+    tokio::time::sleep(PERIOD * 10).await;
+    ztimeout!(cache.close().res_async()).unwrap();
+
+    // A single publication is republished per period, and all of them in turn
+    let received = received.lock().unwrap().clone();
+    let republished: usize = received.values().map(|n| n - 1).sum();
+    assert_eq!(received.len(), KEYS);
+    assert!(received.values().all(|n| *n > 1), "received: {received:?}");
+    assert!(republished <= 12, "republished: {republished}");
+
+    ztimeout!(subscriber.undeclare().res_async()).unwrap();
+    ztimeout!(session.close().res_async()).unwrap();
+}
@@ -0,0 +1,227 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::config::ModeDependentValue;
+use zenoh::prelude::r#async::*;
+use zenoh_core::ztimeout;
+use zenoh_ext::SessionExt;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_millis(500);
+
+async fn get(session: &Session, selector: &str) -> (Vec<String>, usize) {
+    let replies = ztimeout!(session.get(selector).res_async()).unwrap();
+    let mut values = vec![];
+    let mut errors = 0;
+    while let Ok(reply) = ztimeout!(replies.recv_async()) {
+        match reply.sample {
+            Ok(sample) => values.push(sample.value.to_string()),
+            Err(_) => errors += 1,
+        }
+    }
+    values.sort();
+    (values, errors)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn publication_cache_time_range() {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .timestamping
+        .set_enabled(Some(ModeDependentValue::Unique(true)))
+        .unwrap();
+    let session = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+    let cache = ztimeout!(session
+        .declare_publication_cache("test/cache/**")
+        .history(10)
+        .res_async())
+    .unwrap();
+    ztimeout!(session.put("test/cache/a", "a").res_async()).unwrap();
+    ztimeout!(session.put("test/cache/b", "b").res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // All the cached publications are in the last minute, and none is in the future
+    let (values, errors) = get(&session, "test/cache/**?_time=[now(-1m)..]").await;
+    assert_eq!(values, ["a", "b"]);
+    assert_eq!(errors, 0);
+    let (values, errors) = get(&session, "test/cache/a?_time=[now(-1m)..]").await;
+    assert_eq!(values, ["a"]);
+    assert_eq!(errors, 0);
+    let (values, errors) = get(&session, "test/cache/**?_time=[now(1m)..]").await;
+    assert!(values.is_empty());
+    assert_eq!(errors, 0);
+
+    // An invalid time range is replied with an error
+    let (values, errors) = get(&session, "test/cache/**?_time=[invalid..]").await;
+    assert!(values.is_empty());
+    assert_eq!(errors, 1);
+
+    ztimeout!(cache.close().res_async()).unwrap();
+    ztimeout!(session.close().res_async()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn publication_cache_reply_qos() {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .timestamping
+        .set_enabled(Some(ModeDependentValue::Unique(true)))
+        .unwrap();
+    let session = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+    let cache = ztimeout!(session
+        .declare_publication_cache("test/reply_qos/**")
+        .reply_priority(Priority::Background)
+        .reply_congestion_control(CongestionControl::Block)
+        .res_async())
+    .unwrap();
+    ztimeout!(session.put("test/reply_qos/a", "a").res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // The replies carry the configured priority and congestion control
+    let replies = ztimeout!(session.get("test/reply_qos/**").res_async()).unwrap();
+    let sample = ztimeout!(replies.recv_async()).unwrap().sample.unwrap();
+    assert_eq!(sample.value.to_string(), "a");
+    assert_eq!(sample.qos.priority(), Priority::Background);
+    assert_eq!(sample.qos.congestion_control(), CongestionControl::Block);
+
+    ztimeout!(cache.close().res_async()).unwrap();
+    ztimeout!(session.close().res_async()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn publication_cache_max_age() {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .timestamping
+        .set_enabled(Some(ModeDependentValue::Unique(true)))
+        .unwrap();
+    let session = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+    let cache = ztimeout!(session
+        .declare_publication_cache("test/max_age/**")
+        .history(10)
+        .max_age(Duration::from_millis(200))
+        .res_async())
+    .unwrap();
+    ztimeout!(session.put("test/max_age/a", "a").res_async()).unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let (values, _) = get(&session, "test/max_age/**").await;
+    assert_eq!(values, ["a"]);
+
+    // The publication is no longer replied once older than max_age, and is evicted
+    tokio::time::sleep(SLEEP).await;
+    ztimeout!(session.put("test/max_age/b", "b").res_async()).unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let (values, _) = get(&session, "test/max_age/**").await;
+    assert_eq!(values, ["b"]);
+    let stats = cache.stats();
+    assert_eq!(stats.samples, 1);
+    assert_eq!(stats.samples_evicted, 1);
+
+    ztimeout!(cache.close().res_async()).unwrap();
+    ztimeout!(session.close().res_async()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn publication_cache_stats_publication() {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session = Arc::new(ztimeout!(zenoh::open(config).res_async()).unwrap());
+
+    let stats = ztimeout!(session
+        .declare_subscriber(format!(
+            "{}/{}/*/stats",
+            zenoh_ext::CACHE_STATS_KEY_SPACE,
+            session.zid()
+        ))
+        .res_async())
+    .unwrap();
+    let cache_a = ztimeout!(session
+        .declare_publication_cache("test/stats/a/**")
+        .stats_publication(Duration::from_millis(100))
+        .res_async())
+    .unwrap();
+    let cache_b = ztimeout!(session
+        .declare_publication_cache("test/stats/b/**")
+        .stats_publication(Duration::from_millis(100))
+        .res_async())
+    .unwrap();
+
+    // Each cache publishes its statistics on its own key expression
+    let mut caches = HashMap::new();
+    while caches.len() < 2 {
+        let sample = ztimeout!(stats.recv_async()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&sample.value.to_string()).unwrap();
+        let cache = value["key_expr"].as_str().unwrap().to_string();
+        let key_expr = sample.key_expr.to_string();
+        assert_eq!(caches.entry(key_expr).or_insert(cache.clone()), &cache);
+    }
+    let mut published: Vec<_> = caches.into_values().collect();
+    published.sort();
+    assert_eq!(published, ["test/stats/a/**", "test/stats/b/**"]);
+
+    ztimeout!(cache_a.close().res_async()).unwrap();
+    ztimeout!(cache_b.close().res_async()).unwrap();
+    drop(stats);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn publication_cache_persistence_compaction() {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session = ztimeout!(zenoh::open(config).res_async()).unwrap();
+    let path = std::env::temp_dir().join(format!(
+        "zenoh-ext-publication-cache-{}.log",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+
+    let cache = ztimeout!(session
+        .declare_publication_cache("test/persist/**")
+        .persistence(&path)
+        .tombstone_retention(Duration::from_secs(60))
+        .res_async())
+    .unwrap();
+    ztimeout!(session.put("test/persist/a", "old").res_async()).unwrap();
+    ztimeout!(session.delete("test/persist/*").res_async()).unwrap();
+    ztimeout!(session.put("test/persist/a", "new").res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+    ztimeout!(cache.close().res_async()).unwrap();
+
+    // Every restoration compacts the log: the publications more recent than a wildcard deletion
+    // must survive several of them
+    for _ in 0..2 {
+        let cache = ztimeout!(session
+            .declare_publication_cache("test/persist/**")
+            .persistence(&path)
+            .tombstone_retention(Duration::from_secs(60))
+            .res_async())
+        .unwrap();
+        // the retained wildcard deletion is replied too, with an empty value
+        let (values, errors) = get(&session, "test/persist/a").await;
+        assert_eq!(values, ["", "new"]);
+        assert_eq!(errors, 0);
+        ztimeout!(cache.close().res_async()).unwrap();
+    }
+
+    ztimeout!(session.close().res_async()).unwrap();
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,107 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::time::Duration;
+use zenoh::config::ModeDependentValue;
+use zenoh::prelude::r#async::*;
+use zenoh_core::ztimeout;
+use zenoh_ext::{SessionExt, SubscriberBuilderExt};
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_millis(500);
+
+async fn open_session() -> Session {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .timestamping
+        .set_enabled(Some(ModeDependentValue::Unique(true)))
+        .unwrap();
+    ztimeout!(zenoh::open(config).res_async()).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn querying_subscriber_history() {
+    let session = open_session().await;
+    let cache = ztimeout!(session
+        .declare_publication_cache("test/querying/history/**")
+        .history(10)
+        .res_async())
+    .unwrap();
+    ztimeout!(session.put("test/querying/history/a", "a").res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // The history is delivered before the live publications, both by the subscribers declared
+    // with declare_querying_subscriber and by the querying subscribers
+    let subscriber = ztimeout!(session
+        .declare_querying_subscriber("test/querying/history/**")
+        .res_async())
+    .unwrap();
+    let querying = ztimeout!(session
+        .declare_subscriber("test/querying/history/**")
+        .querying()
+        .res_async())
+    .unwrap();
+    ztimeout!(session.put("test/querying/history/b", "b").res_async()).unwrap();
+    for subscriber in [&subscriber, &querying] {
+        for value in ["a", "b"] {
+            let sample = ztimeout!(subscriber.recv_async()).unwrap();
+            assert_eq!(sample.value.to_string(), value);
+        }
+    }
+
+    ztimeout!(cache.close().res_async()).unwrap();
+    ztimeout!(session.close().res_async()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn querying_subscriber_query_prefix() {
+    let session = open_session().await;
+    let cache = ztimeout!(session
+        .declare_publication_cache("test/querying/prefix/**")
+        .queryable_prefix("cache/prefix")
+        .history(10)
+        .res_async())
+    .unwrap();
+    ztimeout!(session.put("test/querying/prefix/a", "a").res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // The history is queried on <query_prefix>/<key_expr>, its replies being on <key_expr>
+    let subscriber = ztimeout!(session
+        .declare_querying_subscriber("test/querying/prefix/**")
+        .query_prefix("cache/prefix")
+        .res_async())
+    .unwrap();
+    let sample = ztimeout!(subscriber.recv_async()).unwrap();
+    assert_eq!(sample.key_expr.as_str(), "test/querying/prefix/a");
+    assert_eq!(sample.value.to_string(), "a");
+
+    // Without the prefix, the cache is not queried
+    let unprefixed = ztimeout!(session
+        .declare_querying_subscriber("test/querying/prefix/**")
+        .res_async())
+    .unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert!(unprefixed.try_recv().is_err());
+
+    // An invalid prefix fails the declaration
+    assert!(session
+        .declare_querying_subscriber("test/querying/prefix/**")
+        .query_prefix("cache//prefix")
+        .res_async()
+        .await
+        .is_err());
+
+    ztimeout!(cache.close().res_async()).unwrap();
+    ztimeout!(session.close().res_async()).unwrap();
+}
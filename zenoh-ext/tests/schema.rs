@@ -0,0 +1,182 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_core::ztimeout;
+use zenoh_ext::{
+    PublisherSchemaExt, SchemaRegistry, SchemaRegistryConf, ValidationPolicy,
+    SCHEMA_ERROR_ATTACHMENT,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_millis(100);
+
+fn pose_registry(policy: ValidationPolicy) -> SchemaRegistry {
+    let registry = SchemaRegistry::new(policy);
+    registry
+        .register(
+            "robot/*/pose",
+            json!({
+                "type": "object",
+                "required": ["x", "y"],
+                "properties": {
+                    "x": { "type": "number" },
+                    "y": { "type": "number" },
+                    "id": { "type": "integer", "minimum": 0 },
+                    "mode": { "enum": ["auto", "manual"] },
+                    "tags": { "type": "array", "items": { "type": "string" }, "maxItems": 2 },
+                },
+                "additionalProperties": false,
+            }),
+        )
+        .unwrap();
+    registry
+}
+
+#[test]
+fn schema_validate() {
+    let registry = pose_registry(ValidationPolicy::Reject);
+    let key = keyexpr::new("robot/1/pose").unwrap();
+    let validate =
+        |payload: serde_json::Value| registry.validate(key, payload.to_string().as_bytes());
+
+    assert!(validate(json!({ "x": 1.5, "y": 2 })).is_ok());
+    assert!(validate(json!({ "x": 1, "y": 2, "mode": "auto", "tags": ["a"] })).is_ok());
+    // A number with a zero fractional part is an integer
+    assert!(validate(json!({ "x": 1, "y": 2, "id": 1.0 })).is_ok());
+    assert!(validate(json!({ "x": 1, "y": 2, "id": 1.5 })).is_err());
+    assert!(validate(json!({ "x": 1, "y": 2, "id": -1 })).is_err());
+    assert!(validate(json!({ "x": 1 })).is_err());
+    assert!(validate(json!({ "x": "1", "y": 2 })).is_err());
+    assert!(validate(json!({ "x": 1, "y": 2, "mode": "off" })).is_err());
+    assert!(validate(json!({ "x": 1, "y": 2, "tags": ["a", 1] })).is_err());
+    assert!(validate(json!({ "x": 1, "y": 2, "tags": ["a", "b", "c"] })).is_err());
+    assert!(validate(json!({ "x": 1, "y": 2, "z": 3 })).is_err());
+    assert!(registry.validate(key, b"not json").is_err());
+
+    // The other key expressions are not validated
+    assert!(registry
+        .validate(keyexpr::new("robot/1/speed").unwrap(), b"not json")
+        .is_ok());
+    assert!(registry.unregister(keyexpr::new("robot/*/pose").unwrap()));
+    assert!(registry.validate(key, b"not json").is_ok());
+}
+
+#[test]
+fn schema_config() {
+    let conf: SchemaRegistryConf = serde_json::from_value(json!({
+        "policy": "tag",
+        "schemas": { "robot/*/speed": { "type": "number", "minimum": 0 } },
+    }))
+    .unwrap();
+    let registry = SchemaRegistry::from_config(conf).unwrap();
+    assert_eq!(registry.policy(), ValidationPolicy::Tag);
+    let key = keyexpr::new("robot/1/speed").unwrap();
+    assert!(registry.validate(key, b"1").is_ok());
+    assert!(registry.validate(key, b"-1").is_err());
+
+    // Invalid schemas are rejected
+    assert!(registry.register("robot/*/speed", json!(1)).is_err());
+}
+
+#[cfg(feature = "protobuf")]
+#[test]
+fn schema_protobuf() {
+    let registry = SchemaRegistry::new(ValidationPolicy::Reject);
+    // An empty descriptor set doesn't define the message type
+    assert!(registry
+        .register_protobuf("robot/*/state", &[], "robot.State")
+        .is_err());
+}
+
+#[test]
+fn schema_policies() {
+    let invalid = Value::from(r#"{ "x": 1 }"#);
+    let valid = Value::from(r#"{ "x": 1, "y": 2 }"#);
+
+    // Reject
+    let registry = pose_registry(ValidationPolicy::Reject);
+    assert!(registry.check("robot/1/pose", &invalid).is_err());
+    assert!(registry.check("robot/1/pose", &valid).unwrap().is_none());
+    let sample = Sample::new(keyexpr::new("robot/1/pose").unwrap(), invalid.clone());
+    assert!(registry.intercept(sample).is_none());
+
+    // Tag
+    let registry = pose_registry(ValidationPolicy::Tag);
+    let attachment = registry.check("robot/1/pose", &invalid).unwrap().unwrap();
+    assert!(attachment.get(&SCHEMA_ERROR_ATTACHMENT).is_some());
+    let sample = Sample::new(keyexpr::new("robot/1/pose").unwrap(), invalid.clone());
+    let sample = registry.intercept(sample).unwrap();
+    assert!(sample
+        .attachment
+        .unwrap()
+        .get(&SCHEMA_ERROR_ATTACHMENT)
+        .is_some());
+
+    // Log
+    let registry = pose_registry(ValidationPolicy::Log);
+    assert!(registry.check("robot/1/pose", &invalid).unwrap().is_none());
+    let sample = Sample::new(keyexpr::new("robot/1/pose").unwrap(), invalid);
+    assert!(registry.intercept(sample).unwrap().attachment.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn schema_publications() {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+    let registry = Arc::new(pose_registry(ValidationPolicy::Reject));
+    let (tx, rx) = flume::unbounded();
+    let subscriber = ztimeout!(session
+        .declare_subscriber("robot/**")
+        .callback(registry.validating(move |sample| tx.send(sample).unwrap()))
+        .res_async())
+    .unwrap();
+    let publisher = ztimeout!(session.declare_publisher("robot/1/pose").res_async()).unwrap();
+
+    // The invalid publications are rejected by the publishing side
+    assert!(publisher.put_checked(&registry, r#"{ "x": 1 }"#).is_err());
+    assert!(registry
+        .put(&session, "robot/1/pose", r#"{ "x": 1 }"#)
+        .is_err());
+    ztimeout!(publisher
+        .put_checked(&registry, r#"{ "x": 1, "y": 2 }"#)
+        .unwrap()
+        .res_async())
+    .unwrap();
+    ztimeout!(registry
+        .put(&session, "robot/1/pose", r#"{ "x": 3, "y": 4 }"#)
+        .unwrap()
+        .res_async())
+    .unwrap();
+
+    // And the invalid samples published anyway are dropped by the subscribing side
+    ztimeout!(publisher.put(r#"{ "x": 5 }"#).res_async()).unwrap();
+    assert_eq!(
+        ztimeout!(rx.recv_async()).unwrap().value.to_string(),
+        r#"{ "x": 1, "y": 2 }"#
+    );
+    assert_eq!(
+        ztimeout!(rx.recv_async()).unwrap().value.to_string(),
+        r#"{ "x": 3, "y": 4 }"#
+    );
+    tokio::time::sleep(SLEEP).await;
+    assert!(rx.is_empty());
+
+    ztimeout!(subscriber.undeclare().res_async()).unwrap();
+    ztimeout!(session.close().res_async()).unwrap();
+}
@@ -0,0 +1,71 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::{Deserialize, Serialize};
+use zenoh_ext::{z_deserialize, z_serialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct PositionV1 {
+    x: f64,
+    y: f64,
+}
+
+// The same type, with reordered fields and a new optional one
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct PositionV2 {
+    y: f64,
+    x: f64,
+    #[serde(default)]
+    z: Option<f64>,
+}
+
+#[test]
+fn serialization_roundtrip() {
+    let value = z_serialize(&PositionV1 { x: 1.0, y: 2.0 }).unwrap();
+    assert_eq!(value.encoding.to_string(), "application/custom;cbor");
+    assert_eq!(
+        z_deserialize::<PositionV1>(&value).unwrap(),
+        PositionV1 { x: 1.0, y: 2.0 }
+    );
+
+    let value = z_serialize(&vec!["a", "b"]).unwrap();
+    assert_eq!(z_deserialize::<Vec<String>>(&value).unwrap(), ["a", "b"]);
+}
+
+#[test]
+fn serialization_evolution() {
+    // The payloads are self-describing: the fields are matched by name
+    let value = z_serialize(&PositionV1 { x: 1.0, y: 2.0 }).unwrap();
+    assert_eq!(
+        z_deserialize::<PositionV2>(&value).unwrap(),
+        PositionV2 {
+            y: 2.0,
+            x: 1.0,
+            z: None
+        }
+    );
+    let value = z_serialize(&PositionV2 {
+        y: 2.0,
+        x: 1.0,
+        z: Some(3.0),
+    })
+    .unwrap();
+    assert_eq!(
+        z_deserialize::<PositionV1>(&value).unwrap(),
+        PositionV1 { x: 1.0, y: 2.0 }
+    );
+
+    // A mismatching payload is an error
+    let value = z_serialize(&"not a position").unwrap();
+    assert!(z_deserialize::<PositionV1>(&value).is_err());
+}
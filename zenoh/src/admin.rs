@@ -18,12 +18,13 @@ use crate::{
     sample::DataInfo,
     Sample, Session, ZResult,
 };
+use serde_json::json;
 use std::{
     collections::hash_map::DefaultHasher,
     hash::{Hash, Hasher},
     sync::Arc,
 };
-use zenoh_core::SyncResolve;
+use zenoh_core::{zread, SyncResolve};
 use zenoh_protocol::{
     core::{Encoding, KnownEncoding, WireExpr},
     network::NetworkMessage,
@@ -43,6 +44,10 @@ lazy_static::lazy_static!(
     static ref KE_PREFIX: &'static keyexpr = ke_for_sure!("@/session");
     static ref KE_TRANSPORT_UNICAST: &'static keyexpr = ke_for_sure!("transport/unicast");
     static ref KE_LINK: &'static keyexpr = ke_for_sure!("link");
+    static ref KE_PUBLISHER: &'static keyexpr = ke_for_sure!("publisher");
+    static ref KE_SUBSCRIBER: &'static keyexpr = ke_for_sure!("subscriber");
+    static ref KE_QUERYABLE: &'static keyexpr = ke_for_sure!("queryable");
+    static ref KE_PUBLICATION: &'static keyexpr = ke_for_sure!("publication");
 );
 
 pub(crate) fn init(session: &Session) {
@@ -60,6 +65,61 @@ pub(crate) fn init(session: &Session) {
                 move |q| super::admin::on_admin_query(&session, q)
             }),
         );
+
+        // The publishers are discoverable by the remote sessions if configured, the local
+        // ones being listed directly by the session
+        #[cfg(feature = "unstable")]
+        let publications = session.runtime.config().lock().adminspace.publications;
+        #[cfg(feature = "unstable")]
+        if publications {
+            let publication_key =
+                KeyExpr::from(*KE_PREFIX / own_zid / *KE_PUBLICATION / *KE_STARSTAR)
+                    .to_wire(session)
+                    .to_owned();
+            let _publication_qabl = session.declare_queryable_inner(
+                &publication_key,
+                false,
+                Locality::Remote,
+                Arc::new({
+                    let session = session.clone();
+                    move |q| super::admin::on_publication_query(&session, q)
+                }),
+            );
+        }
+    }
+}
+
+#[cfg(feature = "unstable")]
+pub(crate) fn local_publishers(session: &Session) -> Vec<crate::info::PublisherInfo> {
+    let zid = session.zid();
+    zread!(session.state)
+        .publishers
+        .values()
+        .map(|p| {
+            let (congestion_control, priority) = *zenoh_core::zlock!(p.qos);
+            crate::info::PublisherInfo {
+                zid,
+                key_expr: p.key_expr.clone().into(),
+                congestion_control,
+                priority,
+            }
+        })
+        .collect()
+}
+
+#[cfg(feature = "unstable")]
+pub(crate) fn on_publication_query(session: &Session, query: Query) {
+    let zid_str = session.zid().to_string();
+    let Ok(own_zid) = keyexpr::new(&zid_str) else {
+        return;
+    };
+    for publisher in local_publishers(session) {
+        let key_expr = *KE_PREFIX / own_zid / *KE_PUBLICATION / &*publisher.key_expr;
+        if query.key_expr().intersects(&key_expr) {
+            let _ = query
+                .reply(Ok(Sample::new(key_expr, publisher.to_json())))
+                .res_sync();
+        }
     }
 }
 
@@ -90,7 +150,62 @@ pub(crate) fn on_admin_query(session: &Session, query: Query) {
         }
     }
 
+    fn reply_entities(own_zid: &keyexpr, session: &Session, query: &Query) {
+        let (publishers, subscribers, queryables): (Vec<_>, Vec<_>, Vec<_>) = {
+            let state = zread!(session.state);
+            (
+                state
+                    .publishers
+                    .values()
+                    .map(|p| {
+                        (
+                            p.id,
+                            json!({ "key_expr": p.key_expr.as_str(), "stats": p.stats.report() }),
+                        )
+                    })
+                    .collect(),
+                state
+                    .subscribers
+                    .values()
+                    .map(|s| {
+                        (
+                            s.id,
+                            json!({ "key_expr": s.key_expr.as_str(), "stats": s.stats.report() }),
+                        )
+                    })
+                    .collect(),
+                state
+                    .queryables
+                    .values()
+                    .filter_map(|q| {
+                        let key_expr = state.local_wireexpr_to_expr(&q.key_expr).ok()?;
+                        Some((
+                            q.id,
+                            json!({ "key_expr": key_expr.as_str(), "complete": q.complete, "stats": q.stats.report() }),
+                        ))
+                    })
+                    .collect(),
+            )
+        };
+        for (kind, entities) in [
+            (*KE_PUBLISHER, publishers),
+            (*KE_SUBSCRIBER, subscribers),
+            (*KE_QUERYABLE, queryables),
+        ] {
+            for (id, value) in entities {
+                let id = id.to_string();
+                if let Ok(id) = keyexpr::new(&id) {
+                    let key_expr = *KE_PREFIX / own_zid / kind / id;
+                    if query.key_expr().intersects(&key_expr) {
+                        let _ = query.reply(Ok(Sample::new(key_expr, value))).res_sync();
+                    }
+                }
+            }
+        }
+    }
+
     if let Ok(own_zid) = keyexpr::new(&session.zid().to_string()) {
+        reply_entities(own_zid, session, &query);
         for transport in zenoh_runtime::ZRuntime::Net
             .block_in_place(session.runtime.manager().get_transports_unicast())
         {
@@ -162,6 +277,10 @@ impl TransportMulticastEventHandler for Handler {
                 Ok(Arc::new(PeerHandler {
                     expr,
                     session: self.session.clone(),
+                    #[cfg(feature = "unstable")]
+                    zid: peer.zid,
+                    #[cfg(feature = "unstable")]
+                    whatami: peer.whatami,
                 }))
             } else {
                 bail!("Unable to build keyexpr from zid")
@@ -183,6 +302,10 @@ impl TransportMulticastEventHandler for Handler {
 pub(crate) struct PeerHandler {
     pub(crate) expr: WireExpr<'static>,
     pub(crate) session: Arc<Session>,
+    #[cfg(feature = "unstable")]
+    pub(crate) zid: zenoh_protocol::core::ZenohId,
+    #[cfg(feature = "unstable")]
+    pub(crate) whatami: zenoh_protocol::core::WhatAmI,
 }
 
 impl TransportPeerEventHandler for PeerHandler {
@@ -230,6 +353,15 @@ impl TransportPeerEventHandler for PeerHandler {
         );
     }
 
+    #[cfg(feature = "unstable")]
+    fn close_reason(&self, reason: u8) {
+        self.session.handle_closing(crate::info::ClosingEvent {
+            zid: self.zid,
+            whatami: self.whatami,
+            reason: reason.into(),
+        });
+    }
+
     fn closing(&self) {}
 
     fn closed(&self) {
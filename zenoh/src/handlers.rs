@@ -60,7 +60,7 @@ pub struct DefaultHandler;
 impl<T: Send + 'static> IntoCallbackReceiverPair<'static, T> for DefaultHandler {
     type Receiver = flume::Receiver<T>;
     fn into_cb_receiver_pair(self) -> (Callback<'static, T>, Self::Receiver) {
-        flume::bounded(*API_DATA_RECEPTION_CHANNEL_SIZE).into_cb_receiver_pair()
+        flume::bounded(API_DATA_RECEPTION_CHANNEL_SIZE.get()).into_cb_receiver_pair()
     }
 }
 impl<T: Send + Sync + 'static> IntoCallbackReceiverPair<'static, T>
@@ -17,6 +17,15 @@ use crate::SessionRef;
 use std::future::Ready;
 use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
 use zenoh_protocol::core::{WhatAmI, ZenohId};
+#[zenoh_macros::unstable]
+use {
+    crate::prelude::{KeyExpr, OwnedKeyExpr, Priority},
+    crate::publication::CongestionControl,
+    crate::query::{ConsolidationMode, QueryTarget, Reply},
+    std::{convert::TryInto, future::Future, pin::Pin, time::Duration},
+    zenoh_buffers::buffer::SplitBuffer,
+    zenoh_result::{zerror, ZResult},
+};
 
 /// A builder retuned by [`SessionInfo::zid()`](SessionInfo::zid) that allows
 /// to access the [`ZenohId`] of the current zenoh [`Session`](crate::Session).
@@ -228,4 +237,259 @@ impl SessionInfo<'_> {
             session: self.session.clone(),
         }
     }
+
+    /// Return the publishers currently declared on the reachable zenoh sessions
+    /// (including this one) whose key expression intersects with `key_expr`.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// for publisher in session.info().publishers("demo/**").res().await.unwrap() {
+    ///     println!("{} publishes on {}", publisher.zid, publisher.key_expr);
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn publishers<'b, TryIntoKeyExpr>(
+        &self,
+        key_expr: TryIntoKeyExpr,
+    ) -> PublishersBuilder<'_, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        PublishersBuilder {
+            session: self.session.clone(),
+            key_expr: key_expr.try_into().map_err(Into::into),
+            timeout: None,
+        }
+    }
+}
+
+/// The description of a publisher, as returned by [`SessionInfo::publishers()`](SessionInfo::publishers).
+#[zenoh_macros::unstable]
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublisherInfo {
+    /// The [`ZenohId`] of the session which declared the publisher.
+    pub zid: ZenohId,
+    /// The key expression of the publisher.
+    pub key_expr: OwnedKeyExpr,
+    /// The congestion control applied by the publisher.
+    pub congestion_control: CongestionControl,
+    /// The priority of the data written by the publisher.
+    pub priority: Priority,
+}
+
+#[zenoh_macros::unstable]
+impl PublisherInfo {
+    pub(crate) fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "zid": self.zid.to_string(),
+            "key_expr": self.key_expr.as_str(),
+            "congestion_control": match self.congestion_control {
+                CongestionControl::Drop => "drop",
+                CongestionControl::Block => "block",
+            },
+            "priority": self.priority as u8,
+        })
+    }
+
+    pub(crate) fn from_json(value: &serde_json::Value) -> ZResult<Self> {
+        let field = |name: &str| {
+            value
+                .get(name)
+                .ok_or_else(|| zerror!("Invalid publisher info: missing {}", name))
+        };
+        let as_str = |name: &str| {
+            field(name)?
+                .as_str()
+                .ok_or_else(|| zerror!("Invalid publisher info: {} is not a string", name))
+        };
+        Ok(PublisherInfo {
+            zid: as_str("zid")?.parse()?,
+            key_expr: OwnedKeyExpr::try_from(as_str("key_expr")?)?,
+            congestion_control: match as_str("congestion_control")? {
+                "block" => CongestionControl::Block,
+                _ => CongestionControl::Drop,
+            },
+            priority: field("priority")?
+                .as_u64()
+                .and_then(|p| u8::try_from(p).ok())
+                .ok_or_else(|| zerror!("Invalid publisher info: invalid priority"))?
+                .try_into()?,
+        })
+    }
+}
+
+/// A builder returned by [`SessionInfo::publishers()`](SessionInfo::publishers) that allows
+/// to list the publishers matching a key expression.
+///
+/// The publishers of the session itself are listed directly, the ones of the other zenoh sessions
+/// are discovered by querying the reachable sessions, the replies being collected until the
+/// query timeout.
+#[zenoh_macros::unstable]
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+#[derive(Debug)]
+pub struct PublishersBuilder<'a, 'b> {
+    pub(crate) session: SessionRef<'a>,
+    pub(crate) key_expr: ZResult<KeyExpr<'b>>,
+    pub(crate) timeout: Option<Duration>,
+}
+
+#[zenoh_macros::unstable]
+impl PublishersBuilder<'_, '_> {
+    /// Set the timeout of the discovery query.
+    /// By default, the session's `queries_default_timeout` is used.
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    fn selector(&self) -> ZResult<String> {
+        let key_expr = self.key_expr.as_ref().map_err(|e| zerror!("{}", e))?;
+        Ok(format!("@/session/*/publication/{key_expr}"))
+    }
+
+    fn local_publishers(&self) -> ZResult<Vec<PublisherInfo>> {
+        let key_expr = self.key_expr.as_ref().map_err(|e| zerror!("{}", e))?;
+        Ok(crate::admin::local_publishers(&self.session)
+            .into_iter()
+            .filter(|p| key_expr.intersects(&p.key_expr))
+            .collect())
+    }
+}
+
+#[zenoh_macros::unstable]
+fn collect_publisher(reply: Reply, publishers: &mut Vec<PublisherInfo>) {
+    if let Ok(sample) = reply.sample {
+        match serde_json::from_slice(&sample.payload.contiguous())
+            .map_err(|e| zerror!("{}", e).into())
+            .and_then(|v| PublisherInfo::from_json(&v))
+        {
+            Ok(publisher) => publishers.push(publisher),
+            Err(e) => tracing::debug!("Invalid publisher info on {}: {}", sample.key_expr, e),
+        }
+    }
+}
+
+#[zenoh_macros::unstable]
+impl Resolvable for PublishersBuilder<'_, '_> {
+    type To = ZResult<Vec<PublisherInfo>>;
+}
+
+#[zenoh_macros::unstable]
+impl SyncResolve for PublishersBuilder<'_, '_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        let mut get = self
+            .session
+            .get(self.selector()?)
+            .target(QueryTarget::All)
+            .consolidation(ConsolidationMode::None);
+        if let Some(timeout) = self.timeout {
+            get = get.timeout(timeout);
+        }
+        let replies = get.res_sync()?;
+        let mut publishers = self.local_publishers()?;
+        while let Ok(reply) = replies.recv() {
+            collect_publisher(reply, &mut publishers);
+        }
+        Ok(publishers)
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<'a> AsyncResolve for PublishersBuilder<'a, '_> {
+    type Future = Pin<Box<dyn Future<Output = Self::To> + Send + 'a>>;
+
+    fn res_async(self) -> Self::Future {
+        let selector = self.selector();
+        let local_publishers = self.local_publishers();
+        let session = self.session.clone();
+        let timeout = self.timeout;
+        Box::pin(async move {
+            let mut get = session
+                .get(selector?)
+                .target(QueryTarget::All)
+                .consolidation(ConsolidationMode::None);
+            if let Some(timeout) = timeout {
+                get = get.timeout(timeout);
+            }
+            let replies = get.res_async().await?;
+            let mut publishers = local_publishers?;
+            while let Ok(reply) = replies.recv_async().await {
+                collect_publisher(reply, &mut publishers);
+            }
+            Ok(publishers)
+        })
+    }
+}
+
+/// The reason of the closure of a transport with a remote zenoh node.
+#[zenoh_macros::unstable]
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseReason {
+    /// The remote node closed the transport without a specific reason.
+    Generic,
+    /// The remote node does not support the requested protocol features.
+    Unsupported,
+    /// The remote node received an invalid message, or refused the authentication.
+    Invalid,
+    /// The remote node reached its maximum number of sessions.
+    MaxSessions,
+    /// The remote node reached its maximum number of links.
+    MaxLinks,
+    /// The lease of the transport expired, i.e. the remote node became unreachable.
+    Expired,
+    /// A reason not known by this version of zenoh.
+    Unknown(u8),
+}
+
+#[zenoh_macros::unstable]
+impl From<u8> for CloseReason {
+    fn from(reason: u8) -> Self {
+        use zenoh_protocol::transport::close::reason;
+        match reason {
+            reason::GENERIC => CloseReason::Generic,
+            reason::UNSUPPORTED => CloseReason::Unsupported,
+            reason::INVALID => CloseReason::Invalid,
+            reason::MAX_SESSIONS => CloseReason::MaxSessions,
+            reason::MAX_LINKS => CloseReason::MaxLinks,
+            reason::EXPIRED => CloseReason::Expired,
+            r => CloseReason::Unknown(r),
+        }
+    }
+}
+
+/// The event notified to the callbacks registered with
+/// [`Session::on_closing()`](crate::Session::on_closing) when a transport is being closed.
+#[zenoh_macros::unstable]
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct ClosingEvent {
+    /// The [`ZenohId`] of the remote node.
+    pub zid: ZenohId,
+    /// The kind of the remote node.
+    pub whatami: WhatAmI,
+    /// The reason of the closure.
+    pub reason: CloseReason,
+}
+
+/// The event notified to the callbacks registered with
+/// [`Session::on_declaration_rejected()`](crate::Session::on_declaration_rejected) when a
+/// declaration of the session exceeded the routing limits of the node it is connected to.
+#[zenoh_macros::unstable]
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct DeclarationRejectedEvent {
+    /// The [`ZenohId`] of the node that rejected the declaration.
+    pub zid: ZenohId,
+    /// The limit the declaration exceeded.
+    pub reason: String,
 }
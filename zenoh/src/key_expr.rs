@@ -242,6 +242,25 @@ impl<'a> KeyExpr<'a> {
     /// ```
     pub fn join<S: AsRef<str> + ?Sized>(&self, s: &S) -> ZResult<KeyExpr<'static>> {
         let r = self.as_keyexpr().join(s)?;
+        Ok(self.joined(r))
+    }
+
+    /// Joins both sides like [`KeyExpr::join`], but fails rather than moving a key in or out of the admin space.
+    ///
+    /// This should be preferred when joining a prefix with user-provided key expressions:
+    /// ```rust
+    /// # use std::convert::TryFrom;
+    /// # use zenoh::prelude::KeyExpr;
+    /// let prefix = KeyExpr::try_from("cache").unwrap();
+    /// assert!(prefix.checked_join("some/topic").is_ok());
+    /// assert!(prefix.checked_join("@/session/**").is_err());
+    /// ```
+    pub fn checked_join<S: AsRef<str> + ?Sized>(&self, s: &S) -> ZResult<KeyExpr<'static>> {
+        let r = self.as_keyexpr().checked_join(s)?;
+        Ok(self.joined(r))
+    }
+
+    fn joined(&self, r: OwnedKeyExpr) -> KeyExpr<'static> {
         if let KeyExprInner::Wire {
             expr_id,
             mapping,
@@ -250,15 +269,15 @@ impl<'a> KeyExpr<'a> {
             ..
         } = &self.0
         {
-            Ok(KeyExpr(KeyExprInner::Wire {
+            KeyExpr(KeyExprInner::Wire {
                 key_expr: r,
                 expr_id: *expr_id,
                 mapping: *mapping,
                 prefix_len: *prefix_len,
                 session_id: *session_id,
-            }))
+            })
         } else {
-            Ok(r.into())
+            r.into()
         }
     }
 
@@ -274,6 +293,14 @@ impl<'a> KeyExpr<'a> {
         if self.ends_with('*') && s.starts_with('*') {
             bail!("Tried to concatenate {} (ends with *) and {} (starts with *), which would likely have caused bugs. If you're sure you want to do this, concatenate these into a string and then try to convert.", self, s)
         }
+        if !s.starts_with('/')
+            && self
+                .rsplit('/')
+                .next()
+                .map_or(false, |chunk| chunk.starts_with('@'))
+        {
+            bail!("Tried to concatenate {} (ends with a verbatim chunk) and {}, which would have altered that verbatim chunk. Use `join` instead.", self, s)
+        }
         let r = OwnedKeyExpr::try_from(format!("{self}{s}"))?;
         if let KeyExprInner::Wire {
             expr_id,
@@ -93,6 +93,12 @@ pub use zenoh_macros::{ke, kedefine, keformat, kewrite};
 use zenoh_protocol::core::WhatAmIMatcher;
 use zenoh_result::{zerror, ZResult};
 use zenoh_util::concat_enabled_features;
+pub use zenoh_util::{init_log_from_env_or, try_init_log_from_env};
+#[zenoh_macros::unstable]
+pub use zenoh_util::LogConfig;
+#[cfg(feature = "log-file")]
+#[zenoh_macros::unstable]
+pub use zenoh_util::LogRotation;
 
 /// A zenoh error.
 pub use zenoh_result::Error;
@@ -111,6 +117,7 @@ pub const FEATURES: &str = concat_enabled_features!(
         "auth_pubkey",
         "auth_usrpwd",
         "complete_n",
+        "log-file",
         "shared-memory",
         "stats",
         "transport_multilink",
@@ -129,6 +136,7 @@ pub const FEATURES: &str = concat_enabled_features!(
 );
 
 mod admin;
+mod ordering;
 #[macro_use]
 mod session;
 pub use session::*;
@@ -150,8 +158,14 @@ pub mod publication;
 pub mod query;
 pub mod queryable;
 pub mod sample;
+#[cfg(feature = "unstable")]
+pub mod scope;
+pub mod stats;
 pub mod subscriber;
 pub mod value;
+/// A link over streams supplied by the application, see [`custom::register_stream`].
+#[cfg(feature = "transport_custom")]
+pub use zenoh_link::custom;
 #[cfg(feature = "shared-memory")]
 pub use zenoh_shm as shm;
 
@@ -239,6 +253,48 @@ where
     }
 }
 
+/// Scout continuously for routers and/or peers, reporting their changes.
+///
+/// [`scout_watch`] spawns a task that periodically sends scout messages and reports a
+/// [`ScoutEvent`](crate::scouting::ScoutEvent) when a node replies for the first time, when its
+/// [`Hello`](crate::scouting::Hello) changes, and when it does not reply within a timeout.
+///
+/// Drop the returned [`Scout`](crate::scouting::Scout) to stop the scouting task.
+///
+/// # Arguments
+///
+/// * `what` - The kind of zenoh process to scout for
+/// * `config` - The configuration [`Config`] to use for scouting
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+/// use zenoh::scouting::WhatAmI;
+///
+/// let receiver = zenoh::scout_watch(WhatAmI::Peer | WhatAmI::Router, config::default())
+///     .res()
+///     .await
+///     .unwrap();
+/// while let Ok(event) = receiver.recv_async().await {
+///     println!("{:?}", event);
+/// }
+/// # }
+/// ```
+#[zenoh_macros::unstable]
+pub fn scout_watch<I: Into<WhatAmIMatcher>, TryIntoConfig>(
+    what: I,
+    config: TryIntoConfig,
+) -> scouting::ScoutWatchBuilder<DefaultHandler>
+where
+    TryIntoConfig: std::convert::TryInto<crate::config::Config> + Send + 'static,
+    <TryIntoConfig as std::convert::TryInto<crate::config::Config>>::Error:
+        Into<zenoh_result::Error>,
+{
+    scouting::ScoutWatchBuilder::new(what.into(), config.try_into().map_err(|e| e.into()))
+}
+
 /// Open a zenoh [`Session`].
 ///
 /// # Arguments
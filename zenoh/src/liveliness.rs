@@ -559,7 +559,7 @@ where
             .declare_subscriber_inner(
                 &key_expr,
                 &Some(KeyExpr::from(*KE_PREFIX_LIVELINESS)),
-                Locality::default(),
+                session.default_locality(),
                 callback,
                 &SubscriberInfo::default(),
             )
@@ -765,11 +765,13 @@ where
                 &Some(KeyExpr::from(*KE_PREFIX_LIVELINESS)),
                 QueryTarget::default(),
                 QueryConsolidation::default(),
-                Locality::default(),
+                self.session.default_locality(),
                 self.timeout,
                 None,
                 #[cfg(feature = "unstable")]
                 None,
+                #[cfg(feature = "unstable")]
+                crate::query::ReplyOrdering::None,
                 callback,
             )
             .map(|_| receiver)
@@ -14,16 +14,17 @@
 use super::Zenoh080Routing;
 use crate::net::protocol::{
     linkstate,
-    linkstate::{LinkState, LinkStateList},
+    linkstate::{ext, LinkState, LinkStateList, LinkStateTtl},
 };
 use core::convert::TryFrom;
 use zenoh_buffers::{
-    reader::{DidntRead, Reader},
-    writer::{DidntWrite, Writer},
+    reader::{DidntRead, HasReader, Reader},
+    writer::{DidntWrite, HasWriter, Writer},
+    ZBuf,
 };
-use zenoh_codec::{RCodec, WCodec, Zenoh080};
+use zenoh_codec::{common::extension, CodecLimits, RCodec, WCodec, Zenoh080, Zenoh080Header};
 use zenoh_protocol::{
-    common::imsg,
+    common::{iext, imsg},
     core::{Locator, WhatAmI, ZenohId},
 };
 
@@ -101,6 +102,7 @@ where
             None
         };
         let len: usize = codec.read(&mut *reader)?;
+        let len = CodecLimits::check_links(reader, len)?;
         let mut links: Vec<u64> = Vec::with_capacity(len);
         for _ in 0..len {
             let l: u64 = codec.read(&mut *reader)?;
@@ -114,6 +116,8 @@ where
             whatami,
             locators,
             links,
+            weights: None,
+            ttl: LinkStateTtl::default(),
         })
     }
 }
@@ -133,6 +137,38 @@ where
             self.write(&mut *writer, ls)?;
         }
 
+        // Extensions
+        let has_ttl = x
+            .link_states
+            .iter()
+            .any(|ls| ls.ttl != LinkStateTtl::default());
+        if x.link_states.iter().any(|ls| ls.weights.is_some()) {
+            let mut value = ZBuf::empty();
+            let mut value_writer = value.writer();
+            for ls in x.link_states.iter() {
+                let weights = ls.weights.as_deref().unwrap_or_default();
+                if !weights.is_empty() && weights.len() != ls.links.len() {
+                    return Err(DidntWrite);
+                }
+                codec.write(&mut value_writer, weights.len())?;
+                for w in weights.iter() {
+                    codec.write(&mut value_writer, *w)?;
+                }
+            }
+            codec.write(&mut *writer, (&ext::Weights::new(value), has_ttl))?;
+        }
+        if has_ttl {
+            let mut value = ZBuf::empty();
+            let mut value_writer = value.writer();
+            for ls in x.link_states.iter() {
+                let hops = ls.ttl.hops.map_or(0, |h| h as u64 + 1);
+                let lifetime = ls.ttl.lifetime.map_or(0, |l| l.saturating_add(1));
+                codec.write(&mut value_writer, hops)?;
+                codec.write(&mut value_writer, lifetime)?;
+            }
+            codec.write(&mut *writer, (&ext::Ttl::new(value), false))?;
+        }
+
         Ok(())
     }
 }
@@ -147,12 +183,217 @@ where
         let codec = Zenoh080::new();
 
         let len: usize = codec.read(&mut *reader)?;
+        let len = CodecLimits::check_links(reader, len)?;
         let mut link_states = Vec::with_capacity(len);
         for _ in 0..len {
             let ls: LinkState = self.read(&mut *reader)?;
             link_states.push(ls);
         }
 
+        // Extensions
+        let mut has_ext = reader.can_read();
+        while has_ext {
+            let header: u8 = codec.read(&mut *reader)?;
+            match iext::eid(header) {
+                ext::Weights::ID => {
+                    let (ext, more): (ext::Weights, bool) =
+                        Zenoh080Header::new(header).read(&mut *reader)?;
+                    let mut r = ext.value.reader();
+                    for ls in link_states.iter_mut() {
+                        let n: usize = codec.read(&mut r)?;
+                        if n == 0 {
+                            continue;
+                        }
+                        if n != ls.links.len() {
+                            return Err(DidntRead);
+                        }
+                        let n = CodecLimits::check_links(&r, n)?;
+                        let mut weights = Vec::with_capacity(n);
+                        for _ in 0..n {
+                            let w: u16 = codec.read(&mut r)?;
+                            weights.push(w);
+                        }
+                        ls.weights = Some(weights);
+                    }
+                    has_ext = more;
+                }
+                ext::Ttl::ID => {
+                    let (ext, more): (ext::Ttl, bool) =
+                        Zenoh080Header::new(header).read(&mut *reader)?;
+                    let mut r = ext.value.reader();
+                    for ls in link_states.iter_mut() {
+                        let hops: u64 = codec.read(&mut r)?;
+                        let lifetime: u64 = codec.read(&mut r)?;
+                        ls.ttl = LinkStateTtl {
+                            hops: match hops {
+                                0 => None,
+                                h => Some(u8::try_from(h - 1).map_err(|_| DidntRead)?),
+                            },
+                            lifetime: lifetime.checked_sub(1),
+                        };
+                    }
+                    has_ext = more;
+                }
+                _ => {
+                    has_ext = extension::skip(reader, "LinkStateList", header)?;
+                }
+            }
+        }
+
         Ok(LinkStateList { link_states })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zenoh_protocol::{common::ZExtZ64, zextz64};
+
+    fn link_state(psid: u64, links: Vec<u64>, weights: Option<Vec<u16>>) -> LinkState {
+        LinkState {
+            psid,
+            sn: 1,
+            zid: Some(ZenohId::default()),
+            whatami: Some(WhatAmI::Router),
+            locators: None,
+            links,
+            weights,
+            ttl: LinkStateTtl::default(),
+        }
+    }
+
+    #[test]
+    fn linkstate_weights() {
+        let codec = Zenoh080Routing::new();
+        let list = LinkStateList {
+            link_states: vec![
+                link_state(0, vec![1, 2], Some(vec![100, 250])),
+                link_state(1, vec![0], None),
+                link_state(2, vec![0], Some(vec![120])),
+            ],
+        };
+
+        let mut buf = ZBuf::empty();
+        codec.write(&mut buf.writer(), &list).unwrap();
+        let read: LinkStateList = codec.read(&mut buf.reader()).unwrap();
+        assert_eq!(read, list);
+
+        // A node not knowing the extensions reads the link states and ignores the rest
+        let mut reader = buf.reader();
+        let len: usize = Zenoh080::new().read(&mut reader).unwrap();
+        assert_eq!(len, 3);
+        for ls in list.link_states.iter() {
+            let read: LinkState = codec.read(&mut reader).unwrap();
+            assert_eq!(
+                read,
+                LinkState {
+                    weights: None,
+                    ..ls.clone()
+                }
+            );
+        }
+
+        // An unknown extension is skipped
+        let mut buf = ZBuf::empty();
+        let mut writer = buf.writer();
+        codec
+            .write(
+                &mut writer,
+                &LinkStateList {
+                    link_states: vec![],
+                },
+            )
+            .unwrap();
+        Zenoh080::new()
+            .write(&mut writer, (&<zextz64!(0x7, false)>::new(42), false))
+            .unwrap();
+        let read: LinkStateList = codec.read(&mut buf.reader()).unwrap();
+        assert!(read.link_states.is_empty());
+
+        // The weights must match the links
+        let list = LinkStateList {
+            link_states: vec![link_state(0, vec![1, 2], Some(vec![100]))],
+        };
+        assert!(codec.write(&mut ZBuf::empty().writer(), &list).is_err());
+    }
+
+    #[test]
+    fn linkstate_ttl() {
+        let codec = Zenoh080Routing::new();
+        let ttl = |hops, lifetime| LinkStateTtl { hops, lifetime };
+        let list = LinkStateList {
+            link_states: vec![
+                LinkState {
+                    ttl: ttl(Some(0), None),
+                    ..link_state(0, vec![1], Some(vec![100]))
+                },
+                link_state(1, vec![0], None),
+                LinkState {
+                    ttl: ttl(Some(u8::MAX), Some(10_000)),
+                    ..link_state(2, vec![0], None)
+                },
+                LinkState {
+                    ttl: ttl(None, Some(0)),
+                    ..link_state(3, vec![], None)
+                },
+            ],
+        };
+
+        let mut buf = ZBuf::empty();
+        codec.write(&mut buf.writer(), &list).unwrap();
+        let read: LinkStateList = codec.read(&mut buf.reader()).unwrap();
+        assert_eq!(read, list);
+
+        // A node not knowing the extensions reads the link states without their limits
+        let mut reader = buf.reader();
+        let len: usize = Zenoh080::new().read(&mut reader).unwrap();
+        assert_eq!(len, 4);
+        for ls in list.link_states.iter() {
+            let read: LinkState = codec.read(&mut reader).unwrap();
+            assert_eq!(
+                read,
+                LinkState {
+                    weights: None,
+                    ttl: LinkStateTtl::default(),
+                    ..ls.clone()
+                }
+            );
+        }
+
+        // No extension is written without limits
+        let list = LinkStateList {
+            link_states: vec![link_state(0, vec![1], None)],
+        };
+        let mut buf = ZBuf::empty();
+        codec.write(&mut buf.writer(), &list).unwrap();
+        let mut reader = buf.reader();
+        let _: LinkStateList = codec.read(&mut reader).unwrap();
+        assert!(!reader.can_read());
+    }
+
+    #[test]
+    fn linkstate_bounded() {
+        let codec = Zenoh080Routing::new();
+
+        // A list announcing a huge number of link states not present in the message
+        let mut buf = ZBuf::empty();
+        Zenoh080::new()
+            .write(&mut buf.writer(), u64::MAX >> 1)
+            .unwrap();
+        let res: Result<LinkStateList, _> = codec.read(&mut buf.reader());
+        assert!(res.is_err());
+
+        // A link state announcing a huge number of links not present in the message
+        let mut bytes = vec![];
+        codec
+            .write(&mut bytes.writer(), &link_state(0, vec![], None))
+            .unwrap();
+        // The number of links is the last field
+        bytes.pop();
+        Zenoh080::new()
+            .write(&mut bytes.writer(), u64::MAX >> 1)
+            .unwrap();
+        let res: Result<LinkState, _> = codec.read(&mut bytes.reader());
+        assert!(res.is_err());
+    }
+}
@@ -13,13 +13,24 @@
 //
 use super::Primitives;
 use crate::net::routing::{
-    dispatcher::face::Face,
+    dispatcher::{
+        face::{declaration_rejected_received, Face},
+        pubsub::congestion_received,
+        queries::queryable_conflict_received,
+        tables::update_face_links,
+    },
     interceptor::{InterceptorTrait, InterceptorsChain},
     RoutingContext,
 };
 use std::{any::Any, sync::Arc};
 use zenoh_link::Link;
-use zenoh_protocol::network::{NetworkBody, NetworkMessage};
+use zenoh_protocol::{
+    common::ZExtBody,
+    network::{
+        oam::id::{OAM_CONGESTION, OAM_DECLARE_REJECTED, OAM_QUERYABLE_CONFLICT},
+        NetworkBody, NetworkMessage,
+    },
+};
 use zenoh_result::ZResult;
 use zenoh_transport::unicast::TransportUnicast;
 use zenoh_transport::TransportPeerEventHandler;
@@ -42,6 +53,14 @@ impl DeMux {
             interceptor,
         }
     }
+
+    // The links of the transport are the next hops of the static routes
+    fn update_links(&self) {
+        if let Some(links) = self.transport.as_ref().and_then(|t| t.get_links().ok()) {
+            let links = links.into_iter().map(|link| link.dst).collect();
+            update_face_links(&self.face.tables, &self.face.state, links);
+        }
+    }
 }
 
 impl TransportPeerEventHandler for DeMux {
@@ -70,6 +89,21 @@ impl TransportPeerEventHandler for DeMux {
             NetworkBody::Request(m) => self.face.send_request(m),
             NetworkBody::Response(m) => self.face.send_response(m),
             NetworkBody::ResponseFinal(m) => self.face.send_response_final(m),
+            NetworkBody::OAM(m) if m.id == OAM_DECLARE_REJECTED => {
+                if let ZExtBody::ZBuf(zbuf) = &m.body {
+                    declaration_rejected_received(&self.face.tables, &self.face.state, zbuf);
+                }
+            }
+            NetworkBody::OAM(m) if m.id == OAM_QUERYABLE_CONFLICT => {
+                if let ZExtBody::ZBuf(zbuf) = &m.body {
+                    queryable_conflict_received(&self.face.tables, &self.face.state, zbuf);
+                }
+            }
+            NetworkBody::OAM(m) if m.id == OAM_CONGESTION => {
+                if let ZExtBody::ZBuf(zbuf) = &m.body {
+                    congestion_received(&self.face.tables, &self.face.state, zbuf);
+                }
+            }
             NetworkBody::OAM(m) => {
                 if let Some(transport) = self.transport.as_ref() {
                     let ctrl_lock = zlock!(self.face.tables.ctrl_lock);
@@ -82,9 +116,13 @@ impl TransportPeerEventHandler for DeMux {
         Ok(())
     }
 
-    fn new_link(&self, _link: Link) {}
+    fn new_link(&self, _link: Link) {
+        self.update_links();
+    }
 
-    fn del_link(&self, _link: Link) {}
+    fn del_link(&self, _link: Link) {
+        self.update_links();
+    }
 
     fn closing(&self) {
         self.face.send_close();
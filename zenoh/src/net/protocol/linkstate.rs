@@ -41,6 +41,19 @@ pub(crate) struct LinkState {
     pub(crate) whatami: Option<WhatAmI>,
     pub(crate) locators: Option<Vec<Locator>>,
     pub(crate) links: Vec<u64>,
+    // The weights of the links, carried by the Weights extension of the list
+    pub(crate) weights: Option<Vec<u16>>,
+    // The limits of the relaying of the locators, carried by the Ttl extension of the list
+    pub(crate) ttl: LinkStateTtl,
+}
+
+// The limits of the relaying of the locators of a node by gossip
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct LinkStateTtl {
+    // The number of times the locators may still be relayed
+    pub(crate) hops: Option<u8>,
+    // The time in milliseconds during which the locators may still be relayed
+    pub(crate) lifetime: Option<u64>,
 }
 
 impl LinkState {
@@ -74,6 +87,15 @@ impl LinkState {
         };
         let n = rng.gen_range(MIN..=MAX);
         let links = (0..n).map(|_| rng.gen()).collect::<Vec<u64>>();
+        let weights = if rng.gen_bool(0.5) {
+            Some((0..n).map(|_| rng.gen()).collect::<Vec<u16>>())
+        } else {
+            None
+        };
+        let ttl = LinkStateTtl {
+            hops: rng.gen_bool(0.5).then(|| rng.gen()),
+            lifetime: rng.gen_bool(0.5).then(|| rng.gen_range(0..u64::MAX)),
+        };
 
         Self {
             psid,
@@ -82,6 +104,8 @@ impl LinkState {
             whatami,
             locators,
             links,
+            weights,
+            ttl,
         }
     }
 }
@@ -92,11 +116,32 @@ impl LinkState {
 // +-+-+-+---------+
 // ~ [link_states] ~
 // +---------------+
+// ~   [list_exts] ~ if there are bytes left
+// +---------------+
+//
+// The extensions follow the link states, so that the nodes not knowing them stop
+// reading before and the ones not knowing some of them skip them by their length.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct LinkStateList {
     pub(crate) link_states: Vec<LinkState>,
 }
 
+pub(crate) mod ext {
+    use zenoh_protocol::{common::ZExtZBuf, zextzbuf};
+
+    /// # Weights extension
+    ///
+    /// For each link state of the list, the number of weights (zero or its number of
+    /// links) followed by the weights.
+    pub(crate) type Weights = zextzbuf!(0x1, false);
+
+    /// # Ttl extension
+    ///
+    /// For each link state of the list, its hop limit and its lifetime, each encoded
+    /// as zero when not limited or as the limit plus one.
+    pub(crate) type Ttl = zextzbuf!(0x2, false);
+}
+
 impl LinkStateList {
     #[cfg(feature = "test")]
     pub fn rand() -> Self {
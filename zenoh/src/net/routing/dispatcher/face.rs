@@ -12,27 +12,48 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use super::super::router::*;
-use super::tables::TablesLock;
+use super::tables::{NodeId, Tables, TablesLock};
 use super::{resource::*, tables};
 use crate::net::primitives::{McastMux, Mux, Primitives};
 use crate::net::routing::interceptor::{InterceptorTrait, InterceptorsChain};
 use crate::KeyExpr;
 use std::any::Any;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::sync::{Arc, Weak};
 use tokio_util::sync::CancellationToken;
+use zenoh_buffers::{buffer::SplitBuffer, ZBuf};
 use zenoh_protocol::zenoh::RequestBody;
 use zenoh_protocol::{
-    core::{ExprId, WhatAmI, ZenohId},
-    network::{Mapping, Push, Request, RequestId, Response, ResponseFinal},
+    common::ZExtBody,
+    core::{ExprId, Locator, WhatAmI, WireExpr, ZenohId},
+    network::{
+        oam::{self, id::OAM_DECLARE_REJECTED},
+        Mapping, NetworkBody, Oam, Push, Request, RequestId, Response, ResponseFinal,
+    },
 };
+use zenoh_result::{bail, ZResult};
 use zenoh_sync::get_mut_unchecked;
 use zenoh_task::TaskController;
 use zenoh_transport::multicast::TransportMulticast;
 #[cfg(feature = "stats")]
 use zenoh_transport::stats::TransportStats;
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum DeclarationKind {
+    Subscriber,
+    Queryable,
+}
+
+/// A subscriber or queryable declared by a face on behalf of a node on a key expression.
+pub(crate) type Declaration = (DeclarationKind, NodeId, String);
+
+/// What a declaration checked against the routing limits declares.
+enum Declared {
+    Resource(ExprId),
+    Entity(DeclarationKind, NodeId),
+}
+
 pub struct FaceState {
     pub(crate) id: usize,
     pub(crate) zid: ZenohId,
@@ -42,9 +63,12 @@ pub struct FaceState {
     pub(crate) primitives: Arc<dyn crate::net::primitives::EPrimitives + Send + Sync>,
     pub(crate) local_mappings: HashMap<ExprId, Arc<Resource>>,
     pub(crate) remote_mappings: HashMap<ExprId, Arc<Resource>>,
+    // The subscribers and queryables declared by this face
+    pub(crate) declarations: HashSet<Declaration>,
     pub(crate) next_qid: RequestId,
     pub(crate) pending_queries: HashMap<RequestId, (Arc<Query>, CancellationToken)>,
     pub(crate) mcast_group: Option<TransportMulticast>,
+    pub(crate) links: Vec<Locator>,
     pub(crate) in_interceptors: Option<Arc<InterceptorsChain>>,
     pub(crate) hat: Box<dyn Any + Send + Sync>,
     pub(crate) task_controller: TaskController,
@@ -59,6 +83,7 @@ impl FaceState {
         #[cfg(feature = "stats")] stats: Option<Arc<TransportStats>>,
         primitives: Arc<dyn crate::net::primitives::EPrimitives + Send + Sync>,
         mcast_group: Option<TransportMulticast>,
+        links: Vec<Locator>,
         in_interceptors: Option<Arc<InterceptorsChain>>,
         hat: Box<dyn Any + Send + Sync>,
     ) -> Arc<FaceState> {
@@ -71,9 +96,11 @@ impl FaceState {
             primitives,
             local_mappings: HashMap::new(),
             remote_mappings: HashMap::new(),
+            declarations: HashSet::new(),
             next_qid: 0,
             pending_queries: HashMap::new(),
             mcast_group,
+            links,
             in_interceptors,
             hat,
             task_controller: TaskController::default(),
@@ -182,27 +209,162 @@ impl Face {
             state: Arc::downgrade(&self.state),
         }
     }
+
+    fn full_expr(tables: &Tables, face: &FaceState, expr: &WireExpr) -> Option<String> {
+        tables
+            .get_mapping(face, &expr.scope, expr.mapping)
+            .map(|prefix| {
+                let mut fullexpr = prefix.expr();
+                fullexpr.push_str(expr.suffix.as_ref());
+                fullexpr
+            })
+    }
+
+    /// Checks a declaration received from this face against the configured routing limits.
+    /// Redeclaring an already declared resource, subscriber or queryable is always allowed.
+    /// The limits only apply to the remote clients: the routers and peers relay the declarations
+    /// of whole networks, and the local sessions are trusted.
+    fn check_limits(&self, expr: &WireExpr, declared: Declared) -> ZResult<()> {
+        let tables = zread!(self.tables.tables);
+        if self.state.whatami != WhatAmI::Client || self.state.zid == tables.zid {
+            return Ok(());
+        }
+        let limits = &tables.limits;
+        // Declarations with unknown scopes are reported by the routing functions
+        let fullexpr = Self::full_expr(&tables, &self.state, expr);
+        if let Some(fullexpr) = &fullexpr {
+            limits.check_key_expr(fullexpr)?;
+        }
+        match declared {
+            Declared::Resource(id) => {
+                if let Some(max) = limits.max_resources {
+                    if !self.state.remote_mappings.contains_key(&id)
+                        && self.state.remote_mappings.len() >= max
+                    {
+                        bail!("Maximum number of declared resources reached ({})", max);
+                    }
+                }
+            }
+            Declared::Entity(kind, node_id) => {
+                if let (Some(max), Some(fullexpr)) = (limits.max_declarations, fullexpr) {
+                    if !self.state.declarations.contains(&(kind, node_id, fullexpr))
+                        && self.state.declarations.len() >= max
+                    {
+                        bail!("Maximum number of declarations reached ({})", max);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether this face was closed, in which case the declarations it still
+    /// receives must not be registered in the routing tables.
+    fn is_closed(&self) -> bool {
+        zread!(self.tables.tables)
+            .tombstones
+            .contains_key(&self.state.id)
+    }
+
+    /// Tracks the subscribers and queryables declared by this face: only actual
+    /// declarations and removals change the count checked against the limits.
+    fn update_declarations(
+        &self,
+        kind: DeclarationKind,
+        node_id: NodeId,
+        expr: &WireExpr,
+        declared: bool,
+    ) {
+        let wtables = zwrite!(self.tables.tables);
+        let Some(fullexpr) = Self::full_expr(&wtables, &self.state, expr) else {
+            return;
+        };
+        let mut state = self.state.clone();
+        let state = get_mut_unchecked(&mut state);
+        if declared {
+            state.declarations.insert((kind, node_id, fullexpr));
+        } else {
+            state.declarations.remove(&(kind, node_id, fullexpr));
+        }
+    }
+
+    /// Notifies the remote session that its declaration was rejected.
+    fn reject_declaration(&self, e: zenoh_result::Error) {
+        tracing::warn!("{} Declaration rejected: {}", self.state, e);
+        if let Some(mux) = self.state.primitives.as_any().downcast_ref::<Mux>() {
+            let _ = mux.handler.schedule(
+                NetworkBody::OAM(Oam {
+                    id: OAM_DECLARE_REJECTED,
+                    body: ZExtBody::ZBuf(e.to_string().into_bytes().into()),
+                    ext_qos: oam::ext::QoSType::oam_default(),
+                    ext_tstamp: None,
+                })
+                .into(),
+            );
+        }
+    }
+}
+
+/// Notifies the local sessions of the rejection of one of their declarations by the remote node of the face.
+#[cfg_attr(not(feature = "unstable"), allow(unused_variables))]
+pub(crate) fn declaration_rejected_received(tables: &TablesLock, face: &FaceState, body: &ZBuf) {
+    let reason = String::from_utf8_lossy(&body.contiguous()).to_string();
+    tracing::error!("Declaration rejected by {}: {}", face.zid, reason);
+    #[cfg(feature = "unstable")]
+    {
+        let faces: Vec<Arc<FaceState>> = zread!(tables.tables).faces.values().cloned().collect();
+        for f in faces {
+            if let Some(session) = f.primitives.as_any().downcast_ref::<crate::Session>() {
+                session.handle_declaration_rejected(crate::info::DeclarationRejectedEvent {
+                    zid: face.zid,
+                    reason: reason.clone(),
+                });
+            }
+        }
+    }
 }
 
 impl Primitives for Face {
     fn send_declare(&self, msg: zenoh_protocol::network::Declare) {
         let ctrl_lock = zlock!(self.tables.ctrl_lock);
+        if self.is_closed() {
+            tracing::debug!("{} Ignore declaration from closed face", self.state);
+            return;
+        }
         match msg.body {
             zenoh_protocol::network::DeclareBody::DeclareKeyExpr(m) => {
-                register_expr(&self.tables, &mut self.state.clone(), m.id, &m.wire_expr);
+                match self.check_limits(&m.wire_expr, Declared::Resource(m.id)) {
+                    Ok(()) => {
+                        register_expr(&self.tables, &mut self.state.clone(), m.id, &m.wire_expr)
+                    }
+                    Err(e) => self.reject_declaration(e),
+                }
             }
             zenoh_protocol::network::DeclareBody::UndeclareKeyExpr(m) => {
                 unregister_expr(&self.tables, &mut self.state.clone(), m.id);
             }
             zenoh_protocol::network::DeclareBody::DeclareSubscriber(m) => {
-                declare_subscription(
-                    ctrl_lock.as_ref(),
-                    &self.tables,
-                    &mut self.state.clone(),
-                    &m.wire_expr,
-                    &m.ext_info,
-                    msg.ext_nodeid.node_id,
-                );
+                let declared =
+                    Declared::Entity(DeclarationKind::Subscriber, msg.ext_nodeid.node_id);
+                match self.check_limits(&m.wire_expr, declared) {
+                    Ok(()) => {
+                        declare_subscription(
+                            ctrl_lock.as_ref(),
+                            &self.tables,
+                            &mut self.state.clone(),
+                            &m.wire_expr,
+                            &m.ext_info,
+                            msg.ext_nodeid.node_id,
+                        );
+                        self.update_declarations(
+                            DeclarationKind::Subscriber,
+                            msg.ext_nodeid.node_id,
+                            &m.wire_expr,
+                            true,
+                        );
+                    }
+                    Err(e) => self.reject_declaration(e),
+                }
             }
             zenoh_protocol::network::DeclareBody::UndeclareSubscriber(m) => {
                 undeclare_subscription(
@@ -212,17 +374,35 @@ impl Primitives for Face {
                     &m.ext_wire_expr.wire_expr,
                     msg.ext_nodeid.node_id,
                 );
-            }
-            zenoh_protocol::network::DeclareBody::DeclareQueryable(m) => {
-                declare_queryable(
-                    ctrl_lock.as_ref(),
-                    &self.tables,
-                    &mut self.state.clone(),
-                    &m.wire_expr,
-                    &m.ext_info,
+                self.update_declarations(
+                    DeclarationKind::Subscriber,
                     msg.ext_nodeid.node_id,
+                    &m.ext_wire_expr.wire_expr,
+                    false,
                 );
             }
+            zenoh_protocol::network::DeclareBody::DeclareQueryable(m) => {
+                let declared = Declared::Entity(DeclarationKind::Queryable, msg.ext_nodeid.node_id);
+                match self.check_limits(&m.wire_expr, declared) {
+                    Ok(()) => {
+                        declare_queryable(
+                            ctrl_lock.as_ref(),
+                            &self.tables,
+                            &mut self.state.clone(),
+                            &m.wire_expr,
+                            &m.ext_info,
+                            msg.ext_nodeid.node_id,
+                        );
+                        self.update_declarations(
+                            DeclarationKind::Queryable,
+                            msg.ext_nodeid.node_id,
+                            &m.wire_expr,
+                            true,
+                        );
+                    }
+                    Err(e) => self.reject_declaration(e),
+                }
+            }
             zenoh_protocol::network::DeclareBody::UndeclareQueryable(m) => {
                 undeclare_queryable(
                     ctrl_lock.as_ref(),
@@ -231,6 +411,12 @@ impl Primitives for Face {
                     &m.ext_wire_expr.wire_expr,
                     msg.ext_nodeid.node_id,
                 );
+                self.update_declarations(
+                    DeclarationKind::Queryable,
+                    msg.ext_nodeid.node_id,
+                    &m.ext_wire_expr.wire_expr,
+                    false,
+                );
             }
             zenoh_protocol::network::DeclareBody::DeclareToken(_m) => todo!(),
             zenoh_protocol::network::DeclareBody::UndeclareToken(_m) => todo!(),
@@ -249,6 +435,7 @@ impl Primitives for Face {
             &msg.wire_expr,
             msg.ext_qos,
             msg.ext_tstamp,
+            msg.ext_hoplimit,
             msg.payload,
             msg.ext_nodeid.node_id,
         );
@@ -11,21 +11,33 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use super::face::FaceState;
+use super::face::{DeclarationKind, FaceState};
 use super::resource::{DataRoutes, Direction, PullCaches, Resource};
-use super::tables::{NodeId, Route, RoutingExpr, Tables, TablesLock};
+use super::tables::{NodeId, RetainedData, Route, RoutingExpr, Tables, TablesLock};
+use crate::net::primitives::Mux;
 use crate::net::routing::hat::HatTrait;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::RwLock;
-use zenoh_core::zread;
+use std::time::Duration;
+use zenoh_buffers::reader::{DidntRead, HasReader};
+use zenoh_buffers::writer::HasWriter;
+use zenoh_buffers::ZBuf;
+use zenoh_codec::{RCodec, WCodec, Zenoh080};
+use zenoh_core::{zread, zwrite};
+use zenoh_protocol::common::ZExtBody;
 use zenoh_protocol::core::key_expr::{keyexpr, OwnedKeyExpr};
 use zenoh_protocol::network::declare::subscriber::ext::SubscriberInfo;
 use zenoh_protocol::network::declare::Mode;
 use zenoh_protocol::{
-    core::{WhatAmI, WireExpr},
-    network::{declare::ext, Push},
+    core::{CongestionControl, Priority, WhatAmI, WireExpr, ZenohId},
+    network::{
+        declare::ext,
+        oam::{self, id::OAM_CONGESTION},
+        push::ext::HopLimitType,
+        NetworkBody, Oam, Push,
+    },
     zenoh::PushBody,
 };
 use zenoh_sync::get_mut_unchecked;
@@ -66,11 +78,23 @@ pub(crate) fn declare_subscription(
                     (res, wtables)
                 };
 
+            // The retained publications are only sent for a new subscription of the face,
+            // not again when it redeclares it
+            let expr = res.expr();
+            let redeclared = face
+                .declarations
+                .iter()
+                .any(|(kind, _, e)| *kind == DeclarationKind::Subscriber && *e == expr);
+
             hat_code.declare_subscription(&mut wtables, face, &mut res, sub_info, node_id);
 
             disable_matches_data_routes(&mut wtables, &mut res);
             drop(wtables);
 
+            if !redeclared {
+                send_retained_data(tables, face, &expr);
+            }
+
             let rtables = zread!(tables.tables);
             let matches_data_routes = compute_matches_data_routes(&rtables, &res);
             drop(rtables);
@@ -256,7 +280,7 @@ pub(crate) fn disable_matches_data_routes(_tables: &mut Tables, res: &mut Arc<Re
 }
 
 macro_rules! treat_timestamp {
-    ($hlc:expr, $payload:expr, $drop:expr) => {
+    ($hlc:expr, $payload:expr, $drop:expr, $bound:expr) => {
         // if an HLC was configured (via Config.add_timestamp),
         // check DataInfo and add a timestamp if there isn't
         if let Some(hlc) = $hlc {
@@ -274,6 +298,7 @@ macro_rules! treat_timestamp {
                                 return;
                             } else {
                                 data.timestamp = Some(hlc.new_timestamp());
+                                data.ext_tbound = $bound;
                                 tracing::error!(
                                     "Error treating timestamp for received Data ({}). Replace timestamp: {:?}",
                                     e,
@@ -284,6 +309,7 @@ macro_rules! treat_timestamp {
                 } else {
                     // Timestamp not present; add one
                     data.timestamp = Some(hlc.new_timestamp());
+                    data.ext_tbound = $bound;
                     tracing::trace!("Adding timestamp to DataInfo: {:?}", data.timestamp);
                 }
             }
@@ -291,6 +317,138 @@ macro_rules! treat_timestamp {
     }
 }
 
+// Retain the last publication on the key expressions configured in `routing.router.retained`
+fn retain_data(
+    tables: &Tables,
+    expr: &mut RoutingExpr,
+    ext_qos: ext::QoSType,
+    ext_tstamp: Option<ext::TimestampType>,
+    payload: &PushBody,
+) {
+    if tables.retained.is_empty() {
+        return;
+    }
+    let Ok(ke) = keyexpr::new(expr.full_expr()) else {
+        return;
+    };
+    if !tables.retained.iter().any(|retained| retained.includes(ke)) {
+        return;
+    }
+    let mut retained_data = zlock!(tables.retained_data);
+    match payload {
+        PushBody::Put(_) => {
+            if !retained_data.contains_key(ke) && retained_data.len() >= tables.retained_max_keys {
+                tracing::warn!(
+                    "Publication on {} not retained: maximum number of retained key expressions reached ({})",
+                    ke,
+                    tables.retained_max_keys
+                );
+                return;
+            }
+            retained_data.insert(
+                ke.into(),
+                RetainedData {
+                    ext_qos,
+                    ext_tstamp,
+                    payload: payload.clone(),
+                },
+            );
+        }
+        PushBody::Del(_) => {
+            retained_data.remove(ke);
+        }
+    }
+}
+
+// Send the retained publications intersecting a new subscription to the subscribing client or peer.
+// The routers don't need them, they route the publications to their own subscribers.
+fn send_retained_data(tables: &TablesLock, face: &Arc<FaceState>, expr: &str) {
+    if face.whatami == WhatAmI::Router {
+        return;
+    }
+    let Ok(sub_ke) = keyexpr::new(expr) else {
+        return;
+    };
+    let rtables = zread!(tables.tables);
+    if rtables.retained.is_empty() {
+        return;
+    }
+    let pushes = zlock!(rtables.retained_data)
+        .iter()
+        .filter(|(ke, _)| sub_ke.intersects(ke))
+        .map(|(ke, data)| Push {
+            wire_expr: ke.to_string().into(),
+            ext_qos: data.ext_qos,
+            ext_tstamp: data.ext_tstamp,
+            ext_nodeid: ext::NodeIdType::default(),
+            ext_hoplimit: None,
+            payload: data.payload.clone(),
+        })
+        .collect::<Vec<Push>>();
+    drop(rtables);
+    for push in pushes {
+        tracing::trace!("Send retained data {} to {}", push.wire_expr, face);
+        face.primitives.send_push(push);
+    }
+}
+
+/// Returns the route imposed by the static routes configuration for `expr`, if any.
+///
+/// Static routes take precedence over the routes learned through declarations towards the
+/// other routers and peers: if no face is connected to the configured next hop, the data is
+/// not forwarded to them. The sessions (i.e. clients) with matching subscribers are still
+/// served through `learned`.
+fn get_static_data_route(
+    tables: &Tables,
+    face: &FaceState,
+    expr: &mut RoutingExpr,
+    local_context: NodeId,
+    learned: &Route,
+) -> Option<Arc<Route>> {
+    if tables.static_routes.is_empty() {
+        return None;
+    }
+    let (prefix, suffix) = (expr.prefix, expr.suffix);
+    let ke = keyexpr::new(expr.full_expr()).ok()?;
+    let mut static_routes = tables
+        .static_routes
+        .iter()
+        .filter(|route| route.key.includes(ke))
+        .peekable();
+    static_routes.peek()?;
+
+    let mut route: Route = learned
+        .iter()
+        .filter(|(_, (outface, _, _))| outface.whatami == WhatAmI::Client)
+        .map(|(id, hop)| (*id, hop.clone()))
+        .collect();
+    let sessions = route.len();
+    for static_route in static_routes {
+        for outface in static_route
+            .faces
+            .iter()
+            .filter(|outface| outface.id != face.id)
+        {
+            route.entry(outface.id).or_insert_with(|| {
+                (
+                    outface.clone(),
+                    Resource::get_best_key(prefix, suffix, outface.id).to_owned(),
+                    tables
+                        .hat_code
+                        .egress_routing_context(tables, face, local_context, outface),
+                )
+            });
+        }
+    }
+    if route.len() == sessions {
+        tracing::trace!(
+            "No face connected to the static next hop(s) for {}: drop data",
+            ke
+        );
+    }
+    Some(Arc::new(route))
+}
+
 #[inline]
 fn get_data_route(
     tables: &Tables,
@@ -302,13 +460,15 @@ fn get_data_route(
     let local_context = tables
         .hat_code
         .map_routing_context(tables, face, routing_context);
-    res.as_ref()
+    let route = res
+        .as_ref()
         .and_then(|res| res.data_route(face.whatami, local_context))
         .unwrap_or_else(|| {
             tables
                 .hat_code
                 .compute_data_route(tables, expr, local_context, face.whatami)
-        })
+        });
+    get_static_data_route(tables, face, expr, local_context, &route).unwrap_or(route)
 }
 
 #[zenoh_macros::unstable]
@@ -424,12 +584,37 @@ macro_rules! inc_stats {
     };
 }
 
+// The hop limit of a message forwarded to `outface`, or `Err` if its hop limit is exhausted.
+// Forwarding to the local sessions doesn't consume any hop.
+#[inline]
+fn forward_hop_limit(
+    zid: &ZenohId,
+    outface: &FaceState,
+    ext_hoplimit: Option<HopLimitType>,
+) -> Result<Option<HopLimitType>, ()> {
+    match ext_hoplimit {
+        Some(_) if outface.zid == *zid => Ok(ext_hoplimit),
+        Some(HopLimitType { hops: 0 }) => {
+            tracing::trace!("Drop data with exhausted hop limit for {}", outface);
+            #[cfg(feature = "stats")]
+            if let Some(stats) = outface.stats.as_ref() {
+                stats.inc_tx_n_expired(1);
+            }
+            Err(())
+        }
+        Some(HopLimitType { hops }) => Ok(Some(HopLimitType::new(hops - 1))),
+        None => Ok(None),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn full_reentrant_route_data(
     tables_ref: &Arc<TablesLock>,
     face: &FaceState,
     expr: &WireExpr,
     ext_qos: ext::QoSType,
     ext_tstamp: Option<ext::TimestampType>,
+    ext_hoplimit: Option<HopLimitType>,
     mut payload: PushBody,
     routing_context: NodeId,
 ) {
@@ -453,6 +638,8 @@ pub fn full_reentrant_route_data(
             }
 
             if tables.hat_code.ingress_filter(&tables, face, &mut expr) {
+                retain_data(&tables, &mut expr, ext_qos, ext_tstamp, &payload);
+
                 let res = Resource::get_resource(&prefix, expr.suffix);
 
                 let route = get_data_route(&tables, face, &res, &mut expr, routing_context);
@@ -460,7 +647,12 @@ pub fn full_reentrant_route_data(
                 let matching_pulls = get_matching_pulls(&tables, &res, &mut expr);
 
                 if !(route.is_empty() && matching_pulls.is_empty()) {
-                    treat_timestamp!(&tables.hlc, payload, tables.drop_future_timestamp);
+                    treat_timestamp!(
+                        &tables.hlc,
+                        payload,
+                        tables.drop_future_timestamp,
+                        tables.timestamp_bound
+                    );
 
                     if route.len() == 1 && matching_pulls.len() == 0 {
                         let (outface, key_expr, context) = route.values().next().unwrap();
@@ -468,7 +660,12 @@ pub fn full_reentrant_route_data(
                             .hat_code
                             .egress_filter(&tables, face, outface, &mut expr)
                         {
+                            let zid = tables.zid;
                             drop(tables);
+                            let Ok(ext_hoplimit) = forward_hop_limit(&zid, outface, ext_hoplimit)
+                            else {
+                                return;
+                            };
                             #[cfg(feature = "stats")]
                             if !admin {
                                 inc_stats!(face, tx, user, payload)
@@ -481,6 +678,7 @@ pub fn full_reentrant_route_data(
                                 ext_qos,
                                 ext_tstamp,
                                 ext_nodeid: ext::NodeIdType { node_id: *context },
+                                ext_hoplimit,
                                 payload,
                             })
                         }
@@ -502,8 +700,14 @@ pub fn full_reentrant_route_data(
                                 .cloned()
                                 .collect::<Vec<Direction>>();
 
+                            let zid = tables.zid;
                             drop(tables);
                             for (outface, key_expr, context) in route {
+                                let Ok(ext_hoplimit) =
+                                    forward_hop_limit(&zid, &outface, ext_hoplimit)
+                                else {
+                                    continue;
+                                };
                                 #[cfg(feature = "stats")]
                                 if !admin {
                                     inc_stats!(face, tx, user, payload)
@@ -516,10 +720,12 @@ pub fn full_reentrant_route_data(
                                     ext_qos,
                                     ext_tstamp,
                                     ext_nodeid: ext::NodeIdType { node_id: context },
+                                    ext_hoplimit,
                                     payload: payload.clone(),
                                 })
                             }
                         } else {
+                            let zid = tables.zid;
                             drop(tables);
                             for (outface, key_expr, context) in route.values() {
                                 if face.id != outface.id
@@ -531,6 +737,11 @@ pub fn full_reentrant_route_data(
                                         _ => true,
                                     }
                                 {
+                                    let Ok(ext_hoplimit) =
+                                        forward_hop_limit(&zid, outface, ext_hoplimit)
+                                    else {
+                                        continue;
+                                    };
                                     #[cfg(feature = "stats")]
                                     if !admin {
                                         inc_stats!(face, tx, user, payload)
@@ -543,6 +754,7 @@ pub fn full_reentrant_route_data(
                                         ext_qos,
                                         ext_tstamp,
                                         ext_nodeid: ext::NodeIdType { node_id: *context },
+                                        ext_hoplimit,
                                         payload: payload.clone(),
                                     })
                                 }
@@ -588,6 +800,7 @@ pub fn pull_data(tables_ref: &RwLock<Tables>, face: &Arc<FaceState>, expr: WireE
                                     ext_qos: ext::QoSType::push_default(),
                                     ext_tstamp: None,
                                     ext_nodeid: ext::NodeIdType::default(),
+                                    ext_hoplimit: None,
                                     payload,
                                 });
                             }
@@ -619,3 +832,134 @@ pub fn pull_data(tables_ref: &RwLock<Tables>, face: &Arc<FaceState>, expr: WireE
         }
     };
 }
+
+// The period at which a congestion is notified again while it lasts: the notified nodes expire
+// the congestions that are not notified again, e.g. when the recovery notification is lost.
+pub(crate) const CONGESTION_REFRESH: Duration = Duration::from_secs(1);
+
+/// The body of an OAM message notifying that the node `zid` is congested, or recovered.
+pub(crate) fn congestion_body(zid: &ZenohId, congested: bool) -> Option<ZBuf> {
+    let codec = Zenoh080::new();
+    let mut body = ZBuf::empty();
+    let mut writer = body.writer();
+    codec
+        .write(&mut writer, zid)
+        .and_then(|_| codec.write(&mut writer, congested as u8))
+        .ok()?;
+    Some(body)
+}
+
+/// Reads the node and its congestion from the body of an OAM congestion message.
+pub(crate) fn read_congestion_body(body: &ZBuf) -> Result<(ZenohId, bool), DidntRead> {
+    let codec = Zenoh080::new();
+    let mut reader = body.reader();
+    let zid: ZenohId = codec.read(&mut reader)?;
+    let congested: u8 = codec.read(&mut reader)?;
+    Ok((zid, congested != 0))
+}
+
+/// The transmission queues of the node `zid` exceed their memory budget, or fall back below it:
+/// notified directly if the face is a local session, or else with an OAM message handled by
+/// the remote node.
+#[cfg_attr(not(feature = "unstable"), allow(unused_variables))]
+fn notify_face_congestion(face: &FaceState, congested: bool, zid: &ZenohId) {
+    #[cfg(feature = "unstable")]
+    if let Some(session) = face.primitives.as_any().downcast_ref::<crate::Session>() {
+        session.handle_congestion(crate::publication::CongestionEvent {
+            congested,
+            zid: *zid,
+        });
+        return;
+    }
+    if let Some(mux) = face.primitives.as_any().downcast_ref::<Mux>() {
+        let Some(body) = congestion_body(zid, congested) else {
+            return;
+        };
+        // The droppable messages are shed while the budget is exceeded, the notification is not
+        let _ = mux.handler.schedule(
+            NetworkBody::OAM(Oam {
+                id: OAM_CONGESTION,
+                body: ZExtBody::ZBuf(body),
+                ext_qos: oam::ext::QoSType::new(Priority::Control, CongestionControl::Block, true),
+                ext_tstamp: None,
+            })
+            .into(),
+        );
+    }
+}
+
+/// Notifies the sessions and the neighbours of this node that its transmission queues exceed
+/// their memory budget, or fall back below it.
+pub(crate) fn notify_congestion(tables: &TablesLock, congested: bool) {
+    let rtables = zread!(tables.tables);
+    let zid = rtables.zid;
+    let faces: Vec<Arc<FaceState>> = rtables.faces.values().cloned().collect();
+    drop(rtables);
+    for face in faces {
+        notify_face_congestion(&face, congested, &zid);
+    }
+}
+
+// Forwards the congestion of the node `zid`, notified through the face `face_id`, to the sessions
+// and clients of this node: their publications may go through the congested node. The congestions
+// are not forwarded to the peers and routers, which are notified by their own neighbours.
+fn forward_congestion(tables: &TablesLock, face_id: usize, zid: &ZenohId, congested: bool) {
+    let faces: Vec<Arc<FaceState>> = zread!(tables.tables)
+        .faces
+        .values()
+        .filter(|f| f.id != face_id && f.whatami == WhatAmI::Client)
+        .cloned()
+        .collect();
+    for f in faces {
+        notify_face_congestion(&f, congested, zid);
+    }
+}
+
+/// Handles a congestion notified by the remote node of the face, forwarding it to the local sessions
+/// and clients.
+pub(crate) fn congestion_received(tables: &TablesLock, face: &FaceState, body: &ZBuf) {
+    let Ok((zid, congested)) = read_congestion_body(body) else {
+        tracing::debug!("{} Received invalid congestion", face);
+        return;
+    };
+    tracing::debug!(
+        "{} {} {}",
+        face,
+        zid,
+        if congested {
+            "congested"
+        } else {
+            "recovered from congestion"
+        }
+    );
+    {
+        let mut wtables = zwrite!(tables.tables);
+        if congested {
+            wtables.congestions.insert(zid, face.id);
+        } else {
+            wtables.congestions.remove(&zid);
+        }
+    }
+    forward_congestion(tables, face.id, &zid, congested);
+}
+
+/// Notifies the recovery of the congestions notified through the closed face, as it will not
+/// notify their recovery itself.
+pub(crate) fn close_face_congestions(tables: &TablesLock, face_id: usize) {
+    let zids: Vec<ZenohId> = {
+        let mut wtables = zwrite!(tables.tables);
+        let zids = wtables
+            .congestions
+            .iter()
+            .filter(|(_, id)| **id == face_id)
+            .map(|(zid, _)| *zid)
+            .collect::<Vec<_>>();
+        for zid in zids.iter() {
+            wtables.congestions.remove(zid);
+        }
+        zids
+    };
+    for zid in zids {
+        forward_congestion(tables, face_id, &zid, false);
+    }
+}
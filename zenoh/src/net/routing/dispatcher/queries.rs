@@ -15,6 +15,7 @@ use super::face::FaceState;
 use super::resource::{QueryRoute, QueryRoutes, QueryTargetQablSet, Resource};
 use super::tables::NodeId;
 use super::tables::{RoutingExpr, Tables, TablesLock};
+use crate::net::primitives::Mux;
 use crate::net::routing::hat::HatTrait;
 use crate::net::routing::RoutingContext;
 use async_trait::async_trait;
@@ -22,11 +23,20 @@ use std::collections::HashMap;
 use std::sync::{Arc, Weak};
 use std::time::Duration;
 use tokio_util::sync::CancellationToken;
+use zenoh_buffers::reader::{DidntRead, HasReader};
+use zenoh_buffers::writer::HasWriter;
 use zenoh_buffers::ZBuf;
+use zenoh_codec::{RCodec, WCodec, Zenoh080};
 use zenoh_config::WhatAmI;
+use zenoh_protocol::common::ZExtBody;
 use zenoh_protocol::core::key_expr::keyexpr;
 use zenoh_protocol::core::KnownEncoding;
+use zenoh_protocol::core::ZenohId;
 use zenoh_protocol::network::declare::queryable::ext::QueryableInfo;
+use zenoh_protocol::network::{
+    oam::{self, id::OAM_QUERYABLE_CONFLICT},
+    NetworkBody, Oam,
+};
 use zenoh_protocol::zenoh;
 use zenoh_protocol::zenoh::ext::ValueType;
 use zenoh_protocol::{
@@ -44,6 +54,267 @@ use zenoh_protocol::{
 use zenoh_sync::get_mut_unchecked;
 use zenoh_util::Timed;
 
+// The maximum number of queryable conflicts recorded by the tables
+const MAX_QUERYABLE_CONFLICTS: usize = 1_024;
+
+/// Two complete queryables declared by different faces, or by a face and a remote node, on
+/// intersecting key expressions, for which the routing of the queries targeting a single
+/// complete queryable is unpredictable.
+#[derive(Debug, Clone)]
+pub(crate) struct QueryableConflict {
+    pub(crate) key_expr: String,
+    pub(crate) face: usize,
+    pub(crate) zid: ZenohId,
+    pub(crate) conflicting_key_expr: String,
+    // The face of the conflicting queryable, if declared by a face of this node
+    pub(crate) conflicting_face: Option<usize>,
+    pub(crate) conflicting_zid: ZenohId,
+}
+
+impl QueryableConflict {
+    pub(crate) fn involves_face(&self, face: usize) -> bool {
+        self.face == face || self.conflicting_face == Some(face)
+    }
+
+    fn involves(&self, face: usize, key_expr: &str) -> bool {
+        (self.face == face && self.key_expr == key_expr)
+            || (self.conflicting_face == Some(face) && self.conflicting_key_expr == key_expr)
+    }
+
+    // Whether the queryable of the face on `key_expr` is already known to conflict
+    // with a queryable on `conflicting_key_expr`, whichever node declared it
+    fn records(&self, face: usize, key_expr: &str, conflicting_key_expr: &str) -> bool {
+        (self.face == face
+            && self.key_expr == key_expr
+            && self.conflicting_key_expr == conflicting_key_expr)
+            || (self.conflicting_face == Some(face)
+                && self.conflicting_key_expr == key_expr
+                && self.key_expr == conflicting_key_expr)
+    }
+
+    pub(crate) fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "key_expr": self.key_expr,
+            "zid": self.zid.to_string(),
+            "conflicting_key_expr": self.conflicting_key_expr,
+            "conflicting_zid": self.conflicting_zid.to_string(),
+        })
+    }
+}
+
+fn record_queryable_conflict(tables: &mut Tables, conflict: QueryableConflict) {
+    if tables.queryable_conflicts.len() >= MAX_QUERYABLE_CONFLICTS {
+        tables.queryable_conflicts.remove(0);
+    }
+    tables.queryable_conflicts.push(conflict);
+}
+
+// Records the conflicts of a complete queryable declared by the face with the complete
+// queryables of the other faces and, for the faces of clients, with the ones of the
+// remote routers and peers, and returns the notifications to send to the faces.
+fn detect_queryable_conflicts(
+    hat_code: &(dyn HatTrait + Send + Sync),
+    tables: &mut Tables,
+    face: &Arc<FaceState>,
+    res: &Arc<Resource>,
+) -> Vec<(Arc<FaceState>, String, String, ZenohId)> {
+    let key_expr = res.expr();
+    let mut notifications = vec![];
+    for m in res.context().matches.iter().filter_map(Weak::upgrade) {
+        // The queryables of the routers and peers networks are only known by their node,
+        // the node forwarding them notifies its own faces on reception of the declaration
+        if face.whatami == WhatAmI::Client {
+            let conflicting_key_expr = m.expr();
+            for zid in hat_code.get_remote_complete_queryables(tables, &m) {
+                if tables
+                    .queryable_conflicts
+                    .iter()
+                    .any(|c| c.records(face.id, &key_expr, &conflicting_key_expr))
+                {
+                    continue;
+                }
+                tracing::warn!(
+                    "Complete queryable {} of {} overlaps complete queryable {} of {}",
+                    key_expr,
+                    face,
+                    conflicting_key_expr,
+                    zid
+                );
+                notifications.push((
+                    face.clone(),
+                    key_expr.clone(),
+                    conflicting_key_expr.clone(),
+                    zid,
+                ));
+                record_queryable_conflict(
+                    tables,
+                    QueryableConflict {
+                        key_expr: key_expr.clone(),
+                        face: face.id,
+                        zid: face.zid,
+                        conflicting_key_expr: conflicting_key_expr.clone(),
+                        conflicting_face: None,
+                        conflicting_zid: zid,
+                    },
+                );
+            }
+        }
+        for ctx in m.session_ctxs.values() {
+            if ctx.face.id == face.id || !ctx.qabl.as_ref().map_or(false, |q| q.complete != 0) {
+                continue;
+            }
+            let conflict = QueryableConflict {
+                key_expr: key_expr.clone(),
+                face: face.id,
+                zid: face.zid,
+                conflicting_key_expr: m.expr(),
+                conflicting_face: Some(ctx.face.id),
+                conflicting_zid: ctx.face.zid,
+            };
+            // Already recorded, e.g. when the queryable is redeclared
+            if tables.queryable_conflicts.iter().any(|c| {
+                c.involves(face.id, &conflict.key_expr)
+                    && c.involves(ctx.face.id, &conflict.conflicting_key_expr)
+            }) {
+                continue;
+            }
+            tracing::warn!(
+                "Complete queryable {} of {} overlaps complete queryable {} of {}",
+                conflict.key_expr,
+                face,
+                conflict.conflicting_key_expr,
+                ctx.face
+            );
+            notifications.push((
+                face.clone(),
+                conflict.key_expr.clone(),
+                conflict.conflicting_key_expr.clone(),
+                ctx.face.zid,
+            ));
+            notifications.push((
+                ctx.face.clone(),
+                conflict.conflicting_key_expr.clone(),
+                conflict.key_expr.clone(),
+                face.zid,
+            ));
+            record_queryable_conflict(tables, conflict);
+        }
+    }
+    notifications
+}
+
+/// Notifies the face that its complete queryable on `key_expr` overlaps the complete queryable
+/// on `conflicting_key_expr` declared by, or through, the node `zid`: directly if the face
+/// is a local session, or else with an OAM message handled by the remote node.
+fn notify_queryable_conflict(
+    face: &FaceState,
+    key_expr: &str,
+    conflicting_key_expr: &str,
+    zid: &ZenohId,
+) {
+    #[cfg(feature = "unstable")]
+    if let Some(session) = face.primitives.as_any().downcast_ref::<crate::Session>() {
+        if let (Ok(key_expr), Ok(conflicting_key_expr)) = (
+            crate::key_expr::KeyExpr::try_from(key_expr.to_string()),
+            crate::key_expr::KeyExpr::try_from(conflicting_key_expr.to_string()),
+        ) {
+            session.handle_queryable_conflict(crate::queryable::QueryableConflictEvent {
+                key_expr,
+                conflicting_key_expr,
+                zid: *zid,
+            });
+        }
+        return;
+    }
+    if let Some(mux) = face.primitives.as_any().downcast_ref::<Mux>() {
+        let codec = Zenoh080::new();
+        let mut body = ZBuf::empty();
+        let mut writer = body.writer();
+        let written = codec
+            .write(&mut writer, key_expr)
+            .and_then(|_| codec.write(&mut writer, conflicting_key_expr))
+            .and_then(|_| codec.write(&mut writer, zid));
+        if written.is_ok() {
+            let _ = mux.handler.schedule(
+                NetworkBody::OAM(Oam {
+                    id: OAM_QUERYABLE_CONFLICT,
+                    body: ZExtBody::ZBuf(body),
+                    ext_qos: oam::ext::QoSType::oam_default(),
+                    ext_tstamp: None,
+                })
+                .into(),
+            );
+        }
+    }
+}
+
+/// Handles a queryable conflict notified by the remote node of the face, forwarding it to
+/// the sessions and clients with a complete queryable intersecting the notified key expression
+/// that are not already notified of it.
+pub(crate) fn queryable_conflict_received(tables: &TablesLock, face: &FaceState, body: &ZBuf) {
+    fn read(body: &ZBuf) -> Result<(String, String, ZenohId), DidntRead> {
+        let codec = Zenoh080::new();
+        let mut reader = body.reader();
+        Ok((
+            codec.read(&mut reader)?,
+            codec.read(&mut reader)?,
+            codec.read(&mut reader)?,
+        ))
+    }
+
+    let Ok((key_expr, conflicting_key_expr, zid)) = read(body) else {
+        tracing::debug!("{} Received invalid queryable conflict", face);
+        return;
+    };
+    tracing::warn!(
+        "{} Complete queryable {} overlaps complete queryable {} of {}",
+        face,
+        key_expr,
+        conflicting_key_expr,
+        zid
+    );
+    let Ok(ke) = keyexpr::new(key_expr.as_str()) else {
+        return;
+    };
+    let mut wtables = zwrite!(tables.tables);
+    let mut faces = vec![];
+    for m in Resource::get_matches(&wtables, ke)
+        .iter()
+        .filter_map(Weak::upgrade)
+    {
+        let expr = m.expr();
+        for ctx in m.session_ctxs.values() {
+            if ctx.face.id != face.id
+                && ctx.face.whatami == WhatAmI::Client
+                && ctx.qabl.as_ref().map_or(false, |q| q.complete != 0)
+                && !wtables
+                    .queryable_conflicts
+                    .iter()
+                    .any(|c| c.records(ctx.face.id, &expr, &conflicting_key_expr))
+            {
+                faces.push((ctx.face.clone(), expr.clone()));
+            }
+        }
+    }
+    for (f, expr) in faces.iter() {
+        record_queryable_conflict(
+            &mut wtables,
+            QueryableConflict {
+                key_expr: expr.clone(),
+                face: f.id,
+                zid: f.zid,
+                conflicting_key_expr: conflicting_key_expr.clone(),
+                conflicting_face: None,
+                conflicting_zid: zid,
+            },
+        );
+    }
+    drop(wtables);
+    for (face, key_expr) in faces {
+        notify_queryable_conflict(&face, &key_expr, &conflicting_key_expr, &zid);
+    }
+}
+
 pub(crate) struct Query {
     src_face: Arc<FaceState>,
     src_qid: RequestId,
@@ -87,9 +358,25 @@ pub(crate) fn declare_queryable(
 
             hat_code.declare_queryable(&mut wtables, face, &mut res, qabl_info, node_id);
 
+            let conflicts = match qabl_info.complete != 0 {
+                true => detect_queryable_conflicts(hat_code, &mut wtables, face, &res),
+                false => {
+                    // An incomplete queryable no longer conflicts
+                    let expr = res.expr();
+                    wtables
+                        .queryable_conflicts
+                        .retain(|c| !c.involves(face.id, &expr));
+                    vec![]
+                }
+            };
+
             disable_matches_query_routes(&mut wtables, &mut res);
             drop(wtables);
 
+            for (face, key_expr, conflicting_key_expr, zid) in conflicts {
+                notify_queryable_conflict(&face, &key_expr, &conflicting_key_expr, &zid);
+            }
+
             let rtables = zread!(tables.tables);
             let matches_query_routes = compute_matches_query_routes(&rtables, &res);
             drop(rtables);
@@ -121,6 +408,10 @@ pub(crate) fn undeclare_queryable(
                 let mut wtables = zwrite!(tables.tables);
 
                 hat_code.undeclare_queryable(&mut wtables, face, &mut res, node_id);
+                let expr = res.expr();
+                wtables
+                    .queryable_conflicts
+                    .retain(|c| !c.involves(face.id, &expr));
 
                 disable_matches_query_routes(&mut wtables, &mut res);
                 drop(wtables);
@@ -342,10 +633,23 @@ fn compute_final_route(
             route
         }
         TargetType::BestMatching => {
-            if let Some(qabl) = qabls
-                .iter()
-                .find(|qabl| qabl.direction.0.id != src_face.id && qabl.complete > 0)
-            {
+            // A complete queryable including the queried key expression answers for all the
+            // others, the query is only routed to the closest one
+            let complete_qabl = match tables.complete_short_circuit {
+                true => qabls.iter().find(|qabl| {
+                    qabl.direction.0.id != src_face.id
+                        && qabl.complete > 0
+                        && tables
+                            .hat_code
+                            .egress_filter(tables, src_face, &qabl.direction.0, expr)
+                }),
+                false => None,
+            };
+            if let Some(qabl) = complete_qabl {
+                tracing::trace!(
+                    "Route query only to complete queryable of {}",
+                    qabl.direction.0
+                );
                 let mut route = HashMap::new();
                 #[cfg(feature = "complete_n")]
                 {
@@ -614,6 +918,7 @@ pub fn route_query(
                         #[cfg(feature = "shared-memory")]
                         ext_shm: None,
                         ext_attachment: None, // @TODO: expose it in the API
+                        ext_tbound: None,
                         ext_unknown: vec![],
                         payload,
                     });
@@ -23,15 +23,26 @@ use std::any::Any;
 use std::collections::HashMap;
 use std::sync::{Arc, Weak};
 use std::sync::{Mutex, RwLock};
-use std::time::Duration;
-use uhlc::HLC;
+use std::time::{Duration, Instant};
+use uhlc::{HLC, NTP64};
 use zenoh_config::unwrap_or_default;
 use zenoh_config::Config;
-use zenoh_protocol::core::{ExprId, WhatAmI, ZenohId};
-use zenoh_protocol::network::Mapping;
-use zenoh_result::ZResult;
+use zenoh_config::StaticRouteConf;
+use zenoh_protocol::core::key_expr::OwnedKeyExpr;
+use zenoh_protocol::core::{ExprId, Locator, WhatAmI, ZenohId};
+use zenoh_protocol::network::{push::ext, Mapping};
+use zenoh_protocol::zenoh::{put, PushBody};
+use zenoh_result::{bail, ZResult};
 use zenoh_sync::get_mut_unchecked;
 
+/// The last publication on a key expression retained by a router,
+/// see the `routing.router.retained` configuration.
+pub(crate) struct RetainedData {
+    pub(crate) ext_qos: ext::QoSType,
+    pub(crate) ext_tstamp: Option<ext::TimestampType>,
+    pub(crate) payload: PushBody,
+}
+
 pub(crate) struct RoutingExpr<'a> {
     pub(crate) prefix: &'a Arc<Resource>,
     pub(crate) suffix: &'a str,
@@ -57,6 +68,76 @@ impl<'a> RoutingExpr<'a> {
     }
 }
 
+/// The limits enforced on the declarations received from the faces.
+#[derive(Default)]
+pub(crate) struct RoutingLimits {
+    pub(crate) max_key_expr_length: Option<usize>,
+    pub(crate) max_key_expr_depth: Option<usize>,
+    pub(crate) max_declarations: Option<usize>,
+    pub(crate) max_resources: Option<usize>,
+}
+
+impl RoutingLimits {
+    fn new(config: &Config) -> Self {
+        let limits = config.routing().limits();
+        RoutingLimits {
+            max_key_expr_length: *limits.max_key_expr_length(),
+            max_key_expr_depth: *limits.max_key_expr_depth(),
+            max_declarations: *limits.max_declarations(),
+            max_resources: *limits.max_resources(),
+        }
+    }
+
+    /// Checks that the given key expression does not exceed the length and depth limits.
+    pub(crate) fn check_key_expr(&self, expr: &str) -> ZResult<()> {
+        if let Some(max) = self.max_key_expr_length {
+            if expr.len() > max {
+                bail!(
+                    "Key expression {} exceeds the maximum length ({} > {})",
+                    expr,
+                    expr.len(),
+                    max
+                );
+            }
+        }
+        if let Some(max) = self.max_key_expr_depth {
+            let depth = expr.split('/').count();
+            if depth > max {
+                bail!(
+                    "Key expression {} exceeds the maximum depth ({} > {})",
+                    expr,
+                    depth,
+                    max
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A static route of the configuration, with the faces currently connected to its next hop.
+pub(crate) struct StaticRoute {
+    pub(crate) key: OwnedKeyExpr,
+    pub(crate) next_hop: Locator,
+    pub(crate) faces: Vec<Arc<FaceState>>,
+}
+
+impl StaticRoute {
+    pub(crate) fn new(conf: StaticRouteConf) -> Self {
+        StaticRoute {
+            key: conf.key,
+            next_hop: conf.face,
+            faces: vec![],
+        }
+    }
+
+    fn is_next_hop(&self, face: &FaceState) -> bool {
+        face.links.iter().any(|link| {
+            link.protocol() == self.next_hop.protocol() && link.address() == self.next_hop.address()
+        })
+    }
+}
+
 pub struct Tables {
     pub(crate) zid: ZenohId,
     pub(crate) whatami: WhatAmI,
@@ -64,12 +145,32 @@ pub struct Tables {
     #[allow(dead_code)]
     pub(crate) hlc: Option<Arc<HLC>>,
     pub(crate) drop_future_timestamp: bool,
+    /// The uncertainty bound advertised with the timestamps added by the router.
+    pub(crate) timestamp_bound: Option<put::ext::TimestampBoundType>,
     pub(crate) queries_default_timeout: Duration,
+    /// Whether the queries targeting the best matching queryables are only routed
+    /// to a complete queryable when there is one.
+    pub(crate) complete_short_circuit: bool,
+    pub(crate) limits: RoutingLimits,
+    /// The recently closed faces, whose declarations received late are ignored,
+    /// and the instant they were closed.
+    pub(crate) tombstones: HashMap<usize, Instant>,
+    pub(crate) tombstone_ttl: Duration,
+    /// The number of stale declarations purged by the consistency sweeps.
+    pub(crate) purged_declarations: usize,
+    /// The complete queryables of different faces overlapping each other.
+    pub(crate) queryable_conflicts: Vec<QueryableConflict>,
+    /// The congested nodes notified by the neighbours, with the face they were notified through.
+    pub(crate) congestions: HashMap<ZenohId, usize>,
     pub(crate) root_res: Arc<Resource>,
     pub(crate) faces: HashMap<usize, Arc<FaceState>>,
     pub(crate) mcast_groups: Vec<Arc<FaceState>>,
     pub(crate) mcast_faces: Vec<Arc<FaceState>>,
     pub(crate) interceptors: Vec<InterceptorFactory>,
+    pub(crate) static_routes: Vec<StaticRoute>,
+    pub(crate) retained: Vec<OwnedKeyExpr>,
+    pub(crate) retained_data: Mutex<HashMap<OwnedKeyExpr, RetainedData>>,
+    pub(crate) retained_max_keys: usize,
     pub(crate) pull_caches_lock: Mutex<()>,
     pub(crate) hat: Box<dyn Any + Send + Sync>,
     pub(crate) hat_code: Arc<dyn HatTrait + Send + Sync>, // @TODO make this a Box
@@ -84,10 +185,34 @@ impl Tables {
     ) -> ZResult<Self> {
         let drop_future_timestamp =
             unwrap_or_default!(config.timestamping().drop_future_timestamp());
+        let timestamp_bound = config
+            .timestamping()
+            .uncertainty()
+            .map(|ms| put::ext::TimestampBoundType::new(NTP64::from(Duration::from_millis(ms))));
         let router_peers_failover_brokering =
             unwrap_or_default!(config.routing().router().peers_failover_brokering());
         let queries_default_timeout =
             Duration::from_millis(unwrap_or_default!(config.queries_default_timeout()));
+        let complete_short_circuit =
+            unwrap_or_default!(config.routing().queries().complete_short_circuit());
+        let (static_routes, retained) = if whatami == WhatAmI::Router {
+            (
+                config
+                    .routing()
+                    .router()
+                    .static_routes()
+                    .iter()
+                    .cloned()
+                    .map(StaticRoute::new)
+                    .collect(),
+                config.routing().router().retained().clone(),
+            )
+        } else {
+            (vec![], vec![])
+        };
+        let retained_max_keys = unwrap_or_default!(config.routing().router().retained_max_keys());
+        let tombstone_ttl =
+            Duration::from_millis(unwrap_or_default!(config.routing().sweep().tombstone_ttl()));
         let hat_code = hat::new_hat(whatami, config);
         Ok(Tables {
             zid,
@@ -95,12 +220,24 @@ impl Tables {
             face_counter: 0,
             hlc,
             drop_future_timestamp,
+            timestamp_bound,
             queries_default_timeout,
+            complete_short_circuit,
+            limits: RoutingLimits::new(config),
+            tombstones: HashMap::new(),
+            tombstone_ttl,
+            purged_declarations: 0,
+            queryable_conflicts: vec![],
+            congestions: HashMap::new(),
             root_res: Resource::root(),
             faces: HashMap::new(),
             mcast_groups: vec![],
             mcast_faces: vec![],
             interceptors: interceptor_factories(config)?,
+            static_routes,
+            retained,
+            retained_data: Mutex::new(HashMap::new()),
+            retained_max_keys,
             pull_caches_lock: Mutex::new(()),
             hat: hat_code.new_tables(router_peers_failover_brokering),
             hat_code: hat_code.into(),
@@ -148,6 +285,19 @@ impl Tables {
         self.faces.values().find(|face| face.zid == *zid)
     }
 
+    /// Updates the faces connected to the next hops of the static routes,
+    /// to be called when a face is added or when the links of a face change.
+    pub(crate) fn update_static_routes(&mut self) {
+        for route in self.static_routes.iter_mut() {
+            route.faces = self
+                .faces
+                .values()
+                .filter(|face| route.is_next_hop(face))
+                .cloned()
+                .collect();
+        }
+    }
+
     fn update_routes(&mut self, res: &mut Arc<Resource>) {
         update_data_routes(self, res);
         update_query_routes(self, res);
@@ -168,18 +318,142 @@ impl Tables {
     }
 }
 
+/// Updates the destination locators of the links of a face, e.g. when a link is added to or
+/// removed from its transport, and the static routes whose next hop they are.
+pub(crate) fn update_face_links(tables: &TablesLock, face: &Arc<FaceState>, links: Vec<Locator>) {
+    let mut wtables = zwrite!(tables.tables);
+    get_mut_unchecked(&mut face.clone()).links = links;
+    wtables.update_static_routes();
+}
+
 pub fn close_face(tables: &TablesLock, face: &Weak<FaceState>) {
     match face.upgrade() {
         Some(mut face) => {
             tracing::debug!("Close {}", face);
             face.task_controller.terminate_all(Duration::from_secs(10));
             finalize_pending_queries(tables, &mut face);
-            zlock!(tables.ctrl_lock).close_face(tables, &mut face);
+            let ctrl_lock = zlock!(tables.ctrl_lock);
+            let mut wtables = zwrite!(tables.tables);
+            let ttl = wtables.tombstone_ttl;
+            wtables
+                .tombstones
+                .retain(|_, closed| closed.elapsed() < ttl);
+            wtables.tombstones.insert(face.id, Instant::now());
+            wtables
+                .queryable_conflicts
+                .retain(|c| !c.involves_face(face.id));
+            // The static routes must not keep the closed face alive
+            for route in wtables.static_routes.iter_mut() {
+                route.faces.retain(|f| f.id != face.id);
+            }
+            drop(wtables);
+            super::pubsub::close_face_congestions(tables, face.id);
+            ctrl_lock.close_face(tables, &mut face);
         }
         None => tracing::error!("Face already closed!"),
     }
 }
 
+fn is_live_face(tables: &Tables, id: usize) -> bool {
+    tables.faces.contains_key(&id)
+        || tables.mcast_groups.iter().any(|f| f.id == id)
+        || tables.mcast_faces.iter().any(|f| f.id == id)
+}
+
+// The number of resources checked by a sweep each time it takes the tables read lock
+const SWEEP_CHUNK: usize = 256;
+
+// Purges the declarations of the resource made by the face, unless they were purged
+// or the face came back since they were found stale.
+fn purge_session_ctx(tables: &TablesLock, res: &Arc<Resource>, face_id: usize) -> usize {
+    let ctrl_lock = zlock!(tables.ctrl_lock);
+    let mut wtables = zwrite!(tables.tables);
+    if is_live_face(&wtables, face_id) {
+        return 0;
+    }
+    let Some(ctx) = res.session_ctxs.get(&face_id).cloned() else {
+        return 0;
+    };
+    let mut res = res.clone();
+    let mut face = ctx.face.clone();
+    tracing::debug!("Purge stale declarations of {} for {}", res.expr(), face);
+    let mut purged = 0;
+    if ctx.subs.is_some() {
+        ctrl_lock.undeclare_subscription(&mut wtables, &mut face, &mut res, 0);
+        purged += 1;
+    }
+    if ctx.qabl.is_some() {
+        ctrl_lock.undeclare_queryable(&mut wtables, &mut face, &mut res, 0);
+        purged += 1;
+    }
+    drop(ctx);
+    get_mut_unchecked(&mut res).session_ctxs.remove(&face_id);
+
+    disable_matches_data_routes(&mut wtables, &mut res);
+    disable_matches_query_routes(&mut wtables, &mut res);
+    for (mut res, data_routes, matching_pulls) in compute_matches_data_routes(&wtables, &res) {
+        get_mut_unchecked(&mut res)
+            .context_mut()
+            .update_data_routes(data_routes);
+        get_mut_unchecked(&mut res)
+            .context_mut()
+            .update_matching_pulls(matching_pulls);
+    }
+    for (mut res, query_routes) in compute_matches_query_routes(&wtables, &res) {
+        get_mut_unchecked(&mut res)
+            .context_mut()
+            .update_query_routes(query_routes);
+    }
+    Resource::clean(&mut res);
+    wtables.purged_declarations += purged;
+    purged
+}
+
+/// Checks the consistency of the routing tables, purging the declarations of the faces
+/// that were closed, and forgets the tombstones older than the configured ttl.
+/// The resources are walked by chunks, releasing the tables lock in between, and each
+/// stale declaration is purged on its own so that the routing is never blocked for long.
+/// Returns the number of purged subscriptions and queryables.
+pub(crate) fn sweep(tables: &TablesLock) -> usize {
+    let mut pending = {
+        let mut wtables = zwrite!(tables.tables);
+        let ttl = wtables.tombstone_ttl;
+        wtables
+            .tombstones
+            .retain(|_, closed| closed.elapsed() < ttl);
+        vec![wtables.root_res.clone()]
+    };
+
+    let mut purged = 0;
+    while !pending.is_empty() {
+        let mut stale = vec![];
+        {
+            let rtables = zread!(tables.tables);
+            for _ in 0..SWEEP_CHUNK {
+                let Some(res) = pending.pop() else {
+                    break;
+                };
+                for ctx in res.session_ctxs.values() {
+                    if !is_live_face(&rtables, ctx.face.id) {
+                        stale.push((res.clone(), ctx.face.id));
+                    }
+                }
+                pending.extend(res.childs.values().cloned());
+            }
+        }
+        for (res, face_id) in stale {
+            purged += purge_session_ctx(tables, &res, face_id);
+        }
+    }
+    if purged > 0 {
+        tracing::warn!(
+            "Purged {} stale declarations from the routing tables",
+            purged
+        );
+    }
+    purged
+}
+
 pub struct TablesLock {
     pub tables: RwLock<Tables>,
     pub(crate) ctrl_lock: Mutex<Box<dyn HatTrait + Send + Sync>>,
@@ -0,0 +1,189 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use crate::net::protocol::linkstate::LinkStateTtl;
+use std::time::{Duration, Instant};
+use zenoh_config::Config;
+
+/// The configured limits of the relaying by gossip of the locators of this node.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct GossipTtlConf {
+    max_hops: Option<u8>,
+    ttl: Option<Duration>,
+}
+
+impl GossipTtlConf {
+    pub(crate) fn new(config: &Config) -> Self {
+        GossipTtlConf {
+            max_hops: *config.scouting().gossip().max_hops(),
+            ttl: config.scouting().gossip().ttl().map(Duration::from_millis),
+        }
+    }
+
+    /// Whether the locators of a node should be relayed: always for this node, within the
+    /// limits received with them if any, or else as decided by `unlimited`.
+    pub(crate) fn propagate(
+        &self,
+        is_self: bool,
+        ttl: &GossipTtl,
+        unlimited: impl FnOnce() -> bool,
+    ) -> bool {
+        if is_self {
+            true
+        } else if ttl.is_limited() {
+            ttl.relayable(Instant::now())
+        } else {
+            unlimited()
+        }
+    }
+
+    /// The limits to advertise with the locators of a node: the configured ones for this
+    /// node, or the remaining ones for the others.
+    pub(crate) fn advertise(&self, is_self: bool, ttl: &GossipTtl) -> LinkStateTtl {
+        if is_self {
+            LinkStateTtl {
+                hops: self.max_hops,
+                lifetime: self.ttl.map(as_millis),
+            }
+        } else {
+            ttl.relayed(Instant::now())
+        }
+    }
+}
+
+/// The limits of the relaying by gossip of the locators received from a node.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct GossipTtl {
+    // The number of times the locators may still be relayed
+    hops: Option<u8>,
+    // The instant until which the locators may still be relayed
+    expiry: Option<Instant>,
+}
+
+impl GossipTtl {
+    pub(crate) fn received(ttl: LinkStateTtl, now: Instant) -> Self {
+        GossipTtl {
+            hops: ttl.hops,
+            // A lifetime too long to be represented is not limited
+            expiry: ttl
+                .lifetime
+                .and_then(|ms| now.checked_add(Duration::from_millis(ms))),
+        }
+    }
+
+    fn is_limited(&self) -> bool {
+        self.hops.is_some() || self.expiry.is_some()
+    }
+
+    fn relayable(&self, now: Instant) -> bool {
+        self.hops.map_or(true, |hops| hops > 0) && self.expiry.map_or(true, |expiry| now < expiry)
+    }
+
+    // The limits of the receivers of the relayed locators: one hop less and the remaining time
+    fn relayed(&self, now: Instant) -> LinkStateTtl {
+        LinkStateTtl {
+            hops: self.hops.map(|hops| hops.saturating_sub(1)),
+            lifetime: self
+                .expiry
+                .map(|expiry| as_millis(expiry.saturating_duration_since(now))),
+        }
+    }
+}
+
+fn as_millis(d: Duration) -> u64 {
+    u64::try_from(d.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conf(max_hops: Option<u8>, ttl: Option<u64>) -> GossipTtlConf {
+        GossipTtlConf {
+            max_hops,
+            ttl: ttl.map(Duration::from_millis),
+        }
+    }
+
+    #[test]
+    fn gossip_ttl_hops() {
+        let now = Instant::now();
+        let conf = conf(Some(2), None);
+
+        // The locators of self are advertised with the configured limit
+        let advertised = conf.advertise(true, &GossipTtl::default());
+        assert_eq!(advertised.hops, Some(2));
+        assert_eq!(advertised.lifetime, None);
+        assert!(conf.propagate(true, &GossipTtl::default(), || false));
+
+        // Each relay decrements the limit until it is reached
+        let mut ttl = GossipTtl::received(advertised, now);
+        for hops in (0..2).rev() {
+            assert!(conf.propagate(false, &ttl, || false));
+            let relayed = ttl.relayed(now);
+            assert_eq!(relayed.hops, Some(hops));
+            ttl = GossipTtl::received(relayed, now);
+        }
+        assert!(!conf.propagate(false, &ttl, || true));
+        assert_eq!(ttl.relayed(now).hops, Some(0));
+    }
+
+    #[test]
+    fn gossip_ttl_lifetime() {
+        let now = Instant::now();
+        let conf = conf(None, Some(1_000));
+
+        let advertised = conf.advertise(true, &GossipTtl::default());
+        assert_eq!(advertised.hops, None);
+        assert_eq!(advertised.lifetime, Some(1_000));
+
+        // Each relay advertises the remaining time
+        let ttl = GossipTtl::received(advertised, now);
+        assert!(ttl.relayable(now));
+        let later = now + Duration::from_millis(400);
+        assert!(ttl.relayable(later));
+        assert_eq!(ttl.relayed(later).lifetime, Some(600));
+
+        // The locators are no longer relayed once expired
+        let expired = now + Duration::from_millis(1_000);
+        assert!(!ttl.relayable(expired));
+        assert_eq!(ttl.relayed(expired).lifetime, Some(0));
+        assert!(!GossipTtl::received(ttl.relayed(expired), expired).relayable(expired));
+
+        // Both limits combine
+        let ttl = GossipTtl::received(
+            LinkStateTtl {
+                hops: Some(0),
+                lifetime: Some(1_000),
+            },
+            now,
+        );
+        assert!(!ttl.relayable(now));
+    }
+
+    #[test]
+    fn gossip_ttl_unlimited() {
+        let conf = conf(None, None);
+        let ttl = GossipTtl::received(LinkStateTtl::default(), Instant::now());
+        assert!(!ttl.is_limited());
+        assert_eq!(ttl.relayed(Instant::now()), LinkStateTtl::default());
+        assert_eq!(
+            conf.advertise(true, &GossipTtl::default()),
+            LinkStateTtl::default()
+        );
+
+        // Without limits the relaying is decided by the multihop and neighbour rules
+        assert!(conf.propagate(false, &ttl, || true));
+        assert!(!conf.propagate(false, &ttl, || false));
+    }
+}
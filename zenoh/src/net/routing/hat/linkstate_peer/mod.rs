@@ -35,7 +35,7 @@ use crate::{
         protocol::linkstate::LinkStateList,
         routing::{
             dispatcher::face::Face,
-            hat::TREES_COMPUTATION_DELAY_MS,
+            hat::{gossip_ttl::GossipTtlConf, TREES_COMPUTATION_DELAY_MS},
             router::{
                 compute_data_routes, compute_matching_pulls, compute_query_routes, RoutesIndexes,
             },
@@ -142,7 +142,7 @@ impl HatTables {
                 zenoh_runtime::ZRuntime::Net,
                 async move {
                     tokio::time::sleep(std::time::Duration::from_millis(
-                        *TREES_COMPUTATION_DELAY_MS,
+                        TREES_COMPUTATION_DELAY_MS.get(),
                     ))
                     .await;
                     let mut tables = zwrite!(tables_ref.tables);
@@ -172,6 +172,7 @@ impl HatBaseTrait for HatCode {
         let whatami = tables.whatami;
         let gossip = unwrap_or_default!(config.scouting().gossip().enabled());
         let gossip_multihop = unwrap_or_default!(config.scouting().gossip().multihop());
+        let gossip_ttl = GossipTtlConf::new(&config);
         let autoconnect = if gossip {
             *unwrap_or_default!(config.scouting().gossip().autoconnect().get(whatami))
         } else {
@@ -192,6 +193,7 @@ impl HatBaseTrait for HatCode {
             router_peers_failover_brokering,
             gossip,
             gossip_multihop,
+            gossip_ttl,
             autoconnect,
         ));
     }
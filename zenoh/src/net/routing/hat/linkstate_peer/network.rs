@@ -12,14 +12,15 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use crate::net::codec::Zenoh080Routing;
-use crate::net::protocol::linkstate::{LinkState, LinkStateList};
+use crate::net::protocol::linkstate::{LinkState, LinkStateList, LinkStateTtl};
 use crate::net::routing::dispatcher::tables::NodeId;
+use crate::net::routing::hat::gossip_ttl::{GossipTtl, GossipTtlConf};
 use crate::net::runtime::Runtime;
 use crate::runtime::WeakRuntime;
 use petgraph::graph::NodeIndex;
 use petgraph::visit::{VisitMap, Visitable};
 use rand::Rng;
-use std::convert::TryInto;
+use std::{convert::TryInto, time::Instant};
 use vec_map::VecMap;
 use zenoh_buffers::writer::{DidntWrite, HasWriter};
 use zenoh_buffers::ZBuf;
@@ -45,6 +46,8 @@ pub(super) struct Node {
     pub(super) locators: Option<Vec<Locator>>,
     pub(super) sn: u64,
     pub(super) links: Vec<ZenohId>,
+    // The limits of the relaying of the locators of the node
+    pub(super) ttl: GossipTtl,
 }
 
 impl std::fmt::Debug for Node {
@@ -111,6 +114,7 @@ pub(super) struct Network {
     pub(super) router_peers_failover_brokering: bool,
     pub(super) gossip: bool,
     pub(super) gossip_multihop: bool,
+    pub(super) gossip_ttl: GossipTtlConf,
     pub(super) autoconnect: WhatAmIMatcher,
     pub(super) idx: NodeIndex,
     pub(super) links: VecMap<Link>,
@@ -130,6 +134,7 @@ impl Network {
         router_peers_failover_brokering: bool,
         gossip: bool,
         gossip_multihop: bool,
+        gossip_ttl: GossipTtlConf,
         autoconnect: WhatAmIMatcher,
     ) -> Self {
         let mut graph = petgraph::stable_graph::StableGraph::default();
@@ -140,6 +145,7 @@ impl Network {
             locators: None,
             sn: 1,
             links: vec![],
+            ttl: GossipTtl::default(),
         });
         Network {
             name,
@@ -147,6 +153,7 @@ impl Network {
             router_peers_failover_brokering,
             gossip,
             gossip_multihop,
+            gossip_ttl,
             autoconnect,
             idx,
             links: VecMap::new(),
@@ -257,6 +264,8 @@ impl Network {
                 None
             },
             links,
+            weights: None,
+            ttl: self.link_state_ttl(idx, &details),
         }
     }
 
@@ -308,18 +317,27 @@ impl Network {
 
     // Indicates if locators should be included when propagating Linkstate message
     // from the given node.
-    // Returns true if gossip is enabled and if multihop gossip is enabled or
-    // the node is one of self neighbours.
+    // Returns true if gossip is enabled and if the node is self, or if the relaying of
+    // its locators is limited and the limits are not reached yet, or else if multihop
+    // gossip is enabled or the node is one of self neighbours.
     fn propagate_locators(&self, idx: NodeIndex) -> bool {
         self.gossip
-            && (self.gossip_multihop
-                || idx == self.idx
-                || self.links.values().any(|link| {
-                    self.graph
-                        .node_weight(idx)
-                        .map(|node| link.zid == node.zid)
-                        .unwrap_or(true)
-                }))
+            && match self.graph.node_weight(idx) {
+                Some(node) => self.gossip_ttl.propagate(idx == self.idx, &node.ttl, || {
+                    self.gossip_multihop || self.links.values().any(|link| link.zid == node.zid)
+                }),
+                None => idx == self.idx || self.gossip_multihop || !self.links.is_empty(),
+            }
+    }
+
+    // The limits of the relaying of the locators of the given node by the receiver.
+    fn link_state_ttl(&self, idx: NodeIndex, details: &Details) -> LinkStateTtl {
+        if details.locators {
+            self.gossip_ttl
+                .advertise(idx == self.idx, &self.graph[idx].ttl)
+        } else {
+            LinkStateTtl::default()
+        }
     }
 
     fn update_edge(&mut self, idx1: NodeIndex, idx2: NodeIndex) {
@@ -359,6 +377,7 @@ impl Network {
         };
 
         // register psid<->zid mappings & apply mapping to nodes
+        let now = Instant::now();
         let link_states = link_states
             .into_iter()
             .filter_map(|link_state| {
@@ -373,6 +392,7 @@ impl Network {
                         link_state.locators,
                         link_state.sn,
                         link_state.links,
+                        GossipTtl::received(link_state.ttl, now),
                     ))
                 } else {
                     match src_link.get_zid(&link_state.psid) {
@@ -382,6 +402,7 @@ impl Network {
                             link_state.locators,
                             link_state.sn,
                             link_state.links,
+                            GossipTtl::received(link_state.ttl, now),
                         )),
                         None => {
                             tracing::error!(
@@ -400,7 +421,7 @@ impl Network {
         let src_link = self.get_link_from_zid(&src).unwrap();
         let link_states = link_states
             .into_iter()
-            .map(|(zid, wai, locs, sn, links)| {
+            .map(|(zid, wai, locs, sn, links, ttl)| {
                 let links: Vec<ZenohId> = links
                     .iter()
                     .filter_map(|l| {
@@ -417,7 +438,7 @@ impl Network {
                         }
                     })
                     .collect();
-                (zid, wai, locs, sn, links)
+                (zid, wai, locs, sn, links, ttl)
             })
             .collect::<Vec<_>>();
 
@@ -441,7 +462,7 @@ impl Network {
                 updated_nodes: vec![],
                 removed_nodes: vec![],
             };
-            for (zid, whatami, locators, sn, links) in link_states.into_iter() {
+            for (zid, whatami, locators, sn, links, ttl) in link_states.into_iter() {
                 let idx = match self.get_idx(&zid) {
                     None => {
                         let idx = self.add_node(Node {
@@ -450,6 +471,7 @@ impl Network {
                             locators: locators.clone(),
                             sn,
                             links,
+                            ttl,
                         });
                         changes.updated_nodes.push((idx, self.graph[idx].clone()));
                         locators.is_some().then_some(idx)
@@ -462,6 +484,7 @@ impl Network {
                                 node.sn = sn;
                                 node.links.clone_from(&links);
                                 changes.updated_nodes.push((idx, node.clone()));
+                                node.ttl = ttl;
                                 (node.locators != locators && locators.is_some()).then(|| {
                                     node.locators.clone_from(&locators);
                                     idx
@@ -473,7 +496,7 @@ impl Network {
 
                 if self.gossip {
                     if let Some(idx) = idx {
-                        if self.gossip_multihop || self.links.values().any(|link| link.zid == zid) {
+                        if self.propagate_locators(idx) {
                             self.send_on_links(
                                 vec![(
                                     idx,
@@ -518,13 +541,14 @@ impl Network {
         let mut link_states = link_states
             .into_iter()
             .filter_map(
-                |(zid, whatami, locators, sn, links)| match self.get_idx(&zid) {
+                |(zid, whatami, locators, sn, links, ttl)| match self.get_idx(&zid) {
                     Some(idx) => {
                         let node = &mut self.graph[idx];
                         let oldsn = node.sn;
                         if oldsn < sn {
                             node.sn = sn;
                             node.links.clone_from(&links);
+                            node.ttl = ttl;
                             if locators.is_some() {
                                 node.locators = locators;
                             }
@@ -544,6 +568,7 @@ impl Network {
                             locators,
                             sn,
                             links: links.clone(),
+                            ttl,
                         };
                         tracing::debug!("{} Add node (state) {}", self.name, zid);
                         let idx = self.add_node(node);
@@ -574,6 +599,7 @@ impl Network {
                         locators: None,
                         sn: 0,
                         links: vec![],
+                        ttl: GossipTtl::default(),
                     };
                     tracing::debug!("{} Add node (reintroduced) {}", self.name, link.clone());
                     let idx = self.add_node(node);
@@ -720,6 +746,7 @@ impl Network {
                             locators: None,
                             sn: 0,
                             links: vec![],
+                            ttl: GossipTtl::default(),
                         }),
                         true,
                     )
@@ -697,6 +697,18 @@ impl HatQueriesTrait for HatCode {
             .collect()
     }
 
+    fn get_remote_complete_queryables(&self, tables: &Tables, res: &Arc<Resource>) -> Vec<ZenohId> {
+        if res.context.is_none() {
+            return vec![];
+        }
+        res_hat!(res)
+            .peer_qabls
+            .iter()
+            .filter(|(zid, info)| **zid != tables.zid && info.complete != 0)
+            .map(|(zid, _)| *zid)
+            .collect()
+    }
+
     fn compute_query_route(
         &self,
         tables: &Tables,
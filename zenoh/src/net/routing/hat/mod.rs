@@ -39,12 +39,16 @@ use zenoh_result::ZResult;
 use zenoh_transport::unicast::TransportUnicast;
 
 mod client;
+mod gossip_ttl;
 mod linkstate_peer;
 mod p2p_peer;
 mod router;
 
 zconfigurable! {
+    #[mutable]
     pub static ref TREES_COMPUTATION_DELAY_MS: u64 = 100;
+    #[mutable]
+    pub static ref LINK_WEIGHTS_REFRESH_MS: u64 = 10_000;
 }
 
 #[derive(serde::Serialize)]
@@ -105,6 +109,19 @@ pub(crate) trait HatBaseTrait {
         routing_context: NodeId,
     ) -> NodeId;
 
+    /// The routing context of the data received from `face` in the local routing context
+    /// `local_context` when forwarded to `out_face`, i.e. the node of the routing tree the
+    /// data is routed along.
+    fn egress_routing_context(
+        &self,
+        _tables: &Tables,
+        _face: &FaceState,
+        local_context: NodeId,
+        _out_face: &FaceState,
+    ) -> NodeId {
+        local_context
+    }
+
     fn ingress_filter(&self, tables: &Tables, face: &FaceState, expr: &mut RoutingExpr) -> bool;
 
     fn egress_filter(
@@ -176,6 +193,10 @@ pub(crate) trait HatQueriesTrait {
 
     fn get_queryables(&self, tables: &Tables) -> Vec<(Arc<Resource>, Sources)>;
 
+    /// The nodes of the routers and peers networks, other than this one, with a complete
+    /// queryable on the given resource.
+    fn get_remote_complete_queryables(&self, tables: &Tables, res: &Arc<Resource>) -> Vec<ZenohId>;
+
     fn compute_query_route(
         &self,
         tables: &Tables,
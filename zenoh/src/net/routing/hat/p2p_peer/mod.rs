@@ -23,6 +23,7 @@ use crate::{
         protocol::linkstate::LinkStateList,
         routing::{
             dispatcher::face::Face,
+            hat::gossip_ttl::GossipTtlConf,
             router::{
                 compute_data_routes, compute_matching_pulls, compute_query_routes, RoutesIndexes,
             },
@@ -101,6 +102,7 @@ impl HatBaseTrait for HatCode {
         let whatami = tables.whatami;
         let gossip = unwrap_or_default!(config.scouting().gossip().enabled());
         let gossip_multihop = unwrap_or_default!(config.scouting().gossip().multihop());
+        let gossip_ttl = GossipTtlConf::new(&config);
         let autoconnect = if gossip {
             *unwrap_or_default!(config.scouting().gossip().autoconnect().get(whatami))
         } else {
@@ -117,6 +119,7 @@ impl HatBaseTrait for HatCode {
             router_peers_failover_brokering,
             gossip,
             gossip_multihop,
+            gossip_ttl,
             autoconnect,
         ));
     }
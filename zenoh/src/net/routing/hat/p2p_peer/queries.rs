@@ -28,7 +28,7 @@ use zenoh_buffers::ZBuf;
 use zenoh_protocol::core::key_expr::include::{Includer, DEFAULT_INCLUDER};
 use zenoh_protocol::core::key_expr::OwnedKeyExpr;
 use zenoh_protocol::{
-    core::{WhatAmI, WireExpr},
+    core::{WhatAmI, WireExpr, ZenohId},
     network::declare::{
         common::ext::WireExprType, ext, queryable::ext::QueryableInfo, Declare, DeclareBody,
         DeclareQueryable, UndeclareQueryable,
@@ -290,6 +290,14 @@ impl HatQueriesTrait for HatCode {
         Vec::from_iter(qabls)
     }
 
+    fn get_remote_complete_queryables(
+        &self,
+        _tables: &Tables,
+        _res: &Arc<Resource>,
+    ) -> Vec<ZenohId> {
+        vec![]
+    }
+
     fn compute_query_route(
         &self,
         tables: &Tables,
@@ -39,7 +39,7 @@ use crate::{
         protocol::linkstate::LinkStateList,
         routing::{
             dispatcher::face::Face,
-            hat::TREES_COMPUTATION_DELAY_MS,
+            hat::{gossip_ttl::GossipTtlConf, LINK_WEIGHTS_REFRESH_MS, TREES_COMPUTATION_DELAY_MS},
             router::{
                 compute_data_routes, compute_matching_pulls, compute_query_routes, RoutesIndexes,
             },
@@ -124,6 +124,7 @@ struct HatTables {
     shared_nodes: Vec<ZenohId>,
     routers_trees_task: Option<TerminatableTask>,
     peers_trees_task: Option<TerminatableTask>,
+    link_weights_task: Option<TerminatableTask>,
     router_peers_failover_brokering: bool,
 }
 
@@ -137,6 +138,9 @@ impl Drop for HatTables {
             let task = self.routers_trees_task.take().unwrap();
             task.terminate(Duration::from_secs(10));
         }
+        if let Some(task) = self.link_weights_task.take() {
+            task.terminate(Duration::from_secs(10));
+        }
     }
 }
 
@@ -152,6 +156,7 @@ impl HatTables {
             shared_nodes: vec![],
             routers_trees_task: None,
             peers_trees_task: None,
+            link_weights_task: None,
             router_peers_failover_brokering,
         }
     }
@@ -261,7 +266,7 @@ impl HatTables {
                 zenoh_runtime::ZRuntime::Net,
                 async move {
                     tokio::time::sleep(std::time::Duration::from_millis(
-                        *TREES_COMPUTATION_DELAY_MS,
+                        TREES_COMPUTATION_DELAY_MS.get(),
                     ))
                     .await;
                     let mut tables = zwrite!(tables_ref.tables);
@@ -294,6 +299,38 @@ impl HatTables {
             };
         }
     }
+
+    // Periodically refreshes the weights of the links of the routers network from their
+    // measured metrics, recomputing the trees when they change.
+    fn schedule_link_weights(&mut self, tables_ref: &Arc<TablesLock>) {
+        if self.link_weights_task.is_some()
+            || !self
+                .routers_net
+                .as_ref()
+                .is_some_and(|net| net.link_weights)
+        {
+            return;
+        }
+        // Do not keep the tables alive
+        let tables_ref = Arc::downgrade(tables_ref);
+        let task = TerminatableTask::spawn_abortable(zenoh_runtime::ZRuntime::Net, async move {
+            loop {
+                tokio::time::sleep(Duration::from_millis(LINK_WEIGHTS_REFRESH_MS.get())).await;
+                let Some(tables_ref) = tables_ref.upgrade() else {
+                    break;
+                };
+                let mut tables = zwrite!(tables_ref.tables);
+                let changed = hat_mut!(tables)
+                    .routers_net
+                    .as_mut()
+                    .map_or(false, |net| net.refresh_link_weights());
+                if changed {
+                    hat_mut!(tables).schedule_compute_trees(tables_ref.clone(), WhatAmI::Router);
+                }
+            }
+        });
+        self.link_weights_task = Some(task);
+    }
 }
 
 pub(crate) struct HatCode {}
@@ -304,6 +341,7 @@ impl HatBaseTrait for HatCode {
         let whatami = tables.whatami;
         let gossip = unwrap_or_default!(config.scouting().gossip().enabled());
         let gossip_multihop = unwrap_or_default!(config.scouting().gossip().multihop());
+        let gossip_ttl = GossipTtlConf::new(&config);
         let autoconnect = if gossip {
             *unwrap_or_default!(config.scouting().gossip().autoconnect().get(whatami))
         } else {
@@ -315,6 +353,8 @@ impl HatBaseTrait for HatCode {
             && unwrap_or_default!(config.routing().peer().mode()) == *"linkstate";
         let router_peers_failover_brokering =
             unwrap_or_default!(config.routing().router().peers_failover_brokering());
+        // The links are only weighted by their metrics when measured
+        let link_weights = config.transport().link().tx().echo_interval().is_some();
         drop(config);
 
         if router_full_linkstate | gossip {
@@ -326,7 +366,9 @@ impl HatBaseTrait for HatCode {
                 router_peers_failover_brokering,
                 gossip,
                 gossip_multihop,
+                gossip_ttl,
                 autoconnect,
+                router_full_linkstate && link_weights,
             ));
         }
         if peer_full_linkstate | gossip {
@@ -338,7 +380,9 @@ impl HatBaseTrait for HatCode {
                 router_peers_failover_brokering,
                 gossip,
                 gossip_multihop,
+                gossip_ttl,
                 autoconnect,
+                false,
             ));
         }
         if router_full_linkstate && peer_full_linkstate {
@@ -409,6 +453,7 @@ impl HatBaseTrait for HatCode {
         match face.state.whatami {
             WhatAmI::Router => {
                 hat_mut!(tables).schedule_compute_trees(tables_ref.clone(), WhatAmI::Router);
+                hat_mut!(tables).schedule_link_weights(tables_ref);
             }
             WhatAmI::Peer => {
                 if hat_mut!(tables).full_net(WhatAmI::Peer) {
@@ -645,6 +690,27 @@ impl HatBaseTrait for HatCode {
         }
     }
 
+    fn egress_routing_context(
+        &self,
+        tables: &Tables,
+        face: &FaceState,
+        local_context: NodeId,
+        out_face: &FaceState,
+    ) -> NodeId {
+        // The trees are rooted at the node the data entered the network through
+        match out_face.whatami {
+            WhatAmI::Router => match face.whatami {
+                WhatAmI::Router => local_context,
+                _ => hat!(tables).routers_net.as_ref().unwrap().idx.index() as NodeId,
+            },
+            WhatAmI::Peer if hat!(tables).full_net(WhatAmI::Peer) => match face.whatami {
+                WhatAmI::Peer => local_context,
+                _ => hat!(tables).peers_net.as_ref().unwrap().idx.index() as NodeId,
+            },
+            _ => 0,
+        }
+    }
+
     fn closing(
         &self,
         tables: &mut Tables,
@@ -12,24 +12,39 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use crate::net::codec::Zenoh080Routing;
-use crate::net::protocol::linkstate::{LinkState, LinkStateList};
+use crate::net::protocol::linkstate::{LinkState, LinkStateList, LinkStateTtl};
 use crate::net::routing::dispatcher::tables::NodeId;
+use crate::net::routing::hat::gossip_ttl::{GossipTtl, GossipTtlConf};
 use crate::net::runtime::Runtime;
 use petgraph::graph::NodeIndex;
 use petgraph::visit::{IntoNodeReferences, VisitMap, Visitable};
 use rand::Rng;
-use std::convert::TryInto;
+use std::{collections::HashMap, convert::TryInto, time::Instant};
 use vec_map::VecMap;
 use zenoh_buffers::writer::{DidntWrite, HasWriter};
 use zenoh_buffers::ZBuf;
 use zenoh_codec::WCodec;
-use zenoh_link::Locator;
+use zenoh_link::{LinkMetrics, Locator};
 use zenoh_protocol::common::ZExtBody;
 use zenoh_protocol::core::{WhatAmI, WhatAmIMatcher, ZenohId};
 use zenoh_protocol::network::oam::id::OAM_LINKSTATE;
 use zenoh_protocol::network::{oam, NetworkBody, NetworkMessage, Oam};
 use zenoh_transport::unicast::TransportUnicast;
 
+// The weight of a link without metrics
+const LINK_WEIGHT_DEFAULT: u16 = 100;
+// The minimal change of the weight of a link to advertise it
+const LINK_WEIGHT_HYSTERESIS: u16 = 10;
+
+// The weight of a link: its round-trip time in milliseconds and its loss in percent
+// on top of the default weight, so that the links without metrics remain comparable.
+fn link_weight(metrics: &LinkMetrics) -> u16 {
+    let srtt = u16::try_from(metrics.srtt.as_millis()).unwrap_or(u16::MAX);
+    LINK_WEIGHT_DEFAULT
+        .saturating_add(srtt)
+        .saturating_add(metrics.loss / 10)
+}
+
 #[derive(Clone)]
 struct Details {
     zid: bool,
@@ -44,6 +59,10 @@ pub(super) struct Node {
     pub(super) locators: Option<Vec<Locator>>,
     pub(super) sn: u64,
     pub(super) links: Vec<ZenohId>,
+    // The limits of the relaying of the locators of the node
+    pub(super) ttl: GossipTtl,
+    // The weights of the links reported by the node, if measured
+    pub(super) weights: HashMap<ZenohId, u16>,
 }
 
 impl std::fmt::Debug for Node {
@@ -110,7 +129,9 @@ pub(super) struct Network {
     pub(super) router_peers_failover_brokering: bool,
     pub(super) gossip: bool,
     pub(super) gossip_multihop: bool,
+    pub(super) gossip_ttl: GossipTtlConf,
     pub(super) autoconnect: WhatAmIMatcher,
+    pub(super) link_weights: bool,
     pub(super) idx: NodeIndex,
     pub(super) links: VecMap<Link>,
     pub(super) trees: Vec<Tree>,
@@ -129,7 +150,9 @@ impl Network {
         router_peers_failover_brokering: bool,
         gossip: bool,
         gossip_multihop: bool,
+        gossip_ttl: GossipTtlConf,
         autoconnect: WhatAmIMatcher,
+        link_weights: bool,
     ) -> Self {
         let mut graph = petgraph::stable_graph::StableGraph::default();
         tracing::debug!("{} Add node (self) {}", name, zid);
@@ -139,6 +162,8 @@ impl Network {
             locators: None,
             sn: 1,
             links: vec![],
+            ttl: GossipTtl::default(),
+            weights: HashMap::new(),
         });
         Network {
             name,
@@ -146,7 +171,9 @@ impl Network {
             router_peers_failover_brokering,
             gossip,
             gossip_multihop,
+            gossip_ttl,
             autoconnect,
+            link_weights,
             idx,
             links: VecMap::new(),
             trees: vec![Tree {
@@ -222,13 +249,19 @@ impl Network {
     }
 
     fn make_link_state(&self, idx: NodeIndex, details: Details) -> LinkState {
-        let links = if details.links {
-            self.graph[idx]
-                .links
+        let node = &self.graph[idx];
+        let (links, weights): (Vec<u64>, Vec<u16>) = if details.links {
+            node.links
                 .iter()
                 .filter_map(|zid| {
                     if let Some(idx2) = self.get_idx(zid) {
-                        Some(idx2.index().try_into().unwrap())
+                        Some((
+                            u64::try_from(idx2.index()).unwrap(),
+                            node.weights
+                                .get(zid)
+                                .copied()
+                                .unwrap_or(LINK_WEIGHT_DEFAULT),
+                        ))
                     } else {
                         tracing::error!(
                             "{} Internal error building link state: cannot get index of {}",
@@ -238,9 +271,9 @@ impl Network {
                         None
                     }
                 })
-                .collect()
+                .unzip()
         } else {
-            vec![]
+            (vec![], vec![])
         };
         LinkState {
             psid: idx.index().try_into().unwrap(),
@@ -261,6 +294,8 @@ impl Network {
                 None
             },
             links,
+            weights: (details.links && !node.weights.is_empty()).then_some(weights),
+            ttl: self.link_state_ttl(idx, &details),
         }
     }
 
@@ -312,18 +347,27 @@ impl Network {
 
     // Indicates if locators should be included when propagating Linkstate message
     // from the given node.
-    // Returns true if gossip is enabled and if multihop gossip is enabled or
-    // the node is one of self neighbours.
+    // Returns true if gossip is enabled and if the node is self, or if the relaying of
+    // its locators is limited and the limits are not reached yet, or else if multihop
+    // gossip is enabled or the node is one of self neighbours.
     fn propagate_locators(&self, idx: NodeIndex) -> bool {
         self.gossip
-            && (self.gossip_multihop
-                || idx == self.idx
-                || self.links.values().any(|link| {
-                    self.graph
-                        .node_weight(idx)
-                        .map(|node| link.zid == node.zid)
-                        .unwrap_or(true)
-                }))
+            && match self.graph.node_weight(idx) {
+                Some(node) => self.gossip_ttl.propagate(idx == self.idx, &node.ttl, || {
+                    self.gossip_multihop || self.links.values().any(|link| link.zid == node.zid)
+                }),
+                None => idx == self.idx || self.gossip_multihop || !self.links.is_empty(),
+            }
+    }
+
+    // The limits of the relaying of the locators of the given node by the receiver.
+    fn link_state_ttl(&self, idx: NodeIndex, details: &Details) -> LinkStateTtl {
+        if details.locators {
+            self.gossip_ttl
+                .advertise(idx == self.idx, &self.graph[idx].ttl)
+        } else {
+            LinkStateTtl::default()
+        }
     }
 
     fn update_edge(&mut self, idx1: NodeIndex, idx2: NodeIndex) {
@@ -336,7 +380,16 @@ impl Network {
             hasher.write(&self.graph[idx1].zid.to_le_bytes());
             hasher.write(&self.graph[idx2].zid.to_le_bytes());
         }
-        let weight = 100.0 + ((hasher.finish() as u32) as f64) / u32::MAX as f64;
+        // Both ends of a link must agree on its weight to compute the same trees
+        let reported = |from: NodeIndex, to: NodeIndex| {
+            self.graph[from]
+                .weights
+                .get(&self.graph[to].zid)
+                .copied()
+                .unwrap_or(LINK_WEIGHT_DEFAULT)
+        };
+        let weight = reported(idx1, idx2).max(reported(idx2, idx1)) as f64
+            + ((hasher.finish() as u32) as f64) / u32::MAX as f64;
         self.graph.update_edge(idx1, idx2, weight);
     }
 
@@ -362,6 +415,7 @@ impl Network {
         };
 
         // register psid<->zid mappings & apply mapping to nodes
+        let now = Instant::now();
         let link_states = link_states
             .into_iter()
             .filter_map(|link_state| {
@@ -376,6 +430,8 @@ impl Network {
                         link_state.locators,
                         link_state.sn,
                         link_state.links,
+                        link_state.weights,
+                        GossipTtl::received(link_state.ttl, now),
                     ))
                 } else {
                     match src_link.get_zid(&link_state.psid) {
@@ -385,6 +441,8 @@ impl Network {
                             link_state.locators,
                             link_state.sn,
                             link_state.links,
+                            link_state.weights,
+                            GossipTtl::received(link_state.ttl, now),
                         )),
                         None => {
                             tracing::error!(
@@ -403,11 +461,16 @@ impl Network {
         let src_link = self.get_link_from_zid(&src).unwrap();
         let link_states = link_states
             .into_iter()
-            .map(|(zid, wai, locs, sn, links)| {
+            .map(|(zid, wai, locs, sn, links, weights, ttl)| {
+                let mut link_weights = HashMap::new();
                 let links: Vec<ZenohId> = links
                     .iter()
-                    .filter_map(|l| {
+                    .enumerate()
+                    .filter_map(|(i, l)| {
                         if let Some(zid) = src_link.get_zid(l) {
+                            if let Some(w) = weights.as_ref().and_then(|w| w.get(i)) {
+                                link_weights.insert(*zid, *w);
+                            }
                             Some(*zid)
                         } else {
                             tracing::error!(
@@ -420,7 +483,7 @@ impl Network {
                         }
                     })
                     .collect();
-                (zid, wai, locs, sn, links)
+                (zid, wai, locs, sn, links, link_weights, ttl)
             })
             .collect::<Vec<_>>();
 
@@ -444,7 +507,7 @@ impl Network {
                 updated_nodes: vec![],
                 removed_nodes: vec![],
             };
-            for (zid, whatami, locators, sn, links) in link_states.into_iter() {
+            for (zid, whatami, locators, sn, links, weights, ttl) in link_states.into_iter() {
                 let idx = match self.get_idx(&zid) {
                     None => {
                         let idx = self.add_node(Node {
@@ -453,6 +516,8 @@ impl Network {
                             locators: locators.clone(),
                             sn,
                             links,
+                            ttl,
+                            weights,
                         });
                         changes.updated_nodes.push((idx, self.graph[idx].clone()));
                         locators.is_some().then_some(idx)
@@ -464,7 +529,9 @@ impl Network {
                             .then(|| {
                                 node.sn = sn;
                                 node.links.clone_from(&links);
+                                node.weights = weights;
                                 changes.updated_nodes.push((idx, node.clone()));
+                                node.ttl = ttl;
                                 (node.locators != locators && locators.is_some()).then(|| {
                                     node.locators.clone_from(&locators);
                                     idx
@@ -476,7 +543,7 @@ impl Network {
 
                 if self.gossip {
                     if let Some(idx) = idx {
-                        if self.gossip_multihop || self.links.values().any(|link| link.zid == zid) {
+                        if self.propagate_locators(idx) {
                             self.send_on_links(
                                 vec![(
                                     idx,
@@ -520,14 +587,16 @@ impl Network {
         // Add nodes to graph & filter out up to date states
         let mut link_states = link_states
             .into_iter()
-            .filter_map(
-                |(zid, whatami, locators, sn, links)| match self.get_idx(&zid) {
+            .filter_map(|(zid, whatami, locators, sn, links, weights, ttl)| {
+                match self.get_idx(&zid) {
                     Some(idx) => {
                         let node = &mut self.graph[idx];
                         let oldsn = node.sn;
                         if oldsn < sn {
                             node.sn = sn;
                             node.links.clone_from(&links);
+                            node.weights = weights;
+                            node.ttl = ttl;
                             if locators.is_some() {
                                 node.locators = locators;
                             }
@@ -547,13 +616,15 @@ impl Network {
                             locators,
                             sn,
                             links: links.clone(),
+                            ttl,
+                            weights,
                         };
                         tracing::debug!("{} Add node (state) {}", self.name, zid);
                         let idx = self.add_node(node);
                         Some((links, idx, true))
                     }
-                },
-            )
+                }
+            })
             .collect::<Vec<(Vec<ZenohId>, NodeIndex, bool)>>();
 
         // Add/remove edges from graph
@@ -577,6 +648,8 @@ impl Network {
                         locators: None,
                         sn: 0,
                         links: vec![],
+                        ttl: GossipTtl::default(),
+                        weights: HashMap::new(),
                     };
                     tracing::debug!("{} Add node (reintroduced) {}", self.name, link.clone());
                     let idx = self.add_node(node);
@@ -723,6 +796,8 @@ impl Network {
                             locators: None,
                             sn: 0,
                             links: vec![],
+                            ttl: GossipTtl::default(),
+                            weights: HashMap::new(),
                         }),
                         true,
                     )
@@ -814,6 +889,7 @@ impl Network {
         tracing::trace!("{} remove_link {}", self.name, zid);
         self.links.retain(|_, link| link.zid != *zid);
         self.graph[self.idx].links.retain(|link| *link != *zid);
+        self.graph[self.idx].weights.remove(zid);
 
         if self.full_linkstate {
             if let Some((edge, _)) = self
@@ -864,6 +940,56 @@ impl Network {
         }
     }
 
+    // Updates the weights of the links of this node from the metrics of their transports.
+    // Returns true if they changed, in which case they are advertised and the trees
+    // should be recomputed.
+    pub(super) fn refresh_link_weights(&mut self) -> bool {
+        if !self.link_weights || !self.full_linkstate {
+            return false;
+        }
+        let weights: HashMap<ZenohId, u16> = self
+            .links
+            .values()
+            .filter_map(|link| {
+                let links = link.transport.get_links().ok()?;
+                let metrics = links
+                    .iter()
+                    .filter_map(|l| l.metrics())
+                    .min_by_key(|m| m.srtt)?;
+                Some((link.zid, link_weight(metrics)))
+            })
+            .collect();
+        let node = &self.graph[self.idx];
+        let changed = weights.len() != node.weights.len()
+            || weights.iter().any(|(zid, w)| {
+                node.weights
+                    .get(zid)
+                    .map_or(true, |old| old.abs_diff(*w) >= LINK_WEIGHT_HYSTERESIS)
+            });
+        if !changed {
+            return false;
+        }
+        tracing::debug!("{} Update link weights {:?}", self.name, weights);
+        self.graph[self.idx].weights = weights;
+        self.graph[self.idx].sn += 1;
+        let neighbors: Vec<NodeIndex> = self.graph.neighbors_undirected(self.idx).collect();
+        for idx in neighbors {
+            self.update_edge(self.idx, idx);
+        }
+        self.send_on_links(
+            vec![(
+                self.idx,
+                Details {
+                    zid: false,
+                    locators: self.gossip,
+                    links: true,
+                },
+            )],
+            |_| true,
+        );
+        true
+    }
+
     fn remove_detached_nodes(&mut self) -> Vec<(NodeIndex, Node)> {
         let mut dfs_stack = vec![self.idx];
         let mut visit_map = self.graph.visit_map();
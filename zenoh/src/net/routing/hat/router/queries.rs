@@ -1110,6 +1110,22 @@ impl HatQueriesTrait for HatCode {
             .collect()
     }
 
+    fn get_remote_complete_queryables(&self, tables: &Tables, res: &Arc<Resource>) -> Vec<ZenohId> {
+        if res.context.is_none() {
+            return vec![];
+        }
+        let peers = hat!(tables)
+            .full_net(WhatAmI::Peer)
+            .then_some(&res_hat!(res).peer_qabls);
+        res_hat!(res)
+            .router_qabls
+            .iter()
+            .chain(peers.into_iter().flatten())
+            .filter(|(zid, info)| **zid != tables.zid && info.complete != 0)
+            .map(|(zid, _)| *zid)
+            .collect()
+    }
+
     fn compute_query_route(
         &self,
         tables: &Tables,
@@ -0,0 +1,328 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This module is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](../zenoh/index.html)
+
+use super::{
+    EgressInterceptor, IngressInterceptor, InterceptorFactory, InterceptorFactoryTrait,
+    InterceptorTrait,
+};
+use crate::net::routing::RoutingContext;
+use crate::KeyExpr;
+use std::any::Any;
+use std::sync::Arc;
+use zenoh_config::{unwrap_or_default, AdminSpaceRuleConf, Config};
+use zenoh_protocol::core::key_expr::{keyexpr, OwnedKeyExpr};
+use zenoh_protocol::core::ZenohId;
+use zenoh_protocol::network::{NetworkBody, NetworkMessage};
+use zenoh_result::ZResult;
+use zenoh_transport::{multicast::TransportMulticast, unicast::TransportUnicast};
+
+/// Enforces the `adminspace.permissions.rules` on the transports: the publications on the
+/// admin space received from a remote instance are dropped unless a rule grants it the
+/// write access, and its queries on the admin space are dropped unless a rule grants it
+/// the read access to some of the queried key expressions. As such a query may also cover
+/// key expressions not granted, the replies from the admin space sent to the instance are
+/// dropped too unless a rule grants it the read access.
+pub(crate) struct AdminSpacePermissions {
+    // The key expression of the admin space of this instance: @/<whatami>/<zid>/**
+    admin_space: OwnedKeyExpr,
+    rules: Arc<Vec<AdminSpaceRuleConf>>,
+}
+
+pub(crate) fn admin_space_interceptor_factories(
+    config: &Config,
+) -> ZResult<Vec<InterceptorFactory>> {
+    let mut res: Vec<InterceptorFactory> = vec![];
+    if let (true, Some(rules)) = (
+        *config.adminspace.enabled(),
+        config.adminspace.permissions().rules(),
+    ) {
+        let admin_space = OwnedKeyExpr::try_from(format!(
+            "@/{}/{}/**",
+            unwrap_or_default!(config.mode()),
+            config.id()
+        ))?;
+        tracing::debug!(
+            "Admin space permissions enabled on {} with {} rules",
+            admin_space,
+            rules.len()
+        );
+        res.push(Box::new(AdminSpacePermissions {
+            admin_space,
+            rules: Arc::new(rules.clone()),
+        }));
+    }
+    Ok(res)
+}
+
+impl AdminSpacePermissions {
+    fn enforcer(
+        &self,
+        zid: Option<ZenohId>,
+        interfaces: &[String],
+        protocols: &[String],
+    ) -> Enforcer {
+        let mut read = vec![];
+        let mut write = vec![];
+        for rule in self.rules.iter() {
+            let matches = rule
+                .zids
+                .as_ref()
+                .map_or(true, |zids| zid.map_or(false, |zid| zids.contains(&zid)))
+                && rule
+                    .interfaces
+                    .as_ref()
+                    .map_or(true, |i| interfaces.iter().any(|x| i.contains(x)))
+                && rule
+                    .protocols
+                    .as_ref()
+                    .map_or(true, |p| protocols.iter().any(|x| p.contains(x)));
+            if matches {
+                if rule.read {
+                    read.extend(rule.key_exprs.iter().cloned());
+                }
+                if rule.write {
+                    write.extend(rule.key_exprs.iter().cloned());
+                }
+            }
+        }
+        Enforcer {
+            admin_space: self.admin_space.clone(),
+            read,
+            write,
+        }
+    }
+}
+
+impl InterceptorFactoryTrait for AdminSpacePermissions {
+    fn new_transport_unicast(
+        &self,
+        transport: &TransportUnicast,
+    ) -> (Option<IngressInterceptor>, Option<EgressInterceptor>) {
+        let zid = transport.get_zid().ok();
+        let links = transport.get_links().unwrap_or_default();
+        let interfaces: Vec<String> = links
+            .iter()
+            .flat_map(|l| l.interfaces.iter().cloned())
+            .collect();
+        let protocols: Vec<String> = links
+            .iter()
+            .map(|l| l.src.protocol().as_str().to_string())
+            .collect();
+        let enforcer = self.enforcer(zid, &interfaces, &protocols);
+        (
+            Some(Box::new(IngressAdminSpaceEnforcer(enforcer.clone()))),
+            Some(Box::new(EgressAdminSpaceEnforcer(enforcer))),
+        )
+    }
+
+    fn new_transport_multicast(
+        &self,
+        _transport: &TransportMulticast,
+    ) -> Option<EgressInterceptor> {
+        None
+    }
+
+    fn new_peer_multicast(&self, _transport: &TransportMulticast) -> Option<IngressInterceptor> {
+        // The peers of a multicast group are not authenticated: they are granted no access
+        Some(Box::new(IngressAdminSpaceEnforcer(self.enforcer(
+            None,
+            &[],
+            &[],
+        ))))
+    }
+}
+
+#[derive(Clone)]
+struct Enforcer {
+    admin_space: OwnedKeyExpr,
+    read: Vec<OwnedKeyExpr>,
+    write: Vec<OwnedKeyExpr>,
+}
+
+impl Enforcer {
+    // None if the key expression is not in the admin space, or else whether it is granted.
+    fn check(&self, key_expr: &keyexpr, granted: &[OwnedKeyExpr]) -> Option<bool> {
+        self.admin_space
+            .includes(key_expr)
+            .then(|| granted.iter().any(|k| k.includes(key_expr)))
+    }
+
+    // None if the queried key expression does not intersect the admin space, or else
+    // whether some of the admin space it queries is granted.
+    fn check_query(&self, key_expr: &keyexpr) -> Option<bool> {
+        self.admin_space.intersects(key_expr).then(|| {
+            self.read
+                .iter()
+                .any(|k| k.intersects(key_expr) && k.intersects(&self.admin_space))
+        })
+    }
+}
+
+// The checks of the publications and the queries on a key expression.
+struct IngressCache {
+    push: Option<bool>,
+    request: Option<bool>,
+}
+
+struct IngressAdminSpaceEnforcer(Enforcer);
+
+impl InterceptorTrait for IngressAdminSpaceEnforcer {
+    fn compute_keyexpr_cache(&self, key_expr: &KeyExpr<'_>) -> Option<Box<dyn Any + Send + Sync>> {
+        Some(Box::new(IngressCache {
+            push: self.0.check(key_expr, &self.0.write),
+            request: self.0.check_query(key_expr),
+        }))
+    }
+
+    fn intercept(
+        &self,
+        ctx: RoutingContext<NetworkMessage>,
+        cache: Option<&Box<dyn Any + Send + Sync>>,
+    ) -> Option<RoutingContext<NetworkMessage>> {
+        let cache = cache.and_then(|c| c.downcast_ref::<IngressCache>());
+        match &ctx.msg.body {
+            NetworkBody::Push(_) => {
+                let granted = match cache {
+                    Some(cache) => cache.push,
+                    None => ctx
+                        .full_key_expr()
+                        .and_then(|k| self.0.check(&k, &self.0.write)),
+                };
+                if granted == Some(false) {
+                    tracing::warn!(
+                        "Publication on {:?} dropped: no write permission on the admin space",
+                        ctx.full_expr()
+                    );
+                    return None;
+                }
+            }
+            NetworkBody::Request(_) => {
+                let granted = match cache {
+                    Some(cache) => cache.request,
+                    None => ctx.full_key_expr().and_then(|k| self.0.check_query(&k)),
+                };
+                if granted == Some(false) {
+                    tracing::warn!(
+                        "Query on {:?} dropped: no read permission on the admin space",
+                        ctx.full_expr()
+                    );
+                    return None;
+                }
+            }
+            _ => {}
+        }
+        Some(ctx)
+    }
+}
+
+struct EgressAdminSpaceEnforcer(Enforcer);
+
+impl InterceptorTrait for EgressAdminSpaceEnforcer {
+    fn compute_keyexpr_cache(&self, _key_expr: &KeyExpr<'_>) -> Option<Box<dyn Any + Send + Sync>> {
+        None
+    }
+
+    fn intercept(
+        &self,
+        ctx: RoutingContext<NetworkMessage>,
+        _cache: Option<&Box<dyn Any + Send + Sync>>,
+    ) -> Option<RoutingContext<NetworkMessage>> {
+        if let NetworkBody::Response(_) = &ctx.msg.body {
+            if let Some(key_expr) = ctx.full_key_expr() {
+                if self.0.check(&key_expr, &self.0.read) == Some(false) {
+                    tracing::debug!(
+                        "Reply on {} dropped: no read permission on the admin space",
+                        key_expr
+                    );
+                    return None;
+                }
+            }
+        }
+        Some(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn permissions(rules: &str) -> AdminSpacePermissions {
+        AdminSpacePermissions {
+            admin_space: OwnedKeyExpr::try_from("@/router/a1/**").unwrap(),
+            rules: Arc::new(serde_json::from_str(rules).unwrap()),
+        }
+    }
+
+    fn ke(s: &str) -> &keyexpr {
+        keyexpr::new(s).unwrap()
+    }
+
+    #[test]
+    fn admin_space_permissions() {
+        let permissions = permissions(
+            r#"[
+                { "protocols": ["unixpipe"], "key_exprs": ["@/router/*/config/**"], "read": true, "write": true },
+                { "zids": ["b2"], "key_exprs": ["@/router/a1/status/**"], "read": true }
+            ]"#,
+        );
+
+        // A unixpipe link may read and write the config
+        let enforcer = permissions.enforcer(None, &[], &["unixpipe".to_string()]);
+        assert_eq!(
+            enforcer.check(ke("@/router/a1/config/x"), &enforcer.write),
+            Some(true)
+        );
+        assert_eq!(
+            enforcer.check(ke("@/router/a1/status/x"), &enforcer.read),
+            Some(false)
+        );
+        assert_eq!(
+            enforcer.check_query(ke("@/router/a1/config/**")),
+            Some(true)
+        );
+        // A query covering the config is let through, its other replies are dropped
+        assert_eq!(enforcer.check_query(ke("@/router/a1/**")), Some(true));
+        assert_eq!(enforcer.check_query(ke("@/router/a1/status")), Some(false));
+        // The key expressions out of the admin space are not concerned
+        assert_eq!(enforcer.check(ke("demo/example"), &enforcer.write), None);
+        assert_eq!(enforcer.check_query(ke("demo/**")), None);
+
+        // b2 may only read the status over any link
+        let zid = ZenohId::try_from([0xb2]).unwrap();
+        let enforcer = permissions.enforcer(Some(zid), &[], &["tcp".to_string()]);
+        assert_eq!(
+            enforcer.check(ke("@/router/a1/config/x"), &enforcer.write),
+            Some(false)
+        );
+        assert_eq!(
+            enforcer.check_query(ke("@/router/a1/config/**")),
+            Some(false)
+        );
+        assert_eq!(enforcer.check_query(ke("@/router/*/status/**")), Some(true));
+
+        // The others are granted nothing
+        let enforcer = permissions.enforcer(None, &[], &["tcp".to_string()]);
+        assert_eq!(enforcer.check_query(ke("@/**")), Some(false));
+        assert_eq!(
+            enforcer.check(ke("@/router/a1/config/x"), &enforcer.write),
+            Some(false)
+        );
+    }
+}
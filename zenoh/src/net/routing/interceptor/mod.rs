@@ -21,6 +21,9 @@
 mod access_control;
 use access_control::acl_interceptor_factories;
 
+mod admin_space;
+use admin_space::admin_space_interceptor_factories;
+
 mod authorization;
 use super::RoutingContext;
 use crate::KeyExpr;
@@ -65,6 +68,7 @@ pub(crate) fn interceptor_factories(config: &Config) -> ZResult<Vec<InterceptorF
     // res.push(Box::new(LoggerInterceptor {}));
     res.extend(downsampling_interceptor_factories(config.downsampling())?);
     res.extend(acl_interceptor_factories(config.access_control())?);
+    res.extend(admin_space_interceptor_factories(config)?);
     Ok(res)
 }
 
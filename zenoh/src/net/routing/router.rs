@@ -90,6 +90,7 @@ impl Router {
                     None,
                     primitives.clone(),
                     None,
+                    vec![],
                     None,
                     ctrl_lock.new_face(),
                 )
@@ -119,6 +120,11 @@ impl Router {
         let zid = transport.get_zid()?;
         #[cfg(feature = "stats")]
         let stats = transport.get_stats()?;
+        let links = transport
+            .get_links()?
+            .into_iter()
+            .map(|link| link.dst)
+            .collect::<Vec<_>>();
         let (ingress, egress): (Vec<_>, Vec<_>) = tables
             .interceptors
             .iter()
@@ -143,6 +149,7 @@ impl Router {
                     Some(stats),
                     mux.clone(),
                     None,
+                    links,
                     Some(ingress.clone()),
                     ctrl_lock.new_face(),
                 )
@@ -158,6 +165,7 @@ impl Router {
         let _ = mux.face.set(Face::downgrade(&face));
 
         ctrl_lock.new_transport_unicast_face(&mut tables, &self.tables, &mut face, &transport)?;
+        tables.update_static_routes();
 
         Ok(Arc::new(DeMux::new(face, Some(transport), ingress)))
     }
@@ -183,6 +191,7 @@ impl Router {
             None,
             mux.clone(),
             Some(transport),
+            vec![],
             None,
             ctrl_lock.new_face(),
         );
@@ -222,6 +231,7 @@ impl Router {
             Some(transport.get_stats().unwrap()),
             Arc::new(DummyPrimitives),
             Some(transport),
+            vec![],
             Some(interceptor.clone()),
             ctrl_lock.new_face(),
         );
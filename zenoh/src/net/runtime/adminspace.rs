@@ -26,6 +26,7 @@ use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::Instant;
 use tracing::{error, trace};
 use zenoh_buffers::buffer::SplitBuffer;
 use zenoh_config::{unwrap_or_default, ConfigValidator, ValidatedMap, WhatAmI};
@@ -127,6 +128,18 @@ impl AdminSpace {
         if let Some(started) = loaded.started_mut() {
             tracing::warn!("Plugin `{}` was already started", started.name());
         } else {
+            if let Some(dependency) = config
+                .depends
+                .iter()
+                .find(|d| plugin_mgr.started_plugin(d).is_none())
+            {
+                bail!(
+                    "Plugin `{}` depends on `{}` which is not started",
+                    name,
+                    dependency
+                );
+            }
+            let loaded = plugin_mgr.loaded_plugin_mut(name).unwrap();
             let started = loaded.start(start_args)?;
             tracing::info!(
                 "Successfully started plugin `{}` from {}",
@@ -171,6 +184,14 @@ impl AdminSpace {
                 Arc::new(peers_linkstate_data),
             );
         }
+        if runtime.state.whatami != WhatAmI::Client {
+            handlers.insert(
+                format!("@/{whatami_str}/{zid_str}/scouting")
+                    .try_into()
+                    .unwrap(),
+                Arc::new(scouting_data),
+            );
+        }
         handlers.insert(
             format!("@/{whatami_str}/{zid_str}/subscriber/**")
                 .try_into()
@@ -183,6 +204,18 @@ impl AdminSpace {
                 .unwrap(),
             Arc::new(queryables_data),
         );
+        handlers.insert(
+            format!("@/{whatami_str}/{zid_str}/queryable_conflict/**")
+                .try_into()
+                .unwrap(),
+            Arc::new(queryable_conflicts_data),
+        );
+        handlers.insert(
+            format!("@/{whatami_str}/{zid_str}/tuning/**")
+                .try_into()
+                .unwrap(),
+            Arc::new(tuning_data),
+        );
 
         #[cfg(all(feature = "unstable", feature = "plugins"))]
         handlers.insert(
@@ -321,6 +354,17 @@ impl AdminSpace {
                 ext_info: SubscriberInfo::default(),
             }),
         });
+
+        primitives.send_declare(Declare {
+            ext_qos: ext::QoSType::declare_default(),
+            ext_tstamp: None,
+            ext_nodeid: ext::NodeIdType::default(),
+            body: DeclareBody::DeclareSubscriber(DeclareSubscriber {
+                id: 1, // @TODO use proper SubscriberId (#703)
+                wire_expr: [&root_key, "/tuning/**"].concat().into(),
+                ext_info: SubscriberInfo::default(),
+            }),
+        });
     }
 
     pub fn key_expr_to_string<'a>(&self, key_expr: &'a WireExpr) -> ZResult<KeyExpr<'a>> {
@@ -409,6 +453,22 @@ impl Primitives for AdminSpace {
                     }
                 }
             }
+        } else if let Some(name) = msg.wire_expr.as_str().strip_prefix(&format!(
+            "@/{}/{}/tuning/",
+            self.context.runtime.state.whatami, self.context.runtime.state.zid
+        )) {
+            match msg.payload {
+                PushBody::Put(put) => match std::str::from_utf8(&put.payload.contiguous()) {
+                    // The value may be sent as a JSON string
+                    Ok(value) => match zenoh_core::configurable::set(name, value.trim_matches('"'))
+                    {
+                        Ok(()) => tracing::info!("Set {} to {}", name, value),
+                        Err(e) => error!("Error setting {} to {} : {}", name, value, e),
+                    },
+                    Err(e) => error!("Received non utf8 value for {} : {}", name, e),
+                },
+                PushBody::Del(_) => error!("{} can not be deleted", msg.wire_expr),
+            }
         }
     }
 
@@ -459,7 +519,9 @@ impl Primitives for AdminSpace {
                     primitives,
                     #[cfg(feature = "unstable")]
                     attachment: query.ext_attachment.map(Into::into),
+                    deadline: msg.ext_timeout.map(|t| Instant::now() + t),
                 }),
+                eid: 0,
             };
 
             for (key, handler) in &self.handlers {
@@ -617,7 +679,6 @@ fn metrics(context: &AdminContext, query: Query) {
     )
     .try_into()
     .unwrap();
-    #[allow(unused_mut)]
     let mut metrics = format!(
         r#"# HELP zenoh_build Informations about zenoh.
 # TYPE zenoh_build gauge
@@ -625,6 +686,13 @@ zenoh_build{{version="{}"}} 1
 "#,
         context.version
     );
+    metrics.push_str(&format!(
+        r#"# HELP zenoh_routing_purged_declarations_total Stale declarations purged from the routing tables.
+# TYPE zenoh_routing_purged_declarations_total counter
+zenoh_routing_purged_declarations_total {}
+"#,
+        zread!(context.runtime.state.router.tables.tables).purged_declarations
+    ));
 
     #[cfg(feature = "stats")]
     metrics.push_str(
@@ -647,6 +715,27 @@ zenoh_build{{version="{}"}} 1
     }
 }
 
+fn scouting_data(context: &AdminContext, query: Query) {
+    let reply_key: OwnedKeyExpr = format!(
+        "@/{}/{}/scouting",
+        context.runtime.state.whatami, context.runtime.state.zid
+    )
+    .try_into()
+    .unwrap();
+
+    let json = zlock!(context.runtime.state.scouting_report).to_json();
+    if let Err(e) = query
+        .reply(Ok(Sample::new(
+            reply_key,
+            Value::from(json.to_string().as_bytes().to_vec())
+                .encoding(KnownEncoding::AppJson.into()),
+        )))
+        .res()
+    {
+        tracing::error!("Error sending AdminSpace reply: {:?}", e);
+    }
+}
+
 fn routers_linkstate_data(context: &AdminContext, query: Query) {
     let reply_key: OwnedKeyExpr = format!(
         "@/{}/{}/linkstate/routers",
@@ -755,6 +844,67 @@ fn queryables_data(context: &AdminContext, query: Query) {
     }
 }
 
+fn queryable_conflicts_data(context: &AdminContext, query: Query) {
+    // The conflicts are grouped by the key expression of the queryable they were detected on
+    let mut conflicts: HashMap<String, Vec<serde_json::Value>> = HashMap::new();
+    for conflict in zread!(context.runtime.state.router.tables.tables)
+        .queryable_conflicts
+        .iter()
+    {
+        conflicts
+            .entry(conflict.key_expr.clone())
+            .or_default()
+            .push(conflict.to_json());
+    }
+    for (key_expr, conflicts) in conflicts {
+        let key = KeyExpr::try_from(format!(
+            "@/{}/{}/queryable_conflict/{}",
+            context.runtime.state.whatami, context.runtime.state.zid, key_expr
+        ))
+        .unwrap();
+        if query.key_expr().intersects(&key) {
+            if let Err(e) = query
+                .reply(Ok(Sample::new(
+                    key,
+                    Value::from(
+                        serde_json::Value::Array(conflicts)
+                            .to_string()
+                            .as_bytes()
+                            .to_vec(),
+                    )
+                    .encoding(KnownEncoding::AppJson.into()),
+                )))
+                .res()
+            {
+                tracing::error!("Error sending AdminSpace reply: {:?}", e);
+            }
+        }
+    }
+}
+
+fn tuning_data(context: &AdminContext, query: Query) {
+    for info in zenoh_core::configurable::list() {
+        let key = KeyExpr::try_from(format!(
+            "@/{}/{}/tuning/{}",
+            context.runtime.state.whatami, context.runtime.state.zid, info.name
+        ))
+        .unwrap();
+        if query.key_expr().intersects(&key) {
+            let value = json!({ "value": info.value });
+            if let Err(e) = query
+                .reply(Ok(Sample::new(
+                    key,
+                    Value::from(value.to_string().as_bytes().to_vec())
+                        .encoding(KnownEncoding::AppJson.into()),
+                )))
+                .res()
+            {
+                tracing::error!("Error sending AdminSpace reply: {:?}", e);
+            }
+        }
+    }
+}
+
 #[cfg(all(feature = "unstable", feature = "plugins"))]
 fn plugins_data(context: &AdminContext, query: Query) {
     let guard = context.runtime.plugins_manager();
@@ -764,14 +914,21 @@ fn plugins_data(context: &AdminContext, query: Query) {
     );
     let root_key = unsafe { keyexpr::from_str_unchecked(&root_key) };
     tracing::debug!("requested plugins status {:?}", query.key_expr());
-    if let [names, ..] = query.key_expr().strip_prefix(root_key)[..] {
-        let statuses = guard.plugins_status(names);
-        for status in statuses {
-            tracing::debug!("plugin status: {:?}", status);
-            let key = root_key.join(status.name()).unwrap();
-            let status = serde_json::to_value(status).unwrap();
-            if let Err(e) = query.reply(Ok(Sample::new(key, Value::from(status)))).res() {
-                tracing::error!("Error sending AdminSpace reply: {:?}", e);
+    // The status of every plugin is replied both on its key and, in the uniform schema used
+    // for monitoring, on its `status` key
+    for status in guard.plugins_status(unsafe { keyexpr::from_str_unchecked("**") }) {
+        tracing::debug!("plugin status: {:?}", status);
+        let key = root_key.join(status.name()).unwrap();
+        let status_key = key.join("status").unwrap();
+        let status = serde_json::to_value(status).unwrap();
+        for key in [key, status_key] {
+            if query.key_expr().intersects(&key) {
+                if let Err(e) = query
+                    .reply(Ok(Sample::new(key, Value::from(status.clone()))))
+                    .res()
+                {
+                    tracing::error!("Error sending AdminSpace reply: {:?}", e);
+                }
             }
         }
     }
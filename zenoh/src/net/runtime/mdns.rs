@@ -0,0 +1,366 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Scouting over mDNS: each instance registers a `_zenoh._udp` DNS-SD service whose TXT
+//! record carries its [`Hello`] and its capabilities, and browses the services of the other
+//! instances.
+use super::orchestrator::{compatibility, Compatibility, SCOUT_PREFERRED_PERIOD};
+use super::Runtime;
+use mdns_sd::{Receiver, ServiceDaemon, ServiceEvent, ServiceInfo};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
+use zenoh_core::zlock;
+use zenoh_link::Locator;
+use zenoh_protocol::{
+    core::{whatami::WhatAmIMatcher, WhatAmI, ZenohId},
+    scouting::{hello, Hello},
+};
+use zenoh_result::{bail, zerror, ZResult};
+
+const SERVICE_TYPE: &str = "_zenoh._udp.local.";
+const TXT_VERSION: &str = "version";
+const TXT_ZID: &str = "zid";
+const TXT_WHATAMI: &str = "whatami";
+const TXT_SHM: &str = "shm";
+const TXT_COMPRESSION: &str = "compression";
+const TXT_VERSIONS: &str = "versions";
+// The locators are stored in separate entries (locator0, locator1, ...) as
+// a TXT entry is limited to 255 bytes
+const TXT_LOCATOR: &str = "locator";
+const TXT_ENTRY_MAX_LEN: usize = u8::MAX as usize;
+// The port of the SRV record when no locator has one
+const SERVICE_DEFAULT_PORT: u16 = 7446;
+
+// Shuts the mDNS daemon down when the scouting task is aborted.
+struct MdnsDaemon(ServiceDaemon);
+
+impl Drop for MdnsDaemon {
+    fn drop(&mut self) {
+        let _ = self.0.shutdown();
+    }
+}
+
+impl MdnsDaemon {
+    fn new() -> ZResult<Self> {
+        ServiceDaemon::new()
+            .map(MdnsDaemon)
+            .map_err(|e| zerror!("Unable to start mDNS daemon: {}", e).into())
+    }
+
+    fn browse(&self) -> ZResult<Receiver<ServiceEvent>> {
+        self.0
+            .browse(SERVICE_TYPE)
+            .map_err(|e| zerror!("Unable to browse {} services: {}", SERVICE_TYPE, e).into())
+    }
+}
+
+fn service_info(hello: &Hello, capabilities: &hello::ext::Capabilities) -> ZResult<ServiceInfo> {
+    let mut properties = HashMap::new();
+    properties.insert(TXT_VERSION.to_string(), hello.version.to_string());
+    properties.insert(TXT_ZID.to_string(), hello.zid.to_string());
+    properties.insert(TXT_WHATAMI.to_string(), hello.whatami.to_string());
+    if capabilities.shm {
+        properties.insert(TXT_SHM.to_string(), "1".to_string());
+    }
+    if capabilities.compression != 0 {
+        let compression = capabilities.compression.to_string();
+        properties.insert(TXT_COMPRESSION.to_string(), compression);
+    }
+    let versions: Vec<String> = capabilities.versions.iter().map(u8::to_string).collect();
+    properties.insert(TXT_VERSIONS.to_string(), versions.join(","));
+    for (i, locator) in hello.locators.iter().enumerate() {
+        let key = format!("{TXT_LOCATOR}{i}");
+        let value = locator.to_string();
+        if key.len() + value.len() + 1 > TXT_ENTRY_MAX_LEN {
+            tracing::warn!("Locator {} too long to be advertised over mDNS", locator);
+            continue;
+        }
+        properties.insert(key, value);
+    }
+    let port = hello
+        .locators
+        .iter()
+        .find_map(|l| SocketAddr::from_str(l.address().as_str()).ok())
+        .map_or(SERVICE_DEFAULT_PORT, |addr| addr.port());
+    let instance = hello.zid.to_string();
+    let host = format!("{instance}.local.");
+    ServiceInfo::new(SERVICE_TYPE, &instance, &host, "", port, properties)
+        .map(ServiceInfo::enable_addr_auto)
+        .map_err(|e| zerror!("Invalid mDNS service for {}: {}", instance, e).into())
+}
+
+// The Hello of the service, and its capabilities unless advertised by an older instance
+fn hello(info: &ServiceInfo) -> Option<(Hello, Option<hello::ext::Capabilities>)> {
+    let version = info
+        .get_property_val_str(TXT_VERSION)
+        .and_then(|v| u8::from_str(v).ok())?;
+    let zid = info
+        .get_property_val_str(TXT_ZID)
+        .and_then(|zid| ZenohId::from_str(zid).ok())?;
+    let whatami = info
+        .get_property_val_str(TXT_WHATAMI)
+        .and_then(|w| WhatAmI::from_str(w).ok())?;
+    let mut locators: Vec<(usize, Locator)> = info
+        .get_properties()
+        .iter()
+        .filter_map(|p| {
+            let i = usize::from_str(p.key().strip_prefix(TXT_LOCATOR)?).ok()?;
+            match Locator::from_str(p.val_str()) {
+                Ok(locator) => Some((i, locator)),
+                Err(e) => {
+                    tracing::trace!("Invalid locator {} in mDNS service: {}", p.val_str(), e);
+                    None
+                }
+            }
+        })
+        .collect();
+    locators.sort_by_key(|(i, _)| *i);
+    let capabilities =
+        info.get_property_val_str(TXT_VERSIONS)
+            .map(|versions| hello::ext::Capabilities {
+                shm: info.get_property_val_str(TXT_SHM) == Some("1"),
+                compression: info
+                    .get_property_val_str(TXT_COMPRESSION)
+                    .and_then(|c| u64::from_str(c).ok())
+                    .unwrap_or(0),
+                versions: versions
+                    .split(',')
+                    .filter_map(|v| u8::from_str(v).ok())
+                    .collect(),
+            });
+    let hello = Hello {
+        version,
+        whatami,
+        zid,
+        locators: locators.into_iter().map(|(_, l)| l).collect(),
+    };
+    Some((hello, capabilities))
+}
+
+// The Hello and the capabilities of the resolved services of the other instances.
+async fn next_hello(
+    receiver: &Receiver<ServiceEvent>,
+    what: WhatAmIMatcher,
+    zid: &ZenohId,
+) -> ZResult<(Hello, Option<hello::ext::Capabilities>)> {
+    loop {
+        match receiver.recv_async().await {
+            Ok(ServiceEvent::ServiceResolved(info)) => match hello(&info) {
+                Some((hello, capabilities)) if &hello.zid != zid && what.matches(hello.whatami) => {
+                    tracing::trace!("Received {:?} with {:?} over mDNS", hello, capabilities);
+                    return Ok((hello, capabilities));
+                }
+                Some(_) => {}
+                None => tracing::trace!("Invalid mDNS service {}", info.get_fullname()),
+            },
+            Ok(_) => {}
+            Err(e) => bail!("mDNS browsing stopped: {}", e),
+        }
+    }
+}
+
+impl Runtime {
+    /// Registers the `_zenoh._udp` service of this instance if `listen`, and connects
+    /// to the instances matching `autoconnect` discovered by browsing the services.
+    pub(super) async fn start_mdns_scout(
+        &self,
+        listen: bool,
+        autoconnect: WhatAmIMatcher,
+    ) -> ZResult<()> {
+        if !listen && autoconnect.is_empty() {
+            return Ok(());
+        }
+        let daemon = MdnsDaemon::new()?;
+        if listen {
+            let info = service_info(&self.hello(), &self.capabilities())?;
+            tracing::debug!("Register mDNS service {}", info.get_fullname());
+            daemon
+                .0
+                .register(info)
+                .map_err(|e| zerror!("Unable to register mDNS service: {}", e))?;
+        }
+        let receiver = match autoconnect.is_empty() {
+            true => None,
+            false => Some(daemon.browse()?),
+        };
+        let this = self.clone();
+        self.spawn_abortable(async move {
+            let _daemon = daemon;
+            match receiver {
+                Some(receiver) => this.mdns_connect_all(&receiver, autoconnect).await,
+                None => futures::future::pending().await,
+            }
+        });
+        Ok(())
+    }
+
+    async fn mdns_connect_all(&self, receiver: &Receiver<ServiceEvent>, what: WhatAmIMatcher) {
+        let zid = self.manager().zid();
+        let local = self.capabilities();
+        loop {
+            let hello = match next_hello(receiver, what, &zid).await {
+                Ok((hello, capabilities)) => {
+                    if compatibility(&local, capabilities.as_ref()) == Compatibility::Incompatible {
+                        tracing::debug!("Ignore incompatible {}: {:?}", hello.zid, capabilities);
+                        continue;
+                    }
+                    hello
+                }
+                Err(e) => {
+                    tracing::warn!("{}", e);
+                    return;
+                }
+            };
+            zlock!(self.state.scouting_report).hello(
+                &hello.zid,
+                &zid,
+                &self.get_locators(),
+                &hello.locators,
+            );
+            if !hello.locators.is_empty() {
+                self.connect_peer(&hello.zid, &hello.locators).await
+            } else {
+                tracing::warn!("Received Hello with no locators over mDNS: {:?}", hello);
+            }
+        }
+    }
+
+    /// Connects to the first instance matching `what` discovered over mDNS within `timeout`,
+    /// preferring the instances with the capabilities of this runtime for a while.
+    pub(super) async fn mdns_connect_first(
+        &self,
+        what: WhatAmIMatcher,
+        timeout: Duration,
+    ) -> ZResult<()> {
+        let daemon = MdnsDaemon::new()?;
+        let receiver = daemon.browse()?;
+        let zid = self.manager().zid();
+        let local = self.capabilities();
+        let deadline = tokio::time::Instant::now() + SCOUT_PREFERRED_PERIOD.min(timeout / 2);
+        let scout = async {
+            // The services are only resolved once, the ones lacking capabilities are kept
+            // until the end of the preferred period
+            let mut deferred = vec![];
+            loop {
+                let next = match deferred.is_empty() {
+                    true => Some(next_hello(&receiver, what, &zid).await?),
+                    false => tokio::time::timeout_at(deadline, next_hello(&receiver, what, &zid))
+                        .await
+                        .ok()
+                        .transpose()?,
+                };
+                let hellos = match next {
+                    Some((hello, capabilities)) => {
+                        tracing::info!("Found {:?} with {:?} over mDNS", hello, capabilities);
+                        match compatibility(&local, capabilities.as_ref()) {
+                            Compatibility::Incompatible => continue,
+                            Compatibility::Degraded if tokio::time::Instant::now() < deadline => {
+                                deferred.push(hello);
+                                continue;
+                            }
+                            _ => vec![hello],
+                        }
+                    }
+                    None => std::mem::take(&mut deferred),
+                };
+                for hello in hellos {
+                    if !hello.locators.is_empty() {
+                        if self.connect(&hello.zid, &hello.locators).await {
+                            return Ok(());
+                        }
+                    } else {
+                        tracing::warn!("Received Hello with no locators over mDNS: {:?}", hello);
+                    }
+                }
+            }
+        };
+        tokio::time::timeout(timeout, scout)
+            .await
+            .map_err(|_| zerror!("timeout"))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mdns_txt_roundtrip() {
+        let sent = Hello {
+            version: 8,
+            whatami: WhatAmI::Router,
+            zid: ZenohId::from_str("a1b2c3").unwrap(),
+            locators: (0..12)
+                .map(|i| format!("tcp/192.168.1.{i}:7447").parse().unwrap())
+                .collect(),
+        };
+        let capabilities = hello::ext::Capabilities {
+            shm: true,
+            compression: 0b101,
+            versions: vec![8, 9],
+        };
+        let info = service_info(&sent, &capabilities).unwrap();
+        assert_eq!(info.get_port(), 7447);
+        assert_eq!(
+            hello(&info),
+            Some((sent.clone(), Some(capabilities.clone())))
+        );
+
+        // The locators too long for a TXT entry are skipped
+        let mut long = sent.clone();
+        long.locators.insert(
+            1,
+            format!("tcp/{}.com:7447", "a".repeat(250)).parse().unwrap(),
+        );
+        assert_eq!(
+            hello(&service_info(&long, &capabilities).unwrap()),
+            Some((sent, Some(capabilities)))
+        );
+
+        // The default capabilities are advertised too
+        let sent = Hello {
+            version: 8,
+            whatami: WhatAmI::Peer,
+            zid: ZenohId::from_str("d4e5f6").unwrap(),
+            locators: vec![],
+        };
+        let info = service_info(&sent, &Default::default()).unwrap();
+        assert_eq!(info.get_port(), SERVICE_DEFAULT_PORT);
+        let (received, capabilities) = hello(&info).unwrap();
+        assert_eq!(capabilities, Some(Default::default()));
+        assert_eq!(received, sent);
+
+        // The older instances do not advertise their capabilities
+        let properties: HashMap<String, String> = [
+            (TXT_VERSION, "8"),
+            (TXT_ZID, "d4e5f6"),
+            (TXT_WHATAMI, "peer"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let info = ServiceInfo::new(
+            SERVICE_TYPE,
+            "d4e5f6",
+            "d4e5f6.local.",
+            "",
+            SERVICE_DEFAULT_PORT,
+            properties,
+        )
+        .unwrap();
+        assert_eq!(hello(&info), Some((sent, None)));
+    }
+}
@@ -18,7 +18,10 @@
 //!
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
 mod adminspace;
+#[cfg(feature = "scouting_mdns")]
+mod mdns;
 pub mod orchestrator;
+mod scouting_report;
 
 use super::primitives::DeMux;
 use super::routing;
@@ -30,6 +33,7 @@ use crate::{GIT_VERSION, LONG_VERSION};
 pub use adminspace::AdminSpace;
 use futures::stream::StreamExt;
 use futures::Future;
+use scouting_report::ScoutingReport;
 use std::any::Any;
 use std::sync::{Arc, Weak};
 #[cfg(all(feature = "unstable", feature = "plugins"))]
@@ -37,7 +41,7 @@ use std::sync::{Mutex, MutexGuard};
 use std::time::Duration;
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
-use uhlc::{HLCBuilder, HLC};
+use uhlc::{HLCBuilder, HLC, NTP64};
 use zenoh_link::{EndPoint, Link};
 use zenoh_plugin_trait::{PluginStartArgs, StructVersion};
 use zenoh_protocol::core::{Locator, WhatAmI, ZenohId};
@@ -46,8 +50,9 @@ use zenoh_result::{bail, ZResult};
 use zenoh_sync::get_mut_unchecked;
 use zenoh_task::TaskController;
 use zenoh_transport::{
-    multicast::TransportMulticast, unicast::TransportUnicast, TransportEventHandler,
-    TransportManager, TransportMulticastEventHandler, TransportPeer, TransportPeerEventHandler,
+    common::budget::MemoryBudgetEvent, multicast::TransportMulticast, unicast::TransportUnicast,
+    TransportEventHandler, TransportManager, TransportMulticastEventHandler, TransportPeer,
+    TransportPeerEventHandler,
 };
 
 pub(crate) struct RuntimeState {
@@ -59,7 +64,10 @@ pub(crate) struct RuntimeState {
     manager: TransportManager,
     transport_handlers: std::sync::RwLock<Vec<Arc<dyn TransportEventHandler>>>,
     locators: std::sync::RwLock<Vec<Locator>>,
+    scouting_report: std::sync::Mutex<ScoutingReport>,
     hlc: Option<Arc<HLC>>,
+    timestamp_bound: Option<NTP64>,
+    max_drift: Option<NTP64>,
     task_controller: TaskController,
     #[cfg(all(feature = "unstable", feature = "plugins"))]
     plugins_manager: Mutex<PluginsManager>,
@@ -104,6 +112,7 @@ impl RuntimeBuilder {
         } = self;
 
         tracing::debug!("Zenoh Rust API {}", GIT_VERSION);
+        register_configurables();
         let zid = *config.id();
         tracing::info!("Using ZID: {}", zid);
 
@@ -111,6 +120,14 @@ impl RuntimeBuilder {
         let metadata = config.metadata().clone();
         let hlc = (*unwrap_or_default!(config.timestamping().enabled().get(whatami)))
             .then(|| Arc::new(HLCBuilder::new().with_id(uhlc::ID::from(&zid)).build()));
+        let timestamp_bound = config
+            .timestamping()
+            .uncertainty()
+            .map(|ms| NTP64::from(Duration::from_millis(ms)));
+        let max_drift = config
+            .timestamping()
+            .max_drift()
+            .map(|ms| NTP64::from(Duration::from_millis(ms)));
 
         let router = Arc::new(Router::new(zid, whatami, hlc.clone(), &config)?);
 
@@ -132,6 +149,7 @@ impl RuntimeBuilder {
             .unwrap_or_else(|| crate::plugins::loader::load_plugins(&config));
         // Admin space creation flag
         let start_admin_space = *config.adminspace.enabled();
+        let sweep_interval = unwrap_or_default!(config.routing().sweep().interval());
 
         let config = Notifier::new(config);
         let runtime = Runtime {
@@ -144,7 +162,10 @@ impl RuntimeBuilder {
                 manager: transport_manager,
                 transport_handlers: std::sync::RwLock::new(vec![]),
                 locators: std::sync::RwLock::new(vec![]),
+                scouting_report: std::sync::Mutex::new(ScoutingReport::default()),
                 hlc,
+                timestamp_bound,
+                max_drift,
                 task_controller: TaskController::default(),
                 #[cfg(all(feature = "unstable", feature = "plugins"))]
                 plugins_manager: Mutex::new(plugins_manager),
@@ -160,7 +181,7 @@ impl RuntimeBuilder {
 
         // Start plugins
         #[cfg(all(feature = "unstable", feature = "plugins"))]
-        crate::plugins::loader::start_plugins(&runtime);
+        crate::plugins::loader::start_plugins(&runtime).await;
 
         // Start notifier task
         let receiver = config.subscribe();
@@ -189,10 +210,79 @@ impl RuntimeBuilder {
             }
         });
 
+        // Start congestion notification task: the memory budget events are raised on the
+        // transmission path, the sessions and neighbours are notified from this task, and notified
+        // again while the congestion lasts
+        let (congestion_tx, congestion_rx) = flume::unbounded();
+        runtime.manager().memory_budget().on_event(move |event| {
+            let _ = congestion_tx.send(matches!(event, MemoryBudgetEvent::Exceeded { .. }));
+        });
+        let tables = Arc::downgrade(&runtime.state.router.tables);
+        runtime.spawn_abortable(async move {
+            let mut congested = false;
+            loop {
+                match tokio::time::timeout(
+                    routing::dispatcher::pubsub::CONGESTION_REFRESH,
+                    congestion_rx.recv_async(),
+                )
+                .await
+                {
+                    Ok(Ok(event)) => congested = event,
+                    Ok(Err(_)) => break,
+                    Err(_) if congested => (),
+                    Err(_) => continue,
+                }
+                match tables.upgrade() {
+                    Some(tables) => {
+                        routing::dispatcher::pubsub::notify_congestion(&tables, congested)
+                    }
+                    None => break,
+                }
+            }
+        });
+
+        // Start routing tables sweep task
+        if sweep_interval > 0 {
+            let tables = Arc::downgrade(&runtime.state.router.tables);
+            let token = runtime.get_cancellation_token();
+            runtime.spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_millis(sweep_interval));
+                interval.tick().await;
+                loop {
+                    tokio::select! {
+                        _ = interval.tick() => {
+                            match tables.upgrade() {
+                                Some(tables) => { routing::dispatcher::tables::sweep(&tables); },
+                                None => { break; }
+                            }
+                        }
+                        _ = token.cancelled() => { break; }
+                    }
+                }
+            });
+        }
+
         Ok(runtime)
     }
 }
 
+// Makes the values that can be modified at runtime known to the admin space before their first use
+fn register_configurables() {
+    use zenoh_core::configurable::register;
+    zenoh_util::register_configurables();
+    zenoh_link::register_configurables();
+    register(&routing::hat::TREES_COMPUTATION_DELAY_MS);
+    register(&routing::hat::LINK_WEIGHTS_REFRESH_MS);
+    register(&crate::session::API_DATA_RECEPTION_CHANNEL_SIZE);
+    register(&crate::session::API_QUERY_RECEPTION_CHANNEL_SIZE);
+    register(&crate::session::API_REPLY_EMISSION_CHANNEL_SIZE);
+    register(&crate::session::API_REPLY_RECEPTION_CHANNEL_SIZE);
+    register(&crate::session::API_REPLY_ORDERING_MAX_SIZE);
+    register(&crate::session::API_OPEN_SESSION_DELAY);
+    #[cfg(all(feature = "unstable", feature = "plugins"))]
+    crate::plugins::loader::register_configurables();
+}
+
 #[derive(Clone)]
 pub struct Runtime {
     state: Arc<RuntimeState>,
@@ -254,6 +344,16 @@ impl Runtime {
         self.state.hlc.as_ref().map(|hlc| hlc.new_timestamp())
     }
 
+    /// The uncertainty bound advertised with the timestamps generated by [`new_timestamp`](Self::new_timestamp).
+    pub(crate) fn timestamp_bound(&self) -> Option<NTP64> {
+        self.state.hlc.as_ref().and(self.state.timestamp_bound)
+    }
+
+    /// The maximum drift tolerated for the timestamps of the received samples.
+    pub(crate) fn max_drift(&self) -> Option<NTP64> {
+        self.state.max_drift
+    }
+
     pub fn get_locators(&self) -> Vec<Locator> {
         self.state.locators.read().unwrap().clone()
     }
@@ -399,6 +499,12 @@ impl TransportPeerEventHandler for RuntimeSession {
         }
     }
 
+    fn close_reason(&self, reason: u8) {
+        for handler in &self.slave_handlers {
+            handler.close_reason(reason);
+        }
+    }
+
     fn closing(&self) {
         self.main_handler.closing();
         Runtime::closing_session(self);
@@ -483,6 +589,12 @@ impl TransportPeerEventHandler for RuntimeMuticastSession {
         }
     }
 
+    fn close_reason(&self, reason: u8) {
+        for handler in &self.slave_handlers {
+            handler.close_reason(reason);
+        }
+    }
+
     fn closing(&self) {
         self.main_handler.closing();
         for handler in &self.slave_handlers {
@@ -15,18 +15,18 @@ use super::{Runtime, RuntimeSession};
 use futures::prelude::*;
 use socket2::{Domain, Socket, Type};
 use std::net::{IpAddr, Ipv6Addr, SocketAddr};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::net::UdpSocket;
 use zenoh_buffers::reader::DidntRead;
 use zenoh_buffers::{reader::HasReader, writer::HasWriter};
-use zenoh_codec::{RCodec, WCodec, Zenoh080};
+use zenoh_codec::{scouting::HelloRef, RCodec, WCodec, Zenoh080};
 use zenoh_config::{
     get_global_connect_timeout, get_global_listener_timeout, unwrap_or_default, ModeDependent,
 };
-use zenoh_link::{Locator, LocatorInspector};
+use zenoh_link::{get_bind_interfaces, Locator, LocatorInspector, BIND_INTERFACE};
 use zenoh_protocol::{
     core::{whatami::WhatAmIMatcher, EndPoint, WhatAmI, ZenohId},
-    scouting::{Hello, Scout, ScoutingBody, ScoutingMessage},
+    scouting::{hello, Hello, Scout, ScoutingBody, ScoutingMessage},
 };
 use zenoh_result::{bail, zerror, ZResult};
 
@@ -34,14 +34,55 @@ const RCV_BUF_SIZE: usize = u16::MAX as usize;
 const SCOUT_INITIAL_PERIOD: Duration = Duration::from_millis(1_000);
 const SCOUT_MAX_PERIOD: Duration = Duration::from_millis(8_000);
 const SCOUT_PERIOD_INCREASE_FACTOR: u32 = 2;
+// The period during which connect_first only connects to the nodes with the preferred
+// capabilities, the others replying again to the next scout messages
+pub(super) const SCOUT_PREFERRED_PERIOD: Duration = Duration::from_millis(1_500);
 const ROUTER_DEFAULT_LISTENER: &str = "tcp/[::]:7447";
 const PEER_DEFAULT_LISTENER: &str = "tcp/[::]:0";
+const INTERFACES_REFRESH_PERIOD: Duration = Duration::from_secs(5);
 
 pub enum Loop {
     Continue,
     Break,
 }
 
+// How well a scouted node matches the capabilities of this runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Compatibility {
+    // The node does not speak the protocol version of this runtime
+    Incompatible,
+    // The node lacks the shared memory or the compression enabled on this runtime
+    Degraded,
+    Full,
+}
+
+pub(super) fn compatibility(
+    local: &hello::ext::Capabilities,
+    remote: Option<&hello::ext::Capabilities>,
+) -> Compatibility {
+    // The older nodes do not advertise their capabilities
+    let Some(remote) = remote else {
+        return match local.shm || local.compression != 0 {
+            true => Compatibility::Degraded,
+            false => Compatibility::Full,
+        };
+    };
+    if !remote.versions.is_empty()
+        && !local
+            .versions
+            .iter()
+            .any(|version| remote.versions.contains(version))
+    {
+        return Compatibility::Incompatible;
+    }
+    if (local.shm && !remote.shm)
+        || (local.compression != 0 && local.compression & remote.compression == 0)
+    {
+        return Compatibility::Degraded;
+    }
+    Compatibility::Full
+}
+
 impl Runtime {
     pub async fn start(&mut self) -> ZResult<()> {
         match self.whatami() {
@@ -52,45 +93,87 @@ impl Runtime {
     }
 
     async fn start_client(&self) -> ZResult<()> {
-        let (peers, scouting, addr, ifaces, timeout) = {
+        let (peers, scouting, mdns, addr, ifaces, ttl, timeout) = {
             let guard = self.state.config.lock();
             (
                 guard.connect().endpoints().clone(),
                 unwrap_or_default!(guard.scouting().multicast().enabled()),
+                unwrap_or_default!(guard.scouting().mdns().enabled()),
                 unwrap_or_default!(guard.scouting().multicast().address()),
                 unwrap_or_default!(guard.scouting().multicast().interface()),
+                unwrap_or_default!(guard.scouting().multicast().ttl()),
                 std::time::Duration::from_millis(unwrap_or_default!(guard.scouting().timeout())),
             )
         };
         match peers.len() {
             0 => {
+                let mut scouts = vec![];
                 if scouting {
-                    tracing::info!("Scouting for router ...");
-                    let ifaces = Runtime::get_interfaces(&ifaces);
-                    if ifaces.is_empty() {
-                        bail!("Unable to find multicast interface!")
-                    } else {
-                        let sockets: Vec<UdpSocket> = ifaces
-                            .into_iter()
-                            .filter_map(|iface| Runtime::bind_ucast_port(iface).ok())
-                            .collect();
-                        if sockets.is_empty() {
-                            bail!("Unable to bind UDP port to any multicast interface!")
-                        } else {
-                            self.connect_first(&sockets, WhatAmI::Router.into(), &addr, timeout)
-                                .await
-                        }
-                    }
-                } else {
-                    bail!("No peer specified and multicast scouting desactivated!")
+                    scouts.push(
+                        self.multicast_connect_first(addr, ifaces, ttl, timeout)
+                            .boxed(),
+                    );
+                }
+                if mdns {
+                    #[cfg(feature = "scouting_mdns")]
+                    scouts.push(
+                        self.mdns_connect_first(WhatAmI::Router.into(), timeout)
+                            .boxed(),
+                    );
+                    #[cfg(not(feature = "scouting_mdns"))]
+                    tracing::warn!(
+                        "mDNS scouting requires zenoh to be built with the scouting_mdns feature"
+                    );
+                }
+                if scouts.is_empty() {
+                    bail!("No peer specified and multicast and mDNS scouting desactivated!")
                 }
+                tracing::info!("Scouting for router ...");
+                // Connect to the first router found by any of the scouting mechanisms
+                future::select_ok(scouts).await.map(|_| ())
             }
             _ => self.connect_peers(&peers, true).await,
         }
     }
 
+    async fn multicast_connect_first(
+        &self,
+        addr: SocketAddr,
+        ifaces: String,
+        ttl: u32,
+        timeout: std::time::Duration,
+    ) -> ZResult<()> {
+        let ifaces = Runtime::get_interfaces(&ifaces);
+        if ifaces.is_empty() {
+            bail!("Unable to find multicast interface!")
+        } else {
+            let sockets: Vec<UdpSocket> = ifaces
+                .into_iter()
+                .filter_map(|iface| Runtime::bind_ucast_port(iface, ttl).ok())
+                .collect();
+            if sockets.is_empty() {
+                bail!("Unable to bind UDP port to any multicast interface!")
+            } else {
+                self.connect_first(&sockets, WhatAmI::Router.into(), &addr, timeout)
+                    .await
+            }
+        }
+    }
+
     async fn start_peer(&self) -> ZResult<()> {
-        let (listeners, peers, scouting, listen, autoconnect, addr, ifaces, delay) = {
+        let (
+            listeners,
+            interfaces,
+            peers,
+            scouting,
+            listen,
+            autoconnect,
+            addr,
+            ifaces,
+            ttl,
+            mdns,
+            delay,
+        ) = {
             let guard = &self.state.config.lock();
             let listeners = if guard.listen().endpoints().is_empty() {
                 let endpoint: EndPoint = PEER_DEFAULT_LISTENER.parse().unwrap();
@@ -112,29 +195,49 @@ impl Runtime {
             };
             (
                 listeners,
+                guard.listen().interfaces().clone(),
                 guard.connect().endpoints().clone(),
                 unwrap_or_default!(guard.scouting().multicast().enabled()),
                 *unwrap_or_default!(guard.scouting().multicast().listen().peer()),
                 *unwrap_or_default!(guard.scouting().multicast().autoconnect().peer()),
                 unwrap_or_default!(guard.scouting().multicast().address()),
                 unwrap_or_default!(guard.scouting().multicast().interface()),
+                unwrap_or_default!(guard.scouting().multicast().ttl()),
+                unwrap_or_default!(guard.scouting().mdns().enabled()).then(|| {
+                    (
+                        *unwrap_or_default!(guard.scouting().mdns().listen().peer()),
+                        *unwrap_or_default!(guard.scouting().mdns().autoconnect().peer()),
+                    )
+                }),
                 Duration::from_millis(unwrap_or_default!(guard.scouting().delay())),
             )
         };
 
-        self.bind_listeners(&listeners).await?;
+        self.bind_listeners_on_interfaces(listeners, interfaces)
+            .await?;
 
         self.connect_peers(&peers, false).await?;
 
         if scouting {
-            self.start_scout(listen, autoconnect, addr, ifaces).await?;
+            self.start_scout(listen, autoconnect, addr, ifaces, ttl)
+                .await?;
+        }
+        #[cfg(feature = "scouting_mdns")]
+        if let Some((listen, autoconnect)) = mdns {
+            self.start_mdns_scout(listen, autoconnect).await?;
+        }
+        #[cfg(not(feature = "scouting_mdns"))]
+        if mdns.is_some() {
+            tracing::warn!(
+                "mDNS scouting requires zenoh to be built with the scouting_mdns feature"
+            );
         }
         tokio::time::sleep(delay).await;
         Ok(())
     }
 
     async fn start_router(&self) -> ZResult<()> {
-        let (listeners, peers, scouting, listen, autoconnect, addr, ifaces) = {
+        let (listeners, interfaces, peers, scouting, listen, autoconnect, addr, ifaces, ttl, mdns) = {
             let guard = self.state.config.lock();
             let listeners = if guard.listen().endpoints().is_empty() {
                 let endpoint: EndPoint = ROUTER_DEFAULT_LISTENER.parse().unwrap();
@@ -156,21 +259,41 @@ impl Runtime {
             };
             (
                 listeners,
+                guard.listen().interfaces().clone(),
                 guard.connect().endpoints().clone(),
                 unwrap_or_default!(guard.scouting().multicast().enabled()),
                 *unwrap_or_default!(guard.scouting().multicast().listen().router()),
                 *unwrap_or_default!(guard.scouting().multicast().autoconnect().router()),
                 unwrap_or_default!(guard.scouting().multicast().address()),
                 unwrap_or_default!(guard.scouting().multicast().interface()),
+                unwrap_or_default!(guard.scouting().multicast().ttl()),
+                unwrap_or_default!(guard.scouting().mdns().enabled()).then(|| {
+                    (
+                        *unwrap_or_default!(guard.scouting().mdns().listen().router()),
+                        *unwrap_or_default!(guard.scouting().mdns().autoconnect().router()),
+                    )
+                }),
             )
         };
 
-        self.bind_listeners(&listeners).await?;
+        self.bind_listeners_on_interfaces(listeners, interfaces)
+            .await?;
 
         self.connect_peers(&peers, false).await?;
 
         if scouting {
-            self.start_scout(listen, autoconnect, addr, ifaces).await?;
+            self.start_scout(listen, autoconnect, addr, ifaces, ttl)
+                .await?;
+        }
+        #[cfg(feature = "scouting_mdns")]
+        if let Some((listen, autoconnect)) = mdns {
+            self.start_mdns_scout(listen, autoconnect).await?;
+        }
+        #[cfg(not(feature = "scouting_mdns"))]
+        if mdns.is_some() {
+            tracing::warn!(
+                "mDNS scouting requires zenoh to be built with the scouting_mdns feature"
+            );
         }
 
         Ok(())
@@ -182,42 +305,72 @@ impl Runtime {
         autoconnect: WhatAmIMatcher,
         addr: SocketAddr,
         ifaces: String,
+        ttl: u32,
     ) -> ZResult<()> {
-        let ifaces = Runtime::get_interfaces(&ifaces);
-        let mcast_socket = Runtime::bind_mcast_port(&addr, &ifaces).await?;
-        if !ifaces.is_empty() {
-            let sockets: Vec<UdpSocket> = ifaces
-                .into_iter()
-                .filter_map(|iface| Runtime::bind_ucast_port(iface).ok())
-                .collect();
-            if !sockets.is_empty() {
-                let this = self.clone();
-                match (listen, autoconnect.is_empty()) {
-                    (true, false) => {
-                        self.spawn_abortable(async move {
-                            tokio::select! {
-                                _ = this.responder(&mcast_socket, &sockets) => {},
-                                _ = this.connect_all(&sockets, autoconnect, &addr) => {},
-                            }
-                        });
-                    }
-                    (true, true) => {
-                        self.spawn_abortable(async move {
-                            this.responder(&mcast_socket, &sockets).await;
-                        });
-                    }
-                    (false, false) => {
-                        self.spawn_abortable(async move {
-                            this.connect_all(&sockets, autoconnect, &addr).await
-                        });
+        let names = ifaces;
+        let mut ifaces = Runtime::get_interfaces(&names);
+        let mut mcast_socket = Runtime::bind_mcast_port(&addr, &ifaces).await?;
+        if !listen && autoconnect.is_empty() {
+            return Ok(());
+        }
+        let this = self.clone();
+        // Rebind the scouting sockets when the addresses of the interfaces change (e.g. on interfaces hotplug)
+        self.spawn_abortable(async move {
+            loop {
+                let sockets: Vec<UdpSocket> = ifaces
+                    .iter()
+                    .filter_map(|iface| Runtime::bind_ucast_port(*iface, ttl).ok())
+                    .collect();
+                tokio::select! {
+                    _ = this.scout_on(listen, autoconnect, &addr, &mcast_socket, &sockets),
+                        if !sockets.is_empty() => break,
+                    changed = Runtime::watch_interfaces(&names, &ifaces) => {
+                        tracing::info!("Scouting interfaces changed: {:?}", changed);
+                        drop(sockets);
+                        match Runtime::bind_mcast_port(&addr, &changed).await {
+                            Ok(socket) => mcast_socket = socket,
+                            Err(e) => tracing::warn!("Unable to rebind the scouting socket: {}", e),
+                        }
+                        ifaces = changed;
                     }
-                    _ => {}
                 }
             }
-        }
+        });
         Ok(())
     }
 
+    async fn scout_on(
+        &self,
+        listen: bool,
+        autoconnect: WhatAmIMatcher,
+        addr: &SocketAddr,
+        mcast_socket: &UdpSocket,
+        sockets: &[UdpSocket],
+    ) {
+        match (listen, autoconnect.is_empty()) {
+            (true, false) => {
+                tokio::select! {
+                    _ = self.responder(mcast_socket, sockets) => {},
+                    _ = self.connect_all(sockets, autoconnect, addr) => {},
+                }
+            }
+            (true, true) => self.responder(mcast_socket, sockets).await,
+            (false, false) => self.connect_all(sockets, autoconnect, addr).await,
+            (false, true) => {}
+        }
+    }
+
+    // Wait for the addresses of the selected interfaces to change
+    async fn watch_interfaces(names: &str, ifaces: &[IpAddr]) -> Vec<IpAddr> {
+        loop {
+            tokio::time::sleep(INTERFACES_REFRESH_PERIOD).await;
+            let current = Runtime::get_interfaces(names);
+            if current != ifaces {
+                return current;
+            }
+        }
+    }
+
     async fn connect_peers(&self, peers: &[EndPoint], single_link: bool) -> ZResult<()> {
         let timeout = self.get_global_connect_timeout();
         if timeout.is_zero() {
@@ -421,6 +574,169 @@ impl Runtime {
         }
     }
 
+    // Bind the listeners, the ones on an unspecified address on the addresses of the selected interfaces,
+    // and follow the changes of these addresses (e.g. on interfaces hotplug). The interfaces are selected
+    // by `listen.interfaces`, or on an endpoint by `iface`, e.g. `tcp/0.0.0.0:7447#iface=eth0|eth1`.
+    async fn bind_listeners_on_interfaces(
+        &self,
+        listeners: Vec<EndPoint>,
+        interfaces: Option<Vec<String>>,
+    ) -> ZResult<()> {
+        if interfaces.is_none() && !listeners.iter().any(Runtime::has_bind_interfaces) {
+            return self.bind_listeners(&listeners).await;
+        }
+        let resolved = Runtime::resolve_listeners(&listeners, interfaces.as_deref());
+        let before = self.manager().get_listeners().await;
+        self.bind_listeners(&resolved).await?;
+        let mut added: Vec<EndPoint> = self
+            .manager()
+            .get_listeners()
+            .await
+            .into_iter()
+            .filter(|l| !before.contains(l))
+            .collect();
+        // The listeners on port 0 are bound on an ephemeral port: keep the actual endpoints to close them.
+        // The endpoints on a given port are matched first so that they don't get claimed by the ones on port 0.
+        let (exact, ephemeral): (Vec<_>, Vec<_>) = resolved
+            .into_iter()
+            .partition(|e| Runtime::endpoint_port(e) != Some(0));
+        let mut bound: Vec<(EndPoint, EndPoint)> = exact
+            .into_iter()
+            .chain(ephemeral)
+            .map(|endpoint| {
+                let actual = Runtime::take_bound_listener(&endpoint, &mut added)
+                    .unwrap_or_else(|| endpoint.clone());
+                (endpoint, actual)
+            })
+            .collect();
+
+        let this = self.clone();
+        self.spawn_abortable(async move {
+            loop {
+                tokio::time::sleep(INTERFACES_REFRESH_PERIOD).await;
+                let resolved = Runtime::resolve_listeners(&listeners, interfaces.as_deref());
+                if resolved.len() == bound.len()
+                    && resolved.iter().all(|e| bound.iter().any(|(b, _)| b == e))
+                {
+                    continue;
+                }
+                let (kept, removed): (Vec<_>, Vec<_>) = bound
+                    .into_iter()
+                    .partition(|(endpoint, _)| resolved.contains(endpoint));
+                for (endpoint, actual) in removed {
+                    tracing::info!("Interface address removed: close listener {}", actual);
+                    if let Err(e) = this.manager().del_listener(&actual).await {
+                        tracing::warn!("Unable to close listener {}: {}", endpoint, e);
+                    }
+                }
+                bound = kept;
+                // The listeners that failed to open are retried at the next refresh
+                for endpoint in resolved {
+                    if bound.iter().any(|(b, _)| *b == endpoint) {
+                        continue;
+                    }
+                    tracing::info!("Interface address added: open listener {}", endpoint);
+                    if let Ok(locator) = this.add_listener(endpoint.clone()).await {
+                        bound.push((endpoint, locator.into()));
+                    }
+                }
+                this.print_locators();
+            }
+        });
+        Ok(())
+    }
+
+    fn endpoint_port(endpoint: &EndPoint) -> Option<u16> {
+        endpoint
+            .address()
+            .as_str()
+            .parse::<SocketAddr>()
+            .ok()
+            .map(|sockaddr| sockaddr.port())
+    }
+
+    // Take the listener bound for an endpoint, on any port if the endpoint is on port 0
+    fn take_bound_listener(endpoint: &EndPoint, listeners: &mut Vec<EndPoint>) -> Option<EndPoint> {
+        let sockaddr = endpoint.address().as_str().parse::<SocketAddr>().ok();
+        let pos = listeners.iter().position(|listener| {
+            listener.protocol() == endpoint.protocol()
+                && match (sockaddr, listener.address().as_str().parse::<SocketAddr>()) {
+                    (Some(a), Ok(b)) => a.ip() == b.ip() && (a.port() == 0 || a.port() == b.port()),
+                    _ => listener.address() == endpoint.address(),
+                }
+        })?;
+        Some(listeners.swap_remove(pos))
+    }
+
+    // Whether the interfaces selected on an endpoint are resolved into their addresses: a socket can only
+    // be bound to one interface, and only by the TCP and UDP links
+    fn has_bind_interfaces(endpoint: &EndPoint) -> bool {
+        match get_bind_interfaces(&endpoint.config()).len() {
+            0 => false,
+            1 => !matches!(endpoint.protocol().as_str(), "tcp" | "udp"),
+            _ => true,
+        }
+    }
+
+    // Replace the listeners on an unspecified address by listeners on the addresses of the selected interfaces:
+    // the ones of the endpoint if they are resolved, or else the given ones
+    fn resolve_listeners(listeners: &[EndPoint], interfaces: Option<&[String]>) -> Vec<EndPoint> {
+        let mut resolved = vec![];
+        for listener in listeners {
+            let sockaddr = match listener.address().as_str().parse::<SocketAddr>() {
+                Ok(sockaddr) if sockaddr.ip().is_unspecified() => sockaddr,
+                _ => {
+                    resolved.push(listener.clone());
+                    continue;
+                }
+            };
+            let mut listener = listener.clone();
+            let selected: Vec<String>;
+            let interfaces = if Runtime::has_bind_interfaces(&listener) {
+                selected = get_bind_interfaces(&listener.config())
+                    .into_iter()
+                    .map(String::from)
+                    .collect();
+                // The listeners are bound to the addresses of the interfaces instead
+                let _ = listener.config_mut().remove(BIND_INTERFACE);
+                &selected[..]
+            } else if let Some(interfaces) = interfaces {
+                interfaces
+            } else {
+                resolved.push(listener);
+                continue;
+            };
+            let addrs = zenoh_util::net::get_unicast_addresses_of_selected_interfaces(interfaces);
+            if addrs.is_empty() {
+                tracing::warn!(
+                    "No listener on the interfaces {:?} for {}",
+                    interfaces,
+                    listener
+                );
+            }
+            for addr in &addrs {
+                // 0.0.0.0 only selects IPv4 addresses, and the IPv6 link-local addresses can't be used without their scope
+                let link_local =
+                    matches!(addr, IpAddr::V6(a) if (a.segments()[0] & 0xffc0) == 0xfe80);
+                if (sockaddr.is_ipv4() && !addr.is_ipv4()) || link_local {
+                    continue;
+                }
+                match EndPoint::new(
+                    listener.protocol(),
+                    SocketAddr::new(*addr, sockaddr.port()).to_string(),
+                    listener.metadata(),
+                    listener.config(),
+                ) {
+                    Ok(endpoint) => resolved.push(endpoint),
+                    Err(e) => {
+                        tracing::warn!("Unable to listen on {} for {}: {}", addr, listener, e)
+                    }
+                }
+            }
+        }
+        resolved
+    }
+
     async fn bind_listeners_impl(&self, listeners: &[EndPoint]) -> ZResult<()> {
         for listener in listeners {
             let endpoint = listener.clone();
@@ -471,16 +787,18 @@ impl Runtime {
         }
     }
 
-    async fn add_listener(&self, listener: EndPoint) -> ZResult<()> {
+    async fn add_listener(&self, listener: EndPoint) -> ZResult<Locator> {
         let endpoint = listener.clone();
         match self.manager().add_listener(endpoint).await {
-            Ok(listener) => tracing::debug!("Listener added: {}", listener),
+            Ok(locator) => {
+                tracing::debug!("Listener added: {}", locator);
+                Ok(locator)
+            }
             Err(err) => {
                 tracing::warn!("Unable to open listener {}: {}", listener, err);
-                return Err(err);
+                Err(err)
             }
         }
-        Ok(())
     }
 
     fn print_locators(&self) {
@@ -502,6 +820,12 @@ impl Runtime {
             } else {
                 ifaces
             }
+        } else if names.contains('*') || names.contains('!') {
+            let patterns: Vec<String> = names.split(',').map(|p| p.trim().to_string()).collect();
+            zenoh_util::net::get_unicast_addresses_of_selected_interfaces(&patterns)
+                .into_iter()
+                .filter(IpAddr::is_ipv4)
+                .collect()
         } else {
             names
                 .split(',')
@@ -610,7 +934,7 @@ impl Runtime {
         Ok(udp_socket)
     }
 
-    pub fn bind_ucast_port(addr: IpAddr) -> ZResult<UdpSocket> {
+    pub fn bind_ucast_port(addr: IpAddr, ttl: u32) -> ZResult<UdpSocket> {
         let socket = match Socket::new(Domain::IPV4, Type::DGRAM, None) {
             Ok(socket) => socket,
             Err(err) => {
@@ -618,6 +942,11 @@ impl Runtime {
                 bail!(err=> "Unable to create datagram socket");
             }
         };
+        // Limit the scope of the scout messages sent on UDP multicast
+        if let Err(err) = socket.set_multicast_ttl_v4(ttl) {
+            tracing::warn!("Unable to set IP_MULTICAST_TTL option: {}", err);
+            bail!(err => "Unable to set IP_MULTICAST_TTL option");
+        }
         match socket.bind(&SocketAddr::new(addr, 0).into()) {
             Ok(()) => {
                 #[allow(clippy::or_fun_call)]
@@ -712,6 +1041,24 @@ impl Runtime {
         F: Fn(Hello) -> Fut + std::marker::Send + std::marker::Sync + Clone,
         Fut: Future<Output = Loop> + std::marker::Send,
         Self: Sized,
+    {
+        Runtime::scout_capabilities(sockets, matcher, mcast_addr, move |hello, _| f(hello)).await
+    }
+
+    /// Like [`scout`](Runtime::scout), also passing the capabilities advertised with the
+    /// [`Hello`] messages, if any.
+    pub(super) async fn scout_capabilities<Fut, F>(
+        sockets: &[UdpSocket],
+        matcher: WhatAmIMatcher,
+        mcast_addr: &SocketAddr,
+        f: F,
+    ) where
+        F: Fn(Hello, Option<hello::ext::Capabilities>) -> Fut
+            + std::marker::Send
+            + std::marker::Sync
+            + Clone,
+        Fut: Future<Output = Loop> + std::marker::Send,
+        Self: Sized,
     {
         let send = async {
             let mut delay = SCOUT_INITIAL_PERIOD;
@@ -765,19 +1112,28 @@ impl Runtime {
                 loop {
                     match socket.recv_from(&mut buf).await {
                         Ok((n, peer)) => {
-                            let mut reader = buf.as_slice()[..n].reader();
+                            let mut reader = &buf.as_slice()[..n];
                             let codec = Zenoh080::new();
-                            let res: Result<ScoutingMessage, DidntRead> = codec.read(&mut reader);
-                            if let Ok(msg) = res {
-                                tracing::trace!("Received {:?} from {}", msg.body, peer);
-                                if let ScoutingBody::Hello(hello) = &msg.body {
-                                    if matcher.matches(hello.whatami) {
-                                        if let Loop::Break = f(hello.clone()).await {
+                            // The locators are only allocated for the matching Hellos
+                            let res: Result<HelloRef, DidntRead> = codec.read(&mut reader);
+                            if let Ok(hello) = res {
+                                tracing::trace!("Received {:?} from {}", hello, peer);
+                                if !matcher.matches(hello.whatami) {
+                                    tracing::warn!("Received unexpected Hello: {:?}", hello);
+                                    continue;
+                                }
+                                let capabilities = hello.capabilities();
+                                match hello.to_hello() {
+                                    Ok(hello) => {
+                                        if let Loop::Break = f(hello, capabilities).await {
                                             break;
                                         }
-                                    } else {
-                                        tracing::warn!("Received unexpected Hello: {:?}", msg.body);
                                     }
+                                    Err(_) => tracing::trace!(
+                                        "Received Hello with invalid locators from {}: {:?}",
+                                        peer,
+                                        hello
+                                    ),
                                 }
                             } else {
                                 tracing::trace!(
@@ -800,7 +1156,7 @@ impl Runtime {
     }
 
     #[must_use]
-    async fn connect(&self, zid: &ZenohId, locators: &[Locator]) -> bool {
+    pub(super) async fn connect(&self, zid: &ZenohId, locators: &[Locator]) -> bool {
         const ERR: &str = "Unable to connect to newly scouted peer ";
 
         let inspector = LocatorInspector::default();
@@ -828,6 +1184,7 @@ impl Runtime {
                             "Successfully connected to newly scouted peer: {:?}",
                             transport
                         );
+                        zlock!(self.state.scouting_report).reachable(zid);
                         return true;
                     }
                     Ok(Err(e)) => tracing::trace!("{} {} on {}: {}", ERR, zid, locator, e),
@@ -845,6 +1202,7 @@ impl Runtime {
                             "Successfully connected to newly scouted peer: {:?}",
                             transport
                         );
+                        zlock!(self.state.scouting_report).reachable(zid);
                         return true;
                     }
                     Ok(Err(e)) => tracing::trace!("{} {} on {}: {}", ERR, zid, locator, e),
@@ -858,6 +1216,7 @@ impl Runtime {
             zid,
             locators
         );
+        zlock!(self.state.scouting_report).unreachable(zid, locators);
         false
     }
 
@@ -893,18 +1252,46 @@ impl Runtime {
         addr: &SocketAddr,
         timeout: std::time::Duration,
     ) -> ZResult<()> {
+        let local = self.capabilities();
+        let local = &local;
+        let preferred_period = SCOUT_PREFERRED_PERIOD.min(timeout / 2);
+        let start = Instant::now();
         let scout = async {
-            Runtime::scout(sockets, what, addr, move |hello| async move {
-                tracing::info!("Found {:?}", hello);
-                if !hello.locators.is_empty() {
-                    if self.connect(&hello.zid, &hello.locators).await {
-                        return Loop::Break;
+            Runtime::scout_capabilities(
+                sockets,
+                what,
+                addr,
+                move |hello, capabilities| async move {
+                    tracing::info!("Found {:?} with {:?}", hello, capabilities);
+                    match compatibility(local, capabilities.as_ref()) {
+                        Compatibility::Incompatible => {
+                            tracing::debug!(
+                                "Ignore incompatible {}: {:?}",
+                                hello.zid,
+                                capabilities
+                            );
+                            return Loop::Continue;
+                        }
+                        Compatibility::Degraded if start.elapsed() < preferred_period => {
+                            tracing::debug!(
+                                "Defer {} lacking capabilities: {:?}",
+                                hello.zid,
+                                capabilities
+                            );
+                            return Loop::Continue;
+                        }
+                        _ => {}
                     }
-                } else {
-                    tracing::warn!("Received Hello with no locators: {:?}", hello);
-                }
-                Loop::Continue
-            })
+                    if !hello.locators.is_empty() {
+                        if self.connect(&hello.zid, &hello.locators).await {
+                            return Loop::Break;
+                        }
+                    } else {
+                        tracing::warn!("Received Hello with no locators: {:?}", hello);
+                    }
+                    Loop::Continue
+                },
+            )
             .await;
             Ok(())
         };
@@ -924,17 +1311,63 @@ impl Runtime {
         what: WhatAmIMatcher,
         addr: &SocketAddr,
     ) {
-        Runtime::scout(ucast_sockets, what, addr, move |hello| async move {
-            if !hello.locators.is_empty() {
-                self.connect_peer(&hello.zid, &hello.locators).await
-            } else {
-                tracing::warn!("Received Hello with no locators: {:?}", hello);
-            }
-            Loop::Continue
-        })
+        let local = self.capabilities();
+        let local = &local;
+        Runtime::scout_capabilities(
+            ucast_sockets,
+            what,
+            addr,
+            move |hello, capabilities| async move {
+                if compatibility(local, capabilities.as_ref()) == Compatibility::Incompatible {
+                    tracing::debug!("Ignore incompatible {}: {:?}", hello.zid, capabilities);
+                    return Loop::Continue;
+                }
+                zlock!(self.state.scouting_report).hello(
+                    &hello.zid,
+                    &self.manager().zid(),
+                    &self.get_locators(),
+                    &hello.locators,
+                );
+                if !hello.locators.is_empty() {
+                    self.connect_peer(&hello.zid, &hello.locators).await
+                } else {
+                    tracing::warn!("Received Hello with no locators: {:?}", hello);
+                }
+                Loop::Continue
+            },
+        )
         .await
     }
 
+    /// The [`Hello`] advertising the locators of this runtime.
+    pub(super) fn hello(&self) -> Hello {
+        Hello {
+            version: zenoh_protocol::VERSION,
+            whatami: self.whatami(),
+            zid: self.manager().zid(),
+            locators: self.get_locators(),
+        }
+    }
+
+    /// The capabilities of this runtime advertised alongside its [`Hello`].
+    pub(super) fn capabilities(&self) -> hello::ext::Capabilities {
+        #[allow(unused_mut)]
+        let mut capabilities = hello::ext::Capabilities {
+            versions: vec![zenoh_protocol::VERSION],
+            ..Default::default()
+        };
+        #[cfg(feature = "shared-memory")]
+        {
+            capabilities.shm = self.manager().config.unicast.is_shm;
+        }
+        #[cfg(feature = "transport_compression")]
+        if self.manager().config.unicast.is_compression {
+            capabilities.compression =
+                zenoh_transport::common::batch::CompressionAlgorithm::SUPPORTED;
+        }
+        capabilities
+    }
+
     async fn responder(&self, mcast_socket: &UdpSocket, ucast_sockets: &[UdpSocket]) {
         fn get_best_match<'a>(addr: &IpAddr, sockets: &'a [UdpSocket]) -> Option<&'a UdpSocket> {
             fn octets(addr: &IpAddr) -> Vec<u8> {
@@ -983,27 +1416,22 @@ impl Runtime {
                         let mut writer = wbuf.writer();
                         let codec = Zenoh080::new();
 
-                        let zid = self.manager().zid();
-                        let hello: ScoutingMessage = Hello {
-                            version: zenoh_protocol::VERSION,
-                            whatami: self.whatami(),
-                            zid,
-                            locators: self.get_locators(),
-                        }
-                        .into();
+                        let hello = self.hello();
+                        let capabilities = self.capabilities();
                         let socket = get_best_match(&peer.ip(), ucast_sockets).unwrap();
                         tracing::trace!(
-                            "Send {:?} to {} on interface {}",
-                            hello.body,
+                            "Send {:?} with {:?} to {} on interface {}",
+                            hello,
+                            capabilities,
                             peer,
                             socket
                                 .local_addr()
                                 .map_or("unknown".to_string(), |addr| addr.ip().to_string())
                         );
-                        codec.write(&mut writer, &hello).unwrap();
+                        codec.write(&mut writer, (&hello, &capabilities)).unwrap();
 
                         if let Err(err) = socket.send_to(wbuf.as_slice(), peer).await {
-                            tracing::error!("Unable to send {:?} to {}: {}", hello.body, peer, err);
+                            tracing::error!("Unable to send {:?} to {}: {}", hello, peer, err);
                         }
                     }
                 }
@@ -1057,3 +1485,107 @@ impl Runtime {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(s: &str) -> EndPoint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn bind_interfaces() {
+        assert!(!Runtime::has_bind_interfaces(&endpoint(
+            "tcp/0.0.0.0:7447#iface=lo"
+        )));
+        assert!(Runtime::has_bind_interfaces(&endpoint(
+            "tcp/0.0.0.0:7447#iface=lo|eth0"
+        )));
+        assert!(Runtime::has_bind_interfaces(&endpoint(
+            "tls/0.0.0.0:7447#iface=lo"
+        )));
+        assert!(!Runtime::has_bind_interfaces(&endpoint("tls/0.0.0.0:7447")));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn resolve_listeners_on_interfaces() {
+        let listeners = [
+            endpoint("tcp/0.0.0.0:7447#iface=lo|none0"),
+            endpoint("tls/0.0.0.0:7448#iface=lo"),
+            endpoint("tcp/0.0.0.0:7449"),
+            endpoint("tcp/127.0.0.1:7450"),
+        ];
+
+        // The interfaces of the endpoints are resolved into their addresses, the others are kept
+        let resolved = Runtime::resolve_listeners(&listeners, None);
+        assert_eq!(
+            resolved,
+            vec![
+                endpoint("tcp/127.0.0.1:7447"),
+                endpoint("tls/127.0.0.1:7448"),
+                endpoint("tcp/0.0.0.0:7449"),
+                endpoint("tcp/127.0.0.1:7450"),
+            ]
+        );
+
+        // The interfaces of `listen.interfaces` apply to the endpoints not selecting theirs
+        let resolved = Runtime::resolve_listeners(&listeners, Some(&["lo".to_string()]));
+        assert_eq!(resolved[2], endpoint("tcp/127.0.0.1:7449"));
+        let resolved = Runtime::resolve_listeners(&listeners, Some(&["none0".to_string()]));
+        assert_eq!(resolved.len(), 3);
+    }
+
+    #[test]
+    fn scouting_compatibility() {
+        let capabilities = |shm, compression, versions: &[u8]| hello::ext::Capabilities {
+            shm,
+            compression,
+            versions: versions.to_vec(),
+        };
+        let local = capabilities(true, 0b01, &[8]);
+
+        assert_eq!(
+            compatibility(&local, Some(&capabilities(true, 0b11, &[8, 9]))),
+            Compatibility::Full
+        );
+        assert_eq!(
+            compatibility(&local, Some(&capabilities(true, 0b01, &[]))),
+            Compatibility::Full
+        );
+        assert_eq!(
+            compatibility(&local, Some(&capabilities(true, 0b01, &[9]))),
+            Compatibility::Incompatible
+        );
+        assert_eq!(
+            compatibility(&local, Some(&capabilities(false, 0b01, &[8]))),
+            Compatibility::Degraded
+        );
+        assert_eq!(
+            compatibility(&local, Some(&capabilities(true, 0b10, &[8]))),
+            Compatibility::Degraded
+        );
+        assert_eq!(compatibility(&local, None), Compatibility::Degraded);
+
+        // Without shared memory nor compression any node speaking the version fits
+        let local = capabilities(false, 0, &[8]);
+        assert_eq!(compatibility(&local, None), Compatibility::Full);
+        assert_eq!(
+            compatibility(&local, Some(&capabilities(false, 0, &[8]))),
+            Compatibility::Full
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn scouting_ttl() {
+        let localhost = IpAddr::from([127, 0, 0, 1]);
+        for ttl in [zenoh_config::defaults::scouting::multicast::ttl, 8] {
+            let socket = Runtime::bind_ucast_port(localhost, ttl).unwrap();
+            assert_eq!(
+                socket2::SockRef::from(&socket).multicast_ttl_v4().unwrap(),
+                ttl
+            );
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use zenoh_protocol::core::{Locator, ZenohId};
+
+// The Hellos replying to the same Scout are received within this delay. Two ZenohIds announcing
+// the same locator within it are distinct instances, not an instance restarted with a new ZenohId.
+const SAME_SCOUT_DELAY: Duration = Duration::from_millis(500);
+// The maximum number of entries of each part of the report, so that a LAN flooded with Hellos can't
+// exhaust the memory
+const MAX_ENTRIES: usize = 1_024;
+
+/// The problems detected while scouting the LAN, which otherwise only manifest as message loss.
+#[derive(Default)]
+pub(crate) struct ScoutingReport {
+    // The locators of the other instances announcing our ZenohId
+    zid_conflicts: HashSet<Locator>,
+    // The locators announced by several ZenohIds
+    duplicate_locators: HashMap<Locator, HashSet<ZenohId>>,
+    // The scouted peers that we couldn't reach on any of their locators
+    unreachable_peers: HashMap<ZenohId, Vec<Locator>>,
+    // The last ZenohId having announced each locator
+    announcers: HashMap<Locator, (ZenohId, Instant)>,
+}
+
+impl ScoutingReport {
+    pub(crate) fn hello(
+        &mut self,
+        zid: &ZenohId,
+        own_zid: &ZenohId,
+        own_locators: &[Locator],
+        locators: &[Locator],
+    ) {
+        self.hello_at(zid, own_zid, own_locators, locators, Instant::now())
+    }
+
+    fn hello_at(
+        &mut self,
+        zid: &ZenohId,
+        own_zid: &ZenohId,
+        own_locators: &[Locator],
+        locators: &[Locator],
+        now: Instant,
+    ) {
+        if zid == own_zid && !locators.iter().any(|l| own_locators.contains(l)) {
+            tracing::error!(
+                "ZenohId conflict: another instance with ZenohId {} announces {:?}",
+                zid,
+                locators
+            );
+            for locator in locators {
+                if self.zid_conflicts.len() < MAX_ENTRIES {
+                    self.zid_conflicts.insert(locator.clone());
+                }
+            }
+        }
+        // Only the announcements of the current Scout are needed to detect the duplicates
+        self.announcers
+            .retain(|_, (_, time)| now.duration_since(*time) < SAME_SCOUT_DELAY);
+        for locator in locators {
+            if let Some((announcer, time)) = self.announcers.get(locator) {
+                if announcer != zid
+                    && (self.duplicate_locators.len() < MAX_ENTRIES
+                        || self.duplicate_locators.contains_key(locator))
+                {
+                    let zids = self.duplicate_locators.entry(locator.clone()).or_default();
+                    if zids.insert(*announcer) | zids.insert(*zid) {
+                        tracing::warn!(
+                            "Locator {} announced by several instances: {:?}",
+                            locator,
+                            zids
+                        );
+                    }
+                }
+            }
+            if self.announcers.len() < MAX_ENTRIES || self.announcers.contains_key(locator) {
+                self.announcers.insert(locator.clone(), (*zid, now));
+            }
+        }
+    }
+
+    pub(crate) fn unreachable(&mut self, zid: &ZenohId, locators: &[Locator]) {
+        if self.unreachable_peers.len() < MAX_ENTRIES || self.unreachable_peers.contains_key(zid) {
+            self.unreachable_peers.insert(*zid, locators.to_vec());
+        }
+    }
+
+    pub(crate) fn reachable(&mut self, zid: &ZenohId) {
+        self.unreachable_peers.remove(zid);
+    }
+
+    pub(crate) fn to_json(&self) -> serde_json::Value {
+        let locators = |locators: &mut dyn Iterator<Item = &Locator>| -> Vec<String> {
+            locators.map(|l| l.to_string()).collect()
+        };
+        json!({
+            "zid_conflicts": locators(&mut self.zid_conflicts.iter()),
+            "duplicate_locators": self
+                .duplicate_locators
+                .iter()
+                .map(|(locator, zids)| {
+                    (
+                        locator.to_string(),
+                        json!(zids.iter().map(|zid| zid.to_string()).collect::<Vec<_>>()),
+                    )
+                })
+                .collect::<serde_json::Map<_, _>>(),
+            "unreachable_peers": self
+                .unreachable_peers
+                .iter()
+                .map(|(zid, l)| (zid.to_string(), json!(locators(&mut l.iter()))))
+                .collect::<serde_json::Map<_, _>>(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locator(port: usize) -> Locator {
+        format!("tcp/192.168.0.1:{port}").parse().unwrap()
+    }
+
+    #[test]
+    fn scouting_report_zid_conflict() {
+        let mut report = ScoutingReport::default();
+        let own_zid = ZenohId::rand();
+        let own_locators = [locator(7447)];
+
+        // Our own Hello isn't a conflict
+        report.hello(&own_zid, &own_zid, &own_locators, &own_locators);
+        assert!(report.zid_conflicts.is_empty());
+
+        report.hello(&own_zid, &own_zid, &own_locators, &[locator(7448)]);
+        assert!(report.zid_conflicts.contains(&locator(7448)));
+        assert_eq!(report.to_json()["zid_conflicts"][0], "tcp/192.168.0.1:7448");
+    }
+
+    #[test]
+    fn scouting_report_duplicate_locators() {
+        let mut report = ScoutingReport::default();
+        let own_zid = ZenohId::rand();
+        let (zid1, zid2) = (ZenohId::rand(), ZenohId::rand());
+        let now = Instant::now();
+
+        report.hello_at(&zid1, &own_zid, &[], &[locator(7447)], now);
+        report.hello_at(&zid2, &own_zid, &[], &[locator(7447)], now);
+        let zids = report.duplicate_locators.get(&locator(7447)).unwrap();
+        assert!(zids.contains(&zid1) && zids.contains(&zid2));
+
+        // A ZenohId replacing another one after the delay of a Scout is a restarted instance
+        report.hello_at(&zid1, &own_zid, &[], &[locator(7448)], now);
+        let later = now + SAME_SCOUT_DELAY * 2;
+        report.hello_at(&zid2, &own_zid, &[], &[locator(7448)], later);
+        assert!(!report.duplicate_locators.contains_key(&locator(7448)));
+        // The announcements of the previous Scouts have been pruned
+        assert_eq!(report.announcers.len(), 1);
+    }
+
+    #[test]
+    fn scouting_report_bounded() {
+        let mut report = ScoutingReport::default();
+        let own_zid = ZenohId::rand();
+        let now = Instant::now();
+        for port in 0..2 * MAX_ENTRIES {
+            let zid = ZenohId::rand();
+            report.hello_at(&zid, &own_zid, &[], &[locator(port)], now);
+            report.unreachable(&zid, &[locator(port)]);
+        }
+        assert_eq!(report.announcers.len(), MAX_ENTRIES);
+        assert_eq!(report.unreachable_peers.len(), MAX_ENTRIES);
+
+        let zid = ZenohId::rand();
+        report.unreachable(&zid, &[locator(0)]);
+        assert!(!report.unreachable_peers.contains_key(&zid));
+        let first = *report.unreachable_peers.keys().next().unwrap();
+        report.reachable(&first);
+        report.unreachable(&zid, &[locator(0)]);
+        assert!(report.unreachable_peers.contains_key(&zid));
+    }
+}
@@ -12,6 +12,7 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use crate::net::primitives::{DummyPrimitives, EPrimitives, Primitives};
+use crate::net::routing::dispatcher::face::Face;
 use crate::net::routing::dispatcher::tables::{self, Tables};
 use crate::net::routing::router::*;
 use crate::net::routing::RoutingContext;
@@ -27,6 +28,7 @@ use zenoh_protocol::core::{
 };
 use zenoh_protocol::network::declare::subscriber::ext::SubscriberInfo;
 use zenoh_protocol::network::declare::Mode;
+use zenoh_protocol::network::declare::{common, DeclareSubscriber, UndeclareSubscriber};
 use zenoh_protocol::network::{ext, Declare, DeclareBody, DeclareKeyExpr};
 use zenoh_protocol::zenoh::{PushBody, Put};
 
@@ -625,6 +627,7 @@ fn client_test() {
         &"test/client/z1_wr1".into(),
         ext::QoSType::default(),
         None,
+        None,
         PushBody::Put(Put {
             timestamp: None,
             encoding: Encoding::default(),
@@ -634,6 +637,7 @@ fn client_test() {
             ext_unknown: vec![],
             payload: ZBuf::empty(),
             ext_attachment: None,
+            ext_tbound: None,
         }),
         0,
     );
@@ -659,6 +663,7 @@ fn client_test() {
         &WireExpr::from(11).with_suffix("/z1_wr2"),
         ext::QoSType::default(),
         None,
+        None,
         PushBody::Put(Put {
             timestamp: None,
             encoding: Encoding::default(),
@@ -668,6 +673,7 @@ fn client_test() {
             ext_unknown: vec![],
             payload: ZBuf::empty(),
             ext_attachment: None,
+            ext_tbound: None,
         }),
         0,
     );
@@ -693,6 +699,7 @@ fn client_test() {
         &"test/client/**".into(),
         ext::QoSType::default(),
         None,
+        None,
         PushBody::Put(Put {
             timestamp: None,
             encoding: Encoding::default(),
@@ -702,6 +709,7 @@ fn client_test() {
             ext_unknown: vec![],
             payload: ZBuf::empty(),
             ext_attachment: None,
+            ext_tbound: None,
         }),
         0,
     );
@@ -727,6 +735,7 @@ fn client_test() {
         &12.into(),
         ext::QoSType::default(),
         None,
+        None,
         PushBody::Put(Put {
             timestamp: None,
             encoding: Encoding::default(),
@@ -736,6 +745,7 @@ fn client_test() {
             ext_unknown: vec![],
             payload: ZBuf::empty(),
             ext_attachment: None,
+            ext_tbound: None,
         }),
         0,
     );
@@ -761,6 +771,7 @@ fn client_test() {
         &22.into(),
         ext::QoSType::default(),
         None,
+        None,
         PushBody::Put(Put {
             timestamp: None,
             encoding: Encoding::default(),
@@ -770,6 +781,7 @@ fn client_test() {
             ext_unknown: vec![],
             payload: ZBuf::empty(),
             ext_attachment: None,
+            ext_tbound: None,
         }),
         0,
     );
@@ -786,3 +798,752 @@ fn client_test() {
     // mapping strategy check
     // assert_eq!(primitives2.get_last_key().unwrap(), KeyExpr::IdWithSuffix(31, "/z2_pub1".to_string()));
 }
+
+#[test]
+fn limits_test() {
+    let mut config = Config::default();
+    config
+        .routing
+        .limits
+        .set_max_key_expr_depth(Some(3))
+        .unwrap();
+    config.routing.limits.set_max_resources(Some(1)).unwrap();
+    config.routing.limits.set_max_declarations(Some(1)).unwrap();
+    let router = Router::new(
+        ZenohId::try_from([1]).unwrap(),
+        WhatAmI::Client,
+        Some(Arc::new(HLC::default())),
+        &config,
+    )
+    .unwrap();
+    let tables = router.tables.clone();
+
+    // A face of a remote node
+    let remote_face = |whatami| {
+        let face = router.new_primitives(Arc::new(DummyPrimitives {}));
+        let mut state = face.state.clone();
+        let state = zenoh_sync::get_mut_unchecked(&mut state);
+        state.zid = ZenohId::try_from([2]).unwrap();
+        state.whatami = whatami;
+        face
+    };
+    let declare = |body| Declare {
+        ext_qos: ext::QoSType::declare_default(),
+        ext_tstamp: None,
+        ext_nodeid: ext::NodeIdType::default(),
+        body,
+    };
+    let declare_keyexpr_on = |face: &Face, id, expr: &str| {
+        Primitives::send_declare(
+            face,
+            declare(DeclareBody::DeclareKeyExpr(DeclareKeyExpr {
+                id,
+                wire_expr: WireExpr::from(expr).to_owned(),
+            })),
+        )
+    };
+
+    // The limits do not apply to the local sessions, nor to the routers and peers
+    let local = router.new_primitives(Arc::new(DummyPrimitives {}));
+    declare_keyexpr_on(local.as_ref(), 1, "a/b/c/d");
+    assert_eq!(local.state.remote_mappings.len(), 1);
+    let peer = remote_face(WhatAmI::Peer);
+    declare_keyexpr_on(peer.as_ref(), 1, "a/b/c/d");
+    declare_keyexpr_on(peer.as_ref(), 2, "a/b/c/e");
+    assert_eq!(peer.state.remote_mappings.len(), 2);
+
+    let face = remote_face(WhatAmI::Client);
+    let declare_keyexpr = |id, expr: &str| {
+        Primitives::send_declare(
+            face.as_ref(),
+            declare(DeclareBody::DeclareKeyExpr(DeclareKeyExpr {
+                id,
+                wire_expr: WireExpr::from(expr).to_owned(),
+            })),
+        )
+    };
+    let declare_subscriber = |id, expr: &str| {
+        Primitives::send_declare(
+            face.as_ref(),
+            declare(DeclareBody::DeclareSubscriber(DeclareSubscriber {
+                id,
+                wire_expr: WireExpr::from(expr).to_owned(),
+                ext_info: SubscriberInfo::default(),
+            })),
+        )
+    };
+
+    // Too deep
+    declare_keyexpr(1, "a/b/c/d");
+    assert!(face.state.remote_mappings.is_empty());
+    declare_keyexpr(1, "a/b/c");
+    assert_eq!(face.state.remote_mappings.len(), 1);
+    // Too many resources
+    declare_keyexpr(2, "a/b");
+    assert_eq!(face.state.remote_mappings.len(), 1);
+
+    let undeclare_subscriber = |id, expr: &str| {
+        Primitives::send_declare(
+            face.as_ref(),
+            declare(DeclareBody::UndeclareSubscriber(UndeclareSubscriber {
+                id,
+                ext_wire_expr: common::ext::WireExprType {
+                    wire_expr: WireExpr::from(expr).to_owned(),
+                },
+            })),
+        )
+    };
+
+    // Too deep
+    declare_subscriber(1, "a/b/c/d");
+    assert_eq!(face.state.declarations.len(), 0);
+    declare_subscriber(1, "a/b");
+    assert_eq!(face.state.declarations.len(), 1);
+    // Redeclaring is allowed
+    declare_subscriber(1, "a/b");
+    assert_eq!(face.state.declarations.len(), 1);
+    // Too many declarations
+    declare_subscriber(2, "a/c");
+    assert_eq!(face.state.declarations.len(), 1);
+    assert!(Resource::get_resource(zread!(tables.tables)._get_root(), "a/c").is_none());
+
+    // Undeclaring what was never declared does not free the quota
+    undeclare_subscriber(2, "a/c");
+    undeclare_subscriber(3, "a/d");
+    assert_eq!(face.state.declarations.len(), 1);
+    declare_subscriber(2, "a/c");
+    assert!(Resource::get_resource(zread!(tables.tables)._get_root(), "a/c").is_none());
+
+    undeclare_subscriber(1, "a/b");
+    assert_eq!(face.state.declarations.len(), 0);
+    declare_subscriber(2, "a/c");
+    assert_eq!(face.state.declarations.len(), 1);
+    assert!(Resource::get_resource(zread!(tables.tables)._get_root(), "a/c").is_some());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn sweep_test() {
+    let router = Router::new(
+        ZenohId::try_from([1]).unwrap(),
+        WhatAmI::Client,
+        Some(Arc::new(HLC::default())),
+        &Config::default(),
+    )
+    .unwrap();
+    let tables = router.tables.clone();
+    let declare_subscriber = |face: &Face, expr: &str| {
+        Primitives::send_declare(
+            face,
+            Declare {
+                ext_qos: ext::QoSType::declare_default(),
+                ext_tstamp: None,
+                ext_nodeid: ext::NodeIdType::default(),
+                body: DeclareBody::DeclareSubscriber(DeclareSubscriber {
+                    id: 1,
+                    wire_expr: WireExpr::from(expr).to_owned(),
+                    ext_info: SubscriberInfo::default(),
+                }),
+            },
+        )
+    };
+
+    // Declarations received after the face was closed are ignored
+    let face = router.new_primitives(Arc::new(DummyPrimitives {}));
+    tables::close_face(&tables, &Arc::downgrade(&face.state));
+    declare_subscriber(face.as_ref(), "a/b");
+    assert!(Resource::get_resource(zread!(tables.tables)._get_root(), "a/b").is_none());
+
+    // Declarations left by a face removed from the tables are purged
+    let face = router.new_primitives(Arc::new(DummyPrimitives {}));
+    declare_subscriber(face.as_ref(), "a/c");
+    zwrite!(tables.tables).faces.remove(&face.state.id);
+    assert_eq!(tables::sweep(&tables), 1);
+    assert_eq!(zread!(tables.tables).purged_declarations, 1);
+    assert!(Resource::get_resource(zread!(tables.tables)._get_root(), "a/c").is_none());
+    assert_eq!(tables::sweep(&tables), 0);
+
+    // The sweep walks the tables by chunks and purges all the stale declarations
+    let face = router.new_primitives(Arc::new(DummyPrimitives {}));
+    for i in 0..1_000 {
+        declare_subscriber(face.as_ref(), &format!("b/{i}"));
+    }
+    zwrite!(tables.tables).faces.remove(&face.state.id);
+    assert_eq!(tables::sweep(&tables), 1_000);
+    assert_eq!(zread!(tables.tables).purged_declarations, 1_001);
+    assert!(Resource::get_resource(zread!(tables.tables)._get_root(), "b/0").is_none());
+}
+
+#[test]
+fn queryable_conflict_test() {
+    use zenoh_protocol::common::ext::WireExprType;
+    use zenoh_protocol::network::declare::queryable::ext::QueryableInfo;
+    use zenoh_protocol::network::declare::{DeclareQueryable, UndeclareQueryable};
+
+    let router = Router::new(
+        ZenohId::try_from([1]).unwrap(),
+        WhatAmI::Client,
+        Some(Arc::new(HLC::default())),
+        &Config::default(),
+    )
+    .unwrap();
+    let tables = router.tables.clone();
+    let declare_queryable = |face: &Face, expr: &str, complete: u8| {
+        Primitives::send_declare(
+            face,
+            Declare {
+                ext_qos: ext::QoSType::declare_default(),
+                ext_tstamp: None,
+                ext_nodeid: ext::NodeIdType::default(),
+                body: DeclareBody::DeclareQueryable(DeclareQueryable {
+                    id: 1,
+                    wire_expr: WireExpr::from(expr).to_owned(),
+                    ext_info: QueryableInfo {
+                        complete,
+                        distance: 0,
+                    },
+                }),
+            },
+        )
+    };
+    let face0 = router.new_primitives(Arc::new(DummyPrimitives {}));
+    let face1 = router.new_primitives(Arc::new(DummyPrimitives {}));
+
+    // Incomplete queryables do not conflict
+    declare_queryable(face0.as_ref(), "a/**", 0);
+    declare_queryable(face1.as_ref(), "a/b", 1);
+    assert!(zread!(tables.tables).queryable_conflicts.is_empty());
+
+    // Intersecting complete queryables of different faces conflict
+    declare_queryable(face0.as_ref(), "a/**", 1);
+    declare_queryable(face0.as_ref(), "a/**", 1);
+    {
+        let rtables = zread!(tables.tables);
+        assert_eq!(rtables.queryable_conflicts.len(), 1);
+        let conflict = &rtables.queryable_conflicts[0];
+        assert_eq!(conflict.key_expr, "a/**");
+        assert_eq!(conflict.conflicting_key_expr, "a/b");
+    }
+
+    // The conflict is forgotten when one of the queryables is undeclared
+    Primitives::send_declare(
+        face1.as_ref(),
+        Declare {
+            ext_qos: ext::QoSType::declare_default(),
+            ext_tstamp: None,
+            ext_nodeid: ext::NodeIdType::default(),
+            body: DeclareBody::UndeclareQueryable(UndeclareQueryable {
+                id: 1,
+                ext_wire_expr: WireExprType {
+                    wire_expr: WireExpr::from("a/b").to_owned(),
+                },
+            }),
+        },
+    );
+    assert!(zread!(tables.tables).queryable_conflicts.is_empty());
+
+    // The conflict is forgotten when one of the queryables is redeclared incomplete
+    declare_queryable(face1.as_ref(), "a/b", 1);
+    assert_eq!(zread!(tables.tables).queryable_conflicts.len(), 1);
+    declare_queryable(face1.as_ref(), "a/b", 0);
+    assert!(zread!(tables.tables).queryable_conflicts.is_empty());
+    declare_queryable(face0.as_ref(), "a/**", 0);
+    declare_queryable(face1.as_ref(), "a/b", 1);
+    assert!(zread!(tables.tables).queryable_conflicts.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn congestion_test() {
+    use crate::net::routing::dispatcher::pubsub::{
+        congestion_body, congestion_received, read_congestion_body,
+    };
+
+    let router = Router::new(
+        ZenohId::try_from([1]).unwrap(),
+        WhatAmI::Router,
+        Some(Arc::new(HLC::default())),
+        &Config::default(),
+    )
+    .unwrap();
+    let tables = router.tables.clone();
+    let face = router.new_primitives(Arc::new(DummyPrimitives {}));
+    let zid = ZenohId::try_from([2]).unwrap();
+
+    // The body of the OAM message carries the congested node and its state
+    for congested in [true, false] {
+        let body = congestion_body(&zid, congested).unwrap();
+        assert_eq!(read_congestion_body(&body).unwrap(), (zid, congested));
+    }
+    assert!(read_congestion_body(&ZBuf::empty()).is_err());
+
+    // The congestions are recorded with the face they are notified through, until they recover
+    congestion_received(&tables, &face.state, &congestion_body(&zid, true).unwrap());
+    assert_eq!(
+        zread!(tables.tables).congestions.get(&zid),
+        Some(&face.state.id)
+    );
+    congestion_received(&tables, &face.state, &congestion_body(&zid, false).unwrap());
+    assert!(zread!(tables.tables).congestions.is_empty());
+
+    // or the face is closed
+    congestion_received(&tables, &face.state, &congestion_body(&zid, true).unwrap());
+    tables::close_face(&tables, &Arc::downgrade(&face.state));
+    assert!(zread!(tables.tables).congestions.is_empty());
+}
+
+#[derive(Default)]
+struct RequestCounter {
+    requests: std::sync::atomic::AtomicUsize,
+}
+
+impl RequestCounter {
+    fn take(&self) -> usize {
+        self.requests.swap(0, std::sync::atomic::Ordering::SeqCst)
+    }
+}
+
+impl EPrimitives for RequestCounter {
+    fn send_declare(&self, _ctx: RoutingContext<Declare>) {}
+
+    fn send_push(&self, _msg: zenoh_protocol::network::Push) {}
+
+    fn send_request(&self, _ctx: RoutingContext<zenoh_protocol::network::Request>) {
+        self.requests
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    fn send_response(&self, _ctx: RoutingContext<zenoh_protocol::network::Response>) {}
+
+    fn send_response_final(&self, _ctx: RoutingContext<zenoh_protocol::network::ResponseFinal>) {}
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn complete_short_circuit_test() {
+    use zenoh_protocol::core::QueryTarget;
+    use zenoh_protocol::network::declare::queryable::ext::QueryableInfo;
+    use zenoh_protocol::network::declare::DeclareQueryable;
+    use zenoh_protocol::network::Request;
+    use zenoh_protocol::zenoh::{Query, RequestBody};
+
+    let run = |config: &Config| {
+        let router = Router::new(
+            ZenohId::try_from([1]).unwrap(),
+            WhatAmI::Client,
+            Some(Arc::new(HLC::default())),
+            config,
+        )
+        .unwrap();
+        let declare_queryable = |face: &Face, expr: &str, complete: u8| {
+            Primitives::send_declare(
+                face,
+                Declare {
+                    ext_qos: ext::QoSType::declare_default(),
+                    ext_tstamp: None,
+                    ext_nodeid: ext::NodeIdType::default(),
+                    body: DeclareBody::DeclareQueryable(DeclareQueryable {
+                        id: 1,
+                        wire_expr: WireExpr::from(expr).to_owned(),
+                        ext_info: QueryableInfo {
+                            complete,
+                            distance: 0,
+                        },
+                    }),
+                },
+            )
+        };
+        let storage = Arc::new(RequestCounter::default());
+        let other = Arc::new(RequestCounter::default());
+        let face0 = router.new_primitives(storage.clone());
+        let face1 = router.new_primitives(other.clone());
+        let face2 = router.new_primitives(Arc::new(DummyPrimitives {}));
+        declare_queryable(face0.as_ref(), "a/**", 1);
+        declare_queryable(face1.as_ref(), "a/b", 0);
+
+        let query = |expr: &str, target: QueryTarget| {
+            Primitives::send_request(
+                face2.as_ref(),
+                Request {
+                    id: 1,
+                    wire_expr: WireExpr::from(expr).to_owned(),
+                    ext_qos: ext::QoSType::request_default(),
+                    ext_tstamp: None,
+                    ext_nodeid: ext::NodeIdType::default(),
+                    ext_target: target,
+                    ext_budget: None,
+                    ext_timeout: None,
+                    payload: RequestBody::Query(Query {
+                        parameters: String::new(),
+                        ext_sinfo: None,
+                        ext_consolidation: Default::default(),
+                        ext_body: None,
+                        ext_attachment: None,
+                        ext_unknown: vec![],
+                    }),
+                },
+            );
+            (storage.take(), other.take())
+        };
+        (
+            query("a/b", QueryTarget::BestMatching),
+            query("a/b", QueryTarget::All),
+        )
+    };
+
+    // The complete queryable alone answers the queries targeting the best matching queryables
+    let config = Config::default();
+    assert_eq!(run(&config), ((1, 0), (1, 1)));
+
+    // Unless the short-circuit is disabled
+    let mut config = Config::default();
+    config
+        .insert_json5("routing/queries/complete_short_circuit", "false")
+        .unwrap();
+    assert_eq!(run(&config), ((1, 1), (1, 1)));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn static_routes_test() {
+    use crate::net::routing::dispatcher::tables::StaticRoute;
+    use zenoh_config::StaticRouteConf;
+
+    let router = Router::new(
+        ZenohId::try_from([1]).unwrap(),
+        WhatAmI::Client,
+        Some(Arc::new(HLC::default())),
+        &Config::default(),
+    )
+    .unwrap();
+    let tables = router.tables.clone();
+    let next_hop: zenoh_protocol::core::Locator = "tcp/10.0.0.5:7447".parse().unwrap();
+    zwrite!(tables.tables).static_routes = vec![StaticRoute::new(StaticRouteConf {
+        key: "test/static/**".try_into().unwrap(),
+        face: next_hop.clone(),
+    })];
+
+    let primitives0 = Arc::new(ClientPrimitives::new());
+    let face0 = router.new_primitives(primitives0.clone());
+    let primitives1 = Arc::new(ClientPrimitives::new());
+    let face1 = router.new_primitives(primitives1.clone());
+    let primitives2 = Arc::new(ClientPrimitives::new());
+    let face2 = router.new_primitives(primitives2.clone());
+    declare_subscription(
+        zlock!(tables.ctrl_lock).as_ref(),
+        &tables,
+        &mut face1.state.clone(),
+        &"test/**".into(),
+        &SubscriberInfo::default(),
+        NodeId::default(),
+    );
+
+    let route = |expr: &str| {
+        primitives1.clear_data();
+        primitives2.clear_data();
+        full_reentrant_route_data(
+            &tables,
+            &face0.state,
+            &expr.into(),
+            ext::QoSType::default(),
+            None,
+            None,
+            PushBody::Put(Put {
+                timestamp: None,
+                encoding: Encoding::default(),
+                ext_sinfo: None,
+                #[cfg(feature = "shared-memory")]
+                ext_shm: None,
+                ext_unknown: vec![],
+                payload: ZBuf::empty(),
+                ext_attachment: None,
+                ext_tbound: None,
+            }),
+            0,
+        );
+        (primitives1.get_last_name(), primitives2.get_last_name())
+    };
+
+    // No face connected to the next hop: the sessions are still served
+    assert_eq!(route("test/static/a"), (Some("test/static/a".into()), None));
+
+    // The data is forwarded to the next hop once connected, without any declaration
+    tables::update_face_links(&tables, &face2.state, vec![next_hop.clone()]);
+    assert_eq!(
+        route("test/static/a"),
+        (Some("test/static/a".into()), Some("test/static/a".into()))
+    );
+    assert_eq!(route("test/other"), (Some("test/other".into()), None));
+
+    // The next hop is forgotten when its link is removed
+    tables::update_face_links(&tables, &face2.state, vec![]);
+    assert_eq!(route("test/static/b"), (Some("test/static/b".into()), None));
+
+    // or when its face is closed, which the static routes do not keep alive
+    tables::update_face_links(&tables, &face2.state, vec![next_hop]);
+    assert_eq!(zread!(tables.tables).static_routes[0].faces.len(), 1);
+    tables::close_face(&tables, &Arc::downgrade(&face2.state));
+    assert!(zread!(tables.tables).static_routes[0].faces.is_empty());
+    assert_eq!(route("test/static/c"), (Some("test/static/c".into()), None));
+}
+
+#[derive(Default)]
+struct PushRecorder {
+    pushes: std::sync::Mutex<Vec<String>>,
+}
+
+impl PushRecorder {
+    fn take(&self) -> Vec<String> {
+        let mut pushes = std::mem::take(&mut *zlock!(self.pushes));
+        pushes.sort();
+        pushes
+    }
+}
+
+impl EPrimitives for PushRecorder {
+    fn send_declare(&self, _ctx: RoutingContext<Declare>) {}
+
+    fn send_push(&self, msg: zenoh_protocol::network::Push) {
+        zlock!(self.pushes).push(msg.wire_expr.suffix.to_string());
+    }
+
+    fn send_request(&self, _ctx: RoutingContext<zenoh_protocol::network::Request>) {}
+
+    fn send_response(&self, _ctx: RoutingContext<zenoh_protocol::network::Response>) {}
+
+    fn send_response_final(&self, _ctx: RoutingContext<zenoh_protocol::network::ResponseFinal>) {}
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[test]
+fn retained_test() {
+    use zenoh_protocol::network::Push;
+    use zenoh_protocol::zenoh::Del;
+
+    let mut config = Config::default();
+    config
+        .insert_json5("routing/router/retained", r#"["test/retained/**"]"#)
+        .unwrap();
+    config
+        .insert_json5("routing/router/retained_max_keys", "2")
+        .unwrap();
+    let router = Router::new(
+        ZenohId::try_from([1]).unwrap(),
+        WhatAmI::Router,
+        Some(Arc::new(HLC::default())),
+        &config,
+    )
+    .unwrap();
+
+    let publisher = router.new_primitives(Arc::new(DummyPrimitives {}));
+    let publish = |expr: &str, payload: PushBody| {
+        Primitives::send_push(
+            publisher.as_ref(),
+            Push {
+                wire_expr: WireExpr::from(expr).to_owned(),
+                ext_qos: ext::QoSType::push_default(),
+                ext_tstamp: None,
+                ext_nodeid: ext::NodeIdType::default(),
+                ext_hoplimit: None,
+                payload,
+            },
+        )
+    };
+    let put = || {
+        PushBody::Put(Put {
+            timestamp: None,
+            encoding: Encoding::default(),
+            ext_sinfo: None,
+            #[cfg(feature = "shared-memory")]
+            ext_shm: None,
+            ext_unknown: vec![],
+            payload: ZBuf::empty(),
+            ext_attachment: None,
+            ext_tbound: None,
+        })
+    };
+    let subscriber = Arc::new(PushRecorder::default());
+    let face = router.new_primitives(subscriber.clone());
+    let subscribe = |id: u32, expr: &str| {
+        Primitives::send_declare(
+            face.as_ref(),
+            Declare {
+                ext_qos: ext::QoSType::declare_default(),
+                ext_tstamp: None,
+                ext_nodeid: ext::NodeIdType::default(),
+                body: DeclareBody::DeclareSubscriber(DeclareSubscriber {
+                    id,
+                    wire_expr: WireExpr::from(expr).to_owned(),
+                    ext_info: SubscriberInfo::default(),
+                }),
+            },
+        )
+    };
+
+    // Only the configured key expressions are retained, up to retained_max_keys
+    publish("test/retained/a", put());
+    publish("test/retained/b", put());
+    publish("test/retained/c", put());
+    publish("test/other", put());
+    subscribe(1, "test/**");
+    assert_eq!(subscriber.take(), ["test/retained/a", "test/retained/b"]);
+
+    // The retained publications are not sent again when the subscription is redeclared
+    subscribe(1, "test/**");
+    assert!(subscriber.take().is_empty());
+
+    // The publications on the retained key expressions are still updated once the limit is reached,
+    // and a deletion removes the retained publication
+    publish("test/retained/a", put());
+    publish(
+        "test/retained/b",
+        PushBody::Del(Del {
+            timestamp: None,
+            ext_sinfo: None,
+            ext_attachment: None,
+            ext_tbound: None,
+            ext_unknown: vec![],
+        }),
+    );
+    publish("test/retained/c", put());
+    subscriber.take();
+    subscribe(2, "test/retained/*");
+    assert_eq!(subscriber.take(), ["test/retained/a", "test/retained/c"]);
+}
+
+struct PushForwarder(Arc<Face>);
+
+impl EPrimitives for PushForwarder {
+    fn send_declare(&self, _ctx: RoutingContext<Declare>) {}
+
+    fn send_push(&self, msg: zenoh_protocol::network::Push) {
+        Primitives::send_push(self.0.as_ref(), msg)
+    }
+
+    fn send_request(&self, _ctx: RoutingContext<zenoh_protocol::network::Request>) {}
+
+    fn send_response(&self, _ctx: RoutingContext<zenoh_protocol::network::Response>) {}
+
+    fn send_response_final(&self, _ctx: RoutingContext<zenoh_protocol::network::ResponseFinal>) {}
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[derive(Default)]
+struct HopLimitRecorder {
+    hops: std::sync::Mutex<Vec<Option<u8>>>,
+}
+
+impl HopLimitRecorder {
+    fn take(&self) -> Vec<Option<u8>> {
+        std::mem::take(&mut *zlock!(self.hops))
+    }
+}
+
+impl EPrimitives for HopLimitRecorder {
+    fn send_declare(&self, _ctx: RoutingContext<Declare>) {}
+
+    fn send_push(&self, msg: zenoh_protocol::network::Push) {
+        zlock!(self.hops).push(msg.ext_hoplimit.map(|h| h.hops));
+    }
+
+    fn send_request(&self, _ctx: RoutingContext<zenoh_protocol::network::Request>) {}
+
+    fn send_response(&self, _ctx: RoutingContext<zenoh_protocol::network::Response>) {}
+
+    fn send_response_final(&self, _ctx: RoutingContext<zenoh_protocol::network::ResponseFinal>) {}
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[test]
+fn hop_limit_test() {
+    use zenoh_protocol::network::{push::ext::HopLimitType, Push};
+
+    let new_router = |zid: u8| {
+        Router::new(
+            ZenohId::try_from([zid]).unwrap(),
+            WhatAmI::Client,
+            Some(Arc::new(HLC::default())),
+            &Config::default(),
+        )
+        .unwrap()
+    };
+    // A face of the remote node zid
+    let remote_face = |router: &Router, zid: u8, primitives: Arc<dyn EPrimitives + Send + Sync>| {
+        let face = router.new_primitives(primitives);
+        let mut state = face.state.clone();
+        let state = zenoh_sync::get_mut_unchecked(&mut state);
+        state.zid = ZenohId::try_from([zid]).unwrap();
+        face
+    };
+    let subscribe = |face: &Face| {
+        Primitives::send_declare(
+            face,
+            Declare {
+                ext_qos: ext::QoSType::declare_default(),
+                ext_tstamp: None,
+                ext_nodeid: ext::NodeIdType::default(),
+                body: DeclareBody::DeclareSubscriber(DeclareSubscriber {
+                    id: 1,
+                    wire_expr: WireExpr::from("test/hops").to_owned(),
+                    ext_info: SubscriberInfo::default(),
+                }),
+            },
+        )
+    };
+
+    // publisher -> A -> B -> subscriber, B also serving a local subscriber
+    let router_a = new_router(1);
+    let router_b = new_router(2);
+    let b_from_a = remote_face(&router_b, 1, Arc::new(DummyPrimitives {}));
+    let a_to_b = remote_face(&router_a, 2, Arc::new(PushForwarder(b_from_a)));
+    subscribe(a_to_b.as_ref());
+    let remote = Arc::new(HopLimitRecorder::default());
+    let b_to_sub = remote_face(&router_b, 3, remote.clone());
+    subscribe(b_to_sub.as_ref());
+    let local = Arc::new(HopLimitRecorder::default());
+    let b_local = router_b.new_primitives(local.clone());
+    subscribe(b_local.as_ref());
+
+    let publisher = router_a.new_primitives(Arc::new(DummyPrimitives {}));
+    let publish = |hops: Option<u8>| {
+        Primitives::send_push(
+            publisher.as_ref(),
+            Push {
+                wire_expr: WireExpr::from("test/hops").to_owned(),
+                ext_qos: ext::QoSType::push_default(),
+                ext_tstamp: None,
+                ext_nodeid: ext::NodeIdType::default(),
+                ext_hoplimit: hops.map(HopLimitType::new),
+                payload: PushBody::Put(Put {
+                    timestamp: None,
+                    encoding: Encoding::default(),
+                    ext_sinfo: None,
+                    #[cfg(feature = "shared-memory")]
+                    ext_shm: None,
+                    ext_unknown: vec![],
+                    payload: ZBuf::empty(),
+                    ext_attachment: None,
+                    ext_tbound: None,
+                }),
+            },
+        );
+        (remote.take(), local.take())
+    };
+
+    // The hop limit is decremented on each link
+    assert_eq!(publish(Some(2)), (vec![Some(0)], vec![Some(1)]));
+    // and the data is not forwarded to the remote nodes once exhausted, only to the local sessions
+    assert_eq!(publish(Some(1)), (vec![], vec![Some(0)]));
+    assert_eq!(publish(Some(0)), (vec![], vec![]));
+    // Without hop limit, the data is forwarded as before
+    assert_eq!(publish(None), (vec![None], vec![None]));
+}
@@ -0,0 +1,252 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Reordering of the samples sent by the publishers with ordered delivery.
+//!
+//! Those publishers tag their samples with a per-publisher sequence number. The samples
+//! received out of order are held until the missing ones are received. A missing sample
+//! is considered lost, and the held samples delivered, when too many samples are held
+//! or when the held samples waited for too long. The wait is checked upon reception and
+//! periodically with [`Reorderer::expire`], which also forgets the idle publishers.
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use zenoh_protocol::core::ZenohId;
+
+/// The maximum number of samples held per publisher.
+pub(crate) const ORDERING_CAPACITY: usize = 256;
+/// The maximum time a sample is held waiting for a missing one.
+pub(crate) const ORDERING_TIMEOUT: Duration = Duration::from_millis(100);
+/// The time after which a publisher without any sample held is forgotten.
+pub(crate) const ORDERING_SOURCE_TIMEOUT: Duration = Duration::from_secs(60);
+
+struct Source<T> {
+    // The next expected sequence number, unwrapped to 64 bits
+    next: u64,
+    pending: BTreeMap<u64, (Instant, T)>,
+    // The time of the last sample received
+    last: Instant,
+}
+
+impl<T> Source<T> {
+    // Deliver the samples following the last delivered one, skipping the missing samples
+    // once too many samples are held or they waited for too long
+    fn release(&mut self, now: Instant, out: &mut Vec<T>) {
+        loop {
+            while let Some(entry) = self.pending.first_entry() {
+                if *entry.key() != self.next {
+                    break;
+                }
+                out.push(entry.remove().1);
+                self.next += 1;
+            }
+            match self.pending.first_key_value() {
+                Some((sn, (t, _)))
+                    if self.pending.len() > ORDERING_CAPACITY
+                        || now.duration_since(*t) > ORDERING_TIMEOUT =>
+                {
+                    // Skip the missing samples
+                    self.next = *sn;
+                }
+                _ => break,
+            }
+        }
+    }
+}
+
+pub(crate) struct Reorderer<T> {
+    sources: HashMap<(ZenohId, u32), Source<T>>,
+}
+
+impl<T> Default for Reorderer<T> {
+    fn default() -> Self {
+        Self {
+            sources: HashMap::new(),
+        }
+    }
+}
+
+impl<T> Reorderer<T> {
+    /// Push a sample of the publisher `eid` of `zid` and returns the samples that
+    /// can be delivered, in publication order.
+    pub(crate) fn push(&mut self, zid: ZenohId, eid: u32, sn: u32, sample: T) -> Vec<T> {
+        let now = Instant::now();
+        let source = self.sources.entry((zid, eid)).or_insert_with(|| Source {
+            next: sn as u64,
+            pending: BTreeMap::new(),
+            last: now,
+        });
+        source.last = now;
+
+        let mut out = vec![];
+        let distance = sn.wrapping_sub(source.next as u32) as i32;
+        if distance < 0 {
+            if distance.unsigned_abs() as usize <= ORDERING_CAPACITY {
+                // A duplicate or a sample already considered lost
+                return out;
+            }
+            // The publisher has been redeclared: start over
+            out.extend(
+                std::mem::take(&mut source.pending)
+                    .into_values()
+                    .map(|(_, s)| s),
+            );
+            source.next = sn as u64;
+        }
+        let sn = source.next + sn.wrapping_sub(source.next as u32) as u64;
+        source.pending.insert(sn, (now, sample));
+        source.release(now, &mut out);
+        out
+    }
+
+    /// Returns the samples held for longer than [`ORDERING_TIMEOUT`] that can be delivered,
+    /// considering the missing ones lost, and forgets the publishers that sent no sample for
+    /// [`ORDERING_SOURCE_TIMEOUT`].
+    pub(crate) fn expire(&mut self, now: Instant) -> Vec<T> {
+        let mut out = vec![];
+        self.sources.retain(|_, source| {
+            source.release(now, &mut out);
+            !source.pending.is_empty() || now.duration_since(source.last) < ORDERING_SOURCE_TIMEOUT
+        });
+        out
+    }
+
+    /// Returns true if no publisher is tracked.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+}
+
+/// The delivery of the samples of the publishers with ordered delivery.
+///
+/// The samples are delivered outside of the lock protecting this structure, by a single
+/// thread at a time, so that the samples released by concurrent receptions are not reordered.
+pub(crate) struct OrderedDelivery<T> {
+    pub(crate) reorderer: Reorderer<T>,
+    ready: VecDeque<T>,
+    delivering: bool,
+    // Whether the task expiring the held samples is running
+    pub(crate) expiring: bool,
+}
+
+impl<T> Default for OrderedDelivery<T> {
+    fn default() -> Self {
+        Self {
+            reorderer: Reorderer::default(),
+            ready: VecDeque::new(),
+            delivering: false,
+            expiring: false,
+        }
+    }
+}
+
+impl<T> OrderedDelivery<T> {
+    /// Queue samples to be delivered.
+    pub(crate) fn queue(&mut self, samples: Vec<T>) {
+        self.ready.extend(samples);
+    }
+
+    /// Returns true if the caller must deliver the queued samples with [`take_ready`](Self::take_ready),
+    /// false if there is nothing to deliver or if another thread is delivering them.
+    pub(crate) fn start_delivery(&mut self) -> bool {
+        if self.delivering || self.ready.is_empty() {
+            return false;
+        }
+        self.delivering = true;
+        true
+    }
+
+    /// Returns the samples to deliver, or None once all of them have been delivered.
+    pub(crate) fn take_ready(&mut self) -> Option<VecDeque<T>> {
+        if self.ready.is_empty() {
+            self.delivering = false;
+            return None;
+        }
+        Some(std::mem::take(&mut self.ready))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reorder() {
+        let zid = ZenohId::rand();
+        let mut reorderer = Reorderer::default();
+        assert_eq!(reorderer.push(zid, 0, 10, 10), vec![10]);
+        assert!(reorderer.push(zid, 0, 12, 12).is_empty());
+        assert!(reorderer.push(zid, 0, 13, 13).is_empty());
+        // Another publisher is not affected
+        assert_eq!(reorderer.push(zid, 1, 5, 5), vec![5]);
+        assert_eq!(reorderer.push(zid, 0, 11, 11), vec![11, 12, 13]);
+        // Duplicates are dropped
+        assert!(reorderer.push(zid, 0, 12, 12).is_empty());
+        // Sequence numbers wrap around
+        let mut reorderer = Reorderer::default();
+        assert_eq!(reorderer.push(zid, 0, u32::MAX, 0), vec![0]);
+        assert!(reorderer.push(zid, 0, 1, 2).is_empty());
+        assert_eq!(reorderer.push(zid, 0, 0, 1), vec![1, 2]);
+    }
+
+    #[test]
+    fn reorder_lost() {
+        let zid = ZenohId::rand();
+        let mut reorderer = Reorderer::default();
+        assert_eq!(reorderer.push(zid, 0, 0, 0), vec![0]);
+        for sn in 2..=ORDERING_CAPACITY as u32 + 1 {
+            assert!(reorderer.push(zid, 0, sn, sn).is_empty());
+        }
+        // Sample 1 is considered lost once the capacity is exceeded
+        let out = reorderer.push(zid, 0, ORDERING_CAPACITY as u32 + 2, 0);
+        assert_eq!(out.len(), ORDERING_CAPACITY + 1);
+        assert_eq!(out[0], 2);
+    }
+
+    #[test]
+    fn reorder_expire() {
+        let zid = ZenohId::rand();
+        let mut reorderer = Reorderer::default();
+        let start = Instant::now();
+        assert_eq!(reorderer.push(zid, 0, 0, 0), vec![0]);
+        assert!(reorderer.push(zid, 0, 2, 2).is_empty());
+        assert!(reorderer.push(zid, 0, 3, 3).is_empty());
+
+        // The held samples are delivered once they waited for too long, without a new reception
+        assert!(reorderer.expire(start).is_empty());
+        let out = reorderer.expire(start + ORDERING_TIMEOUT * 2);
+        assert_eq!(out, vec![2, 3]);
+        assert!(!reorderer.is_empty());
+
+        // The idle publishers are forgotten
+        assert!(reorderer
+            .expire(start + ORDERING_SOURCE_TIMEOUT * 2)
+            .is_empty());
+        assert!(reorderer.is_empty());
+    }
+
+    #[test]
+    fn ordered_delivery() {
+        let mut delivery = OrderedDelivery::default();
+        assert!(!delivery.start_delivery());
+        delivery.queue(vec![0, 1]);
+        assert!(delivery.start_delivery());
+        // Another thread queuing samples while the first one delivers does not deliver them
+        delivery.queue(vec![2]);
+        assert!(!delivery.start_delivery());
+        assert_eq!(delivery.take_ready(), Some(VecDeque::from([0, 1, 2])));
+        assert_eq!(delivery.take_ready(), None);
+        delivery.queue(vec![3]);
+        assert!(delivery.start_delivery());
+    }
+}
@@ -13,9 +13,23 @@
 //
 use super::sealed::{PluginsManager, PLUGIN_PREFIX};
 use crate::runtime::Runtime;
+use std::collections::HashMap;
+use std::time::Duration;
 use zenoh_config::{Config, PluginLoad};
+use zenoh_core::zconfigurable;
+use zenoh_plugin_trait::PluginReportLevel;
 use zenoh_result::ZResult;
 
+zconfigurable! {
+    // Amount of time in milliseconds to wait before retrying to start the plugins.
+    #[mutable]
+    static ref PLUGIN_START_RETRY_DELAY: u64 = 1_000;
+}
+
+pub(crate) fn register_configurables() {
+    zenoh_core::configurable::register(&PLUGIN_START_RETRY_DELAY);
+}
+
 pub(crate) fn load_plugin(
     plugin_mgr: &mut PluginsManager,
     name: &str,
@@ -51,6 +65,7 @@ pub(crate) fn load_plugins(config: &Config) -> PluginsManager {
             name,
             paths,
             required,
+            ..
         } = plugin_load;
         tracing::info!(
             "Loading {req} plugin \"{name}\"",
@@ -67,51 +82,315 @@ pub(crate) fn load_plugins(config: &Config) -> PluginsManager {
     manager
 }
 
-pub(crate) fn start_plugins(runtime: &Runtime) {
-    let mut manager = runtime.plugins_manager();
-    for plugin in manager.loaded_plugins_iter_mut() {
-        let required = plugin.required();
+// The readiness of the dependencies of a plugin
+#[derive(Debug)]
+enum Dependencies {
+    Ready,
+    NotReady(String),
+    Unavailable(String),
+}
+
+// The plugins to start, as seen when ordering their starts
+trait PluginStarter {
+    // Whether the plugin is loaded
+    fn loaded(&self, name: &str) -> bool;
+    // Whether the plugin is started and ready
+    fn ready(&self, name: &str) -> bool;
+    fn start(&mut self, name: &str) -> Result<(), String>;
+}
+
+struct ManagerStarter<'a> {
+    manager: &'a mut PluginsManager,
+    runtime: &'a Runtime,
+}
+
+impl PluginStarter for ManagerStarter<'_> {
+    fn loaded(&self, name: &str) -> bool {
+        self.manager.loaded_plugin(name).is_some()
+    }
+
+    // A started plugin is ready unless its status reports an error
+    fn ready(&self, name: &str) -> bool {
+        self.manager.started_plugin(name).map_or(false, |started| {
+            started.as_status().report().get_level() != PluginReportLevel::Error
+        })
+    }
+
+    fn start(&mut self, name: &str) -> Result<(), String> {
+        let Some(plugin) = self.manager.loaded_plugin_mut(name) else {
+            return Err("plugin is not loaded".to_string());
+        };
         tracing::info!(
             "Starting {req} plugin \"{name}\"",
-            req = if required { "required" } else { "" },
-            name = plugin.name()
+            req = if plugin.required() { "required" } else { "" },
         );
-        match plugin.start(runtime) {
+        match plugin.start(self.runtime) {
             Ok(_) => {
                 tracing::info!(
                     "Successfully started plugin {} from {:?}",
                     plugin.name(),
                     plugin.path()
                 );
+                Ok(())
             }
-            Err(e) => {
-                let report = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| e.to_string())) {
-                        Ok(s) => s,
-                        Err(_) => panic!("Formatting the error from plugin {} ({:?}) failed, this is likely due to ABI unstability.\r\nMake sure your plugin was built with the same version of cargo as zenohd", plugin.name(), plugin.path()),
-                    };
-                if required {
-                    panic!(
-                        "Plugin \"{}\" failed to start: {}",
-                        plugin.name(),
-                        if report.is_empty() {
-                            "no details provided"
-                        } else {
-                            report.as_str()
-                        }
-                    );
-                } else {
-                    tracing::error!(
-                        "Required plugin \"{}\" failed to start: {}",
-                        plugin.name(),
-                        if report.is_empty() {
-                            "no details provided"
-                        } else {
-                            report.as_str()
-                        }
-                    );
+            Err(e) => Err(start_error(name, plugin.path(), e)),
+        }
+    }
+}
+
+fn dependencies<S: PluginStarter>(
+    starter: &S,
+    depends: &[String],
+    failed: &[&str],
+) -> Dependencies {
+    for dependency in depends {
+        if failed.contains(&dependency.as_str()) || !starter.loaded(dependency) {
+            return Dependencies::Unavailable(format!(
+                "dependency `{dependency}` is not available"
+            ));
+        }
+        if !starter.ready(dependency) {
+            return Dependencies::NotReady(format!("dependency `{dependency}` is not ready"));
+        }
+    }
+    Dependencies::Ready
+}
+
+fn start_error(plugin: &str, path: &str, e: zenoh_result::Error) -> String {
+    let report = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| e.to_string())) {
+        Ok(s) => s,
+        Err(_) => panic!("Formatting the error from plugin {} ({:?}) failed, this is likely due to ABI unstability.\r\nMake sure your plugin was built with the same version of cargo as zenohd", plugin, path),
+    };
+    if report.is_empty() {
+        "no details provided".to_string()
+    } else {
+        report
+    }
+}
+
+// Attempts once to start the pending plugins, with their remaining attempts, whose dependencies
+// are ready. Returns the plugins left to start and whether any plugin was started.
+fn start_round<S: PluginStarter>(
+    starter: &mut S,
+    depends: &HashMap<String, Vec<String>>,
+    pending: Vec<(String, u32)>,
+    failed: &mut Vec<(String, String)>,
+) -> (Vec<(String, u32)>, bool) {
+    let mut progress = false;
+    let mut waiting = vec![];
+    for (name, mut attempts) in pending {
+        if !starter.loaded(&name) {
+            continue;
+        }
+        let depends = depends.get(&name).map(Vec::as_slice).unwrap_or_default();
+        let failed_names: Vec<&str> = failed.iter().map(|(n, _)| n.as_str()).collect();
+        match dependencies(starter, depends, &failed_names) {
+            Dependencies::Unavailable(reason) => failed.push((name, reason)),
+            Dependencies::NotReady(reason) => waiting.push((name, attempts, false, reason)),
+            Dependencies::Ready => match starter.start(&name) {
+                Ok(()) => progress = true,
+                Err(reason) => {
+                    attempts -= 1;
+                    if attempts == 0 {
+                        failed.push((name, reason));
+                    } else {
+                        tracing::warn!("Plugin \"{}\" failed to start: {}", name, reason);
+                        waiting.push((name, attempts, true, reason));
+                    }
+                }
+            },
+        }
+    }
+
+    let mut pending = vec![];
+    for (name, mut attempts, attempted, reason) in waiting {
+        // Without progress, the plugins waiting for their dependencies consume an attempt
+        if !progress && !attempted {
+            attempts -= 1;
+        }
+        if attempts == 0 {
+            failed.push((name, reason));
+        } else {
+            pending.push((name, attempts));
+        }
+    }
+    (pending, progress)
+}
+
+/// Start the loaded plugins, each of them once the plugins it depends on (`__depends__`) are
+/// started and ready. The start of a plugin is retried `__retries__` times should it fail or
+/// should its dependencies not be ready yet, waiting [`PLUGIN_START_RETRY_DELAY`] between attempts.
+pub(crate) async fn start_plugins(runtime: &Runtime) {
+    let requests: HashMap<String, PluginLoad> = runtime
+        .config()
+        .lock()
+        .plugins()
+        .load_requests()
+        .map(|r| (r.name.clone(), r))
+        .collect();
+    let depends: HashMap<String, Vec<String>> = requests
+        .iter()
+        .map(|(name, r)| (name.clone(), r.depends.clone()))
+        .collect();
+
+    // The plugins left to start with their remaining attempts, in declaration order
+    let mut pending: Vec<(String, u32)> = runtime
+        .plugins_manager()
+        .loaded_plugins_iter()
+        .map(|plugin| {
+            let retries = requests.get(plugin.name()).map_or(0, |r| r.retries);
+            (plugin.name().to_string(), retries.saturating_add(1))
+        })
+        .collect();
+    let mut failed: Vec<(String, String)> = vec![];
+    while !pending.is_empty() {
+        let progress;
+        // The plugins manager is not locked while waiting
+        {
+            let mut manager = runtime.plugins_manager();
+            let mut starter = ManagerStarter {
+                manager: &mut manager,
+                runtime,
+            };
+            (pending, progress) = start_round(&mut starter, &depends, pending, &mut failed);
+        }
+        if !progress && !pending.is_empty() {
+            tokio::time::sleep(Duration::from_millis(PLUGIN_START_RETRY_DELAY.get())).await;
+        }
+    }
+
+    let manager = runtime.plugins_manager();
+    for (name, reason) in failed {
+        let required = manager
+            .loaded_plugin(&name)
+            .map_or(false, |plugin| plugin.required());
+        if required {
+            panic!("Required plugin \"{}\" failed to start: {}", name, reason);
+        } else {
+            tracing::error!("Plugin \"{}\" failed to start: {}", name, reason);
+        }
+    }
+    tracing::info!("Finished loading plugins");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct MockStarter {
+        loaded: Vec<&'static str>,
+        // The number of times each plugin fails to start before starting
+        failures: HashMap<&'static str, u32>,
+        started: Vec<String>,
+    }
+
+    impl PluginStarter for MockStarter {
+        fn loaded(&self, name: &str) -> bool {
+            self.loaded.contains(&name)
+        }
+
+        fn ready(&self, name: &str) -> bool {
+            self.started.iter().any(|s| s == name)
+        }
+
+        fn start(&mut self, name: &str) -> Result<(), String> {
+            match self.failures.get_mut(name) {
+                Some(n) if *n > 0 => {
+                    *n -= 1;
+                    Err("failure".to_string())
+                }
+                _ => {
+                    self.started.push(name.to_string());
+                    Ok(())
                 }
             }
         }
-        tracing::info!("Finished loading plugins");
+    }
+
+    // Runs the start rounds of the plugins (name, retries, depends) until none is pending,
+    // returning the number of rounds and the failed plugins
+    fn run(starter: &mut MockStarter, plugins: &[(&str, u32, &[&str])]) -> (usize, Vec<String>) {
+        let depends = plugins
+            .iter()
+            .map(|(name, _, d)| (name.to_string(), d.iter().map(|d| d.to_string()).collect()))
+            .collect();
+        let mut pending: Vec<(String, u32)> = plugins
+            .iter()
+            .map(|(name, retries, _)| (name.to_string(), retries + 1))
+            .collect();
+        let mut failed = vec![];
+        let mut rounds = 0;
+        while !pending.is_empty() {
+            rounds += 1;
+            assert!(rounds < 100);
+            (pending, _) = start_round(starter, &depends, pending, &mut failed);
+        }
+        (rounds, failed.into_iter().map(|(name, _)| name).collect())
+    }
+
+    #[test]
+    fn plugins_start_order() {
+        // The plugins are started after their dependencies, whatever their declaration order
+        let mut starter = MockStarter {
+            loaded: vec!["a", "b", "c"],
+            ..Default::default()
+        };
+        let (_, failed) = run(
+            &mut starter,
+            &[("a", 0, &["b"]), ("b", 0, &["c"]), ("c", 0, &[])],
+        );
+        assert!(failed.is_empty());
+        assert_eq!(starter.started, ["c", "b", "a"]);
+
+        // A failed start is retried
+        let mut starter = MockStarter {
+            loaded: vec!["a", "b"],
+            failures: HashMap::from([("b", 1)]),
+            ..Default::default()
+        };
+        let (_, failed) = run(&mut starter, &[("a", 1, &["b"]), ("b", 1, &[])]);
+        assert!(failed.is_empty());
+        assert_eq!(starter.started, ["b", "a"]);
+
+        // and the plugin fails once out of retries, as do the plugins depending on it
+        let mut starter = MockStarter {
+            loaded: vec!["a", "b"],
+            failures: HashMap::from([("b", 2)]),
+            ..Default::default()
+        };
+        let (_, failed) = run(&mut starter, &[("a", 1, &["b"]), ("b", 1, &[])]);
+        assert_eq!(failed, ["b", "a"]);
+        assert!(starter.started.is_empty());
+    }
+
+    #[test]
+    fn plugins_start_missing() {
+        // A plugin depending on a plugin not loaded is not started, the others are
+        let mut starter = MockStarter {
+            loaded: vec!["a", "b"],
+            ..Default::default()
+        };
+        let (_, failed) = run(&mut starter, &[("a", 3, &["x"]), ("b", 0, &[])]);
+        assert_eq!(failed, ["a"]);
+        assert_eq!(starter.started, ["b"]);
+    }
+
+    #[test]
+    fn plugins_start_cycle() {
+        // The plugins depending on each other fail once out of retries, the others are started
+        let mut starter = MockStarter {
+            loaded: vec!["a", "b", "c"],
+            ..Default::default()
+        };
+        let (rounds, mut failed) = run(
+            &mut starter,
+            &[("a", 2, &["b"]), ("b", 2, &["a"]), ("c", 0, &[])],
+        );
+        failed.sort();
+        assert_eq!(failed, ["a", "b"]);
+        assert_eq!(starter.started, ["c"]);
+        // The first round starts c: the 3 attempts are consumed by the rounds without progress
+        assert_eq!(rounds, 4);
     }
 }
@@ -23,14 +23,22 @@ use crate::prelude::*;
 use crate::sample::Attachment;
 use crate::sample::DataInfo;
 use crate::sample::QoS;
+use crate::stats::EntityStats;
+#[zenoh_macros::unstable]
+use crate::stats::EntityStatsReport;
+#[zenoh_macros::unstable]
+use crate::time::Timestamp;
 use crate::Encoding;
 use crate::SessionRef;
 use crate::Undeclarable;
 use std::future::Ready;
-use zenoh_core::{zread, AsyncResolve, Resolvable, Resolve, SyncResolve};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use zenoh_core::{zlock, zread, AsyncResolve, Resolvable, Resolve, SyncResolve};
 use zenoh_protocol::network::push::ext;
 use zenoh_protocol::network::Mapping;
 use zenoh_protocol::network::Push;
+use zenoh_protocol::zenoh::ext::{SourceInfoType, TimestampBoundType};
 use zenoh_protocol::zenoh::Del;
 use zenoh_protocol::zenoh::PushBody;
 use zenoh_protocol::zenoh::Put;
@@ -82,19 +90,25 @@ pub type DeleteBuilder<'a, 'b> = PutBuilder<'a, 'b>;
 pub struct PutBuilder<'a, 'b> {
     pub(crate) publisher: PublisherBuilder<'a, 'b>,
     pub(crate) value: Value,
+    pub(crate) encoding: Option<Encoding>,
     pub(crate) kind: SampleKind,
     #[cfg(feature = "unstable")]
     pub(crate) attachment: Option<Attachment>,
+    #[cfg(feature = "unstable")]
+    pub(crate) timestamp: Option<Timestamp>,
 }
 
 impl PutBuilder<'_, '_> {
     /// Change the encoding of the written data.
+    ///
+    /// An explicit encoding, even "application/octet-stream", is never replaced by the
+    /// configured `publishing/default_encoding`.
     #[inline]
     pub fn encoding<IntoEncoding>(mut self, encoding: IntoEncoding) -> Self
     where
         IntoEncoding: Into<Encoding>,
     {
-        self.value.encoding = encoding.into();
+        self.encoding = Some(encoding.into());
         self
     }
     /// Change the `congestion_control` to apply when routing the data.
@@ -120,6 +134,15 @@ impl PutBuilder<'_, '_> {
         self
     }
 
+    /// Limit the number of links the written data may be forwarded on.
+    /// See [`PublisherBuilder::hop_limit`].
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn hop_limit(mut self, hops: u8) -> Self {
+        self.publisher = self.publisher.hop_limit(hops);
+        self
+    }
+
     pub fn kind(mut self, kind: SampleKind) -> Self {
         self.kind = kind;
         self
@@ -130,6 +153,14 @@ impl PutBuilder<'_, '_> {
         self.attachment = Some(attachment);
         self
     }
+
+    /// Publish the data with the given [`Timestamp`] instead of a newly generated one
+    /// (e.g. when replaying data that was produced earlier).
+    #[zenoh_macros::unstable]
+    pub fn with_timestamp(mut self, timestamp: Timestamp) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
 }
 
 impl Resolvable for PutBuilder<'_, '_> {
@@ -145,6 +176,8 @@ impl SyncResolve for PutBuilder<'_, '_> {
             congestion_control,
             priority,
             destination,
+            hop_limit,
+            ..
         } = self.publisher;
 
         let publisher = Publisher {
@@ -153,14 +186,19 @@ impl SyncResolve for PutBuilder<'_, '_> {
             congestion_control,
             priority,
             destination,
+            hop_limit,
+            state: None,
         };
 
         resolve_put(
             &publisher,
             self.value,
+            self.encoding,
             self.kind,
             #[cfg(feature = "unstable")]
             self.attachment,
+            #[cfg(feature = "unstable")]
+            self.timestamp,
         )
     }
 }
@@ -247,6 +285,181 @@ pub struct Publisher<'a> {
     pub(crate) congestion_control: CongestionControl,
     pub(crate) priority: Priority,
     pub(crate) destination: Locality,
+    pub(crate) hop_limit: Option<u8>,
+    pub(crate) state: Option<Arc<PublisherState>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct PublisherState {
+    pub(crate) id: Id,
+    pub(crate) key_expr: KeyExpr<'static>,
+    pub(crate) stats: EntityStats,
+    // The congestion control and priority of the publisher, for its discovery
+    pub(crate) qos: std::sync::Mutex<(CongestionControl, Priority)>,
+    // The sequence number of the next sample, when ordered delivery is enabled
+    pub(crate) sn: Option<AtomicU32>,
+    // Tracks the matching subscribers, when write filtering is enabled
+    #[cfg(feature = "unstable")]
+    pub(crate) write_filter: std::sync::OnceLock<Arc<MatchingListenerState>>,
+    #[cfg(feature = "unstable")]
+    pub(crate) congestion: PublisherCongestion,
+}
+
+/// The event notified to the callbacks registered with [`Publisher::on_congestion()`]
+/// when the transmission queues of a node the publications go through exceed their memory budget
+/// (see `transport/link/tx/memory_budget` in the configuration), or fall back below it.
+#[zenoh_macros::unstable]
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CongestionEvent {
+    /// Whether the node is congested, or recovered from a congestion.
+    pub congested: bool,
+    /// The [`ZenohId`] of the congested node: the node of the session, one of its neighbours,
+    /// or a neighbour of the router or peer a client session is connected to.
+    pub zid: ZenohId,
+}
+
+// The duration of congestion after which the throttling rate of a publisher is halved
+#[cfg(feature = "unstable")]
+const THROTTLING_PERIOD: std::time::Duration = std::time::Duration::from_millis(100);
+// The maximum throttling rate of a publisher, as a power of 2: 1 publication out of 64 is sent
+#[cfg(feature = "unstable")]
+const MAX_THROTTLING_SHIFT: u32 = 6;
+// The duration after which a congestion that is not notified again is considered over
+#[cfg(feature = "unstable")]
+const CONGESTION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// The congestion state of a publisher, notified to its callbacks and throttling its publications.
+#[cfg(feature = "unstable")]
+pub(crate) struct PublisherCongestion {
+    callbacks: std::sync::Mutex<Vec<Callback<'static, CongestionEvent>>>,
+    throttling: bool,
+    // The congested nodes, with the beginning of their congestion and its last notification
+    congested: std::sync::Mutex<
+        std::collections::HashMap<ZenohId, (std::time::Instant, std::time::Instant)>,
+    >,
+    is_congested: std::sync::atomic::AtomicBool,
+    counter: std::sync::atomic::AtomicU64,
+}
+
+#[cfg(feature = "unstable")]
+impl PublisherCongestion {
+    pub(crate) fn new(throttling: bool) -> Self {
+        Self {
+            callbacks: std::sync::Mutex::new(Vec::new()),
+            throttling,
+            congested: std::sync::Mutex::new(std::collections::HashMap::new()),
+            is_congested: std::sync::atomic::AtomicBool::new(false),
+            counter: std::sync::atomic::AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn notify(&self, event: CongestionEvent) {
+        self.notify_at(event, std::time::Instant::now())
+    }
+
+    fn notify_at(&self, event: CongestionEvent, now: std::time::Instant) {
+        let (mut events, changed) = {
+            let mut congested = zlock!(self.congested);
+            let expired = self.expire(&mut congested, now);
+            // The callbacks are only notified of the beginning and the end of a congestion,
+            // not of the notifications refreshing it
+            let changed = if event.congested {
+                match congested.entry(event.zid) {
+                    std::collections::hash_map::Entry::Occupied(mut e) => {
+                        e.get_mut().1 = now;
+                        false
+                    }
+                    std::collections::hash_map::Entry::Vacant(e) => {
+                        e.insert((now, now));
+                        true
+                    }
+                }
+            } else {
+                congested.remove(&event.zid).is_some()
+            };
+            self.is_congested
+                .store(!congested.is_empty(), Ordering::Relaxed);
+            (expired, changed)
+        };
+        if changed {
+            events.push(event);
+        }
+        self.callback(events);
+    }
+
+    // Removes the congestions that were not notified again within the timeout,
+    // returning their recovery events
+    fn expire(
+        &self,
+        congested: &mut std::collections::HashMap<
+            ZenohId,
+            (std::time::Instant, std::time::Instant),
+        >,
+        now: std::time::Instant,
+    ) -> Vec<CongestionEvent> {
+        let mut expired = vec![];
+        congested.retain(|zid, (_, refreshed)| {
+            let alive = now.saturating_duration_since(*refreshed) < CONGESTION_TIMEOUT;
+            if !alive {
+                expired.push(CongestionEvent {
+                    congested: false,
+                    zid: *zid,
+                });
+            }
+            alive
+        });
+        expired
+    }
+
+    fn callback(&self, events: Vec<CongestionEvent>) {
+        if events.is_empty() {
+            return;
+        }
+        let callbacks = zlock!(self.callbacks).clone();
+        for event in events {
+            for callback in callbacks.iter() {
+                callback(event);
+            }
+        }
+    }
+
+    /// Whether the next publication is skipped: while congested, a throttling publisher sends
+    /// 1 publication out of 2, then out of 4, 8... as the congestion lasts, up to 1 out of 64.
+    pub(crate) fn throttle(&self) -> bool {
+        self.throttle_at(std::time::Instant::now())
+    }
+
+    fn throttle_at(&self, now: std::time::Instant) -> bool {
+        if !self.is_congested.load(Ordering::Relaxed) {
+            return false;
+        }
+        let (since, expired) = {
+            let mut congested = zlock!(self.congested);
+            let expired = self.expire(&mut congested, now);
+            self.is_congested
+                .store(!congested.is_empty(), Ordering::Relaxed);
+            (congested.values().map(|(since, _)| *since).min(), expired)
+        };
+        self.callback(expired);
+        let Some(since) = since.filter(|_| self.throttling) else {
+            return false;
+        };
+        let periods =
+            now.saturating_duration_since(since).as_millis() / THROTTLING_PERIOD.as_millis();
+        let shift = (periods + 1).min(MAX_THROTTLING_SHIFT as u128) as u32;
+        self.counter.fetch_add(1, Ordering::Relaxed) % (1 << shift) != 0
+    }
+}
+
+#[cfg(feature = "unstable")]
+impl std::fmt::Debug for PublisherCongestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PublisherCongestion")
+            .field("throttling", &self.throttling)
+            .field("congested", &*zlock!(self.congested))
+            .finish()
+    }
 }
 
 impl<'a> Publisher<'a> {
@@ -254,10 +467,61 @@ impl<'a> Publisher<'a> {
         &self.key_expr
     }
 
+    /// Returns a snapshot of the statistics of this `Publisher`.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let publisher = session.declare_publisher("key/expression").res().await.unwrap();
+    /// publisher.put("value").res().await.unwrap();
+    /// assert_eq!(publisher.stats().samples, 1);
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn stats(&self) -> EntityStatsReport {
+        self.state
+            .as_ref()
+            .map(|state| state.stats.report())
+            .unwrap_or_default()
+    }
+
+    /// Register a callback notified every time a node the publications of this `Publisher`
+    /// go through is congested, or recovers from a congestion.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let publisher = session.declare_publisher("key/expression").res().await.unwrap();
+    /// publisher.on_congestion(|event| {
+    ///     println!("Node {} congested: {}", event.zid, event.congested);
+    /// });
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn on_congestion<C>(&self, callback: C)
+    where
+        C: Fn(CongestionEvent) + Send + Sync + 'static,
+    {
+        if let Some(state) = &self.state {
+            zlock!(state.congestion.callbacks).push(Arc::new(callback));
+        }
+    }
+
     /// Change the `congestion_control` to apply when routing the data.
     #[inline]
     pub fn congestion_control(mut self, congestion_control: CongestionControl) -> Self {
         self.congestion_control = congestion_control;
+        if let Some(state) = &self.state {
+            zlock!(state.qos).0 = congestion_control;
+        }
         self
     }
 
@@ -265,6 +529,9 @@ impl<'a> Publisher<'a> {
     #[inline]
     pub fn priority(mut self, priority: Priority) -> Self {
         self.priority = priority;
+        if let Some(state) = &self.state {
+            zlock!(state.qos).1 = priority;
+        }
         self
     }
 
@@ -317,6 +584,7 @@ impl<'a> Publisher<'a> {
         Publication {
             publisher: self,
             value,
+            encoding: None,
             kind,
             #[cfg(feature = "unstable")]
             attachment: None,
@@ -601,6 +869,9 @@ impl SyncResolve for PublisherUndeclaration<'_> {
         session
             .undeclare_publication_intent(key_expr.clone())
             .res_sync()?;
+        if let Some(state) = self.publisher.state.take() {
+            self.publisher.session.undeclare_publisher_state(state.id);
+        }
         self.publisher.key_expr = unsafe { keyexpr::from_str_unchecked("") }.into();
         Ok(())
     }
@@ -622,6 +893,9 @@ impl Drop for Publisher<'_> {
                 .undeclare_publication_intent(self.key_expr.clone())
                 .res_sync();
         }
+        if let Some(state) = self.state.take() {
+            self.session.undeclare_publisher_state(state.id);
+        }
     }
 }
 
@@ -631,12 +905,26 @@ impl Drop for Publisher<'_> {
 pub struct Publication<'a> {
     publisher: &'a Publisher<'a>,
     value: Value,
+    encoding: Option<Encoding>,
     kind: SampleKind,
     #[cfg(feature = "unstable")]
     pub(crate) attachment: Option<Attachment>,
 }
 
 impl<'a> Publication<'a> {
+    /// Change the encoding of the written data.
+    ///
+    /// An explicit encoding, even "application/octet-stream", is never replaced by the
+    /// configured `publishing/default_encoding`.
+    #[inline]
+    pub fn encoding<IntoEncoding>(mut self, encoding: IntoEncoding) -> Self
+    where
+        IntoEncoding: Into<Encoding>,
+    {
+        self.encoding = Some(encoding.into());
+        self
+    }
+
     #[zenoh_macros::unstable]
     pub fn with_attachment(mut self, attachment: Attachment) -> Self {
         self.attachment = Some(attachment);
@@ -653,9 +941,12 @@ impl SyncResolve for Publication<'_> {
         resolve_put(
             self.publisher,
             self.value,
+            self.encoding,
             self.kind,
             #[cfg(feature = "unstable")]
             self.attachment,
+            #[cfg(feature = "unstable")]
+            None,
         )
     }
 }
@@ -721,6 +1012,10 @@ pub struct PublisherBuilder<'a, 'b: 'a> {
     pub(crate) congestion_control: CongestionControl,
     pub(crate) priority: Priority,
     pub(crate) destination: Locality,
+    pub(crate) ordered_delivery: bool,
+    pub(crate) write_filtering: bool,
+    pub(crate) congestion_throttling: bool,
+    pub(crate) hop_limit: Option<u8>,
 }
 
 impl<'a, 'b> Clone for PublisherBuilder<'a, 'b> {
@@ -734,6 +1029,10 @@ impl<'a, 'b> Clone for PublisherBuilder<'a, 'b> {
             congestion_control: self.congestion_control,
             priority: self.priority,
             destination: self.destination,
+            ordered_delivery: self.ordered_delivery,
+            write_filtering: self.write_filtering,
+            congestion_throttling: self.congestion_throttling,
+            hop_limit: self.hop_limit,
         }
     }
 }
@@ -761,6 +1060,57 @@ impl<'a, 'b> PublisherBuilder<'a, 'b> {
         self.destination = destination;
         self
     }
+
+    /// Guarantee that the subscribers observe the samples of this publisher in publication order.
+    ///
+    /// The samples are tagged with a per-publisher sequence number and the samples received out of
+    /// order (e.g. over multiple links or routes) are held by the subscribing sessions until the
+    /// missing ones are received. A missing sample is considered lost after a while, in which case
+    /// the held samples are delivered without it.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn ordered_delivery(mut self, ordered_delivery: bool) -> Self {
+        self.ordered_delivery = ordered_delivery;
+        self
+    }
+
+    /// Drop the publications locally while no subscriber matches them.
+    ///
+    /// The publisher tracks the subscribers declared to its session (the local ones and the remote ones
+    /// propagated by the routers and peers), and skips the encoding and the routing of its publications
+    /// while none of them matches its key expression. This saves resources for chatty publishers
+    /// whose data is rarely consumed.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn write_filtering(mut self, write_filtering: bool) -> Self {
+        self.write_filtering = write_filtering;
+        self
+    }
+
+    /// Throttle the publications while a node they go through is congested (see [`Publisher::on_congestion()`]).
+    ///
+    /// While congested, 1 publication out of 2 is sent, then 1 out of 4, 8... as the congestion lasts,
+    /// up to 1 out of 64. The full rate is restored once the congestion is over.
+    /// The publications with [`CongestionControl::Block`] are not throttled.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn congestion_throttling(mut self, congestion_throttling: bool) -> Self {
+        self.congestion_throttling = congestion_throttling;
+        self
+    }
+
+    /// Limit the number of links the published data may be forwarded on (default: unlimited).
+    ///
+    /// Each node forwarding the data to another node decrements its hop limit, and the data whose
+    /// hop limit is exhausted is only delivered to the local subscribers of the node.
+    /// For instance, a hop limit of 1 restricts the delivery to the nodes directly connected
+    /// to this session, and a hop limit of 0 to this session.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn hop_limit(mut self, hops: u8) -> Self {
+        self.hop_limit = Some(hops);
+        self
+    }
 }
 
 impl<'a, 'b> Resolvable for PublisherBuilder<'a, 'b> {
@@ -803,13 +1153,29 @@ impl<'a, 'b> SyncResolve for PublisherBuilder<'a, 'b> {
         self.session
             .declare_publication_intent(key_expr.clone())
             .res_sync()?;
+        let state = self.session.declare_publisher_state(
+            &key_expr,
+            self.congestion_control,
+            self.priority,
+            self.ordered_delivery,
+            self.congestion_throttling,
+        );
         let publisher = Publisher {
             session: self.session,
             key_expr,
             congestion_control: self.congestion_control,
             priority: self.priority,
             destination: self.destination,
+            hop_limit: self.hop_limit,
+            state: Some(state.clone()),
         };
+        #[cfg(feature = "unstable")]
+        if self.write_filtering {
+            let filter = publisher
+                .session
+                .declare_matches_listener_inner(&publisher, Arc::new(|_| {}))?;
+            let _ = state.write_filter.set(filter);
+        }
         tracing::trace!("publish({:?})", publisher.key_expr);
         Ok(publisher)
     }
@@ -826,16 +1192,83 @@ impl<'a, 'b> AsyncResolve for PublisherBuilder<'a, 'b> {
 fn resolve_put(
     publisher: &Publisher<'_>,
     value: Value,
+    encoding: Option<Encoding>,
     kind: SampleKind,
     #[cfg(feature = "unstable")] attachment: Option<Attachment>,
+    #[cfg(feature = "unstable")] timestamp: Option<Timestamp>,
 ) -> ZResult<()> {
     tracing::trace!("write({:?}, [...])", &publisher.key_expr);
-    let primitives = zread!(publisher.session.state)
-        .primitives
+    let value = match (kind, encoding) {
+        (SampleKind::Put, encoding) => publisher.session.publication_encoding(value, encoding),
+        (_, Some(encoding)) => value.encoding(encoding),
+        (_, None) => value,
+    };
+    let primitives = match zread!(publisher.session.state).primitives.as_ref() {
+        Some(primitives) => primitives.clone(),
+        None => {
+            if let Some(state) = &publisher.state {
+                state.stats.record_drop();
+            }
+            bail!(
+                "Failed to publish on {}: session closed",
+                publisher.key_expr
+            )
+        }
+    };
+    #[cfg(feature = "unstable")]
+    if let Some(filter) = publisher
+        .state
         .as_ref()
-        .unwrap()
-        .clone();
-    let timestamp = publisher.session.runtime.new_timestamp();
+        .and_then(|state| state.write_filter.get())
+    {
+        if !*zlock!(filter.current) {
+            if let Some(state) = &publisher.state {
+                state.stats.record_drop();
+            }
+            tracing::trace!(
+                "Filtered publication on {}: no matching subscriber",
+                publisher.key_expr
+            );
+            return Ok(());
+        }
+    }
+    // The blocking publications are slowed down by the congestion itself, they are not throttled
+    #[cfg(feature = "unstable")]
+    if let Some(state) = publisher
+        .state
+        .as_ref()
+        .filter(|_| publisher.congestion_control == CongestionControl::Drop)
+    {
+        if state.congestion.throttle() {
+            state.stats.record_drop();
+            tracing::trace!(
+                "Throttled publication on {}: congestion",
+                publisher.key_expr
+            );
+            return Ok(());
+        }
+    }
+    if let Some(state) = &publisher.state {
+        state.stats.record_sample(value.payload.len());
+    }
+    let runtime = &publisher.session.runtime;
+    // The uncertainty bound of the clock of the session only applies to the timestamps it generates
+    #[cfg(feature = "unstable")]
+    let (timestamp, ext_tbound) = match timestamp {
+        Some(timestamp) => (Some(timestamp), None),
+        None => (runtime.new_timestamp(), runtime.timestamp_bound()),
+    };
+    #[cfg(not(feature = "unstable"))]
+    let (timestamp, ext_tbound) = (runtime.new_timestamp(), runtime.timestamp_bound());
+    let ext_tbound = ext_tbound.map(TimestampBoundType::new);
+    // Tag the sample with the publisher sequence number when ordered delivery is enabled
+    let sinfo = publisher.state.as_ref().and_then(|state| {
+        state.sn.as_ref().map(|sn| SourceInfoType {
+            zid: publisher.session.runtime.zid(),
+            eid: state.id as u32,
+            sn: sn.fetch_add(1, Ordering::Relaxed),
+        })
+    });
 
     if publisher.destination != Locality::SessionLocal {
         primitives.send_push(Push {
@@ -847,6 +1280,7 @@ fn resolve_put(
             ),
             ext_tstamp: None,
             ext_nodeid: ext::NodeIdType::default(),
+            ext_hoplimit: publisher.hop_limit.map(ext::HopLimitType::new),
             payload: match kind {
                 SampleKind::Put => {
                     #[allow(unused_mut)]
@@ -860,10 +1294,11 @@ fn resolve_put(
                     PushBody::Put(Put {
                         timestamp,
                         encoding: value.encoding.clone(),
-                        ext_sinfo: None,
+                        ext_sinfo: sinfo.clone(),
                         #[cfg(feature = "shared-memory")]
                         ext_shm: None,
                         ext_attachment,
+                        ext_tbound,
                         ext_unknown: vec![],
                         payload: value.payload.clone(),
                     })
@@ -879,8 +1314,9 @@ fn resolve_put(
                     }
                     PushBody::Del(Del {
                         timestamp,
-                        ext_sinfo: None,
+                        ext_sinfo: sinfo.clone(),
                         ext_attachment,
+                        ext_tbound: ext_tbound.map(|b| TimestampBoundType::new(b.bound)),
                         ext_unknown: vec![],
                     })
                 }
@@ -892,13 +1328,14 @@ fn resolve_put(
             kind,
             encoding: Some(value.encoding),
             timestamp,
-            source_id: None,
-            source_sn: None,
+            source_id: sinfo.as_ref().map(|i| i.zid),
+            source_sn: sinfo.as_ref().map(|i| i.sn as u64),
             qos: QoS::from(ext::QoSType::new(
                 publisher.priority.into(),
                 publisher.congestion_control,
                 false,
             )),
+            timestamp_bound: ext_tbound.map(|b| b.bound),
         };
 
         publisher.session.handle_data(
@@ -966,6 +1403,20 @@ impl TryFrom<u8> for Priority {
     }
 }
 
+impl From<zenoh_config::PriorityConf> for Priority {
+    fn from(conf: zenoh_config::PriorityConf) -> Self {
+        match conf {
+            zenoh_config::PriorityConf::RealTime => Priority::RealTime,
+            zenoh_config::PriorityConf::InteractiveHigh => Priority::InteractiveHigh,
+            zenoh_config::PriorityConf::InteractiveLow => Priority::InteractiveLow,
+            zenoh_config::PriorityConf::DataHigh => Priority::DataHigh,
+            zenoh_config::PriorityConf::Data => Priority::Data,
+            zenoh_config::PriorityConf::DataLow => Priority::DataLow,
+            zenoh_config::PriorityConf::Background => Priority::Background,
+        }
+    }
+}
+
 type ProtocolPriority = zenoh_protocol::core::Priority;
 impl From<Priority> for ProtocolPriority {
     fn from(prio: Priority) -> Self {
@@ -1441,4 +1892,94 @@ mod tests {
         sample_kind_integrity_in_put_builder_with(SampleKind::Put);
         sample_kind_integrity_in_put_builder_with(SampleKind::Delete);
     }
+
+    #[cfg(feature = "unstable")]
+    #[test]
+    fn congestion_throttling() {
+        use super::{CongestionEvent, PublisherCongestion, THROTTLING_PERIOD};
+        use std::time::Instant;
+        use zenoh_protocol::core::ZenohId;
+
+        let congestion = PublisherCongestion::new(true);
+        let zid = ZenohId::rand();
+        let sent = |now| (0..64).filter(|_| !congestion.throttle_at(now)).count();
+        let start = Instant::now();
+        assert_eq!(sent(start), 64);
+
+        // 1 publication out of 2 is sent, then out of 4 after a throttling period, up to 1 out of 64
+        congestion.notify_at(
+            CongestionEvent {
+                congested: true,
+                zid,
+            },
+            start,
+        );
+        assert_eq!(sent(start), 32);
+        assert_eq!(sent(start + THROTTLING_PERIOD), 16);
+        assert_eq!(sent(start + 10 * THROTTLING_PERIOD), 1);
+
+        // The full rate is restored on recovery
+        congestion.notify_at(
+            CongestionEvent {
+                congested: false,
+                zid,
+            },
+            start,
+        );
+        assert_eq!(sent(start), 64);
+
+        // A publisher that does not throttle is not throttled
+        let congestion = PublisherCongestion::new(false);
+        congestion.notify_at(
+            CongestionEvent {
+                congested: true,
+                zid,
+            },
+            start,
+        );
+        assert!(!congestion.throttle_at(start));
+    }
+
+    #[cfg(feature = "unstable")]
+    #[test]
+    fn congestion_recovery() {
+        use super::{CongestionEvent, PublisherCongestion, CONGESTION_TIMEOUT};
+        use std::sync::{atomic::Ordering, Arc, Mutex};
+        use std::time::{Duration, Instant};
+        use zenoh_protocol::core::ZenohId;
+
+        let congestion = PublisherCongestion::new(true);
+        let events = Arc::new(Mutex::new(vec![]));
+        let c_events = events.clone();
+        congestion
+            .callbacks
+            .lock()
+            .unwrap()
+            .push(Arc::new(move |event| c_events.lock().unwrap().push(event)));
+        let (a, b) = (ZenohId::rand(), ZenohId::rand());
+        let event = |congested, zid| CongestionEvent { congested, zid };
+        let start = Instant::now();
+
+        // The callbacks are notified of the beginning and end of the congestions, not of their refresh
+        congestion.notify_at(event(true, a), start);
+        congestion.notify_at(event(true, a), start + Duration::from_secs(1));
+        congestion.notify_at(event(true, b), start + Duration::from_secs(1));
+        congestion.notify_at(event(false, b), start + Duration::from_secs(2));
+        congestion.notify_at(event(false, b), start + Duration::from_secs(2));
+        assert_eq!(
+            *events.lock().unwrap(),
+            [event(true, a), event(true, b), event(false, b)]
+        );
+        events.lock().unwrap().clear();
+
+        // A congestion that is not notified again expires
+        let refreshed = start + Duration::from_secs(1);
+        congestion.throttle_at(refreshed + CONGESTION_TIMEOUT - Duration::from_millis(1));
+        assert!(congestion.is_congested.load(Ordering::Relaxed));
+        assert!(events.lock().unwrap().is_empty());
+        congestion.throttle_at(refreshed + CONGESTION_TIMEOUT);
+        assert!(!congestion.is_congested.load(Ordering::Relaxed));
+        assert!(!congestion.throttle_at(refreshed + CONGESTION_TIMEOUT));
+        assert_eq!(*events.lock().unwrap(), [event(false, a)]);
+    }
 }
@@ -77,6 +77,9 @@ impl Default for QueryConsolidation {
 }
 
 /// Structs returned by a [`get`](Session::get).
+///
+/// A `Reply` carries private fields (e.g. its application error code) and is non exhaustive:
+/// it cannot be built with a struct literal, use [`Reply::new`] instead.
 #[non_exhaustive]
 #[derive(Clone, Debug)]
 pub struct Reply {
@@ -84,6 +87,56 @@ pub struct Reply {
     pub sample: Result<Sample, Value>,
     /// The id of the zenoh instance that answered this Reply.
     pub replier_id: ZenohId,
+    // The id of the queryable that answered this Reply within its session, if known.
+    pub(crate) replier_eid: Option<u32>,
+    // The application error code of an error reply.
+    pub(crate) error_code: u16,
+}
+
+impl Reply {
+    /// Build a Reply answered by the zenoh instance `replier_id`, e.g. to fake the replies
+    /// of a query in tests. An error reply built this way has an error code of 0.
+    pub fn new(sample: Result<Sample, Value>, replier_id: ZenohId) -> Self {
+        Reply {
+            sample,
+            replier_id,
+            replier_eid: None,
+            error_code: 0,
+        }
+    }
+
+    /// Set the application error code of this Reply if it is an error.
+    #[zenoh_macros::unstable]
+    pub fn with_error_code(mut self, code: u16) -> Self {
+        self.error_code = code;
+        self
+    }
+
+    /// The id of the zenoh instance that answered this Reply.
+    ///
+    /// When several queryables (e.g. the replicas of a storage) answer the same query,
+    /// this allows to attribute each reply to its responder.
+    #[inline]
+    pub fn replier_id(&self) -> ZenohId {
+        self.replier_id
+    }
+
+    /// The id of the queryable that answered this Reply, unique within the session identified
+    /// by [`replier_id`](Reply::replier_id). `None` for the replies not sent by a queryable
+    /// (e.g. timeouts), or received from an instance not providing it.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn replier_eid(&self) -> Option<u32> {
+        self.replier_eid
+    }
+
+    /// The application error code of this Reply if it is an error
+    /// (see [`Query::reply_err`](crate::queryable::Query::reply_err)).
+    /// Error replies that do not specify a code, such as the ones caused by a timeout, have a code of 0.
+    #[zenoh_macros::unstable]
+    pub fn error_code(&self) -> Option<u16> {
+        self.sample.as_ref().err().map(|_| self.error_code)
+    }
 }
 
 pub(crate) struct QueryState {
@@ -92,9 +145,73 @@ pub(crate) struct QueryState {
     pub(crate) scope: Option<KeyExpr<'static>>,
     pub(crate) reception_mode: ConsolidationMode,
     pub(crate) replies: Option<HashMap<OwnedKeyExpr, Reply>>,
+    pub(crate) ordered: Option<OrderedReplies>,
     pub(crate) callback: Callback<'static, Reply>,
 }
 
+impl QueryState {
+    /// The replies left to hand to the application once the query is complete.
+    pub(crate) fn take_pending_replies(&mut self) -> Vec<Reply> {
+        let mut replies: Vec<Reply> = match self.reception_mode {
+            ConsolidationMode::Latest => self
+                .replies
+                .take()
+                .map(|replies| replies.into_values().collect())
+                .unwrap_or_default(),
+            _ => vec![],
+        };
+        if let Some(ordered) = self.ordered.as_mut() {
+            ordered.replies.append(&mut replies);
+            replies = ordered.drain();
+        }
+        replies
+    }
+}
+
+/// The replies of a query buffered to be handed sorted by key expression, then by timestamp.
+#[derive(Default)]
+pub(crate) struct OrderedReplies {
+    replies: Vec<Reply>,
+    // The size in bytes of the buffered replies
+    size: usize,
+}
+
+impl OrderedReplies {
+    /// Buffers a reply, returning the sorted buffered replies to hand if the buffer is full.
+    pub(crate) fn push(&mut self, reply: Reply) -> Option<Vec<Reply>> {
+        if let Ok(sample) = &reply.sample {
+            self.size += sample.key_expr.len() + sample.value.payload.len();
+        }
+        self.replies.push(reply);
+        if self.size > crate::session::API_REPLY_ORDERING_MAX_SIZE.get() {
+            tracing::warn!(
+                "More than {} bytes of replies to order: handing the {} buffered replies",
+                crate::session::API_REPLY_ORDERING_MAX_SIZE.get(),
+                self.replies.len()
+            );
+            return Some(self.drain());
+        }
+        None
+    }
+
+    /// The buffered replies sorted by key expression, then by timestamp, the errors last.
+    pub(crate) fn drain(&mut self) -> Vec<Reply> {
+        let mut replies = std::mem::take(&mut self.replies);
+        self.size = 0;
+        replies.sort_by(|a, b| match (&a.sample, &b.sample) {
+            (Ok(a), Ok(b)) => a
+                .key_expr
+                .as_str()
+                .cmp(b.key_expr.as_str())
+                .then_with(|| a.timestamp.cmp(&b.timestamp)),
+            (Ok(_), Err(_)) => std::cmp::Ordering::Less,
+            (Err(_), Ok(_)) => std::cmp::Ordering::Greater,
+            (Err(_), Err(_)) => std::cmp::Ordering::Equal,
+        });
+        replies
+    }
+}
+
 /// A builder for initializing a `query`.
 ///
 /// # Examples
@@ -131,6 +248,8 @@ pub struct GetBuilder<'a, 'b, Handler> {
     pub(crate) value: Option<Value>,
     #[cfg(feature = "unstable")]
     pub(crate) attachment: Option<Attachment>,
+    #[cfg(feature = "unstable")]
+    pub(crate) reply_ordering: ReplyOrdering,
 }
 
 impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
@@ -167,6 +286,8 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             value,
             #[cfg(feature = "unstable")]
             attachment,
+            #[cfg(feature = "unstable")]
+            reply_ordering,
             handler: _,
         } = self;
         GetBuilder {
@@ -180,6 +301,8 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             value,
             #[cfg(feature = "unstable")]
             attachment,
+            #[cfg(feature = "unstable")]
+            reply_ordering,
             handler: callback,
         }
     }
@@ -252,6 +375,8 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             value,
             #[cfg(feature = "unstable")]
             attachment,
+            #[cfg(feature = "unstable")]
+            reply_ordering,
             handler: _,
         } = self;
         GetBuilder {
@@ -265,6 +390,8 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             value,
             #[cfg(feature = "unstable")]
             attachment,
+            #[cfg(feature = "unstable")]
+            reply_ordering,
             handler,
         }
     }
@@ -316,6 +443,17 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
         self
     }
 
+    /// Change the order in which the replies are handed to the application.
+    ///
+    /// With [`ReplyOrdering::KeyThenTime`], the replies are buffered until the query completes
+    /// and handed sorted by key expression, then by timestamp.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn reply_ordering(mut self, ordering: ReplyOrdering) -> Self {
+        self.reply_ordering = ordering;
+        self
+    }
+
     /// By default, `get` guarantees that it will only receive replies whose key expressions intersect
     /// with the queried key expression.
     ///
@@ -333,6 +471,7 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
             timeout,
             value,
             attachment,
+            reply_ordering,
             handler,
         } = self;
         Self {
@@ -345,6 +484,7 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
             timeout,
             value,
             attachment,
+            reply_ordering,
             handler,
         }
     }
@@ -362,6 +502,21 @@ pub enum ReplyKeyExpr {
     MatchingQuery,
 }
 
+/// The order in which the replies to a [`get`](Session::get) are handed to the application.
+#[zenoh_macros::unstable]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ReplyOrdering {
+    /// The replies are handed as they are received.
+    #[default]
+    None,
+    /// The replies are buffered until the query completes, then handed sorted by key expression
+    /// and by timestamp for a same key expression, followed by the errors.
+    ///
+    /// To bound the memory used by a query, the buffered replies are handed as soon as their
+    /// size exceeds a limit (16 MiB by default), each batch of replies being sorted.
+    KeyThenTime,
+}
+
 impl<Handler> Resolvable for GetBuilder<'_, '_, Handler>
 where
     Handler: IntoCallbackReceiverPair<'static, Reply> + Send,
@@ -389,6 +544,8 @@ where
                 self.value,
                 #[cfg(feature = "unstable")]
                 self.attachment,
+                #[cfg(feature = "unstable")]
+                self.reply_ordering,
                 callback,
             )
             .map(|_| receiver)
@@ -22,6 +22,9 @@ use crate::query::ReplyKeyExpr;
 #[zenoh_macros::unstable]
 use crate::sample::Attachment;
 use crate::sample::DataInfo;
+use crate::stats::EntityStats;
+#[zenoh_macros::unstable]
+use crate::stats::EntityStatsReport;
 use crate::SessionRef;
 use crate::Undeclarable;
 
@@ -29,6 +32,7 @@ use std::fmt;
 use std::future::Ready;
 use std::ops::Deref;
 use std::sync::Arc;
+use std::time::Instant;
 use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
 use zenoh_protocol::core::WireExpr;
 use zenoh_protocol::network::{response, Mapping, RequestId, Response, ResponseFinal};
@@ -50,6 +54,8 @@ pub(crate) struct QueryInner {
     pub(crate) primitives: Arc<dyn Primitives>,
     #[cfg(feature = "unstable")]
     pub(crate) attachment: Option<Attachment>,
+    /// The instant after which the querier no longer waits for replies.
+    pub(crate) deadline: Option<Instant>,
 }
 
 impl Drop for QueryInner {
@@ -66,6 +72,8 @@ impl Drop for QueryInner {
 #[derive(Clone)]
 pub struct Query {
     pub(crate) inner: Arc<QueryInner>,
+    // The id of the queryable this Query was delivered to, sent back with the replies.
+    pub(crate) eid: u32,
 }
 
 impl Query {
@@ -101,6 +109,12 @@ impl Query {
         self.inner.attachment.as_ref()
     }
 
+    /// The instant after which the querier stops waiting for replies, if it set a timeout.
+    #[zenoh_macros::unstable]
+    pub fn deadline(&self) -> Option<Instant> {
+        self.inner.deadline
+    }
+
     /// Sends a reply to this Query.
     ///
     /// By default, queries only accept replies whose key expression intersects with the query's.
@@ -111,6 +125,24 @@ impl Query {
         ReplyBuilder {
             query: self,
             result,
+            code: 0,
+            qos: response::ext::QoSType::response_default(),
+        }
+    }
+
+    /// Sends an error reply to this Query, with an application defined error `code`
+    /// that the querier can retrieve through [`Reply::error_code`](crate::query::Reply::error_code).
+    #[zenoh_macros::unstable]
+    #[inline(always)]
+    pub fn reply_err<IntoValue>(&self, code: u16, value: IntoValue) -> ReplyBuilder<'_>
+    where
+        IntoValue: Into<Value>,
+    {
+        ReplyBuilder {
+            query: self,
+            result: Err(value.into()),
+            code,
+            qos: response::ext::QoSType::response_default(),
         }
     }
 
@@ -159,9 +191,29 @@ impl fmt::Display for Query {
 pub struct ReplyBuilder<'a> {
     query: &'a Query,
     result: Result<Sample, Value>,
+    code: u16,
+    qos: response::ext::QoSType,
 }
 
 impl<'a> ReplyBuilder<'a> {
+    /// Change the congestion control to apply when routing this reply.
+    #[inline]
+    pub fn congestion_control(mut self, congestion_control: CongestionControl) -> Self {
+        self.qos.set_congestion_control(congestion_control);
+        self
+    }
+
+    /// Change the priority of this reply.
+    #[inline]
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.qos = response::ext::QoSType::new(
+            priority.into(),
+            self.qos.get_congestion_control(),
+            self.qos.is_express(),
+        );
+        self
+    }
+
     #[allow(clippy::result_large_err)]
     #[zenoh_macros::unstable]
     pub fn with_attachment(mut self, attachment: Attachment) -> Result<Self, (Self, Attachment)> {
@@ -198,6 +250,10 @@ impl SyncResolve for ReplyBuilder<'_> {
                     source_info,
                     #[cfg(feature = "unstable")]
                     attachment,
+                    #[cfg(feature = "unstable")]
+                    timestamp_uncertainty,
+                    #[cfg(feature = "unstable")]
+                        reception_timestamp: _,
                 } = sample;
                 #[allow(unused_mut)]
                 let mut data_info = DataInfo {
@@ -207,6 +263,7 @@ impl SyncResolve for ReplyBuilder<'_> {
                     qos,
                     source_id: None,
                     source_sn: None,
+                    timestamp_bound: None,
                 };
                 #[allow(unused_mut)]
                 let mut ext_attachment = None;
@@ -214,6 +271,7 @@ impl SyncResolve for ReplyBuilder<'_> {
                 {
                     data_info.source_id = source_info.source_id;
                     data_info.source_sn = source_info.source_sn;
+                    data_info.timestamp_bound = timestamp_uncertainty.bound;
                     if let Some(attachment) = attachment {
                         ext_attachment = Some(attachment.into());
                     }
@@ -242,14 +300,17 @@ impl SyncResolve for ReplyBuilder<'_> {
                         #[cfg(feature = "shared-memory")]
                         ext_shm: None,
                         ext_attachment,
+                        ext_tbound: data_info
+                            .timestamp_bound
+                            .map(zenoh::reply::ext::TimestampBoundType::new),
                         ext_unknown: vec![],
                         payload,
                     }),
-                    ext_qos: response::ext::QoSType::response_default(),
+                    ext_qos: self.qos,
                     ext_tstamp: None,
                     ext_respid: Some(response::ext::ResponderIdType {
                         zid: self.query.inner.zid,
-                        eid: 0, // @TODO use proper EntityId (#703)
+                        eid: self.query.eid,
                     }),
                 });
                 Ok(())
@@ -273,13 +334,13 @@ impl SyncResolve for ReplyBuilder<'_> {
                             payload: payload.payload,
                             encoding: payload.encoding,
                         }),
-                        code: 0, // TODO
+                        code: self.code,
                     }),
-                    ext_qos: response::ext::QoSType::response_default(),
+                    ext_qos: self.qos,
                     ext_tstamp: None,
                     ext_respid: Some(response::ext::ResponderIdType {
                         zid: self.query.inner.zid,
-                        eid: 0, // @TODO use proper EntityId (#703)
+                        eid: self.query.eid,
                     }),
                 });
                 Ok(())
@@ -302,6 +363,7 @@ pub(crate) struct QueryableState {
     pub(crate) complete: bool,
     pub(crate) origin: Locality,
     pub(crate) callback: Arc<dyn Fn(Query) + Send + Sync>,
+    pub(crate) stats: EntityStats,
 }
 
 impl fmt::Debug for QueryableState {
@@ -593,6 +655,29 @@ pub struct Queryable<'a, Receiver> {
 }
 
 impl<'a, Receiver> Queryable<'a, Receiver> {
+    /// Returns a snapshot of the statistics of this [`Queryable`].
+    ///
+    /// For a wildcard queryable, [`EntityStatsReport::hot_keys`] lists the concrete
+    /// keys that were queried the most.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let queryable = session.declare_queryable("key/**").res().await.unwrap();
+    /// for hot_key in queryable.stats().hot_keys {
+    ///     println!("{}: {} queries", hot_key.key_expr, hot_key.count);
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn stats(&self) -> EntityStatsReport {
+        self.queryable.state.stats.report()
+    }
+
     #[inline]
     pub fn undeclare(self) -> impl Resolve<ZResult<()>> + 'a {
         Undeclarable::undeclare_inner(self, ())
@@ -658,3 +743,18 @@ where
         std::future::ready(self.res_sync())
     }
 }
+
+/// The event notified to the callbacks registered with
+/// [`Session::on_queryable_conflict()`](crate::Session::on_queryable_conflict) when a complete
+/// queryable of the session overlaps a complete queryable declared by another session.
+#[zenoh_macros::unstable]
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct QueryableConflictEvent {
+    /// The key expression of the complete queryable of this session.
+    pub key_expr: KeyExpr<'static>,
+    /// The key expression of the overlapping complete queryable.
+    pub conflicting_key_expr: KeyExpr<'static>,
+    /// The [`ZenohId`] of the node the overlapping complete queryable was declared by, or through.
+    pub zid: ZenohId,
+}
@@ -17,11 +17,15 @@ use crate::buffers::ZBuf;
 use crate::prelude::ZenohId;
 use crate::prelude::{KeyExpr, SampleKind, Value};
 use crate::query::Reply;
-use crate::time::{new_reception_timestamp, Timestamp};
+use crate::time::{new_reception_timestamp, Timestamp, NTP64};
 use crate::Priority;
 #[zenoh_macros::unstable]
 use serde::Serialize;
 use std::convert::{TryFrom, TryInto};
+#[zenoh_macros::unstable]
+use std::ops::RangeInclusive;
+#[zenoh_macros::unstable]
+use zenoh_core::zlock;
 use zenoh_protocol::core::{CongestionControl, Encoding};
 use zenoh_protocol::network::push::ext::QoSType;
 
@@ -45,6 +49,16 @@ pub(crate) enum Locality {
     Any,
 }
 
+impl From<zenoh_config::LocalityConf> for Locality {
+    fn from(conf: zenoh_config::LocalityConf) -> Self {
+        match conf {
+            zenoh_config::LocalityConf::SessionLocal => Locality::SessionLocal,
+            zenoh_config::LocalityConf::Remote => Locality::Remote,
+            zenoh_config::LocalityConf::Any => Locality::Any,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub(crate) struct DataInfo {
     pub kind: SampleKind,
@@ -53,6 +67,7 @@ pub(crate) struct DataInfo {
     pub source_id: Option<ZenohId>,
     pub source_sn: Option<SourceSn>,
     pub qos: QoS,
+    pub timestamp_bound: Option<NTP64>,
 }
 
 /// Informations on the source of a zenoh [`Sample`].
@@ -84,6 +99,272 @@ impl SourceInfo {
     }
 }
 
+/// The uncertainty of the [`Timestamp`] of a zenoh [`Sample`].
+#[zenoh_macros::unstable]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimestampUncertainty {
+    /// The bound of the uncertainty of the clock that generated the timestamp, if advertised by its source.
+    pub bound: Option<NTP64>,
+    /// Whether the timestamp is ahead of or behind the clock of the receiving session by more than
+    /// its bound plus the `timestamping/max_drift` configured on the session.
+    pub exceeds_max_drift: bool,
+}
+
+#[zenoh_macros::unstable]
+impl TimestampUncertainty {
+    pub(crate) fn on_reception(
+        timestamp: Option<&Timestamp>,
+        bound: Option<NTP64>,
+        max_drift: Option<NTP64>,
+    ) -> Self {
+        let now = *new_reception_timestamp().get_time();
+        Self::at(timestamp, bound, max_drift, now)
+    }
+
+    fn at(
+        timestamp: Option<&Timestamp>,
+        bound: Option<NTP64>,
+        max_drift: Option<NTP64>,
+        now: NTP64,
+    ) -> Self {
+        let exceeds_max_drift = match (timestamp, max_drift) {
+            (Some(timestamp), Some(max_drift)) => {
+                let time = timestamp.get_time().0;
+                let tolerance = max_drift.0.saturating_add(bound.map_or(0, |b| b.0));
+                time > now.0.saturating_add(tolerance) || time < now.0.saturating_sub(tolerance)
+            }
+            _ => false,
+        };
+        TimestampUncertainty {
+            bound,
+            exceeds_max_drift,
+        }
+    }
+
+    /// The interval of the times `timestamp` may actually stand for, given its uncertainty bound.
+    ///
+    /// Two samples are only ordered with certainty if the intervals of their timestamps do not overlap.
+    pub fn interval(&self, timestamp: &Timestamp) -> RangeInclusive<NTP64> {
+        let time = timestamp.get_time().0;
+        let bound = self.bound.map_or(0, |b| b.0);
+        NTP64(time.saturating_sub(bound))..=NTP64(time.saturating_add(bound))
+    }
+}
+
+#[test]
+#[cfg(feature = "unstable")]
+fn timestamp_uncertainty_on_reception() {
+    use crate::time::TimestampId;
+    use std::time::Duration;
+
+    let ntp = |secs| NTP64::from(Duration::from_secs(secs));
+    let at = |secs| Timestamp::new(ntp(secs), TimestampId::try_from([1]).unwrap());
+    let now = ntp(100);
+
+    // Not checked without a timestamp or a max drift
+    assert!(!TimestampUncertainty::at(None, None, Some(ntp(1)), now).exceeds_max_drift);
+    assert!(!TimestampUncertainty::at(Some(&at(200)), None, None, now).exceeds_max_drift);
+
+    // Within the max drift, ahead of or behind the local clock
+    assert!(!TimestampUncertainty::at(Some(&at(101)), None, Some(ntp(1)), now).exceeds_max_drift);
+    assert!(!TimestampUncertainty::at(Some(&at(99)), None, Some(ntp(1)), now).exceeds_max_drift);
+
+    // Beyond the max drift, ahead of or behind the local clock
+    assert!(TimestampUncertainty::at(Some(&at(102)), None, Some(ntp(1)), now).exceeds_max_drift);
+    assert!(TimestampUncertainty::at(Some(&at(98)), None, Some(ntp(1)), now).exceeds_max_drift);
+
+    // The bound of the timestamp widens the tolerated drift, and is kept
+    let uncertainty = TimestampUncertainty::at(Some(&at(98)), Some(ntp(1)), Some(ntp(1)), now);
+    assert!(!uncertainty.exceeds_max_drift);
+    assert_eq!(uncertainty.bound, Some(ntp(1)));
+    assert!(
+        TimestampUncertainty::at(Some(&at(97)), Some(ntp(1)), Some(ntp(1)), now).exceeds_max_drift
+    );
+
+    // Against the local clock
+    let timestamp = new_reception_timestamp();
+    assert!(
+        !TimestampUncertainty::on_reception(Some(&timestamp), None, Some(ntp(60)))
+            .exceeds_max_drift
+    );
+    let late = Timestamp::new(ntp(0), TimestampId::try_from([1]).unwrap());
+    assert!(TimestampUncertainty::on_reception(Some(&late), None, Some(ntp(60))).exceeds_max_drift);
+}
+
+/// The skew of the clock of a source, estimated by a [`ClockSkewEstimator`].
+#[zenoh_macros::unstable]
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClockSkew {
+    /// The smallest difference in nanoseconds between the reception time and the timestamp of the samples
+    /// of the source, over the current window: the skew of the clock of the source plus the minimal
+    /// latency of its samples. It is negative when the clock of the source is ahead of the local clock.
+    pub offset: i64,
+    /// The variation of the offset in nanoseconds per second between the last two windows, i.e. the drift
+    /// of the clock of the source relatively to the local clock, once two windows have elapsed.
+    pub drift: Option<f64>,
+    /// The number of samples of the source the estimation is based on.
+    pub samples: u64,
+}
+
+#[zenoh_macros::unstable]
+#[derive(Debug)]
+struct SourceSkew {
+    // The reception time of the first sample of the current window, and the smallest offset over it
+    window_start: i64,
+    window_offset: i64,
+    // The same for the previous window
+    previous: Option<(i64, i64)>,
+    drift: Option<f64>,
+    samples: u64,
+    // The reception time of the last sample
+    last_reception: i64,
+}
+
+#[zenoh_macros::unstable]
+impl SourceSkew {
+    fn skew(&self) -> ClockSkew {
+        ClockSkew {
+            offset: self.window_offset,
+            drift: self.drift,
+            samples: self.samples,
+        }
+    }
+}
+
+/// An estimator of the skew of the clocks of the sources of samples, comparing the
+/// [`reception_timestamp`](Sample::reception_timestamp) of the samples with their [`Timestamp`],
+/// so that the drift of the clock of a publisher can be detected without running NTP diagnostics.
+///
+/// The sources are identified by the [`TimestampId`](crate::time::TimestampId) of the timestamps,
+/// the samples without timestamp being ignored. A source is forgotten once no sample of it has been
+/// received for [`EVICTION_WINDOWS`](Self::EVICTION_WINDOWS) windows.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use std::{sync::Arc, time::Duration};
+/// use zenoh::prelude::r#async::*;
+/// use zenoh::sample::ClockSkewEstimator;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let estimator = Arc::new(ClockSkewEstimator::new(Duration::from_secs(10)));
+/// let subscriber = session
+///     .declare_subscriber("key/expression")
+///     .callback({
+///         let estimator = estimator.clone();
+///         move |sample| {
+///             if let Some(skew) = estimator.update(&sample) {
+///                 println!("Offset of {:?}: {}ns", sample.timestamp, skew.offset);
+///             }
+///         }
+///     })
+///     .res()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+#[zenoh_macros::unstable]
+#[derive(Debug)]
+pub struct ClockSkewEstimator {
+    window: i64,
+    sources: std::sync::Mutex<SourceSkews>,
+}
+
+#[zenoh_macros::unstable]
+#[derive(Debug, Default)]
+struct SourceSkews {
+    skews: std::collections::HashMap<crate::time::TimestampId, SourceSkew>,
+    // The reception time the silent sources were last evicted at
+    last_eviction: i64,
+}
+
+#[zenoh_macros::unstable]
+impl ClockSkewEstimator {
+    /// The number of windows without any sample of a source after which it is forgotten.
+    pub const EVICTION_WINDOWS: i64 = 10;
+
+    /// Creates an estimator whose offsets are the smallest ones over windows of `window`,
+    /// filtering out the variations of the latency of the samples.
+    pub fn new(window: std::time::Duration) -> Self {
+        Self {
+            window: window.as_nanos().min(i64::MAX as u128) as i64,
+            sources: std::sync::Mutex::new(SourceSkews::default()),
+        }
+    }
+
+    /// Updates the estimation of the skew of the source of `sample`, returning it.
+    ///
+    /// Returns `None` if the sample has no timestamp or no reception timestamp.
+    pub fn update(&self, sample: &Sample) -> Option<ClockSkew> {
+        fn nanos(timestamp: &Timestamp) -> i64 {
+            timestamp
+                .get_time()
+                .to_duration()
+                .as_nanos()
+                .min(i64::MAX as u128) as i64
+        }
+
+        let timestamp = sample.timestamp.as_ref()?;
+        let reception = nanos(sample.reception_timestamp.as_ref()?);
+        let offset = reception.saturating_sub(nanos(timestamp));
+        let mut sources = zlock!(self.sources);
+        let source = sources
+            .skews
+            .entry(*timestamp.get_id())
+            .or_insert_with(|| SourceSkew {
+                window_start: reception,
+                window_offset: offset,
+                previous: None,
+                drift: None,
+                samples: 0,
+                last_reception: reception,
+            });
+        if reception.saturating_sub(source.window_start) >= self.window {
+            if let Some((start, offset)) = source.previous {
+                let elapsed = source.window_start.saturating_sub(start);
+                if elapsed > 0 {
+                    source.drift = Some(
+                        source.window_offset.saturating_sub(offset) as f64 * 1e9 / elapsed as f64,
+                    );
+                }
+            }
+            source.previous = Some((source.window_start, source.window_offset));
+            source.window_start = reception;
+            source.window_offset = offset;
+        } else {
+            source.window_offset = source.window_offset.min(offset);
+        }
+        source.samples += 1;
+        source.last_reception = source.last_reception.max(reception);
+        let skew = source.skew();
+        // The silent sources are looked for once per window
+        if reception.saturating_sub(sources.last_eviction) >= self.window {
+            let timeout = self.window.saturating_mul(Self::EVICTION_WINDOWS);
+            sources
+                .skews
+                .retain(|_, s| reception.saturating_sub(s.last_reception) < timeout);
+            sources.last_eviction = reception;
+        }
+        Some(skew)
+    }
+
+    /// The estimated skew of the clock of a source, if any sample of it has been received.
+    pub fn skew(&self, source: &crate::time::TimestampId) -> Option<ClockSkew> {
+        zlock!(self.sources).skews.get(source).map(SourceSkew::skew)
+    }
+
+    /// The estimated skews of the clocks of all the sources.
+    pub fn skews(&self) -> Vec<(crate::time::TimestampId, ClockSkew)> {
+        zlock!(self.sources)
+            .skews
+            .iter()
+            .map(|(id, source)| (*id, source.skew()))
+            .collect()
+    }
+}
+
 #[zenoh_macros::unstable]
 impl From<DataInfo> for SourceInfo {
     fn from(data_info: DataInfo) -> Self {
@@ -354,6 +635,26 @@ pub struct Sample {
     ///
     /// A map of key-value pairs, where each key and value are byte-slices.
     pub attachment: Option<Attachment>,
+
+    #[cfg(feature = "unstable")]
+    /// <div class="stab unstable">
+    ///   <span class="emoji">🔬</span>
+    ///   This API has been marked as unstable: it works as advertised, but we may change it in a future release.
+    ///   To use it, you must enable zenoh's <code>unstable</code> feature flag.
+    /// </div>
+    ///
+    /// The uncertainty of the timestamp of this Sample.
+    pub timestamp_uncertainty: TimestampUncertainty,
+
+    #[cfg(feature = "unstable")]
+    /// <div class="stab unstable">
+    ///   <span class="emoji">🔬</span>
+    ///   This API has been marked as unstable: it works as advertised, but we may change it in a future release.
+    ///   To use it, you must enable zenoh's <code>unstable</code> feature flag.
+    /// </div>
+    ///
+    /// The local time this Sample was received at by the session, if it has been received.
+    pub reception_timestamp: Option<Timestamp>,
 }
 
 impl Sample {
@@ -374,6 +675,10 @@ impl Sample {
             source_info: SourceInfo::empty(),
             #[cfg(feature = "unstable")]
             attachment: None,
+            #[cfg(feature = "unstable")]
+            timestamp_uncertainty: TimestampUncertainty::default(),
+            #[cfg(feature = "unstable")]
+            reception_timestamp: None,
         }
     }
     /// Creates a new Sample.
@@ -397,6 +702,10 @@ impl Sample {
             source_info: SourceInfo::empty(),
             #[cfg(feature = "unstable")]
             attachment: None,
+            #[cfg(feature = "unstable")]
+            timestamp_uncertainty: TimestampUncertainty::default(),
+            #[cfg(feature = "unstable")]
+            reception_timestamp: None,
         })
     }
 
@@ -419,9 +728,16 @@ impl Sample {
                 timestamp: data_info.timestamp,
                 qos: data_info.qos,
                 #[cfg(feature = "unstable")]
+                timestamp_uncertainty: TimestampUncertainty {
+                    bound: data_info.timestamp_bound,
+                    exceeds_max_drift: false,
+                },
+                #[cfg(feature = "unstable")]
                 source_info: data_info.into(),
                 #[cfg(feature = "unstable")]
                 attachment: None,
+                #[cfg(feature = "unstable")]
+                reception_timestamp: None,
             }
         } else {
             Sample {
@@ -434,6 +750,10 @@ impl Sample {
                 source_info: SourceInfo::empty(),
                 #[cfg(feature = "unstable")]
                 attachment: None,
+                #[cfg(feature = "unstable")]
+                timestamp_uncertainty: TimestampUncertainty::default(),
+                #[cfg(feature = "unstable")]
+                reception_timestamp: None,
             }
         }
     }
@@ -489,6 +809,162 @@ impl Sample {
         self.attachment = Some(attachment);
         self
     }
+
+    /// Gets the local time this Sample was received at by the session, if it has been received.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn reception_timestamp(&self) -> Option<&Timestamp> {
+        self.reception_timestamp.as_ref()
+    }
+}
+
+/// A builder of [`Sample`], allowing to construct new samples or to rewrite received ones
+/// (e.g. to forward them on another key expression) without cloning them.
+///
+/// # Examples
+/// ```
+/// use zenoh::prelude::sync::*;
+/// use zenoh::sample::SampleBuilder;
+///
+/// let key_expr = KeyExpr::try_from("key/expression").unwrap();
+/// let sample = SampleBuilder::put(key_expr, "value")
+///     .priority(Priority::RealTime)
+///     .build();
+/// let forwarded = SampleBuilder::from(sample)
+///     .key_expr(KeyExpr::try_from("bridge/key/expression").unwrap())
+///     .build();
+/// assert_eq!(forwarded.qos.priority(), Priority::RealTime);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SampleBuilder(Sample);
+
+impl SampleBuilder {
+    /// Creates a builder of a [`SampleKind::Put`] sample.
+    pub fn put<IntoKeyExpr, IntoValue>(key_expr: IntoKeyExpr, value: IntoValue) -> Self
+    where
+        IntoKeyExpr: Into<KeyExpr<'static>>,
+        IntoValue: Into<Value>,
+    {
+        SampleBuilder(Sample::new(key_expr, value))
+    }
+
+    /// Creates a builder of a [`SampleKind::Delete`] sample.
+    pub fn delete<IntoKeyExpr>(key_expr: IntoKeyExpr) -> Self
+    where
+        IntoKeyExpr: Into<KeyExpr<'static>>,
+    {
+        SampleBuilder(Sample::new(key_expr, Value::empty())).kind(SampleKind::Delete)
+    }
+
+    /// Changes the key expression of the sample.
+    pub fn key_expr<IntoKeyExpr>(mut self, key_expr: IntoKeyExpr) -> Self
+    where
+        IntoKeyExpr: Into<KeyExpr<'static>>,
+    {
+        self.0.key_expr = key_expr.into();
+        self
+    }
+
+    /// Changes the value of the sample, i.e. its payload and its encoding.
+    pub fn value<IntoValue>(mut self, value: IntoValue) -> Self
+    where
+        IntoValue: Into<Value>,
+    {
+        self.0.value = value.into();
+        self
+    }
+
+    /// Changes the payload of the sample, keeping its encoding.
+    pub fn payload<IntoZBuf>(mut self, payload: IntoZBuf) -> Self
+    where
+        IntoZBuf: Into<ZBuf>,
+    {
+        self.0.value.payload = payload.into();
+        self
+    }
+
+    /// Changes the encoding of the sample.
+    pub fn encoding<IntoEncoding>(mut self, encoding: IntoEncoding) -> Self
+    where
+        IntoEncoding: Into<Encoding>,
+    {
+        self.0.value.encoding = encoding.into();
+        self
+    }
+
+    /// Changes the kind of the sample.
+    pub fn kind(mut self, kind: SampleKind) -> Self {
+        self.0.kind = kind;
+        self
+    }
+
+    /// Changes the timestamp of the sample.
+    pub fn timestamp(mut self, timestamp: Option<Timestamp>) -> Self {
+        self.0.timestamp = timestamp;
+        self
+    }
+
+    /// Changes the quality of service settings of the sample.
+    pub fn qos(mut self, qos: QoS) -> Self {
+        self.0.qos = qos;
+        self
+    }
+
+    /// Changes the priority of the sample.
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.0.qos = self.0.qos.with_priority(priority);
+        self
+    }
+
+    /// Changes the congestion control of the sample.
+    pub fn congestion_control(mut self, congestion_control: CongestionControl) -> Self {
+        self.0.qos = self.0.qos.with_congestion_control(congestion_control);
+        self
+    }
+
+    /// Changes the express flag of the sample.
+    pub fn express(mut self, is_express: bool) -> Self {
+        self.0.qos = self.0.qos.with_express(is_express);
+        self
+    }
+
+    /// Changes the source info of the sample.
+    #[zenoh_macros::unstable]
+    pub fn source_info(mut self, source_info: SourceInfo) -> Self {
+        self.0.source_info = source_info;
+        self
+    }
+
+    /// Changes the attachment of the sample.
+    #[zenoh_macros::unstable]
+    pub fn attachment(mut self, attachment: Option<Attachment>) -> Self {
+        self.0.attachment = attachment;
+        self
+    }
+
+    /// Changes the uncertainty of the timestamp of the sample.
+    #[zenoh_macros::unstable]
+    pub fn timestamp_uncertainty(mut self, timestamp_uncertainty: TimestampUncertainty) -> Self {
+        self.0.timestamp_uncertainty = timestamp_uncertainty;
+        self
+    }
+
+    /// Builds the sample.
+    pub fn build(self) -> Sample {
+        self.0
+    }
+}
+
+impl From<Sample> for SampleBuilder {
+    fn from(sample: Sample) -> Self {
+        SampleBuilder(sample)
+    }
+}
+
+impl From<SampleBuilder> for Sample {
+    fn from(builder: SampleBuilder) -> Self {
+        builder.0
+    }
 }
 
 impl std::ops::Deref for Sample {
@@ -555,7 +1031,12 @@ impl QoS {
 
     /// Sets priority value.
     pub fn with_priority(mut self, priority: Priority) -> Self {
-        self.inner.set_priority(priority.into());
+        // the priority bits are or-ed by set_priority, rebuild the QoS to replace them
+        self.inner = QoSType::new(
+            priority.into(),
+            self.inner.get_congestion_control(),
+            self.inner.is_express(),
+        );
         self
     }
 
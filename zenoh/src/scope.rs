@@ -0,0 +1,248 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Declarations undeclared together.
+//!
+//! see [`DeclarationScope`]
+use crate::publication::Publisher;
+use crate::queryable::{CallbackQueryable, Queryable};
+use crate::subscriber::{Subscriber, SubscriberInner};
+use std::fmt;
+use std::future::Ready;
+use std::sync::{Arc, Mutex};
+use zenoh_core::{zlock, AsyncResolve, Resolvable, SyncResolve};
+use zenoh_result::ZResult;
+
+pub(crate) enum ScopedDeclaration<'a> {
+    Subscriber(SubscriberInner<'a>),
+    Queryable(CallbackQueryable<'a>),
+    Publisher(Arc<Publisher<'a>>),
+}
+
+impl ScopedDeclaration<'_> {
+    fn undeclare(self) -> ZResult<()> {
+        match self {
+            ScopedDeclaration::Subscriber(mut subscriber) => {
+                subscriber.alive = false;
+                subscriber.session.unsubscribe(subscriber.state.id)
+            }
+            ScopedDeclaration::Queryable(mut queryable) => {
+                queryable.alive = false;
+                queryable.session.close_queryable(queryable.state.id)
+            }
+            ScopedDeclaration::Publisher(publisher) => match Arc::try_unwrap(publisher) {
+                Ok(publisher) => publisher.undeclare().res_sync(),
+                // The publisher may still be used through the other references to it,
+                // it is undeclared here and its remaining resources released when it is dropped
+                Err(publisher) => {
+                    publisher
+                        .session
+                        .undeclare_publication_intent(publisher.key_expr.clone())
+                        .res_sync()?;
+                    if let Some(state) = publisher.state.as_ref() {
+                        publisher.session.undeclare_publisher_state(state.id);
+                    }
+                    Ok(())
+                }
+            },
+        }
+    }
+}
+
+/// A collection of declarations undeclared all at once, with [`undeclare`](DeclarationScope::undeclare)
+/// or when the scope is dropped.
+///
+/// The scope takes the ownership of the [`Subscriber`]s, [`Queryable`]s and [`Publisher`]s
+/// added to it, so that their handles do not have to be kept by the application.
+/// A [`Publisher`] is shared with the application, and is only dropped with the scope
+/// once the application dropped it too.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+/// use zenoh::scope::DeclarationScope;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let scope = DeclarationScope::new();
+/// for i in 0..100 {
+///     scope.add(
+///         session
+///             .declare_subscriber(format!("sensors/{i}"))
+///             .callback(|sample| println!("Received: {:?}", sample))
+///             .res()
+///             .await
+///             .unwrap(),
+///     );
+/// }
+/// let publisher = scope.add(session.declare_publisher("sensors/status").res().await.unwrap());
+/// publisher.put("running").res().await.unwrap();
+/// scope.undeclare().res().await.unwrap();
+/// # }
+/// ```
+#[derive(Default)]
+pub struct DeclarationScope<'a> {
+    declarations: Mutex<Vec<ScopedDeclaration<'a>>>,
+}
+
+impl<'a> DeclarationScope<'a> {
+    /// Create an empty scope.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a declaration to the scope, returning what the application still needs from it:
+    /// the receiver of a [`Subscriber`] or a [`Queryable`], and a shared [`Publisher`].
+    pub fn add<D>(&self, declaration: D) -> D::Output
+    where
+        D: IntoScopedDeclaration<'a>,
+    {
+        declaration.into_scoped(self)
+    }
+
+    pub(crate) fn push(&self, declaration: ScopedDeclaration<'a>) {
+        zlock!(self.declarations).push(declaration);
+    }
+
+    /// The number of declarations in the scope.
+    pub fn len(&self) -> usize {
+        zlock!(self.declarations).len()
+    }
+
+    /// Whether the scope has no declaration.
+    pub fn is_empty(&self) -> bool {
+        zlock!(self.declarations).is_empty()
+    }
+
+    /// Undeclare all the declarations of the scope.
+    ///
+    /// The scope is left empty and can be reused.
+    /// All the declarations are undeclared even if some fail, the first error being returned.
+    pub fn undeclare(&self) -> ScopeUndeclaration<'_, 'a> {
+        ScopeUndeclaration { scope: self }
+    }
+}
+
+impl fmt::Debug for DeclarationScope<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeclarationScope")
+            .field("declarations", &self.len())
+            .finish()
+    }
+}
+
+/// A [`Resolvable`] returned by [`DeclarationScope::undeclare`].
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct ScopeUndeclaration<'s, 'a> {
+    scope: &'s DeclarationScope<'a>,
+}
+
+impl Resolvable for ScopeUndeclaration<'_, '_> {
+    type To = ZResult<()>;
+}
+
+impl SyncResolve for ScopeUndeclaration<'_, '_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        let declarations = std::mem::take(&mut *zlock!(self.scope.declarations));
+        let mut result = Ok(());
+        for declaration in declarations {
+            if let Err(e) = declaration.undeclare() {
+                tracing::warn!("Error undeclaring scoped declaration: {}", e);
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+}
+
+impl AsyncResolve for ScopeUndeclaration<'_, '_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A declaration that can be added to a [`DeclarationScope`].
+pub trait IntoScopedDeclaration<'a> {
+    /// What the application keeps from the declaration once added to the scope.
+    type Output;
+
+    #[doc(hidden)]
+    fn into_scoped(self, scope: &DeclarationScope<'a>) -> Self::Output;
+}
+
+impl<'a, Receiver> IntoScopedDeclaration<'a> for Subscriber<'a, Receiver> {
+    type Output = Receiver;
+
+    fn into_scoped(self, scope: &DeclarationScope<'a>) -> Self::Output {
+        scope.push(ScopedDeclaration::Subscriber(self.subscriber));
+        self.receiver
+    }
+}
+
+impl<'a, Receiver> IntoScopedDeclaration<'a> for Queryable<'a, Receiver> {
+    type Output = Receiver;
+
+    fn into_scoped(self, scope: &DeclarationScope<'a>) -> Self::Output {
+        scope.push(ScopedDeclaration::Queryable(self.queryable));
+        self.receiver
+    }
+}
+
+impl<'a> IntoScopedDeclaration<'a> for Publisher<'a> {
+    type Output = Arc<Publisher<'a>>;
+
+    fn into_scoped(self, scope: &DeclarationScope<'a>) -> Self::Output {
+        let publisher = Arc::new(self);
+        scope.push(ScopedDeclaration::Publisher(publisher.clone()));
+        publisher
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn scope_undeclare() {
+        use super::DeclarationScope;
+        use crate::{open, prelude::sync::*};
+        use zenoh_core::zread;
+
+        let session = open(Config::default()).res().unwrap();
+        let scope = DeclarationScope::new();
+        let subscriber = scope.add(session.declare_subscriber("test/scope/**").res().unwrap());
+        let publisher = scope.add(session.declare_publisher("test/scope/a").res().unwrap());
+        // A publisher only referenced by the scope
+        drop(scope.add(session.declare_publisher("test/scope/b").res().unwrap()));
+        assert_eq!(scope.len(), 3);
+        assert_eq!(zread!(session.state).publishers.len(), 2);
+
+        publisher.put("value").res().unwrap();
+        assert_eq!(subscriber.recv().unwrap().value.to_string(), "value");
+
+        scope.undeclare().res().unwrap();
+        assert!(scope.is_empty());
+        assert!(zread!(session.state).publishers.is_empty());
+        assert!(zread!(session.state).subscribers.is_empty());
+        // The subscriber does not receive anything anymore
+        assert!(subscriber.recv().is_err());
+
+        // The publisher still referenced by the application is released when dropped
+        drop(publisher);
+        assert!(zread!(session.state).publishers.is_empty());
+    }
+}
@@ -14,11 +14,21 @@
 use crate::handlers::{locked, Callback, DefaultHandler};
 use crate::net::runtime::{orchestrator::Loop, Runtime};
 
+#[zenoh_macros::unstable]
+use std::collections::HashMap;
+#[zenoh_macros::unstable]
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+#[zenoh_macros::unstable]
+use std::time::Instant;
 use std::{fmt, future::Ready, net::SocketAddr, ops::Deref};
 use tokio::net::UdpSocket;
+#[zenoh_macros::unstable]
+use zenoh_core::zlock;
 use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
 use zenoh_protocol::core::WhatAmIMatcher;
+#[zenoh_macros::unstable]
+use zenoh_protocol::core::ZenohId;
 use zenoh_result::ZResult;
 use zenoh_task::TerminatableTask;
 
@@ -304,48 +314,359 @@ impl<Receiver> Scout<Receiver> {
     }
 }
 
-fn scout(
-    what: WhatAmIMatcher,
-    config: zenoh_config::Config,
-    callback: Callback<'static, Hello>,
-) -> ZResult<ScoutInner> {
-    tracing::trace!("scout({}, {})", what, &config);
+// The sockets to scout on and the multicast address to send the Scout messages to
+fn scout_sockets(config: &zenoh_config::Config) -> (Vec<UdpSocket>, SocketAddr) {
     let default_addr = SocketAddr::from(zenoh_config::defaults::scouting::multicast::address);
     let addr = config.scouting.multicast.address().unwrap_or(default_addr);
     let ifaces = config.scouting.multicast.interface().as_ref().map_or(
         zenoh_config::defaults::scouting::multicast::interface,
         |s| s.as_ref(),
     );
-    let ifaces = Runtime::get_interfaces(ifaces);
-    if !ifaces.is_empty() {
-        let sockets: Vec<UdpSocket> = ifaces
-            .into_iter()
-            .filter_map(|iface| Runtime::bind_ucast_port(iface).ok())
-            .collect();
-        if !sockets.is_empty() {
-            let cancellation_token = TerminatableTask::create_cancellation_token();
-            let cancellation_token_clone = cancellation_token.clone();
-            let task = TerminatableTask::spawn(
-                zenoh_runtime::ZRuntime::Acceptor,
-                async move {
-                    let scout = Runtime::scout(&sockets, what, &addr, move |hello| {
-                        let callback = callback.clone();
-                        async move {
-                            callback(hello);
-                            Loop::Continue
-                        }
-                    });
-                    tokio::select! {
-                        _ = scout => {},
-                        _ = cancellation_token_clone.cancelled() => { tracing::trace!("stop scout({}, {})", what, &config); },
+    let ttl = config
+        .scouting
+        .multicast
+        .ttl()
+        .unwrap_or(zenoh_config::defaults::scouting::multicast::ttl);
+    let sockets = Runtime::get_interfaces(ifaces)
+        .into_iter()
+        .filter_map(|iface| Runtime::bind_ucast_port(iface, ttl).ok())
+        .collect();
+    (sockets, addr)
+}
+
+fn scout(
+    what: WhatAmIMatcher,
+    config: zenoh_config::Config,
+    callback: Callback<'static, Hello>,
+) -> ZResult<ScoutInner> {
+    tracing::trace!("scout({}, {})", what, &config);
+    let (sockets, addr) = scout_sockets(&config);
+    if !sockets.is_empty() {
+        let cancellation_token = TerminatableTask::create_cancellation_token();
+        let cancellation_token_clone = cancellation_token.clone();
+        let task = TerminatableTask::spawn(
+            zenoh_runtime::ZRuntime::Acceptor,
+            async move {
+                let scout = Runtime::scout(&sockets, what, &addr, move |hello| {
+                    let callback = callback.clone();
+                    async move {
+                        callback(hello);
+                        Loop::Continue
+                    }
+                });
+                tokio::select! {
+                    _ = scout => {},
+                    _ = cancellation_token_clone.cancelled() => { tracing::trace!("stop scout({}, {})", what, &config); },
+                }
+            },
+            cancellation_token.clone(),
+        );
+        return Ok(ScoutInner {
+            scout_task: Some(task),
+        });
+    }
+    Ok(ScoutInner { scout_task: None })
+}
+
+// Three times the maximum period between two Scout messages
+#[zenoh_macros::unstable]
+const SCOUT_WATCH_DEFAULT_TIMEOUT: Duration = Duration::from_secs(24);
+
+/// A change in the zenoh nodes discovered by [`scout_watch`](crate::scout_watch).
+#[zenoh_macros::unstable]
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScoutEvent {
+    /// A node replied for the first time, or after having disappeared.
+    Appeared(Hello),
+    /// A node replied with a [`Hello`] different from its previous one, e.g. with new locators.
+    Updated(Hello),
+    /// A node did not reply within the timeout of the watch.
+    Disappeared(ZenohId),
+}
+
+#[zenoh_macros::unstable]
+impl ScoutEvent {
+    /// The [`ZenohId`] of the node this event is about.
+    pub fn zid(&self) -> ZenohId {
+        match self {
+            ScoutEvent::Appeared(hello) | ScoutEvent::Updated(hello) => hello.zid,
+            ScoutEvent::Disappeared(zid) => *zid,
+        }
+    }
+}
+
+/// A builder for initializing a [`Scout`] reporting [`ScoutEvent`]s.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+/// use zenoh::scouting::{ScoutEvent, WhatAmI};
+///
+/// let receiver = zenoh::scout_watch(WhatAmI::Peer | WhatAmI::Router, config::default())
+///     .res()
+///     .await
+///     .unwrap();
+/// while let Ok(event) = receiver.recv_async().await {
+///     match event {
+///         ScoutEvent::Appeared(hello) => println!("+ {}", hello),
+///         ScoutEvent::Updated(hello) => println!("~ {}", hello),
+///         ScoutEvent::Disappeared(zid) => println!("- {}", zid),
+///         _ => {}
+///     }
+/// }
+/// # }
+/// ```
+#[zenoh_macros::unstable]
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+#[derive(Debug)]
+pub struct ScoutWatchBuilder<Handler> {
+    pub(crate) what: WhatAmIMatcher,
+    pub(crate) config: ZResult<crate::config::Config>,
+    pub(crate) timeout: Duration,
+    pub(crate) handler: Handler,
+}
+
+#[zenoh_macros::unstable]
+impl ScoutWatchBuilder<DefaultHandler> {
+    pub(crate) fn new(what: WhatAmIMatcher, config: ZResult<crate::config::Config>) -> Self {
+        ScoutWatchBuilder {
+            what,
+            config,
+            timeout: SCOUT_WATCH_DEFAULT_TIMEOUT,
+            handler: DefaultHandler,
+        }
+    }
+
+    /// Receive the [`ScoutEvent`]s of this scout with a callback.
+    #[inline]
+    pub fn callback<Callback>(self, callback: Callback) -> ScoutWatchBuilder<Callback>
+    where
+        Callback: Fn(ScoutEvent) + Send + Sync + 'static,
+    {
+        self.with(callback)
+    }
+
+    /// Receive the [`ScoutEvent`]s of this scout with a mutable callback.
+    ///
+    /// Using this guarantees that your callback will never be called concurrently.
+    #[inline]
+    pub fn callback_mut<CallbackMut>(
+        self,
+        callback: CallbackMut,
+    ) -> ScoutWatchBuilder<impl Fn(ScoutEvent) + Send + Sync + 'static>
+    where
+        CallbackMut: FnMut(ScoutEvent) + Send + Sync + 'static,
+    {
+        self.callback(locked(callback))
+    }
+
+    /// Receive the [`ScoutEvent`]s of this scout with a [`Handler`](crate::prelude::IntoCallbackReceiverPair).
+    #[inline]
+    pub fn with<Handler>(self, handler: Handler) -> ScoutWatchBuilder<Handler>
+    where
+        Handler: crate::prelude::IntoCallbackReceiverPair<'static, ScoutEvent>,
+    {
+        let ScoutWatchBuilder {
+            what,
+            config,
+            timeout,
+            handler: _,
+        } = self;
+        ScoutWatchBuilder {
+            what,
+            config,
+            timeout,
+            handler,
+        }
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<Handler> ScoutWatchBuilder<Handler> {
+    /// The time after which a node that did not reply is reported as [`ScoutEvent::Disappeared`].
+    ///
+    /// It should be greater than the maximum period between two Scout messages (8 seconds).
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<Handler> Resolvable for ScoutWatchBuilder<Handler>
+where
+    Handler: crate::prelude::IntoCallbackReceiverPair<'static, ScoutEvent> + Send,
+    Handler::Receiver: Send,
+{
+    type To = ZResult<Scout<Handler::Receiver>>;
+}
+
+#[zenoh_macros::unstable]
+impl<Handler> SyncResolve for ScoutWatchBuilder<Handler>
+where
+    Handler: crate::prelude::IntoCallbackReceiverPair<'static, ScoutEvent> + Send,
+    Handler::Receiver: Send,
+{
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        scout_watch(self.what, self.config?, self.timeout, callback)
+            .map(|scout| Scout { scout, receiver })
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<Handler> AsyncResolve for ScoutWatchBuilder<Handler>
+where
+    Handler: crate::prelude::IntoCallbackReceiverPair<'static, ScoutEvent> + Send,
+    Handler::Receiver: Send,
+{
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+// The last Hello received from each node, with its reception time
+#[zenoh_macros::unstable]
+#[derive(Default)]
+struct ScoutedNodes(HashMap<ZenohId, (Hello, Instant)>);
+
+#[zenoh_macros::unstable]
+impl ScoutedNodes {
+    // The event to report on the reception of a Hello, if any
+    fn received(&mut self, hello: Hello, now: Instant) -> Option<ScoutEvent> {
+        match self.0.insert(hello.zid, (hello.clone(), now)) {
+            None => Some(ScoutEvent::Appeared(hello)),
+            Some((previous, _)) if previous != hello => Some(ScoutEvent::Updated(hello)),
+            Some(_) => None,
+        }
+    }
+
+    // Forgets the nodes that did not reply within the timeout, returning them
+    fn expire(&mut self, now: Instant, timeout: Duration) -> Vec<ZenohId> {
+        let mut disappeared = vec![];
+        self.0.retain(|zid, (_, last)| {
+            let alive = now.saturating_duration_since(*last) < timeout;
+            if !alive {
+                disappeared.push(*zid);
+            }
+            alive
+        });
+        disappeared
+    }
+}
+
+#[zenoh_macros::unstable]
+fn scout_watch(
+    what: WhatAmIMatcher,
+    config: zenoh_config::Config,
+    timeout: Duration,
+    callback: Callback<'static, ScoutEvent>,
+) -> ZResult<ScoutInner> {
+    tracing::trace!("scout_watch({}, {})", what, &config);
+    let (sockets, addr) = scout_sockets(&config);
+    if sockets.is_empty() {
+        return Ok(ScoutInner { scout_task: None });
+    }
+    let nodes = Arc::new(Mutex::new(ScoutedNodes::default()));
+    let cancellation_token = TerminatableTask::create_cancellation_token();
+    let cancellation_token_clone = cancellation_token.clone();
+    let task = TerminatableTask::spawn(
+        zenoh_runtime::ZRuntime::Acceptor,
+        async move {
+            let scout = Runtime::scout(&sockets, what, &addr, {
+                let nodes = nodes.clone();
+                let callback = callback.clone();
+                move |hello| {
+                    let event = zlock!(nodes).received(hello, Instant::now());
+                    if let Some(event) = event {
+                        callback(event);
                     }
-                },
-                cancellation_token.clone(),
-            );
-            return Ok(ScoutInner {
-                scout_task: Some(task),
+                    async { Loop::Continue }
+                }
             });
+            let expire = async {
+                let mut interval =
+                    tokio::time::interval((timeout / 4).max(Duration::from_millis(100)));
+                loop {
+                    interval.tick().await;
+                    let disappeared = zlock!(nodes).expire(Instant::now(), timeout);
+                    for zid in disappeared {
+                        callback(ScoutEvent::Disappeared(zid));
+                    }
+                }
+            };
+            tokio::select! {
+                _ = scout => {},
+                _ = expire => {},
+                _ = cancellation_token_clone.cancelled() => { tracing::trace!("stop scout_watch({}, {})", what, &config); },
+            }
+        },
+        cancellation_token.clone(),
+    );
+    Ok(ScoutInner {
+        scout_task: Some(task),
+    })
+}
+
+#[cfg(all(test, feature = "unstable"))]
+mod tests {
+    use super::*;
+
+    fn hello(zid: ZenohId, locators: &[&str]) -> Hello {
+        Hello {
+            version: zenoh_protocol::VERSION,
+            whatami: WhatAmI::Peer,
+            zid,
+            locators: locators.iter().map(|l| l.parse().unwrap()).collect(),
         }
     }
-    Ok(ScoutInner { scout_task: None })
+
+    #[test]
+    fn scout_watch_events() {
+        let timeout = Duration::from_secs(10);
+        let now = Instant::now();
+        let mut nodes = ScoutedNodes::default();
+        let (zid1, zid2) = (ZenohId::rand(), ZenohId::rand());
+
+        // A node is reported on its first reply only, unless its Hello changes
+        let hello1 = hello(zid1, &["tcp/127.0.0.1:7447"]);
+        assert_eq!(
+            nodes.received(hello1.clone(), now),
+            Some(ScoutEvent::Appeared(hello1.clone()))
+        );
+        assert_eq!(nodes.received(hello1.clone(), now), None);
+        let hello1 = hello(zid1, &["tcp/127.0.0.1:7447", "udp/127.0.0.1:7447"]);
+        assert_eq!(
+            nodes.received(hello1.clone(), now),
+            Some(ScoutEvent::Updated(hello1.clone()))
+        );
+        let hello2 = hello(zid2, &["tcp/127.0.0.1:7448"]);
+        assert_eq!(
+            nodes.received(hello2.clone(), now + timeout / 2),
+            Some(ScoutEvent::Appeared(hello2.clone()))
+        );
+
+        // A node disappears when it did not reply within the timeout
+        assert!(nodes.expire(now + timeout / 2, timeout).is_empty());
+        assert_eq!(nodes.expire(now + timeout, timeout), vec![zid1]);
+        assert!(nodes.expire(now + timeout, timeout).is_empty());
+
+        // A reply keeps a node alive
+        assert_eq!(nodes.received(hello2.clone(), now + timeout), None);
+        assert!(nodes.expire(now + timeout * 3 / 2, timeout).is_empty());
+        assert_eq!(nodes.expire(now + timeout * 2, timeout), vec![zid2]);
+
+        // A node that disappeared appears again on its next reply
+        assert_eq!(
+            nodes.received(hello1.clone(), now + timeout * 2),
+            Some(ScoutEvent::Appeared(hello1))
+        );
+        assert_eq!(ScoutEvent::Disappeared(zid2).zid(), zid2);
+    }
 }
@@ -23,6 +23,7 @@ use crate::liveliness::{Liveliness, LivelinessTokenState};
 use crate::net::primitives::Primitives;
 use crate::net::routing::dispatcher::face::Face;
 use crate::net::runtime::Runtime;
+use crate::ordering::{OrderedDelivery, ORDERING_TIMEOUT};
 use crate::prelude::Locality;
 use crate::prelude::{KeyExpr, Parameters};
 use crate::publication::*;
@@ -33,7 +34,10 @@ use crate::runtime::RuntimeBuilder;
 use crate::sample::Attachment;
 use crate::sample::DataInfo;
 use crate::sample::QoS;
+#[cfg(feature = "unstable")]
+use crate::sample::TimestampUncertainty;
 use crate::selector::TIME_RANGE_KEY;
+use crate::stats::EntityStats;
 use crate::subscriber::*;
 use crate::Id;
 use crate::Priority;
@@ -46,22 +50,26 @@ use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::fmt;
 use std::ops::Deref;
-use std::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU16, AtomicU32, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::sync::RwLock;
-use std::time::Duration;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 use tracing::{error, trace, warn};
 use uhlc::HLC;
-use zenoh_buffers::ZBuf;
+use zenoh_buffers::{buffer::Buffer, ZBuf};
 use zenoh_collections::SingleOrVec;
 use zenoh_config::unwrap_or_default;
-use zenoh_core::{zconfigurable, zread, Resolve, ResolveClosure, ResolveFuture, SyncResolve};
+use zenoh_core::{
+    zconfigurable, zlock, zread, Resolve, ResolveClosure, ResolveFuture, SyncResolve,
+};
 use zenoh_protocol::network::AtomicRequestId;
 use zenoh_protocol::network::RequestId;
 use zenoh_protocol::{
     core::{
         key_expr::{keyexpr, OwnedKeyExpr},
-        AtomicExprId, CongestionControl, ExprId, WireExpr, ZenohId, EMPTY_EXPR_ID,
+        AtomicExprId, CongestionControl, Encoding, ExprId, KnownEncoding, WireExpr, ZenohId,
+        EMPTY_EXPR_ID,
     },
     network::{
         declare::{
@@ -86,10 +94,17 @@ use zenoh_task::TaskController;
 use zenoh_util::core::AsyncResolve;
 
 zconfigurable! {
+    #[mutable]
     pub(crate) static ref API_DATA_RECEPTION_CHANNEL_SIZE: usize = 256;
+    #[mutable]
     pub(crate) static ref API_QUERY_RECEPTION_CHANNEL_SIZE: usize = 256;
+    #[mutable]
     pub(crate) static ref API_REPLY_EMISSION_CHANNEL_SIZE: usize = 256;
+    #[mutable]
     pub(crate) static ref API_REPLY_RECEPTION_CHANNEL_SIZE: usize = 256;
+    #[mutable]
+    pub(crate) static ref API_REPLY_ORDERING_MAX_SIZE: usize = 16 * 1024 * 1024;
+    #[mutable]
     pub(crate) static ref API_OPEN_SESSION_DELAY: u64 = 500;
 }
 
@@ -101,6 +116,7 @@ pub(crate) struct SessionState {
     pub(crate) local_resources: HashMap<ExprId, Resource>,
     pub(crate) remote_resources: HashMap<ExprId, Resource>,
     //pub(crate) publications: Vec<OwnedKeyExpr>,
+    pub(crate) publishers: HashMap<Id, Arc<PublisherState>>,
     pub(crate) subscribers: HashMap<Id, Arc<SubscriberState>>,
     pub(crate) queryables: HashMap<Id, Arc<QueryableState>>,
     #[cfg(feature = "unstable")]
@@ -110,6 +126,12 @@ pub(crate) struct SessionState {
     pub(crate) queries: HashMap<RequestId, QueryState>,
     pub(crate) aggregated_subscribers: Vec<OwnedKeyExpr>,
     //pub(crate) aggregated_publishers: Vec<OwnedKeyExpr>,
+    #[cfg(feature = "unstable")]
+    pub(crate) closing_callbacks: Vec<Callback<'static, ClosingEvent>>,
+    #[cfg(feature = "unstable")]
+    pub(crate) queryable_conflict_callbacks: Vec<Callback<'static, QueryableConflictEvent>>,
+    #[cfg(feature = "unstable")]
+    pub(crate) declaration_rejected_callbacks: Vec<Callback<'static, DeclarationRejectedEvent>>,
 }
 
 impl SessionState {
@@ -125,12 +147,19 @@ impl SessionState {
             local_resources: HashMap::new(),
             remote_resources: HashMap::new(),
             //publications: Vec::new(),
+            publishers: HashMap::new(),
             subscribers: HashMap::new(),
             queryables: HashMap::new(),
             #[cfg(feature = "unstable")]
             tokens: HashMap::new(),
             #[cfg(feature = "unstable")]
             matching_listeners: HashMap::new(),
+            #[cfg(feature = "unstable")]
+            closing_callbacks: Vec::new(),
+            #[cfg(feature = "unstable")]
+            queryable_conflict_callbacks: Vec::new(),
+            #[cfg(feature = "unstable")]
+            declaration_rejected_callbacks: Vec::new(),
             queries: HashMap::new(),
             aggregated_subscribers,
             //aggregated_publishers,
@@ -300,7 +329,7 @@ impl<'s, 'a> SessionDeclarations<'s, 'a> for SessionRef<'a> {
             key_expr: TryIntoKeyExpr::try_into(key_expr).map_err(Into::into),
             reliability: Reliability::default(),
             mode: PushMode,
-            origin: Locality::default(),
+            origin: self.default_locality(),
             handler: DefaultHandler,
         }
     }
@@ -316,7 +345,7 @@ impl<'s, 'a> SessionDeclarations<'s, 'a> for SessionRef<'a> {
             session: self.clone(),
             key_expr: key_expr.try_into().map_err(Into::into),
             complete: false,
-            origin: Locality::default(),
+            origin: self.default_locality(),
             handler: DefaultHandler,
         }
     }
@@ -328,12 +357,17 @@ impl<'s, 'a> SessionDeclarations<'s, 'a> for SessionRef<'a> {
         TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
         <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
     {
+        let (congestion_control, priority) = self.default_qos();
         PublisherBuilder {
             session: self.clone(),
             key_expr: key_expr.try_into().map_err(Into::into),
-            congestion_control: CongestionControl::default(),
-            priority: Priority::default(),
-            destination: Locality::default(),
+            congestion_control,
+            priority,
+            destination: self.default_locality(),
+            ordered_delivery: false,
+            write_filtering: false,
+            congestion_throttling: false,
+            hop_limit: None,
         }
     }
     #[zenoh_macros::unstable]
@@ -396,6 +430,14 @@ pub struct Session {
     pub(crate) alive: bool,
     owns_runtime: bool,
     task_controller: TaskController,
+    // The samples of the publishers with ordered delivery, held until they can be delivered in order
+    ordering: Arc<Mutex<OrderedDelivery<Push>>>,
+    // The locality of the entities declared without explicit allowed origin/destination,
+    // read from the configuration when the session is opened
+    default_locality: Locality,
+    // The same for the congestion control and priority, and the encoding of the publications
+    default_qos: (CongestionControl, Priority),
+    default_encoding: Option<Encoding>,
 }
 
 static SESSION_ID_COUNTER: AtomicU16 = AtomicU16::new(0);
@@ -411,6 +453,17 @@ impl Session {
                 aggregated_subscribers,
                 aggregated_publishers,
             )));
+            let (default_locality, default_qos, default_encoding) = {
+                let conf = runtime.config().lock();
+                let locality: Locality = unwrap_or_default!(conf.default_locality()).into();
+                let qos: (CongestionControl, Priority) = (
+                    unwrap_or_default!(conf.qos().default_congestion_control()).into(),
+                    unwrap_or_default!(conf.qos().default_priority()).into(),
+                );
+                let encoding: Option<Encoding> =
+                    conf.publishing().default_encoding().clone().map(Into::into);
+                (locality, qos, encoding)
+            };
             let session = Session {
                 runtime: runtime.clone(),
                 state: state.clone(),
@@ -418,6 +471,10 @@ impl Session {
                 alive: true,
                 owns_runtime: false,
                 task_controller: TaskController::default(),
+                ordering: Arc::new(Mutex::new(OrderedDelivery::default())),
+                default_locality,
+                default_qos,
+                default_encoding,
             };
 
             runtime.new_handler(Arc::new(admin::Handler::new(session.clone())));
@@ -579,6 +636,118 @@ impl Session {
     pub fn config(&self) -> &Notifier<Config> {
         self.runtime.config()
     }
+
+    /// Register a callback called when a transport with a remote zenoh node is being closed
+    /// for a known reason, e.g. when the lease expired or when the remote node closed it.
+    /// This allows to distinguish a network loss from a closure decided by the remote node.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// session.on_closing(|event| println!("Closing transport with {}: {:?}", event.zid, event.reason));
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn on_closing<C>(&self, callback: C)
+    where
+        C: Fn(ClosingEvent) + Send + Sync + 'static,
+    {
+        zwrite!(self.state)
+            .closing_callbacks
+            .push(Arc::new(callback));
+    }
+
+    #[zenoh_macros::unstable]
+    pub(crate) fn handle_closing(&self, event: ClosingEvent) {
+        let callbacks = zread!(self.state).closing_callbacks.clone();
+        for callback in callbacks {
+            callback(event.clone());
+        }
+    }
+
+    /// Register a callback called when a complete queryable of this session overlaps a complete
+    /// queryable declared by another session on an intersecting key expression, in which case
+    /// the queries targeting a single complete queryable may be routed to either of them.
+    /// The conflicts are detected by the nodes the queryables are declared through, so the
+    /// same conflict may be notified several times.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// session.on_queryable_conflict(|event| {
+    ///     println!("Queryable {} overlaps {}", event.key_expr, event.conflicting_key_expr)
+    /// });
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn on_queryable_conflict<C>(&self, callback: C)
+    where
+        C: Fn(QueryableConflictEvent) + Send + Sync + 'static,
+    {
+        zwrite!(self.state)
+            .queryable_conflict_callbacks
+            .push(Arc::new(callback));
+    }
+
+    #[zenoh_macros::unstable]
+    pub(crate) fn handle_queryable_conflict(&self, event: QueryableConflictEvent) {
+        let callbacks = zread!(self.state).queryable_conflict_callbacks.clone();
+        for callback in callbacks {
+            callback(event.clone());
+        }
+    }
+
+    /// Register a callback called when a declaration of this session (e.g. of a subscriber or
+    /// a queryable) is rejected by the node it is connected to, for exceeding its routing limits
+    /// (see `routing.limits` in the configuration). The rejected declaration is not routed, while
+    /// the declaring API call has already returned successfully.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// session.on_declaration_rejected(|event| {
+    ///     println!("Declaration rejected by {}: {}", event.zid, event.reason)
+    /// });
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn on_declaration_rejected<C>(&self, callback: C)
+    where
+        C: Fn(DeclarationRejectedEvent) + Send + Sync + 'static,
+    {
+        zwrite!(self.state)
+            .declaration_rejected_callbacks
+            .push(Arc::new(callback));
+    }
+
+    #[zenoh_macros::unstable]
+    pub(crate) fn handle_declaration_rejected(&self, event: DeclarationRejectedEvent) {
+        let callbacks = zread!(self.state).declaration_rejected_callbacks.clone();
+        for callback in callbacks {
+            callback(event.clone());
+        }
+    }
+
+    #[zenoh_macros::unstable]
+    pub(crate) fn handle_congestion(&self, event: crate::publication::CongestionEvent) {
+        let publishers: Vec<Arc<PublisherState>> =
+            zread!(self.state).publishers.values().cloned().collect();
+        for publisher in publishers {
+            publisher.congestion.notify(event);
+        }
+    }
 }
 
 impl<'a> SessionDeclarations<'a, 'a> for Session {
@@ -718,9 +887,12 @@ impl Session {
         PutBuilder {
             publisher: self.declare_publisher(key_expr),
             value: value.into(),
+            encoding: None,
             kind: SampleKind::Put,
             #[cfg(feature = "unstable")]
             attachment: None,
+            #[cfg(feature = "unstable")]
+            timestamp: None,
         }
     }
 
@@ -752,9 +924,12 @@ impl Session {
         PutBuilder {
             publisher: self.declare_publisher(key_expr),
             value: Value::empty(),
+            encoding: None,
             kind: SampleKind::Delete,
             #[cfg(feature = "unstable")]
             attachment: None,
+            #[cfg(feature = "unstable")]
+            timestamp: None,
         }
     }
     /// Query data from the matching queryables in the system.
@@ -798,11 +973,13 @@ impl Session {
             scope: Ok(None),
             target: QueryTarget::default(),
             consolidation: QueryConsolidation::default(),
-            destination: Locality::default(),
+            destination: self.default_locality(),
             timeout,
             value: None,
             #[cfg(feature = "unstable")]
             attachment: None,
+            #[cfg(feature = "unstable")]
+            reply_ordering: ReplyOrdering::default(),
             handler: DefaultHandler,
         }
     }
@@ -817,9 +994,37 @@ impl Session {
             alive: false,
             owns_runtime: self.owns_runtime,
             task_controller: self.task_controller.clone(),
+            ordering: self.ordering.clone(),
+            default_locality: self.default_locality,
+            default_qos: self.default_qos,
+            default_encoding: self.default_encoding.clone(),
         }
     }
 
+    // The locality of the entities declared without explicit allowed origin/destination
+    pub(crate) fn default_locality(&self) -> Locality {
+        self.default_locality
+    }
+
+    // The congestion control and priority of the publications declared without explicit ones
+    pub(crate) fn default_qos(&self) -> (CongestionControl, Priority) {
+        self.default_qos
+    }
+
+    // Applies the explicit encoding of a publication or, when none was set, the configured
+    // default encoding to a published raw payload
+    pub(crate) fn publication_encoding(&self, value: Value, encoding: Option<Encoding>) -> Value {
+        if let Some(encoding) = encoding {
+            return value.encoding(encoding);
+        }
+        if value.encoding == KnownEncoding::AppOctetStream.into() {
+            if let Some(encoding) = &self.default_encoding {
+                return value.encoding(encoding.clone());
+            }
+        }
+        value
+    }
+
     #[allow(clippy::new_ret_no_self)]
     pub(super) fn new(config: Config) -> impl Resolve<ZResult<Session>> {
         ResolveFuture::new(async move {
@@ -838,7 +1043,7 @@ impl Session {
             session.owns_runtime = true;
             runtime.start().await?;
             // Workaround for the declare_and_shoot problem
-            tokio::time::sleep(Duration::from_millis(*API_OPEN_SESSION_DELAY)).await;
+            tokio::time::sleep(Duration::from_millis(API_OPEN_SESSION_DELAY.get())).await;
             Ok(session)
         })
     }
@@ -970,6 +1175,40 @@ impl Session {
         })
     }
 
+    pub(crate) fn declare_publisher_state(
+        &self,
+        key_expr: &KeyExpr,
+        congestion_control: CongestionControl,
+        priority: Priority,
+        ordered_delivery: bool,
+        #[cfg_attr(not(feature = "unstable"), allow(unused_variables))] congestion_throttling: bool,
+    ) -> Arc<PublisherState> {
+        let mut state = zwrite!(self.state);
+        let id = state.decl_id_counter.fetch_add(1, Ordering::SeqCst);
+        let pub_state = Arc::new(PublisherState {
+            id,
+            key_expr: key_expr.clone().into_owned(),
+            stats: EntityStats::default(),
+            qos: std::sync::Mutex::new((congestion_control, priority)),
+            sn: ordered_delivery.then(|| AtomicU32::new(0)),
+            #[cfg(feature = "unstable")]
+            write_filter: std::sync::OnceLock::new(),
+            #[cfg(feature = "unstable")]
+            congestion: crate::publication::PublisherCongestion::new(congestion_throttling),
+        });
+        state.publishers.insert(id, pub_state.clone());
+        pub_state
+    }
+
+    pub(crate) fn undeclare_publisher_state(&self, pid: Id) {
+        let mut state = zwrite!(self.state);
+        let _pub_state = state.publishers.remove(&pid);
+        #[cfg(feature = "unstable")]
+        if let Some(filter) = _pub_state.as_ref().and_then(|s| s.write_filter.get()) {
+            state.matching_listeners.remove(&filter.id);
+        }
+    }
+
     pub(crate) fn declare_subscriber_inner(
         &self,
         key_expr: &KeyExpr,
@@ -992,6 +1231,7 @@ impl Session {
             scope: scope.clone().map(|e| e.into_owned()),
             origin,
             callback,
+            stats: EntityStats::new(&key_expr),
         });
 
         #[cfg(not(feature = "unstable"))]
@@ -1199,12 +1439,17 @@ impl Session {
         let mut state = zwrite!(self.state);
         tracing::trace!("queryable({:?})", key_expr);
         let id = state.decl_id_counter.fetch_add(1, Ordering::SeqCst);
+        let stats = match state.local_wireexpr_to_expr(key_expr) {
+            Ok(expr) => EntityStats::new(&expr),
+            Err(_) => EntityStats::default(),
+        };
         let qable_state = Arc::new(QueryableState {
             id,
             key_expr: key_expr.to_owned(),
             complete,
             origin,
             callback,
+            stats,
         });
         #[cfg(feature = "complete_n")]
         {
@@ -1587,6 +1832,93 @@ impl Session {
         }
     }
 
+    // Deliver the samples of the publishers with ordered delivery without holding the lock,
+    // unless another thread is already delivering them
+    fn deliver_ordered<'a>(&'a self, mut ordering: MutexGuard<'a, OrderedDelivery<Push>>) {
+        if !ordering.start_delivery() {
+            return;
+        }
+        while let Some(ready) = ordering.take_ready() {
+            drop(ordering);
+            for msg in ready {
+                self.handle_push(msg);
+            }
+            ordering = zlock!(self.ordering);
+        }
+    }
+
+    // Periodically deliver the samples held for too long waiting for missing ones,
+    // until no publisher with ordered delivery is tracked anymore
+    fn start_ordering_expiration(&self) {
+        let token = self.task_controller.get_cancellation_token();
+        self.task_controller
+            .spawn_with_rt(zenoh_runtime::ZRuntime::Net, {
+                let session = self.clone();
+                async move {
+                    let mut interval = tokio::time::interval(ORDERING_TIMEOUT / 2);
+                    loop {
+                        tokio::select! {
+                            _ = interval.tick() => {
+                                let mut ordering = zlock!(session.ordering);
+                                let ready = ordering.reorderer.expire(Instant::now());
+                                ordering.queue(ready);
+                                if ordering.reorderer.is_empty() {
+                                    ordering.expiring = false;
+                                    session.deliver_ordered(ordering);
+                                    break;
+                                }
+                                session.deliver_ordered(ordering);
+                            }
+                            _ = token.cancelled() => break,
+                        }
+                    }
+                }
+            });
+    }
+
+    fn handle_push(&self, msg: Push) {
+        match msg.payload {
+            PushBody::Put(m) => {
+                let info = DataInfo {
+                    kind: SampleKind::Put,
+                    encoding: Some(m.encoding),
+                    timestamp: m.timestamp,
+                    qos: QoS::from(msg.ext_qos),
+                    source_id: m.ext_sinfo.as_ref().map(|i| i.zid),
+                    source_sn: m.ext_sinfo.as_ref().map(|i| i.sn as u64),
+                    timestamp_bound: m.ext_tbound.map(|b| b.bound),
+                };
+                self.handle_data(
+                    false,
+                    &msg.wire_expr,
+                    Some(info),
+                    m.payload,
+                    #[cfg(feature = "unstable")]
+                    m.ext_attachment.map(Into::into),
+                )
+            }
+            PushBody::Del(m) => {
+                let info = DataInfo {
+                    kind: SampleKind::Delete,
+                    encoding: None,
+                    timestamp: m.timestamp,
+                    qos: QoS::from(msg.ext_qos),
+                    source_id: m.ext_sinfo.as_ref().map(|i| i.zid),
+                    source_sn: m.ext_sinfo.as_ref().map(|i| i.sn as u64),
+                    timestamp_bound: m.ext_tbound.map(|b| b.bound),
+                };
+                self.handle_data(
+                    false,
+                    &msg.wire_expr,
+                    Some(info),
+                    ZBuf::empty(),
+                    #[cfg(feature = "unstable")]
+                    m.ext_attachment.map(Into::into),
+                )
+            }
+        }
+    }
+
     pub(crate) fn handle_data(
         &self,
         local: bool,
@@ -1607,6 +1939,7 @@ impl Session {
                             match &sub.scope {
                                 Some(scope) => {
                                     if !res.key_expr.starts_with(&***scope) {
+                                        sub.stats.record_drop();
                                         tracing::warn!(
                                             "Received Data for `{}`, which didn't start with scope `{}`: don't deliver to scoped Subscriber.",
                                             res.key_expr,
@@ -1615,11 +1948,16 @@ impl Session {
                                     } else {
                                         match KeyExpr::try_from(&res.key_expr[(scope.len() + 1)..])
                                         {
-                                            Ok(key_expr) => callbacks.push((
-                                                sub.callback.clone(),
-                                                key_expr.into_owned(),
-                                            )),
+                                            Ok(key_expr) => {
+                                                sub.stats.record_sample(payload.len());
+                                                sub.stats.record_key(&key_expr);
+                                                callbacks.push((
+                                                    sub.callback.clone(),
+                                                    key_expr.into_owned(),
+                                                ))
+                                            }
                                             Err(e) => {
+                                                sub.stats.record_drop();
                                                 tracing::warn!(
                                                     "Error unscoping received Data for `{}`: {}",
                                                     res.key_expr,
@@ -1629,8 +1967,12 @@ impl Session {
                                         }
                                     }
                                 }
-                                None => callbacks
-                                    .push((sub.callback.clone(), res.key_expr.clone().into())),
+                                None => {
+                                    sub.stats.record_sample(payload.len());
+                                    sub.stats.record_key(&res.key_expr);
+                                    callbacks
+                                        .push((sub.callback.clone(), res.key_expr.clone().into()))
+                                }
                             };
                         }
                     }
@@ -1658,6 +2000,7 @@ impl Session {
                             match &sub.scope {
                                 Some(scope) => {
                                     if !key_expr.starts_with(&***scope) {
+                                        sub.stats.record_drop();
                                         tracing::warn!(
                                             "Received Data for `{}`, which didn't start with scope `{}`: don't deliver to scoped Subscriber.",
                                             key_expr,
@@ -1665,11 +2008,16 @@ impl Session {
                                         );
                                     } else {
                                         match KeyExpr::try_from(&key_expr[(scope.len() + 1)..]) {
-                                            Ok(key_expr) => callbacks.push((
-                                                sub.callback.clone(),
-                                                key_expr.into_owned(),
-                                            )),
+                                            Ok(key_expr) => {
+                                                sub.stats.record_sample(payload.len());
+                                                sub.stats.record_key(&key_expr);
+                                                callbacks.push((
+                                                    sub.callback.clone(),
+                                                    key_expr.into_owned(),
+                                                ))
+                                            }
                                             Err(e) => {
+                                                sub.stats.record_drop();
                                                 tracing::warn!(
                                                     "Error unscoping received Data for `{}`: {}",
                                                     key_expr,
@@ -1679,8 +2027,12 @@ impl Session {
                                         }
                                     }
                                 }
-                                None => callbacks
-                                    .push((sub.callback.clone(), key_expr.clone().into_owned())),
+                                None => {
+                                    sub.stats.record_sample(payload.len());
+                                    sub.stats.record_key(&key_expr);
+                                    callbacks
+                                        .push((sub.callback.clone(), key_expr.clone().into_owned()))
+                                }
                             };
                         }
                     }
@@ -1692,6 +2044,26 @@ impl Session {
             }
         };
         drop(state);
+        // The samples published by the session itself are timestamped by its own clock
+        #[cfg(feature = "unstable")]
+        let timestamp_uncertainty = match info.as_ref() {
+            Some(info) => TimestampUncertainty::on_reception(
+                info.timestamp.as_ref(),
+                info.timestamp_bound,
+                (!local).then(|| self.runtime.max_drift()).flatten(),
+            ),
+            None => TimestampUncertainty::default(),
+        };
+        #[cfg(feature = "unstable")]
+        let reception_timestamp = Some(crate::time::new_reception_timestamp());
+        #[cfg(feature = "unstable")]
+        if timestamp_uncertainty.exceeds_max_drift {
+            tracing::debug!(
+                "Received Data for `{}` with timestamp {:?} exceeding the max drift",
+                key_expr,
+                info.as_ref().and_then(|i| i.timestamp.as_ref())
+            );
+        }
         let zenoh_collections::single_or_vec::IntoIter { drain, last } = callbacks.into_iter();
         for (cb, key_expr) in drain {
             #[allow(unused_mut)]
@@ -1699,6 +2071,8 @@ impl Session {
             #[cfg(feature = "unstable")]
             {
                 sample.attachment.clone_from(&attachment);
+                sample.timestamp_uncertainty = timestamp_uncertainty;
+                sample.reception_timestamp = reception_timestamp;
             }
             cb(sample);
         }
@@ -1708,6 +2082,8 @@ impl Session {
             #[cfg(feature = "unstable")]
             {
                 sample.attachment = attachment;
+                sample.timestamp_uncertainty = timestamp_uncertainty;
+                sample.reception_timestamp = reception_timestamp;
             }
             cb(sample);
         }
@@ -1747,6 +2123,7 @@ impl Session {
         timeout: Duration,
         value: Option<Value>,
         #[cfg(feature = "unstable")] attachment: Option<Attachment>,
+        #[cfg(feature = "unstable")] reply_ordering: ReplyOrdering,
         callback: Callback<'static, Reply>,
     ) -> ZResult<()> {
         tracing::trace!("get({}, {:?}, {:?})", selector, target, consolidation);
@@ -1776,18 +2153,13 @@ impl Session {
                     tokio::select! {
                         _ = tokio::time::sleep(timeout) => {
                             let mut state = zwrite!(state);
-                            if let Some(query) = state.queries.remove(&qid) {
+                            if let Some(mut query) = state.queries.remove(&qid) {
                                 std::mem::drop(state);
                                 tracing::debug!("Timeout on query {}! Send error and close.", qid);
-                                if query.reception_mode == ConsolidationMode::Latest {
-                                    for (_, reply) in query.replies.unwrap().into_iter() {
-                                        (query.callback)(reply);
-                                    }
+                                for reply in query.take_pending_replies() {
+                                    (query.callback)(reply);
                                 }
-                                (query.callback)(Reply {
-                                    sample: Err("Timeout".into()),
-                                    replier_id: zid,
-                                });
+                                (query.callback)(Reply::new(Err("Timeout".into()), zid));
                             }
                         }
                         _ = token.cancelled() => {}
@@ -1803,6 +2175,13 @@ impl Session {
             None => selector.clone(),
         };
 
+        #[allow(unused_mut)]
+        let mut ordered = None;
+        #[cfg(feature = "unstable")]
+        if reply_ordering == ReplyOrdering::KeyThenTime {
+            ordered = Some(OrderedReplies::default());
+        }
+
         tracing::trace!("Register query {} (nb_final = {})", qid, nb_final);
         let wexpr = selector.key_expr.to_wire(self).to_owned();
         state.queries.insert(
@@ -1813,6 +2192,7 @@ impl Session {
                 scope: scope.clone().map(|e| e.into_owned()),
                 reception_mode: consolidation,
                 replies: (consolidation != ConsolidationMode::None).then(HashMap::new),
+                ordered,
                 callback,
             },
         );
@@ -1867,6 +2247,7 @@ impl Session {
                     encoding: v.encoding.clone(),
                     payload: v.payload.clone(),
                 }),
+                Some(timeout),
                 #[cfg(feature = "unstable")]
                 attachment,
             );
@@ -1884,8 +2265,10 @@ impl Session {
         _target: TargetType,
         _consolidation: ConsolidationType,
         body: Option<QueryBodyType>,
+        timeout: Option<Duration>,
         #[cfg(feature = "unstable")] attachment: Option<Attachment>,
     ) {
+        let body_len = body.as_ref().map(|b| b.payload.len()).unwrap_or(0);
         let (primitives, key_expr, callbacks) = {
             let state = zread!(self.state);
             match state.wireexpr_to_keyexpr(key_expr, local) {
@@ -1911,8 +2294,12 @@ impl Session {
                                     }
                                 }
                         )
-                        .map(|qable| qable.callback.clone())
-                        .collect::<Vec<Arc<dyn Fn(Query) + Send + Sync>>>();
+                        .map(|qable| {
+                            qable.stats.record_sample(body_len);
+                            qable.stats.record_key(&key_expr);
+                            (qable.id as u32, qable.callback.clone())
+                        })
+                        .collect::<Vec<(u32, Arc<dyn Fn(Query) + Send + Sync>)>>();
                     (
                         state.primitives.as_ref().unwrap().clone(),
                         key_expr.into_owned(),
@@ -1930,27 +2317,29 @@ impl Session {
 
         let zid = self.runtime.zid(); // @TODO build/use prebuilt specific zid
 
-        let query = Query {
-            inner: Arc::new(QueryInner {
-                key_expr,
-                parameters,
-                value: body.map(|b| Value {
-                    payload: b.payload,
-                    encoding: b.encoding,
-                }),
-                qid,
-                zid,
-                primitives: if local {
-                    Arc::new(self.clone())
-                } else {
-                    primitives
-                },
-                #[cfg(feature = "unstable")]
-                attachment,
+        let inner = Arc::new(QueryInner {
+            key_expr,
+            parameters,
+            value: body.map(|b| Value {
+                payload: b.payload,
+                encoding: b.encoding,
             }),
-        };
-        for callback in callbacks.iter() {
-            callback(query.clone());
+            qid,
+            zid,
+            primitives: if local {
+                Arc::new(self.clone())
+            } else {
+                primitives
+            },
+            #[cfg(feature = "unstable")]
+            attachment,
+            deadline: timeout.map(|t| Instant::now() + t),
+        });
+        for (eid, callback) in callbacks.iter() {
+            callback(Query {
+                inner: inner.clone(),
+                eid: *eid,
+            });
         }
     }
 }
@@ -1993,7 +2382,7 @@ impl<'s> SessionDeclarations<'s, 'static> for Arc<Session> {
             key_expr: key_expr.try_into().map_err(Into::into),
             reliability: Reliability::default(),
             mode: PushMode,
-            origin: Locality::default(),
+            origin: self.default_locality(),
             handler: DefaultHandler,
         }
     }
@@ -2038,7 +2427,7 @@ impl<'s> SessionDeclarations<'s, 'static> for Arc<Session> {
             session: SessionRef::Shared(self.clone()),
             key_expr: key_expr.try_into().map_err(Into::into),
             complete: false,
-            origin: Locality::default(),
+            origin: self.default_locality(),
             handler: DefaultHandler,
         }
     }
@@ -2071,12 +2460,17 @@ impl<'s> SessionDeclarations<'s, 'static> for Arc<Session> {
         TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
         <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
     {
+        let (congestion_control, priority) = self.default_qos();
         PublisherBuilder {
             session: SessionRef::Shared(self.clone()),
             key_expr: key_expr.try_into().map_err(Into::into),
-            congestion_control: CongestionControl::default(),
-            priority: Priority::default(),
-            destination: Locality::default(),
+            congestion_control,
+            priority,
+            destination: self.default_locality(),
+            ordered_delivery: false,
+            write_filtering: false,
+            congestion_throttling: false,
+            hop_limit: None,
         }
     }
 
@@ -2227,43 +2621,23 @@ impl Primitives for Session {
 
     fn send_push(&self, msg: Push) {
         trace!("recv Push {:?}", msg);
-        match msg.payload {
-            PushBody::Put(m) => {
-                let info = DataInfo {
-                    kind: SampleKind::Put,
-                    encoding: Some(m.encoding),
-                    timestamp: m.timestamp,
-                    qos: QoS::from(msg.ext_qos),
-                    source_id: m.ext_sinfo.as_ref().map(|i| i.zid),
-                    source_sn: m.ext_sinfo.as_ref().map(|i| i.sn as u64),
-                };
-                self.handle_data(
-                    false,
-                    &msg.wire_expr,
-                    Some(info),
-                    m.payload,
-                    #[cfg(feature = "unstable")]
-                    m.ext_attachment.map(Into::into),
-                )
-            }
-            PushBody::Del(m) => {
-                let info = DataInfo {
-                    kind: SampleKind::Delete,
-                    encoding: None,
-                    timestamp: m.timestamp,
-                    qos: QoS::from(msg.ext_qos),
-                    source_id: m.ext_sinfo.as_ref().map(|i| i.zid),
-                    source_sn: m.ext_sinfo.as_ref().map(|i| i.sn as u64),
-                };
-                self.handle_data(
-                    false,
-                    &msg.wire_expr,
-                    Some(info),
-                    ZBuf::empty(),
-                    #[cfg(feature = "unstable")]
-                    m.ext_attachment.map(Into::into),
-                )
+        let sinfo = match &msg.payload {
+            PushBody::Put(m) => m.ext_sinfo.as_ref(),
+            PushBody::Del(m) => m.ext_sinfo.as_ref(),
+        };
+        match sinfo.map(|i| (i.zid, i.eid, i.sn)) {
+            // Sent by a publisher with ordered delivery
+            Some((zid, eid, sn)) => {
+                let mut ordering = zlock!(self.ordering);
+                let ready = ordering.reorderer.push(zid, eid, sn, msg);
+                ordering.queue(ready);
+                if !ordering.expiring {
+                    ordering.expiring = true;
+                    self.start_ordering_expiration();
+                }
+                self.deliver_ordered(ordering);
             }
+            None => self.handle_push(msg),
         }
     }
 
@@ -2278,6 +2652,7 @@ impl Primitives for Session {
                 msg.ext_target,
                 m.ext_consolidation,
                 m.ext_body,
+                msg.ext_timeout,
                 #[cfg(feature = "unstable")]
                 m.ext_attachment.map(Into::into),
             ),
@@ -2316,13 +2691,16 @@ impl Primitives for Session {
                                 encoding: zenoh_protocol::core::Encoding::EMPTY,
                             },
                         };
-                        let replier_id = match e.ext_sinfo {
-                            Some(info) => info.zid,
-                            None => ZenohId::rand(),
+                        let (replier_id, replier_eid) = match (msg.ext_respid, e.ext_sinfo) {
+                            (Some(respid), _) => (respid.zid, Some(respid.eid)),
+                            (None, Some(info)) => (info.zid, None),
+                            (None, None) => (ZenohId::rand(), None),
                         };
                         let new_reply = Reply {
                             replier_id,
+                            replier_eid,
                             sample: Err(value),
+                            error_code: e.code,
                         };
                         callback(new_reply);
                     }
@@ -2391,6 +2769,7 @@ impl Primitives for Session {
                             qos: QoS::from(msg.ext_qos),
                             source_id: m.ext_sinfo.as_ref().map(|i| i.zid),
                             source_sn: m.ext_sinfo.as_ref().map(|i| i.sn as u64),
+                            timestamp_bound: m.ext_tbound.map(|b| b.bound),
                         };
                         #[allow(unused_mut)]
                         let mut sample =
@@ -2398,10 +2777,22 @@ impl Primitives for Session {
                         #[cfg(feature = "unstable")]
                         {
                             sample.attachment = m.ext_attachment.map(Into::into);
+                            sample.timestamp_uncertainty = TimestampUncertainty::on_reception(
+                                sample.timestamp.as_ref(),
+                                sample.timestamp_uncertainty.bound,
+                                self.runtime.max_drift(),
+                            );
+                            sample.reception_timestamp =
+                                Some(crate::time::new_reception_timestamp());
                         }
                         let new_reply = Reply {
                             sample: Ok(sample),
-                            replier_id: ZenohId::rand(), // TODO
+                            replier_id: msg
+                                .ext_respid
+                                .as_ref()
+                                .map_or_else(ZenohId::rand, |respid| respid.zid),
+                            replier_eid: msg.ext_respid.as_ref().map(|respid| respid.eid),
+                            error_code: 0,
                         };
                         let callback =
                             match query.reception_mode {
@@ -2482,10 +2873,23 @@ impl Primitives for Session {
                                     None
                                 }
                             };
+                        // The ordered replies are buffered until the query completes
+                        let mut callback = callback;
+                        let mut ordered = None;
+                        if let Some(buffer) = query.ordered.as_mut() {
+                            if let Some((callback, new_reply)) = callback.take() {
+                                ordered = buffer.push(new_reply).map(|replies| (callback, replies));
+                            }
+                        }
                         std::mem::drop(state);
                         if let Some((callback, new_reply)) = callback {
                             callback(new_reply);
                         }
+                        if let Some((callback, replies)) = ordered {
+                            for reply in replies {
+                                callback(reply);
+                            }
+                        }
                     }
                     None => {
                         tracing::warn!("Received ReplyData for unkown Query: {}", msg.rid);
@@ -2502,12 +2906,10 @@ impl Primitives for Session {
             Some(query) => {
                 query.nb_final -= 1;
                 if query.nb_final == 0 {
-                    let query = state.queries.remove(&msg.rid).unwrap();
+                    let mut query = state.queries.remove(&msg.rid).unwrap();
                     std::mem::drop(state);
-                    if query.reception_mode == ConsolidationMode::Latest {
-                        for (_, reply) in query.replies.unwrap().into_iter() {
-                            (query.callback)(reply);
-                        }
+                    for reply in query.take_pending_replies() {
+                        (query.callback)(reply);
                     }
                     trace!("Close query {}", msg.rid);
                 }
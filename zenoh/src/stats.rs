@@ -0,0 +1,260 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Statistics of declared entities.
+use rand::Rng;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use zenoh_keyexpr::keyexpr;
+
+/// The number of hot keys reported for a wildcard declaration.
+pub(crate) const HOT_KEYS_TOP_K: usize = 10;
+/// The duration of a hot keys counting window.
+pub(crate) const HOT_KEYS_WINDOW: Duration = Duration::from_secs(10);
+/// The maximum number of distinct keys counted in a window, bounding the memory
+/// used by declarations matching a large key space. Keys first seen once this
+/// bound is reached are not counted until the next window.
+const HOT_KEYS_MAX_TRACKED: usize = 1024;
+/// Only one key out of this number is counted, at random, so that the data path
+/// rarely takes the lock of the counts. The reported counts are scaled accordingly.
+const HOT_KEYS_SAMPLING: u32 = 16;
+
+/// The counters of a declared entity (i.e. [`Publisher`](crate::publication::Publisher),
+/// [`Subscriber`](crate::subscriber::Subscriber) or [`Queryable`](crate::queryable::Queryable)),
+/// updated on the data path.
+#[derive(Debug, Default)]
+pub(crate) struct EntityStats {
+    samples: AtomicU64,
+    bytes: AtomicU64,
+    drops: AtomicU64,
+    // Microseconds since UNIX_EPOCH, 0 meaning no activity yet.
+    last_activity: AtomicU64,
+    // Only set for wildcard declarations.
+    hot_keys: Option<HotKeys>,
+}
+
+impl EntityStats {
+    /// Creates the statistics of a declaration on `key_expr`, counting the
+    /// concrete keys it matches if it is a wildcard.
+    pub(crate) fn new(key_expr: &keyexpr) -> Self {
+        EntityStats {
+            hot_keys: key_expr
+                .is_wild()
+                .then(|| HotKeys::new(HOT_KEYS_TOP_K, HOT_KEYS_WINDOW, HOT_KEYS_SAMPLING)),
+            ..Default::default()
+        }
+    }
+
+    /// Counts `key` towards the hot keys of a wildcard declaration.
+    #[inline]
+    pub(crate) fn record_key(&self, key: &str) {
+        if let Some(hot_keys) = &self.hot_keys {
+            hot_keys.record(key);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn record_sample(&self, bytes: usize) {
+        self.samples.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.touch();
+    }
+
+    #[inline]
+    pub(crate) fn record_drop(&self) {
+        self.drops.fetch_add(1, Ordering::Relaxed);
+        self.touch();
+    }
+
+    #[inline]
+    fn touch(&self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_micros() as u64)
+            .unwrap_or(0);
+        self.last_activity.store(now, Ordering::Relaxed);
+    }
+
+    pub(crate) fn report(&self) -> EntityStatsReport {
+        let last_activity = match self.last_activity.load(Ordering::Relaxed) {
+            0 => None,
+            us => Some(UNIX_EPOCH + Duration::from_micros(us)),
+        };
+        EntityStatsReport {
+            samples: self.samples.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            drops: self.drops.load(Ordering::Relaxed),
+            last_activity,
+            hot_keys: self
+                .hot_keys
+                .as_ref()
+                .map(HotKeys::report)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Windowed counts of the concrete keys matched by a wildcard declaration.
+#[derive(Debug)]
+struct HotKeys {
+    top_k: usize,
+    window: Duration,
+    sampling: u32,
+    inner: Mutex<HotKeysWindows>,
+}
+
+#[derive(Debug)]
+struct HotKeysWindows {
+    start: Instant,
+    current: HashMap<String, u64>,
+    // The counts of the last complete window.
+    previous: HashMap<String, u64>,
+}
+
+impl HotKeys {
+    fn new(top_k: usize, window: Duration, sampling: u32) -> Self {
+        HotKeys {
+            top_k,
+            window,
+            sampling,
+            inner: Mutex::new(HotKeysWindows {
+                start: Instant::now(),
+                current: HashMap::new(),
+                previous: HashMap::new(),
+            }),
+        }
+    }
+
+    fn rotate(&self, windows: &mut HotKeysWindows) {
+        let elapsed = windows.start.elapsed();
+        if elapsed >= self.window {
+            windows.previous = if elapsed >= 2 * self.window {
+                // No activity during the whole last window.
+                HashMap::new()
+            } else {
+                std::mem::take(&mut windows.current)
+            };
+            windows.current.clear();
+            windows.start = Instant::now();
+        }
+    }
+
+    fn record(&self, key: &str) {
+        if self.sampling > 1 && !rand::thread_rng().gen_ratio(1, self.sampling) {
+            return;
+        }
+        let weight = self.sampling as u64;
+        let mut windows = zlock!(self.inner);
+        self.rotate(&mut windows);
+        if let Some(count) = windows.current.get_mut(key) {
+            *count += weight;
+        } else if windows.current.len() < HOT_KEYS_MAX_TRACKED {
+            windows.current.insert(key.to_owned(), weight);
+        }
+    }
+
+    // The top-K keys over the last complete window and the current one.
+    fn report(&self) -> Vec<HotKey> {
+        let mut windows = zlock!(self.inner);
+        self.rotate(&mut windows);
+        let mut counts = windows.previous.clone();
+        for (key, count) in &windows.current {
+            *counts.entry(key.clone()).or_default() += count;
+        }
+        drop(windows);
+        let mut hot_keys: Vec<HotKey> = counts
+            .into_iter()
+            .map(|(key_expr, count)| HotKey { key_expr, count })
+            .collect();
+        hot_keys.sort_unstable_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.key_expr.cmp(&b.key_expr))
+        });
+        hot_keys.truncate(self.top_k);
+        hot_keys
+    }
+}
+
+/// A concrete key matched by a wildcard [`Subscriber`](crate::subscriber::Subscriber)
+/// or [`Queryable`](crate::queryable::Queryable), with the number of samples or
+/// queries received for it over the last counting windows, estimated from a sample of them.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HotKey {
+    pub key_expr: String,
+    pub count: u64,
+}
+
+/// A snapshot of the statistics of a [`Publisher`](crate::publication::Publisher),
+/// a [`Subscriber`](crate::subscriber::Subscriber) or a [`Queryable`](crate::queryable::Queryable).
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EntityStatsReport {
+    /// The number of samples sent by a publisher or received by a subscriber,
+    /// or the number of queries received by a queryable.
+    pub samples: u64,
+    /// The cumulated size in bytes of the payloads of those samples.
+    pub bytes: u64,
+    /// The number of samples that were dropped before reaching the network (publisher)
+    /// or the application (subscriber).
+    pub drops: u64,
+    /// The time of the last sample sent, received or dropped, if any.
+    pub last_activity: Option<SystemTime>,
+    /// The most frequent concrete keys received by a wildcard subscriber or queryable,
+    /// most frequent first. Always empty for other declarations.
+    pub hot_keys: Vec<HotKey>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hot_keys() {
+        let hot_keys = HotKeys::new(HOT_KEYS_TOP_K, HOT_KEYS_WINDOW, 1);
+        for (key, n) in [("a/1", 3), ("a/2", 5), ("a/3", 1)] {
+            for _ in 0..n {
+                hot_keys.record(key);
+            }
+        }
+        let hot_keys = hot_keys.report();
+        assert_eq!(hot_keys[0].key_expr, "a/2");
+        assert_eq!(hot_keys[0].count, 5);
+        assert_eq!(hot_keys[1].key_expr, "a/1");
+        assert_eq!(hot_keys.len(), 3);
+
+        let stats = EntityStats::new(keyexpr::new("a/1").unwrap());
+        stats.record_key("a/1");
+        assert!(stats.report().hot_keys.is_empty());
+    }
+
+    #[test]
+    fn hot_keys_sampling() {
+        let stats = EntityStats::new(keyexpr::new("a/**").unwrap());
+        // Interleaved keys, so that a periodic sampling would only see one of them
+        for i in 0..20_000 {
+            stats.record_key(if i % 4 == 0 { "a/rare" } else { "a/hot" });
+        }
+        let hot_keys = stats.report().hot_keys;
+        assert_eq!(hot_keys[0].key_expr, "a/hot");
+        assert_eq!(hot_keys[1].key_expr, "a/rare");
+        // The counts are estimated within 20%
+        assert!((12_000..=18_000).contains(&hot_keys[0].count));
+        assert!((4_000..=6_000).contains(&hot_keys[1].count));
+    }
+}
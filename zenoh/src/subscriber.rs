@@ -16,6 +16,9 @@
 use crate::handlers::{locked, Callback, DefaultHandler};
 use crate::prelude::Locality;
 use crate::prelude::{Id, IntoCallbackReceiverPair, KeyExpr, Sample};
+use crate::stats::EntityStats;
+#[zenoh_macros::unstable]
+use crate::stats::EntityStatsReport;
 use crate::Undeclarable;
 use crate::{Result as ZResult, SessionRef};
 use std::fmt;
@@ -37,6 +40,7 @@ pub(crate) struct SubscriberState {
     pub(crate) scope: Option<KeyExpr<'static>>,
     pub(crate) origin: Locality,
     pub(crate) callback: Callback<'static, Sample>,
+    pub(crate) stats: EntityStats,
 }
 
 impl fmt::Debug for SubscriberState {
@@ -743,6 +747,12 @@ impl<'a, Receiver> PullSubscriber<'a, Receiver> {
         self.subscriber.pull()
     }
 
+    /// Returns a snapshot of the statistics of this [`PullSubscriber`].
+    #[zenoh_macros::unstable]
+    pub fn stats(&self) -> EntityStatsReport {
+        self.subscriber.inner.state.stats.report()
+    }
+
     /// Close a [`PullSubscriber`].
     ///
     /// Subscribers are automatically closed when dropped, but you may want to use this function to handle errors or
@@ -775,6 +785,40 @@ impl<'a, Receiver> Subscriber<'a, Receiver> {
         &self.subscriber.state.key_expr
     }
 
+    /// Returns a snapshot of the statistics of this [`Subscriber`].
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("key/expression").res().await.unwrap();
+    /// let stats = subscriber.stats();
+    /// println!("Received {} samples ({} bytes)", stats.samples, stats.bytes);
+    /// # }
+    /// ```
+    ///
+    /// For a wildcard subscriber, [`EntityStatsReport::hot_keys`] lists the concrete
+    /// keys that were received the most:
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("**").res().await.unwrap();
+    /// for hot_key in subscriber.stats().hot_keys {
+    ///     println!("{}: {} samples", hot_key.key_expr, hot_key.count);
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn stats(&self) -> EntityStatsReport {
+        self.subscriber.state.stats.report()
+    }
+
     /// Close a [`Subscriber`].
     ///
     /// Subscribers are automatically closed when dropped, but you may want to use this function to handle errors or
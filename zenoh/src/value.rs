@@ -61,6 +61,111 @@ impl Value {
         self.encoding = encoding;
         self
     }
+
+    /// A reader over the payload of this Value, reading its slices in place
+    /// instead of copying them in a contiguous buffer.
+    #[zenoh_macros::unstable]
+    pub fn reader(&self) -> impl std::io::Read + std::io::Seek + '_ {
+        use crate::buffers::reader::HasReader;
+        self.payload.reader()
+    }
+}
+
+/// A writer building the payload of a [`Value`] incrementally, e.g. to produce a very large value.
+///
+/// The written bytes are stored in chunks of a fixed capacity that become the slices of the payload,
+/// so that they are never moved to a bigger buffer while the payload grows.
+#[zenoh_macros::unstable]
+#[derive(Debug)]
+pub struct PayloadWriter {
+    payload: ZBuf,
+    chunk: Vec<u8>,
+    chunk_size: usize,
+}
+
+#[zenoh_macros::unstable]
+impl PayloadWriter {
+    /// The default capacity of the chunks of the payload.
+    pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+    /// Creates an empty writer with chunks of [`DEFAULT_CHUNK_SIZE`](Self::DEFAULT_CHUNK_SIZE) bytes.
+    pub fn new() -> Self {
+        Self::with_chunk_size(Self::DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Creates an empty writer with chunks of `chunk_size` bytes.
+    pub fn with_chunk_size(chunk_size: usize) -> Self {
+        PayloadWriter {
+            payload: ZBuf::empty(),
+            chunk: Vec::new(),
+            chunk_size: chunk_size.max(1),
+        }
+    }
+
+    /// Appends the slices of `payload` without copying them.
+    pub fn append(&mut self, payload: ZBuf) {
+        self.push_chunk();
+        for zslice in payload.zslices() {
+            self.payload.push_zslice(zslice.clone());
+        }
+    }
+
+    /// The number of bytes written so far.
+    pub fn len(&self) -> usize {
+        use crate::buffers::buffer::Buffer;
+        self.payload.len() + self.chunk.len()
+    }
+
+    /// Whether nothing has been written so far.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The written payload.
+    pub fn finish(mut self) -> ZBuf {
+        self.push_chunk();
+        self.payload
+    }
+
+    fn push_chunk(&mut self) {
+        if !self.chunk.is_empty() {
+            let chunk = std::mem::take(&mut self.chunk);
+            self.payload.push_zslice(chunk.into());
+        }
+    }
+}
+
+#[zenoh_macros::unstable]
+impl Default for PayloadWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[zenoh_macros::unstable]
+impl std::io::Write for PayloadWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.chunk.capacity() == 0 {
+            self.chunk.reserve_exact(self.chunk_size);
+        }
+        let len = buf.len().min(self.chunk_size - self.chunk.len());
+        self.chunk.extend_from_slice(&buf[..len]);
+        if self.chunk.len() == self.chunk_size {
+            self.push_chunk();
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[zenoh_macros::unstable]
+impl From<PayloadWriter> for Value {
+    fn from(writer: PayloadWriter) -> Self {
+        Value::new(writer.finish())
+    }
 }
 
 impl std::fmt::Debug for Value {
@@ -703,3 +808,56 @@ impl TryFrom<Value> for Properties {
         Self::try_from(&v)
     }
 }
+
+#[cfg(all(test, feature = "unstable"))]
+mod tests {
+    use super::*;
+    use crate::buffers::buffer::Buffer;
+    use std::io::{Read, Seek, SeekFrom, Write};
+
+    #[test]
+    fn payload_writer() {
+        let data: Vec<u8> = (0..=255).cycle().take(2_500).collect();
+
+        let mut writer = PayloadWriter::with_chunk_size(1_000);
+        assert!(writer.is_empty());
+        // A write is bounded by the room left in the current chunk
+        assert_eq!(writer.write(&data).unwrap(), 1_000);
+        writer.write_all(&data[1_000..]).unwrap();
+        assert_eq!(writer.len(), 2_500);
+        let payload = writer.finish();
+        let sizes: Vec<usize> = payload.zslices().map(|s| s.len()).collect();
+        assert_eq!(sizes, vec![1_000, 1_000, 500]);
+        assert_eq!(payload.contiguous(), data.as_slice());
+
+        // The appended payloads keep their slices, after the bytes written before them
+        let mut writer = PayloadWriter::with_chunk_size(1_000);
+        writer.write_all(&data[..10]).unwrap();
+        writer.append(payload.clone());
+        writer.write_all(&data[..10]).unwrap();
+        assert_eq!(writer.len(), 2_520);
+        let value: Value = writer.into();
+        let sizes: Vec<usize> = value.payload.zslices().map(|s| s.len()).collect();
+        assert_eq!(sizes, vec![10, 1_000, 1_000, 500, 10]);
+        let slices: Vec<_> = value.payload.zslices().collect();
+        for (appended, original) in slices[1..4].iter().zip(payload.zslices()) {
+            assert!(std::ptr::eq(
+                appended.as_slice().as_ptr(),
+                original.as_slice().as_ptr()
+            ));
+        }
+
+        // The payload is read back across its slices
+        let mut reader = value.reader();
+        let mut read = vec![];
+        reader.read_to_end(&mut read).unwrap();
+        assert_eq!(&read[10..2_510], data.as_slice());
+        reader.seek(SeekFrom::Start(1_005)).unwrap();
+        let mut buf = [0u8; 10];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf[..], &data[995..1_005]);
+
+        // Nothing written produces an empty payload
+        assert!(PayloadWriter::new().finish().is_empty());
+    }
+}
@@ -0,0 +1,161 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "unstable")]
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_core::ztimeout;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_secs(1);
+
+async fn admin_space_replies(endpoint: &str, rules: &str) -> Vec<String> {
+    let mut config = config::default();
+    config.set_mode(Some(WhatAmI::Router)).unwrap();
+    config.listen.endpoints = vec![endpoint.parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.insert_json5("adminspace/enabled", "true").unwrap();
+    config
+        .insert_json5("adminspace/permissions/rules", rules)
+        .unwrap();
+    let router = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+    let config = config::client([endpoint.parse::<EndPoint>().unwrap()]);
+    let client = ztimeout!(zenoh::open(config).res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let replies = ztimeout!(client
+        .get(format!("@/router/{}/**", router.zid()))
+        .timeout(SLEEP)
+        .res_async())
+    .unwrap();
+    let mut keys = vec![];
+    while let Ok(reply) = ztimeout!(replies.recv_async()) {
+        if let Ok(sample) = reply.sample {
+            keys.push(sample.key_expr.to_string());
+        }
+    }
+
+    ztimeout!(client.close().res_async()).unwrap();
+    ztimeout!(router.close().res_async()).unwrap();
+    keys
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn admin_space_permission_rules() {
+    zenoh_util::try_init_log_from_env();
+
+    // The queries of the instances granted nothing do not reach the admin space
+    let keys = admin_space_replies(
+        "tcp/127.0.0.1:27450",
+        r#"[{ protocols: ["unixpipe"], key_exprs: ["@/router/*/**"], read: true }]"#,
+    )
+    .await;
+    assert!(keys.is_empty(), "{keys:?}");
+
+    // Only the replies on the granted key expressions are sent
+    let keys = admin_space_replies(
+        "tcp/127.0.0.1:27451",
+        r#"[{ protocols: ["tcp"], key_exprs: ["@/router/*/linkstate/**"], read: true }]"#,
+    )
+    .await;
+    assert!(!keys.is_empty());
+    assert!(keys.iter().all(|k| k.contains("/linkstate/")), "{keys:?}");
+
+    let keys = admin_space_replies(
+        "tcp/127.0.0.1:27452",
+        r#"[{ protocols: ["tcp"], key_exprs: ["@/router/*/**"], read: true }]"#,
+    )
+    .await;
+    assert!(keys.iter().any(|k| !k.contains("/linkstate/")), "{keys:?}");
+}
+
+#[cfg(feature = "plugins")]
+struct StatusPlugin;
+
+#[cfg(feature = "plugins")]
+struct RunningStatusPlugin;
+
+#[cfg(feature = "plugins")]
+impl zenoh_plugin_trait::PluginControl for RunningStatusPlugin {
+    fn report(&self) -> zenoh_plugin_trait::PluginReport {
+        let mut report = zenoh_plugin_trait::PluginReport::new();
+        report.add_error("storage unavailable");
+        report.set_metric("requests", 42);
+        report
+    }
+}
+
+#[cfg(feature = "plugins")]
+impl zenoh::plugins::RunningPluginTrait for RunningStatusPlugin {}
+
+#[cfg(feature = "plugins")]
+impl zenoh_plugin_trait::Plugin for StatusPlugin {
+    type StartArgs = zenoh::runtime::Runtime;
+    type Instance = zenoh::plugins::RunningPlugin;
+
+    const DEFAULT_NAME: &'static str = "status_test";
+    const PLUGIN_VERSION: &'static str = "1.2.3";
+    const PLUGIN_LONG_VERSION: &'static str = "1.2.3-test";
+
+    fn start(_name: &str, _runtime: &Self::StartArgs) -> zenoh_result::ZResult<Self::Instance> {
+        Ok(Box::new(RunningStatusPlugin))
+    }
+}
+
+#[cfg(feature = "plugins")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn admin_space_plugin_status() {
+    zenoh_util::try_init_log_from_env();
+
+    let mut config = config::default();
+    config.set_mode(Some(WhatAmI::Router)).unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.insert_json5("adminspace/enabled", "true").unwrap();
+    let plugins_manager = zenoh::plugins::PluginsManager::static_plugins_only()
+        .declare_static_plugin::<StatusPlugin>(true);
+    let runtime = ztimeout!(zenoh::runtime::RuntimeBuilder::new(config)
+        .plugins_manager(plugins_manager)
+        .build())
+    .unwrap();
+    let session = ztimeout!(zenoh::init(runtime).res_async()).unwrap();
+
+    // The uniform status of the plugin is replied once under its `status` key
+    let replies = ztimeout!(session
+        .get(format!(
+            "@/router/{}/plugins/status_test/status",
+            session.zid()
+        ))
+        .res_async())
+    .unwrap();
+    let mut statuses = vec![];
+    while let Ok(reply) = ztimeout!(replies.recv_async()) {
+        let sample = reply.sample.unwrap();
+        assert_eq!(
+            sample.key_expr.as_str(),
+            format!("@/router/{}/plugins/status_test/status", session.zid())
+        );
+        statuses
+            .push(serde_json::from_str::<serde_json::Value>(&sample.value.to_string()).unwrap());
+    }
+    assert_eq!(statuses.len(), 1);
+    let status = &statuses[0];
+    assert_eq!(status["name"], "status_test");
+    assert_eq!(status["version"], "1.2.3");
+    assert_eq!(status["state"], "Started");
+    assert_eq!(status["report"]["level"], "Error");
+    assert_eq!(status["report"]["last_error"], "storage unavailable");
+    assert_eq!(status["report"]["metrics"]["requests"], 42);
+
+    ztimeout!(session.close().res_async()).unwrap();
+}
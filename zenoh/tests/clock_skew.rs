@@ -0,0 +1,106 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#[cfg(feature = "unstable")]
+#[test]
+fn clock_skew_estimation() {
+    use std::{convert::TryFrom, time::Duration};
+    use zenoh::prelude::sync::*;
+    use zenoh::sample::ClockSkewEstimator;
+    use zenoh::time::{Timestamp, TimestampId};
+
+    let source = TimestampId::try_from([2]).unwrap();
+    let silent = TimestampId::try_from([3]).unwrap();
+    let local = TimestampId::try_from([1]).unwrap();
+    let sample_of = |source: TimestampId, sent_ms: u64, received_ms: u64| {
+        let mut sample = Sample::try_from("test/skew", "value")
+            .unwrap()
+            .with_timestamp(Timestamp::new(
+                Duration::from_millis(sent_ms).into(),
+                source,
+            ));
+        sample.reception_timestamp = Some(Timestamp::new(
+            Duration::from_millis(received_ms).into(),
+            local,
+        ));
+        sample
+    };
+    let sample = |sent_ms: u64, received_ms: u64| sample_of(source, sent_ms, received_ms);
+
+    let estimator = ClockSkewEstimator::new(Duration::from_secs(1));
+    // No reception timestamp
+    assert!(estimator
+        .update(&Sample::try_from("test/skew", "value").unwrap())
+        .is_none());
+
+    // The source is 100ms behind, with a latency between 1ms and 5ms
+    for (i, latency) in [5, 1, 3].iter().enumerate() {
+        let sent = 10_000 + i as u64 * 100;
+        estimator.update(&sample(sent, sent + 100 + latency));
+    }
+    let skew = estimator.skew(&source).unwrap();
+    // The conversions of the timestamps to nanoseconds may be off by a few nanoseconds
+    assert!((skew.offset - 101_000_000).abs() < 10, "{}", skew.offset);
+    assert_eq!(skew.drift, None);
+    assert_eq!(skew.samples, 3);
+
+    // The source drifts by 10ms per second
+    estimator.update(&sample(10_994, 11_105));
+    let skew = estimator.update(&sample(11_984, 12_105)).unwrap();
+    assert!((skew.offset - 121_000_000).abs() < 10, "{}", skew.offset);
+    let drift = skew.drift.unwrap();
+    assert!((drift - 10_000_000.0).abs() < 100.0, "{drift}");
+    assert_eq!(estimator.skews().len(), 1);
+
+    // The sources silent for 10 windows are forgotten
+    estimator.update(&sample_of(silent, 12_000, 12_105));
+    assert_eq!(estimator.skews().len(), 2);
+    estimator.update(&sample(20_000, 20_105));
+    assert!(estimator.skew(&silent).is_some());
+    estimator.update(&sample(22_200, 22_305));
+    assert!(estimator.skew(&silent).is_none());
+    assert!(estimator.skew(&source).is_some());
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn reception_timestamp() {
+    use std::time::Duration;
+    use zenoh::prelude::sync::*;
+
+    let timeout = Duration::from_secs(5);
+    let session = zenoh::open(Config::default()).res().unwrap();
+    let subscriber = session.declare_subscriber("test/reception").res().unwrap();
+    let _queryable = session
+        .declare_queryable("test/reception")
+        .callback(|query| {
+            query
+                .reply(Ok(Sample::try_from("test/reception", "reply").unwrap()))
+                .res()
+                .unwrap()
+        })
+        .res()
+        .unwrap();
+
+    session.put("test/reception", "put").res().unwrap();
+    let sample = subscriber.recv_timeout(timeout).unwrap();
+    assert!(sample.reception_timestamp().is_some());
+
+    let replies = session
+        .get("test/reception")
+        .timeout(timeout)
+        .res()
+        .unwrap();
+    let sample = replies.recv_timeout(timeout).unwrap().sample.unwrap();
+    assert!(sample.reception_timestamp().is_some());
+}
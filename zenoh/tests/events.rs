@@ -108,3 +108,27 @@ async fn zenoh_events() {
     sub1.undeclare().res().await.unwrap();
     close_session(session).await;
 }
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_events_close_reason() {
+    use zenoh::info::CloseReason;
+
+    let session = open_session(&["tcp/127.0.0.1:18449"], &[]).await;
+    let (tx, rx) = flume::unbounded();
+    session.on_closing(move |event| {
+        let _ = tx.send(event);
+    });
+
+    let session2 = open_session(&["tcp/127.0.0.1:18450"], &["tcp/127.0.0.1:18449"]).await;
+    let zid2 = session2.zid();
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    close_session(session2).await;
+
+    let event = ztimeout!(rx.recv_async()).unwrap();
+    assert_eq!(event.zid, zid2);
+    assert_eq!(event.reason, CloseReason::Generic);
+
+    close_session(session).await;
+}
@@ -226,3 +226,108 @@ async fn zenoh_matching_status_local() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_publishers_discovery() -> Result<()> {
+    let locator = "tcp/127.0.0.1:18005";
+    let mut config1 = zenoh::config::peer();
+    config1.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config1
+        .listen
+        .set_endpoints(vec![locator.parse().unwrap()])
+        .unwrap();
+    config1.adminspace.set_publications(true).unwrap();
+    let config2 = zenoh::config::client([Locator::from_str(locator).unwrap()]);
+    let session1 = ztimeout!(zenoh::open(config1).res_async()).unwrap();
+    let session2 = ztimeout!(zenoh::open(config2).res_async()).unwrap();
+
+    let _publisher1 = ztimeout!(session1
+        .declare_publisher("zenoh_publishers_discovery_test/a")
+        .priority(Priority::DataHigh)
+        .res_async())
+    .unwrap();
+    let _publisher2 = ztimeout!(session2
+        .declare_publisher("zenoh_publishers_discovery_test/**")
+        .congestion_control(CongestionControl::Block)
+        .res_async())
+    .unwrap();
+    let _publisher3 = ztimeout!(session2
+        .declare_publisher("zenoh_publishers_discovery_other_test")
+        .res_async())
+    .unwrap();
+    tokio::time::sleep(RECV_TIMEOUT).await;
+
+    let mut publishers = ztimeout!(session2
+        .info()
+        .publishers("zenoh_publishers_discovery_test/a")
+        .timeout(RECV_TIMEOUT)
+        .res_async())
+    .unwrap();
+    publishers.sort_by(|a, b| a.key_expr.as_str().cmp(b.key_expr.as_str()));
+    assert_eq!(publishers.len(), 2);
+    assert_eq!(publishers[0].zid, session2.zid());
+    assert_eq!(publishers[0].congestion_control, CongestionControl::Block);
+    assert_eq!(publishers[1].zid, session1.zid());
+    assert_eq!(publishers[1].priority, Priority::DataHigh);
+
+    Ok(())
+}
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_publishers_discovery_local_only() -> Result<()> {
+    let (session1, session2) = create_session_pair("tcp/127.0.0.1:18007").await;
+
+    let _publisher1 = ztimeout!(session1
+        .declare_publisher("zenoh_publishers_discovery_local_only_test")
+        .res_async())
+    .unwrap();
+    let _publisher2 = ztimeout!(session2
+        .declare_publisher("zenoh_publishers_discovery_local_only_test")
+        .res_async())
+    .unwrap();
+    tokio::time::sleep(RECV_TIMEOUT).await;
+
+    // The publishers of session1 are not exposed to the remote sessions by default
+    let publishers = ztimeout!(session2
+        .info()
+        .publishers("zenoh_publishers_discovery_local_only_test")
+        .timeout(RECV_TIMEOUT)
+        .res_async())
+    .unwrap();
+    assert_eq!(publishers.len(), 1);
+    assert_eq!(publishers[0].zid, session2.zid());
+
+    Ok(())
+}
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_write_filtering() -> Result<()> {
+    let (session1, session2) = create_session_pair("tcp/127.0.0.1:18006").await;
+
+    let publisher = ztimeout!(session2
+        .declare_publisher("zenoh_write_filtering_test")
+        .write_filtering(true)
+        .res_async())
+    .unwrap();
+
+    ztimeout!(publisher.put("filtered").res_async()).unwrap();
+    assert_eq!(publisher.stats().samples, 0);
+    assert_eq!(publisher.stats().drops, 1);
+
+    let sub = ztimeout!(session1
+        .declare_subscriber("zenoh_write_filtering_test")
+        .res_async())
+    .unwrap();
+    tokio::time::sleep(RECV_TIMEOUT).await;
+
+    ztimeout!(publisher.put("delivered").res_async()).unwrap();
+    assert_eq!(publisher.stats().samples, 1);
+    assert_eq!(publisher.stats().drops, 1);
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.value.to_string(), "delivered");
+
+    Ok(())
+}
@@ -53,3 +53,89 @@ async fn pubsub() {
     assert_eq!(qos.priority(), Priority::DataLow);
     assert_eq!(qos.congestion_control(), CongestionControl::Block);
 }
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn ordered_delivery() {
+    const N: usize = 100;
+
+    let session1 = ztimeout!(zenoh::open(zenoh_config::peer()).res_async()).unwrap();
+    let session2 = ztimeout!(zenoh::open(zenoh_config::peer()).res_async()).unwrap();
+
+    let publisher = ztimeout!(session1
+        .declare_publisher("test/qos/ordered")
+        .ordered_delivery(true)
+        .res())
+    .unwrap();
+
+    let subscriber = ztimeout!(session2.declare_subscriber("test/qos/ordered").res()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    for i in 0..N {
+        ztimeout!(publisher.put(i.to_string()).res_async()).unwrap();
+    }
+    for i in 0..N {
+        let sample = ztimeout!(subscriber.recv_async()).unwrap();
+        assert_eq!(sample.value.to_string(), i.to_string());
+        assert_eq!(sample.source_info.source_id, Some(session1.zid()));
+        assert_eq!(sample.source_info.source_sn, Some(i as u64));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn defaults() {
+    let mut config = zenoh_config::peer();
+    config
+        .qos
+        .set_default_priority(Some(zenoh_config::PriorityConf::DataHigh))
+        .unwrap();
+    config
+        .qos
+        .set_default_congestion_control(Some(zenoh_config::CongestionControlModeConf::Block))
+        .unwrap();
+    config
+        .publishing
+        .set_default_encoding(Some("application/json".to_string()))
+        .unwrap();
+    let session1 = ztimeout!(zenoh::open(config).res_async()).unwrap();
+    let session2 = ztimeout!(zenoh::open(zenoh_config::peer()).res_async()).unwrap();
+
+    let subscriber = ztimeout!(session2.declare_subscriber("test/qos/defaults").res()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // Raw payload: the default encoding applies
+    ztimeout!(session1.put("test/qos/defaults", vec![b'1']).res_async()).unwrap();
+    let sample = ztimeout!(subscriber.recv_async()).unwrap();
+    assert_eq!(sample.qos.priority(), Priority::DataHigh);
+    assert_eq!(sample.qos.congestion_control(), CongestionControl::Block);
+    assert_eq!(sample.value.encoding, KnownEncoding::AppJson.into());
+
+    // Explicit settings take precedence over the defaults
+    ztimeout!(session1
+        .put("test/qos/defaults", vec![b'1'])
+        .priority(Priority::DataLow)
+        .encoding(KnownEncoding::AppOctetStream)
+        .res_async())
+    .unwrap();
+    let sample = ztimeout!(subscriber.recv_async()).unwrap();
+    assert_eq!(sample.qos.priority(), Priority::DataLow);
+    assert_eq!(sample.value.encoding, KnownEncoding::AppOctetStream.into());
+
+    // Typed payload: the encoding is implied by the type
+    ztimeout!(session1.put("test/qos/defaults", "text").res_async()).unwrap();
+    let sample = ztimeout!(subscriber.recv_async()).unwrap();
+    assert_eq!(sample.value.encoding, KnownEncoding::TextPlain.into());
+
+    // Same on a publisher
+    let publisher = ztimeout!(session1.declare_publisher("test/qos/defaults").res_async()).unwrap();
+    ztimeout!(publisher.put(vec![b'1']).res_async()).unwrap();
+    let sample = ztimeout!(subscriber.recv_async()).unwrap();
+    assert_eq!(sample.value.encoding, KnownEncoding::AppJson.into());
+    ztimeout!(publisher
+        .put(vec![b'1'])
+        .encoding(KnownEncoding::AppOctetStream)
+        .res_async())
+    .unwrap();
+    let sample = ztimeout!(subscriber.recv_async()).unwrap();
+    assert_eq!(sample.value.encoding, KnownEncoding::AppOctetStream.into());
+}
@@ -0,0 +1,103 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "unstable")]
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh::queryable::QueryableConflictEvent;
+use zenoh_core::{zlock, ztimeout};
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_secs(1);
+
+async fn open_router(listen: &str, connect: Option<&str>) -> Session {
+    let mut config = config::default();
+    config.set_mode(Some(WhatAmI::Router)).unwrap();
+    config.listen.endpoints = vec![listen.parse().unwrap()];
+    if let Some(connect) = connect {
+        config.connect.endpoints = vec![connect.parse().unwrap()];
+    }
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.scouting.gossip.set_enabled(Some(false)).unwrap();
+    ztimeout!(zenoh::open(config).res_async()).unwrap()
+}
+
+async fn open_client(connect: &str) -> (Session, Arc<Mutex<Vec<QueryableConflictEvent>>>) {
+    let config = config::client([connect.parse::<EndPoint>().unwrap()]);
+    let session = ztimeout!(zenoh::open(config).res_async()).unwrap();
+    let events = Arc::new(Mutex::new(vec![]));
+    let c_events = events.clone();
+    session.on_queryable_conflict(move |event| zlock!(c_events).push(event));
+    (session, events)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_queryable_conflict() {
+    zenoh_util::try_init_log_from_env();
+    let router1 = open_router("tcp/127.0.0.1:17461", None).await;
+    let router2 = open_router("tcp/127.0.0.1:17462", Some("tcp/127.0.0.1:17461")).await;
+    tokio::time::sleep(SLEEP).await;
+
+    // The clients are notified by their routers with OAM messages
+    let (client1, events1) = open_client("tcp/127.0.0.1:17461").await;
+    let (client2, events2) = open_client("tcp/127.0.0.1:17462").await;
+
+    let qabl1 = ztimeout!(client1
+        .declare_queryable("test/conflict/**")
+        .complete(true)
+        .callback(|_| {})
+        .res_async())
+    .unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert!(zlock!(events1).is_empty());
+
+    // The conflict with the queryable of the remote router is detected by both routers,
+    // and each client is notified once
+    let qabl2 = ztimeout!(client2
+        .declare_queryable("test/conflict/a")
+        .complete(true)
+        .callback(|_| {})
+        .res_async())
+    .unwrap();
+    tokio::time::sleep(SLEEP).await;
+    {
+        let events1 = zlock!(events1);
+        assert_eq!(events1.len(), 1);
+        assert_eq!(events1[0].key_expr.as_str(), "test/conflict/**");
+        assert_eq!(events1[0].conflicting_key_expr.as_str(), "test/conflict/a");
+        let events2 = zlock!(events2);
+        assert_eq!(events2.len(), 1);
+        assert_eq!(events2[0].key_expr.as_str(), "test/conflict/a");
+        assert_eq!(events2[0].conflicting_key_expr.as_str(), "test/conflict/**");
+    }
+
+    // An incomplete queryable does not conflict
+    ztimeout!(qabl2.undeclare().res_async()).unwrap();
+    let qabl2 = ztimeout!(client2
+        .declare_queryable("test/conflict/b")
+        .complete(false)
+        .callback(|_| {})
+        .res_async())
+    .unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(zlock!(events1).len(), 1);
+    assert_eq!(zlock!(events2).len(), 1);
+
+    ztimeout!(qabl1.undeclare().res_async()).unwrap();
+    ztimeout!(qabl2.undeclare().res_async()).unwrap();
+    ztimeout!(client1.close().res_async()).unwrap();
+    ztimeout!(client2.close().res_async()).unwrap();
+    ztimeout!(router2.close().res_async()).unwrap();
+    ztimeout!(router1.close().res_async()).unwrap();
+}
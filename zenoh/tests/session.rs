@@ -163,9 +163,18 @@ async fn test_session_qryrep(peer01: &Session, peer02: &Session, reliability: Re
         // Get data
         println!("[QR][02c] Getting on peer02 session. {msg_count} msgs.");
         let mut cnt = 0;
+        #[cfg(feature = "unstable")]
+        let mut replier_eid = None;
         for _ in 0..msg_count {
             let rs = ztimeout!(peer02.get(key_expr).res_async()).unwrap();
             while let Ok(s) = ztimeout!(rs.recv_async()) {
+                assert_eq!(s.replier_id(), peer01.zid());
+                // All the replies are sent by the same queryable
+                #[cfg(feature = "unstable")]
+                {
+                    assert!(s.replier_eid().is_some());
+                    assert_eq!(*replier_eid.get_or_insert(s.replier_eid()), s.replier_eid());
+                }
                 assert_eq!(s.sample.unwrap().value.payload.len(), size);
                 cnt += 1;
             }
@@ -243,3 +252,118 @@ async fn zenoh_2sessions_1runtime_init() {
     println!("[  ][02e] Closing r2 runtime");
     ztimeout!(r2.close()).unwrap();
 }
+
+#[cfg(feature = "unstable")]
+#[test]
+fn zenoh_session_reply_ordering() {
+    use zenoh::prelude::sync::*;
+    use zenoh::query::ReplyOrdering;
+
+    let session = zenoh::open(Config::default()).res().unwrap();
+    let _queryable = session
+        .declare_queryable("test/ordering/**")
+        .callback(|query| {
+            for key in ["test/ordering/c", "test/ordering/a", "test/ordering/b"] {
+                query
+                    .reply(Ok(Sample::new(KeyExpr::try_from(key).unwrap(), key)))
+                    .res()
+                    .unwrap();
+            }
+        })
+        .res()
+        .unwrap();
+
+    let replies = session
+        .get("test/ordering/**")
+        .consolidation(ConsolidationMode::None)
+        .reply_ordering(ReplyOrdering::KeyThenTime)
+        .res()
+        .unwrap();
+    let mut keys = vec![];
+    while let Ok(reply) = replies.recv() {
+        keys.push(reply.sample.unwrap().key_expr.to_string());
+    }
+    assert_eq!(
+        keys,
+        ["test/ordering/a", "test/ordering/b", "test/ordering/c"]
+    );
+}
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_session_reply_error_code() {
+    zenoh_util::try_init_log_from_env();
+    let key_expr = "test/session/error_code";
+    let (peer01, peer02) = open_session_unicast(&["tcp/127.0.0.1:17600"]).await;
+
+    let qbl = ztimeout!(peer01
+        .declare_queryable(key_expr)
+        .callback(|query| {
+            tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current().block_on(async {
+                    ztimeout!(query.reply_err(404, "not found").res_async()).unwrap()
+                })
+            });
+        })
+        .res_async())
+    .unwrap();
+
+    // Wait for the declaration to propagate
+    tokio::time::sleep(SLEEP).await;
+
+    let rs = ztimeout!(peer02.get(key_expr).res_async()).unwrap();
+    let reply = ztimeout!(rs.recv_async()).unwrap();
+    assert_eq!(reply.replier_id(), peer01.zid());
+    assert!(reply.replier_eid().is_some());
+    assert_eq!(reply.error_code(), Some(404));
+    let value = reply.sample.unwrap_err();
+    assert_eq!(value.to_string(), "not found");
+    assert!(ztimeout!(rs.recv_async()).is_err());
+
+    ztimeout!(qbl.undeclare().res_async()).unwrap();
+    close_session(peer01, peer02).await;
+}
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_session_default_locality() {
+    use zenoh::sample::Locality;
+
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .set_default_locality(Some(zenoh_config::LocalityConf::Remote))
+        .unwrap();
+    let session = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+    // The configured default applies to the subscribers declared without allowed origin
+    let default_sub = ztimeout!(session
+        .declare_subscriber("test/session/default_locality")
+        .res_async())
+    .unwrap();
+    // and is overridden by an explicit one
+    let any_sub = ztimeout!(session
+        .declare_subscriber("test/session/default_locality")
+        .allowed_origin(Locality::Any)
+        .res_async())
+    .unwrap();
+
+    ztimeout!(session
+        .put("test/session/default_locality", "local")
+        .allowed_destination(Locality::Any)
+        .res_async())
+    .unwrap();
+    let sample = ztimeout!(any_sub.recv_async()).unwrap();
+    assert_eq!(sample.value.to_string(), "local");
+    tokio::time::sleep(SLEEP).await;
+    assert!(default_sub.try_recv().is_err());
+
+    // The publications without allowed destination are not delivered locally
+    ztimeout!(session
+        .put("test/session/default_locality", "remote")
+        .res_async())
+    .unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert!(any_sub.try_recv().is_err());
+    assert!(default_sub.try_recv().is_err());
+}
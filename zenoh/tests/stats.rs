@@ -0,0 +1,74 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "unstable")]
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh::query::Reply;
+use zenoh_core::ztimeout;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SAMPLES: u64 = 10;
+const PAYLOAD: &str = "stats";
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn subscriber_stats() {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session = ztimeout!(zenoh::open(config).res_async()).unwrap();
+    let zid = session.zid();
+
+    let subscriber = ztimeout!(session.declare_subscriber("test/stats").res_async()).unwrap();
+    let publisher = ztimeout!(session.declare_publisher("test/stats").res_async()).unwrap();
+    for _ in 0..SAMPLES {
+        ztimeout!(publisher.put(PAYLOAD).res_async()).unwrap();
+    }
+    for _ in 0..SAMPLES {
+        ztimeout!(subscriber.recv_async()).unwrap();
+    }
+
+    let stats = subscriber.stats();
+    assert_eq!(stats.samples, SAMPLES);
+    assert_eq!(stats.bytes, SAMPLES * PAYLOAD.len() as u64);
+    assert_eq!(stats.drops, 0);
+    assert!(stats.last_activity.is_some());
+    assert_eq!(publisher.stats().samples, SAMPLES);
+
+    // The same counters are listed in the admin space of the session
+    let replies: Vec<Reply> = ztimeout!(session
+        .get(format!("@/session/{zid}/subscriber/*"))
+        .res_async())
+    .unwrap()
+    .into_iter()
+    .collect();
+    assert_eq!(replies.len(), 1);
+    let sample = replies[0].sample.as_ref().unwrap();
+    let value: serde_json::Value = serde_json::from_str(&sample.value.to_string()).unwrap();
+    assert_eq!(value["key_expr"], "test/stats");
+    assert_eq!(value["stats"]["samples"], SAMPLES);
+    assert_eq!(value["stats"]["bytes"], SAMPLES * PAYLOAD.len() as u64);
+    assert_eq!(value["stats"]["drops"], 0);
+
+    let replies: Vec<Reply> = ztimeout!(session
+        .get(format!("@/session/{zid}/publisher/*"))
+        .res_async())
+    .unwrap()
+    .into_iter()
+    .collect();
+    assert_eq!(replies.len(), 1);
+    let sample = replies[0].sample.as_ref().unwrap();
+    let value: serde_json::Value = serde_json::from_str(&sample.value.to_string()).unwrap();
+    assert_eq!(value["stats"]["samples"], SAMPLES);
+
+    ztimeout!(session.close().res_async()).unwrap();
+}
@@ -12,18 +12,25 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use clap::Parser;
-use futures::future;
 use git_version::git_version;
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::EnvFilter;
-use zenoh::config::{Config, ModeDependentValue, PermissionsConf, ValidatedMap};
+use zenoh::config::{
+    AclConfigRules, Action, Config, DownsamplingItemConf, DownsamplingRuleConf, InterceptorFlow,
+    ModeDependentValue, Permission, ValidatedMap,
+};
 use zenoh::prelude::r#async::*;
 use zenoh::Result;
 
 #[cfg(feature = "loki")]
 use url::Url;
 
+mod service;
+mod state;
+
 #[cfg(feature = "loki")]
 const LOKI_ENDPOINT_VAR: &str = "LOKI_ENDPOINT";
 
@@ -89,32 +96,102 @@ struct Args {
     /// Configure the read and/or write permissions on the admin space. Default is read only.
     #[arg(long, value_name = "[r|w|rw|none]")]
     adminspace_permissions: Option<String>,
+    /// Enables the access control, adding a rule allowing or denying some actions on a key expression,
+    /// with the format `<allow|deny>:<ACTIONS>:<KEY_EXPR>` where ACTIONS is a comma-separated list
+    /// of `put`, `get`, `declare_subscriber` and `declare_queryable`.
+    /// Repeat this option to add several rules. Example: --acl-rule='allow:put,get:demo/**'
+    #[arg(long, value_name = "RULE")]
+    acl_rule: Vec<String>,
+    /// The permission of the actions not matching any access control rule. Default is deny.
+    #[arg(long, value_name = "[allow|deny]")]
+    acl_default_permission: Option<String>,
+    /// Limits the frequency of the publications routed on a key expression, with the format
+    /// `<KEY_EXPR>:<FREQ>` where FREQ is the maximum frequency in Hertz.
+    /// Repeat this option to add several rules. Example: --downsampling='demo/**:10'
+    #[arg(long, value_name = "RULE")]
+    downsampling: Vec<String>,
+    /// Run zenohd in background, detached from the terminal.
+    /// Use it with `--log-file` to keep the logs, as the standard output is then discarded. Unix only.
+    #[arg(long)]
+    daemon: bool,
+    /// Run zenohd as a service of the Service Control Manager, which must have been registered with this option
+    /// (e.g. `sc.exe create zenohd binPath= "C:\zenoh\zenohd.exe --service -c C:\zenoh\zenohd.json5"`). Windows only.
+    #[arg(long)]
+    service: bool,
+    /// A file where to write the process id of zenohd. The file is removed when zenohd stops.
+    #[arg(long, value_name = "PATH")]
+    pid_file: Option<PathBuf>,
+    /// A file where to append the logs instead of writing them on the standard output.
+    #[arg(long, value_name = "PATH")]
+    log_file: Option<PathBuf>,
 }
 
 fn main() {
+    let args = Args::parse();
+
+    // Daemonize before starting any thread
+    #[cfg(unix)]
+    if args.daemon {
+        if let Err(e) = service::daemonize() {
+            println!("Couldn't daemonize: {e}. Exiting...");
+            std::process::exit(-1);
+        }
+    }
+    #[cfg(not(unix))]
+    if args.daemon {
+        println!("Option --daemon is only supported on unix. Exiting...");
+        std::process::exit(-1);
+    }
+    #[cfg(not(windows))]
+    if args.service {
+        println!("Option --service is only supported on windows. Exiting...");
+        std::process::exit(-1);
+    }
+
+    #[cfg(windows)]
+    let res = if args.service {
+        service::windows::run(move |stop| run(args, async move { stop.notified().await }))
+    } else {
+        run(args, service::stopped())
+    };
+    #[cfg(not(windows))]
+    let res = run(args, service::stopped());
+
+    if let Err(e) = res {
+        println!("{e}. Exiting...");
+        std::process::exit(-1);
+    }
+}
+
+/// Run the router until `stopped` completes.
+fn run<F>(args: Args, stopped: F) -> Result<()>
+where
+    F: Future<Output = ()>,
+{
     tokio::runtime::Builder::new_multi_thread()
         .enable_all()
-        .build()
-        .unwrap()
+        .build()?
         .block_on(async {
-            init_logging().unwrap();
+            init_logging(args.log_file.as_deref())?;
 
             tracing::info!("zenohd {}", *LONG_VERSION);
 
-            let args = Args::parse();
-            let config = config_from_args(&args);
+            let mut config = config_from_args(&args);
+            state::restore(&mut config, id_is_configured(&args))?;
             tracing::info!("Initial conf: {}", &config);
 
-            let _session = match zenoh::open(config).res().await {
-                Ok(runtime) => runtime,
-                Err(e) => {
-                    println!("{e}. Exiting...");
-                    std::process::exit(-1);
-                }
-            };
+            let _pid_file = args
+                .pid_file
+                .as_deref()
+                .map(service::PidFile::create)
+                .transpose()?;
 
-            future::pending::<()>().await;
-        });
+            let session = zenoh::open(config).res().await?;
+
+            stopped.await;
+            tracing::info!("zenohd stopping");
+            session.close().res().await
+        })
 }
 
 fn config_from_args(args: &Args) -> Config {
@@ -224,40 +301,56 @@ fn config_from_args(args: &Args) -> Config {
         (false, false) => {}
     };
     if let Some(adminspace_permissions) = &args.adminspace_permissions {
-        match adminspace_permissions.as_str() {
-            "r" => config
-                .adminspace
-                .set_permissions(PermissionsConf {
-                    read: true,
-                    write: false,
-                })
-                .unwrap(),
-            "w" => config
-                .adminspace
-                .set_permissions(PermissionsConf {
-                    read: false,
-                    write: true,
-                })
-                .unwrap(),
-            "rw" => config
-                .adminspace
-                .set_permissions(PermissionsConf {
-                    read: true,
-                    write: true,
-                })
-                .unwrap(),
-            "none" => config
-                .adminspace
-                .set_permissions(PermissionsConf {
-                    read: false,
-                    write: false,
-                })
-                .unwrap(),
+        let (read, write) = match adminspace_permissions.as_str() {
+            "r" => (true, false),
+            "w" => (false, true),
+            "rw" => (true, true),
+            "none" => (false, false),
             s => panic!(
                 r#"Invalid option: --adminspace-permissions={} - Accepted values: "r", "w", "rw" or "none""#,
                 s
             ),
         };
+        // Keep the rules of the configuration file
+        config.adminspace.permissions.read = read;
+        config.adminspace.permissions.write = write;
+    }
+    if let Some(permission) = &args.acl_default_permission {
+        config.access_control.enabled = true;
+        config.access_control.default_permission =
+            parse_permission(permission).unwrap_or_else(|e| {
+                panic!("Invalid option: --acl-default-permission={permission} - {e}")
+            });
+    }
+    if !args.acl_rule.is_empty() {
+        let rules = args.acl_rule.iter().map(|rule| {
+            parse_acl_rule(rule)
+                .unwrap_or_else(|e| panic!("Invalid option: --acl-rule={rule} - {e}"))
+        });
+        config.access_control.enabled = true;
+        // Append to the rules of the configuration file
+        config
+            .access_control
+            .rules
+            .get_or_insert_with(Vec::new)
+            .extend(rules);
+    }
+    if !args.downsampling.is_empty() {
+        let rules = args
+            .downsampling
+            .iter()
+            .map(|rule| {
+                parse_downsampling_rule(rule)
+                    .unwrap_or_else(|e| panic!("Invalid option: --downsampling={rule} - {e}"))
+            })
+            .collect();
+        let mut downsampling = config.downsampling().clone();
+        downsampling.push(DownsamplingItemConf {
+            interfaces: None,
+            rules,
+            flow: InterceptorFlow::Egress,
+        });
+        config.set_downsampling(downsampling).unwrap();
     }
     for json in &args.cfg {
         if let Some((key, value)) = json.split_once(':') {
@@ -277,7 +370,76 @@ fn config_from_args(args: &Args) -> Config {
     config
 }
 
-fn init_logging() -> Result<()> {
+/// Whether the ZenohId is set by the command line or by the configuration file, rather than generated.
+fn id_is_configured(args: &Args) -> bool {
+    if args.id.is_some() {
+        return true;
+    }
+    let set_by_cfg = args.cfg.iter().any(|json| {
+        json.split_once(':')
+            .is_some_and(|(key, _)| key.trim_matches('/') == "id")
+    });
+    if set_by_cfg {
+        return true;
+    }
+    // Without an id, each loading of the configuration file generates a different random one
+    args.config.as_ref().is_some_and(|conf_file| {
+        match (Config::from_file(conf_file), Config::from_file(conf_file)) {
+            (Ok(a), Ok(b)) => a.id() == b.id(),
+            _ => false,
+        }
+    })
+}
+
+fn parse_permission(permission: &str) -> std::result::Result<Permission, String> {
+    match permission {
+        "allow" => Ok(Permission::Allow),
+        "deny" => Ok(Permission::Deny),
+        _ => Err(r#"Accepted permissions: "allow" or "deny""#.to_string()),
+    }
+}
+
+fn parse_acl_rule(rule: &str) -> std::result::Result<AclConfigRules, String> {
+    let mut parts = rule.splitn(3, ':');
+    let (Some(permission), Some(actions), Some(key_expr)) =
+        (parts.next(), parts.next(), parts.next())
+    else {
+        return Err("Expected format: <allow|deny>:<ACTIONS>:<KEY_EXPR>".to_string());
+    };
+    let actions = actions
+        .split(',')
+        .map(|action| match action {
+            "put" => Ok(Action::Put),
+            "get" => Ok(Action::Get),
+            "declare_subscriber" => Ok(Action::DeclareSubscriber),
+            "declare_queryable" => Ok(Action::DeclareQueryable),
+            a => Err(format!(
+                r#"Unknown action "{a}" - Accepted actions: "put", "get", "declare_subscriber" or "declare_queryable""#
+            )),
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(AclConfigRules {
+        interfaces: None,
+        key_exprs: vec![key_expr.to_string()],
+        actions,
+        flows: None,
+        permission: parse_permission(permission)?,
+    })
+}
+
+fn parse_downsampling_rule(rule: &str) -> std::result::Result<DownsamplingRuleConf, String> {
+    let (key_expr, freq) = rule
+        .rsplit_once(':')
+        .ok_or_else(|| "Expected format: <KEY_EXPR>:<FREQ>".to_string())?;
+    Ok(DownsamplingRuleConf {
+        key_expr: OwnedKeyExpr::autocanonize(key_expr.to_string()).map_err(|e| e.to_string())?,
+        freq: freq
+            .parse()
+            .map_err(|e| format!("Invalid frequency {freq}: {e}"))?,
+    })
+}
+
+fn init_logging(log_file: Option<&Path>) -> Result<()> {
     let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("z=info"));
 
     let fmt_layer = tracing_subscriber::fmt::Layer::new()
@@ -286,9 +448,26 @@ fn init_logging() -> Result<()> {
         .with_level(true)
         .with_target(true);
 
+    // Either log on the standard output or in the log file
+    let (stdout_layer, file_layer) = match log_file {
+        Some(path) => {
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| format!("Couldn't open log file {}: {}", path.display(), e))?;
+            let file_layer = fmt_layer
+                .with_ansi(false)
+                .with_writer(std::sync::Mutex::new(file));
+            (None, Some(file_layer))
+        }
+        None => (Some(fmt_layer), None),
+    };
+
     let tracing_sub = tracing_subscriber::registry()
         .with(env_filter)
-        .with(fmt_layer);
+        .with(stdout_layer)
+        .with(file_layer);
 
     #[cfg(feature = "loki")]
     match (
@@ -330,6 +509,61 @@ pub fn get_loki_apikey_header() -> Option<String> {
     std::env::var(LOKI_API_KEY_HEADER_VAR).ok()
 }
 
+#[test]
+fn test_cli_acl_and_downsampling() {
+    let args = Args::parse_from([
+        "zenohd",
+        "--acl-rule=allow:put,get:demo/**",
+        "--acl-default-permission=deny",
+        "--downsampling=demo/example/**:10",
+        "--rest-http-port=none",
+    ]);
+    let config = config_from_args(&args);
+    assert!(config.access_control.enabled);
+    let rules = config.access_control.rules.as_ref().unwrap();
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0].actions, vec![Action::Put, Action::Get]);
+    assert_eq!(rules[0].key_exprs, vec!["demo/**".to_string()]);
+    assert_eq!(rules[0].permission, Permission::Allow);
+    let downsampling = config.downsampling();
+    assert_eq!(downsampling.len(), 1);
+    assert_eq!(
+        downsampling[0].rules[0].key_expr.as_str(),
+        "demo/example/**"
+    );
+    assert_eq!(downsampling[0].rules[0].freq, 10.0);
+
+    assert!(parse_acl_rule("allow:publish:demo/**").is_err());
+    assert!(parse_downsampling_rule("demo/**").is_err());
+}
+
+#[test]
+fn test_id_is_configured() {
+    assert!(!id_is_configured(&Args::parse_from(["zenohd"])));
+    assert!(id_is_configured(&Args::parse_from(["zenohd", "--id=a1b2"])));
+    assert!(id_is_configured(&Args::parse_from([
+        "zenohd",
+        r#"--cfg=id:"a1b2""#
+    ])));
+
+    let dir = std::env::temp_dir().join(format!("zenohd-id-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let with_id = dir.join("with_id.json5");
+    std::fs::write(&with_id, r#"{ id: "a1b2" }"#).unwrap();
+    let without_id = dir.join("without_id.json5");
+    std::fs::write(&without_id, r#"{ mode: "router" }"#).unwrap();
+    let config_arg = |path: &std::path::Path| format!("--config={}", path.display());
+    assert!(id_is_configured(&Args::parse_from([
+        "zenohd".to_string(),
+        config_arg(&with_id)
+    ])));
+    assert!(!id_is_configured(&Args::parse_from([
+        "zenohd".to_string(),
+        config_arg(&without_id)
+    ])));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
 #[test]
 #[cfg(feature = "default")]
 fn test_default_features() {
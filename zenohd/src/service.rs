@@ -0,0 +1,177 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+//! Integration of zenohd with the service managers of the host OS:
+//!  - on unix, zenohd can detach itself from the terminal (`--daemon`);
+//!  - on windows, zenohd can be run by the Service Control Manager (`--service`).
+use std::path::{Path, PathBuf};
+use zenoh::Result;
+
+/// A pid file, removed when dropped.
+pub(crate) struct PidFile(PathBuf);
+
+impl PidFile {
+    pub(crate) fn create(path: &Path) -> Result<PidFile> {
+        std::fs::write(path, format!("{}\n", std::process::id()))
+            .map_err(|e| format!("Couldn't write pid file {}: {}", path.display(), e))?;
+        Ok(PidFile(path.to_path_buf()))
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.0) {
+            tracing::warn!("Couldn't remove pid file {}: {}", self.0.display(), e);
+        }
+    }
+}
+
+/// Detach the process from its terminal: fork twice so that the daemon is not a session leader,
+/// and redirect the standard streams to `/dev/null`.
+/// The working directory is kept so that the relative paths of the configuration remain valid.
+///
+/// This must be called before any thread (e.g. the tokio runtime) is started.
+#[cfg(unix)]
+pub(crate) fn daemonize() -> Result<()> {
+    use nix::unistd::{dup2, fork, setsid, ForkResult};
+    use std::os::unix::io::AsRawFd;
+
+    // SAFETY: no other thread is running at this point
+    if let ForkResult::Parent { .. } = unsafe { fork() }? {
+        std::process::exit(0);
+    }
+    setsid()?;
+    // SAFETY: no other thread is running at this point
+    if let ForkResult::Parent { .. } = unsafe { fork() }? {
+        std::process::exit(0);
+    }
+
+    let null = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/null")?;
+    for fd in 0..3 {
+        dup2(null.as_raw_fd(), fd)?;
+    }
+    Ok(())
+}
+
+/// Wait for SIGINT or SIGTERM.
+#[cfg(unix)]
+pub(crate) async fn stopped() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    match signal(SignalKind::terminate()) {
+        Ok(mut sigterm) => {
+            tokio::select! {
+                _ = sigterm.recv() => {}
+                _ = tokio::signal::ctrl_c() => {}
+            }
+        }
+        Err(e) => {
+            tracing::warn!("Couldn't listen to SIGTERM: {}", e);
+            let _ = tokio::signal::ctrl_c().await;
+        }
+    }
+}
+
+/// Wait for Ctrl-C.
+#[cfg(not(unix))]
+pub(crate) async fn stopped() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+#[cfg(windows)]
+pub(crate) mod windows {
+    use std::ffi::OsString;
+    use std::sync::{Arc, Mutex, OnceLock};
+    use std::time::Duration;
+    use tokio::sync::Notify;
+    use windows_service::service::{
+        ServiceControl, ServiceControlAccept, ServiceExitCode, ServiceState, ServiceStatus,
+        ServiceType,
+    };
+    use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+    use windows_service::{define_windows_service, service_dispatcher};
+    use zenoh::Result;
+
+    pub(crate) const SERVICE_NAME: &str = "zenohd";
+
+    type Run = Box<dyn FnOnce(Arc<Notify>) -> Result<()> + Send>;
+
+    // The service entry point can't capture any state: the router is passed through a global
+    static RUN: OnceLock<Mutex<Option<Run>>> = OnceLock::new();
+
+    define_windows_service!(ffi_service_main, service_main);
+
+    /// Run the router as a windows service: `run` is called once the service is started,
+    /// and must return when the given [`Notify`] is notified of the stop request of the Service Control Manager.
+    ///
+    /// This blocks until the service is stopped.
+    pub(crate) fn run<F>(run: F) -> Result<()>
+    where
+        F: FnOnce(Arc<Notify>) -> Result<()> + Send + 'static,
+    {
+        let _ = RUN.set(Mutex::new(Some(Box::new(run))));
+        service_dispatcher::start(SERVICE_NAME, ffi_service_main)?;
+        Ok(())
+    }
+
+    fn service_main(_arguments: Vec<OsString>) {
+        if let Err(e) = run_service() {
+            tracing::error!("Service {} failed: {}", SERVICE_NAME, e);
+        }
+    }
+
+    fn run_service() -> Result<()> {
+        let stop = Arc::new(Notify::new());
+        let c_stop = stop.clone();
+        let status_handle =
+            service_control_handler::register(SERVICE_NAME, move |control| match control {
+                ServiceControl::Stop | ServiceControl::Shutdown => {
+                    c_stop.notify_one();
+                    ServiceControlHandlerResult::NoError
+                }
+                ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+                _ => ServiceControlHandlerResult::NotImplemented,
+            })?;
+
+        let status = |state, controls_accepted, exit_code| ServiceStatus {
+            service_type: ServiceType::OWN_PROCESS,
+            current_state: state,
+            controls_accepted,
+            exit_code: ServiceExitCode::Win32(exit_code),
+            checkpoint: 0,
+            wait_hint: Duration::default(),
+            process_id: None,
+        };
+        status_handle.set_service_status(status(
+            ServiceState::Running,
+            ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+            0,
+        ))?;
+
+        let run = RUN
+            .get()
+            .and_then(|r| r.lock().unwrap().take())
+            .ok_or("Service started twice")?;
+        let res = run(stop);
+
+        status_handle.set_service_status(status(
+            ServiceState::Stopped,
+            ServiceControlAccept::empty(),
+            if res.is_ok() { 0 } else { 1 },
+        ))?;
+        res
+    }
+}
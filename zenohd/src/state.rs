@@ -0,0 +1,64 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::io::ErrorKind;
+use std::path::Path;
+use zenoh::config::Config;
+use zenoh::Result;
+
+// The file of the state directory storing the ZenohId
+const ZID_FILE: &str = "zid";
+
+/// Restore the ZenohId stored in the `state_dir` of the configuration, if any, so that zenohd keeps
+/// its identity across restarts. The peers then resume the transports they hibernated for it, and the
+/// replication and access control rules bound to this ZenohId remain valid.
+///
+/// At the first start, or if the id is configured (i.e. with `--id` or in the configuration file), the
+/// ZenohId of the configuration is stored instead.
+pub(crate) fn restore(config: &mut Config, id_configured: bool) -> Result<()> {
+    let Some(state_dir) = config.state_dir().clone() else {
+        return Ok(());
+    };
+    let state_dir = Path::new(&state_dir);
+    std::fs::create_dir_all(state_dir).map_err(|e| {
+        format!(
+            "Couldn't create state directory {}: {}",
+            state_dir.display(),
+            e
+        )
+    })?;
+    let path = state_dir.join(ZID_FILE);
+    if !id_configured {
+        match std::fs::read_to_string(&path) {
+            Ok(zid) => {
+                config
+                    .set_id(zid.trim().parse()?)
+                    .map_err(|_| format!("Invalid ZenohId in {}", path.display()))?;
+                tracing::info!("Restored ZenohId {} from {}", config.id(), path.display());
+                return Ok(());
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Couldn't read {}: {}", path.display(), e).into()),
+        }
+    }
+    write_atomically(&path, &format!("{}\n", config.id()))
+}
+
+// Write the file through a temporary file renamed over it, so that a crash can't leave it truncated
+fn write_atomically(path: &Path, content: &str) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, content)
+        .and_then(|_| std::fs::File::open(&tmp)?.sync_all())
+        .and_then(|_| std::fs::rename(&tmp, path))
+        .map_err(|e| format!("Couldn't write {}: {}", path.display(), e).into())
+}